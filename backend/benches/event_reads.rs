@@ -0,0 +1,174 @@
+//! Timings for creating and reading an event through the in-process router.
+//!
+//! Run with `cargo bench -p agreed-time-backend` against the migrated
+//! database in `DATABASE_URL`; the events it creates are deleted at the end. The response cache is off so every read reaches the database; the
+//! concurrent rounds share one pool, as replicas under load do.
+
+use std::{
+    env,
+    time::{Duration, Instant},
+};
+
+use agreed_time_backend::{config::Config, routes::create_router_with_state, state::AppState};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{TimeZone, Utc};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+const RUNS: usize = 20;
+
+/// Candidate slots per event, one hour each with an hour between them.
+const SLOTS: usize = 200;
+
+/// Respondents besides the organizer; stays under the participant limit.
+const PARTICIPANTS: usize = 9;
+
+async fn send(app: &Router, method: &str, uri: &str, body: Value) -> Value {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK, "{} {}", method, uri);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn slots() -> Vec<Value> {
+    let origin = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+    (0..SLOTS)
+        .map(|i| {
+            let start = origin + chrono::Duration::hours(2 * i as i64);
+            json!({ "start_at": start, "end_at": start + chrono::Duration::hours(1) })
+        })
+        .collect()
+}
+
+async fn create_event(app: &Router) -> String {
+    let created = send(
+        app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Bench",
+            "description": null,
+            "organizer_name": "Alice",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": slots(),
+            "roles": [{ "name": "Host", "required": 1 }]
+        }),
+    )
+    .await;
+    created["public_token"].as_str().unwrap().to_string()
+}
+
+/// Median wall time of `RUNS` calls; returns what they returned.
+async fn measure<F: Future>(name: &str, mut f: impl FnMut() -> F) -> Vec<F::Output> {
+    let mut times = Vec::with_capacity(RUNS);
+    let mut outputs = Vec::with_capacity(RUNS);
+    for _ in 0..RUNS {
+        let started = Instant::now();
+        outputs.push(f().await);
+        times.push(started.elapsed());
+    }
+    times.sort();
+    println!("{:<48} {:>10.3?}", name, times[RUNS / 2]);
+    outputs
+}
+
+/// Median latency of `concurrency` requests in flight at once, `RUNS` rounds.
+async fn measure_concurrent(name: &str, app: &Router, uri: &str, concurrency: usize) {
+    let mut times: Vec<Duration> = Vec::with_capacity(RUNS * concurrency);
+    for _ in 0..RUNS {
+        let round = (0..concurrency).map(|_| {
+            let app = app.clone();
+            let uri = uri.to_string();
+            tokio::spawn(async move {
+                let started = Instant::now();
+                send(&app, "GET", &uri, Value::Null).await;
+                started.elapsed()
+            })
+        });
+        for time in futures::future::join_all(round).await {
+            times.push(time.unwrap());
+        }
+    }
+    times.sort();
+    println!("{:<48} {:>10.3?}", name, times[times.len() / 2]);
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must point at a database");
+    let pool = PgPool::connect(&database_url).await.unwrap();
+    let config = Config {
+        response_cache_ttl_secs: 0,
+        submission_rate_limit: 0,
+        rate_limit_default: None,
+        rate_limit_create_event: None,
+        rate_limit_health: None,
+        abuse_max_events: 0,
+        abuse_max_failures: 0,
+        ..Config::default()
+    };
+    let app = create_router_with_state(AppState::new(pool.clone()).with_config(config));
+
+    let mut created = measure(&format!("POST /events {} slots", SLOTS), || {
+        create_event(&app)
+    })
+    .await;
+
+    let public_token = create_event(&app).await;
+    for i in 0..PARTICIPANTS {
+        let availabilities: Vec<Value> = slots().into_iter().skip(i % 2).step_by(2).collect();
+        send(
+            &app,
+            "POST",
+            &format!("/events/{}/availability", public_token),
+            json!({
+                "participant_name": format!("Participant {}", i),
+                "availabilities": availabilities,
+                "role": "Host",
+                "comment": null
+            }),
+        )
+        .await;
+    }
+    created.push(public_token.clone());
+
+    let event_uri = format!("/events/{}", public_token);
+    let results_uri = format!("/events/{}/results", public_token);
+    measure("GET /events/{public_token}", || async {
+        send(&app, "GET", &event_uri, Value::Null).await;
+    })
+    .await;
+    measure("GET /events/{public_token}/results", || async {
+        send(&app, "GET", &results_uri, Value::Null).await;
+    })
+    .await;
+    for concurrency in [8, 32] {
+        measure_concurrent(
+            &format!("GET /events/{{public_token}} x{}", concurrency),
+            &app,
+            &event_uri,
+            concurrency,
+        )
+        .await;
+    }
+
+    sqlx::query("DELETE FROM events WHERE public_token = ANY($1)")
+        .bind(&created)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
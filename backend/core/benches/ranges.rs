@@ -0,0 +1,103 @@
+//! Timings for range merging and overlap counting on large-event inputs.
+//!
+//! Run with `cargo bench -p agreed-time-core`. Inputs are generated from a
+//! fixed seed so runs are comparable across changes.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use agreed_time_core::{
+    ParticipantRanges, TimeRange, bucket_availability, coverage, intersect_time_ranges,
+    merge_time_ranges, subtract_time_ranges,
+};
+use chrono::{TimeZone, Utc};
+
+const RUNS: usize = 20;
+
+/// Linear congruential generator; good enough for spreading ranges around.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+}
+
+/// `count` ranges of 30 minutes to 4 hours, aligned to 15 minutes, within
+/// four weeks.
+fn random_ranges(rng: &mut Lcg, count: usize) -> Vec<TimeRange> {
+    let origin = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+    (0..count)
+        .map(|_| {
+            let start = origin + chrono::Duration::minutes(15 * rng.next(4 * 7 * 96) as i64);
+            let length = chrono::Duration::minutes(15 * (2 + rng.next(15)) as i64);
+            TimeRange::new(start, start + length)
+        })
+        .collect()
+}
+
+/// Median wall time of `RUNS` calls.
+fn measure<T>(name: &str, mut f: impl FnMut() -> T) {
+    let mut times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let started = Instant::now();
+            black_box(f());
+            started.elapsed()
+        })
+        .collect();
+    times.sort();
+    println!("{:<48} {:>10.3?}", name, times[RUNS / 2]);
+}
+
+fn main() {
+    let mut rng = Lcg(2030);
+
+    for count in [1_000, 10_000, 100_000] {
+        let ranges = random_ranges(&mut rng, count);
+        measure(&format!("merge_time_ranges {} ranges", count), || {
+            merge_time_ranges(ranges.clone())
+        });
+    }
+
+    let ranges = random_ranges(&mut rng, 10_000);
+    let other = random_ranges(&mut rng, 10_000);
+    measure("subtract_time_ranges 10000 - 10000", || {
+        subtract_time_ranges(ranges.clone(), &other)
+    });
+    measure("intersect_time_ranges 10000 x 10000", || {
+        intersect_time_ranges(&ranges, &other)
+    });
+
+    for participants in [100, 1_000, 5_000] {
+        let sets: Vec<Vec<TimeRange>> = (0..participants)
+            .map(|_| random_ranges(&mut rng, 5))
+            .collect();
+        measure(&format!("coverage {} participants", participants), || {
+            coverage(&sets)
+        });
+
+        // Cell bucketing, the current heatmap path, for comparison
+        let participant_ranges: Vec<ParticipantRanges> = sets
+            .iter()
+            .enumerate()
+            .map(|(i, availabilities)| ParticipantRanges {
+                name: format!("Participant {}", i),
+                is_organizer: false,
+                availabilities: availabilities.clone(),
+                if_need_be: vec![],
+                buffer_minutes: 0,
+                role: None,
+            })
+            .collect();
+        measure(
+            &format!("bucket_availability {} participants", participants),
+            || bucket_availability(&participant_ranges, 15),
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! Picking compatible times across several events of the same organizer.
+
+use crate::ranges::TimeRange;
+
+/// Partial choices tried before giving up on finding a compatible set.
+pub const SEARCH_BUDGET: usize = 10_000;
+
+/// Picks one candidate per event so that no two picks overlap each other or
+/// any of `fixed` (times the organizer is already committed to). Candidates
+/// are listed best first and earlier events take precedence, so the first
+/// combination found is the preferred one. Events without candidates get
+/// `None`. Returns `None` when no combination exists or the search budget
+/// runs out.
+pub fn compatible_choice(
+    candidates: &[Vec<TimeRange>],
+    fixed: &[TimeRange],
+) -> Option<Vec<Option<usize>>> {
+    let mut chosen = Vec::with_capacity(candidates.len());
+    let mut budget = SEARCH_BUDGET;
+    search(candidates, fixed, &mut chosen, &mut budget).then_some(chosen)
+}
+
+fn search(
+    candidates: &[Vec<TimeRange>],
+    fixed: &[TimeRange],
+    chosen: &mut Vec<Option<usize>>,
+    budget: &mut usize,
+) -> bool {
+    let event = chosen.len();
+    if event == candidates.len() {
+        return true;
+    }
+    if candidates[event].is_empty() {
+        chosen.push(None);
+        if search(candidates, fixed, chosen, budget) {
+            return true;
+        }
+        chosen.pop();
+        return false;
+    }
+
+    for (index, candidate) in candidates[event].iter().enumerate() {
+        if *budget == 0 {
+            return false;
+        }
+        *budget -= 1;
+
+        let clashes = fixed.iter().any(|range| range.overlaps(candidate))
+            || chosen.iter().enumerate().any(|(other, pick)| {
+                pick.is_some_and(|i| candidates[other][i].overlaps(candidate))
+            });
+        if clashes {
+            continue;
+        }
+
+        chosen.push(Some(index));
+        if search(candidates, fixed, chosen, budget) {
+            return true;
+        }
+        chosen.pop();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2030, 1, 1, hour, 0, 0).unwrap()
+    }
+
+    fn range(start: u32, end: u32) -> TimeRange {
+        TimeRange::new(at(start), at(end))
+    }
+
+    #[test]
+    fn test_keeps_best_candidates_when_they_fit() {
+        let candidates = vec![vec![range(9, 10)], vec![range(10, 11), range(12, 13)]];
+        assert_eq!(
+            compatible_choice(&candidates, &[]),
+            Some(vec![Some(0), Some(0)])
+        );
+    }
+
+    #[test]
+    fn test_backtracks_around_overlaps_and_fixed_times() {
+        // The second event's best time clashes with the first; its only
+        // other time clashes with a finalized event, so the first must move
+        let candidates = vec![
+            vec![range(9, 11), range(13, 14)],
+            vec![range(10, 11), range(14, 15)],
+            vec![],
+        ];
+        let fixed = vec![range(14, 16)];
+        assert_eq!(
+            compatible_choice(&candidates, &fixed),
+            Some(vec![Some(1), Some(0), None])
+        );
+    }
+
+    #[test]
+    fn test_reports_impossible_combinations() {
+        let candidates = vec![vec![range(9, 10)], vec![range(9, 10)]];
+        assert_eq!(compatible_choice(&candidates, &[]), None);
+    }
+}
@@ -0,0 +1,303 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::{heatmap::ParticipantRanges, ranges::TimeRange};
+
+/// Local calendar dates a UTC range covers in some time zone.
+///
+/// A UTC day viewed from UTC-5 starts on the previous local date, so
+/// `start_date` and `end_date` differ; frontends should show both rather than
+/// picking one and being off by a day.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct LocalDateSpan {
+    pub start_date: NaiveDate,
+    /// Local date of the last instant inside the range (end is exclusive).
+    pub end_date: NaiveDate,
+}
+
+pub fn is_known_time_zone(time_zone: &str) -> bool {
+    time_zone.parse::<Tz>().is_ok()
+}
+
+/// Canonical spelling of a zone name, matched ignoring surrounding spaces
+/// and ASCII case (`asia/taipei` → `Asia/Taipei`); `None` when unknown.
+pub fn normalize_time_zone(time_zone: &str) -> Option<&'static str> {
+    let time_zone = time_zone.trim();
+    if let Ok(tz) = time_zone.parse::<Tz>() {
+        return Some(tz.name());
+    }
+    chrono_tz::TZ_VARIANTS
+        .iter()
+        .find(|tz| tz.name().eq_ignore_ascii_case(time_zone))
+        .map(|tz| tz.name())
+}
+
+/// Every zone name in the bundled database, in alphabetical order.
+pub fn time_zone_names() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = chrono_tz::TZ_VARIANTS.iter().map(|tz| tz.name()).collect();
+    names.sort_unstable();
+    names
+}
+
+/// Seconds `time_zone` is ahead of UTC at `at`.
+pub fn utc_offset_seconds(time_zone: &str, at: DateTime<Utc>) -> Option<i32> {
+    let tz: Tz = time_zone.parse().ok()?;
+    Some(
+        tz.offset_from_utc_datetime(&at.naive_utc())
+            .fix()
+            .local_minus_utc(),
+    )
+}
+
+/// Returns `None` when `time_zone` is not a known IANA zone name.
+pub fn local_date_span(range: &TimeRange, time_zone: &str) -> Option<LocalDateSpan> {
+    let tz: Tz = time_zone.parse().ok()?;
+    let last_instant = if range.end_at > range.start_at {
+        range.end_at - Duration::nanoseconds(1)
+    } else {
+        range.start_at
+    };
+
+    Some(LocalDateSpan {
+        start_date: range.start_at.with_timezone(&tz).date_naive(),
+        end_date: last_instant.with_timezone(&tz).date_naive(),
+    })
+}
+
+/// Local times skipped by a DST change are read with the offset in effect
+/// just before it.
+pub(crate) fn local_instant(tz: &Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let local = date.and_time(time);
+    match tz.from_local_datetime(&local).earliest() {
+        Some(at) => at.with_timezone(&Utc),
+        None => {
+            let offset = tz.offset_from_utc_datetime(&local).fix();
+            Utc.from_utc_datetime(&local) - Duration::seconds(offset.local_minus_utc() as i64)
+        }
+    }
+}
+
+/// `date` from local midnight to the next, so 23 or 25 hours across a DST
+/// change. Returns `None` when `time_zone` is not a known IANA zone name.
+pub fn day_range(date: NaiveDate, time_zone: &str) -> Option<TimeRange> {
+    let tz: Tz = time_zone.parse().ok()?;
+    Some(TimeRange::new(
+        local_instant(&tz, date, NaiveTime::MIN),
+        local_instant(&tz, date + Duration::days(1), NaiveTime::MIN),
+    ))
+}
+
+/// `range` moved by `days` calendar days in `time_zone`, keeping its local
+/// wall-clock times: a 09:00 meeting stays at 09:00 across a DST change.
+/// Returns `None` when `time_zone` is not a known IANA zone name.
+pub fn shift_days(range: &TimeRange, days: i64, time_zone: &str) -> Option<TimeRange> {
+    let tz: Tz = time_zone.parse().ok()?;
+    let shift = |at: DateTime<Utc>| {
+        let local = at.with_timezone(&tz).naive_local() + Duration::days(days);
+        local_instant(&tz, local.date(), local.time())
+    };
+    Some(TimeRange::new(shift(range.start_at), shift(range.end_at)))
+}
+
+/// Every local date the ranges touch, in order and without repeats. Returns
+/// `None` when `time_zone` is not a known IANA zone name.
+pub fn covered_dates(ranges: &[TimeRange], time_zone: &str) -> Option<Vec<NaiveDate>> {
+    let mut dates = Vec::new();
+    for range in ranges {
+        let span = local_date_span(range, time_zone)?;
+        let mut date = span.start_date;
+        while date <= span.end_date {
+            dates.push(date);
+            date += Duration::days(1);
+        }
+    }
+    dates.sort_unstable();
+    dates.dedup();
+    Some(dates)
+}
+
+/// Who can make one candidate date of a date poll.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DateVotes {
+    pub date: NaiveDate,
+    pub count: usize,
+    /// Names of the available participants, in input order.
+    pub participants: Vec<String>,
+    /// Names of participants available only if need be, in input order.
+    pub if_need_be: Vec<String>,
+}
+
+/// Counts, per date, the participants whose availability overlaps that
+/// local day; buffers don't apply to whole days. Someone with both kinds of
+/// ranges on a date counts as available. Returns `None` when `time_zone` is
+/// not a known IANA zone name.
+pub fn date_votes(
+    dates: &[NaiveDate],
+    participants: &[ParticipantRanges],
+    time_zone: &str,
+) -> Option<Vec<DateVotes>> {
+    let mut votes = Vec::with_capacity(dates.len());
+    for &date in dates {
+        let day = day_range(date, time_zone)?;
+        let mut available = Vec::new();
+        let mut if_need_be = Vec::new();
+        for participant in participants {
+            if participant.availabilities.iter().any(|r| r.overlaps(&day)) {
+                available.push(participant.name.clone());
+            } else if participant.if_need_be.iter().any(|r| r.overlaps(&day)) {
+                if_need_be.push(participant.name.clone());
+            }
+        }
+        votes.push(DateVotes {
+            date,
+            count: available.len(),
+            participants: available,
+            if_need_be,
+        });
+    }
+    Some(votes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc_day() -> TimeRange {
+        let start = Utc.with_ymd_and_hms(2030, 1, 2, 0, 0, 0).unwrap();
+        TimeRange::new(start, start + Duration::days(1))
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2030, 1, day).unwrap()
+    }
+
+    #[test]
+    fn test_utc_day_in_utc() {
+        let span = local_date_span(&utc_day(), "UTC").unwrap();
+        assert_eq!((span.start_date, span.end_date), (date(2), date(2)));
+    }
+
+    #[test]
+    fn test_utc_day_west_of_utc_starts_previous_date() {
+        let span = local_date_span(&utc_day(), "America/New_York").unwrap();
+        assert_eq!((span.start_date, span.end_date), (date(1), date(2)));
+    }
+
+    #[test]
+    fn test_utc_day_east_of_utc_ends_next_date() {
+        let span = local_date_span(&utc_day(), "Asia/Taipei").unwrap();
+        assert_eq!((span.start_date, span.end_date), (date(2), date(3)));
+    }
+
+    #[test]
+    fn test_unknown_zone() {
+        assert!(local_date_span(&utc_day(), "Mars/Olympus").is_none());
+    }
+
+    #[test]
+    fn test_normalize_time_zone() {
+        assert_eq!(normalize_time_zone("Asia/Taipei"), Some("Asia/Taipei"));
+        assert_eq!(
+            normalize_time_zone(" america/new_york "),
+            Some("America/New_York")
+        );
+        assert_eq!(normalize_time_zone("utc"), Some("UTC"));
+        assert_eq!(normalize_time_zone("Mars/Olympus"), None);
+        assert_eq!(normalize_time_zone(""), None);
+    }
+
+    #[test]
+    fn test_utc_offset_follows_dst() {
+        let winter = Utc.with_ymd_and_hms(2030, 1, 15, 12, 0, 0).unwrap();
+        let summer = Utc.with_ymd_and_hms(2030, 7, 15, 12, 0, 0).unwrap();
+        assert_eq!(utc_offset_seconds("Europe/Berlin", winter), Some(3600));
+        assert_eq!(utc_offset_seconds("Europe/Berlin", summer), Some(7200));
+        assert_eq!(utc_offset_seconds("Asia/Kolkata", winter), Some(19800));
+        assert_eq!(utc_offset_seconds("Mars/Olympus", winter), None);
+
+        let names = time_zone_names();
+        assert!(names.is_sorted());
+        assert!(names.contains(&"Asia/Taipei"));
+    }
+
+    #[test]
+    fn test_day_range_follows_local_midnight_and_dst() {
+        let day = day_range(date(2), "Asia/Taipei").unwrap();
+        assert_eq!(
+            day.start_at,
+            Utc.with_ymd_and_hms(2030, 1, 1, 16, 0, 0).unwrap()
+        );
+        assert_eq!(day.end_at - day.start_at, Duration::hours(24));
+
+        // Europe/Paris springs forward on 2030-03-31
+        let short = day_range(
+            NaiveDate::from_ymd_opt(2030, 3, 31).unwrap(),
+            "Europe/Paris",
+        );
+        assert_eq!(
+            short.map(|d| d.end_at - d.start_at),
+            Some(Duration::hours(23))
+        );
+        assert!(day_range(date(2), "Mars/Olympus").is_none());
+    }
+
+    #[test]
+    fn test_shift_days_keeps_local_time_across_dst() {
+        // Europe/Paris springs forward on 2030-03-31: 09:00 is 08:00 UTC
+        // before and 07:00 UTC after
+        let standup = TimeRange::new(
+            Utc.with_ymd_and_hms(2030, 3, 25, 8, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2030, 3, 25, 8, 30, 0).unwrap(),
+        );
+        let next_week = shift_days(&standup, 7, "Europe/Paris").unwrap();
+        assert_eq!(
+            next_week.start_at,
+            Utc.with_ymd_and_hms(2030, 4, 1, 7, 0, 0).unwrap()
+        );
+        assert_eq!(next_week.end_at - next_week.start_at, Duration::minutes(30));
+
+        let back = shift_days(&next_week, -7, "Europe/Paris").unwrap();
+        assert_eq!(back, standup);
+        assert_eq!(
+            shift_days(&standup, 1, "UTC").unwrap().start_at,
+            standup.start_at + Duration::days(1)
+        );
+        assert!(shift_days(&standup, 1, "Mars/Olympus").is_none());
+    }
+
+    #[test]
+    fn test_covered_dates_lists_each_day_once() {
+        let two_days = TimeRange::new(
+            day_range(date(2), "UTC").unwrap().start_at,
+            day_range(date(3), "UTC").unwrap().end_at,
+        );
+        let dates = covered_dates(&[two_days, utc_day()], "UTC").unwrap();
+        assert_eq!(dates, vec![date(2), date(3)]);
+    }
+
+    #[test]
+    fn test_date_votes() {
+        let participant =
+            |name: &str, available: Vec<TimeRange>, if_need_be: Vec<TimeRange>| ParticipantRanges {
+                name: name.to_string(),
+                is_organizer: false,
+                availabilities: available,
+                if_need_be,
+                buffer_minutes: 0,
+                role: None,
+            };
+        let day = |d: u32| day_range(date(d), "UTC").unwrap();
+        let participants = vec![
+            participant("Alice", vec![day(2), day(3)], vec![]),
+            participant("Bob", vec![day(3)], vec![day(2)]),
+        ];
+
+        let votes = date_votes(&[date(2), date(3), date(4)], &participants, "UTC").unwrap();
+        assert_eq!(
+            votes.iter().map(|v| v.count).collect::<Vec<_>>(),
+            vec![1, 2, 0]
+        );
+        assert_eq!(votes[0].if_need_be, vec!["Bob".to_string()]);
+    }
+}
@@ -0,0 +1,97 @@
+//! Display strings for instants and ranges in a given time zone.
+//!
+//! For clients that can't do time zone math themselves (chat bots, emails,
+//! SMS): one fixed, unambiguous layout with the weekday, ISO date, 24-hour
+//! time and the zone abbreviation in effect at that instant.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::ranges::TimeRange;
+
+const DATE_TIME: &str = "%a %Y-%m-%d %H:%M";
+
+/// `Tue 2030-01-01 10:00 CET`. Returns `None` when `time_zone` is not a
+/// known IANA zone name.
+pub fn format_instant(at: DateTime<Utc>, time_zone: &str) -> Option<String> {
+    let tz: Tz = time_zone.parse().ok()?;
+    let local = at.with_timezone(&tz);
+    Some(format!(
+        "{} {}",
+        local.format(DATE_TIME),
+        local.format("%Z")
+    ))
+}
+
+/// `Tue 2030-01-01 10:00–12:00 CET`. Both ends are written out in full when
+/// the range crosses local midnight or a DST change.
+pub fn format_range(range: &TimeRange, time_zone: &str) -> Option<String> {
+    let tz: Tz = time_zone.parse().ok()?;
+    let start = range.start_at.with_timezone(&tz);
+    let end = range.end_at.with_timezone(&tz);
+
+    Some(
+        if start.date_naive() == end.date_naive()
+            && start.format("%Z").to_string() == end.format("%Z").to_string()
+        {
+            format!("{}–{}", start.format(DATE_TIME), end.format("%H:%M %Z"))
+        } else {
+            format!(
+                "{} {} – {} {}",
+                start.format(DATE_TIME),
+                start.format("%Z"),
+                end.format(DATE_TIME),
+                end.format("%Z")
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2030, month, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_format_instant() {
+        assert_eq!(
+            format_instant(at(1, 1, 9), "Europe/Paris").unwrap(),
+            "Tue 2030-01-01 10:00 CET"
+        );
+        assert_eq!(
+            format_instant(at(1, 1, 9), "UTC").unwrap(),
+            "Tue 2030-01-01 09:00 UTC"
+        );
+        assert!(format_instant(at(1, 1, 9), "Mars/Olympus").is_none());
+    }
+
+    #[test]
+    fn test_format_range_within_a_day() {
+        let range = TimeRange::new(at(1, 1, 9), at(1, 1, 11));
+        assert_eq!(
+            format_range(&range, "Europe/Paris").unwrap(),
+            "Tue 2030-01-01 10:00–12:00 CET"
+        );
+    }
+
+    #[test]
+    fn test_format_range_across_midnight_and_dst() {
+        // 22:00 UTC is already the next local day in Taipei
+        let range = TimeRange::new(at(1, 1, 14), at(1, 1, 17));
+        assert_eq!(
+            format_range(&range, "Asia/Taipei").unwrap(),
+            "Tue 2030-01-01 22:00 CST – Wed 2030-01-02 01:00 CST"
+        );
+
+        // Paris switches to summer time at 01:00 UTC on 31 March 2030
+        let range = TimeRange::new(at(3, 31, 0), at(3, 31, 2));
+        assert_eq!(
+            format_range(&range, "Europe/Paris").unwrap(),
+            "Sun 2030-03-31 01:00 CET – Sun 2030-03-31 04:00 CEST"
+        );
+    }
+}
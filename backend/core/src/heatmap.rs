@@ -0,0 +1,237 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    ranges::{TimeRange, merged},
+    slots::expand_slots,
+};
+
+/// Score of a participant who is available; one who is available only if
+/// need be scores 1, so two "maybe"s weigh as much as one "yes".
+pub const AVAILABLE_WEIGHT: usize = 2;
+
+/// One participant's submitted availability.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ParticipantRanges {
+    pub name: String,
+    pub is_organizer: bool,
+    pub availabilities: Vec<TimeRange>,
+    /// Ranges the participant can make only if need be. Where these overlap
+    /// `availabilities`, the participant counts as available.
+    #[serde(default)]
+    pub if_need_be: Vec<TimeRange>,
+    /// Minutes the participant needs free before and after a meeting.
+    #[serde(default)]
+    pub buffer_minutes: i32,
+    /// Role picked when responding, matched against `RoleRequirement::role`.
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+impl ParticipantRanges {
+    /// Availability shrunk by `buffer_minutes` on both ends of every merged
+    /// range; ranges too short to hold the buffer disappear.
+    pub fn effective_availabilities(&self) -> Vec<TimeRange> {
+        self.shrink_by_buffer(&self.availabilities)
+    }
+
+    /// `if_need_be` with the same buffer applied.
+    pub fn effective_if_need_be(&self) -> Vec<TimeRange> {
+        self.shrink_by_buffer(&self.if_need_be)
+    }
+
+    fn shrink_by_buffer(&self, ranges: &[TimeRange]) -> Vec<TimeRange> {
+        let buffer = Duration::minutes(self.buffer_minutes.max(0) as i64);
+        merged(ranges)
+            .into_iter()
+            .map(|r| TimeRange::new(r.start_at + buffer, r.end_at - buffer))
+            .filter(|r| r.start_at < r.end_at)
+            .collect()
+    }
+}
+
+/// Number of participants available in one `slot_duration` cell.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SlotBucket {
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    pub count: usize,
+    /// Names of the available participants, in input order.
+    pub participants: Vec<String>,
+    /// Names of participants available only if need be, in input order.
+    #[serde(default)]
+    pub if_need_be: Vec<String>,
+}
+
+impl SlotBucket {
+    /// [`AVAILABLE_WEIGHT`] per available participant plus one per
+    /// participant available only if need be.
+    pub fn score(&self) -> usize {
+        AVAILABLE_WEIGHT * self.count + self.if_need_be.len()
+    }
+}
+
+/// Indices (into the participant list) of everyone in one cell.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct CellIndices {
+    pub available: Vec<usize>,
+    pub if_need_be: Vec<usize>,
+}
+
+impl CellIndices {
+    pub fn score(&self) -> usize {
+        AVAILABLE_WEIGHT * self.available.len() + self.if_need_be.len()
+    }
+}
+
+/// Buckets every participant's availability into `slot_duration` cells,
+/// sorted by start time. Each participant counts at most once per cell.
+pub fn bucket_availability(
+    participants: &[ParticipantRanges],
+    slot_duration: i32,
+) -> Vec<SlotBucket> {
+    let step = Duration::minutes(slot_duration as i64);
+    let names = |indices: Vec<usize>| -> Vec<String> {
+        indices
+            .into_iter()
+            .map(|i| participants[i].name.clone())
+            .collect()
+    };
+    bucket_indices(participants, slot_duration)
+        .into_iter()
+        .map(|(start_at, cell)| SlotBucket {
+            start_at,
+            end_at: start_at + step,
+            count: cell.available.len(),
+            participants: names(cell.available),
+            if_need_be: names(cell.if_need_be),
+        })
+        .collect()
+}
+
+/// Cell start -> who is available there.
+pub(crate) fn bucket_indices(
+    participants: &[ParticipantRanges],
+    slot_duration: i32,
+) -> BTreeMap<DateTime<Utc>, CellIndices> {
+    bucket_indices_with(participants, slot_duration, |p| {
+        (merged(&p.availabilities), merged(&p.if_need_be))
+    })
+}
+
+/// Like `bucket_indices`, but honours each participant's buffer.
+pub(crate) fn bucket_effective_indices(
+    participants: &[ParticipantRanges],
+    slot_duration: i32,
+) -> BTreeMap<DateTime<Utc>, CellIndices> {
+    bucket_indices_with(participants, slot_duration, |p| {
+        (p.effective_availabilities(), p.effective_if_need_be())
+    })
+}
+
+fn bucket_indices_with(
+    participants: &[ParticipantRanges],
+    slot_duration: i32,
+    ranges_of: impl Fn(&ParticipantRanges) -> (Vec<TimeRange>, Vec<TimeRange>),
+) -> BTreeMap<DateTime<Utc>, CellIndices> {
+    let mut cells: BTreeMap<DateTime<Utc>, CellIndices> = BTreeMap::new();
+
+    for (index, participant) in participants.iter().enumerate() {
+        let (available, if_need_be) = ranges_of(participant);
+        let available = expand_slots(&available, slot_duration);
+        for start in &available {
+            cells.entry(*start).or_default().available.push(index);
+        }
+        for start in expand_slots(&if_need_be, slot_duration) {
+            if available.binary_search(&start).is_err() {
+                cells.entry(start).or_default().if_need_be.push(index);
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_bucket_availability_counts_overlap() {
+        let nine = Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap();
+        let participants = vec![
+            ParticipantRanges {
+                name: "Alice".to_string(),
+                is_organizer: true,
+                availabilities: vec![TimeRange::new(nine, nine + Duration::hours(2))],
+                if_need_be: vec![],
+                buffer_minutes: 0,
+                role: None,
+            },
+            ParticipantRanges {
+                name: "Bob".to_string(),
+                is_organizer: false,
+                availabilities: vec![TimeRange::new(
+                    nine + Duration::hours(1),
+                    nine + Duration::hours(2),
+                )],
+                if_need_be: vec![],
+                buffer_minutes: 0,
+                role: None,
+            },
+        ];
+
+        let buckets = bucket_availability(&participants, 60);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].count, 1);
+        assert_eq!(buckets[1].count, 2);
+        assert_eq!(buckets[1].participants, vec!["Alice", "Bob"]);
+    }
+
+    #[test]
+    fn test_bucket_availability_separates_if_need_be() {
+        let nine = Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap();
+        let participants = vec![ParticipantRanges {
+            name: "Alice".to_string(),
+            is_organizer: false,
+            availabilities: vec![TimeRange::new(nine, nine + Duration::hours(1))],
+            // Overlaps the available hour, which wins
+            if_need_be: vec![TimeRange::new(nine, nine + Duration::hours(2))],
+            buffer_minutes: 0,
+            role: None,
+        }];
+
+        let buckets = bucket_availability(&participants, 60);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!((buckets[0].count, buckets[0].if_need_be.len()), (1, 0));
+        assert_eq!((buckets[1].count, buckets[1].if_need_be.len()), (0, 1));
+        assert_eq!(buckets[0].score(), 2);
+        assert_eq!(buckets[1].score(), 1);
+    }
+
+    #[test]
+    fn test_effective_availabilities_apply_buffer() {
+        let nine = Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap();
+        let participant = ParticipantRanges {
+            name: "Alice".to_string(),
+            is_organizer: false,
+            availabilities: vec![
+                TimeRange::new(nine, nine + Duration::hours(2)),
+                TimeRange::new(nine + Duration::hours(5), nine + Duration::minutes(320)),
+            ],
+            if_need_be: vec![],
+            buffer_minutes: 15,
+            role: None,
+        };
+
+        // The 20-minute range cannot hold 15 minutes on both sides
+        assert_eq!(
+            participant.effective_availabilities(),
+            vec![TimeRange::new(
+                nine + Duration::minutes(15),
+                nine + Duration::minutes(105)
+            )]
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! Pure scheduling algorithms shared by the server and the browser.
+//!
+//! Nothing in this crate touches the database or HTTP; everything works on
+//! plain UTC time ranges so it can be compiled to WASM unchanged.
+
+pub mod conflicts;
+pub mod dates;
+pub mod format;
+pub mod heatmap;
+pub mod ranges;
+pub mod roles;
+pub mod scheduler;
+pub mod slots;
+pub mod suggest;
+pub mod sweep;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod weekly;
+
+pub use conflicts::compatible_choice;
+pub use dates::{
+    DateVotes, LocalDateSpan, covered_dates, date_votes, day_range, is_known_time_zone,
+    local_date_span, normalize_time_zone, shift_days, time_zone_names, utc_offset_seconds,
+};
+pub use format::{format_instant, format_range};
+pub use heatmap::{AVAILABLE_WEIGHT, ParticipantRanges, SlotBucket, bucket_availability};
+pub use ranges::{
+    TimeRange, intersect_time_ranges, merge_in_place, merge_time_ranges, merged,
+    subtract_time_ranges,
+};
+pub use roles::{RoleCoverage, RoleRequirement};
+pub use scheduler::{AssignmentOptions, Fit, assign};
+pub use slots::expand_slots;
+pub use suggest::{
+    Explanation, ScoreBreakdown, Suggestion, first_at_quorum, rank_slots, suggest,
+    suggest_with_roles,
+};
+pub use sweep::{Coverage, coverage, covered_by_at_least};
+pub use weekly::{WeeklySlot, from_reference_ranges, reference_week, to_reference_ranges};
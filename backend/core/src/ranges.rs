@@ -0,0 +1,202 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Half-open UTC interval `[start_at, end_at)`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+}
+
+impl TimeRange {
+    pub fn new(start_at: DateTime<Utc>, end_at: DateTime<Utc>) -> Self {
+        TimeRange { start_at, end_at }
+    }
+
+    pub fn contains(&self, other: &TimeRange) -> bool {
+        self.start_at <= other.start_at && other.end_at <= self.end_at
+    }
+
+    /// Ranges that merely touch do not overlap.
+    pub fn overlaps(&self, other: &TimeRange) -> bool {
+        self.start_at < other.end_at && other.start_at < self.end_at
+    }
+}
+
+/// Sorts ranges and merges any that overlap or touch, reusing the vector's
+/// allocation. O(n log n).
+pub fn merge_time_ranges(mut ranges: Vec<TimeRange>) -> Vec<TimeRange> {
+    merge_in_place(&mut ranges);
+    ranges
+}
+
+/// [`merge_time_ranges`] for borrowed input.
+pub fn merged(ranges: &[TimeRange]) -> Vec<TimeRange> {
+    merge_time_ranges(ranges.to_vec())
+}
+
+/// Sorts `ranges` and merges overlapping or touching ones in place.
+pub fn merge_in_place(ranges: &mut Vec<TimeRange>) {
+    if ranges.len() < 2 {
+        return;
+    }
+
+    ranges.sort_unstable_by_key(|range| range.start_at);
+
+    // `last` is the range being grown; everything before it is final
+    let mut last = 0;
+    for next in 1..ranges.len() {
+        let candidate = ranges[next];
+        if candidate.start_at <= ranges[last].end_at {
+            if candidate.end_at > ranges[last].end_at {
+                ranges[last].end_at = candidate.end_at;
+            }
+        } else {
+            last += 1;
+            ranges[last] = candidate;
+        }
+    }
+    ranges.truncate(last + 1);
+}
+
+/// Removes every instant covered by `remove` from `ranges`, splitting ranges
+/// that contain a removed span. The result is merged and sorted.
+/// O((n + m) log(n + m)).
+pub fn subtract_time_ranges(ranges: Vec<TimeRange>, remove: &[TimeRange]) -> Vec<TimeRange> {
+    let ranges = merge_time_ranges(ranges);
+    let cuts = merged(remove);
+
+    let mut remaining = Vec::with_capacity(ranges.len());
+    let mut c = 0;
+    for range in ranges {
+        // Cuts ending before this range can't affect it or any later one
+        while c < cuts.len() && cuts[c].end_at <= range.start_at {
+            c += 1;
+        }
+
+        let mut start_at = range.start_at;
+        let mut k = c;
+        while k < cuts.len() && cuts[k].start_at < range.end_at {
+            if start_at < cuts[k].start_at {
+                remaining.push(TimeRange::new(start_at, cuts[k].start_at));
+            }
+            start_at = start_at.max(cuts[k].end_at);
+            k += 1;
+        }
+        if start_at < range.end_at {
+            remaining.push(TimeRange::new(start_at, range.end_at));
+        }
+    }
+    remaining
+}
+
+/// Parts of `ranges` that lie inside `within`, merged and sorted.
+/// O((n + m) log(n + m)).
+pub fn intersect_time_ranges(ranges: &[TimeRange], within: &[TimeRange]) -> Vec<TimeRange> {
+    let ranges = merged(ranges);
+    let within = merged(within);
+
+    let mut overlaps = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < ranges.len() && j < within.len() {
+        let start_at = ranges[i].start_at.max(within[j].start_at);
+        let end_at = ranges[i].end_at.min(within[j].end_at);
+        if start_at < end_at {
+            overlaps.push(TimeRange::new(start_at, end_at));
+        }
+        // Advance whichever ends first; the other may still overlap the next one
+        if ranges[i].end_at < within[j].end_at {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    // Both inputs are merged, so the pieces are already sorted and apart
+    overlaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn range(start: i64, end: i64) -> TimeRange {
+        TimeRange::new(
+            Utc.timestamp_opt(start, 0).unwrap(),
+            Utc.timestamp_opt(end, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_merge_time_ranges_no_overlap() {
+        let merged = merge_time_ranges(vec![range(1000, 2000), range(3000, 4000)]);
+        assert_eq!(merged.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_time_ranges_overlap() {
+        let merged = merge_time_ranges(vec![range(1000, 3000), range(2000, 4000)]);
+        assert_eq!(merged, vec![range(1000, 4000)]);
+    }
+
+    #[test]
+    fn test_merge_time_ranges_adjacent_and_unsorted() {
+        let merged = merge_time_ranges(vec![
+            range(3000, 4000),
+            range(1000, 2000),
+            range(2000, 3000),
+        ]);
+        assert_eq!(merged, vec![range(1000, 4000)]);
+    }
+
+    #[test]
+    fn test_subtract_time_ranges_splits_and_trims() {
+        let remaining = subtract_time_ranges(
+            vec![range(1000, 4000), range(5000, 6000)],
+            &[range(2000, 3000), range(5500, 7000)],
+        );
+        assert_eq!(
+            remaining,
+            vec![range(1000, 2000), range(3000, 4000), range(5000, 5500)]
+        );
+    }
+
+    #[test]
+    fn test_subtract_time_ranges_removes_covered() {
+        let remaining = subtract_time_ranges(vec![range(1000, 2000)], &[range(0, 3000)]);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn test_merge_in_place_contained_and_duplicates() {
+        let mut ranges = vec![
+            range(1000, 5000),
+            range(2000, 3000),
+            range(1000, 5000),
+            range(6000, 7000),
+        ];
+        merge_in_place(&mut ranges);
+        assert_eq!(ranges, vec![range(1000, 5000), range(6000, 7000)]);
+    }
+
+    #[test]
+    fn test_subtract_time_ranges_with_overlapping_cuts() {
+        let remaining = subtract_time_ranges(
+            vec![range(0, 10000)],
+            &[range(3000, 5000), range(1000, 2000), range(4000, 6000)],
+        );
+        assert_eq!(
+            remaining,
+            vec![range(0, 1000), range(2000, 3000), range(6000, 10000)]
+        );
+    }
+
+    #[test]
+    fn test_intersect_time_ranges() {
+        let overlaps = intersect_time_ranges(
+            &[range(1000, 5000)],
+            &[range(0, 2000), range(3000, 4000), range(6000, 7000)],
+        );
+        assert_eq!(overlaps, vec![range(1000, 2000), range(3000, 4000)]);
+    }
+}
@@ -0,0 +1,52 @@
+//! Role requirements for interview/ops style polls: every window must be
+//! staffable, e.g. one host and two reviewers available for all of it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::heatmap::{CellIndices, ParticipantRanges};
+
+/// At least `required` participants with `role` must be available.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RoleRequirement {
+    pub role: String,
+    pub required: usize,
+}
+
+/// Who could fill one role in a window.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RoleCoverage {
+    pub role: String,
+    pub required: usize,
+    /// Participants with the role available (or available if need be) for
+    /// the whole window, in input order.
+    pub filled: Vec<String>,
+}
+
+/// Coverage of every requirement in `cell`, or `None` when any role is short.
+/// Each participant holds a single role, so counting per role is enough; no
+/// assignment search is needed.
+pub(crate) fn cover_roles(
+    participants: &[ParticipantRanges],
+    cell: &CellIndices,
+    requirements: &[RoleRequirement],
+) -> Option<Vec<RoleCoverage>> {
+    requirements
+        .iter()
+        .map(|requirement| {
+            let filled: Vec<String> = participants
+                .iter()
+                .enumerate()
+                .filter(|(index, participant)| {
+                    participant.role.as_deref() == Some(requirement.role.as_str())
+                        && (cell.available.contains(index) || cell.if_need_be.contains(index))
+                })
+                .map(|(_, participant)| participant.name.clone())
+                .collect();
+            (filled.len() >= requirement.required).then(|| RoleCoverage {
+                role: requirement.role.clone(),
+                required: requirement.required,
+                filled,
+            })
+        })
+        .collect()
+}
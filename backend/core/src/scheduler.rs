@@ -0,0 +1,346 @@
+//! Assignment of participants to several finalized slots.
+//!
+//! Modelled as a min-cost flow: source -> participant -> (participant, slot)
+//! -> role seat or general seat -> slot -> sink. Costs make filling a
+//! required role worth more than any number of ordinary seats, ordinary
+//! seats worth more than leaving someone out, and "if need be" attendance
+//! slightly worse than plain availability. General seats get dearer the
+//! fuller a slot is, which spreads people evenly.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{heatmap::ParticipantRanges, ranges::TimeRange, roles::RoleRequirement};
+
+const ROLE_SEAT_REWARD: i64 = 100_000;
+const GENERAL_SEAT_REWARD: i64 = 1_000;
+const IF_NEED_BE_PENALTY: i64 = 100;
+
+/// How well a participant can make a slot.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Fit {
+    Available,
+    IfNeedBe,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AssignmentOptions {
+    /// Attendees per slot; `None` means no limit.
+    pub capacity: Option<usize>,
+    /// Slots each participant attends at most.
+    pub sessions_per_participant: usize,
+}
+
+impl Default for AssignmentOptions {
+    fn default() -> Self {
+        AssignmentOptions {
+            capacity: None,
+            sessions_per_participant: 1,
+        }
+    }
+}
+
+/// Whether `participant` can attend all of `slot`, honouring their buffer.
+/// Plain availability wins; otherwise the slot must be covered by
+/// availability and "if need be" ranges together.
+pub fn fit(participant: &ParticipantRanges, slot: &TimeRange) -> Option<Fit> {
+    let available = participant.effective_availabilities();
+    if available.iter().any(|range| range.contains(slot)) {
+        return Some(Fit::Available);
+    }
+    let mut either = available;
+    either.extend(participant.effective_if_need_be());
+    crate::ranges::merge_time_ranges(either)
+        .iter()
+        .any(|range| range.contains(slot))
+        .then_some(Fit::IfNeedBe)
+}
+
+/// Proposes attendees for each of `slots`: indices into `participants`,
+/// sorted, one list per slot. Every slot must staff each of `requirements`
+/// by participant role; shortfalls are left for the caller to report.
+pub fn assign(
+    participants: &[ParticipantRanges],
+    slots: &[TimeRange],
+    requirements: &[RoleRequirement],
+    options: &AssignmentOptions,
+) -> Vec<Vec<usize>> {
+    let mut graph = Graph::default();
+    let source = graph.add_node();
+    let sink = graph.add_node();
+    let seats_per_slot = options
+        .capacity
+        .unwrap_or(participants.len())
+        .min(participants.len());
+
+    let participant_nodes: Vec<usize> = participants
+        .iter()
+        .map(|_| {
+            let node = graph.add_node();
+            graph.add_edge(source, node, options.sessions_per_participant as i64, 0);
+            node
+        })
+        .collect();
+
+    // (slot, participant, edge) for every possible attendance
+    let mut attendance_edges = Vec::new();
+    for (slot_index, slot) in slots.iter().enumerate() {
+        let slot_node = graph.add_node();
+        graph.add_edge(slot_node, sink, seats_per_slot as i64, 0);
+
+        let general = graph.add_node();
+        for seat in 0..seats_per_slot {
+            graph.add_edge(general, slot_node, 1, seat as i64 - GENERAL_SEAT_REWARD);
+        }
+        let role_nodes: Vec<(usize, &str)> = requirements
+            .iter()
+            .map(|requirement| {
+                let node = graph.add_node();
+                graph.add_edge(
+                    node,
+                    slot_node,
+                    requirement.required as i64,
+                    -ROLE_SEAT_REWARD,
+                );
+                (node, requirement.role.as_str())
+            })
+            .collect();
+
+        for (index, participant) in participants.iter().enumerate() {
+            let Some(fit) = fit(participant, slot) else {
+                continue;
+            };
+            let cost = match fit {
+                Fit::Available => 0,
+                Fit::IfNeedBe => IF_NEED_BE_PENALTY,
+            };
+            let seat = graph.add_node();
+            let edge = graph.add_edge(participant_nodes[index], seat, 1, cost);
+            attendance_edges.push((slot_index, index, edge));
+
+            graph.add_edge(seat, general, 1, 0);
+            for (node, role) in &role_nodes {
+                if participant.role.as_deref() == Some(*role) {
+                    graph.add_edge(seat, *node, 1, 0);
+                }
+            }
+        }
+    }
+
+    graph.min_cost_flow(source, sink);
+
+    let mut assigned = vec![Vec::new(); slots.len()];
+    for (slot_index, participant_index, edge) in attendance_edges {
+        if graph.flow(edge) > 0 {
+            assigned[slot_index].push(participant_index);
+        }
+    }
+    assigned
+}
+
+struct Edge {
+    to: usize,
+    rev: usize,
+    cap: i64,
+    cost: i64,
+}
+
+#[derive(Default)]
+struct Graph {
+    adj: Vec<Vec<Edge>>,
+}
+
+impl Graph {
+    fn add_node(&mut self) -> usize {
+        self.adj.push(Vec::new());
+        self.adj.len() - 1
+    }
+
+    /// Returns `(from, index)` so the edge's flow can be read back.
+    fn add_edge(&mut self, from: usize, to: usize, cap: i64, cost: i64) -> (usize, usize) {
+        let forward = self.adj[from].len();
+        let backward = self.adj[to].len() + usize::from(from == to);
+        self.adj[from].push(Edge {
+            to,
+            rev: backward,
+            cap,
+            cost,
+        });
+        self.adj[to].push(Edge {
+            to: from,
+            rev: forward,
+            cap: 0,
+            cost: -cost,
+        });
+        (from, forward)
+    }
+
+    /// Flow on an edge is the capacity left on its reverse edge.
+    fn flow(&self, (from, index): (usize, usize)) -> i64 {
+        let edge = &self.adj[from][index];
+        self.adj[edge.to][edge.rev].cap
+    }
+
+    /// Successive shortest paths with Bellman-Ford (costs may be negative),
+    /// augmenting only while a path still lowers the total cost.
+    fn min_cost_flow(&mut self, source: usize, sink: usize) {
+        loop {
+            let mut dist = vec![i64::MAX; self.adj.len()];
+            let mut prev: Vec<Option<(usize, usize)>> = vec![None; self.adj.len()];
+            dist[source] = 0;
+
+            let mut updated = true;
+            while updated {
+                updated = false;
+                for node in 0..self.adj.len() {
+                    if dist[node] == i64::MAX {
+                        continue;
+                    }
+                    for (index, edge) in self.adj[node].iter().enumerate() {
+                        if edge.cap > 0 && dist[node] + edge.cost < dist[edge.to] {
+                            dist[edge.to] = dist[node] + edge.cost;
+                            prev[edge.to] = Some((node, index));
+                            updated = true;
+                        }
+                    }
+                }
+            }
+
+            if dist[sink] >= 0 {
+                return;
+            }
+
+            let mut bottleneck = i64::MAX;
+            let mut node = sink;
+            while let Some((from, index)) = prev[node] {
+                bottleneck = bottleneck.min(self.adj[from][index].cap);
+                node = from;
+            }
+            let mut node = sink;
+            while let Some((from, index)) = prev[node] {
+                self.adj[from][index].cap -= bottleneck;
+                let (to, rev) = (self.adj[from][index].to, self.adj[from][index].rev);
+                self.adj[to][rev].cap += bottleneck;
+                node = from;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2030, 1, 1, hour, 0, 0).unwrap()
+    }
+
+    fn slot(start: u32, end: u32) -> TimeRange {
+        TimeRange::new(at(start), at(end))
+    }
+
+    fn participant(name: &str, role: Option<&str>, ranges: &[(u32, u32)]) -> ParticipantRanges {
+        ParticipantRanges {
+            name: name.to_string(),
+            is_organizer: false,
+            availabilities: ranges.iter().map(|&(s, e)| slot(s, e)).collect(),
+            if_need_be: vec![],
+            buffer_minutes: 0,
+            role: role.map(str::to_string),
+        }
+    }
+
+    fn requirement(role: &str, required: usize) -> RoleRequirement {
+        RoleRequirement {
+            role: role.to_string(),
+            required,
+        }
+    }
+
+    #[test]
+    fn test_fit_prefers_plain_availability() {
+        let mut ann = participant("Ann", None, &[(9, 10)]);
+        ann.if_need_be = vec![slot(10, 11)];
+
+        assert_eq!(fit(&ann, &slot(9, 10)), Some(Fit::Available));
+        assert_eq!(fit(&ann, &slot(9, 11)), Some(Fit::IfNeedBe));
+        assert_eq!(fit(&ann, &slot(11, 12)), None);
+    }
+
+    #[test]
+    fn test_assign_spreads_participants_across_slots() {
+        let participants: Vec<_> = ["A", "B", "C", "D"]
+            .iter()
+            .map(|name| participant(name, None, &[(9, 11)]))
+            .collect();
+
+        let assigned = assign(
+            &participants,
+            &[slot(9, 10), slot(10, 11)],
+            &[],
+            &AssignmentOptions::default(),
+        );
+        assert_eq!(assigned[0].len(), 2);
+        assert_eq!(assigned[1].len(), 2);
+    }
+
+    #[test]
+    fn test_assign_staffs_roles_in_every_slot() {
+        // Two hosts, each free for both interviews; the reviewer pool is split
+        let participants = vec![
+            participant("Hana", Some("host"), &[(9, 11)]),
+            participant("Hugo", Some("host"), &[(9, 11)]),
+            participant("Rui", Some("reviewer"), &[(9, 10)]),
+            participant("Sam", Some("reviewer"), &[(9, 11)]),
+        ];
+        let requirements = vec![requirement("host", 1), requirement("reviewer", 1)];
+
+        let assigned = assign(
+            &participants,
+            &[slot(9, 10), slot(10, 11)],
+            &requirements,
+            &AssignmentOptions::default(),
+        );
+        // Rui can only make 9-10, so Sam must take 10-11
+        assert!(assigned[0].contains(&2));
+        assert!(assigned[1].contains(&3));
+        for slot in &assigned {
+            assert_eq!(slot.iter().filter(|&&i| i < 2).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_assign_respects_capacity_and_prefers_available() {
+        let mut maybe = participant("Maybe", None, &[]);
+        maybe.if_need_be = vec![slot(9, 10)];
+        let participants = vec![maybe, participant("Yes", None, &[(9, 10)])];
+
+        let assigned = assign(
+            &participants,
+            &[slot(9, 10)],
+            &[],
+            &AssignmentOptions {
+                capacity: Some(1),
+                sessions_per_participant: 1,
+            },
+        );
+        assert_eq!(assigned, vec![vec![1]]);
+    }
+
+    #[test]
+    fn test_assign_allows_several_sessions() {
+        let participants = vec![participant("Ann", None, &[(9, 11)])];
+
+        let assigned = assign(
+            &participants,
+            &[slot(9, 10), slot(10, 11)],
+            &[],
+            &AssignmentOptions {
+                capacity: None,
+                sessions_per_participant: 2,
+            },
+        );
+        assert_eq!(assigned, vec![vec![0], vec![0]]);
+    }
+}
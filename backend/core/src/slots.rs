@@ -0,0 +1,47 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::ranges::TimeRange;
+
+/// Splits each range into `slot_duration`-minute cells and returns the cell
+/// start times. Cells are aligned to the start of their range, matching the
+/// frontend grid; a trailing partial cell is dropped.
+pub fn expand_slots(ranges: &[TimeRange], slot_duration: i32) -> Vec<DateTime<Utc>> {
+    if slot_duration <= 0 {
+        return vec![];
+    }
+    let step = Duration::minutes(slot_duration as i64);
+
+    let mut starts = Vec::new();
+    for range in ranges {
+        let mut current = range.start_at;
+        while current + step <= range.end_at {
+            starts.push(current);
+            current += step;
+        }
+    }
+    starts.sort();
+    starts.dedup();
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_expand_slots() {
+        let start = Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap();
+        let ranges = vec![TimeRange::new(start, start + Duration::minutes(150))];
+
+        let slots = expand_slots(&ranges, 60);
+        assert_eq!(slots, vec![start, start + Duration::hours(1)]);
+    }
+
+    #[test]
+    fn test_expand_slots_invalid_duration() {
+        let start = Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap();
+        let ranges = vec![TimeRange::new(start, start + Duration::hours(1))];
+        assert!(expand_slots(&ranges, 0).is_empty());
+    }
+}
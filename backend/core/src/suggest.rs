@@ -0,0 +1,367 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    heatmap::{CellIndices, ParticipantRanges, SlotBucket, bucket_effective_indices},
+    roles::{RoleCoverage, RoleRequirement, cover_roles},
+};
+
+/// A candidate meeting window: consecutive cells shared by the same people.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    pub count: usize,
+    pub explanation: Explanation,
+}
+
+/// Why a window was ranked where it is.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// Participants available for the whole window, in input order.
+    pub available: Vec<String>,
+    /// Participants available for the whole window only if need be.
+    #[serde(default)]
+    pub if_need_be: Vec<String>,
+    /// Participants not available for the window, in input order.
+    pub missing: Vec<String>,
+    pub score: ScoreBreakdown,
+    /// How each required role is filled; empty when nothing is required.
+    #[serde(default)]
+    pub roles: Vec<RoleCoverage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ScoreBreakdown {
+    /// Primary ranking key: `AVAILABLE_WEIGHT` per available participant
+    /// plus one per participant available only if need be.
+    pub score: usize,
+    pub participants_available: usize,
+    #[serde(default)]
+    pub participants_if_need_be: usize,
+    pub participants_total: usize,
+    pub organizer_available: bool,
+}
+
+/// Orders buckets best-first: highest score, then earliest start, then
+/// shortest. The order is total, so equal scores never depend on input order.
+pub fn rank_slots(mut buckets: Vec<SlotBucket>) -> Vec<SlotBucket> {
+    buckets.sort_by(|a, b| {
+        b.score()
+            .cmp(&a.score())
+            .then_with(|| a.start_at.cmp(&b.start_at))
+            .then_with(|| (a.end_at - a.start_at).cmp(&(b.end_at - b.start_at)))
+    });
+    buckets
+}
+
+/// Folds availability into windows (adjacent cells where the same people are
+/// available and the same are available if need be) and returns the best
+/// `limit` of them. Each participant's `buffer_minutes` is trimmed off their
+/// availability first.
+///
+/// Ranking: highest score (see [`ScoreBreakdown::score`]), then earliest
+/// start, then shortest window.
+pub fn suggest(
+    participants: &[ParticipantRanges],
+    slot_duration: i32,
+    limit: usize,
+) -> Vec<Suggestion> {
+    suggest_with_roles(participants, slot_duration, limit, &[])
+}
+
+/// Like [`suggest`], but drops windows where any of `requirements` cannot be
+/// filled by participants available (or available if need be) throughout.
+pub fn suggest_with_roles(
+    participants: &[ParticipantRanges],
+    slot_duration: i32,
+    limit: usize,
+    requirements: &[RoleRequirement],
+) -> Vec<Suggestion> {
+    let step = chrono::Duration::minutes(slot_duration as i64);
+
+    let mut windows: Vec<(DateTime<Utc>, DateTime<Utc>, CellIndices)> = Vec::new();
+    for (start_at, cell) in bucket_effective_indices(participants, slot_duration) {
+        match windows.last_mut() {
+            Some((_, end_at, current)) if *end_at == start_at && *current == cell => {
+                *end_at = start_at + step;
+            }
+            _ => windows.push((start_at, start_at + step, cell)),
+        }
+    }
+
+    let mut windows: Vec<_> = windows
+        .into_iter()
+        .filter_map(|(start_at, end_at, cell)| {
+            let roles = cover_roles(participants, &cell, requirements)?;
+            Some((start_at, end_at, cell, roles))
+        })
+        .collect();
+
+    windows.sort_by(|a, b| {
+        b.2.score()
+            .cmp(&a.2.score())
+            .then_with(|| a.0.cmp(&b.0))
+            .then_with(|| (a.1 - a.0).cmp(&(b.1 - b.0)))
+    });
+
+    windows
+        .into_iter()
+        .take(limit)
+        .map(|(start_at, end_at, cell, roles)| Suggestion {
+            start_at,
+            end_at,
+            count: cell.available.len(),
+            explanation: explain(participants, &cell, roles),
+        })
+        .collect()
+}
+
+/// The earliest `slot_duration` cell where at least `quorum` participants
+/// are available (if need be doesn't count), buffers applied. `None` while no
+/// cell gets there.
+pub fn first_at_quorum(
+    participants: &[ParticipantRanges],
+    slot_duration: i32,
+    quorum: usize,
+) -> Option<Suggestion> {
+    let step = chrono::Duration::minutes(slot_duration as i64);
+    bucket_effective_indices(participants, slot_duration)
+        .into_iter()
+        .find(|(_, cell)| cell.available.len() >= quorum)
+        .map(|(start_at, cell)| Suggestion {
+            start_at,
+            end_at: start_at + step,
+            count: cell.available.len(),
+            explanation: explain(participants, &cell, Vec::new()),
+        })
+}
+
+fn explain(
+    participants: &[ParticipantRanges],
+    cell: &CellIndices,
+    roles: Vec<RoleCoverage>,
+) -> Explanation {
+    let mut names_available = Vec::new();
+    let mut names_if_need_be = Vec::new();
+    let mut names_missing = Vec::new();
+    let mut organizer_available = false;
+
+    for (index, participant) in participants.iter().enumerate() {
+        if cell.available.contains(&index) {
+            organizer_available |= participant.is_organizer;
+            names_available.push(participant.name.clone());
+        } else if cell.if_need_be.contains(&index) {
+            names_if_need_be.push(participant.name.clone());
+        } else {
+            names_missing.push(participant.name.clone());
+        }
+    }
+
+    Explanation {
+        score: ScoreBreakdown {
+            score: cell.score(),
+            participants_available: names_available.len(),
+            participants_if_need_be: names_if_need_be.len(),
+            participants_total: participants.len(),
+            organizer_available,
+        },
+        available: names_available,
+        if_need_be: names_if_need_be,
+        missing: names_missing,
+        roles,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranges::TimeRange;
+    use chrono::{Duration, TimeZone};
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2030, 1, 1, hour, 0, 0).unwrap()
+    }
+
+    fn bucket(start: u32, end: u32, count: usize) -> SlotBucket {
+        SlotBucket {
+            start_at: at(start),
+            end_at: at(end),
+            count,
+            participants: vec![],
+            if_need_be: vec![],
+        }
+    }
+
+    fn participant(name: &str, is_organizer: bool, ranges: &[(u32, u32)]) -> ParticipantRanges {
+        ParticipantRanges {
+            name: name.to_string(),
+            is_organizer,
+            availabilities: ranges
+                .iter()
+                .map(|&(s, e)| TimeRange::new(at(s), at(e)))
+                .collect(),
+            if_need_be: vec![],
+            buffer_minutes: 0,
+            role: None,
+        }
+    }
+
+    #[test]
+    fn test_first_at_quorum_picks_earliest_cell() {
+        let mut maybe = participant("Carol", false, &[]);
+        maybe.if_need_be = vec![TimeRange::new(at(9), at(10))];
+        let participants = vec![
+            participant("Alice", true, &[(9, 13)]),
+            participant("Bob", false, &[(11, 13)]),
+            maybe,
+            participant("Dave", false, &[(12, 13)]),
+        ];
+
+        let first = first_at_quorum(&participants, 60, 2).unwrap();
+        assert_eq!((first.start_at, first.end_at), (at(11), at(12)));
+        assert_eq!(first.explanation.available, vec!["Alice", "Bob"]);
+
+        let first = first_at_quorum(&participants, 60, 3).unwrap();
+        assert_eq!(first.start_at, at(12));
+        assert!(first_at_quorum(&participants, 60, 4).is_none());
+    }
+
+    #[test]
+    fn test_rank_slots_by_count_then_time_then_length() {
+        let ranked = rank_slots(vec![
+            bucket(11, 12, 2),
+            bucket(9, 10, 1),
+            bucket(10, 12, 2),
+            bucket(10, 11, 2),
+        ]);
+        let order: Vec<_> = ranked.iter().map(|b| (b.start_at, b.end_at)).collect();
+        assert_eq!(
+            order,
+            vec![
+                (at(10), at(11)),
+                (at(10), at(12)),
+                (at(11), at(12)),
+                (at(9), at(10))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suggest_is_independent_of_input_order() {
+        let mut participants = vec![
+            participant("Alice", true, &[(9, 12)]),
+            participant("Bob", false, &[(9, 10), (11, 12)]),
+        ];
+        let first = suggest(&participants, 60, 10);
+        participants.reverse();
+        let second = suggest(&participants, 60, 10);
+
+        let windows = |s: &[Suggestion]| -> Vec<_> {
+            s.iter().map(|x| (x.start_at, x.end_at, x.count)).collect()
+        };
+        assert_eq!(windows(&first), windows(&second));
+        // Two equal-score windows: the earlier one wins
+        assert_eq!(first[0].start_at, at(9));
+        assert_eq!(first[1].start_at, at(11));
+        assert_eq!(first[2].count, 1);
+    }
+
+    #[test]
+    fn test_suggest_merges_windows_and_explains() {
+        let participants = vec![
+            participant("Alice", true, &[(9, 12)]),
+            participant("Bob", false, &[(10, 12)]),
+            participant("Carol", false, &[]),
+        ];
+
+        let suggestions = suggest(&participants, 60, 1);
+        assert_eq!(suggestions.len(), 1);
+
+        let best = &suggestions[0];
+        assert_eq!(
+            (best.start_at, best.end_at),
+            (at(10), at(10) + Duration::hours(2))
+        );
+        assert_eq!(best.explanation.available, vec!["Alice", "Bob"]);
+        assert_eq!(best.explanation.missing, vec!["Carol"]);
+        assert_eq!(
+            best.explanation.score,
+            ScoreBreakdown {
+                score: 4,
+                participants_available: 2,
+                participants_if_need_be: 0,
+                participants_total: 3,
+                organizer_available: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_suggest_respects_buffer() {
+        // 30-minute cells; Bob is free 9-11 but needs 30 minutes either side
+        let mut bob = participant("Bob", false, &[(9, 11)]);
+        bob.buffer_minutes = 30;
+        let participants = vec![participant("Alice", true, &[(9, 11)]), bob];
+
+        let best = &suggest(&participants, 30, 1)[0];
+        assert_eq!(best.count, 2);
+        assert_eq!(best.start_at, at(9) + Duration::minutes(30));
+        assert_eq!(best.end_at, at(10) + Duration::minutes(30));
+    }
+
+    #[test]
+    fn test_suggest_weights_if_need_be_below_available() {
+        let mut bob = participant("Bob", false, &[(9, 10)]);
+        bob.if_need_be = vec![TimeRange::new(at(10), at(11))];
+        let mut carol = participant("Carol", false, &[]);
+        carol.if_need_be = vec![TimeRange::new(at(10), at(11))];
+        let participants = vec![participant("Alice", true, &[(9, 11)]), bob, carol];
+
+        let suggestions = suggest(&participants, 60, 2);
+        // Two "maybe"s (score 2+1+1) tie with one "yes" (2+2); the earlier window wins
+        assert_eq!(suggestions[0].start_at, at(9));
+        assert_eq!(suggestions[0].explanation.score.score, 4);
+        assert_eq!(suggestions[1].start_at, at(10));
+        assert_eq!(suggestions[1].count, 1);
+        assert_eq!(suggestions[1].explanation.if_need_be, vec!["Bob", "Carol"]);
+        assert!(suggestions[1].explanation.missing.is_empty());
+        assert_eq!(suggestions[1].explanation.score.participants_if_need_be, 2);
+    }
+
+    #[test]
+    fn test_suggest_with_roles_requires_every_role() {
+        let mut host = participant("Hana", true, &[(9, 12)]);
+        host.role = Some("host".to_string());
+        let mut first = participant("Rui", false, &[(9, 11)]);
+        first.role = Some("reviewer".to_string());
+        let mut second = participant("Sam", false, &[]);
+        second.if_need_be = vec![TimeRange::new(at(10), at(12))];
+        second.role = Some("reviewer".to_string());
+        let participants = vec![host, first, second];
+        let requirements = vec![
+            RoleRequirement {
+                role: "host".to_string(),
+                required: 1,
+            },
+            RoleRequirement {
+                role: "reviewer".to_string(),
+                required: 2,
+            },
+        ];
+
+        // Only 10-11 has both reviewers; 9-10 and 11-12 have one each
+        let suggestions = suggest_with_roles(&participants, 60, 10, &requirements);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(
+            (suggestions[0].start_at, suggestions[0].end_at),
+            (at(10), at(11))
+        );
+        let roles = &suggestions[0].explanation.roles;
+        assert_eq!(roles[0].filled, vec!["Hana"]);
+        assert_eq!(roles[1].filled, vec!["Rui", "Sam"]);
+
+        // Without requirements every window is a candidate
+        assert_eq!(suggest(&participants, 60, 10).len(), 3);
+    }
+}
@@ -0,0 +1,169 @@
+//! Sweep-line overlap counting across many participants.
+//!
+//! Where `heatmap` expands availability into fixed `slot_duration` cells,
+//! this works on range boundaries only: O(N log N) in the total number of
+//! ranges, independent of the slot duration and of how long the ranges are.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ranges::{TimeRange, merge_in_place, merged};
+
+/// A maximal span during which the same number of participants overlap.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Coverage {
+    pub range: TimeRange,
+    pub count: usize,
+}
+
+/// How many participants are available over time: sorted, maximal spans of
+/// constant count. Spans nobody covers are left out. Each participant counts
+/// at most once at any instant, even if their own ranges overlap.
+pub fn coverage<S: AsRef<[TimeRange]>>(participants: &[S]) -> Vec<Coverage> {
+    let mut boundaries = Vec::new();
+    for ranges in participants {
+        for range in merged(ranges.as_ref()) {
+            boundaries.push((range.start_at, 1i64));
+            boundaries.push((range.end_at, -1i64));
+        }
+    }
+    boundaries.sort_unstable();
+
+    let mut spans: Vec<Coverage> = Vec::new();
+    let mut count = 0i64;
+    let mut i = 0;
+    while i < boundaries.len() {
+        let at = boundaries[i].0;
+        // Apply every boundary at this instant before emitting the next span
+        while i < boundaries.len() && boundaries[i].0 == at {
+            count += boundaries[i].1;
+            i += 1;
+        }
+        let Some(&(next, _)) = boundaries.get(i) else {
+            break;
+        };
+        if count == 0 {
+            continue;
+        }
+
+        let span = TimeRange::new(at, next);
+        match spans.last_mut() {
+            // One participant leaving as another arrives keeps the count
+            Some(last) if last.range.end_at == at && last.count == count as usize => {
+                last.range.end_at = next;
+            }
+            _ => spans.push(Coverage {
+                range: span,
+                count: count as usize,
+            }),
+        }
+    }
+    spans
+}
+
+/// Merged ranges where at least `min_count` participants overlap.
+pub fn covered_by_at_least(coverage: &[Coverage], min_count: usize) -> Vec<TimeRange> {
+    let mut ranges: Vec<TimeRange> = coverage
+        .iter()
+        .filter(|span| span.count >= min_count)
+        .map(|span| span.range)
+        .collect();
+    merge_in_place(&mut ranges);
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn range(start: i64, end: i64) -> TimeRange {
+        TimeRange::new(
+            Utc.timestamp_opt(start, 0).unwrap(),
+            Utc.timestamp_opt(end, 0).unwrap(),
+        )
+    }
+
+    fn span(start: i64, end: i64, count: usize) -> Coverage {
+        Coverage {
+            range: range(start, end),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_coverage_counts_overlaps() {
+        let participants = vec![
+            vec![range(0, 100)],
+            vec![range(50, 150)],
+            vec![range(200, 300)],
+        ];
+        assert_eq!(
+            coverage(&participants),
+            vec![
+                span(0, 50, 1),
+                span(50, 100, 2),
+                span(100, 150, 1),
+                span(200, 300, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coverage_counts_participant_once_and_joins_handovers() {
+        let participants = vec![
+            // Overlapping ranges of one participant
+            vec![range(0, 100), range(50, 100)],
+            // Hands over to the next participant at 100
+            vec![range(100, 200)],
+        ];
+        assert_eq!(coverage(&participants), vec![span(0, 200, 1)]);
+    }
+
+    #[test]
+    fn test_covered_by_at_least() {
+        let participants = vec![
+            vec![range(0, 100)],
+            vec![range(50, 150)],
+            vec![range(80, 120)],
+        ];
+        let spans = coverage(&participants);
+        assert_eq!(covered_by_at_least(&spans, 2), vec![range(50, 120)]);
+        assert_eq!(covered_by_at_least(&spans, 3), vec![range(80, 100)]);
+        assert!(covered_by_at_least(&spans, 4).is_empty());
+    }
+
+    #[test]
+    fn test_coverage_matches_brute_force() {
+        // Deterministic pseudo-random input over a small integer timeline
+        let mut seed: u64 = 42;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+        let participants: Vec<Vec<TimeRange>> = (0..20)
+            .map(|_| {
+                (0..5)
+                    .map(|_| {
+                        let start = next(200) as i64;
+                        range(start, start + 1 + next(40) as i64)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let spans = coverage(&participants);
+        for t in 0..260 {
+            let expected = participants
+                .iter()
+                .filter(|ranges| ranges.iter().any(|r| r.contains(&range(t, t + 1))))
+                .count();
+            let actual = spans
+                .iter()
+                .find(|s| s.range.contains(&range(t, t + 1)))
+                .map_or(0, |s| s.count);
+            assert_eq!(actual, expected, "at {}", t);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! JSON-in/JSON-out entry points for the browser build.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{ParticipantRanges, TimeRange};
+
+fn parse<T: serde::de::DeserializeOwned>(input: &str) -> Result<T, JsError> {
+    serde_json::from_str(input).map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn render<T: serde::Serialize>(value: &T) -> Result<String, JsError> {
+    serde_json::to_string(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[wasm_bindgen(js_name = mergeTimeRanges)]
+pub fn merge_time_ranges(ranges_json: &str) -> Result<String, JsError> {
+    let ranges: Vec<TimeRange> = parse(ranges_json)?;
+    render(&crate::merge_time_ranges(ranges))
+}
+
+/// Takes one array of ranges per participant.
+#[wasm_bindgen(js_name = coverage)]
+pub fn coverage(participants_json: &str) -> Result<String, JsError> {
+    let participants: Vec<Vec<TimeRange>> = parse(participants_json)?;
+    render(&crate::coverage(&participants))
+}
+
+#[wasm_bindgen(js_name = bucketAvailability)]
+pub fn bucket_availability(participants_json: &str, slot_duration: i32) -> Result<String, JsError> {
+    let participants: Vec<ParticipantRanges> = parse(participants_json)?;
+    render(&crate::bucket_availability(&participants, slot_duration))
+}
+
+#[wasm_bindgen(js_name = rankSlots)]
+pub fn rank_slots(participants_json: &str, slot_duration: i32) -> Result<String, JsError> {
+    let participants: Vec<ParticipantRanges> = parse(participants_json)?;
+    render(&crate::rank_slots(crate::bucket_availability(
+        &participants,
+        slot_duration,
+    )))
+}
+
+#[wasm_bindgen]
+pub fn suggest(
+    participants_json: &str,
+    slot_duration: i32,
+    limit: usize,
+) -> Result<String, JsError> {
+    let participants: Vec<ParticipantRanges> = parse(participants_json)?;
+    render(&crate::suggest(&participants, slot_duration, limit))
+}
+
+/// Weekly slots as ranges in the reference week; see [`crate::weekly`].
+#[wasm_bindgen(js_name = weeklyToRanges)]
+pub fn weekly_to_ranges(slots_json: &str, time_zone: &str) -> Result<String, JsError> {
+    let slots: Vec<crate::WeeklySlot> = parse(slots_json)?;
+    let ranges = crate::to_reference_ranges(&slots, time_zone)
+        .ok_or_else(|| JsError::new("Unknown time zone"))?;
+    render(&ranges)
+}
@@ -0,0 +1,180 @@
+//! Weekly patterns: candidate times given as a weekday and local times of
+//! day ("Mondays 09:00–12:00") rather than dates.
+//!
+//! They are stored as ordinary ranges by placing every weekday on its date in
+//! one fixed reference week, in the event's zone. Merging, availability,
+//! results and suggestions then work unchanged; only the week boundary needs
+//! care, since a Sunday slot running past midnight continues on Monday.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::dates::local_instant as instant;
+use crate::ranges::{TimeRange, merge_time_ranges};
+
+/// A recurring window on one weekday, in local time.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct WeeklySlot {
+    pub weekday: Weekday,
+    pub start_time: NaiveTime,
+    /// At or before `start_time` means the next day, so `00:00` is midnight.
+    pub end_time: NaiveTime,
+}
+
+/// Monday of the reference week. January avoids DST changes in nearly every zone.
+fn reference_monday() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, 1).expect("valid date")
+}
+
+/// The reference week in `time_zone`, from Monday 00:00 to the next Monday
+/// 00:00. Returns `None` when `time_zone` is not a known IANA zone name.
+pub fn reference_week(time_zone: &str) -> Option<TimeRange> {
+    let tz: Tz = time_zone.parse().ok()?;
+    let monday = reference_monday();
+    Some(TimeRange::new(
+        instant(&tz, monday, NaiveTime::MIN),
+        instant(&tz, monday + Duration::days(7), NaiveTime::MIN),
+    ))
+}
+
+/// Places `slots` in the reference week and merges them. The part of a
+/// Sunday slot past midnight wraps around to the start of the week.
+/// Returns `None` when `time_zone` is not a known IANA zone name.
+pub fn to_reference_ranges(slots: &[WeeklySlot], time_zone: &str) -> Option<Vec<TimeRange>> {
+    let tz: Tz = time_zone.parse().ok()?;
+    let week = reference_week(time_zone)?;
+
+    let mut ranges = Vec::with_capacity(slots.len());
+    for slot in slots {
+        let date = reference_monday() + Duration::days(slot.weekday.num_days_from_monday() as i64);
+        let end_date = if slot.end_time <= slot.start_time {
+            date + Duration::days(1)
+        } else {
+            date
+        };
+        let start_at = instant(&tz, date, slot.start_time);
+        let end_at = instant(&tz, end_date, slot.end_time);
+
+        if end_at <= week.end_at {
+            ranges.push(TimeRange::new(start_at, end_at));
+        } else {
+            ranges.push(TimeRange::new(start_at, week.end_at));
+            ranges.push(TimeRange::new(
+                week.start_at,
+                week.start_at + (end_at - week.end_at),
+            ));
+        }
+    }
+    Some(merge_time_ranges(ranges))
+}
+
+/// Reads reference-week ranges back as weekly slots, split at local
+/// midnight so each slot stays within one weekday. Returns `None` when
+/// `time_zone` is not a known IANA zone name.
+pub fn from_reference_ranges(ranges: &[TimeRange], time_zone: &str) -> Option<Vec<WeeklySlot>> {
+    let tz: Tz = time_zone.parse().ok()?;
+
+    let mut slots = Vec::new();
+    for range in ranges {
+        let mut start_at = range.start_at;
+        while start_at < range.end_at {
+            let local_start = start_at.with_timezone(&tz);
+            let midnight = instant(
+                &tz,
+                local_start.date_naive() + Duration::days(1),
+                NaiveTime::MIN,
+            );
+            let end_at = range.end_at.min(midnight);
+            slots.push(WeeklySlot {
+                weekday: local_start.weekday(),
+                start_time: local_start.time(),
+                end_time: end_at.with_timezone(&tz).time(),
+            });
+            start_at = end_at;
+        }
+    }
+    Some(slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn time(hour: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn slot(weekday: Weekday, start: u32, end: u32) -> WeeklySlot {
+        WeeklySlot {
+            weekday,
+            start_time: time(start),
+            end_time: time(end),
+        }
+    }
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_slots_land_on_their_weekday_in_the_zone() {
+        let ranges = to_reference_ranges(&[slot(Weekday::Wed, 9, 12)], "Asia/Tokyo").unwrap();
+        assert_eq!(ranges, vec![TimeRange::new(at(3, 0), at(3, 3))]);
+        assert!(to_reference_ranges(&[], "Mars/Olympus").is_none());
+    }
+
+    #[test]
+    fn test_overlapping_and_touching_slots_merge() {
+        let ranges = to_reference_ranges(
+            &[
+                slot(Weekday::Mon, 9, 12),
+                slot(Weekday::Mon, 11, 14),
+                slot(Weekday::Mon, 14, 15),
+                slot(Weekday::Tue, 9, 10),
+            ],
+            "UTC",
+        )
+        .unwrap();
+        assert_eq!(
+            ranges,
+            vec![
+                TimeRange::new(at(1, 9), at(1, 15)),
+                TimeRange::new(at(2, 9), at(2, 10)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sunday_night_wraps_to_monday_morning() {
+        let ranges = to_reference_ranges(
+            &[slot(Weekday::Sun, 22, 2), slot(Weekday::Mon, 1, 4)],
+            "UTC",
+        )
+        .unwrap();
+        assert_eq!(
+            ranges,
+            vec![
+                TimeRange::new(at(1, 0), at(1, 4)),
+                TimeRange::new(at(7, 22), at(8, 0)),
+            ]
+        );
+
+        let slots = from_reference_ranges(&ranges, "UTC").unwrap();
+        assert_eq!(
+            slots,
+            vec![slot(Weekday::Mon, 0, 4), slot(Weekday::Sun, 22, 0)]
+        );
+    }
+
+    #[test]
+    fn test_ranges_split_at_local_midnight() {
+        let slots =
+            from_reference_ranges(&[TimeRange::new(at(2, 20), at(3, 2))], "Europe/Paris").unwrap();
+        assert_eq!(
+            slots,
+            vec![slot(Weekday::Tue, 21, 0), slot(Weekday::Wed, 0, 3)]
+        );
+    }
+}
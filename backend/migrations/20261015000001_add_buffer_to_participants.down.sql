@@ -0,0 +1 @@
+ALTER TABLE participants DROP COLUMN buffer_minutes;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE participants ADD COLUMN buffer_minutes INT NOT NULL DEFAULT 0;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE participants ADD COLUMN time_zone VARCHAR(100);
\ No newline at end of file
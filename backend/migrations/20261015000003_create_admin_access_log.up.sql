@@ -0,0 +1,11 @@
+-- Every admin lookup is recorded for accountability
+CREATE TABLE admin_access_log (
+    id BIGSERIAL PRIMARY KEY,
+    action VARCHAR(100) NOT NULL,
+    details TEXT NOT NULL,
+    actor VARCHAR(255) NOT NULL,
+    result_count INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_admin_access_log_created_at ON admin_access_log(created_at);
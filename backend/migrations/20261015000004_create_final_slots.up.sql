@@ -0,0 +1,10 @@
+-- Slots the organizer picked when finalizing an event
+CREATE TABLE final_slots (
+    id BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    start_at TIMESTAMPTZ NOT NULL,
+    end_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_final_slots_event_id ON final_slots(event_id);
@@ -0,0 +1,7 @@
+-- Short-lived lease held by the co-host currently editing an event's slots
+CREATE TABLE event_edit_locks (
+    event_id UUID PRIMARY KEY REFERENCES events(id) ON DELETE CASCADE,
+    holder_name VARCHAR(50) NOT NULL,
+    acquired_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_participants_token;
+CREATE INDEX idx_participants_token ON participants(token);
@@ -0,0 +1,3 @@
+-- Participant tokens are the only edit credential; enforce uniqueness
+DROP INDEX IF EXISTS idx_participants_token;
+CREATE UNIQUE INDEX idx_participants_token ON participants(token);
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS email_outbox;
+ALTER TABLE events DROP COLUMN IF EXISTS final_revision;
+ALTER TABLE participants DROP COLUMN IF EXISTS rsvp_at;
+ALTER TABLE participants DROP COLUMN IF EXISTS rsvp_status;
+ALTER TABLE participants DROP COLUMN IF EXISTS email;
@@ -0,0 +1,25 @@
+-- Optional invitee address and the latest calendar RSVP received for it
+ALTER TABLE participants ADD COLUMN email VARCHAR(254);
+ALTER TABLE participants ADD COLUMN rsvp_status VARCHAR(20);
+ALTER TABLE participants ADD COLUMN rsvp_at TIMESTAMPTZ;
+
+-- Bumped on every finalize so calendar clients replace earlier invitations
+ALTER TABLE events ADD COLUMN final_revision INTEGER NOT NULL DEFAULT 0;
+
+-- Mail written in the same transaction as the change that caused it and
+-- delivered by a background task
+CREATE TABLE email_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    recipient VARCHAR(254) NOT NULL,
+    subject TEXT NOT NULL,
+    body TEXT NOT NULL,
+    calendar TEXT,
+    calendar_method VARCHAR(20),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    sent_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_email_outbox_pending ON email_outbox(id) WHERE sent_at IS NULL;
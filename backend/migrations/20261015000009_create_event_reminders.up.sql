@@ -0,0 +1,9 @@
+-- One row per organizer-triggered reminder batch; used for the daily limit
+CREATE TABLE event_reminders (
+    id BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    recipient_count INT NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_event_reminders_event_id_sent_at ON event_reminders(event_id, sent_at);
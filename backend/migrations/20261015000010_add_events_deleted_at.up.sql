@@ -0,0 +1,4 @@
+-- Soft delete: trashed events are hidden everywhere and purged after a grace period
+ALTER TABLE events ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX idx_events_deleted_at ON events(deleted_at) WHERE deleted_at IS NOT NULL;
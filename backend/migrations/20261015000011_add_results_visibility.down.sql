@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_events_results_token;
+ALTER TABLE events DROP COLUMN IF EXISTS results_token;
+ALTER TABLE events DROP COLUMN IF EXISTS results_visibility;
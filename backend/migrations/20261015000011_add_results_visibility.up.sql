@@ -0,0 +1,6 @@
+-- 'public': anyone with the public link sees results; 'private': results need the results token
+ALTER TABLE events ADD COLUMN results_visibility VARCHAR(20) NOT NULL DEFAULT 'public';
+-- Volatile default, so existing rows each get their own token
+ALTER TABLE events ADD COLUMN results_token VARCHAR(255) NOT NULL DEFAULT gen_random_uuid()::text;
+
+CREATE UNIQUE INDEX idx_events_results_token ON events(results_token);
@@ -0,0 +1,3 @@
+ALTER TABLE events DROP COLUMN IF EXISTS results_revealed_at;
+ALTER TABLE events DROP COLUMN IF EXISTS blind_until_closed;
+ALTER TABLE events DROP COLUMN IF EXISTS reveal_results_at;
@@ -0,0 +1,5 @@
+-- Blind polls: results stay hidden until reveal_results_at passes and/or the event closes
+ALTER TABLE events ADD COLUMN reveal_results_at TIMESTAMPTZ;
+ALTER TABLE events ADD COLUMN blind_until_closed BOOLEAN NOT NULL DEFAULT FALSE;
+-- Set once the reveal notification has been queued
+ALTER TABLE events ADD COLUMN results_revealed_at TIMESTAMPTZ;
@@ -0,0 +1,2 @@
+-- 'available', 'if_need_be' or 'unavailable'; rows before this migration were all "yes"
+ALTER TABLE availabilities ADD COLUMN availability_level VARCHAR(20) NOT NULL DEFAULT 'available';
@@ -0,0 +1,2 @@
+ALTER TABLE participants DROP COLUMN IF EXISTS role;
+DROP TABLE IF EXISTS event_roles;
@@ -0,0 +1,11 @@
+-- Roles every suggested window must be able to staff, e.g. 1 host + 2 reviewers
+CREATE TABLE event_roles (
+    id BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    required_count INT NOT NULL,
+    UNIQUE(event_id, name)
+);
+
+-- Role the participant picked when responding; NULL fills no role
+ALTER TABLE participants ADD COLUMN role VARCHAR(50);
@@ -0,0 +1,2 @@
+ALTER TABLE events DROP COLUMN IF EXISTS assignment_confirmed_at;
+DROP TABLE IF EXISTS slot_assignments;
@@ -0,0 +1,9 @@
+-- Who attends which final slot when an event is finalized with several
+CREATE TABLE slot_assignments (
+    final_slot_id BIGINT NOT NULL REFERENCES final_slots(id) ON DELETE CASCADE,
+    participant_id BIGINT NOT NULL REFERENCES participants(id) ON DELETE CASCADE,
+    PRIMARY KEY (final_slot_id, participant_id)
+);
+
+-- Set when the organizer confirms the assignment; re-finalizing clears it
+ALTER TABLE events ADD COLUMN assignment_confirmed_at TIMESTAMPTZ;
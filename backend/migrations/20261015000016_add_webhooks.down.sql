@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS webhook_deliveries;
+ALTER TABLE events DROP COLUMN IF EXISTS webhook_secret;
+ALTER TABLE events DROP COLUMN IF EXISTS webhook_url;
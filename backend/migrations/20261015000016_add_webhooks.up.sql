@@ -0,0 +1,20 @@
+-- Organizer callback for event activity; deliveries are signed with the secret
+ALTER TABLE events ADD COLUMN webhook_url VARCHAR(2048);
+ALTER TABLE events ADD COLUMN webhook_secret VARCHAR(64);
+
+-- Written in the same transaction as the activity and posted by a background
+-- task; rows stay as the delivery log
+CREATE TABLE webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    event_type VARCHAR(40) NOT NULL,
+    url VARCHAR(2048) NOT NULL,
+    payload TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_webhook_deliveries_pending ON webhook_deliveries(next_attempt_at) WHERE delivered_at IS NULL;
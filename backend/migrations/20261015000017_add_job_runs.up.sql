@@ -0,0 +1,11 @@
+-- One row per background task run, served at /admin/jobs
+CREATE TABLE job_runs (
+    id BIGSERIAL PRIMARY KEY,
+    job VARCHAR(40) NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    affected_rows BIGINT NOT NULL DEFAULT 0,
+    error TEXT
+);
+
+CREATE INDEX idx_job_runs_job_started ON job_runs(job, started_at DESC);
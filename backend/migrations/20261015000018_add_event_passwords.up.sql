@@ -0,0 +1,2 @@
+-- Optional passphrase (argon2 PHC string) gating the participant endpoints
+ALTER TABLE events ADD COLUMN password_hash TEXT;
@@ -0,0 +1 @@
+ALTER TABLE events DROP COLUMN IF EXISTS close_at_quorum;
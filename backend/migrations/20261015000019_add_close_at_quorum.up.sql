@@ -0,0 +1,2 @@
+-- Close the event once this many participants are available in one cell
+ALTER TABLE events ADD COLUMN close_at_quorum INTEGER;
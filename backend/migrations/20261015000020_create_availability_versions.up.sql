@@ -0,0 +1,13 @@
+-- Snapshot of a participant's availability after every change, for the
+-- organizer's history view
+CREATE TABLE availability_versions (
+    id BIGSERIAL PRIMARY KEY,
+    participant_id BIGINT NOT NULL REFERENCES participants(id) ON DELETE CASCADE,
+    -- [{start_at, end_at, availability_level}], merged and sorted
+    availabilities JSONB NOT NULL,
+    -- 'participant' for their own edits, 'organizer' when slot changes trimmed them
+    source VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_availability_versions_participant ON availability_versions(participant_id, id);
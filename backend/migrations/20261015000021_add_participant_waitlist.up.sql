@@ -0,0 +1,3 @@
+-- Submissions past the participant cap wait for the organizer as `pending`
+-- and stay out of results until approved
+ALTER TABLE participants ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'confirmed';
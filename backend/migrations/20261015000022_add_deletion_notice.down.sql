@@ -0,0 +1,4 @@
+ALTER TABLE events DROP COLUMN IF EXISTS deletion_notice_sent_at;
+ALTER TABLE events DROP COLUMN IF EXISTS deletion_notice;
+ALTER TABLE events DROP COLUMN IF EXISTS retention_extended_until;
+ALTER TABLE events DROP COLUMN IF EXISTS organizer_email;
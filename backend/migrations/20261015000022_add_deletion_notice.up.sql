@@ -0,0 +1,9 @@
+-- Where service notices to the organizer go, e.g. the upcoming deletion
+ALTER TABLE events ADD COLUMN organizer_email VARCHAR(254);
+-- Set by POST /events/{organizer_token}/extend; retention counts from
+-- created_at while NULL
+ALTER TABLE events ADD COLUMN retention_extended_until TIMESTAMPTZ;
+-- Per-event suppression: the organizer's opt-out, and when the notice for
+-- the current expiry went out (cleared by extending)
+ALTER TABLE events ADD COLUMN deletion_notice BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE events ADD COLUMN deletion_notice_sent_at TIMESTAMPTZ;
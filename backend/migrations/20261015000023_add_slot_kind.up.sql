@@ -0,0 +1,3 @@
+-- 'weekly' events keep their slots in a fixed reference week and present
+-- them as weekday + time of day (core::weekly)
+ALTER TABLE events ADD COLUMN slot_kind VARCHAR(20) NOT NULL DEFAULT 'dates';
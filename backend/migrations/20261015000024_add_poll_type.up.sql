@@ -0,0 +1,3 @@
+-- 'date' polls offer whole days: each candidate date is stored as the slot
+-- from local midnight to midnight in the event's zone
+ALTER TABLE events ADD COLUMN poll_type VARCHAR(20) NOT NULL DEFAULT 'datetime';
@@ -0,0 +1,4 @@
+-- Notification mails are rendered in the event's locale; HTML bodies are
+-- sent as an alternative to the plain-text one
+ALTER TABLE events ADD COLUMN locale VARCHAR(20) NOT NULL DEFAULT 'en';
+ALTER TABLE email_outbox ADD COLUMN html TEXT;
@@ -0,0 +1,4 @@
+DELETE FROM webhook_deliveries WHERE event_id IS NULL;
+ALTER TABLE webhook_deliveries DROP COLUMN IF EXISTS webhook_secret;
+ALTER TABLE webhook_deliveries ALTER COLUMN event_id SET NOT NULL;
+ALTER TABLE events DROP COLUMN IF EXISTS expiry_webhook_sent_at;
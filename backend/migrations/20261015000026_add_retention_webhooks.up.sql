@@ -0,0 +1,8 @@
+-- When `event.expiring_soon` went out for the current expiry (cleared by
+-- extending); kept apart from the mail notice, which organizers can opt out of
+ALTER TABLE events ADD COLUMN expiry_webhook_sent_at TIMESTAMPTZ;
+
+-- `event.purged` outlives its event: the row is detached before the purge and
+-- carries the secret it is signed with
+ALTER TABLE webhook_deliveries ALTER COLUMN event_id DROP NOT NULL;
+ALTER TABLE webhook_deliveries ADD COLUMN webhook_secret VARCHAR(64);
@@ -0,0 +1 @@
+ALTER TABLE event_slots DROP COLUMN IF EXISTS locked;
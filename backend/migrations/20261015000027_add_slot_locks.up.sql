@@ -0,0 +1,3 @@
+-- Set by the organizer for slots that are no longer on offer (e.g. booked
+-- elsewhere); submissions are clipped to the unlocked slots
+ALTER TABLE event_slots ADD COLUMN locked BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_events_organizer_account_id;
+ALTER TABLE events DROP COLUMN IF EXISTS organizer_account_id;
+DROP TABLE IF EXISTS organizer_accounts;
@@ -0,0 +1,11 @@
+-- Groups an organizer's events for their dashboard; the token is the only
+-- credential, like the per-event tokens
+CREATE TABLE organizer_accounts (
+    id UUID PRIMARY KEY,
+    token VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+ALTER TABLE events ADD COLUMN organizer_account_id UUID REFERENCES organizer_accounts(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_events_organizer_account_id ON events(organizer_account_id, created_at DESC);
@@ -0,0 +1,7 @@
+DELETE FROM email_outbox WHERE event_id IS NULL;
+ALTER TABLE email_outbox ALTER COLUMN event_id SET NOT NULL;
+DROP INDEX IF EXISTS idx_events_user_id;
+ALTER TABLE events DROP COLUMN IF EXISTS user_id;
+DROP TABLE IF EXISTS user_sessions;
+DROP TABLE IF EXISTS magic_links;
+DROP TABLE IF EXISTS users;
@@ -0,0 +1,33 @@
+-- Created on the first verified login; `email` is stored lowercased
+CREATE TABLE users (
+    id UUID PRIMARY KEY,
+    email VARCHAR(254) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL,
+    last_login_at TIMESTAMPTZ
+);
+
+-- Single-use login links; only the SHA-256 of the emailed token is kept
+CREATE TABLE magic_links (
+    token_hash VARCHAR(64) PRIMARY KEY,
+    email VARCHAR(254) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_magic_links_email ON magic_links(email, created_at);
+
+-- Browser sessions behind the session cookie, hashed like the links
+CREATE TABLE user_sessions (
+    token_hash VARCHAR(64) PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+ALTER TABLE events ADD COLUMN user_id UUID REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_events_user_id ON events(user_id, created_at DESC);
+
+-- Login mail belongs to no event
+ALTER TABLE email_outbox ALTER COLUMN event_id DROP NOT NULL;
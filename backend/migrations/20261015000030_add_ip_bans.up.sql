@@ -0,0 +1,10 @@
+-- Clients blocked by the abuse detector or an operator; read back into every
+-- replica's memory so bans survive restarts
+CREATE TABLE ip_bans (
+    ip VARCHAR(45) PRIMARY KEY,
+    reason TEXT NOT NULL,
+    -- `auto` for the detector, otherwise the admin actor
+    banned_by VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
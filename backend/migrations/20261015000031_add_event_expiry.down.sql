@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_events_expires_at;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS retention_extended_until TIMESTAMPTZ;
+UPDATE events SET retention_extended_until = expires_at
+WHERE expires_at <> created_at + INTERVAL '7 days';
+ALTER TABLE events DROP COLUMN IF EXISTS expires_at;
+ALTER TABLE events DROP COLUMN IF EXISTS retention_days;
@@ -0,0 +1,10 @@
+-- Retention the organizer chose at creation; /extend keeps the event this
+-- long again
+ALTER TABLE events ADD COLUMN retention_days INTEGER NOT NULL DEFAULT 7;
+-- When the retention job trashes the event; replaces the nullable
+-- retention_extended_until, which counted from created_at while unset
+ALTER TABLE events ADD COLUMN expires_at TIMESTAMPTZ;
+UPDATE events SET expires_at = COALESCE(retention_extended_until, created_at + INTERVAL '7 days');
+ALTER TABLE events ALTER COLUMN expires_at SET NOT NULL;
+ALTER TABLE events DROP COLUMN retention_extended_until;
+CREATE INDEX idx_events_expires_at ON events (expires_at) WHERE deleted_at IS NULL;
@@ -0,0 +1,12 @@
+-- Discussion under an event; each comment belongs to the participant who
+-- wrote it and goes when they are removed
+CREATE TABLE event_comments (
+    id BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    participant_id BIGINT NOT NULL REFERENCES participants(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_event_comments_event ON event_comments(event_id, id);
+CREATE INDEX idx_event_comments_participant ON event_comments(participant_id);
@@ -0,0 +1,3 @@
+-- Public results show counts instead of names; fixed at creation since
+-- participants answer on that promise
+ALTER TABLE events ADD COLUMN anonymous_results BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,11 @@
+-- Conditions a final slot must meet, e.g. at least 5 attendees or Bob
+-- present; one row per condition
+CREATE TABLE finalization_rules (
+    id BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    min_participants INT,
+    participant_name VARCHAR(50),
+    CHECK ((min_participants IS NULL) <> (participant_name IS NULL))
+);
+
+CREATE INDEX idx_finalization_rules_event_id ON finalization_rules(event_id);
@@ -0,0 +1,11 @@
+-- Reusable event structures, shared by their token; events created from
+-- one keep no link to it
+CREATE TABLE templates (
+    id UUID PRIMARY KEY,
+    template_token VARCHAR(255) NOT NULL UNIQUE,
+    name VARCHAR(100) NOT NULL,
+    -- TemplateStructure: title, settings and candidate slots of the source
+    -- event, without participants, secrets or history
+    structure JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
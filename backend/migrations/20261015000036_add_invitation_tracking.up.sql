@@ -0,0 +1,5 @@
+-- Invitee of a calendar invitation, so the organizer can see per
+-- participant whether it went out; NULL for every other mail
+ALTER TABLE email_outbox ADD COLUMN participant_id BIGINT REFERENCES participants(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_email_outbox_participant ON email_outbox(participant_id, id) WHERE participant_id IS NOT NULL;
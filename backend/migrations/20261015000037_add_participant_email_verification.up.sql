@@ -0,0 +1,18 @@
+-- Set once the participant follows the link mailed to `email`; cleared when
+-- the address changes. Only confirmed addresses receive participant mail.
+ALTER TABLE participants ADD COLUMN email_verified_at TIMESTAMPTZ;
+
+-- Single-use confirmation links; only the SHA-256 of the emailed token is kept.
+-- `email` is the address the link confirms, so a link for an address the
+-- participant has since replaced confirms nothing.
+CREATE TABLE email_verifications (
+    token_hash VARCHAR(64) PRIMARY KEY,
+    participant_id BIGINT NOT NULL REFERENCES participants(id) ON DELETE CASCADE,
+    email VARCHAR(254) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_email_verifications_participant ON email_verifications(participant_id, created_at);
+CREATE INDEX idx_email_verifications_email ON email_verifications(LOWER(email), created_at);
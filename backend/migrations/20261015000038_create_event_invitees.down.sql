@@ -0,0 +1,2 @@
+ALTER TABLE event_reminders DROP COLUMN IF EXISTS invitee_count;
+DROP TABLE IF EXISTS event_invitees;
@@ -0,0 +1,13 @@
+-- Addresses the organizer registered on creation; reminders go to those that
+-- haven't answered. Matched to participants by address, case-insensitively.
+CREATE TABLE event_invitees (
+    id BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    email VARCHAR(254) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_event_invitees_event_id_email ON event_invitees(event_id, LOWER(email));
+
+-- Split of each reminder batch for the send log
+ALTER TABLE event_reminders ADD COLUMN invitee_count INT NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE webhook_deliveries DROP COLUMN IF EXISTS channel;
+ALTER TABLE events DROP COLUMN IF EXISTS slack_webhook_url;
@@ -0,0 +1,5 @@
+-- Slack incoming webhook for activity messages; unlike `webhook_url` unsigned
+ALTER TABLE events ADD COLUMN slack_webhook_url VARCHAR(2048);
+
+-- Chat messages share the webhook queue; only `webhook` rows are signed
+ALTER TABLE webhook_deliveries ADD COLUMN channel VARCHAR(20) NOT NULL DEFAULT 'webhook';
@@ -0,0 +1,2 @@
+ALTER TABLE events DROP COLUMN IF EXISTS chat_webhook_url;
+ALTER TABLE events DROP COLUMN IF EXISTS discord_webhook_url;
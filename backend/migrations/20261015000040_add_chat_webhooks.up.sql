@@ -0,0 +1,3 @@
+-- Discord and generic JSON chat webhooks, alongside `slack_webhook_url`
+ALTER TABLE events ADD COLUMN discord_webhook_url VARCHAR(2048);
+ALTER TABLE events ADD COLUMN chat_webhook_url VARCHAR(2048);
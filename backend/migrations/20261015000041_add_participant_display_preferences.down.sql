@@ -0,0 +1,2 @@
+ALTER TABLE participants DROP COLUMN IF EXISTS is_hidden;
+ALTER TABLE participants DROP COLUMN IF EXISTS display_order;
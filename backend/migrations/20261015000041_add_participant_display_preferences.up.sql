@@ -0,0 +1,4 @@
+-- Organizer display preferences: pinned participants carry their position,
+-- hidden ones stay stored but out of public results
+ALTER TABLE participants ADD COLUMN display_order INTEGER;
+ALTER TABLE participants ADD COLUMN is_hidden BOOLEAN NOT NULL DEFAULT false;
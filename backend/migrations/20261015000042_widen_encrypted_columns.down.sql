@@ -0,0 +1,3 @@
+ALTER TABLE webhook_deliveries ALTER COLUMN webhook_secret TYPE VARCHAR(64);
+ALTER TABLE events ALTER COLUMN organizer_email TYPE VARCHAR(254);
+ALTER TABLE events ALTER COLUMN webhook_secret TYPE VARCHAR(64);
@@ -0,0 +1,5 @@
+-- Encrypted values (`enc:v1:<key id>:<base64>`) outgrow the plaintext limits;
+-- lengths are checked by the handlers before sealing
+ALTER TABLE events ALTER COLUMN webhook_secret TYPE TEXT;
+ALTER TABLE events ALTER COLUMN organizer_email TYPE TEXT;
+ALTER TABLE webhook_deliveries ALTER COLUMN webhook_secret TYPE TEXT;
@@ -0,0 +1,495 @@
+//! User accounts with email magic-link login.
+//!
+//! Asking for a link mails a single-use URL valid for
+//! [`MAGIC_LINK_TTL_MINUTES`]; following it opens a session kept in the
+//! [`SESSION_COOKIE`] cookie for [`SESSION_TTL_DAYS`]. Events created while a
+//! session is open are tied to the user, so the organizer links survive a
+//! cleared browser. Link and session tokens are random; only their SHA-256
+//! is stored.
+//!
+//! API clients can trade a session for a short-lived HS256 JWT at
+//! `POST /auth/token` and send it as `Authorization: Bearer`. [`JwtLayer`]
+//! checks it against `JWT_SECRET` and puts a [`CurrentUser`] in the request
+//! extensions; handlers take `CurrentUser` to require one or
+//! `Option<CurrentUser>` to use it when present.
+
+use std::{
+    convert::Infallible,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts, Request},
+    http::{
+        HeaderMap,
+        header::{AUTHORIZATION, COOKIE},
+        request::Parts,
+    },
+    response::{IntoResponse, Response},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Duration, Utc};
+use futures::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+use tower::{Layer, Service};
+use uuid::Uuid;
+
+use crate::{
+    clock::{Clock, SharedClock},
+    error::AppError,
+};
+
+pub const MAGIC_LINK_TTL_MINUTES: i64 = 15;
+pub const SESSION_TTL_DAYS: i64 = 30;
+pub const SESSION_COOKIE: &str = "agreed_time_session";
+pub const JWT_TTL_MINUTES: i64 = 60;
+
+/// Tolerated clock difference with whoever else signs tokens with the key.
+const JWT_LEEWAY_SECONDS: i64 = 30;
+
+/// Unused links one address may hold at once; further requests send nothing,
+/// so the endpoint can't be used to flood a mailbox.
+pub const MAX_PENDING_LINKS: i64 = 3;
+
+#[derive(Debug, Clone)]
+pub struct User {
+    pub id: Uuid,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+}
+
+pub(crate) fn generate_secret() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Stores a new link for `email` and returns its token, or `None` when the
+/// address already holds [`MAX_PENDING_LINKS`] unused ones.
+pub async fn create_magic_link(
+    conn: &mut PgConnection,
+    email: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<String>, sqlx::Error> {
+    let pending = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM magic_links
+        WHERE email = $1 AND used_at IS NULL AND expires_at > $2
+        "#,
+        email,
+        now
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if pending >= MAX_PENDING_LINKS {
+        return Ok(None);
+    }
+
+    let token = generate_secret();
+    sqlx::query!(
+        r#"
+        INSERT INTO magic_links (token_hash, email, created_at, expires_at)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        hash_token(&token),
+        email,
+        now,
+        now + Duration::minutes(MAGIC_LINK_TTL_MINUTES)
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(Some(token))
+}
+
+/// Spends a link and opens a session for its address, creating the user on
+/// first login. `None` for unknown, used and expired links.
+pub async fn redeem_magic_link(
+    conn: &mut PgConnection,
+    token: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<(User, String)>, sqlx::Error> {
+    let Some(email) = sqlx::query_scalar!(
+        r#"
+        UPDATE magic_links SET used_at = $2
+        WHERE token_hash = $1 AND used_at IS NULL AND expires_at > $2
+        RETURNING email
+        "#,
+        hash_token(token),
+        now
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        INSERT INTO users (id, email, created_at, last_login_at)
+        VALUES ($1, $2, $3, $3)
+        ON CONFLICT (email) DO UPDATE SET last_login_at = EXCLUDED.last_login_at
+        RETURNING id, email, created_at
+        "#,
+        Uuid::new_v4(),
+        email,
+        now
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let session = generate_secret();
+    sqlx::query!(
+        r#"
+        INSERT INTO user_sessions (token_hash, user_id, created_at, expires_at)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        hash_token(&session),
+        user.id,
+        now,
+        now + Duration::days(SESSION_TTL_DAYS)
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(Some((user, session)))
+}
+
+/// The session token sent in the request's cookies, if any.
+pub fn session_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, value)| *name == SESSION_COOKIE && !value.is_empty())
+        .map(|(_, value)| value)
+}
+
+/// The user whose live session the request carries.
+pub async fn session_user(
+    conn: &mut PgConnection,
+    headers: &HeaderMap,
+    now: DateTime<Utc>,
+) -> Result<Option<User>, sqlx::Error> {
+    let Some(token) = session_token(headers) else {
+        return Ok(None);
+    };
+    sqlx::query_as!(
+        User,
+        r#"
+        SELECT u.id, u.email, u.created_at
+        FROM user_sessions s
+        JOIN users u ON u.id = s.user_id
+        WHERE s.token_hash = $1 AND s.expires_at > $2
+        "#,
+        hash_token(token),
+        now
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+pub async fn end_session(conn: &mut PgConnection, token: &str) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM user_sessions WHERE token_hash = $1",
+        hash_token(token)
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+pub fn session_cookie(token: &str) -> String {
+    format!(
+        "{}={}; HttpOnly; Secure; SameSite=Lax; Path=/; Max-Age={}",
+        SESSION_COOKIE,
+        token,
+        Duration::days(SESSION_TTL_DAYS).num_seconds()
+    )
+}
+
+pub fn cleared_session_cookie() -> String {
+    format!(
+        "{}=; HttpOnly; Secure; SameSite=Lax; Path=/; Max-Age=0",
+        SESSION_COOKIE
+    )
+}
+
+/// The user behind the request's bearer token or, failing that, its session
+/// cookie. A token for a user deleted since it was issued counts as none.
+pub async fn request_user(
+    conn: &mut PgConnection,
+    bearer: Option<&CurrentUser>,
+    headers: &HeaderMap,
+    now: DateTime<Utc>,
+) -> Result<Option<User>, sqlx::Error> {
+    match bearer {
+        Some(current) => {
+            sqlx::query_as!(
+                User,
+                "SELECT id, email, created_at FROM users WHERE id = $1",
+                current.id
+            )
+            .fetch_optional(conn)
+            .await
+        }
+        None => session_user(conn, headers, now).await,
+    }
+}
+
+/// Drops expired sessions and links that are used or past their expiry.
+pub async fn prune_expired(pool: &PgPool, clock: &dyn Clock) -> Result<u64, sqlx::Error> {
+    let now = clock.now();
+    let sessions = sqlx::query!("DELETE FROM user_sessions WHERE expires_at <= $1", now)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    let links = sqlx::query!(
+        "DELETE FROM magic_links WHERE used_at IS NOT NULL OR expires_at <= $1",
+        now
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+    Ok(sessions + links)
+}
+
+/// The user a verified bearer token was issued to, placed in the request
+/// extensions by [`JwtLayer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentUser {
+    pub id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or(AppError::Unauthorized)
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for CurrentUser {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<CurrentUser>().cloned())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Claims {
+    sub: Uuid,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    #[serde(default)]
+    typ: Option<String>,
+}
+
+fn jwt_mac(secret: &str, signing_input: &str) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(signing_input.as_bytes());
+    mac
+}
+
+fn encode_part<T: Serialize>(value: &T) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(value).expect("claims serialize"))
+}
+
+fn decode_part<T: for<'de> Deserialize<'de>>(part: &str) -> Option<T> {
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(part).ok()?).ok()
+}
+
+/// Signs an HS256 token for `user_id` valid for [`JWT_TTL_MINUTES`].
+pub fn issue_jwt(secret: &str, user_id: Uuid, now: DateTime<Utc>) -> (String, DateTime<Utc>) {
+    let expires_at = now + Duration::minutes(JWT_TTL_MINUTES);
+    let header = encode_part(&JwtHeader {
+        alg: "HS256".to_string(),
+        typ: Some("JWT".to_string()),
+    });
+    let claims = encode_part(&Claims {
+        sub: user_id,
+        iat: now.timestamp(),
+        exp: expires_at.timestamp(),
+    });
+    let signing_input = format!("{}.{}", header, claims);
+    let signature = jwt_mac(secret, &signing_input).finalize().into_bytes();
+    let token = format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature));
+    (token, expires_at)
+}
+
+/// `None` for tokens with another algorithm, a bad signature, an `exp` in
+/// the past, and anything that doesn't parse.
+pub fn verify_jwt(secret: &str, token: &str, now: DateTime<Utc>) -> Option<CurrentUser> {
+    let (signing_input, signature) = token.rsplit_once('.')?;
+    let (header, claims) = signing_input.split_once('.')?;
+    // Only HS256: never let the token pick a weaker or unsigned scheme
+    if decode_part::<JwtHeader>(header)?.alg != "HS256" {
+        return None;
+    }
+    let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+    jwt_mac(secret, signing_input)
+        .verify_slice(&signature)
+        .ok()?;
+    let claims: Claims = decode_part(claims)?;
+    let expires_at = DateTime::from_timestamp(claims.exp, 0)?;
+    if expires_at + Duration::seconds(JWT_LEEWAY_SECONDS) <= now {
+        return None;
+    }
+    Some(CurrentUser {
+        id: claims.sub,
+        expires_at,
+    })
+}
+
+/// Checks `Authorization: Bearer` tokens when `JWT_SECRET` is set. Requests
+/// without one pass untouched; an invalid or expired token gets 401 rather
+/// than silently running anonymous. Disabled, it lets everything through.
+#[derive(Clone)]
+pub struct JwtLayer {
+    secret: Option<Arc<str>>,
+    clock: SharedClock,
+}
+
+impl JwtLayer {
+    pub fn new(secret: Option<&str>, clock: SharedClock) -> Self {
+        JwtLayer {
+            secret: secret.map(Arc::from),
+            clock,
+        }
+    }
+}
+
+impl<S> Layer<S> for JwtLayer {
+    type Service = JwtService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        JwtService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct JwtService<S> {
+    inner: S,
+    layer: JwtLayer,
+}
+
+impl<S> Service<Request> for JwtService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        if let Some(secret) = self.layer.secret.as_deref() {
+            let bearer = req
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "));
+            if let Some(token) = bearer {
+                match verify_jwt(secret, token.trim(), self.layer.clock.now()) {
+                    Some(user) => {
+                        req.extensions_mut().insert(user);
+                    }
+                    None => {
+                        return Box::pin(async { Ok(AppError::Unauthorized.into_response()) });
+                    }
+                }
+            }
+        }
+        Box::pin(self.inner.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_session_token_is_read_from_cookie_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(session_token(&headers), None);
+
+        headers.insert(
+            COOKIE,
+            HeaderValue::from_static("theme=dark; agreed_time_session=abc123; lang=ja"),
+        );
+        assert_eq!(session_token(&headers), Some("abc123"));
+
+        headers.insert(COOKIE, HeaderValue::from_static("agreed_time_session="));
+        assert_eq!(session_token(&headers), None);
+    }
+
+    #[test]
+    fn test_verify_jwt_checks_signature_algorithm_and_expiry() {
+        use chrono::TimeZone;
+
+        let now = Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+        let user_id = Uuid::new_v4();
+        let (token, expires_at) = issue_jwt("secret", user_id, now);
+
+        assert_eq!(
+            verify_jwt("secret", &token, now),
+            Some(CurrentUser {
+                id: user_id,
+                expires_at
+            })
+        );
+        assert_eq!(verify_jwt("other", &token, now), None);
+        assert_eq!(
+            verify_jwt("secret", &token, expires_at + Duration::minutes(1)),
+            None
+        );
+
+        // Same claims, unsigned
+        let (_, claims_and_signature) = token.split_once('.').unwrap();
+        let (claims, _) = claims_and_signature.split_once('.').unwrap();
+        let none_header = URL_SAFE_NO_PAD.encode(br#"{"alg":"none"}"#);
+        assert_eq!(
+            verify_jwt("secret", &format!("{}.{}.", none_header, claims), now),
+            None
+        );
+        assert_eq!(verify_jwt("secret", "not-a-token", now), None);
+    }
+
+    #[test]
+    fn test_hash_token_is_stable_hex() {
+        assert_eq!(hash_token("abc"), hash_token("abc"));
+        assert_ne!(hash_token("abc"), hash_token("abd"));
+        assert_eq!(hash_token("abc").len(), 64);
+    }
+}
@@ -0,0 +1,271 @@
+//! Short-lived in-process copies of the participant and results views.
+//!
+//! When a results page is shared in a group chat, dozens of tabs refresh the
+//! same event at once. [`ResponseCache`] keeps each built `EventResponse` and
+//! `EventResultsResponse` with its ETag for `RESPONSE_CACHE_TTL_SECS`, per
+//! event and per variant (display zone, results view), so only the first
+//! request in that window pays for the reads. Ages are measured against the
+//! `now` callers pass in, i.e. the app clock. Access checks still run on
+//! every request, since the variant only covers what a caller may see.
+//!
+//! Handlers that change what these views show call [`ResponseCache::invalidate`]
+//! after committing. Writes this replica doesn't see (other replicas,
+//! background jobs) show up once the entry expires.
+
+use axum::http::HeaderValue;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use uuid::Uuid;
+
+use crate::models::{EventResponse, EventResultsResponse};
+
+/// Events kept at most; beyond that, new entries wait for old ones to
+/// expire rather than growing the map.
+pub const MAX_EVENTS: usize = 10_000;
+
+pub type SharedResponseCache = Arc<ResponseCache>;
+
+struct Entry<T> {
+    etag: HeaderValue,
+    value: T,
+    stored_at: DateTime<Utc>,
+}
+
+impl<T> Entry<T> {
+    fn is_fresh(&self, ttl: TimeDelta, now: DateTime<Utc>) -> bool {
+        self.stored_at <= now && now - self.stored_at < ttl
+    }
+}
+
+#[derive(Default)]
+struct EventEntries {
+    event: HashMap<String, Entry<EventResponse>>,
+    results: HashMap<String, Entry<EventResultsResponse>>,
+}
+
+pub struct ResponseCache {
+    ttl: TimeDelta,
+    events: Mutex<HashMap<Uuid, EventEntries>>,
+}
+
+impl ResponseCache {
+    /// A zero `ttl` turns the cache off.
+    pub fn new(ttl: Duration) -> Self {
+        ResponseCache {
+            ttl: TimeDelta::from_std(ttl).unwrap_or(TimeDelta::MAX),
+            events: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn event(
+        &self,
+        event_id: Uuid,
+        variant: &str,
+        now: DateTime<Utc>,
+    ) -> Option<(HeaderValue, EventResponse)> {
+        let events = self.events.lock().unwrap();
+        self.fresh(events.get(&event_id)?.event.get(variant)?, now)
+    }
+
+    pub fn store_event(
+        &self,
+        event_id: Uuid,
+        variant: String,
+        etag: HeaderValue,
+        value: &EventResponse,
+        now: DateTime<Utc>,
+    ) {
+        self.store(event_id, now, |entries, stored_at| {
+            entries.event.insert(
+                variant,
+                Entry {
+                    etag,
+                    value: value.clone(),
+                    stored_at,
+                },
+            );
+        });
+    }
+
+    pub fn results(
+        &self,
+        event_id: Uuid,
+        variant: &str,
+        now: DateTime<Utc>,
+    ) -> Option<(HeaderValue, EventResultsResponse)> {
+        let events = self.events.lock().unwrap();
+        self.fresh(events.get(&event_id)?.results.get(variant)?, now)
+    }
+
+    pub fn store_results(
+        &self,
+        event_id: Uuid,
+        variant: String,
+        etag: HeaderValue,
+        value: &EventResultsResponse,
+        now: DateTime<Utc>,
+    ) {
+        self.store(event_id, now, |entries, stored_at| {
+            entries.results.insert(
+                variant,
+                Entry {
+                    etag,
+                    value: value.clone(),
+                    stored_at,
+                },
+            );
+        });
+    }
+
+    /// Forgets every cached view of the event.
+    pub fn invalidate(&self, event_id: Uuid) {
+        self.events.lock().unwrap().remove(&event_id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn fresh<T: Clone>(&self, entry: &Entry<T>, now: DateTime<Utc>) -> Option<(HeaderValue, T)> {
+        entry
+            .is_fresh(self.ttl, now)
+            .then(|| (entry.etag.clone(), entry.value.clone()))
+    }
+
+    fn store(
+        &self,
+        event_id: Uuid,
+        now: DateTime<Utc>,
+        insert: impl FnOnce(&mut EventEntries, DateTime<Utc>),
+    ) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut events = self.events.lock().unwrap();
+        if events.len() >= MAX_EVENTS && !events.contains_key(&event_id) {
+            let ttl = self.ttl;
+            events.retain(|_, entries| {
+                entries.event.retain(|_, entry| entry.is_fresh(ttl, now));
+                entries.results.retain(|_, entry| entry.is_fresh(ttl, now));
+                !entries.event.is_empty() || !entries.results.is_empty()
+            });
+            if events.len() >= MAX_EVENTS {
+                return;
+            }
+        }
+        insert(events.entry(event_id).or_default(), now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{EventState, PollType, ResultsVisibility, SlotKind};
+
+    fn response(title: &str) -> EventResponse {
+        EventResponse {
+            id: Uuid::nil(),
+            title: title.to_string(),
+            description: None,
+            time_zone: None,
+            slot_duration: 60,
+            state: EventState::Open,
+            event_slots: Vec::new(),
+            slot_kind: SlotKind::Dates,
+            weekly_slots: Vec::new(),
+            poll_type: PollType::Datetime,
+            dates: Vec::new(),
+            organizer_name: "Alice".to_string(),
+            results_visibility: ResultsVisibility::Public,
+            reveal_results_at: None,
+            blind_until_closed: false,
+            anonymous_results: false,
+            roles: Vec::new(),
+            finalization_rules: Default::default(),
+            close_at_quorum: None,
+            seconds_until_deadline: None,
+            expires_at: chrono::Utc::now(),
+            seconds_until_expiry: 0,
+            display: None,
+        }
+    }
+
+    #[test]
+    fn test_entries_are_per_variant_until_invalidated() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        let event_id = Uuid::new_v4();
+        let etag = HeaderValue::from_static("W/\"abc\"");
+        let now = Utc::now();
+        cache.store_event(
+            event_id,
+            String::new(),
+            etag.clone(),
+            &response("Standup"),
+            now,
+        );
+
+        let (cached_etag, cached) = cache.event(event_id, "", now).unwrap();
+        assert_eq!(cached_etag, etag);
+        assert_eq!(cached.title, "Standup");
+        assert!(cache.event(event_id, "Asia/Tokyo", now).is_none());
+        assert!(cache.results(event_id, "", now).is_none());
+
+        cache.invalidate(event_id);
+        assert!(cache.event(event_id, "", now).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_entries_expire_by_the_given_time() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        let event_id = Uuid::new_v4();
+        let now = Utc::now();
+        cache.store_event(
+            event_id,
+            String::new(),
+            HeaderValue::from_static("W/\"abc\""),
+            &response("Standup"),
+            now,
+        );
+
+        assert!(
+            cache
+                .event(event_id, "", now + TimeDelta::seconds(59))
+                .is_some()
+        );
+        assert!(
+            cache
+                .event(event_id, "", now + TimeDelta::seconds(60))
+                .is_none()
+        );
+        // A clock set back doesn't revive or extend entries
+        assert!(
+            cache
+                .event(event_id, "", now - TimeDelta::seconds(1))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_zero_ttl_stores_nothing() {
+        let cache = ResponseCache::new(Duration::ZERO);
+        let event_id = Uuid::new_v4();
+        cache.store_event(
+            event_id,
+            String::new(),
+            HeaderValue::from_static("W/\"abc\""),
+            &response("Standup"),
+            Utc::now(),
+        );
+        assert!(cache.event(event_id, "", Utc::now()).is_none());
+        assert!(cache.is_empty());
+    }
+}
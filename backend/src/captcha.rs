@@ -0,0 +1,149 @@
+//! CAPTCHA verification for event creation and availability submissions.
+//!
+//! When `CAPTCHA_PROVIDER` and `CAPTCHA_SECRET` are set, `POST /events` and
+//! `POST /events/{public_token}/availability` need a `captcha_token` solved
+//! in the provider's widget. The token is checked against the provider's
+//! `siteverify` endpoint; both hCaptcha and Turnstile take the same form and
+//! answer `{ "success": bool, "error-codes": [...] }`. Tokens are single use,
+//! so clients reset the widget after every attempt.
+
+use std::{str::FromStr, sync::OnceLock, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{
+    config::Config,
+    error::{AppError, AppResult},
+};
+
+/// A provider that doesn't answer within this is treated as a failed check.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptchaProvider {
+    HCaptcha,
+    Turnstile,
+}
+
+impl CaptchaProvider {
+    /// Name used in `CAPTCHA_PROVIDER` and `/capabilities`.
+    pub fn name(self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "hcaptcha",
+            CaptchaProvider::Turnstile => "turnstile",
+        }
+    }
+
+    pub fn verify_url(self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "https://api.hcaptcha.com/siteverify",
+            CaptchaProvider::Turnstile => {
+                "https://challenges.cloudflare.com/turnstile/v0/siteverify"
+            }
+        }
+    }
+}
+
+impl FromStr for CaptchaProvider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "hcaptcha" => Ok(CaptchaProvider::HCaptcha),
+            "turnstile" => Ok(CaptchaProvider::Turnstile),
+            other => anyhow::bail!(
+                "unknown captcha provider {:?} (hcaptcha or turnstile)",
+                other
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Verdict {
+    success: bool,
+    #[serde(rename = "error-codes", default)]
+    error_codes: Vec<String>,
+}
+
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("default TLS backend is available")
+    })
+}
+
+/// Passes when the deployment doesn't require a CAPTCHA; otherwise 403
+/// `CAPTCHA_FAILED` unless the provider accepts `token`. An unreachable
+/// provider fails the check rather than letting requests through.
+pub async fn check(config: &Config, token: Option<&str>) -> AppResult<()> {
+    let (Some(provider), Some(secret)) = (config.captcha_provider, &config.captcha_secret) else {
+        return Ok(());
+    };
+    let Some(token) = token.map(str::trim).filter(|token| !token.is_empty()) else {
+        return Err(AppError::CaptchaFailed);
+    };
+    let url = config
+        .captcha_verify_url
+        .as_deref()
+        .unwrap_or(provider.verify_url());
+
+    let verdict = async {
+        client()
+            .post(url)
+            .form(&[("secret", secret.as_str()), ("response", token)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Verdict>()
+            .await
+    }
+    .await;
+
+    match verdict {
+        Ok(verdict) if verdict.success => Ok(()),
+        Ok(verdict) => {
+            tracing::debug!(errors = ?verdict.error_codes, "Captcha rejected");
+            Err(AppError::CaptchaFailed)
+        }
+        Err(e) => {
+            tracing::error!(
+                provider = provider.name(),
+                "Captcha verification failed: {}",
+                e
+            );
+            Err(AppError::CaptchaFailed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_names_round_trip() {
+        for provider in [CaptchaProvider::HCaptcha, CaptchaProvider::Turnstile] {
+            assert_eq!(
+                provider.name().parse::<CaptchaProvider>().unwrap(),
+                provider
+            );
+        }
+        assert!("recaptcha".parse::<CaptchaProvider>().is_err());
+    }
+
+    #[test]
+    fn test_verdict_reads_error_codes() {
+        let verdict: Verdict =
+            serde_json::from_str(r#"{"success":false,"error-codes":["invalid-input-response"]}"#)
+                .unwrap();
+        assert!(!verdict.success);
+        assert_eq!(verdict.error_codes, ["invalid-input-response"]);
+
+        let verdict: Verdict = serde_json::from_str(r#"{"success":true}"#).unwrap();
+        assert!(verdict.success);
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Source of wall-clock and monotonic time.
+///
+/// Handlers, background tasks and middleware read time through this trait
+/// instead of calling `Utc::now()` / `Instant::now()` directly, so tests can
+/// drive deadlines, retention and rate-limit windows deterministically.
+pub trait Clock: Send + Sync + 'static {
+    /// Current wall-clock time (used for timestamps persisted to the DB).
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Current monotonic instant (used for in-memory windows).
+    fn instant(&self) -> Instant;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Real system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Manually controlled clock for tests. Time only moves on `advance`.
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    state: Arc<Mutex<(DateTime<Utc>, Instant)>>,
+}
+
+impl TestClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        TestClock {
+            state: Arc::new(Mutex::new((start, Instant::now()))),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.0 += chrono::Duration::from_std(by).expect("duration out of range");
+        state.1 += by;
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        let mut state = self.state.lock().unwrap();
+        let delta = now - state.0;
+        state.0 = now;
+        // Instants cannot go backwards; only forward jumps move the monotonic side.
+        if let Ok(forward) = delta.to_std() {
+            state.1 += forward;
+        }
+    }
+}
+
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new(Utc::now())
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.state.lock().unwrap().0
+    }
+
+    fn instant(&self) -> Instant {
+        self.state.lock().unwrap().1
+    }
+}
+
+pub fn system() -> SharedClock {
+    Arc::new(SystemClock)
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::models::AuditEntry;
+
+/// Appends an entry to an event's audit log. Takes a connection so callers
+/// can record inside the same transaction as the change itself.
+pub async fn record(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    action: &str,
+    actor: &str,
+    details: Option<&str>,
+    at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO event_audit_log (event_id, action, actor, details, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        event_id,
+        action,
+        actor,
+        details,
+        at
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// An event's audit log, newest first.
+pub async fn entries(pool: &PgPool, event_id: Uuid) -> Result<Vec<AuditEntry>, sqlx::Error> {
+    sqlx::query_as!(
+        AuditEntry,
+        r#"
+        SELECT id, action, actor, details, created_at
+        FROM event_audit_log
+        WHERE event_id = $1
+        ORDER BY created_at DESC, id DESC
+        "#,
+        event_id
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,116 @@
+//! A participant's submitted ranges.
+
+use agreed_time_core::TimeRange;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgExecutor};
+use uuid::Uuid;
+
+use crate::models::{AvailabilityLevel, TimeRangeRequest};
+
+/// Stores one participant's ranges, each at its own level, in one statement.
+pub async fn insert(
+    conn: &mut PgConnection,
+    participant_id: i64,
+    ranges: &[TimeRangeRequest],
+) -> Result<(), sqlx::Error> {
+    if ranges.is_empty() {
+        return Ok(());
+    }
+    let starts: Vec<DateTime<Utc>> = ranges.iter().map(|range| range.start_at).collect();
+    let ends: Vec<DateTime<Utc>> = ranges.iter().map(|range| range.end_at).collect();
+    let levels: Vec<&str> = ranges.iter().map(|range| range.level().as_str()).collect();
+    sqlx::query!(
+        r#"
+        INSERT INTO availabilities (participant_id, start_at, end_at, availability_level)
+        SELECT $1, * FROM UNNEST($2::TIMESTAMPTZ[], $3::TIMESTAMPTZ[], $4::VARCHAR[])
+        "#,
+        participant_id,
+        &starts,
+        &ends,
+        &levels as &[&str]
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Forgets everything the participant submitted, before storing a new answer.
+pub async fn delete_for_participant(
+    conn: &mut PgConnection,
+    participant_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM availabilities WHERE participant_id = $1",
+        participant_id
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// A participant's ranges in start order, each with its level.
+pub async fn for_participant(
+    executor: impl PgExecutor<'_>,
+    participant_id: i64,
+) -> Result<Vec<TimeRangeRequest>, sqlx::Error> {
+    let rows = sqlx::query!(
+        "SELECT start_at, end_at, availability_level FROM availabilities WHERE participant_id = $1 ORDER BY start_at",
+        participant_id
+    )
+    .fetch_all(executor)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| TimeRangeRequest {
+            start_at: row.start_at,
+            end_at: row.end_at,
+            availability_level: Some(AvailabilityLevel::from_db(&row.availability_level)),
+        })
+        .collect())
+}
+
+/// One stored availability row of some participant of an event.
+#[derive(Debug)]
+pub struct StoredRange {
+    pub id: i64,
+    pub participant_id: i64,
+    pub range: TimeRange,
+    pub level: AvailabilityLevel,
+}
+
+/// Every availability row of the event's participants.
+pub async fn for_event(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<Vec<StoredRange>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT a.id, a.participant_id, a.start_at, a.end_at, a.availability_level
+        FROM availabilities a
+        JOIN participants p ON p.id = a.participant_id
+        WHERE p.event_id = $1
+        "#,
+        event_id
+    )
+    .fetch_all(conn)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| StoredRange {
+            id: row.id,
+            participant_id: row.participant_id,
+            range: TimeRange::new(row.start_at, row.end_at),
+            level: AvailabilityLevel::from_db(&row.availability_level),
+        })
+        .collect())
+}
+
+pub async fn delete(conn: &mut PgConnection, ids: &[i64]) -> Result<(), sqlx::Error> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    sqlx::query!("DELETE FROM availabilities WHERE id = ANY($1)", ids)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
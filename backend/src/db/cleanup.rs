@@ -1,14 +1,381 @@
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
 use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    clock::Clock,
+    db::{audit, jobs},
+    encryption::Keyring,
+    integrations::{
+        mail,
+        templates::{Notification, Params, Templates},
+        webhooks::{self, WebhookEvent},
+    },
+};
+
+/// Events are moved to the trash by the background cleanup task this long
+/// after creation, unless they asked for another `retention_days`.
+pub const RETENTION_DAYS: i64 = 7;
+
+/// Upper bound of `MAX_RETENTION_DAYS`, the longest retention an event may ask for.
+pub const MAX_RETENTION_DAYS: i64 = 90;
+
+/// Trashed events are permanently removed this long after deletion.
+pub const TRASH_RETENTION_DAYS: i64 = 7;
+
+/// Lifetime of events created in demo mode; they cannot be extended.
+pub const DEMO_RETENTION_HOURS: i64 = 24;
+
+/// Finalized events are archived once their last final slot ended this long ago.
+pub const ARCHIVE_AFTER_DAYS: i64 = 1;
+
+/// Soft-deletes events past their `expires_at`; [`purge_deleted_events`] removes them later.
+pub async fn delete_expired_events(pool: &PgPool, clock: &dyn Clock) -> Result<u64, sqlx::Error> {
+    let now = clock.now();
 
-pub async fn delete_expired_events(pool: &PgPool) -> Result<u64, sqlx::Error> {
     let result = sqlx::query!(
         r#"
-        DELETE FROM events
-        WHERE created_at < NOW() - INTERVAL '7 days'
-        "#
+        UPDATE events
+        SET deleted_at = $1
+        WHERE deleted_at IS NULL AND expires_at < $1
+        "#,
+        now
     )
     .execute(pool)
     .await?;
 
     Ok(result.rows_affected())
 }
+
+#[derive(Debug, Clone, Default)]
+pub struct CleanupOptions {
+    /// Only list what would be trashed and purged
+    pub dry_run: bool,
+    /// Also trash live events created longer ago than this, whatever their
+    /// `expires_at`
+    pub older_than: Option<Duration>,
+}
+
+/// An event the cleanup trashes, or a trashed one it purges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanupCandidate {
+    pub id: Uuid,
+    pub public_token: String,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    pub trashed: Vec<CleanupCandidate>,
+    pub purged: Vec<CleanupCandidate>,
+}
+
+impl CleanupReport {
+    pub fn render(&self) -> String {
+        let (trash, purge) = if self.dry_run {
+            ("Would trash", "Would purge")
+        } else {
+            ("Trashed", "Purged")
+        };
+        let mut out = format!("{} {} events\n", trash, self.trashed.len());
+        for event in &self.trashed {
+            out.push_str(&format!(
+                "  {}  created {}  expires {}  {}\n",
+                event.public_token,
+                event.created_at.to_rfc3339(),
+                event.expires_at.to_rfc3339(),
+                event.title
+            ));
+        }
+        out.push_str(&format!("{} {} trashed events\n", purge, self.purged.len()));
+        for event in &self.purged {
+            out.push_str(&format!(
+                "  {}  deleted {}  {}\n",
+                event.public_token,
+                event
+                    .deleted_at
+                    .map(|at| at.to_rfc3339())
+                    .unwrap_or_default(),
+                event.title
+            ));
+        }
+        out
+    }
+}
+
+/// The trash and purge steps of the hourly jobs on demand, for the `cleanup`
+/// command. Lists the events first so operators see what goes; runs are
+/// recorded in `job_runs` under the usual names.
+pub async fn run_cleanup(
+    pool: &PgPool,
+    clock: &dyn Clock,
+    options: &CleanupOptions,
+) -> Result<CleanupReport, sqlx::Error> {
+    let now = clock.now();
+    let created_before = options.older_than.map(|age| now - age);
+    let trashed = sqlx::query_as!(
+        CleanupCandidate,
+        r#"
+        SELECT id, public_token, title, created_at, expires_at, deleted_at
+        FROM events
+        WHERE deleted_at IS NULL
+          AND (expires_at < $1 OR created_at < $2)
+        ORDER BY created_at, id
+        "#,
+        now,
+        created_before
+    )
+    .fetch_all(pool)
+    .await?;
+    // Events trashed just now only become purgeable after the grace period,
+    // so listing before trashing gives the same result
+    let purged = sqlx::query_as!(
+        CleanupCandidate,
+        r#"
+        SELECT id, public_token, title, created_at, expires_at, deleted_at
+        FROM events
+        WHERE deleted_at < $1
+        ORDER BY deleted_at, id
+        "#,
+        now - Duration::days(TRASH_RETENTION_DAYS)
+    )
+    .fetch_all(pool)
+    .await?;
+
+    if !options.dry_run {
+        let ids: Vec<Uuid> = trashed.iter().map(|event| event.id).collect();
+        jobs::track(pool, clock, jobs::TRASH_EXPIRED, async {
+            sqlx::query!(
+                "UPDATE events SET deleted_at = $2 WHERE id = ANY($1) AND deleted_at IS NULL",
+                &ids,
+                now
+            )
+            .execute(pool)
+            .await
+            .map(|result| result.rows_affected())
+        })
+        .await?;
+        jobs::track(
+            pool,
+            clock,
+            jobs::PURGE_TRASHED,
+            purge_deleted_events(pool, clock),
+        )
+        .await?;
+    }
+
+    Ok(CleanupReport {
+        dry_run: options.dry_run,
+        trashed,
+        purged,
+    })
+}
+
+/// Queues one mail per event that expires within `notice`, to organizers who
+/// left an address and didn't opt out. Each event is marked so the next run
+/// skips it; extending clears the mark, so the new expiry is announced too.
+pub async fn notify_upcoming_deletions(
+    pool: &PgPool,
+    clock: &dyn Clock,
+    templates: &Templates,
+    keys: &Keyring,
+    notice: Duration,
+) -> Result<u64, sqlx::Error> {
+    let now = clock.now();
+    let mut transaction = pool.begin().await?;
+
+    let expiring = sqlx::query!(
+        r#"
+        UPDATE events
+        SET deletion_notice_sent_at = $1
+        WHERE deleted_at IS NULL
+          AND deletion_notice
+          AND deletion_notice_sent_at IS NULL
+          AND organizer_email IS NOT NULL
+          AND expires_at <= $2
+        RETURNING id, title, locale, organizer_email AS "organizer_email!", expires_at, retention_days
+        "#,
+        now,
+        now + notice
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    for event in &expiring {
+        let mail = templates.render(
+            Notification::DeletionNotice,
+            &event.locale,
+            &Params::new()
+                .text("title", event.title.as_str())
+                .text(
+                    "deleted_at",
+                    event.expires_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+                )
+                .text("extend_days", event.retention_days.to_string()),
+        );
+        mail::enqueue(
+            &mut transaction,
+            mail::NewMail {
+                event_id: Some(event.id),
+                participant_id: None,
+                recipient: &keys.open(&event.organizer_email)?,
+                subject: &mail.subject,
+                body: &mail.text,
+                html: Some(&mail.html),
+                calendar: None,
+            },
+        )
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(expiring.len() as u64)
+}
+
+/// Sends `event.expiring_soon` for events with a webhook that expire within
+/// `notice`, independent of the organizer's mail notice. Marked and cleared
+/// like [`notify_upcoming_deletions`].
+pub async fn announce_upcoming_deletions(
+    pool: &PgPool,
+    clock: &dyn Clock,
+    notice: Duration,
+) -> Result<u64, sqlx::Error> {
+    let now = clock.now();
+    let mut transaction = pool.begin().await?;
+
+    let expiring = sqlx::query!(
+        r#"
+        UPDATE events
+        SET expiry_webhook_sent_at = $1
+        WHERE deleted_at IS NULL
+          AND expiry_webhook_sent_at IS NULL
+          AND webhook_url IS NOT NULL
+          AND expires_at <= $2
+        RETURNING id, expires_at
+        "#,
+        now,
+        now + notice
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    for event in &expiring {
+        webhooks::enqueue(
+            &mut transaction,
+            event.id,
+            WebhookEvent::EventExpiringSoon,
+            json!({
+                "expires_at": event.expires_at,
+                "purge_at": event.expires_at + Duration::days(TRASH_RETENTION_DAYS),
+            }),
+            now,
+        )
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(expiring.len() as u64)
+}
+
+/// Hard-deletes trashed events; slots, participants, availabilities and all
+/// other per-event rows cascade. Events with a webhook get `event.purged`,
+/// queued apart from the event so it survives the delete.
+pub async fn purge_deleted_events(pool: &PgPool, clock: &dyn Clock) -> Result<u64, sqlx::Error> {
+    let now = clock.now();
+    let cutoff = now - Duration::days(TRASH_RETENTION_DAYS);
+
+    let mut transaction = pool.begin().await?;
+
+    let hooked = sqlx::query!(
+        r#"
+        SELECT id, deleted_at AS "deleted_at!"
+        FROM events
+        WHERE deleted_at < $1 AND webhook_url IS NOT NULL
+        FOR UPDATE
+        "#,
+        cutoff
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    for event in &hooked {
+        webhooks::enqueue_purged(
+            &mut transaction,
+            event.id,
+            json!({ "deleted_at": event.deleted_at }),
+            now,
+        )
+        .await?;
+    }
+
+    let purged = sqlx::query!(
+        r#"
+        DELETE FROM events
+        WHERE deleted_at < $1
+        "#,
+        cutoff
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    // Detached deliveries are the only trace of purged events; drop them
+    // once they are settled and as old as the trash itself
+    sqlx::query!(
+        r#"
+        DELETE FROM webhook_deliveries
+        WHERE event_id IS NULL
+          AND (delivered_at IS NOT NULL OR attempts >= $1)
+          AND created_at < $2
+        "#,
+        webhooks::MAX_ATTEMPTS,
+        cutoff
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(purged.rows_affected())
+}
+
+pub async fn archive_finished_events(pool: &PgPool, clock: &dyn Clock) -> Result<u64, sqlx::Error> {
+    let now = clock.now();
+    let cutoff = now - Duration::days(ARCHIVE_AFTER_DAYS);
+
+    let mut transaction = pool.begin().await?;
+
+    let archived = sqlx::query_scalar!(
+        r#"
+        UPDATE events
+        SET state = 'archived', updated_at = $2
+        WHERE state = 'finalized' AND deleted_at IS NULL
+          -- Weekly final slots sit in a reference week long past
+          AND slot_kind = 'dates'
+          AND id IN (
+              SELECT event_id FROM final_slots
+              GROUP BY event_id
+              HAVING MAX(end_at) < $1
+          )
+        RETURNING id
+        "#,
+        cutoff,
+        now
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    for event_id in &archived {
+        audit::record(&mut transaction, *event_id, "archived", "system", None, now).await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(archived.len() as u64)
+}
@@ -0,0 +1,260 @@
+//! Copies a sanitized sample of events into another database, e.g. to
+//! refresh staging with production-shaped data.
+//!
+//! Structure and volume are kept (slots, availability, roles, final slots,
+//! assignments); anything personal or secret is not: titles and names are
+//! replaced, descriptions, comments and emails dropped, and every token is
+//! freshly minted so production links never work against the copy.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{clock::Clock, tokens};
+
+#[derive(Debug, Clone)]
+pub struct CloneOptions {
+    /// Most recent events first
+    pub limit: i64,
+    /// Only events created at or after this time
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CloneReport {
+    pub events: u64,
+    pub participants: u64,
+}
+
+/// Copies up to `options.limit` live events from `source` into `target`.
+/// The copies are stamped as created now so the retention job on the target
+/// does not trash them straight away. Each event is copied in its own
+/// transaction.
+pub async fn clone_events(
+    source: &PgPool,
+    target: &PgPool,
+    clock: &dyn Clock,
+    options: &CloneOptions,
+) -> Result<CloneReport, sqlx::Error> {
+    let now = clock.now();
+    let events = sqlx::query!(
+        r#"
+        SELECT id, state, time_zone, slot_duration, slot_kind, poll_type, locale, final_revision, results_visibility,
+               reveal_results_at, blind_until_closed, anonymous_results, results_revealed_at, assignment_confirmed_at,
+               retention_days
+        FROM events
+        WHERE deleted_at IS NULL AND ($1::timestamptz IS NULL OR created_at >= $1)
+        ORDER BY created_at DESC
+        LIMIT $2
+        "#,
+        options.since,
+        options.limit
+    )
+    .fetch_all(source)
+    .await?;
+
+    let mut report = CloneReport::default();
+    for event in events {
+        let mut transaction = target.begin().await?;
+        let event_id = Uuid::new_v4();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO events (
+                id, public_token, organizer_token, title, description, state, time_zone,
+                slot_duration, slot_kind, poll_type, locale, final_revision, results_visibility, reveal_results_at,
+                blind_until_closed, anonymous_results, results_revealed_at, assignment_confirmed_at,
+                retention_days, created_at, updated_at, expires_at
+            )
+            VALUES (
+                $1, $2, $3, $4, NULL, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $19,
+                $19::timestamptz + make_interval(days => $18)
+            )
+            "#,
+            event_id,
+            tokens::generate(),
+            tokens::generate(),
+            format!("Event {}", &event_id.simple().to_string()[..8]),
+            event.state,
+            event.time_zone,
+            event.slot_duration,
+            event.slot_kind,
+            event.poll_type,
+            event.locale,
+            event.final_revision,
+            event.results_visibility,
+            event.reveal_results_at,
+            event.blind_until_closed,
+            event.anonymous_results,
+            event.results_revealed_at,
+            event.assignment_confirmed_at,
+            event.retention_days,
+            now
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        for slot in sqlx::query!(
+            "SELECT start_at, end_at, locked FROM event_slots WHERE event_id = $1 ORDER BY start_at",
+            event.id
+        )
+        .fetch_all(source)
+        .await?
+        {
+            sqlx::query!(
+                "INSERT INTO event_slots (event_id, start_at, end_at, locked) VALUES ($1, $2, $3, $4)",
+                event_id,
+                slot.start_at,
+                slot.end_at,
+                slot.locked
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        for role in sqlx::query!(
+            "SELECT name, required_count FROM event_roles WHERE event_id = $1 ORDER BY id",
+            event.id
+        )
+        .fetch_all(source)
+        .await?
+        {
+            sqlx::query!(
+                "INSERT INTO event_roles (event_id, name, required_count) VALUES ($1, $2, $3)",
+                event_id,
+                role.name,
+                role.required_count
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        // Required names would point at replaced participants; keep only
+        // the head count
+        for min_participants in sqlx::query_scalar!(
+            r#"SELECT min_participants AS "min_participants!" FROM finalization_rules WHERE event_id = $1 AND min_participants IS NOT NULL"#,
+            event.id
+        )
+        .fetch_all(source)
+        .await?
+        {
+            sqlx::query!(
+                "INSERT INTO finalization_rules (event_id, min_participants) VALUES ($1, $2)",
+                event_id,
+                min_participants
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        // Ids differ in the target; assignments are remapped through these
+        let mut participant_ids = HashMap::new();
+        let participants = sqlx::query!(
+            r#"
+            SELECT id, is_organizer, buffer_minutes, time_zone, rsvp_status, rsvp_at, role
+            FROM participants
+            WHERE event_id = $1
+            ORDER BY is_organizer DESC, created_at, id
+            "#,
+            event.id
+        )
+        .fetch_all(source)
+        .await?;
+        for (index, participant) in participants.iter().enumerate() {
+            let name = if participant.is_organizer {
+                "Organizer".to_string()
+            } else {
+                format!("Participant {}", index)
+            };
+            let id = sqlx::query_scalar!(
+                r#"
+                INSERT INTO participants (
+                    event_id, name, is_organizer, buffer_minutes, time_zone, rsvp_status, rsvp_at,
+                    role, created_at, updated_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9)
+                RETURNING id
+                "#,
+                event_id,
+                name,
+                participant.is_organizer,
+                participant.buffer_minutes,
+                participant.time_zone,
+                participant.rsvp_status,
+                participant.rsvp_at,
+                participant.role,
+                now
+            )
+            .fetch_one(&mut *transaction)
+            .await?;
+            participant_ids.insert(participant.id, id);
+
+            for range in sqlx::query!(
+                r#"
+                SELECT start_at, end_at, availability_level
+                FROM availabilities
+                WHERE participant_id = $1
+                ORDER BY start_at
+                "#,
+                participant.id
+            )
+            .fetch_all(source)
+            .await?
+            {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO availabilities (participant_id, start_at, end_at, availability_level)
+                    VALUES ($1, $2, $3, $4)
+                    "#,
+                    id,
+                    range.start_at,
+                    range.end_at,
+                    range.availability_level
+                )
+                .execute(&mut *transaction)
+                .await?;
+            }
+        }
+
+        for slot in sqlx::query!(
+            "SELECT id, start_at, end_at FROM final_slots WHERE event_id = $1 ORDER BY start_at",
+            event.id
+        )
+        .fetch_all(source)
+        .await?
+        {
+            let final_slot_id = sqlx::query_scalar!(
+                "INSERT INTO final_slots (event_id, start_at, end_at) VALUES ($1, $2, $3) RETURNING id",
+                event_id,
+                slot.start_at,
+                slot.end_at
+            )
+            .fetch_one(&mut *transaction)
+            .await?;
+
+            for participant_id in sqlx::query_scalar!(
+                "SELECT participant_id FROM slot_assignments WHERE final_slot_id = $1",
+                slot.id
+            )
+            .fetch_all(source)
+            .await?
+            {
+                sqlx::query!(
+                    "INSERT INTO slot_assignments (final_slot_id, participant_id) VALUES ($1, $2)",
+                    final_slot_id,
+                    participant_ids[&participant_id]
+                )
+                .execute(&mut *transaction)
+                .await?;
+            }
+        }
+
+        transaction.commit().await?;
+        report.events += 1;
+        report.participants += participants.len() as u64;
+    }
+
+    Ok(report)
+}
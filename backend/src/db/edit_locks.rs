@@ -0,0 +1,63 @@
+//! The advisory lease behind `/events/{organizer_token}/edit-lock`, which
+//! tells co-organizers someone else is editing.
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgExecutor};
+use uuid::Uuid;
+
+use crate::models::EditLock;
+
+/// The lease still running at `now`, if any.
+pub async fn current(
+    executor: impl PgExecutor<'_>,
+    event_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Option<EditLock>, sqlx::Error> {
+    sqlx::query_as!(
+        EditLock,
+        r#"
+        SELECT holder_name, acquired_at, expires_at
+        FROM event_edit_locks
+        WHERE event_id = $1 AND expires_at > $2
+        "#,
+        event_id,
+        now
+    )
+    .fetch_optional(executor)
+    .await
+}
+
+/// Takes or renews the lease, replacing an expired one.
+pub async fn hold(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    holder_name: &str,
+    acquired_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+) -> Result<EditLock, sqlx::Error> {
+    sqlx::query_as!(
+        EditLock,
+        r#"
+        INSERT INTO event_edit_locks (event_id, holder_name, acquired_at, expires_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (event_id) DO UPDATE
+        SET holder_name = EXCLUDED.holder_name,
+            acquired_at = EXCLUDED.acquired_at,
+            expires_at = EXCLUDED.expires_at
+        RETURNING holder_name, acquired_at, expires_at
+        "#,
+        event_id,
+        holder_name,
+        acquired_at,
+        expires_at
+    )
+    .fetch_one(conn)
+    .await
+}
+
+pub async fn release(conn: &mut PgConnection, event_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM event_edit_locks WHERE event_id = $1", event_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,1301 @@
+//! Event rows and what hangs off them: candidate and final slots, roles,
+//! finalization rules and invitees.
+
+use agreed_time_core::TimeRange;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    etag::EventVersion,
+    models::{
+        Event, EventRole, EventSlot, EventState, FinalSlot, FinalizationRules, PollType,
+        ResultsVisibility, SlotKind, TimeRangeRequest,
+    },
+};
+
+/// An event found by its public token, with what the polled reads check
+/// before building anything.
+#[derive(Debug)]
+pub struct PublicEvent {
+    pub event: Event,
+    pub password_hash: Option<String>,
+    pub slot_kind: SlotKind,
+    pub poll_type: PollType,
+    pub version: EventVersion,
+}
+
+/// Looks up a live event together with its passphrase hash and ETag
+/// version, so a poll of an unchanged event is answered after one round trip.
+pub async fn find_public(
+    pool: &PgPool,
+    public_token: &str,
+) -> Result<Option<PublicEvent>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT e.id, e.public_token, e.organizer_token, e.title, e.description,
+               e.state AS "state: EventState", e.time_zone, e.slot_duration, e.created_at, e.updated_at,
+               e.password_hash, e.slot_kind, e.poll_type, e.expires_at, e.reveal_results_at,
+               p.updated_at AS participants_updated_at, p.count AS "participants!"
+        FROM events e
+        CROSS JOIN LATERAL (
+            SELECT MAX(updated_at) AS updated_at, COUNT(*) AS count
+            FROM participants WHERE event_id = e.id
+        ) p
+        WHERE e.public_token = $1 AND e.deleted_at IS NULL
+        "#,
+        public_token
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| PublicEvent {
+        event: Event {
+            id: row.id,
+            public_token: row.public_token,
+            organizer_token: row.organizer_token,
+            title: row.title,
+            description: row.description,
+            state: row.state,
+            time_zone: row.time_zone,
+            slot_duration: row.slot_duration,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        },
+        password_hash: row.password_hash,
+        slot_kind: SlotKind::from_db(&row.slot_kind),
+        poll_type: PollType::from_db(&row.poll_type),
+        version: EventVersion {
+            updated_at: row.updated_at,
+            expires_at: row.expires_at,
+            reveal_results_at: row.reveal_results_at,
+            participants_updated_at: row.participants_updated_at,
+            participants: row.participants,
+        },
+    }))
+}
+
+/// Everything the participant view shows beyond the [`Event`] row.
+#[derive(Debug, Clone)]
+pub struct EventDetails {
+    pub results_visibility: ResultsVisibility,
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    pub blind_until_closed: bool,
+    pub anonymous_results: bool,
+    pub close_at_quorum: Option<i32>,
+    pub expires_at: DateTime<Utc>,
+    pub slot_kind: SlotKind,
+    pub poll_type: PollType,
+    pub organizer_name: String,
+    pub slots: Vec<EventSlot>,
+    pub roles: Vec<EventRole>,
+    pub finalization_rules: FinalizationRules,
+}
+
+/// One round trip: slots, roles and rules come back as parallel arrays.
+pub async fn details(pool: &PgPool, event_id: Uuid) -> Result<EventDetails, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT e.results_visibility, e.reveal_results_at, e.blind_until_closed, e.anonymous_results,
+               e.close_at_quorum, e.expires_at, e.slot_kind, e.poll_type,
+               (SELECT name FROM participants WHERE event_id = e.id AND is_organizer = true LIMIT 1)
+                   AS "organizer_name!",
+               COALESCE(s.ids, '{}') AS "slot_ids!",
+               COALESCE(s.starts, '{}') AS "slot_starts!",
+               COALESCE(s.ends, '{}') AS "slot_ends!",
+               COALESCE(s.locked, '{}') AS "slot_locked!",
+               ARRAY(SELECT name FROM event_roles WHERE event_id = e.id ORDER BY id) AS "role_names!",
+               ARRAY(SELECT required_count FROM event_roles WHERE event_id = e.id ORDER BY id)
+                   AS "role_required!",
+               (SELECT min_participants FROM finalization_rules
+                WHERE event_id = e.id AND min_participants IS NOT NULL
+                ORDER BY id DESC LIMIT 1) AS min_participants,
+               ARRAY(SELECT participant_name FROM finalization_rules
+                     WHERE event_id = e.id AND participant_name IS NOT NULL ORDER BY id)
+                   AS "required_participants!: Vec<String>"
+        FROM events e
+        CROSS JOIN LATERAL (
+            SELECT array_agg(id ORDER BY start_at, id) AS ids,
+                   array_agg(start_at ORDER BY start_at, id) AS starts,
+                   array_agg(end_at ORDER BY start_at, id) AS ends,
+                   array_agg(locked ORDER BY start_at, id) AS locked
+            FROM event_slots WHERE event_id = e.id
+        ) s
+        WHERE e.id = $1
+        "#,
+        event_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(EventDetails {
+        results_visibility: ResultsVisibility::from_db(&row.results_visibility),
+        reveal_results_at: row.reveal_results_at,
+        blind_until_closed: row.blind_until_closed,
+        anonymous_results: row.anonymous_results,
+        close_at_quorum: row.close_at_quorum,
+        expires_at: row.expires_at,
+        slot_kind: SlotKind::from_db(&row.slot_kind),
+        poll_type: PollType::from_db(&row.poll_type),
+        organizer_name: row.organizer_name,
+        slots: row
+            .slot_ids
+            .into_iter()
+            .zip(row.slot_starts)
+            .zip(row.slot_ends)
+            .zip(row.slot_locked)
+            .map(|(((id, start_at), end_at), locked)| EventSlot {
+                id,
+                event_id,
+                start_at,
+                end_at,
+                locked,
+            })
+            .collect(),
+        roles: row
+            .role_names
+            .into_iter()
+            .zip(row.role_required)
+            .map(|(name, required)| EventRole { name, required })
+            .collect(),
+        finalization_rules: FinalizationRules {
+            min_participants: row.min_participants,
+            required_participants: row.required_participants,
+        },
+    })
+}
+
+/// The settings `/results` and its siblings check before showing anything.
+#[derive(Debug, Clone)]
+pub struct ResultsAccess {
+    pub state: EventState,
+    pub results_visibility: ResultsVisibility,
+    pub results_token: String,
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    pub blind_until_closed: bool,
+    pub anonymous_results: bool,
+}
+
+pub async fn results_access(pool: &PgPool, event_id: Uuid) -> Result<ResultsAccess, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT state AS "state: EventState", results_visibility, results_token, reveal_results_at,
+               blind_until_closed, anonymous_results
+        FROM events
+        WHERE id = $1
+        "#,
+        event_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(ResultsAccess {
+        state: row.state,
+        results_visibility: ResultsVisibility::from_db(&row.results_visibility),
+        results_token: row.results_token,
+        reveal_results_at: row.reveal_results_at,
+        blind_until_closed: row.blind_until_closed,
+        anonymous_results: row.anonymous_results,
+    })
+}
+
+/// Candidate slots in start order.
+pub async fn slots(pool: &PgPool, event_id: Uuid) -> Result<Vec<EventSlot>, sqlx::Error> {
+    sqlx::query_as!(
+        EventSlot,
+        r#"
+        SELECT id, event_id, start_at, end_at, locked
+        FROM event_slots
+        WHERE event_id = $1
+        ORDER BY start_at
+        "#,
+        event_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn final_slots(pool: &PgPool, event_id: Uuid) -> Result<Vec<FinalSlot>, sqlx::Error> {
+    sqlx::query_as!(
+        FinalSlot,
+        r#"
+        SELECT id, start_at, end_at
+        FROM final_slots
+        WHERE event_id = $1
+        ORDER BY start_at
+        "#,
+        event_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn roles(pool: &PgPool, event_id: Uuid) -> Result<Vec<EventRole>, sqlx::Error> {
+    sqlx::query_as!(
+        EventRole,
+        r#"
+        SELECT name, required_count AS required
+        FROM event_roles
+        WHERE event_id = $1
+        ORDER BY id
+        "#,
+        event_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn finalization_rules(
+    pool: &PgPool,
+    event_id: Uuid,
+) -> Result<FinalizationRules, sqlx::Error> {
+    let rows = sqlx::query!(
+        "SELECT min_participants, participant_name FROM finalization_rules WHERE event_id = $1 ORDER BY id",
+        event_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut rules = FinalizationRules::default();
+    for row in rows {
+        if let Some(min) = row.min_participants {
+            rules.min_participants = Some(min);
+        }
+        if let Some(name) = row.participant_name {
+            rules.required_participants.push(name);
+        }
+    }
+    Ok(rules)
+}
+
+/// Stores candidate slots in one statement.
+pub async fn insert_slots(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    slots: impl IntoIterator<Item = (DateTime<Utc>, DateTime<Utc>, bool)>,
+) -> Result<(), sqlx::Error> {
+    let (mut starts, mut ends, mut locked) = (Vec::new(), Vec::new(), Vec::new());
+    for (start_at, end_at, is_locked) in slots {
+        starts.push(start_at);
+        ends.push(end_at);
+        locked.push(is_locked);
+    }
+    if starts.is_empty() {
+        return Ok(());
+    }
+    sqlx::query!(
+        r#"
+        INSERT INTO event_slots (event_id, start_at, end_at, locked)
+        SELECT $1, * FROM UNNEST($2::TIMESTAMPTZ[], $3::TIMESTAMPTZ[], $4::BOOL[])
+        "#,
+        event_id,
+        &starts,
+        &ends,
+        &locked
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn insert_invitees(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    invitees: &[String],
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    if invitees.is_empty() {
+        return Ok(());
+    }
+    sqlx::query!(
+        r#"
+        INSERT INTO event_invitees (event_id, email, created_at)
+        SELECT $1, email, $3 FROM UNNEST($2::VARCHAR[]) AS email
+        "#,
+        event_id,
+        invitees,
+        now
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn insert_roles(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    roles: &[EventRole],
+) -> Result<(), sqlx::Error> {
+    if roles.is_empty() {
+        return Ok(());
+    }
+    let names: Vec<&str> = roles.iter().map(|role| role.name.trim()).collect();
+    let required: Vec<i32> = roles.iter().map(|role| role.required).collect();
+    sqlx::query!(
+        r#"
+        INSERT INTO event_roles (event_id, name, required_count)
+        SELECT $1, r.name, r.required_count
+        FROM UNNEST($2::VARCHAR[], $3::INT[]) WITH ORDINALITY AS r(name, required_count, n)
+        ORDER BY r.n
+        "#,
+        event_id,
+        &names as &[&str],
+        &required
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn insert_finalization_rules(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    rules: &FinalizationRules,
+) -> Result<(), sqlx::Error> {
+    let names: Vec<&str> = rules
+        .required_participants
+        .iter()
+        .map(|name| name.trim())
+        .collect();
+    if rules.min_participants.is_none() && names.is_empty() {
+        return Ok(());
+    }
+    // The quorum row first, then one row per required name in order
+    sqlx::query!(
+        r#"
+        INSERT INTO finalization_rules (event_id, min_participants, participant_name)
+        SELECT $1::UUID, $2::INT, NULL::VARCHAR WHERE $2::INT IS NOT NULL
+        UNION ALL
+        (SELECT $1::UUID, NULL, r.name
+         FROM UNNEST($3::VARCHAR[]) WITH ORDINALITY AS r(name, n)
+         ORDER BY r.n)
+        "#,
+        event_id,
+        rules.min_participants,
+        &names as &[&str]
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// A new event's row. The tokens are passed to [`insert`] separately, so a
+/// collision can be retried with fresh ones; the webhook secret and the
+/// organizer's address arrive sealed.
+#[derive(Debug)]
+pub struct NewEvent<'a> {
+    pub id: Uuid,
+    pub title: &'a str,
+    pub description: Option<&'a str>,
+    pub time_zone: Option<&'a str>,
+    pub slot_duration: i32,
+    pub slot_kind: SlotKind,
+    pub poll_type: PollType,
+    pub results_visibility: ResultsVisibility,
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    pub blind_until_closed: bool,
+    pub anonymous_results: bool,
+    pub close_at_quorum: Option<i32>,
+    pub password_hash: Option<&'a str>,
+    pub webhook_url: Option<&'a str>,
+    pub webhook_secret: Option<String>,
+    pub slack_webhook_url: Option<&'a str>,
+    pub discord_webhook_url: Option<&'a str>,
+    pub chat_webhook_url: Option<&'a str>,
+    pub organizer_email: Option<String>,
+    pub locale: &'a str,
+    pub organizer_account_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub retention_days: i32,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Inserts the event as open; `false` when one of the tokens is taken.
+pub async fn insert(
+    conn: &mut PgConnection,
+    event: &NewEvent<'_>,
+    public_token: &str,
+    organizer_token: &str,
+    results_token: &str,
+) -> Result<bool, sqlx::Error> {
+    let inserted = sqlx::query_scalar!(
+        r#"
+        INSERT INTO events (
+            id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at,
+            results_token, results_visibility, reveal_results_at, blind_until_closed,
+            webhook_url, webhook_secret, password_hash, close_at_quorum, organizer_email, slot_kind, poll_type,
+            locale, organizer_account_id, user_id, retention_days, expires_at, anonymous_results,
+            slack_webhook_url, discord_webhook_url, chat_webhook_url
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20,
+            $21, $22, $23, $24, $25, $26, $27, $28, $29
+        )
+        ON CONFLICT DO NOTHING
+        RETURNING id
+        "#,
+        event.id,
+        public_token,
+        organizer_token,
+        event.title,
+        event.description,
+        EventState::Open.as_str(),
+        event.time_zone,
+        event.slot_duration,
+        event.created_at,
+        results_token,
+        event.results_visibility.as_str(),
+        event.reveal_results_at,
+        event.blind_until_closed,
+        event.webhook_url,
+        event.webhook_secret,
+        event.password_hash,
+        event.close_at_quorum,
+        event.organizer_email,
+        event.slot_kind.as_str(),
+        event.poll_type.as_str(),
+        event.locale,
+        event.organizer_account_id,
+        event.user_id,
+        event.retention_days,
+        event.expires_at,
+        event.anonymous_results,
+        event.slack_webhook_url,
+        event.discord_webhook_url,
+        event.chat_webhook_url
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(inserted.is_some())
+}
+
+/// Locks the live event row behind `organizer_token` for the rest of the
+/// transaction; returns its id and state.
+pub async fn lock_by_organizer_token(
+    conn: &mut PgConnection,
+    organizer_token: &str,
+) -> Result<Option<(Uuid, EventState)>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"SELECT id, state AS "state: EventState" FROM events WHERE organizer_token = $1 AND deleted_at IS NULL FOR UPDATE"#,
+        organizer_token
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(row.map(|row| (row.id, row.state)))
+}
+
+/// The slots the organizer locked, which submissions may not cover.
+pub async fn locked_slots(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<Vec<TimeRange>, sqlx::Error> {
+    sqlx::query_as!(
+        TimeRange,
+        "SELECT start_at, end_at FROM event_slots WHERE event_id = $1 AND locked",
+        event_id
+    )
+    .fetch_all(conn)
+    .await
+}
+
+pub async fn has_role(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    role: &str,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM event_roles WHERE event_id = $1 AND name = $2) AS "known!""#,
+        event_id,
+        role
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// The event a submission goes to, locked until the transaction ends.
+#[derive(Debug)]
+pub struct SubmissionTarget {
+    pub id: Uuid,
+    pub state: EventState,
+    pub password_hash: Option<String>,
+    pub poll_type: String,
+    pub time_zone: Option<String>,
+}
+
+pub async fn lock_for_submission(
+    conn: &mut PgConnection,
+    public_token: &str,
+) -> Result<Option<SubmissionTarget>, sqlx::Error> {
+    sqlx::query_as!(
+        SubmissionTarget,
+        r#"SELECT id, state AS "state: EventState", password_hash, poll_type, time_zone FROM events WHERE public_token = $1 AND deleted_at IS NULL FOR UPDATE"#,
+        public_token
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// The live event behind `organizer_token`.
+pub async fn find_by_organizer_token(
+    pool: &PgPool,
+    organizer_token: &str,
+) -> Result<Option<Event>, sqlx::Error> {
+    sqlx::query_as!(
+        Event,
+        r#"
+        SELECT id, public_token, organizer_token, title, description, state AS "state: EventState", time_zone, slot_duration, created_at, updated_at
+        FROM events
+        WHERE organizer_token = $1 AND deleted_at IS NULL
+        "#,
+        organizer_token
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Id of the live event behind `organizer_token`.
+pub async fn id_by_organizer_token(
+    pool: &PgPool,
+    organizer_token: &str,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT id FROM events WHERE organizer_token = $1 AND deleted_at IS NULL",
+        organizer_token
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn find(pool: &PgPool, event_id: Uuid) -> Result<Event, sqlx::Error> {
+    sqlx::query_as!(
+        Event,
+        r#"
+        SELECT id, public_token, organizer_token, title, description, state AS "state: EventState", time_zone, slot_duration, created_at, updated_at
+        FROM events
+        WHERE id = $1
+        "#,
+        event_id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// What only the organizer view shows. The webhook secret and the
+/// organizer's address are still sealed.
+#[derive(Debug)]
+pub struct OrganizerSettings {
+    pub results_visibility: ResultsVisibility,
+    pub results_token: String,
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    pub blind_until_closed: bool,
+    pub anonymous_results: bool,
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+    pub slack_webhook_url: Option<String>,
+    pub discord_webhook_url: Option<String>,
+    pub chat_webhook_url: Option<String>,
+    pub password_protected: bool,
+    pub close_at_quorum: Option<i32>,
+    pub organizer_email: Option<String>,
+    pub deletion_notice: bool,
+    pub retention_days: i32,
+    pub expires_at: DateTime<Utc>,
+    pub slot_kind: SlotKind,
+    pub poll_type: PollType,
+    pub locale: String,
+}
+
+pub async fn organizer_settings(
+    pool: &PgPool,
+    event_id: Uuid,
+) -> Result<OrganizerSettings, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT results_visibility, results_token, reveal_results_at, blind_until_closed, anonymous_results, webhook_url, webhook_secret, slack_webhook_url, discord_webhook_url, chat_webhook_url, password_hash IS NOT NULL AS \"password_protected!\", close_at_quorum, organizer_email, deletion_notice, retention_days, expires_at, slot_kind, poll_type, locale FROM events WHERE id = $1",
+        event_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(OrganizerSettings {
+        results_visibility: ResultsVisibility::from_db(&row.results_visibility),
+        results_token: row.results_token,
+        reveal_results_at: row.reveal_results_at,
+        blind_until_closed: row.blind_until_closed,
+        anonymous_results: row.anonymous_results,
+        webhook_url: row.webhook_url,
+        webhook_secret: row.webhook_secret,
+        slack_webhook_url: row.slack_webhook_url,
+        discord_webhook_url: row.discord_webhook_url,
+        chat_webhook_url: row.chat_webhook_url,
+        password_protected: row.password_protected,
+        close_at_quorum: row.close_at_quorum,
+        organizer_email: row.organizer_email,
+        deletion_notice: row.deletion_notice,
+        retention_days: row.retention_days,
+        expires_at: row.expires_at,
+        slot_kind: SlotKind::from_db(&row.slot_kind),
+        poll_type: PollType::from_db(&row.poll_type),
+        locale: row.locale,
+    })
+}
+
+/// Addresses the organizer invited, in the order they were added.
+pub async fn invitees(pool: &PgPool, event_id: Uuid) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT email FROM event_invitees WHERE event_id = $1 ORDER BY id",
+        event_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// The settings an edit of the slots is checked against.
+#[derive(Debug)]
+pub struct SlotSettings {
+    pub state: EventState,
+    pub slot_kind: SlotKind,
+    pub poll_type: PollType,
+    pub time_zone: Option<String>,
+    pub slot_duration: i32,
+}
+
+pub async fn slot_settings(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<SlotSettings, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"SELECT state AS "state: EventState", slot_kind, poll_type, time_zone, slot_duration FROM events WHERE id = $1"#,
+        event_id
+    )
+    .fetch_one(conn)
+    .await?;
+    Ok(SlotSettings {
+        state: row.state,
+        slot_kind: SlotKind::from_db(&row.slot_kind),
+        poll_type: PollType::from_db(&row.poll_type),
+        time_zone: row.time_zone,
+        slot_duration: row.slot_duration,
+    })
+}
+
+/// Changes to an event's own settings; `None` leaves a field as it is. An
+/// empty description, passphrase hash or address clears it, as does a
+/// quorum of 0. The address arrives sealed.
+#[derive(Debug, Default)]
+pub struct EventChanges<'a> {
+    pub title: Option<&'a str>,
+    pub description: Option<&'a str>,
+    pub time_zone: Option<&'a str>,
+    pub slot_duration: Option<i32>,
+    pub results_visibility: Option<ResultsVisibility>,
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    pub blind_until_closed: Option<bool>,
+    pub password_hash: Option<String>,
+    pub close_at_quorum: Option<i32>,
+    pub organizer_email: Option<String>,
+    pub deletion_notice: Option<bool>,
+    pub locale: Option<&'a str>,
+}
+
+/// Applies `changes`. New reveal conditions reset the reveal notification,
+/// so they get their own.
+pub async fn update(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    changes: &EventChanges<'_>,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE events
+        SET title = COALESCE($2, title),
+            description = CASE WHEN $3::text IS NULL THEN description ELSE NULLIF($3, '') END,
+            time_zone = COALESCE($4, time_zone),
+            slot_duration = COALESCE($5, slot_duration),
+            results_visibility = COALESCE($7, results_visibility),
+            reveal_results_at = COALESCE($8, reveal_results_at),
+            blind_until_closed = COALESCE($9, blind_until_closed),
+            results_revealed_at = CASE
+                WHEN $8::timestamptz IS NULL AND $9::boolean IS NULL THEN results_revealed_at
+            END,
+            password_hash = CASE WHEN $10::text IS NULL THEN password_hash ELSE NULLIF($10, '') END,
+            close_at_quorum = CASE WHEN $11::integer IS NULL THEN close_at_quorum ELSE NULLIF($11, 0) END,
+            organizer_email = CASE WHEN $12::text IS NULL THEN organizer_email ELSE NULLIF($12, '') END,
+            deletion_notice = COALESCE($13, deletion_notice),
+            locale = COALESCE($14, locale),
+            updated_at = $6
+        WHERE id = $1
+        "#,
+        event_id,
+        changes.title,
+        changes.description,
+        changes.time_zone,
+        changes.slot_duration,
+        now,
+        changes.results_visibility.map(|v| v.as_str()),
+        changes.reveal_results_at,
+        changes.blind_until_closed,
+        changes.password_hash,
+        changes.close_at_quorum,
+        changes.organizer_email,
+        changes.deletion_notice,
+        changes.locale
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Bumps `updated_at`, for changes stored outside the event row.
+pub async fn touch(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE events SET updated_at = $2 WHERE id = $1",
+        event_id,
+        now
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Replaces the roles the organizer asks for.
+pub async fn replace_roles(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    roles: &[EventRole],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM event_roles WHERE event_id = $1", event_id)
+        .execute(&mut *conn)
+        .await?;
+    insert_roles(conn, event_id, roles).await
+}
+
+pub async fn replace_finalization_rules(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    rules: &FinalizationRules,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM finalization_rules WHERE event_id = $1",
+        event_id
+    )
+    .execute(&mut *conn)
+    .await?;
+    insert_finalization_rules(conn, event_id, rules).await
+}
+
+/// Every candidate slot, locked or not, in no particular order.
+pub async fn slot_ranges(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<Vec<TimeRange>, sqlx::Error> {
+    sqlx::query_as!(
+        TimeRange,
+        "SELECT start_at, end_at FROM event_slots WHERE event_id = $1",
+        event_id
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Drops every candidate slot, before [`insert_slots`] stores the new set.
+pub async fn delete_slots(conn: &mut PgConnection, event_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM event_slots WHERE event_id = $1", event_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Moves the event to the trash; `None` when there is no live event behind
+/// `organizer_token`.
+pub async fn trash(
+    conn: &mut PgConnection,
+    organizer_token: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "UPDATE events SET deleted_at = $2 WHERE organizer_token = $1 AND deleted_at IS NULL RETURNING id",
+        organizer_token,
+        now
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// Locks a trashed event; returns its id and expiry.
+pub async fn lock_trashed(
+    conn: &mut PgConnection,
+    organizer_token: &str,
+) -> Result<Option<(Uuid, DateTime<Utc>)>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT id, expires_at FROM events WHERE organizer_token = $1 AND deleted_at IS NOT NULL FOR UPDATE",
+        organizer_token
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(row.map(|row| (row.id, row.expires_at)))
+}
+
+pub async fn restore(conn: &mut PgConnection, event_id: Uuid) -> Result<Event, sqlx::Error> {
+    sqlx::query_as!(
+        Event,
+        r#"
+        UPDATE events
+        SET deleted_at = NULL
+        WHERE id = $1
+        RETURNING id, public_token, organizer_token, title, description, state AS "state: EventState", time_zone, slot_duration, created_at, updated_at
+        "#,
+        event_id
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// What a clone copies beyond the event row itself.
+#[derive(Debug)]
+pub struct CloneSource {
+    pub id: Uuid,
+    pub time_zone: Option<String>,
+    pub slot_kind: SlotKind,
+    pub retention_days: i32,
+    pub webhook_url: Option<String>,
+    pub organizer_name: String,
+    /// Candidate slots in start order
+    pub slots: Vec<TimeRange>,
+}
+
+pub async fn clone_source(
+    conn: &mut PgConnection,
+    organizer_token: &str,
+) -> Result<Option<CloneSource>, sqlx::Error> {
+    let Some(row) = sqlx::query!(
+        r#"
+        SELECT e.id, e.time_zone, e.slot_kind, e.retention_days, e.webhook_url,
+               p.name AS organizer_name
+        FROM events e
+        JOIN participants p ON p.event_id = e.id AND p.is_organizer
+        WHERE e.organizer_token = $1 AND e.deleted_at IS NULL
+        "#,
+        organizer_token
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    else {
+        return Ok(None);
+    };
+    let slots = sqlx::query_as!(
+        TimeRange,
+        "SELECT start_at, end_at FROM event_slots WHERE event_id = $1 ORDER BY start_at",
+        row.id
+    )
+    .fetch_all(conn)
+    .await?;
+    Ok(Some(CloneSource {
+        id: row.id,
+        time_zone: row.time_zone,
+        slot_kind: SlotKind::from_db(&row.slot_kind),
+        retention_days: row.retention_days,
+        webhook_url: row.webhook_url,
+        organizer_name: row.organizer_name,
+        slots,
+    }))
+}
+
+/// A clone's own values; everything else is copied from the source row. The
+/// tokens are passed to [`insert_copy`] separately, as for [`insert`].
+#[derive(Debug)]
+pub struct EventCopy {
+    pub id: Uuid,
+    pub source_id: Uuid,
+    /// Sealed
+    pub webhook_secret: Option<String>,
+    /// Days the reveal deadline moves along with the slots
+    pub offset_days: i32,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Copies the source's settings column by column, then its roles and
+/// finalization rules; `false` when one of the tokens is taken. State,
+/// history and anything participants left behind start over.
+pub async fn insert_copy(
+    conn: &mut PgConnection,
+    copy: &EventCopy,
+    public_token: &str,
+    organizer_token: &str,
+    results_token: &str,
+) -> Result<bool, sqlx::Error> {
+    let inserted = sqlx::query_scalar!(
+        r#"
+        INSERT INTO events (
+            id, public_token, organizer_token, results_token, created_at, updated_at, expires_at,
+            webhook_secret, reveal_results_at,
+            title, description, time_zone, slot_duration, results_visibility, blind_until_closed,
+            webhook_url, password_hash, close_at_quorum, organizer_email, deletion_notice,
+            slot_kind, poll_type, locale, organizer_account_id, user_id, retention_days,
+            anonymous_results, slack_webhook_url, discord_webhook_url, chat_webhook_url
+        )
+        SELECT $1, $2, $3, $4, $5, $5, $6,
+               $7, reveal_results_at + make_interval(days => $8),
+               title, description, time_zone, slot_duration, results_visibility, blind_until_closed,
+               webhook_url, password_hash, close_at_quorum, organizer_email, deletion_notice,
+               slot_kind, poll_type, locale, organizer_account_id, user_id, retention_days,
+               anonymous_results, slack_webhook_url, discord_webhook_url, chat_webhook_url
+        FROM events
+        WHERE id = $9
+        ON CONFLICT DO NOTHING
+        RETURNING id
+        "#,
+        copy.id,
+        public_token,
+        organizer_token,
+        results_token,
+        copy.created_at,
+        copy.expires_at,
+        copy.webhook_secret,
+        copy.offset_days,
+        copy.source_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    if inserted.is_none() {
+        return Ok(false);
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO event_roles (event_id, name, required_count)
+        SELECT $1, name, required_count FROM event_roles WHERE event_id = $2 ORDER BY id
+        "#,
+        copy.id,
+        copy.source_id
+    )
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query!(
+        r#"
+        INSERT INTO finalization_rules (event_id, min_participants, participant_name)
+        SELECT $1, min_participants, participant_name FROM finalization_rules WHERE event_id = $2 ORDER BY id
+        "#,
+        copy.id,
+        copy.source_id
+    )
+    .execute(conn)
+    .await?;
+    Ok(true)
+}
+
+/// Locks the live event behind `organizer_token`; returns its expiry and
+/// retention.
+pub async fn lock_retention(
+    conn: &mut PgConnection,
+    organizer_token: &str,
+) -> Result<Option<(DateTime<Utc>, i32)>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT expires_at, retention_days FROM events WHERE organizer_token = $1 AND deleted_at IS NULL FOR UPDATE",
+        organizer_token
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(row.map(|row| (row.expires_at, row.retention_days)))
+}
+
+/// Moves the expiry. Clearing the notice marks announces the new one in
+/// time as well.
+pub async fn extend(
+    conn: &mut PgConnection,
+    organizer_token: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<Event, sqlx::Error> {
+    sqlx::query_as!(
+        Event,
+        r#"
+        UPDATE events
+        SET expires_at = $2, deletion_notice_sent_at = NULL, expiry_webhook_sent_at = NULL
+        WHERE organizer_token = $1
+        RETURNING id, public_token, organizer_token, title, description, state AS "state: EventState", time_zone, slot_duration, created_at, updated_at
+        "#,
+        organizer_token,
+        expires_at
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// Closes the event; a finalized one stays finalized.
+pub async fn close(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Event, sqlx::Error> {
+    sqlx::query_as!(
+        Event,
+        r#"
+        UPDATE events
+        SET state = CASE WHEN state = 'finalized' THEN state ELSE 'closed' END, updated_at = $2
+        WHERE id = $1
+        RETURNING id, public_token, organizer_token, title, description, state AS "state: EventState", time_zone, slot_duration, created_at, updated_at
+        "#,
+        event_id,
+        now
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// Replaces the final slots with `slots`.
+pub async fn replace_final_slots(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    slots: &[TimeRangeRequest],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM final_slots WHERE event_id = $1", event_id)
+        .execute(&mut *conn)
+        .await?;
+    for slot in slots {
+        sqlx::query!(
+            "INSERT INTO final_slots (event_id, start_at, end_at) VALUES ($1, $2, $3)",
+            event_id,
+            slot.start_at,
+            slot.end_at
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// An event just finalized.
+#[derive(Debug)]
+pub struct Finalized {
+    pub state: EventState,
+    /// Counts decisions, so calendar updates supersede earlier ones
+    pub final_revision: i32,
+    pub slot_kind: SlotKind,
+}
+
+/// Marks the event finalized under a new revision; assignments made for the
+/// previous decision need confirming again.
+pub async fn finalize(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Finalized, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        UPDATE events
+        SET state = 'finalized', final_revision = final_revision + 1,
+            assignment_confirmed_at = NULL, updated_at = $2
+        WHERE id = $1
+        RETURNING state AS "state: EventState", final_revision, slot_kind
+        "#,
+        event_id,
+        now
+    )
+    .fetch_one(conn)
+    .await?;
+    Ok(Finalized {
+        state: row.state,
+        final_revision: row.final_revision,
+        slot_kind: SlotKind::from_db(&row.slot_kind),
+    })
+}
+
+/// What a calendar invitation and its mail say about the event.
+#[derive(Debug)]
+pub struct InvitationDetails {
+    pub public_token: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub time_zone: Option<String>,
+    pub locale: String,
+}
+
+pub async fn invitation_details(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<InvitationDetails, sqlx::Error> {
+    sqlx::query_as!(
+        InvitationDetails,
+        "SELECT public_token, title, description, time_zone, locale FROM events WHERE id = $1",
+        event_id
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// Id and current decision revision of the live event behind
+/// `organizer_token`.
+pub async fn revision_by_organizer_token(
+    pool: &PgPool,
+    organizer_token: &str,
+) -> Result<Option<(Uuid, i32)>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT id, final_revision FROM events WHERE organizer_token = $1 AND deleted_at IS NULL",
+        organizer_token
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|row| (row.id, row.final_revision)))
+}
+
+/// What a reminder mail needs: the title, the state and the locale.
+#[derive(Debug)]
+pub struct ReminderDetails {
+    pub title: String,
+    pub state: EventState,
+    pub locale: String,
+}
+
+pub async fn reminder_details(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<ReminderDetails, sqlx::Error> {
+    sqlx::query_as!(
+        ReminderDetails,
+        r#"SELECT title, state AS "state: EventState", locale FROM events WHERE id = $1"#,
+        event_id
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// Archives a finalized event; `None` when it isn't finalized.
+pub async fn archive(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Option<EventState>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"UPDATE events SET state = 'archived', updated_at = $2 WHERE id = $1 AND state = 'finalized' RETURNING state AS "state: EventState""#,
+        event_id,
+        now
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// Id of the live event behind `public_token`.
+pub async fn id_by_public_token(
+    pool: &PgPool,
+    public_token: &str,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT id FROM events WHERE public_token = $1 AND deleted_at IS NULL",
+        public_token
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Locks the live event; returns its state.
+pub async fn lock_by_id(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<Option<EventState>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT state AS "state: EventState" FROM events WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"#,
+        event_id
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// Hands the event a new organizer token, so the old one stops resolving;
+/// returns the public token, or `None` when `organizer_token` is taken.
+pub async fn rotate_organizer_token(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    organizer_token: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        UPDATE events SET organizer_token = $1, updated_at = $2
+        WHERE id = $3 AND NOT EXISTS (SELECT 1 FROM events WHERE organizer_token = $1)
+        RETURNING public_token
+        "#,
+        organizer_token,
+        now,
+        event_id
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// Locks or unlocks one of the event's slots.
+pub async fn set_slot_locked(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    slot_id: i64,
+    locked: bool,
+) -> Result<Option<EventSlot>, sqlx::Error> {
+    sqlx::query_as!(
+        EventSlot,
+        r#"
+        UPDATE event_slots SET locked = $3
+        WHERE id = $1 AND event_id = $2
+        RETURNING id, event_id, start_at, end_at, locked
+        "#,
+        slot_id,
+        event_id,
+        locked
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+/// States of the live events behind `public_tokens`, keyed by token.
+pub async fn states_by_public_token(
+    pool: &PgPool,
+    public_tokens: &[String],
+) -> Result<Vec<(String, EventState)>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT public_token, state AS "state: EventState"
+        FROM events
+        WHERE public_token = ANY($1) AND deleted_at IS NULL
+        "#,
+        public_tokens
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.public_token, row.state))
+        .collect())
+}
+
+/// An event an organizer compares with their others for clashes.
+#[derive(Debug)]
+pub struct OrganizedEvent {
+    pub id: Uuid,
+    pub organizer_token: String,
+    pub public_token: String,
+    pub title: String,
+    pub state: EventState,
+    pub time_zone: Option<String>,
+    pub slot_duration: i32,
+}
+
+/// The live, unarchived events behind `organizer_tokens`, in no particular
+/// order.
+pub async fn organized(
+    pool: &PgPool,
+    organizer_tokens: &[String],
+) -> Result<Vec<OrganizedEvent>, sqlx::Error> {
+    sqlx::query_as!(
+        OrganizedEvent,
+        r#"
+        SELECT id, organizer_token, public_token, title, state AS "state: EventState", time_zone, slot_duration
+        FROM events
+        WHERE organizer_token = ANY($1) AND deleted_at IS NULL AND state <> 'archived'
+        "#,
+        organizer_tokens
+    )
+    .fetch_all(pool)
+    .await
+}
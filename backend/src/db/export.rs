@@ -0,0 +1,310 @@
+//! Dumps one event, trashed or not, straight from the database for support
+//! and debugging (`export` command). Organizer, results and participant
+//! tokens, the webhook secret and the passphrase hash are left out so a dump
+//! can be attached to a ticket.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::encryption::Keyring;
+
+#[derive(Debug, Serialize)]
+pub struct EventExport {
+    pub event: ExportedEvent,
+    pub slots: Vec<ExportedSlot>,
+    pub final_slots: Vec<ExportedRange>,
+    pub roles: Vec<ExportedRole>,
+    /// One row per rule: a minimum head count or a required name
+    pub finalization_rules: Vec<ExportedFinalizationRule>,
+    pub participants: Vec<ExportedParticipant>,
+    pub comments: Vec<ExportedComment>,
+    pub audit_log: Vec<ExportedAuditEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedEvent {
+    pub id: Uuid,
+    pub public_token: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub state: String,
+    pub time_zone: Option<String>,
+    pub slot_duration: i32,
+    pub slot_kind: String,
+    pub poll_type: String,
+    pub locale: String,
+    pub results_visibility: String,
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    pub blind_until_closed: bool,
+    pub anonymous_results: bool,
+    pub close_at_quorum: Option<i32>,
+    pub password_protected: bool,
+    pub webhook_url: Option<String>,
+    pub organizer_email: Option<String>,
+    pub final_revision: i32,
+    pub retention_days: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedSlot {
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    pub locked: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedRange {
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedRole {
+    pub name: String,
+    pub required_count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedFinalizationRule {
+    pub min_participants: Option<i32>,
+    pub participant_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedParticipant {
+    pub id: i64,
+    pub name: String,
+    pub is_organizer: bool,
+    pub status: String,
+    pub role: Option<String>,
+    pub email: Option<String>,
+    pub comment: Option<String>,
+    pub time_zone: Option<String>,
+    pub buffer_minutes: i32,
+    pub rsvp_status: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub availabilities: Vec<ExportedAvailability>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedAvailability {
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    pub availability_level: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedComment {
+    /// `id` of the author in `participants`
+    pub participant_id: i64,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedAuditEntry {
+    pub action: String,
+    pub actor: String,
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The event behind a public or organizer token; `None` when there is none.
+/// Sealed columns come out decrypted with `keys`.
+pub async fn export_event(
+    pool: &PgPool,
+    keys: &Keyring,
+    token: &str,
+) -> Result<Option<EventExport>, sqlx::Error> {
+    let Some(mut event) = sqlx::query_as!(
+        ExportedEvent,
+        r#"
+        SELECT id, public_token, title, description, state, time_zone, slot_duration, slot_kind,
+               poll_type, locale, results_visibility, reveal_results_at, blind_until_closed,
+               anonymous_results, close_at_quorum, password_hash IS NOT NULL AS "password_protected!",
+               webhook_url, organizer_email, final_revision, retention_days, created_at, updated_at,
+               expires_at, deleted_at
+        FROM events
+        WHERE public_token = $1 OR organizer_token = $1
+        "#,
+        token
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+    event.organizer_email = keys.open_opt(event.organizer_email)?;
+
+    let slots = sqlx::query_as!(
+        ExportedSlot,
+        "SELECT start_at, end_at, locked FROM event_slots WHERE event_id = $1 ORDER BY start_at",
+        event.id
+    )
+    .fetch_all(pool)
+    .await?;
+    let final_slots = sqlx::query_as!(
+        ExportedRange,
+        "SELECT start_at, end_at FROM final_slots WHERE event_id = $1 ORDER BY start_at",
+        event.id
+    )
+    .fetch_all(pool)
+    .await?;
+    let roles = sqlx::query_as!(
+        ExportedRole,
+        "SELECT name, required_count FROM event_roles WHERE event_id = $1 ORDER BY id",
+        event.id
+    )
+    .fetch_all(pool)
+    .await?;
+    let finalization_rules = sqlx::query_as!(
+        ExportedFinalizationRule,
+        "SELECT min_participants, participant_name FROM finalization_rules WHERE event_id = $1 ORDER BY id",
+        event.id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, name, is_organizer, status, role, email, comment, time_zone, buffer_minutes,
+               rsvp_status, created_at, updated_at
+        FROM participants
+        WHERE event_id = $1
+        ORDER BY is_organizer DESC, created_at, id
+        "#,
+        event.id
+    )
+    .fetch_all(pool)
+    .await?;
+    let mut participants = Vec::with_capacity(rows.len());
+    for row in rows {
+        let availabilities = sqlx::query_as!(
+            ExportedAvailability,
+            r#"
+            SELECT start_at, end_at, availability_level
+            FROM availabilities
+            WHERE participant_id = $1
+            ORDER BY start_at
+            "#,
+            row.id
+        )
+        .fetch_all(pool)
+        .await?;
+        participants.push(ExportedParticipant {
+            id: row.id,
+            name: row.name,
+            is_organizer: row.is_organizer,
+            status: row.status,
+            role: row.role,
+            email: row.email,
+            comment: keys.open_opt(row.comment)?,
+            time_zone: row.time_zone,
+            buffer_minutes: row.buffer_minutes,
+            rsvp_status: row.rsvp_status,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            availabilities,
+        });
+    }
+
+    let mut comments = sqlx::query_as!(
+        ExportedComment,
+        "SELECT participant_id, body, created_at FROM event_comments WHERE event_id = $1 ORDER BY id",
+        event.id
+    )
+    .fetch_all(pool)
+    .await?;
+    for comment in &mut comments {
+        comment.body = keys.open(&comment.body)?;
+    }
+
+    let audit_log = sqlx::query_as!(
+        ExportedAuditEntry,
+        r#"
+        SELECT action, actor, details, created_at
+        FROM event_audit_log
+        WHERE event_id = $1
+        ORDER BY created_at, id
+        "#,
+        event.id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Some(EventExport {
+        event,
+        slots,
+        final_slots,
+        roles,
+        finalization_rules,
+        participants,
+        comments,
+        audit_log,
+    }))
+}
+
+impl EventExport {
+    /// One row per availability range, and one with empty range columns for
+    /// participants who marked nothing.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "participant,is_organizer,status,role,start_at,end_at,availability_level\n",
+        );
+        for participant in &self.participants {
+            let mut row = |start_at: String, end_at: String, level: &str| {
+                let fields = [
+                    csv_field(&participant.name),
+                    participant.is_organizer.to_string(),
+                    csv_field(&participant.status),
+                    csv_field(participant.role.as_deref().unwrap_or_default()),
+                    start_at,
+                    end_at,
+                    csv_field(level),
+                ];
+                out.push_str(&fields.join(","));
+                out.push('\n');
+            };
+            if participant.availabilities.is_empty() {
+                row(String::new(), String::new(), "");
+            }
+            for range in &participant.availabilities {
+                row(
+                    range.start_at.to_rfc3339(),
+                    range.end_at.to_rfc3339(),
+                    &range.availability_level,
+                );
+            }
+        }
+        out
+    }
+}
+
+/// Quoted when it holds a comma, quote or line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quotes_when_needed() {
+        assert_eq!(csv_field("Alice"), "Alice");
+        assert_eq!(csv_field("Smith, Jo"), "\"Smith, Jo\"");
+        assert_eq!(csv_field("The \"boss\""), "\"The \"\"boss\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+}
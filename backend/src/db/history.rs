@@ -0,0 +1,171 @@
+//! Availability versions behind
+//! `GET /events/{organizer_token}/participants/{id}/history`.
+//!
+//! Every write to a participant's availability snapshots the resulting
+//! ranges, so organizers can see who took away which times and when.
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+
+use crate::models::{AvailabilityLevel, AvailabilityVersion, TimeRangeRequest};
+
+/// The participant changed their own availability.
+pub const PARTICIPANT: &str = "participant";
+/// The organizer's slot changes trimmed it.
+pub const ORGANIZER: &str = "organizer";
+
+/// Snapshots the participant's current availability rows. Call inside the
+/// transaction that changed them.
+pub async fn record(
+    conn: &mut PgConnection,
+    participant_id: i64,
+    source: &str,
+    at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO availability_versions (participant_id, availabilities, source, created_at)
+        SELECT $1,
+               COALESCE(jsonb_agg(jsonb_build_object(
+                   'start_at', start_at,
+                   'end_at', end_at,
+                   'availability_level', availability_level
+               ) ORDER BY start_at), '[]'::jsonb),
+               $2, $3
+        FROM availabilities
+        WHERE participant_id = $1
+        "#,
+        participant_id,
+        source,
+        at
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Versions of one participant, newest first, each compared with the one
+/// before it.
+pub async fn versions(
+    pool: &PgPool,
+    participant_id: i64,
+) -> Result<Vec<AvailabilityVersion>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, availabilities, source, created_at
+        FROM availability_versions
+        WHERE participant_id = $1
+        ORDER BY id
+        "#,
+        participant_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut previous: Vec<TimeRangeRequest> = Vec::new();
+    let mut versions = Vec::with_capacity(rows.len());
+    for row in rows {
+        // Written by `record` only, so a parse failure means a corrupt row
+        let availabilities: Vec<TimeRangeRequest> = serde_json::from_value(row.availabilities)
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let (added, removed) = diff(&previous, &availabilities);
+        versions.push(AvailabilityVersion {
+            id: row.id,
+            created_at: row.created_at,
+            source: row.source,
+            added,
+            removed,
+            availabilities: availabilities.clone(),
+        });
+        previous = availabilities;
+    }
+    versions.reverse();
+    Ok(versions)
+}
+
+/// Ranges in `new` but not `old`, and the other way round, per level: moving
+/// a time from available to "if need be" shows up on both sides.
+pub fn diff(
+    old: &[TimeRangeRequest],
+    new: &[TimeRangeRequest],
+) -> (Vec<TimeRangeRequest>, Vec<TimeRangeRequest>) {
+    let at_level = |ranges: &[TimeRangeRequest], level: AvailabilityLevel| {
+        ranges
+            .iter()
+            .filter(|range| range.level() == level)
+            .map(|range| agreed_time_core::TimeRange::new(range.start_at, range.end_at))
+            .collect::<Vec<_>>()
+    };
+    let labelled = |ranges: Vec<agreed_time_core::TimeRange>, level: AvailabilityLevel| {
+        ranges.into_iter().map(move |range| TimeRangeRequest {
+            start_at: range.start_at,
+            end_at: range.end_at,
+            availability_level: Some(level),
+        })
+    };
+
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    for level in [
+        AvailabilityLevel::Available,
+        AvailabilityLevel::IfNeedBe,
+        AvailabilityLevel::Unavailable,
+    ] {
+        let old = at_level(old, level);
+        let new = at_level(new, level);
+        added.extend(labelled(
+            agreed_time_core::subtract_time_ranges(new.clone(), &old),
+            level,
+        ));
+        removed.extend(labelled(
+            agreed_time_core::subtract_time_ranges(old, &new),
+            level,
+        ));
+    }
+    added.sort_by_key(|range| range.start_at);
+    removed.sort_by_key(|range| range.start_at);
+    (added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn range(start: u32, end: u32, level: AvailabilityLevel) -> TimeRangeRequest {
+        TimeRangeRequest {
+            start_at: Utc.with_ymd_and_hms(2030, 1, 1, start, 0, 0).unwrap(),
+            end_at: Utc.with_ymd_and_hms(2030, 1, 1, end, 0, 0).unwrap(),
+            availability_level: Some(level),
+        }
+    }
+
+    fn bounds(
+        ranges: &[TimeRangeRequest],
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>, AvailabilityLevel)> {
+        ranges
+            .iter()
+            .map(|r| (r.start_at, r.end_at, r.level()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_reports_added_and_removed_per_level() {
+        use AvailabilityLevel::{Available, IfNeedBe};
+        let old = vec![range(9, 17, Available)];
+        // Afternoon dropped, one morning hour downgraded to "if need be"
+        let new = vec![range(9, 10, Available), range(10, 11, IfNeedBe)];
+
+        let (added, removed) = diff(&old, &new);
+        assert_eq!(bounds(&added), bounds(&[range(10, 11, IfNeedBe)]));
+        assert_eq!(bounds(&removed), bounds(&[range(10, 17, Available)]));
+    }
+
+    #[test]
+    fn test_diff_of_unchanged_ranges_is_empty() {
+        let ranges = vec![range(9, 12, AvailabilityLevel::Available)];
+        let (added, removed) = diff(&ranges, &ranges);
+        assert!(added.is_empty() && removed.is_empty());
+    }
+}
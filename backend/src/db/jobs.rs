@@ -0,0 +1,159 @@
+//! Run history of the background tasks, served at `/admin/jobs` so operators
+//! can tell whether maintenance actually ran.
+
+use std::{fmt::Display, future::Future, time::Instant};
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+
+use crate::{clock::Clock, integrations::mail::DeliveryReport};
+
+/// Runs older than this are dropped by [`prune_job_runs`].
+pub const JOB_RUN_RETENTION_DAYS: i64 = 14;
+
+pub const DELETION_NOTICE: &str = "cleanup.deletion_notice";
+pub const EXPIRY_WEBHOOKS: &str = "cleanup.expiry_webhooks";
+pub const TRASH_EXPIRED: &str = "cleanup.trash_expired";
+pub const PURGE_TRASHED: &str = "cleanup.purge_trashed";
+pub const ARCHIVE_FINISHED: &str = "cleanup.archive_finished";
+pub const PRUNE_JOB_RUNS: &str = "cleanup.prune_job_runs";
+pub const PRUNE_LOGINS: &str = "cleanup.prune_logins";
+pub const MAIL_DELIVERY: &str = "mail.deliver";
+pub const WEBHOOK_DELIVERY: &str = "webhooks.deliver";
+pub const RESULTS_REVEAL: &str = "reveal.notify";
+
+/// What a job reports as its affected row count.
+pub trait JobOutcome {
+    fn affected_rows(&self) -> u64;
+
+    /// For the log line of a run that affected anything.
+    fn describe(&self) -> String {
+        format!("{} rows affected", self.affected_rows())
+    }
+}
+
+impl JobOutcome for u64 {
+    fn affected_rows(&self) -> u64 {
+        *self
+    }
+}
+
+impl JobOutcome for DeliveryReport {
+    fn affected_rows(&self) -> u64 {
+        self.sent + self.failed
+    }
+
+    fn describe(&self) -> String {
+        format!("{} sent, {} failed", self.sent, self.failed)
+    }
+}
+
+/// Awaits `task` and records the run in `job_runs`. The task's result is
+/// passed through untouched; failing to record is logged, never raised, so
+/// bookkeeping cannot take a job down.
+pub async fn track<T, E, F>(pool: &PgPool, clock: &dyn Clock, job: &str, task: F) -> Result<T, E>
+where
+    T: JobOutcome,
+    E: Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let started_at = clock.now();
+    let timer = Instant::now();
+    let result = task.await;
+    let duration_ms = timer.elapsed().as_millis() as i64;
+
+    let (affected_rows, error) = match &result {
+        Ok(outcome) => (outcome.affected_rows() as i64, None),
+        Err(e) => (0, Some(e.to_string())),
+    };
+
+    if let Err(e) = sqlx::query!(
+        r#"
+        INSERT INTO job_runs (job, started_at, duration_ms, affected_rows, error)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        job,
+        started_at,
+        duration_ms,
+        affected_rows,
+        error
+    )
+    .execute(pool)
+    .await
+    {
+        tracing::error!("Failed to record {} run: {:?}", job, e);
+    }
+
+    result
+}
+
+/// Deletes runs past [`JOB_RUN_RETENTION_DAYS`].
+pub async fn prune_job_runs(pool: &PgPool, clock: &dyn Clock) -> Result<u64, sqlx::Error> {
+    let cutoff = clock.now() - Duration::days(JOB_RUN_RETENTION_DAYS);
+
+    let result = sqlx::query!("DELETE FROM job_runs WHERE started_at < $1", cutoff)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[derive(Debug, Clone)]
+pub struct JobRunRecord {
+    pub id: i64,
+    pub job: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub affected_rows: i64,
+    pub error: Option<String>,
+}
+
+/// Most recent runs first, optionally of one job and/or failures only.
+pub async fn recent_runs(
+    pool: &PgPool,
+    job: Option<&str>,
+    failed_only: bool,
+    limit: i64,
+) -> Result<Vec<JobRunRecord>, sqlx::Error> {
+    sqlx::query_as!(
+        JobRunRecord,
+        r#"
+        SELECT id, job, started_at, duration_ms, affected_rows, error
+        FROM job_runs
+        WHERE ($1::text IS NULL OR job = $1) AND (NOT $2 OR error IS NOT NULL)
+        ORDER BY started_at DESC, id DESC
+        LIMIT $3
+        "#,
+        job,
+        failed_only,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[derive(Debug, Clone)]
+pub struct JobStatusRecord {
+    pub job: String,
+    pub last_started_at: DateTime<Utc>,
+    pub last_succeeded_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// Latest run per job that has ever run; `last_error` is only set when that
+/// latest run failed.
+pub async fn job_statuses(pool: &PgPool) -> Result<Vec<JobStatusRecord>, sqlx::Error> {
+    sqlx::query_as!(
+        JobStatusRecord,
+        r#"
+        SELECT DISTINCT ON (r.job)
+               r.job, r.started_at AS last_started_at, r.error AS last_error,
+               (SELECT MAX(s.started_at) FROM job_runs s
+                WHERE s.job = r.job AND s.error IS NULL) AS last_succeeded_at
+        FROM job_runs r
+        ORDER BY r.job, r.started_at DESC, r.id DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
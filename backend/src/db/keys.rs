@@ -0,0 +1,103 @@
+//! Re-encrypts sealed columns with the current data key, for `rotate-keys`.
+//!
+//! Rows are read a batch at a time, opened with whichever configured key
+//! sealed them and written back sealed with the current one. Rows already
+//! under the current key drop out of the next batch, so an interrupted run
+//! picks up where it stopped. Plaintext values from before encryption was
+//! turned on are sealed along the way.
+
+use sqlx::{PgPool, Row};
+
+use crate::encryption::Keyring;
+
+/// Every column [`Keyring::seal`] writes: table, id type, column.
+pub const SEALED_COLUMNS: &[(&str, &str, &str)] = &[
+    ("events", "uuid", "webhook_secret"),
+    ("events", "uuid", "organizer_email"),
+    ("webhook_deliveries", "bigint", "webhook_secret"),
+    ("participants", "bigint", "comment"),
+    ("event_comments", "bigint", "body"),
+];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RotationReport {
+    /// Rows re-encrypted per `table.column`, in [`SEALED_COLUMNS`] order
+    pub columns: Vec<(String, u64)>,
+}
+
+impl RotationReport {
+    pub fn total(&self) -> u64 {
+        self.columns.iter().map(|(_, rows)| rows).sum()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = format!("Re-encrypted {} values\n", self.total());
+        for (column, rows) in &self.columns {
+            out.push_str(&format!("  {:<32} {}\n", column, rows));
+        }
+        out
+    }
+}
+
+/// Seals every stored value with the current key of `keys`, `batch_size`
+/// rows per query. Fails on a value none of the configured keys opens,
+/// leaving the rows done so far rotated.
+pub async fn rotate_keys(
+    pool: &PgPool,
+    keys: &Keyring,
+    batch_size: i64,
+) -> anyhow::Result<RotationReport> {
+    let Some(prefix) = keys.current_prefix() else {
+        anyhow::bail!("Rotating needs a current encryption key");
+    };
+    let pattern = format!("{}%", prefix);
+
+    let mut report = RotationReport::default();
+    for &(table, id_type, column) in SEALED_COLUMNS {
+        // Runtime queries: the table and column come from the list above
+        let pending = format!("{column} IS NOT NULL AND {column} <> '' AND {column} NOT LIKE $1");
+        let total: i64 =
+            sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table} WHERE {pending}"))
+                .bind(&pattern)
+                .fetch_one(pool)
+                .await?;
+
+        let mut done = 0u64;
+        loop {
+            let rows = sqlx::query(&format!(
+                "SELECT id::text AS id, {column} AS value FROM {table} WHERE {pending} ORDER BY id LIMIT $2"
+            ))
+            .bind(&pattern)
+            .bind(batch_size)
+            .fetch_all(pool)
+            .await?;
+            if rows.is_empty() {
+                break;
+            }
+
+            let mut transaction = pool.begin().await?;
+            for row in rows {
+                let id: String = row.try_get("id")?;
+                let stored: String = row.try_get("value")?;
+                let plain = keys
+                    .open(&stored)
+                    .map_err(|e| anyhow::anyhow!("{}.{} of row {}: {}", table, column, id, e))?;
+                // A row written since the read is already under the current
+                // key and keeps what was written
+                let updated = sqlx::query(&format!(
+                    "UPDATE {table} SET {column} = $1 WHERE id = $2::{id_type} AND {column} = $3"
+                ))
+                .bind(keys.seal(&plain))
+                .bind(&id)
+                .bind(&stored)
+                .execute(&mut *transaction)
+                .await?;
+                done += updated.rows_affected();
+            }
+            transaction.commit().await?;
+            tracing::info!("{}.{}: {}/{} re-encrypted", table, column, done, total);
+        }
+        report.columns.push((format!("{}.{}", table, column), done));
+    }
+    Ok(report)
+}
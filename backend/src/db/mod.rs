@@ -1,11 +1,218 @@
-use sqlx::{PgPool, postgres::PgPoolOptions};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    PgConnection, PgExecutor, PgPool,
+    migrate::Migrator,
+    postgres::{PgConnectOptions, PgPoolOptions},
+};
+use std::{collections::HashSet, time::Duration};
+use utoipa::ToSchema;
+use uuid::Uuid;
 
+use crate::{
+    config::PoolConfig,
+    models::{EventSlot, FinalSlot},
+};
+
+pub mod audit;
+pub mod availabilities;
 pub mod cleanup;
+pub mod clone;
+pub mod edit_locks;
+pub mod events;
+pub mod export;
+pub mod history;
+pub mod jobs;
+pub mod keys;
+pub mod participants;
+pub mod quorum;
+pub mod reminders;
+pub mod reveal;
+pub mod transfers;
+pub mod verification;
+
+/// The reads behind the public event and results views, so their handler
+/// logic can run against an in-memory store in unit tests. `PgPool` is the
+/// real implementation; each method is one function of the repository
+/// modules above.
+pub trait EventStore: Send + Sync {
+    fn public_event(
+        &self,
+        public_token: &str,
+    ) -> impl Future<Output = Result<Option<events::PublicEvent>, sqlx::Error>> + Send;
+
+    fn event_details(
+        &self,
+        event_id: Uuid,
+    ) -> impl Future<Output = Result<events::EventDetails, sqlx::Error>> + Send;
+
+    fn results_access(
+        &self,
+        event_id: Uuid,
+    ) -> impl Future<Output = Result<events::ResultsAccess, sqlx::Error>> + Send;
+
+    fn event_slots(
+        &self,
+        event_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<EventSlot>, sqlx::Error>> + Send;
+
+    fn final_slots(
+        &self,
+        event_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<FinalSlot>, sqlx::Error>> + Send;
+
+    fn confirmed_participants(
+        &self,
+        event_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<participants::ConfirmedParticipant>, sqlx::Error>> + Send;
+
+    fn has_participant(
+        &self,
+        event_id: Uuid,
+        token: Uuid,
+    ) -> impl Future<Output = Result<bool, sqlx::Error>> + Send;
+}
+
+impl EventStore for PgPool {
+    async fn public_event(
+        &self,
+        public_token: &str,
+    ) -> Result<Option<events::PublicEvent>, sqlx::Error> {
+        events::find_public(self, public_token).await
+    }
+
+    async fn event_details(&self, event_id: Uuid) -> Result<events::EventDetails, sqlx::Error> {
+        events::details(self, event_id).await
+    }
+
+    async fn results_access(&self, event_id: Uuid) -> Result<events::ResultsAccess, sqlx::Error> {
+        events::results_access(self, event_id).await
+    }
+
+    async fn event_slots(&self, event_id: Uuid) -> Result<Vec<EventSlot>, sqlx::Error> {
+        events::slots(self, event_id).await
+    }
+
+    async fn final_slots(&self, event_id: Uuid) -> Result<Vec<FinalSlot>, sqlx::Error> {
+        events::final_slots(self, event_id).await
+    }
+
+    async fn confirmed_participants(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<participants::ConfirmedParticipant>, sqlx::Error> {
+        participants::confirmed_with_availability(self, event_id).await
+    }
 
-// For testing without actual database connection
-pub fn create_pool_lazy(database_url: &str) -> PgPool {
+    async fn has_participant(&self, event_id: Uuid, token: Uuid) -> Result<bool, sqlx::Error> {
+        participants::has_token(self, event_id, token).await
+    }
+}
+
+/// Schema migrations embedded at build time.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Advisory lock key `migrate` holds while it compares and applies
+/// migrations ("agreedti" in ASCII).
+const MIGRATION_LOCK: i64 = 0x6167_7265_6564_7469;
+
+/// Applies the embedded migrations the database is missing and returns
+/// their versions. The whole run holds a Postgres advisory lock on one
+/// connection, so replicas starting together apply each migration once: the
+/// others wait for the lock and then find nothing left to do.
+pub async fn migrate(pool: &PgPool) -> anyhow::Result<Vec<i64>> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK)
+        .execute(&mut *conn)
+        .await?;
+    let applied = apply_pending(&mut conn).await;
+    // Closing the connection would release it too, but the pool keeps it
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK)
+        .execute(&mut *conn)
+        .await?;
+    applied
+}
+
+async fn apply_pending(conn: &mut PgConnection) -> anyhow::Result<Vec<i64>> {
+    // A fresh database has no migrations table yet, so everything is pending
+    let pending = match pending_migrations(&mut *conn).await {
+        Ok(pending) => pending,
+        Err(_) => MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .map(|migration| migration.version)
+            .collect(),
+    };
+    if pending.is_empty() {
+        return Ok(pending);
+    }
+    MIGRATOR.run(&mut *conn).await.map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to apply database migrations: {}. Check that DATABASE_URL points at the \
+             right database and that its role may create tables",
+            e
+        )
+    })?;
+    Ok(pending)
+}
+
+/// Versions of embedded migrations the database hasn't applied successfully.
+/// Errors when the migrations table doesn't exist yet.
+pub async fn pending_migrations(executor: impl PgExecutor<'_>) -> Result<Vec<i64>, sqlx::Error> {
+    // Runtime query: the table belongs to sqlx, not to our schema
+    let applied: HashSet<i64> =
+        sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(executor)
+            .await?
+            .into_iter()
+            .collect();
+    Ok(MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| migration.version)
+        .filter(|version| !applied.contains(version))
+        .collect())
+}
+
+/// Connects on first use, so commands and tests run without a database
+/// until they query one.
+pub fn create_pool_lazy(database_url: &str, config: &PoolConfig) -> PgPool {
+    let mut options: PgConnectOptions = database_url.parse().expect("Invalid DATABASE_URL");
+    if config.statement_timeout_ms > 0 {
+        options = options.options([("statement_timeout", config.statement_timeout_ms.to_string())]);
+    }
     PgPoolOptions::new()
-        .max_connections(5)
-        .connect_lazy(database_url)
-        .expect("Failed to create lazy pool")
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+        .idle_timeout(
+            (config.idle_timeout_secs > 0).then(|| Duration::from_secs(config.idle_timeout_secs)),
+        )
+        .connect_lazy_with(options)
+}
+
+/// How busy the connection pool is right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PoolStats {
+    /// `DB_MAX_CONNECTIONS`
+    pub max_connections: u32,
+    /// Connections currently open, idle or not
+    pub open: u32,
+    pub idle: u32,
+    /// Checked out by a query or transaction
+    pub in_use: u32,
+}
+
+impl PoolStats {
+    pub fn of(pool: &PgPool) -> Self {
+        let open = pool.size();
+        let idle = pool.num_idle() as u32;
+        PoolStats {
+            max_connections: pool.options().get_max_connections(),
+            open,
+            idle,
+            in_use: open.saturating_sub(idle),
+        }
+    }
 }
@@ -0,0 +1,581 @@
+//! Participant rows: answers, the waitlist, and what the results views read.
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::models::{
+    AvailabilityLevel, EventState, ParticipantStatus, TimeRangeRequest, WaitlistEntry,
+};
+
+/// A confirmed participant with their stored ranges, as the results and
+/// suggestions read them.
+#[derive(Debug, Clone)]
+pub struct ConfirmedParticipant {
+    pub id: i64,
+    pub name: String,
+    pub is_organizer: bool,
+    pub comment: Option<String>,
+    pub buffer_minutes: i32,
+    pub time_zone: Option<String>,
+    pub rsvp_status: Option<String>,
+    pub role: Option<String>,
+    /// `None` without an address
+    pub email_verified: Option<bool>,
+    /// Position the organizer pinned them at, from 1
+    pub display_order: Option<i32>,
+    /// Left out of public results by the organizer
+    pub is_hidden: bool,
+    pub ranges: Vec<TimeRangeRequest>,
+}
+
+/// Confirmed participants, those the organizer pinned first in their order,
+/// then the organizer and everyone else in order of arrival, each with their
+/// ranges in start order. Hidden participants are included.
+pub async fn confirmed_with_availability(
+    pool: &PgPool,
+    event_id: Uuid,
+) -> Result<Vec<ConfirmedParticipant>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT p.id, p.name, p.is_organizer, p.comment, p.buffer_minutes, p.time_zone, p.rsvp_status, p.role,
+               p.display_order, p.is_hidden,
+               p.email IS NOT NULL AS "has_email!", p.email_verified_at IS NOT NULL AS "email_verified!",
+               a.start_at AS "start_at?", a.end_at AS "end_at?", a.availability_level AS "availability_level?"
+        FROM participants p
+        LEFT JOIN availabilities a ON p.id = a.participant_id
+        WHERE p.event_id = $1 AND p.status = 'confirmed'
+        ORDER BY p.display_order ASC NULLS LAST, p.is_organizer DESC, p.created_at ASC, p.id, a.start_at
+        "#,
+        event_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Rows arrive grouped per participant id (two people may share a name),
+    // so appending whenever the id changes preserves their order.
+    let mut participants: Vec<ConfirmedParticipant> = Vec::new();
+    for row in rows {
+        if participants.last().map(|p| p.id) != Some(row.id) {
+            participants.push(ConfirmedParticipant {
+                id: row.id,
+                name: row.name,
+                is_organizer: row.is_organizer,
+                comment: row.comment,
+                buffer_minutes: row.buffer_minutes,
+                time_zone: row.time_zone,
+                rsvp_status: row.rsvp_status,
+                role: row.role,
+                email_verified: row.has_email.then_some(row.email_verified),
+                display_order: row.display_order,
+                is_hidden: row.is_hidden,
+                ranges: Vec::new(),
+            });
+        }
+
+        if let (Some(start_at), Some(end_at), Some(level)) =
+            (row.start_at, row.end_at, row.availability_level)
+            && let Some(participant) = participants.last_mut()
+        {
+            participant.ranges.push(TimeRangeRequest {
+                start_at,
+                end_at,
+                availability_level: Some(AvailabilityLevel::from_db(&level)),
+            });
+        }
+    }
+    Ok(participants)
+}
+
+/// Whether `token` belongs to someone who answered this event, pending or not.
+pub async fn has_token(pool: &PgPool, event_id: Uuid, token: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM participants WHERE event_id = $1 AND token = $2) AS "exists!""#,
+        event_id,
+        token
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Replaces the event's display preferences: `order` pinned in that order
+/// ahead of everyone else, `hidden` out of public results. Participants in neither
+/// list go back to their place by arrival and are shown again.
+pub async fn set_display_preferences(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    order: &[i64],
+    hidden: &[i64],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE participants
+        SET display_order = array_position($2::BIGINT[], id), is_hidden = id = ANY($3)
+        WHERE event_id = $1
+        "#,
+        event_id,
+        order,
+        hidden
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Adds the organizer as the event's first participant; returns their id.
+pub async fn insert_organizer(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    name: &str,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO participants (event_id, name, is_organizer)
+        VALUES ($1, $2, true)
+        RETURNING id
+        "#,
+        event_id,
+        name
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// What a participant says about themselves when they answer.
+#[derive(Debug)]
+pub struct Answer<'a> {
+    pub name: &'a str,
+    pub comment: Option<&'a str>,
+    pub buffer_minutes: i32,
+    pub time_zone: Option<&'a str>,
+    pub email: Option<&'a str>,
+    pub role: Option<&'a str>,
+}
+
+/// A participant row locked for the rest of the transaction.
+#[derive(Debug)]
+pub struct LockedParticipant {
+    pub id: i64,
+    pub token: Uuid,
+    pub status: ParticipantStatus,
+    pub is_hidden: bool,
+}
+
+/// Locks the participant `token` belongs to, if they answered this event.
+pub async fn lock_by_token(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    token: Uuid,
+) -> Result<Option<LockedParticipant>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT id, token, status, is_hidden FROM participants WHERE token = $1 AND event_id = $2 FOR UPDATE",
+        token,
+        event_id
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(row.map(|row| LockedParticipant {
+        id: row.id,
+        token: row.token,
+        status: ParticipantStatus::from_db(&row.status),
+        is_hidden: row.is_hidden,
+    }))
+}
+
+/// Replaces a participant's answer. A changed address drops its
+/// confirmation; the same one in another case keeps it.
+pub async fn update_answer(
+    conn: &mut PgConnection,
+    participant_id: i64,
+    answer: &Answer<'_>,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE participants SET name = $1, comment = $2, buffer_minutes = $3, time_zone = $4, email = $5, email_verified_at = CASE WHEN LOWER(email) = LOWER($5::VARCHAR) THEN email_verified_at END, role = $6, updated_at = $8 WHERE id = $7",
+        answer.name,
+        answer.comment,
+        answer.buffer_minutes,
+        answer.time_zone,
+        answer.email,
+        answer.role,
+        participant_id,
+        now
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Adds a participant who isn't the organizer; returns their id and edit
+/// token.
+pub async fn insert(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    answer: &Answer<'_>,
+    status: ParticipantStatus,
+) -> Result<(i64, Uuid), sqlx::Error> {
+    let row = sqlx::query!(
+        "INSERT INTO participants (event_id, name, is_organizer, comment, buffer_minutes, time_zone, email, role, status) VALUES ($1, $2, false, $3, $4, $5, $6, $7, $8) RETURNING id, token",
+        event_id,
+        answer.name,
+        answer.comment,
+        answer.buffer_minutes,
+        answer.time_zone,
+        answer.email,
+        answer.role,
+        status.as_str()
+    )
+    .fetch_one(conn)
+    .await?;
+    Ok((row.id, row.token))
+}
+
+/// How many participants the event has confirmed and how many wait.
+#[derive(Debug, Clone, Copy)]
+pub struct StatusCounts {
+    pub confirmed: i64,
+    pub pending: i64,
+}
+
+pub async fn status_counts(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<StatusCounts, sqlx::Error> {
+    sqlx::query_as!(
+        StatusCounts,
+        r#"
+        SELECT COUNT(*) FILTER (WHERE status = 'confirmed') AS "confirmed!",
+               COUNT(*) FILTER (WHERE status = 'pending') AS "pending!"
+        FROM participants
+        WHERE event_id = $1
+        "#,
+        event_id
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// The organizer's own participant row.
+pub async fn organizer_id(conn: &mut PgConnection, event_id: Uuid) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT id FROM participants WHERE event_id = $1 AND is_organizer = true LIMIT 1",
+        event_id
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// Unassigns participants whose role is not among `names`.
+pub async fn clear_roles_except(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    names: &[String],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE participants SET role = NULL WHERE event_id = $1 AND role <> ALL($2)",
+        event_id,
+        names
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn organizer_name(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<String, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT name FROM participants WHERE event_id = $1 AND is_organizer = true LIMIT 1",
+        event_id
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// A confirmed participant with a verified address, who gets calendar
+/// invitations.
+#[derive(Debug)]
+pub struct Attendee {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+}
+
+pub async fn attendees(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<Vec<Attendee>, sqlx::Error> {
+    sqlx::query_as!(
+        Attendee,
+        r#"SELECT id, name, email AS "email!" FROM participants WHERE event_id = $1 AND email_verified_at IS NOT NULL AND status = 'confirmed' ORDER BY id"#,
+        event_id
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// An [`Attendee`] with their newest calendar invitation, if one was queued.
+#[derive(Debug)]
+pub struct InvitationRow {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+    pub rsvp_status: Option<String>,
+    pub attempts: Option<i32>,
+    pub last_error: Option<String>,
+    pub queued_at: Option<DateTime<Utc>>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+pub async fn invitations(pool: &PgPool, event_id: Uuid) -> Result<Vec<InvitationRow>, sqlx::Error> {
+    sqlx::query_as!(
+        InvitationRow,
+        r#"
+        SELECT p.id, p.name, p.email AS "email!", p.rsvp_status,
+               o.attempts AS "attempts?", o.last_error, o.created_at AS "queued_at?", o.sent_at
+        FROM participants p
+        LEFT JOIN LATERAL (
+            SELECT attempts, last_error, created_at, sent_at
+            FROM email_outbox
+            WHERE participant_id = p.id AND calendar_method = 'REQUEST'
+            ORDER BY id DESC
+            LIMIT 1
+        ) o ON true
+        WHERE p.event_id = $1 AND p.email_verified_at IS NOT NULL AND p.status = 'confirmed'
+        ORDER BY p.id
+        "#,
+        event_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// A participant as they see their own answer. The comment is still sealed.
+#[derive(Debug)]
+pub struct OwnAnswer {
+    pub id: i64,
+    pub name: String,
+    pub comment: Option<String>,
+    pub buffer_minutes: i32,
+    pub time_zone: Option<String>,
+    pub email: Option<String>,
+    pub rsvp_status: Option<String>,
+    pub role: Option<String>,
+    pub status: ParticipantStatus,
+}
+
+/// The answer behind `token`, if it belongs to the event.
+pub async fn find_by_token(
+    pool: &PgPool,
+    event_id: Uuid,
+    token: Uuid,
+) -> Result<Option<OwnAnswer>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT id, name, comment, buffer_minutes, time_zone, email, rsvp_status, role, status FROM participants WHERE token = $1 AND event_id = $2",
+        token,
+        event_id
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|row| OwnAnswer {
+        id: row.id,
+        name: row.name,
+        comment: row.comment,
+        buffer_minutes: row.buffer_minutes,
+        time_zone: row.time_zone,
+        email: row.email,
+        rsvp_status: row.rsvp_status,
+        role: row.role,
+        status: ParticipantStatus::from_db(&row.status),
+    }))
+}
+
+/// Name of participant `participant_id`, if they belong to the live event
+/// behind `organizer_token`.
+pub async fn name_for_organizer(
+    pool: &PgPool,
+    organizer_token: &str,
+    participant_id: i64,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT p.name
+        FROM participants p
+        JOIN events e ON e.id = p.event_id
+        WHERE e.organizer_token = $1 AND e.deleted_at IS NULL AND p.id = $2
+        "#,
+        organizer_token,
+        participant_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// A participant the organizer is about to remove.
+#[derive(Debug)]
+pub struct Removal {
+    pub name: String,
+    pub is_organizer: bool,
+    pub status: ParticipantStatus,
+}
+
+pub async fn find_for_removal(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    participant_id: i64,
+) -> Result<Option<Removal>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT name, is_organizer, status FROM participants WHERE id = $1 AND event_id = $2",
+        participant_id,
+        event_id
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(row.map(|row| Removal {
+        name: row.name,
+        is_organizer: row.is_organizer,
+        status: ParticipantStatus::from_db(&row.status),
+    }))
+}
+
+/// Removes the participant; their availability, its history and slot
+/// assignments cascade.
+pub async fn delete(conn: &mut PgConnection, participant_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM participants WHERE id = $1", participant_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Removes a confirmed participant other than the organizer; `false` when
+/// `participant_id` is none.
+pub async fn delete_confirmed(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    participant_id: i64,
+) -> Result<bool, sqlx::Error> {
+    let removed = sqlx::query!(
+        "DELETE FROM participants WHERE id = $1 AND event_id = $2 AND status = 'confirmed' AND is_organizer = false",
+        participant_id,
+        event_id
+    )
+    .execute(conn)
+    .await?
+    .rows_affected();
+    Ok(removed > 0)
+}
+
+/// Name and role of a waitlist entry of the event.
+pub async fn find_pending(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    participant_id: i64,
+) -> Result<Option<(String, Option<String>)>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT name, role FROM participants WHERE id = $1 AND event_id = $2 AND status = 'pending'",
+        participant_id,
+        event_id
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(row.map(|row| (row.name, row.role)))
+}
+
+/// Takes a waitlist entry into the results.
+pub async fn confirm(
+    conn: &mut PgConnection,
+    participant_id: i64,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE participants SET status = 'confirmed', updated_at = $1 WHERE id = $2",
+        now,
+        participant_id
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Waitlist entries, oldest first. Comments are still sealed.
+pub async fn waitlist(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<Vec<WaitlistEntry>, sqlx::Error> {
+    sqlx::query_as!(
+        WaitlistEntry,
+        r#"
+        SELECT id, name, comment, role, created_at
+        FROM participants
+        WHERE event_id = $1 AND status = 'pending'
+        ORDER BY created_at, id
+        "#,
+        event_id
+    )
+    .fetch_all(conn)
+    .await
+}
+
+pub async fn confirmed_ids(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT id FROM participants WHERE event_id = $1 AND status = 'confirmed'",
+        event_id
+    )
+    .fetch_all(conn)
+    .await
+}
+
+pub async fn rename_organizer(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    name: &str,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE participants SET name = $1, updated_at = $2 WHERE event_id = $3 AND is_organizer = true",
+        name,
+        now,
+        event_id
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// A participant token resolved across events.
+#[derive(Debug)]
+pub struct Participation {
+    pub id: i64,
+    pub token: Uuid,
+    pub name: String,
+    pub event_id: Uuid,
+    pub public_token: String,
+    pub title: String,
+    pub state: EventState,
+}
+
+/// The participants behind `tokens` in live events, in no particular order.
+/// `dated_only` leaves out weekly events.
+pub async fn participations(
+    pool: &PgPool,
+    tokens: &[Uuid],
+    dated_only: bool,
+) -> Result<Vec<Participation>, sqlx::Error> {
+    sqlx::query_as!(
+        Participation,
+        r#"
+        SELECT p.id, p.token, p.name, e.id AS event_id, e.public_token, e.title, e.state AS "state: EventState"
+        FROM participants p
+        JOIN events e ON e.id = p.event_id
+        WHERE p.token = ANY($1) AND e.deleted_at IS NULL AND (NOT $2 OR e.slot_kind = 'dates')
+        "#,
+        tokens,
+        dated_only
+    )
+    .fetch_all(pool)
+    .await
+}
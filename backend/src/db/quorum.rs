@@ -0,0 +1,154 @@
+use agreed_time_core::{ParticipantRanges, Suggestion, TimeRange, first_at_quorum};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    db::audit,
+    integrations::{
+        mail, notifications,
+        templates::{Notification, Params, Templates},
+        webhooks::{self, WebhookEvent},
+    },
+    models::EventState,
+    summary::format_span,
+};
+
+/// Closes an open event once `close_at_quorum` participants are available in
+/// the same `slot_duration` cell, announcing the earliest such cell through
+/// the `event.closed` webhook, Slack and a mail to every participant who
+/// confirmed an address. `public_url` makes the Slack message link the
+/// results. Runs inside the transaction that changed availability, which must
+/// hold the event row lock so concurrent submissions are counted in turn.
+pub async fn close_if_reached(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    templates: &Templates,
+    public_url: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<Option<Suggestion>, sqlx::Error> {
+    let event = sqlx::query!(
+        r#"SELECT title, locale, state AS "state: EventState", slot_duration, close_at_quorum FROM events WHERE id = $1"#,
+        event_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let Some(quorum) = event.close_at_quorum else {
+        return Ok(None);
+    };
+    if event.state != EventState::Open {
+        return Ok(None);
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT p.id, p.name, p.is_organizer, p.buffer_minutes, a.start_at, a.end_at
+        FROM participants p
+        JOIN availabilities a ON p.id = a.participant_id
+        WHERE p.event_id = $1 AND p.status = 'confirmed' AND a.availability_level = 'available'
+        ORDER BY p.is_organizer DESC, p.created_at ASC, p.id, a.start_at
+        "#,
+        event_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut participants: Vec<(i64, ParticipantRanges)> = Vec::new();
+    for row in rows {
+        if participants.last().map(|(id, _)| *id) != Some(row.id) {
+            participants.push((
+                row.id,
+                ParticipantRanges {
+                    name: row.name,
+                    is_organizer: row.is_organizer,
+                    availabilities: Vec::new(),
+                    if_need_be: Vec::new(),
+                    buffer_minutes: row.buffer_minutes,
+                    role: None,
+                },
+            ));
+        }
+        if let Some((_, participant)) = participants.last_mut() {
+            participant
+                .availabilities
+                .push(TimeRange::new(row.start_at, row.end_at));
+        }
+    }
+    let participants: Vec<ParticipantRanges> = participants.into_iter().map(|(_, p)| p).collect();
+
+    let Some(winner) = first_at_quorum(&participants, event.slot_duration, quorum.max(0) as usize)
+    else {
+        return Ok(None);
+    };
+
+    sqlx::query!(
+        "UPDATE events SET state = 'closed', updated_at = $2 WHERE id = $1",
+        event_id,
+        now
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let span = format_span(winner.start_at, winner.end_at);
+    audit::record(conn, event_id, "closed", "quorum", Some(&span), now).await?;
+    webhooks::enqueue(
+        conn,
+        event_id,
+        WebhookEvent::EventClosed,
+        json!({
+            "reason": "quorum",
+            "slot": { "start_at": winner.start_at, "end_at": winner.end_at },
+            "participants": winner.explanation.available,
+        }),
+        now,
+    )
+    .await?;
+    notifications::enqueue(
+        conn,
+        event_id,
+        notifications::Activity::Closed,
+        public_url,
+        now,
+    )
+    .await?;
+
+    let recipients = sqlx::query_scalar!(
+        r#"
+        SELECT email AS "email!"
+        FROM participants
+        WHERE event_id = $1 AND status = 'confirmed' AND email_verified_at IS NOT NULL
+        ORDER BY id
+        "#,
+        event_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mail = templates.render(
+        Notification::QuorumClosed,
+        &event.locale,
+        &Params::new()
+            .text("title", event.title.as_str())
+            .text("time", span.as_str())
+            .text("count", winner.count.to_string())
+            .text("available", winner.explanation.available.join(", ")),
+    );
+    for recipient in &recipients {
+        mail::enqueue(
+            conn,
+            mail::NewMail {
+                event_id: Some(event_id),
+                participant_id: None,
+                recipient,
+                subject: &mail.subject,
+                body: &mail.text,
+                html: Some(&mail.html),
+                calendar: None,
+            },
+        )
+        .await?;
+    }
+
+    Ok(Some(winner))
+}
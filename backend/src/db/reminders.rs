@@ -0,0 +1,122 @@
+//! Reminder batches behind `/events/{organizer_token}/remind`, and who they
+//! go to.
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::models::ReminderBatch;
+
+/// Batches sent since `since`, for the daily limit.
+pub async fn count_since(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    since: DateTime<Utc>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM event_reminders WHERE event_id = $1 AND sent_at > $2"#,
+        event_id,
+        since
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// A participant who still owes an answer.
+#[derive(Debug)]
+pub struct Recipient {
+    pub name: String,
+    pub email: String,
+}
+
+/// Confirmed participants with a verified address who haven't answered.
+/// Before finalizing, answering means giving availability; afterwards,
+/// answering the calendar invitation.
+pub async fn recipients(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    finalized: bool,
+) -> Result<Vec<Recipient>, sqlx::Error> {
+    sqlx::query_as!(
+        Recipient,
+        r#"
+        SELECT p.name, p.email AS "email!"
+        FROM participants p
+        WHERE p.event_id = $1
+          AND p.is_organizer = false
+          AND p.status = 'confirmed'
+          AND p.email_verified_at IS NOT NULL
+          AND CASE WHEN $2
+              THEN p.rsvp_status IS NULL
+              ELSE NOT EXISTS (SELECT 1 FROM availabilities a WHERE a.participant_id = p.id)
+          END
+        ORDER BY p.id
+        "#,
+        event_id,
+        finalized
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Invited addresses no participant holding them has answered from or is
+/// reminded at.
+pub async fn silent_invitees(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT i.email
+        FROM event_invitees i
+        WHERE i.event_id = $1
+          AND NOT EXISTS (
+              SELECT 1 FROM participants p
+              WHERE p.event_id = i.event_id
+                AND LOWER(p.email) = LOWER(i.email)
+                AND (p.email_verified_at IS NOT NULL
+                     OR EXISTS (SELECT 1 FROM availabilities a WHERE a.participant_id = p.id))
+          )
+        ORDER BY i.id
+        "#,
+        event_id
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Logs a sent batch; `recipient_count` includes the invitees.
+pub async fn record(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    recipient_count: i32,
+    invitee_count: i32,
+    sent_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO event_reminders (event_id, recipient_count, invitee_count, sent_at) VALUES ($1, $2, $3, $4)",
+        event_id,
+        recipient_count,
+        invitee_count,
+        sent_at
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Batches sent for the event, newest first.
+pub async fn batches(pool: &PgPool, event_id: Uuid) -> Result<Vec<ReminderBatch>, sqlx::Error> {
+    sqlx::query_as!(
+        ReminderBatch,
+        r#"
+        SELECT sent_at, recipient_count, invitee_count
+        FROM event_reminders
+        WHERE event_id = $1
+        ORDER BY sent_at DESC, id DESC
+        "#,
+        event_id
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,79 @@
+use sqlx::PgPool;
+
+use crate::{
+    clock::Clock,
+    integrations::{
+        mail,
+        templates::{Notification, Params, Templates},
+    },
+};
+
+/// Marks blind polls whose results have become visible and, when `notify` is
+/// set, queues a mail to every participant who confirmed an address. The
+/// visibility condition mirrors `handlers::events::results_revealed`.
+pub async fn notify_revealed_results(
+    pool: &PgPool,
+    clock: &dyn Clock,
+    templates: &Templates,
+    notify: bool,
+) -> Result<u64, sqlx::Error> {
+    let now = clock.now();
+    let mut transaction = pool.begin().await?;
+
+    let revealed = sqlx::query!(
+        r#"
+        UPDATE events
+        SET results_revealed_at = $1
+        WHERE deleted_at IS NULL
+          AND results_revealed_at IS NULL
+          AND (reveal_results_at IS NOT NULL OR blind_until_closed)
+          AND (reveal_results_at IS NULL OR reveal_results_at <= $1)
+          AND NOT (blind_until_closed AND state = 'open')
+        RETURNING id, title, locale
+        "#,
+        now
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    if notify {
+        for event in &revealed {
+            let recipients = sqlx::query_scalar!(
+                r#"
+                SELECT email AS "email!"
+                FROM participants
+                WHERE event_id = $1 AND status = 'confirmed' AND email_verified_at IS NOT NULL
+                ORDER BY id
+                "#,
+                event.id
+            )
+            .fetch_all(&mut *transaction)
+            .await?;
+
+            let mail = templates.render(
+                Notification::ResultsRevealed,
+                &event.locale,
+                &Params::new().text("title", event.title.as_str()),
+            );
+            for recipient in &recipients {
+                mail::enqueue(
+                    &mut transaction,
+                    mail::NewMail {
+                        event_id: Some(event.id),
+                        participant_id: None,
+                        recipient,
+                        subject: &mail.subject,
+                        body: &mail.text,
+                        html: Some(&mail.html),
+                        calendar: None,
+                    },
+                )
+                .await?;
+            }
+        }
+    }
+
+    transaction.commit().await?;
+
+    Ok(revealed.len() as u64)
+}
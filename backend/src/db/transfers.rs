@@ -0,0 +1,78 @@
+//! One-time claim links that hand an event to a new organizer.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Drops the claim links not yet redeemed; only the latest one is valid.
+pub async fn revoke_pending(conn: &mut PgConnection, event_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM ownership_transfers WHERE event_id = $1 AND redeemed_at IS NULL",
+        event_id
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Stores a claim link; `false` when `claim_token` is taken.
+pub async fn insert(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    claim_token: &str,
+    expires_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let inserted = sqlx::query_scalar!(
+        r#"
+        INSERT INTO ownership_transfers (event_id, claim_token, expires_at, created_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT DO NOTHING
+        RETURNING id
+        "#,
+        event_id,
+        claim_token,
+        expires_at,
+        now
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(inserted.is_some())
+}
+
+/// Locks the claim link if it is still redeemable; returns its id and the
+/// event's.
+pub async fn lock_redeemable(
+    conn: &mut PgConnection,
+    claim_token: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<(i64, Uuid)>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT id, event_id
+        FROM ownership_transfers
+        WHERE claim_token = $1 AND redeemed_at IS NULL AND expires_at > $2
+        FOR UPDATE
+        "#,
+        claim_token,
+        now
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(row.map(|row| (row.id, row.event_id)))
+}
+
+pub async fn redeem(
+    conn: &mut PgConnection,
+    transfer_id: i64,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE ownership_transfers SET redeemed_at = $1 WHERE id = $2",
+        now,
+        transfer_id
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
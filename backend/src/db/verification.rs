@@ -0,0 +1,177 @@
+//! Confirmation of the addresses participants leave.
+//!
+//! Submitting an address that isn't confirmed yet mails a single-use link
+//! valid for [`VERIFICATION_TTL_HOURS`]; following it sets
+//! `participants.email_verified_at`, and changing the address clears it.
+//! Invitations, reminders and result notices only go to confirmed addresses,
+//! so a mistyped or borrowed address receives nothing beyond the link.
+//! Tokens are stored hashed like login links.
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgConnection;
+
+use crate::{
+    auth,
+    config::Config,
+    integrations::{
+        mail,
+        templates::{Notification, Params, Templates},
+    },
+};
+
+pub const VERIFICATION_TTL_HOURS: i64 = 72;
+
+/// Unused links one participant or address may hold at once; further
+/// submissions send nothing, so resubmitting can't flood a mailbox.
+pub const MAX_PENDING_VERIFICATIONS: i64 = 3;
+
+/// Mails confirmation links; exists only when participant mail can go out.
+pub struct Verifier<'a> {
+    public_url: &'a str,
+    templates: &'a Templates,
+}
+
+impl<'a> Verifier<'a> {
+    /// `None` without `MAIL_FROM` or `PUBLIC_URL`: no link can be sent, so
+    /// addresses stay unconfirmed.
+    pub fn new(config: &'a Config, templates: &'a Templates) -> Option<Self> {
+        match (&config.mail_from, &config.public_url) {
+            (Some(_), Some(public_url)) => Some(Verifier {
+                public_url,
+                templates,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Queues a link for the participant's address unless there is none, it
+    /// is confirmed or enough links are pending. Returns whether one was.
+    pub async fn request(
+        &self,
+        conn: &mut PgConnection,
+        participant_id: i64,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let Some(participant) = sqlx::query!(
+            r#"
+            SELECT p.email AS "email!", e.id AS event_id, e.title, e.locale
+            FROM participants p
+            JOIN events e ON e.id = p.event_id
+            WHERE p.id = $1 AND p.email IS NOT NULL AND p.email_verified_at IS NULL
+            "#,
+            participant_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        else {
+            return Ok(false);
+        };
+
+        let pending = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!" FROM email_verifications
+            WHERE (participant_id = $1 OR LOWER(email) = LOWER($2))
+              AND used_at IS NULL AND expires_at > $3
+            "#,
+            participant_id,
+            participant.email,
+            now
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        if pending >= MAX_PENDING_VERIFICATIONS {
+            return Ok(false);
+        }
+
+        let token = auth::generate_secret();
+        sqlx::query!(
+            r#"
+            INSERT INTO email_verifications (token_hash, participant_id, email, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            auth::hash_token(&token),
+            participant_id,
+            participant.email,
+            now,
+            now + Duration::hours(VERIFICATION_TTL_HOURS)
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        let rendered = self.templates.render(
+            Notification::EmailVerification,
+            &participant.locale,
+            &Params::new()
+                .text("title", participant.title)
+                .text(
+                    "link",
+                    format!(
+                        "{}/participants/verify-email?token={}",
+                        self.public_url, token
+                    ),
+                )
+                .text("hours", VERIFICATION_TTL_HOURS.to_string()),
+        );
+        mail::enqueue(
+            &mut *conn,
+            mail::NewMail {
+                event_id: Some(participant.event_id),
+                participant_id: None,
+                recipient: &participant.email,
+                subject: &rendered.subject,
+                body: &rendered.text,
+                html: Some(&rendered.html),
+                calendar: None,
+            },
+        )
+        .await?;
+        Ok(true)
+    }
+}
+
+/// The address a followed link confirmed and the event it was left for.
+#[derive(Debug)]
+pub struct Confirmed {
+    pub email: String,
+    pub title: String,
+    pub public_token: String,
+}
+
+/// Spends a link and confirms the address it was sent to. `None` for
+/// unknown, used and expired links, and for addresses replaced since.
+pub async fn confirm(
+    conn: &mut PgConnection,
+    token: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<Confirmed>, sqlx::Error> {
+    let Some(link) = sqlx::query!(
+        r#"
+        UPDATE email_verifications SET used_at = $2
+        WHERE token_hash = $1 AND used_at IS NULL AND expires_at > $2
+        RETURNING participant_id, email
+        "#,
+        auth::hash_token(token),
+        now
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    sqlx::query_as!(
+        Confirmed,
+        r#"
+        UPDATE participants p
+        SET email_verified_at = COALESCE(p.email_verified_at, $3)
+        FROM events e
+        WHERE p.id = $1 AND LOWER(p.email) = LOWER($2) AND e.id = p.event_id
+        RETURNING p.email AS "email!", e.title, e.public_token
+        "#,
+        link.participant_id,
+        link.email,
+        now
+    )
+    .fetch_optional(conn)
+    .await
+}
@@ -0,0 +1,247 @@
+//! Column encryption for data that is stored and read back but never
+//! searched: webhook secrets, organizer emails and comments.
+//!
+//! With `DATA_ENCRYPTION_KEY` set, [`Keyring::seal`] stores values as
+//! `enc:v1:<key id>:<base64 nonce + ciphertext>` (AES-256-GCM, the key id is
+//! the first bytes of the key's SHA-256), and [`Keyring::open`] reads them
+//! back with whichever configured key the id names. During a rotation the old
+//! key stays readable as `DATA_ENCRYPTION_KEY_PREVIOUS` while the
+//! `rotate-keys` command re-encrypts rows ([`crate::db::keys`]).
+//!
+//! Without a key values are stored as `plain:<value>`, so text a user typed
+//! that happens to start with `enc:v1:` is never mistaken for ciphertext.
+//! Untagged values were written before either prefix existed and are
+//! returned as they are.
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use rand::RngCore;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+
+const PREFIX: &str = "enc:v1:";
+const PLAIN: &str = "plain:";
+
+pub type SharedKeyring = Arc<Keyring>;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum EncryptionError {
+    #[error("value is encrypted with unknown key {0}")]
+    UnknownKey(String),
+    #[error("encrypted value is corrupt")]
+    Corrupt,
+}
+
+impl From<EncryptionError> for sqlx::Error {
+    fn from(error: EncryptionError) -> Self {
+        sqlx::Error::Decode(Box::new(error))
+    }
+}
+
+/// A 256-bit key, given base64-encoded.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DataKey([u8; 32]);
+
+impl DataKey {
+    /// Hex prefix of the key's SHA-256, stored with every value it seals.
+    pub fn id(&self) -> String {
+        hex::encode(&Sha256::digest(self.0)[..4])
+    }
+
+    /// A fresh random key, base64-encoded for the env file.
+    pub fn generate() -> String {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn cipher(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.0).expect("32 bytes fit AES-256"))
+    }
+}
+
+impl FromStr for DataKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(s.trim())
+            .map_err(|_| anyhow::anyhow!("Encryption keys must be base64"))?;
+        let Ok(key) = <[u8; 32]>::try_from(bytes) else {
+            anyhow::bail!("Encryption keys must be 32 bytes");
+        };
+        Ok(DataKey(key))
+    }
+}
+
+impl fmt::Debug for DataKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DataKey({})", self.id())
+    }
+}
+
+/// The key new values are sealed with and the one still accepted on reads.
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    current: Option<DataKey>,
+    previous: Option<DataKey>,
+}
+
+impl Keyring {
+    pub fn new(current: Option<DataKey>, previous: Option<DataKey>) -> Self {
+        Keyring { current, previous }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Keyring::new(
+            config.data_encryption_key.clone(),
+            config.data_encryption_key_previous.clone(),
+        )
+    }
+
+    /// `enc:v1:<key id>:` for the current key; `None` when encryption is off.
+    pub fn current_prefix(&self) -> Option<String> {
+        self.current
+            .as_ref()
+            .map(|key| format!("{}{}:", PREFIX, key.id()))
+    }
+
+    /// Encrypts `value` with the current key; tagged plaintext when there is
+    /// none.
+    pub fn seal(&self, value: &str) -> String {
+        let Some(key) = &self.current else {
+            return format!("{}{}", PLAIN, value);
+        };
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let mut sealed = value.as_bytes().to_vec();
+        key.cipher()
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut sealed,
+            )
+            .expect("AES-GCM seals any value of column size");
+        let mut payload = nonce.to_vec();
+        payload.extend(sealed);
+        format!("{}{}:{}", PREFIX, key.id(), URL_SAFE_NO_PAD.encode(payload))
+    }
+
+    pub fn seal_opt(&self, value: Option<&str>) -> Option<String> {
+        value.map(|value| self.seal(value))
+    }
+
+    /// Decrypts a stored value with the key it names; plaintext passes through.
+    pub fn open(&self, stored: &str) -> Result<String, EncryptionError> {
+        if let Some(plain) = stored.strip_prefix(PLAIN) {
+            return Ok(plain.to_string());
+        }
+        let Some(rest) = stored.strip_prefix(PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let (key_id, payload) = rest.split_once(':').ok_or(EncryptionError::Corrupt)?;
+        let key = [&self.current, &self.previous]
+            .into_iter()
+            .flatten()
+            .find(|key| key.id() == key_id)
+            .ok_or_else(|| EncryptionError::UnknownKey(key_id.to_string()))?;
+
+        let payload = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|_| EncryptionError::Corrupt)?;
+        if payload.len() < NONCE_LEN {
+            return Err(EncryptionError::Corrupt);
+        }
+        let (nonce, sealed) = payload.split_at(NONCE_LEN);
+        let nonce =
+            Nonce::try_assume_unique_for_key(nonce).map_err(|_| EncryptionError::Corrupt)?;
+        let mut sealed = sealed.to_vec();
+        let plain = key
+            .cipher()
+            .open_in_place(nonce, Aad::empty(), &mut sealed)
+            .map_err(|_| EncryptionError::Corrupt)?;
+        String::from_utf8(plain.to_vec()).map_err(|_| EncryptionError::Corrupt)
+    }
+
+    pub fn open_opt(&self, stored: Option<String>) -> Result<Option<String>, EncryptionError> {
+        stored.map(|stored| self.open(&stored)).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> DataKey {
+        DataKey([byte; 32])
+    }
+
+    #[test]
+    fn test_seal_round_trip() {
+        let keys = Keyring::new(Some(key(1)), None);
+        let sealed = keys.seal("organizer@example.com");
+        assert!(sealed.starts_with(&keys.current_prefix().unwrap()));
+        assert!(!sealed.contains("organizer"));
+        assert_ne!(sealed, keys.seal("organizer@example.com"));
+        assert_eq!(keys.open(&sealed).unwrap(), "organizer@example.com");
+    }
+
+    #[test]
+    fn test_plaintext_without_a_key() {
+        let keys = Keyring::default();
+        assert_eq!(keys.seal("hello"), "plain:hello");
+        assert_eq!(keys.open("plain:hello").unwrap(), "hello");
+        // Written before values were tagged
+        assert_eq!(keys.open("hello").unwrap(), "hello");
+        assert_eq!(
+            Keyring::new(Some(key(1)), None)
+                .open("plain:hello")
+                .unwrap(),
+            "hello"
+        );
+    }
+
+    #[test]
+    fn test_text_that_looks_sealed_round_trips() {
+        let typed = "enc:v1:x:y";
+        for keys in [Keyring::default(), Keyring::new(Some(key(1)), None)] {
+            assert_eq!(keys.open(&keys.seal(typed)).unwrap(), typed);
+        }
+    }
+
+    #[test]
+    fn test_previous_key_still_opens() {
+        let old = Keyring::new(Some(key(1)), None);
+        let sealed = old.seal("secret");
+
+        let rotating = Keyring::new(Some(key(2)), Some(key(1)));
+        assert_eq!(rotating.open(&sealed).unwrap(), "secret");
+
+        let rotated = Keyring::new(Some(key(2)), None);
+        assert_eq!(
+            rotated.open(&sealed),
+            Err(EncryptionError::UnknownKey(key(1).id()))
+        );
+    }
+
+    #[test]
+    fn test_tampered_values_are_rejected() {
+        let keys = Keyring::new(Some(key(1)), None);
+        let mut sealed = keys.seal("secret");
+        let last = sealed.pop().unwrap();
+        sealed.push(if last == 'A' { 'B' } else { 'A' });
+        assert_eq!(keys.open(&sealed), Err(EncryptionError::Corrupt));
+    }
+
+    #[test]
+    fn test_parse_data_key() {
+        let generated = DataKey::generate();
+        assert!(generated.parse::<DataKey>().is_ok());
+        for invalid in ["", "not base64!", "c2hvcnQ="] {
+            assert!(invalid.parse::<DataKey>().is_err(), "{}", invalid);
+        }
+    }
+}
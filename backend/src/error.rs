@@ -3,7 +3,31 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
-use serde_json::json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::encryption::EncryptionError;
+
+/// JSON body returned for every error response.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+    /// Same as the `X-Request-Id` header; quote it when reporting a problem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// One entry per invalid field, for `VALIDATION_FAILED`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FieldError {
+    /// Payload field, with an index for list items (`time_slots[2]`)
+    pub field: String,
+    pub message: String,
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
@@ -13,11 +37,44 @@ pub enum AppError {
     #[error("Not found")]
     NotFound,
 
+    #[error("Unauthorized")]
+    Unauthorized,
+
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    #[error("Invalid input: {0:?}")]
+    Validation(Vec<FieldError>),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Event is archived")]
+    Archived,
+
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
     #[error("Event has reached maximum limit of {0} participants")]
     ParticipantLimitReached(i64),
+
+    #[error("Event password required")]
+    PasswordRequired,
+
+    #[error("Results locked: {0}")]
+    ResultsLocked(String),
+
+    #[error("Creation ticket required")]
+    CreationTicketRequired,
+
+    #[error("Captcha verification failed")]
+    CaptchaFailed,
+
+    #[error("Client banned until {0}")]
+    Banned(DateTime<Utc>),
+
+    #[error("Calendar unavailable: {0}")]
+    CalendarUnavailable(String),
 }
 
 impl AppError {
@@ -25,38 +82,103 @@ impl AppError {
         match self {
             AppError::Database(_) => "INTERNAL_SERVER_ERROR",
             AppError::NotFound => "NOT_FOUND",
+            AppError::Unauthorized => "UNAUTHORIZED",
             AppError::BadRequest(_) => "BAD_REQUEST",
+            AppError::Validation(_) => "VALIDATION_FAILED",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::Archived => "ARCHIVED",
+            AppError::TooManyRequests(_) => "TOO_MANY_REQUESTS",
             AppError::ParticipantLimitReached(_) => "PARTICIPANT_LIMIT_REACHED",
+            AppError::PasswordRequired => "PASSWORD_REQUIRED",
+            AppError::ResultsLocked(_) => "RESULTS_LOCKED",
+            AppError::CreationTicketRequired => "CREATION_TICKET_REQUIRED",
+            AppError::CaptchaFailed => "CAPTCHA_FAILED",
+            AppError::Banned(_) => "BANNED",
+            AppError::CalendarUnavailable(_) => "CALENDAR_UNAVAILABLE",
         }
     }
-}
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let code = self.code().to_string(); // Get code before consuming self
-
-        let (status, message) = match self {
-            AppError::Database(e) => {
-                tracing::error!("Database error: {:?}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "Database error".to_string(),
-                )
-            }
+    /// The JSON body this error is answered with.
+    pub fn body(&self) -> ErrorResponse {
+        ErrorResponse {
+            error: self.status_and_message().1,
+            code: self.code().to_string(),
+            request_id: crate::middleware::current_request_id(),
+            details: match self {
+                AppError::Validation(errors) => errors.clone(),
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    fn status_and_message(&self) -> (StatusCode, String) {
+        match *self {
+            AppError::Database(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error".to_string(),
+            ),
             AppError::NotFound => (StatusCode::NOT_FOUND, "Resource not found".to_string()),
+            AppError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                "Missing or invalid credentials".to_string(),
+            ),
             AppError::BadRequest(ref msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+            AppError::Validation(ref errors) => (
+                StatusCode::BAD_REQUEST,
+                errors
+                    .iter()
+                    .map(|e| e.message.as_str())
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ),
+            AppError::Conflict(ref msg) => (StatusCode::CONFLICT, msg.clone()),
+            AppError::Archived => (
+                StatusCode::CONFLICT,
+                "Event is archived and read-only".to_string(),
+            ),
+            AppError::TooManyRequests(ref msg) => (StatusCode::TOO_MANY_REQUESTS, msg.clone()),
             AppError::ParticipantLimitReached(limit) => (
                 StatusCode::BAD_REQUEST,
                 format!("Event has reached maximum limit of {} participants", limit),
             ),
-        };
+            AppError::PasswordRequired => (
+                StatusCode::FORBIDDEN,
+                "Event password is missing or wrong".to_string(),
+            ),
+            AppError::ResultsLocked(ref msg) => (StatusCode::FORBIDDEN, msg.clone()),
+            AppError::CreationTicketRequired => (
+                StatusCode::FORBIDDEN,
+                "Creating events needs a fresh ticket from POST /events/ticket".to_string(),
+            ),
+            AppError::CaptchaFailed => (
+                StatusCode::FORBIDDEN,
+                "Captcha is missing, expired or was already used".to_string(),
+            ),
+            AppError::Banned(until) => (
+                StatusCode::FORBIDDEN,
+                format!(
+                    "Requests from this address are blocked until {}",
+                    until.format("%Y-%m-%d %H:%M UTC")
+                ),
+            ),
+            AppError::CalendarUnavailable(ref msg) => (StatusCode::BAD_GATEWAY, msg.clone()),
+        }
+    }
+}
 
-        let body = Json(json!({
-            "error": message,
-            "code": code,
-        }));
+/// A value that can't be decrypted is as broken as an unreadable row.
+impl From<EncryptionError> for AppError {
+    fn from(error: EncryptionError) -> Self {
+        AppError::Database(error.into())
+    }
+}
 
-        (status, body).into_response()
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        if let AppError::Database(e) = &self {
+            tracing::error!("Database error: {:?}", e);
+        }
+        (self.status_and_message().0, Json(self.body())).into_response()
     }
 }
 
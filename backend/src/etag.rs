@@ -0,0 +1,184 @@
+//! Conditional GETs for the event reads open tabs keep polling.
+//!
+//! The weak ETag hashes the event's version: its `updated_at`, its
+//! `expires_at` (which `/extend` moves without touching `updated_at`), the
+//! latest participant `updated_at` and the participant count (so removals
+//! count too), plus which clock-driven deadlines have passed. Handlers add
+//! whatever else their response depends on, such as the display zone or the
+//! results view, and check `If-None-Match` after the access checks but before
+//! the heavy reads, so a poll of an unchanged event costs the lookup alone.
+//!
+//! Countdowns (`seconds_until_*`) are part of the body, so views carrying
+//! them add [`countdown_bucket`] to their tag: it rolls over every
+//! [`COUNTDOWN_BUCKET_SECS`], which bounds how old a revalidated countdown
+//! gets. Clients that need them to the second should count from the
+//! absolute times instead.
+
+use axum::{
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+/// Browsers may keep the body but must revalidate before every reuse;
+/// shared caches must not keep it at all, since the event password and
+/// results token gate what it contains.
+pub const CACHE_CONTROL: &str = "private, no-cache";
+
+/// How long a tag covers a body with countdowns; see [`countdown_bucket`].
+pub const COUNTDOWN_BUCKET_SECS: i64 = 60;
+
+/// Tag part for responses with `seconds_until_*` countdowns: the same
+/// within each [`COUNTDOWN_BUCKET_SECS`] window.
+pub fn countdown_bucket(now: DateTime<Utc>) -> String {
+    now.timestamp()
+        .div_euclid(COUNTDOWN_BUCKET_SECS)
+        .to_string()
+}
+
+/// What an event read can change with; handlers select it together with
+/// the event lookup.
+#[derive(Debug, Clone)]
+pub struct EventVersion {
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    pub participants_updated_at: Option<DateTime<Utc>>,
+    pub participants: i64,
+}
+
+impl EventVersion {
+    /// Weak ETag of this version at `now` for a response that also depends
+    /// on `variant`.
+    pub fn etag(&self, now: DateTime<Utc>, variant: &[&str]) -> HeaderValue {
+        let mut hasher = Sha256::new();
+        for part in [
+            self.updated_at.to_rfc3339(),
+            self.expires_at.to_rfc3339(),
+            self.participants_updated_at
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+            self.participants.to_string(),
+            self.reveal_results_at
+                .is_none_or(|at| at <= now)
+                .to_string(),
+            (self.expires_at <= now).to_string(),
+        ]
+        .iter()
+        .map(String::as_str)
+        .chain(variant.iter().copied())
+        {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        let digest = hex::encode(&hasher.finalize()[..16]);
+        HeaderValue::from_str(&format!("W/\"{}\"", digest)).expect("hex is a valid header value")
+    }
+}
+
+/// Whether `If-None-Match` already names `etag`, compared weakly
+/// (RFC 9110 §13.1.2).
+pub fn is_fresh(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let Ok(etag) = etag.to_str().map(opaque) else {
+        return false;
+    };
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == etag)
+}
+
+/// `304 Not Modified` with the validator repeated, as the spec asks.
+pub fn not_modified(etag: HeaderValue) -> Response {
+    (
+        StatusCode::NOT_MODIFIED,
+        [
+            (header::ETAG, etag),
+            (
+                header::CACHE_CONTROL,
+                HeaderValue::from_static(CACHE_CONTROL),
+            ),
+        ],
+    )
+        .into_response()
+}
+
+/// `body` with the headers that let the next poll be conditional.
+pub fn with_etag(etag: HeaderValue, body: impl IntoResponse) -> Response {
+    (
+        [
+            (header::ETAG, etag),
+            (
+                header::CACHE_CONTROL,
+                HeaderValue::from_static(CACHE_CONTROL),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version() -> EventVersion {
+        EventVersion {
+            updated_at: "2030-01-01T09:00:00Z".parse().unwrap(),
+            expires_at: "2030-03-01T09:00:00Z".parse().unwrap(),
+            reveal_results_at: Some("2030-01-02T09:00:00Z".parse().unwrap()),
+            participants_updated_at: None,
+            participants: 1,
+        }
+    }
+
+    #[test]
+    fn test_etag_changes_with_version_variant_and_deadlines() {
+        let now = "2030-01-01T12:00:00Z".parse().unwrap();
+        let etag = version().etag(now, &["UTC"]);
+        assert!(etag.to_str().unwrap().starts_with("W/\""));
+        assert_eq!(version().etag(now, &["UTC"]), etag);
+        assert_ne!(version().etag(now, &["Asia/Tokyo"]), etag);
+        let joined = EventVersion {
+            participants: 2,
+            ..version()
+        };
+        assert_ne!(joined.etag(now, &["UTC"]), etag);
+        let revealed = "2030-01-02T12:00:00Z".parse().unwrap();
+        assert_ne!(version().etag(revealed, &["UTC"]), etag);
+    }
+
+    #[test]
+    fn test_countdown_bucket_rolls_over_each_window() {
+        let start: DateTime<Utc> = "2030-01-01T12:00:00Z".parse().unwrap();
+        let bucket = countdown_bucket(start);
+        assert_eq!(
+            countdown_bucket(start + chrono::Duration::seconds(COUNTDOWN_BUCKET_SECS - 1)),
+            bucket
+        );
+        assert_ne!(
+            countdown_bucket(start + chrono::Duration::seconds(COUNTDOWN_BUCKET_SECS)),
+            bucket
+        );
+    }
+
+    #[test]
+    fn test_is_fresh_compares_weakly() {
+        let etag = HeaderValue::from_static("W/\"abc\"");
+        let mut headers = HeaderMap::new();
+        assert!(!is_fresh(&headers, &etag));
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_static("\"xyz\", \"abc\""),
+        );
+        assert!(is_fresh(&headers, &etag));
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("W/\"xyz\""));
+        assert!(!is_fresh(&headers, &etag));
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("*"));
+        assert!(is_fresh(&headers, &etag));
+    }
+}
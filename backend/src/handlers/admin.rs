@@ -0,0 +1,949 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use std::{collections::BTreeMap, net::IpAddr, sync::Arc, sync::atomic::Ordering};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    clock::{Clock, SharedClock},
+    config::{Config, RateLimit},
+    db::{self, cleanup, jobs},
+    error::{AppError, AppResult, ErrorResponse},
+    integrations::{
+        templates::{DEFAULT_LOCALE, Notification, SharedTemplates},
+        webhooks,
+    },
+    metrics::{self, RouteCounts, SharedCounters, SharedMetrics},
+    middleware::AdminActor,
+    models::EventState,
+    security::{self, SharedBans},
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EventSearchQuery {
+    /// Prefix of either the public or the organizer token (min 4 characters)
+    pub token_prefix: Option<String>,
+    /// Case-insensitive fragment of the title (min 3 characters)
+    pub title: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminEventSummary {
+    pub id: Uuid,
+    pub public_token: String,
+    pub organizer_token: String,
+    pub title: String,
+    pub state: EventState,
+    pub organizer_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Set while the event is in the trash
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminEventSearchResponse {
+    pub events: Vec<AdminEventSummary>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EventListQuery {
+    pub state: Option<EventState>,
+    /// `true` for trashed events only, `false` for live ones only; both when unset
+    pub trashed: Option<bool>,
+    /// Created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Created before this time
+    pub created_before: Option<DateTime<Utc>>,
+    /// Page size (default 50, at most 200)
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminEventListResponse {
+    /// Newest first
+    pub events: Vec<AdminEventSummary>,
+    /// Matching events across all pages
+    pub total: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminRouteLimit {
+    /// `default`, `create_event` or `health`
+    pub group: String,
+    /// Null when the group is not limited
+    pub requests: Option<u32>,
+    pub window_secs: Option<u64>,
+    /// Decisions since the process started
+    pub allowed: u64,
+    pub limited: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminRateLimitsResponse {
+    /// `redis` when limits are shared between replicas, otherwise `memory`
+    pub store: String,
+    pub routes: Vec<AdminRouteLimit>,
+    /// Clients this process's in-memory limiter tracks right now
+    pub tracked_clients: u64,
+    pub max_clients: usize,
+    /// Clients dropped to stay under `max_clients`
+    pub evicted_clients: u64,
+    /// Per-event submission limit; 0 when disabled
+    pub submission_limit: u32,
+    pub submission_window_secs: u64,
+    pub submissions_limited: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminCleanupResponse {
+    /// Expired events moved to the trash
+    pub trashed: u64,
+    /// Trashed events past their retention removed for good
+    pub purged: u64,
+    /// Finished events archived
+    pub archived: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminStatsResponse {
+    pub collected_at: DateTime<Utc>,
+    /// Live events per state
+    pub events_by_state: BTreeMap<String, i64>,
+    pub events_trashed: i64,
+    /// Open events starting within 48 hours that nobody answered yet
+    pub events_at_risk: i64,
+    pub events_created_last_24h: i64,
+    pub events_created_last_7d: i64,
+    /// Participants of live events, organizers excluded
+    pub participants: i64,
+    /// Availability ranges of live events
+    pub availabilities: i64,
+    /// Live events the retention job trashes within a day / a week
+    pub events_expiring_next_24h: i64,
+    pub events_expiring_next_7d: i64,
+    pub users: i64,
+    pub organizer_accounts: i64,
+    pub outbox_pending: i64,
+    pub outbox_failed: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminBan {
+    pub ip: String,
+    pub reason: String,
+    /// `auto` for the abuse detector, otherwise the admin actor
+    pub banned_by: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminBansResponse {
+    /// Active bans, those ending soonest first
+    pub bans: Vec<AdminBan>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateBanRequest {
+    /// IPv4 or IPv6 address
+    pub ip: String,
+    pub reason: Option<String>,
+    /// Defaults to `ABUSE_BAN_SECS`
+    pub duration_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct JobRunsQuery {
+    /// Only runs of this job, e.g. `cleanup.trash_expired`
+    pub job: Option<String>,
+    /// Only failed runs
+    pub failed: Option<bool>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminJobRun {
+    pub id: i64,
+    pub job: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    /// Rows trashed/purged/archived, mails or webhooks attempted, events revealed
+    pub affected_rows: i64,
+    /// Set when the run failed
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminJobStatus {
+    pub job: String,
+    pub last_started_at: DateTime<Utc>,
+    pub last_succeeded_at: Option<DateTime<Utc>>,
+    /// Error of the latest run; null when it succeeded
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminJobsResponse {
+    /// Latest state of every job that has run within the retention window
+    pub jobs: Vec<AdminJobStatus>,
+    /// Matching runs, most recent first
+    pub runs: Vec<AdminJobRun>,
+}
+
+/// Escapes LIKE wildcards so user input is matched literally.
+fn escape_like(input: &str) -> String {
+    input
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Records an admin lookup. Failing to log fails the request: unaudited
+/// access is not allowed.
+pub(crate) async fn log_access(
+    pool: &PgPool,
+    actor: &AdminActor,
+    action: &str,
+    details: &str,
+    result_count: i32,
+) -> AppResult<()> {
+    tracing::info!(actor = %actor.0, action, details, result_count, "Admin access");
+
+    sqlx::query!(
+        r#"
+        INSERT INTO admin_access_log (action, details, actor, result_count)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        action,
+        details,
+        actor.0,
+        result_count
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/events/search",
+    tag = "admin",
+    params(EventSearchQuery),
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Matching events (max 50)", body = AdminEventSearchResponse),
+        (status = 400, description = "No usable filter given", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse)
+    )
+)]
+pub async fn search_events(
+    State(pool): State<PgPool>,
+    Extension(actor): Extension<AdminActor>,
+    Query(query): Query<EventSearchQuery>,
+) -> AppResult<Json<AdminEventSearchResponse>> {
+    let token_prefix = query
+        .token_prefix
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty());
+    let title = query
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+
+    if token_prefix.is_none() && title.is_none() {
+        return Err(AppError::BadRequest(
+            "Provide token_prefix or title".to_string(),
+        ));
+    }
+    if token_prefix.is_some_and(|p| p.len() < 4) {
+        return Err(AppError::BadRequest(
+            "token_prefix must be at least 4 characters".to_string(),
+        ));
+    }
+    if title.is_some_and(|t| t.len() < 3) {
+        return Err(AppError::BadRequest(
+            "title must be at least 3 characters".to_string(),
+        ));
+    }
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 50);
+    let token_pattern = token_prefix.map(|p| format!("{}%", escape_like(p)));
+    let title_pattern = title.map(|t| format!("%{}%", escape_like(t)));
+
+    let events = sqlx::query_as!(
+        AdminEventSummary,
+        r#"
+        SELECT e.id, e.public_token, e.organizer_token, e.title, e.state AS "state: EventState", e.created_at, e.deleted_at,
+               (SELECT p.name FROM participants p
+                WHERE p.event_id = e.id AND p.is_organizer = true
+                LIMIT 1) AS organizer_name
+        FROM events e
+        WHERE ($1::text IS NULL OR e.public_token LIKE $1 OR e.organizer_token LIKE $1)
+          AND ($2::text IS NULL OR e.title ILIKE $2)
+        ORDER BY e.created_at DESC
+        LIMIT $3
+        "#,
+        token_pattern,
+        title_pattern,
+        limit
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let details = format!(
+        "token_prefix={:?} title={:?}",
+        token_prefix.unwrap_or_default(),
+        title.unwrap_or_default()
+    );
+    log_access(
+        &pool,
+        &actor,
+        "events.search",
+        &details,
+        events.len() as i32,
+    )
+    .await?;
+
+    Ok(Json(AdminEventSearchResponse { events }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/events",
+    tag = "admin",
+    params(EventListQuery),
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Events matching every given filter", body = AdminEventListResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse)
+    )
+)]
+pub async fn list_events(
+    State(pool): State<PgPool>,
+    Extension(actor): Extension<AdminActor>,
+    Query(query): Query<EventListQuery>,
+) -> AppResult<Json<AdminEventListResponse>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let state = query.state.map(|state| state.as_str());
+
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM events
+        WHERE ($1::text IS NULL OR state = $1)
+          AND ($2::bool IS NULL OR (deleted_at IS NOT NULL) = $2)
+          AND ($3::timestamptz IS NULL OR created_at >= $3)
+          AND ($4::timestamptz IS NULL OR created_at < $4)
+        "#,
+        state,
+        query.trashed,
+        query.created_after,
+        query.created_before
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let events = sqlx::query_as!(
+        AdminEventSummary,
+        r#"
+        SELECT e.id, e.public_token, e.organizer_token, e.title, e.state AS "state: EventState", e.created_at, e.deleted_at,
+               (SELECT p.name FROM participants p
+                WHERE p.event_id = e.id AND p.is_organizer = true
+                LIMIT 1) AS organizer_name
+        FROM events e
+        WHERE ($1::text IS NULL OR e.state = $1)
+          AND ($2::bool IS NULL OR (e.deleted_at IS NOT NULL) = $2)
+          AND ($3::timestamptz IS NULL OR e.created_at >= $3)
+          AND ($4::timestamptz IS NULL OR e.created_at < $4)
+        ORDER BY e.created_at DESC, e.id
+        LIMIT $5 OFFSET $6
+        "#,
+        state,
+        query.trashed,
+        query.created_after,
+        query.created_before,
+        limit,
+        offset
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let details = format!(
+        "state={:?} trashed={:?} created_after={:?} created_before={:?} offset={}",
+        state.unwrap_or_default(),
+        query.trashed,
+        query.created_after,
+        query.created_before,
+        offset
+    );
+    log_access(&pool, &actor, "events.list", &details, events.len() as i32).await?;
+
+    Ok(Json(AdminEventListResponse { events, total }))
+}
+
+/// Removes an event for good, whether live or trashed, as the purge job
+/// would: everything attached cascades and a configured webhook still gets
+/// `event.purged`.
+#[utoipa::path(
+    delete,
+    path = "/admin/events/{public_token}",
+    tag = "admin",
+    params(("public_token" = String, Path, description = "Public token")),
+    security(("admin_key" = [])),
+    responses(
+        (status = 204, description = "Event deleted"),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn force_delete_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Extension(actor): Extension<AdminActor>,
+    Path(public_token): Path<String>,
+) -> AppResult<StatusCode> {
+    let now = clock.now();
+    let mut transaction = pool.begin().await?;
+
+    let event_id = sqlx::query_scalar!(
+        "SELECT id FROM events WHERE public_token = $1 FOR UPDATE",
+        public_token
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    webhooks::enqueue_purged(
+        &mut transaction,
+        event_id,
+        json!({ "deleted_at": now }),
+        now,
+    )
+    .await?;
+    sqlx::query!("DELETE FROM events WHERE id = $1", event_id)
+        .execute(&mut *transaction)
+        .await?;
+    transaction.commit().await?;
+
+    log_access(
+        &pool,
+        &actor,
+        "events.delete",
+        &format!("public_token={}", public_token),
+        1,
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Limits in effect and the limiter's decisions so far, as counted by this
+/// process. Carries no client addresses and is not written to
+/// `admin_access_log`.
+#[utoipa::path(
+    get,
+    path = "/admin/rate-limits",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Configured limits and counters", body = AdminRateLimitsResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse)
+    )
+)]
+pub async fn rate_limits(
+    State(config): State<Arc<Config>>,
+    State(counters): State<SharedCounters>,
+) -> Json<AdminRateLimitsResponse> {
+    let route = |group: &str, limit: Option<RateLimit>, counts: &RouteCounts| AdminRouteLimit {
+        group: group.to_string(),
+        requests: limit.map(|limit| limit.requests),
+        window_secs: limit.map(|limit| limit.window.as_secs()),
+        allowed: counts.allowed.load(Ordering::Relaxed),
+        limited: counts.limited.load(Ordering::Relaxed),
+    };
+    let counts = &counters.rate_limit;
+
+    Json(AdminRateLimitsResponse {
+        store: if config.redis_url.is_some() {
+            "redis"
+        } else {
+            "memory"
+        }
+        .to_string(),
+        routes: vec![
+            route("default", config.rate_limit_default, &counts.default),
+            route(
+                "create_event",
+                config.rate_limit_create_event,
+                &counts.create_event,
+            ),
+            route("health", config.rate_limit_health, &counts.health),
+        ],
+        tracked_clients: counts.clients.load(Ordering::Relaxed),
+        max_clients: config.rate_limit_max_clients,
+        evicted_clients: counts.evicted.load(Ordering::Relaxed),
+        submission_limit: config.submission_rate_limit,
+        submission_window_secs: config.submission_rate_window_secs,
+        submissions_limited: counters.submissions_rate_limited.load(Ordering::Relaxed),
+    })
+}
+
+/// Runs the retention steps of the hourly cleanup now: trash expired events,
+/// purge the trash, archive finished events. Runs are recorded under the
+/// usual job names, so they show up in `/admin/jobs`.
+#[utoipa::path(
+    post,
+    path = "/admin/cleanup",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Rows each step affected", body = AdminCleanupResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse)
+    )
+)]
+pub async fn run_cleanup(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Extension(actor): Extension<AdminActor>,
+) -> AppResult<Json<AdminCleanupResponse>> {
+    let clock = clock.as_ref();
+    let trashed = jobs::track(
+        &pool,
+        clock,
+        jobs::TRASH_EXPIRED,
+        cleanup::delete_expired_events(&pool, clock),
+    )
+    .await?;
+    let purged = jobs::track(
+        &pool,
+        clock,
+        jobs::PURGE_TRASHED,
+        cleanup::purge_deleted_events(&pool, clock),
+    )
+    .await?;
+    let archived = jobs::track(
+        &pool,
+        clock,
+        jobs::ARCHIVE_FINISHED,
+        cleanup::archive_finished_events(&pool, clock),
+    )
+    .await?;
+
+    let details = format!(
+        "trashed={} purged={} archived={}",
+        trashed, purged, archived
+    );
+    log_access(
+        &pool,
+        &actor,
+        "cleanup.run",
+        &details,
+        (trashed + purged + archived) as i32,
+    )
+    .await?;
+
+    Ok(Json(AdminCleanupResponse {
+        trashed,
+        purged,
+        archived,
+    }))
+}
+
+/// The business gauges of `/admin/metrics` as JSON, collected fresh, plus
+/// totals that aren't worth a Prometheus series. Counts only; not written to
+/// `admin_access_log`.
+#[utoipa::path(
+    get,
+    path = "/admin/stats",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Aggregate counts across the deployment", body = AdminStatsResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse)
+    )
+)]
+pub async fn stats(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+) -> AppResult<Json<AdminStatsResponse>> {
+    Ok(Json(collect_stats(&pool, clock.as_ref()).await?))
+}
+
+/// Also printed by the `stats` command.
+pub async fn collect_stats(
+    pool: &PgPool,
+    clock: &dyn Clock,
+) -> Result<AdminStatsResponse, sqlx::Error> {
+    let snapshot = metrics::collect(pool, clock).await?;
+    let now = snapshot.collected_at;
+
+    let totals = sqlx::query!(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM events WHERE created_at >= $1) AS "last_24h!",
+            (SELECT COUNT(*) FROM events WHERE created_at >= $2) AS "last_7d!",
+            (SELECT COUNT(*) FROM participants p JOIN events e ON e.id = p.event_id
+             WHERE e.deleted_at IS NULL AND NOT p.is_organizer) AS "participants!",
+            (SELECT COUNT(*) FROM availabilities a
+             JOIN participants p ON p.id = a.participant_id
+             JOIN events e ON e.id = p.event_id
+             WHERE e.deleted_at IS NULL) AS "availabilities!",
+            (SELECT COUNT(*) FROM events
+             WHERE deleted_at IS NULL AND expires_at <= $3::timestamptz + INTERVAL '24 hours') AS "expiring_24h!",
+            (SELECT COUNT(*) FROM events
+             WHERE deleted_at IS NULL AND expires_at <= $3::timestamptz + INTERVAL '7 days') AS "expiring_7d!",
+            (SELECT COUNT(*) FROM users) AS "users!",
+            (SELECT COUNT(*) FROM organizer_accounts) AS "organizer_accounts!"
+        "#,
+        now - chrono::Duration::hours(24),
+        now - chrono::Duration::days(7),
+        now
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(AdminStatsResponse {
+        collected_at: now,
+        events_by_state: snapshot.events_by_state,
+        events_trashed: snapshot.events_trashed,
+        events_at_risk: snapshot.events_at_risk,
+        events_created_last_24h: totals.last_24h,
+        events_created_last_7d: totals.last_7d,
+        participants: totals.participants,
+        availabilities: totals.availabilities,
+        events_expiring_next_24h: totals.expiring_24h,
+        events_expiring_next_7d: totals.expiring_7d,
+        users: totals.users,
+        organizer_accounts: totals.organizer_accounts,
+        outbox_pending: snapshot.outbox_pending,
+        outbox_failed: snapshot.outbox_failed,
+    })
+}
+
+impl AdminStatsResponse {
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Collected at {}\n\nEvents\n",
+            self.collected_at.to_rfc3339()
+        );
+        for (state, count) in &self.events_by_state {
+            out.push_str(&format!("  {:<24}{}\n", state, count));
+        }
+        let lines = [
+            ("trashed", self.events_trashed),
+            ("at risk", self.events_at_risk),
+            ("created in 24h", self.events_created_last_24h),
+            ("created in 7d", self.events_created_last_7d),
+            ("expiring in 24h", self.events_expiring_next_24h),
+            ("expiring in 7d", self.events_expiring_next_7d),
+        ];
+        for (label, count) in lines {
+            out.push_str(&format!("  {:<24}{}\n", label, count));
+        }
+        out.push_str("\nTotals\n");
+        let lines = [
+            ("participants", self.participants),
+            ("availabilities", self.availabilities),
+            ("users", self.users),
+            ("organizer accounts", self.organizer_accounts),
+            ("mail pending", self.outbox_pending),
+            ("mail failed", self.outbox_failed),
+        ];
+        for (label, count) in lines {
+            out.push_str(&format!("  {:<24}{}\n", label, count));
+        }
+        out
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/bans",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Active IP bans", body = AdminBansResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse)
+    )
+)]
+pub async fn list_bans(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Extension(actor): Extension<AdminActor>,
+) -> AppResult<Json<AdminBansResponse>> {
+    let bans = sqlx::query_as!(
+        AdminBan,
+        r#"
+        SELECT ip, reason, banned_by, created_at, expires_at
+        FROM ip_bans
+        WHERE expires_at > $1
+        ORDER BY expires_at, ip
+        "#,
+        clock.now()
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    log_access(&pool, &actor, "bans.list", "", bans.len() as i32).await?;
+
+    Ok(Json(AdminBansResponse { bans }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/bans",
+    tag = "admin",
+    request_body = CreateBanRequest,
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Ban in effect; an existing longer ban is kept", body = AdminBan),
+        (status = 400, description = "Not an IP address", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse)
+    )
+)]
+pub async fn create_ban(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(config): State<Arc<Config>>,
+    State(bans): State<SharedBans>,
+    Extension(actor): Extension<AdminActor>,
+    Json(payload): Json<CreateBanRequest>,
+) -> AppResult<Json<AdminBan>> {
+    let ip: IpAddr = payload
+        .ip
+        .trim()
+        .parse()
+        .map_err(|_| AppError::BadRequest("Invalid IP address".to_string()))?;
+    let reason = payload
+        .reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty())
+        .unwrap_or_else(|| "banned by an operator".to_string());
+    let duration = payload.duration_secs.unwrap_or(config.abuse_ban_secs);
+
+    let now = clock.now();
+    let expires_at = security::ban(
+        &pool,
+        &bans,
+        ip,
+        &reason,
+        &actor.0,
+        now,
+        now + chrono::Duration::seconds(duration as i64),
+    )
+    .await?;
+
+    log_access(
+        &pool,
+        &actor,
+        "bans.create",
+        &format!("ip={} until={}", ip, expires_at),
+        1,
+    )
+    .await?;
+
+    Ok(Json(AdminBan {
+        ip: ip.to_string(),
+        reason,
+        banned_by: actor.0,
+        created_at: now,
+        expires_at,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/bans/{ip}",
+    tag = "admin",
+    params(("ip" = String, Path, description = "Banned address")),
+    security(("admin_key" = [])),
+    responses(
+        (status = 204, description = "Ban lifted"),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse),
+        (status = 404, description = "Address is not banned", body = ErrorResponse)
+    )
+)]
+pub async fn delete_ban(
+    State(pool): State<PgPool>,
+    State(bans): State<SharedBans>,
+    Extension(actor): Extension<AdminActor>,
+    Path(ip): Path<String>,
+) -> AppResult<StatusCode> {
+    let ip: IpAddr = ip.parse().map_err(|_| AppError::NotFound)?;
+    if !security::unban(&pool, &bans, ip).await? {
+        return Err(AppError::NotFound);
+    }
+
+    log_access(&pool, &actor, "bans.delete", &format!("ip={}", ip), 1).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Business gauges, request counters and connection pool gauges for
+/// Prometheus. Business gauges come from the collector task's latest
+/// snapshot; only before its first run is the database queried directly. Scrapes carry no event data and are not
+/// written to `admin_access_log`.
+#[utoipa::path(
+    get,
+    path = "/admin/metrics",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Prometheus text exposition format", body = String, content_type = "text/plain"),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse)
+    )
+)]
+pub async fn business_metrics(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(shared): State<SharedMetrics>,
+    State(counters): State<SharedCounters>,
+) -> AppResult<impl IntoResponse> {
+    let snapshot = shared.read().unwrap().clone();
+    let snapshot = match snapshot {
+        Some(snapshot) => snapshot,
+        None => {
+            let collected = metrics::collect(&pool, clock.as_ref()).await?;
+            *shared.write().unwrap() = Some(collected.clone());
+            collected
+        }
+    };
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render(&snapshot)
+            + &metrics::render_counters(&counters)
+            + &metrics::render_pool(&db::PoolStats::of(&pool)),
+    ))
+}
+
+/// Background task history. Runs carry no event data and are not written to
+/// `admin_access_log`.
+#[utoipa::path(
+    get,
+    path = "/admin/jobs",
+    tag = "admin",
+    params(JobRunsQuery),
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Per-job status and recent runs (max 200)", body = AdminJobsResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse)
+    )
+)]
+pub async fn job_runs(
+    State(pool): State<PgPool>,
+    Query(query): Query<JobRunsQuery>,
+) -> AppResult<Json<AdminJobsResponse>> {
+    let job = query
+        .job
+        .as_deref()
+        .map(str::trim)
+        .filter(|j| !j.is_empty());
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let runs = jobs::recent_runs(&pool, job, query.failed.unwrap_or(false), limit)
+        .await?
+        .into_iter()
+        .map(|run| AdminJobRun {
+            id: run.id,
+            job: run.job,
+            started_at: run.started_at,
+            duration_ms: run.duration_ms,
+            affected_rows: run.affected_rows,
+            error: run.error,
+        })
+        .collect();
+    let jobs = jobs::job_statuses(&pool)
+        .await?
+        .into_iter()
+        .map(|status| AdminJobStatus {
+            job: status.job,
+            last_started_at: status.last_started_at,
+            last_succeeded_at: status.last_succeeded_at,
+            last_error: status.last_error,
+        })
+        .collect();
+
+    Ok(Json(AdminJobsResponse { jobs, runs }))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MailPreviewQuery {
+    /// Defaults to `en`
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminMailPreview {
+    pub notification: String,
+    pub locale: String,
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Renders a notification with sample values through the templates this
+/// server loaded, overrides included. Previews carry no event data and are
+/// not written to `admin_access_log`.
+#[utoipa::path(
+    get,
+    path = "/admin/mail-templates/{notification}/preview",
+    tag = "admin",
+    params(
+        ("notification" = String, Path, description = "`invitation`, `availability_reminder`, `rsvp_reminder`, `results_revealed`, `quorum_closed`, `deletion_notice` or `magic_link`"),
+        MailPreviewQuery
+    ),
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Rendered subject, plain-text and HTML bodies", body = AdminMailPreview),
+        (status = 400, description = "Unsupported locale", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse),
+        (status = 404, description = "Unknown notification", body = ErrorResponse)
+    )
+)]
+pub async fn preview_mail_template(
+    State(templates): State<SharedTemplates>,
+    Path(notification): Path<String>,
+    Query(query): Query<MailPreviewQuery>,
+) -> AppResult<Json<AdminMailPreview>> {
+    let notification = Notification::parse(&notification).ok_or(AppError::NotFound)?;
+    let locale = query.locale.unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+    if !templates.supports(&locale) {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported locale (available: {})",
+            templates.locales().collect::<Vec<_>>().join(", ")
+        )));
+    }
+
+    let rendered = templates.render(notification, &locale, &notification.sample());
+    Ok(Json(AdminMailPreview {
+        notification: notification.as_str().to_string(),
+        locale,
+        subject: rendered.subject,
+        text: rendered.text,
+        html: rendered.html,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("50%_off\\"), "50\\%\\_off\\\\");
+    }
+}
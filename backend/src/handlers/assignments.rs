@@ -0,0 +1,423 @@
+use std::collections::HashSet;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    clock::SharedClock,
+    db::{self, audit},
+    error::{AppError, AppResult, ErrorResponse},
+    handlers::events::{MAX_FINAL_SLOTS, MAX_PARTICIPANTS, lock_event_by_organizer_token},
+    models::{
+        AssignRequest, AssignedParticipant, AssignmentParticipant, AssignmentResponse,
+        AvailabilityLevel, EventState, RoleCoverage, SlotAssignment, UpdateAssignmentRequest,
+    },
+};
+
+struct Participant {
+    id: i64,
+    ranges: agreed_time_core::ParticipantRanges,
+}
+
+/// Participants with their availability split by level, in response order.
+async fn fetch_participants(pool: &PgPool, event_id: Uuid) -> AppResult<Vec<Participant>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT p.id, p.name, p.is_organizer, p.buffer_minutes, p.role,
+               a.start_at AS "start_at?", a.end_at AS "end_at?", a.availability_level AS "availability_level?"
+        FROM participants p
+        LEFT JOIN availabilities a ON p.id = a.participant_id
+        WHERE p.event_id = $1 AND p.status = 'confirmed'
+        ORDER BY p.is_organizer DESC, p.created_at ASC, p.id, a.start_at
+        "#,
+        event_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut participants: Vec<Participant> = Vec::new();
+    for row in rows {
+        if participants.last().map(|p| p.id) != Some(row.id) {
+            participants.push(Participant {
+                id: row.id,
+                ranges: agreed_time_core::ParticipantRanges {
+                    name: row.name,
+                    is_organizer: row.is_organizer,
+                    availabilities: Vec::new(),
+                    if_need_be: Vec::new(),
+                    buffer_minutes: row.buffer_minutes,
+                    role: row.role,
+                },
+            });
+        }
+
+        if let (Some(start), Some(end), Some(level)) =
+            (row.start_at, row.end_at, row.availability_level)
+        {
+            let ranges = &mut participants.last_mut().expect("pushed above").ranges;
+            let range = agreed_time_core::TimeRange::new(start, end);
+            match AvailabilityLevel::from_db(&level) {
+                AvailabilityLevel::Available => ranges.availabilities.push(range),
+                AvailabilityLevel::IfNeedBe => ranges.if_need_be.push(range),
+                AvailabilityLevel::Unavailable => {}
+            }
+        }
+    }
+    Ok(participants)
+}
+
+/// Assignments are only possible once the organizer has committed to final
+/// slots, and only until they confirm.
+async fn ensure_assignable(conn: &mut PgConnection, event_id: Uuid) -> AppResult<()> {
+    let event = sqlx::query!(
+        r#"SELECT state AS "state: EventState", assignment_confirmed_at FROM events WHERE id = $1"#,
+        event_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if event.state != EventState::Finalized {
+        return Err(AppError::Conflict(
+            "Event must be finalized before assigning participants".to_string(),
+        ));
+    }
+    if event.assignment_confirmed_at.is_some() {
+        return Err(AppError::Conflict(
+            "Assignment is already confirmed".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Replaces every assignment row of the event.
+async fn replace_assignments(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    assignments: &[(i64, i64)],
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM slot_assignments
+        WHERE final_slot_id IN (SELECT id FROM final_slots WHERE event_id = $1)
+        "#,
+        event_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    for (final_slot_id, participant_id) in assignments {
+        sqlx::query!(
+            "INSERT INTO slot_assignments (final_slot_id, participant_id) VALUES ($1, $2)",
+            final_slot_id,
+            participant_id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn build_assignment_response(pool: &PgPool, event_id: Uuid) -> AppResult<AssignmentResponse> {
+    let confirmed_at = sqlx::query_scalar!(
+        "SELECT assignment_confirmed_at FROM events WHERE id = $1",
+        event_id
+    )
+    .fetch_one(pool)
+    .await?;
+    let final_slots = db::events::final_slots(pool, event_id).await?;
+    let participants = fetch_participants(pool, event_id).await?;
+    let roles = db::events::roles(pool, event_id).await?;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT sa.final_slot_id, sa.participant_id
+        FROM slot_assignments sa
+        JOIN final_slots fs ON fs.id = sa.final_slot_id
+        WHERE fs.event_id = $1
+        "#,
+        event_id
+    )
+    .fetch_all(pool)
+    .await?;
+    let assigned: HashSet<(i64, i64)> = rows
+        .iter()
+        .map(|row| (row.final_slot_id, row.participant_id))
+        .collect();
+
+    let slots: Vec<SlotAssignment> = final_slots
+        .into_iter()
+        .map(|slot| {
+            let range = agreed_time_core::TimeRange::new(slot.start_at, slot.end_at);
+            let attendees: Vec<AssignedParticipant> = participants
+                .iter()
+                .filter(|p| assigned.contains(&(slot.id, p.id)))
+                .map(|p| AssignedParticipant {
+                    participant_id: p.id,
+                    name: p.ranges.name.clone(),
+                    role: p.ranges.role.clone(),
+                    availability_level: match agreed_time_core::scheduler::fit(&p.ranges, &range) {
+                        Some(agreed_time_core::Fit::Available) => AvailabilityLevel::Available,
+                        Some(agreed_time_core::Fit::IfNeedBe) => AvailabilityLevel::IfNeedBe,
+                        None => AvailabilityLevel::Unavailable,
+                    },
+                })
+                .collect();
+            let roles = roles
+                .iter()
+                .map(|role| RoleCoverage {
+                    role: role.name.clone(),
+                    required: role.required.max(0) as usize,
+                    filled: attendees
+                        .iter()
+                        .filter(|a| a.role.as_deref() == Some(role.name.as_str()))
+                        .map(|a| a.name.clone())
+                        .collect(),
+                })
+                .collect();
+            SlotAssignment {
+                final_slot_id: slot.id,
+                start_at: slot.start_at,
+                end_at: slot.end_at,
+                attendees,
+                roles,
+            }
+        })
+        .collect();
+
+    let unassigned = participants
+        .iter()
+        .filter(|p| !assigned.iter().any(|&(_, id)| id == p.id))
+        .map(|p| AssignmentParticipant {
+            participant_id: p.id,
+            name: p.ranges.name.clone(),
+            role: p.ranges.role.clone(),
+        })
+        .collect();
+
+    Ok(AssignmentResponse {
+        confirmed_at,
+        slots,
+        unassigned,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{organizer_token}/assign",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 200, description = "Current assignment of participants to final slots", body = AssignmentResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_assignment(
+    State(pool): State<PgPool>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<Json<AssignmentResponse>> {
+    let event_id = sqlx::query_scalar!(
+        "SELECT id FROM events WHERE organizer_token = $1 AND deleted_at IS NULL",
+        organizer_token
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound)?;
+
+    Ok(Json(build_assignment_response(&pool, event_id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/assign",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    request_body = AssignRequest,
+    responses(
+        (status = 200, description = "Proposed assignment, replacing any draft", body = AssignmentResponse),
+        (status = 400, description = "Invalid capacity or sessions per participant", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Event not finalized, assignment confirmed, or event archived", body = ErrorResponse)
+    )
+)]
+pub async fn propose_assignment(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Path(organizer_token): Path<String>,
+    Json(payload): Json<AssignRequest>,
+) -> AppResult<Json<AssignmentResponse>> {
+    if let Some(capacity) = payload.capacity
+        && !(1..=MAX_PARTICIPANTS).contains(&(capacity as i64))
+    {
+        return Err(AppError::BadRequest(format!(
+            "Capacity must be between 1 and {}",
+            MAX_PARTICIPANTS
+        )));
+    }
+    let sessions = payload.sessions_per_participant.unwrap_or(1);
+    if !(1..=MAX_FINAL_SLOTS as i32).contains(&sessions) {
+        return Err(AppError::BadRequest(format!(
+            "Sessions per participant must be between 1 and {}",
+            MAX_FINAL_SLOTS
+        )));
+    }
+
+    let mut transaction = pool.begin().await?;
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+    ensure_assignable(&mut transaction, event_id).await?;
+
+    let final_slots = db::events::final_slots(&pool, event_id).await?;
+    let participants = fetch_participants(&pool, event_id).await?;
+    let requirements: Vec<agreed_time_core::RoleRequirement> = db::events::roles(&pool, event_id)
+        .await?
+        .into_iter()
+        .map(|role| agreed_time_core::RoleRequirement {
+            role: role.name,
+            required: role.required.max(0) as usize,
+        })
+        .collect();
+
+    let ranges: Vec<agreed_time_core::ParticipantRanges> =
+        participants.iter().map(|p| p.ranges.clone()).collect();
+    let slots: Vec<agreed_time_core::TimeRange> = final_slots
+        .iter()
+        .map(|slot| agreed_time_core::TimeRange::new(slot.start_at, slot.end_at))
+        .collect();
+    let proposal = agreed_time_core::assign(
+        &ranges,
+        &slots,
+        &requirements,
+        &agreed_time_core::AssignmentOptions {
+            capacity: payload.capacity.map(|c| c as usize),
+            sessions_per_participant: sessions as usize,
+        },
+    );
+
+    let assignments: Vec<(i64, i64)> = final_slots
+        .iter()
+        .zip(&proposal)
+        .flat_map(|(slot, attendees)| {
+            attendees
+                .iter()
+                .map(|&index| (slot.id, participants[index].id))
+        })
+        .collect();
+    replace_assignments(&mut transaction, event_id, &assignments).await?;
+
+    audit::record(
+        &mut transaction,
+        event_id,
+        "assignment_proposed",
+        "organizer",
+        None,
+        clock.now(),
+    )
+    .await?;
+    transaction.commit().await?;
+
+    Ok(Json(build_assignment_response(&pool, event_id).await?))
+}
+
+#[utoipa::path(
+    put,
+    path = "/events/{organizer_token}/assign",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    request_body = UpdateAssignmentRequest,
+    responses(
+        (status = 200, description = "Assignment saved, and confirmed if requested", body = AssignmentResponse),
+        (status = 400, description = "Unknown or repeated final slot or participant", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Event not finalized, assignment confirmed, or event archived", body = ErrorResponse)
+    )
+)]
+pub async fn update_assignment(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Path(organizer_token): Path<String>,
+    Json(payload): Json<UpdateAssignmentRequest>,
+) -> AppResult<Json<AssignmentResponse>> {
+    let mut transaction = pool.begin().await?;
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+    ensure_assignable(&mut transaction, event_id).await?;
+
+    let final_slot_ids: HashSet<i64> = db::events::final_slots(&pool, event_id)
+        .await?
+        .iter()
+        .map(|slot| slot.id)
+        .collect();
+    let participant_ids: HashSet<i64> = sqlx::query_scalar!(
+        "SELECT id FROM participants WHERE event_id = $1 AND status = 'confirmed'",
+        event_id
+    )
+    .fetch_all(&mut *transaction)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut seen_slots = HashSet::new();
+    let mut assignments = Vec::new();
+    for slot in &payload.slots {
+        if !final_slot_ids.contains(&slot.final_slot_id) {
+            return Err(AppError::BadRequest(format!(
+                "Unknown final slot {}",
+                slot.final_slot_id
+            )));
+        }
+        if !seen_slots.insert(slot.final_slot_id) {
+            return Err(AppError::BadRequest(format!(
+                "Final slot {} is listed more than once",
+                slot.final_slot_id
+            )));
+        }
+        let mut seen_participants = HashSet::new();
+        for &participant_id in &slot.participant_ids {
+            if !participant_ids.contains(&participant_id) {
+                return Err(AppError::BadRequest(format!(
+                    "Unknown participant {}",
+                    participant_id
+                )));
+            }
+            if !seen_participants.insert(participant_id) {
+                return Err(AppError::BadRequest(format!(
+                    "Participant {} is listed twice for final slot {}",
+                    participant_id, slot.final_slot_id
+                )));
+            }
+            assignments.push((slot.final_slot_id, participant_id));
+        }
+    }
+
+    replace_assignments(&mut transaction, event_id, &assignments).await?;
+
+    let now = clock.now();
+    if payload.confirm {
+        sqlx::query!(
+            "UPDATE events SET assignment_confirmed_at = $1, updated_at = $1 WHERE id = $2",
+            now,
+            event_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+
+    audit::record(
+        &mut transaction,
+        event_id,
+        if payload.confirm {
+            "assignment_confirmed"
+        } else {
+            "assignment_updated"
+        },
+        "organizer",
+        None,
+        now,
+    )
+    .await?;
+    transaction.commit().await?;
+
+    Ok(Json(build_assignment_response(&pool, event_id).await?))
+}
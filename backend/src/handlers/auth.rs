@@ -0,0 +1,242 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, HeaderName, StatusCode, header::SET_COOKIE},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    auth::{self, CurrentUser},
+    clock::SharedClock,
+    config::Config,
+    error::{AppError, AppResult, ErrorResponse},
+    handlers::{
+        events::{validate_email, validate_locale},
+        organizers::{self, OrganizerEventsQuery, OrganizerEventsResponse},
+    },
+    integrations::{
+        mail,
+        templates::{DEFAULT_LOCALE, Notification, Params, SharedTemplates},
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MagicLinkRequest {
+    pub email: String,
+    /// Language of the login mail; defaults to English
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct VerifyQuery {
+    /// Token from the mailed link
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserResponse {
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TokenResponse {
+    /// HS256 JWT for `Authorization: Bearer`
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<auth::User> for UserResponse {
+    fn from(user: auth::User) -> Self {
+        UserResponse {
+            email: user.email,
+            created_at: user.created_at,
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/magic-link",
+    tag = "auth",
+    request_body = MagicLinkRequest,
+    responses(
+        (status = 202, description = "Login link mailed, unless the address already holds several unused ones"),
+        (status = 400, description = "Invalid address or locale", body = ErrorResponse),
+        (status = 404, description = "Login is disabled (`MAIL_FROM` or `PUBLIC_URL` unset)", body = ErrorResponse)
+    )
+)]
+pub async fn request_magic_link(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(config): State<Arc<Config>>,
+    State(templates): State<SharedTemplates>,
+    Json(payload): Json<MagicLinkRequest>,
+) -> AppResult<StatusCode> {
+    let (Some(_), Some(public_url)) = (&config.mail_from, &config.public_url) else {
+        return Err(AppError::NotFound);
+    };
+    let email = payload.email.trim().to_lowercase();
+    validate_email(&Some(email.clone()))?;
+    validate_locale(&templates, &payload.locale)?;
+
+    let now = clock.now();
+    let mut transaction = pool.begin().await?;
+    // The answer is the same either way, so it doesn't reveal how many
+    // links are pending for an address
+    if let Some(token) = auth::create_magic_link(&mut transaction, &email, now).await? {
+        let rendered = templates.render(
+            Notification::MagicLink,
+            payload.locale.as_deref().unwrap_or(DEFAULT_LOCALE),
+            &Params::new()
+                .text(
+                    "link",
+                    format!("{}/auth/verify?token={}", public_url, token),
+                )
+                .text("minutes", auth::MAGIC_LINK_TTL_MINUTES.to_string()),
+        );
+        mail::enqueue(
+            &mut transaction,
+            mail::NewMail {
+                event_id: None,
+                participant_id: None,
+                recipient: &email,
+                subject: &rendered.subject,
+                body: &rendered.text,
+                html: Some(&rendered.html),
+                calendar: None,
+            },
+        )
+        .await?;
+    }
+    transaction.commit().await?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/verify",
+    tag = "auth",
+    params(VerifyQuery),
+    responses(
+        (status = 200, description = "Logged in; the session cookie is set", body = UserResponse),
+        (status = 401, description = "Link unknown, already used or expired", body = ErrorResponse)
+    )
+)]
+pub async fn verify_magic_link(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Query(query): Query<VerifyQuery>,
+) -> AppResult<([(HeaderName, String); 1], Json<UserResponse>)> {
+    let mut transaction = pool.begin().await?;
+    let (user, session) = auth::redeem_magic_link(&mut transaction, &query.token, clock.now())
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+    transaction.commit().await?;
+
+    Ok((
+        [(SET_COOKIE, auth::session_cookie(&session))],
+        Json(user.into()),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/me",
+    tag = "auth",
+    responses(
+        (status = 200, description = "The logged-in user", body = UserResponse),
+        (status = 401, description = "No live session or bearer token", body = ErrorResponse)
+    )
+)]
+pub async fn get_current_user(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    bearer: Option<CurrentUser>,
+    headers: HeaderMap,
+) -> AppResult<Json<UserResponse>> {
+    let mut conn = pool.acquire().await?;
+    let user = auth::request_user(&mut conn, bearer.as_ref(), &headers, clock.now())
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+    Ok(Json(user.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    tag = "auth",
+    responses((status = 204, description = "Session ended and cookie cleared"))
+)]
+pub async fn logout(
+    State(pool): State<PgPool>,
+    headers: HeaderMap,
+) -> AppResult<([(HeaderName, String); 1], StatusCode)> {
+    if let Some(token) = auth::session_token(&headers) {
+        let mut conn = pool.acquire().await?;
+        auth::end_session(&mut conn, token).await?;
+    }
+    Ok((
+        [(SET_COOKIE, auth::cleared_session_cookie())],
+        StatusCode::NO_CONTENT,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/token",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Bearer token for the session's user", body = TokenResponse),
+        (status = 401, description = "No live session; a bearer token can't renew itself", body = ErrorResponse),
+        (status = 404, description = "Bearer tokens are disabled (`JWT_SECRET` unset)", body = ErrorResponse)
+    )
+)]
+pub async fn issue_token(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(config): State<Arc<Config>>,
+    headers: HeaderMap,
+) -> AppResult<Json<TokenResponse>> {
+    let secret = config.jwt_secret.as_deref().ok_or(AppError::NotFound)?;
+    let now = clock.now();
+    let mut conn = pool.acquire().await?;
+    // Only a session mints tokens, so a leaked token can't outlive its expiry
+    let user = auth::session_user(&mut conn, &headers, now)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+    let (token, expires_at) = auth::issue_jwt(secret, user.id, now);
+    Ok(Json(TokenResponse { token, expires_at }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/events",
+    tag = "auth",
+    params(OrganizerEventsQuery),
+    responses(
+        (status = 200, description = "Events created while logged in", body = OrganizerEventsResponse),
+        (status = 401, description = "No live session or bearer token", body = ErrorResponse)
+    )
+)]
+pub async fn list_user_events(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    bearer: Option<CurrentUser>,
+    headers: HeaderMap,
+    Query(query): Query<OrganizerEventsQuery>,
+) -> AppResult<Json<OrganizerEventsResponse>> {
+    let mut conn = pool.acquire().await?;
+    let user = auth::request_user(&mut conn, bearer.as_ref(), &headers, clock.now())
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+    drop(conn);
+    Ok(Json(
+        organizers::event_page(&pool, None, Some(user.id), &query).await?,
+    ))
+}
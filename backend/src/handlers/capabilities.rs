@@ -0,0 +1,160 @@
+use axum::{Json, extract::State};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::{
+    config::{CaldavImport, Config},
+    db::cleanup::{ARCHIVE_AFTER_DAYS, DEMO_RETENTION_HOURS, TRASH_RETENTION_DAYS},
+    handlers::events::{
+        EDIT_LOCK_TTL_SECONDS, MAX_BATCH_SUBMISSIONS, MAX_BATCH_TOKENS, MAX_BUFFER_MINUTES,
+        MAX_FINAL_SLOTS, MAX_INVITEES, MAX_PARTICIPANTS, MAX_REMINDERS_PER_DAY, MAX_ROLES,
+        MAX_SUGGESTIONS, MAX_WAITLIST, TRANSFER_CLAIM_TTL_HOURS,
+    },
+    validation::{
+        MAX_COMMENT_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_NAME_LENGTH, MAX_RANGE_SPAN_DAYS,
+        MAX_TIME_RANGES, MAX_TITLE_LENGTH,
+    },
+};
+
+/// Bumped on breaking API changes; clients compare against `api_versions`.
+pub const API_VERSION: &str = "1";
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CapabilitiesResponse {
+    /// API versions this deployment serves, oldest first
+    pub api_versions: Vec<String>,
+    /// Backend build version
+    pub server_version: String,
+    /// Supported poll kinds; `time_ranges` is free-form availability over candidate windows
+    pub poll_types: Vec<String>,
+    pub limits: Limits,
+    pub integrations: Integrations,
+    /// Features scheduled for removal; empty when nothing is deprecated
+    pub deprecations: Vec<Deprecation>,
+    /// Public sandbox: events are deleted `demo_retention_hours` after
+    /// creation and can't be extended; mail and webhooks are off
+    pub demo_mode: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Limits {
+    pub max_participants: i64,
+    /// Submissions past `max_participants` held for organizer approval
+    pub max_waitlist: i64,
+    pub max_title_length: usize,
+    pub max_description_length: usize,
+    pub max_name_length: usize,
+    pub max_comment_length: usize,
+    /// Candidate slots of an event, or ranges in one submission
+    pub max_time_ranges: usize,
+    /// Earliest start to latest end of those ranges
+    pub max_range_span_days: i64,
+    pub max_buffer_minutes: i32,
+    pub max_final_slots: usize,
+    pub max_batch_tokens: usize,
+    /// Entries of one `POST /events/{public_token}/availability/batch`
+    pub max_batch_submissions: usize,
+    pub max_suggestions: usize,
+    pub max_reminders_per_day: i64,
+    /// Addresses `POST /events` takes in `invitees`
+    pub max_invitees: usize,
+    /// Roles an event may require per window
+    pub max_roles: usize,
+    /// Retention of events created without `retention_days`
+    pub retention_days: i64,
+    /// Longest `retention_days` an event may ask for (`MAX_RETENTION_DAYS`)
+    pub max_retention_days: i64,
+    /// Days a deleted event stays restorable before it is purged
+    pub trash_retention_days: i64,
+    pub archive_after_days: i64,
+    pub edit_lock_ttl_seconds: i64,
+    pub transfer_claim_ttl_hours: i64,
+    pub demo_retention_hours: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Integrations {
+    pub ics_export: bool,
+    /// Finalizing sends iTIP invitations (`MAIL_FROM` set)
+    pub email_invitations: bool,
+    /// `POST /integrations/itip/reply` is available
+    pub itip_replies: bool,
+    /// `/admin/*` is enabled (`ADMIN_API_KEY` set)
+    pub admin_api: bool,
+    /// `/auth/*` magic-link login is available (`MAIL_FROM` and `PUBLIC_URL` set)
+    pub email_login: bool,
+    /// `hcaptcha` or `turnstile` when creating events and submitting
+    /// availability need a `captcha_token`
+    pub captcha: Option<String>,
+    /// `POST /events/{public_token}/caldav/freebusy` is available
+    /// (`CALDAV_IMPORT` set)
+    pub caldav_import: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Deprecation {
+    /// Endpoint or field, e.g. `POST /events/batch-check`
+    pub feature: String,
+    pub replacement: Option<String>,
+    /// Date after which the feature may be removed
+    pub sunset: Option<NaiveDate>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/capabilities",
+    tag = "health",
+    responses((status = 200, description = "Features and limits of this deployment", body = CapabilitiesResponse))
+)]
+pub async fn get_capabilities(State(config): State<Arc<Config>>) -> Json<CapabilitiesResponse> {
+    let admin_api = config.admin_api_key.is_some();
+    let email_invitations = config.mail_from.is_some();
+
+    Json(CapabilitiesResponse {
+        api_versions: vec![API_VERSION.to_string()],
+        server_version: env!("CARGO_PKG_VERSION").to_string(),
+        poll_types: vec!["time_ranges".to_string()],
+        limits: Limits {
+            max_participants: MAX_PARTICIPANTS,
+            max_waitlist: MAX_WAITLIST,
+            max_title_length: MAX_TITLE_LENGTH,
+            max_description_length: MAX_DESCRIPTION_LENGTH,
+            max_name_length: MAX_NAME_LENGTH,
+            max_comment_length: MAX_COMMENT_LENGTH,
+            max_time_ranges: MAX_TIME_RANGES,
+            max_range_span_days: MAX_RANGE_SPAN_DAYS,
+            max_buffer_minutes: MAX_BUFFER_MINUTES,
+            max_final_slots: MAX_FINAL_SLOTS,
+            max_batch_tokens: MAX_BATCH_TOKENS,
+            max_batch_submissions: MAX_BATCH_SUBMISSIONS,
+            max_suggestions: MAX_SUGGESTIONS,
+            max_reminders_per_day: MAX_REMINDERS_PER_DAY,
+            max_invitees: MAX_INVITEES,
+            max_roles: MAX_ROLES,
+            retention_days: config.default_retention_days(),
+            max_retention_days: config.max_retention_days,
+            trash_retention_days: TRASH_RETENTION_DAYS,
+            archive_after_days: ARCHIVE_AFTER_DAYS,
+            edit_lock_ttl_seconds: EDIT_LOCK_TTL_SECONDS,
+            transfer_claim_ttl_hours: TRANSFER_CLAIM_TTL_HOURS,
+            demo_retention_hours: DEMO_RETENTION_HOURS,
+        },
+        integrations: Integrations {
+            ics_export: true,
+            email_invitations,
+            // Replies arrive at the MAIL_FROM mailbox and are fed in with the admin key
+            itip_replies: admin_api && email_invitations,
+            admin_api,
+            email_login: email_invitations && config.public_url.is_some(),
+            captcha: config
+                .captcha_provider
+                .filter(|_| config.captcha_secret.is_some())
+                .map(|provider| provider.name().to_string()),
+            caldav_import: config.caldav_import != CaldavImport::Off,
+        },
+        deprecations: Vec::new(),
+        demo_mode: config.demo_mode,
+    })
+}
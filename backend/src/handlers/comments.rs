@@ -0,0 +1,165 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+};
+use sqlx::PgPool;
+
+use crate::{
+    clock::SharedClock,
+    encryption::SharedKeyring,
+    error::{AppError, AppResult, ErrorResponse},
+    handlers::events::{ensure_not_archived, ensure_password, password_header},
+    models::{
+        CommentListResponse, CommentsQuery, CreateCommentRequest, EventComment, EventState,
+        ParticipantStatus,
+    },
+    realtime::{RealtimeMessage, SharedHub},
+    validation::{MAX_COMMENT_LENGTH, Validator},
+};
+
+#[utoipa::path(
+    post,
+    path = "/events/{public_token}/comments",
+    tag = "events",
+    params(
+        ("public_token" = String, Path, description = "Public event token"),
+        ("X-Event-Password" = Option<String>, Header, description = "Required when the event has a password")
+    ),
+    request_body = CreateCommentRequest,
+    responses(
+        (status = 200, description = "Comment posted", body = EventComment),
+        (status = 400, description = "Empty or too long comment", body = ErrorResponse),
+        (status = 403, description = "Event password missing or wrong", body = ErrorResponse),
+        (status = 404, description = "Event or participant not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived, or the participant is still on the waitlist", body = ErrorResponse)
+    )
+)]
+pub async fn create_comment(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(keys): State<SharedKeyring>,
+    State(hub): State<SharedHub>,
+    Path(public_token): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateCommentRequest>,
+) -> AppResult<Json<EventComment>> {
+    let mut validator = Validator::new();
+    validator.required_text("body", "Comment", &payload.body, MAX_COMMENT_LENGTH);
+    validator.finish()?;
+
+    let event = sqlx::query!(
+        r#"SELECT id, state AS "state: EventState", password_hash FROM events WHERE public_token = $1 AND deleted_at IS NULL"#,
+        public_token
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    ensure_password(event.password_hash.as_deref(), password_header(&headers))?;
+    ensure_not_archived(event.state)?;
+
+    let author = sqlx::query!(
+        "SELECT id, name, is_organizer, status FROM participants WHERE token = $1 AND event_id = $2",
+        payload.participant_token,
+        event.id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    // Waitlisted names stay hidden until the organizer lets them in
+    if ParticipantStatus::from_db(&author.status) == ParticipantStatus::Pending {
+        return Err(AppError::Conflict(
+            "Waitlisted participants can comment once approved".to_string(),
+        ));
+    }
+
+    let body = payload.body.trim();
+    let comment = sqlx::query!(
+        r#"
+        INSERT INTO event_comments (event_id, participant_id, body, created_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, created_at
+        "#,
+        event.id,
+        author.id,
+        keys.seal(body),
+        clock.now()
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let comment = EventComment {
+        id: comment.id,
+        author_name: author.name,
+        is_organizer: author.is_organizer,
+        body: body.to_string(),
+        created_at: comment.created_at,
+    };
+    hub.publish(event.id, RealtimeMessage::Comment(comment.clone()));
+
+    Ok(Json(comment))
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{public_token}/comments",
+    tag = "events",
+    params(
+        ("public_token" = String, Path, description = "Public event token"),
+        CommentsQuery,
+        ("X-Event-Password" = Option<String>, Header, description = "Required when the event has a password")
+    ),
+    responses(
+        (status = 200, description = "One page of the discussion, oldest first", body = CommentListResponse),
+        (status = 403, description = "Event password missing or wrong", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn list_comments(
+    State(pool): State<PgPool>,
+    State(keys): State<SharedKeyring>,
+    Path(public_token): Path<String>,
+    Query(query): Query<CommentsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<CommentListResponse>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let event = sqlx::query!(
+        "SELECT id, password_hash FROM events WHERE public_token = $1 AND deleted_at IS NULL",
+        public_token
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    ensure_password(event.password_hash.as_deref(), password_header(&headers))?;
+
+    let total = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM event_comments WHERE event_id = $1"#,
+        event.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let mut comments = sqlx::query_as!(
+        EventComment,
+        r#"
+        SELECT c.id, p.name AS author_name, p.is_organizer, c.body, c.created_at
+        FROM event_comments c
+        JOIN participants p ON p.id = c.participant_id
+        WHERE c.event_id = $1
+        ORDER BY c.id
+        LIMIT $2 OFFSET $3
+        "#,
+        event.id,
+        limit,
+        offset
+    )
+    .fetch_all(&pool)
+    .await?;
+    for comment in &mut comments {
+        comment.body = keys.open(&comment.body)?;
+    }
+
+    Ok(Json(CommentListResponse { comments, total }))
+}
@@ -0,0 +1,82 @@
+use axum::{Extension, Json, extract::State};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::{
+    config::Config,
+    error::{AppError, AppResult, ErrorResponse},
+    handlers::admin,
+    middleware::AdminActor,
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DemoResetResponse {
+    /// Events removed, trashed ones included
+    pub events: u64,
+    pub users: u64,
+    pub organizer_accounts: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/demo/reset",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Every event, account and queued message removed", body = DemoResetResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse),
+        (status = 404, description = "Not a demo deployment", body = ErrorResponse)
+    )
+)]
+pub async fn reset_demo(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    Extension(actor): Extension<AdminActor>,
+) -> AppResult<Json<DemoResetResponse>> {
+    // Deliberately unreachable outside the sandbox, admin key or not
+    if !config.demo_mode {
+        return Err(AppError::NotFound);
+    }
+
+    let mut transaction = pool.begin().await?;
+    // Participants, slots, logs and event-bound outbox rows cascade
+    let events = sqlx::query!("DELETE FROM events")
+        .execute(&mut *transaction)
+        .await?
+        .rows_affected();
+    let users = sqlx::query!("DELETE FROM users")
+        .execute(&mut *transaction)
+        .await?
+        .rows_affected();
+    let organizer_accounts = sqlx::query!("DELETE FROM organizer_accounts")
+        .execute(&mut *transaction)
+        .await?
+        .rows_affected();
+    sqlx::query!("DELETE FROM magic_links")
+        .execute(&mut *transaction)
+        .await?;
+    sqlx::query!("DELETE FROM email_outbox")
+        .execute(&mut *transaction)
+        .await?;
+    sqlx::query!("DELETE FROM webhook_deliveries")
+        .execute(&mut *transaction)
+        .await?;
+    transaction.commit().await?;
+
+    admin::log_access(
+        &pool,
+        &actor,
+        "demo_reset",
+        &format!("users={}, organizer_accounts={}", users, organizer_accounts),
+        events as i32,
+    )
+    .await?;
+
+    Ok(Json(DemoResetResponse {
+        events,
+        users,
+        organizer_accounts,
+    }))
+}
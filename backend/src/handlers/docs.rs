@@ -0,0 +1,44 @@
+use std::sync::LazyLock;
+
+use axum::{
+    Json,
+    response::{Html, IntoResponse},
+};
+use utoipa::OpenApi;
+
+use crate::openapi::ApiDoc;
+
+/// Pinned so the UI cannot change under integrators between deploys.
+const SWAGGER_UI_VERSION: &str = "5.17.14";
+
+static SPEC: LazyLock<serde_json::Value> =
+    LazyLock::new(|| serde_json::to_value(ApiDoc::openapi()).expect("OpenAPI document serializes"));
+
+/// The OpenAPI 3.1 document generated from the handler annotations.
+pub async fn openapi_spec() -> impl IntoResponse {
+    Json(SPEC.clone())
+}
+
+/// Swagger UI for [`openapi_spec`]. The assets come from the jsDelivr CDN so
+/// the binary doesn't have to bundle them.
+pub async fn swagger_ui() -> Html<String> {
+    Html(format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>AgreedTime API</title>
+  <link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/swagger-ui-dist@{version}/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://cdn.jsdelivr.net/npm/swagger-ui-dist@{version}/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({{ url: "/openapi.json", dom_id: "#swagger-ui" }});
+  </script>
+</body>
+</html>
+"##,
+        version = SWAGGER_UI_VERSION
+    ))
+}
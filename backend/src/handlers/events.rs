@@ -1,175 +1,1015 @@
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use serde_json::json;
+use sqlx::{Acquire, PgConnection, PgPool};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    sync::Arc,
 };
-use chrono::{DateTime, Utc};
-use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::{
-    error::{AppError, AppResult},
+    auth::{self, CurrentUser},
+    cache::{ResponseCache, SharedResponseCache},
+    captcha,
+    clock::SharedClock,
+    config::Config,
+    db::{
+        self, EventStore, audit, cleanup,
+        events::{EventDetails, PublicEvent, SubmissionTarget},
+        history,
+        participants::ConfirmedParticipant,
+        quorum,
+        verification::{self, Verifier},
+    },
+    encryption::{Keyring, SharedKeyring},
+    error::{AppError, AppResult, ErrorResponse},
+    etag,
+    handlers::organizers,
+    ics,
+    integrations::{
+        itip, mail, notifications,
+        outbound::{self, OutboundError},
+        templates::{DEFAULT_LOCALE, Notification, Params, SharedTemplates, Templates},
+        webhooks::{self, WebhookEvent},
+    },
+    middleware::constant_time_eq,
     models::{
-        BatchCheckStatusRequest, BatchCheckStatusResponse, CreateEventRequest, CreateEventResponse,
-        Event, EventResponse, EventResultsResponse, EventSlot, OrganizerEventResponse,
-        ParticipantAvailability, ParticipantResponse, SubmitAvailabilityRequest,
-        SubmitAvailabilityResponse, TimeRangeRequest, UpdateParticipantRequest,
+        AcquireEditLockRequest, AggregatedParticipation, ArchiveEventResponse, AuditLogResponse,
+        AvailabilityHistoryResponse, AvailabilityLevel, AvailabilityOverlap,
+        BatchCheckParticipantsRequest, BatchCheckParticipantsResponse, BatchCheckStatusRequest,
+        BatchCheckStatusResponse, BatchSubmissionResult, BatchSubmitAvailabilityResponse,
+        CandidateConflicts, ClaimOwnershipRequest, ClaimOwnershipResponse, CloneEventRequest,
+        ConflictCheckRequest, ConflictCheckResponse, ConflictingTime, CreateEventRequest,
+        CreateEventResponse, CreationTicketResponse, DateVotes, DisplayQuery, DisplayTimes,
+        EditLock, EmailVerifiedResponse, Event, EventConflicts, EventResponse,
+        EventResultsResponse, EventRole, EventSlot, EventState, EventSuggestionsResponse,
+        EventSummary, ExtendEventRequest, FinalSlot, FinalizationRules, FinalizeEventRequest,
+        FinalizeEventResponse, InvitationDelivery, InvitationListResponse, InvitationStatus,
+        LocalRange, ModerateParticipantRequest, ModerationDecision, OrganizerEventResponse,
+        ParticipantAggregateQuery, ParticipantAggregateResponse, ParticipantAvailability,
+        ParticipantDisplay, ParticipantOrderRequest, ParticipantOrderResponse, ParticipantResponse,
+        ParticipantStatus, ParticipantTokenStatus, PollType, RemindParticipantsResponse,
+        ReminderLogResponse, ResultsQuery, ResultsVisibility, SlotCount, SlotKind, SlotLocalDates,
+        SlotSuggestion, SubmitAvailabilityRequest, SubmitAvailabilityResponse, SuggestionsQuery,
+        SummaryFormat, SummaryQuery, TimeRangeRequest, TransferOwnershipResponse,
+        UpdateEventRequest, UpdateParticipantRequest, UpdateSlotRequest, VerifyEmailQuery,
+        WaitlistResponse, WeeklySlot,
+    },
+    passwords,
+    realtime::{RealtimeMessage, SharedHub},
+    summary, tickets, tokens,
+    validation::{
+        MAX_COMMENT_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_NAME_LENGTH, MAX_RANGE_SPAN_DAYS,
+        MAX_TITLE_LENGTH, Validator,
     },
 };
 
-fn generate_token() -> String {
-    Uuid::new_v4().to_string()
+/// Limits below are also advertised by `GET /capabilities`.
+/// Participants per event, organizer included.
+pub(crate) const MAX_PARTICIPANTS: i64 = 10;
+/// Pending submissions once `MAX_PARTICIPANTS` are confirmed.
+pub(crate) const MAX_WAITLIST: i64 = 10;
+pub(crate) const MAX_BUFFER_MINUTES: i32 = 240;
+pub(crate) const MAX_FINAL_SLOTS: usize = 20;
+pub(crate) const MAX_BATCH_TOKENS: usize = 50;
+/// Entries of one `POST /events/{public_token}/availability/batch`: a full
+/// event plus its waitlist.
+pub(crate) const MAX_BATCH_SUBMISSIONS: usize = (MAX_PARTICIPANTS + MAX_WAITLIST) as usize;
+pub(crate) const MAX_SUGGESTIONS: usize = 20;
+pub(crate) const MAX_ROLES: usize = 10;
+/// Organizer-triggered reminder batches per event in any rolling 24 hours.
+pub(crate) const MAX_REMINDERS_PER_DAY: i64 = 1;
+/// Registered invitees per event; more could never all get a seat.
+pub(crate) const MAX_INVITEES: usize = (MAX_PARTICIPANTS + MAX_WAITLIST) as usize;
+/// Stands in for participant names in the public results of
+/// `anonymous_results` events.
+const ANONYMOUS_NAME: &str = "Anonymous";
+
+/// Archived events are read-only; every mutation checks this first.
+pub(crate) fn ensure_not_archived(state: EventState) -> AppResult<()> {
+    if state == EventState::Archived {
+        return Err(AppError::Archived);
+    }
+    Ok(())
 }
 
-fn merge_time_ranges(mut ranges: Vec<TimeRangeRequest>) -> Vec<TimeRangeRequest> {
-    if ranges.is_empty() {
-        return vec![];
+/// Passphrase-protected events reject participants without the right
+/// password; events without one accept anything.
+pub(crate) fn ensure_password(
+    password_hash: Option<&str>,
+    password: Option<&str>,
+) -> AppResult<()> {
+    match password_hash {
+        Some(hash) if !password.is_some_and(|password| passwords::verify(password, hash)) => {
+            Err(AppError::PasswordRequired)
+        }
+        _ => Ok(()),
     }
+}
+
+pub(crate) fn password_header(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(passwords::HEADER)
+        .and_then(|value| value.to_str().ok())
+}
 
-    ranges.sort_by(|a, b| a.start_at.cmp(&b.start_at));
+fn request_origin(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::ORIGIN)
+        .and_then(|value| value.to_str().ok())
+}
 
-    let mut merged = Vec::new();
-    let mut current = ranges[0].clone();
+/// Names the IANA zone event responses should render `display` times in.
+pub const DISPLAY_TIMEZONE_HEADER: &str = "x-display-timezone";
 
-    for next in ranges.into_iter().skip(1) {
-        if next.start_at <= current.end_at {
-            if next.end_at > current.end_at {
-                current.end_at = next.end_at;
-            }
-        } else {
-            merged.push(current);
-            current = next;
-        }
+/// Zone from `?tz=`, else `X-Display-Timezone`; unknown names are rejected
+/// rather than silently answered in UTC.
+fn display_time_zone(tz: Option<&str>, headers: &HeaderMap) -> AppResult<Option<String>> {
+    let header = headers
+        .get(DISPLAY_TIMEZONE_HEADER)
+        .map(|value| value.to_str().unwrap_or_default());
+    let Some(requested) = tz.or(header) else {
+        return Ok(None);
+    };
+    match agreed_time_core::normalize_time_zone(requested) {
+        Some(zone) => Ok(Some(zone.to_string())),
+        None => Err(AppError::BadRequest(
+            "tz and X-Display-Timezone must be an IANA time zone name".to_string(),
+        )),
     }
-    merged.push(current);
+}
+
+/// `start_at..end_at` on the wall clock of a zone checked by
+/// `display_time_zone`.
+fn local_range(start_at: DateTime<Utc>, end_at: DateTime<Utc>, time_zone: &str) -> LocalRange {
+    let local = |at: DateTime<Utc>| {
+        let offset = agreed_time_core::utc_offset_seconds(time_zone, at)
+            .and_then(FixedOffset::east_opt)
+            .unwrap_or(FixedOffset::east_opt(0).expect("zero offset is valid"));
+        at.with_timezone(&offset)
+    };
+    LocalRange {
+        start_at: local(start_at),
+        end_at: local(end_at),
+        label: agreed_time_core::format_range(
+            &agreed_time_core::TimeRange::new(start_at, end_at),
+            time_zone,
+        )
+        .unwrap_or_default(),
+    }
+}
+
+fn display_times(
+    time_zone: String,
+    event_slots: &[EventSlot],
+    final_slots: &[FinalSlot],
+    reveal_results_at: Option<DateTime<Utc>>,
+    expires_at: DateTime<Utc>,
+) -> DisplayTimes {
+    // The zone was validated by `display_time_zone`, so formatting can't fail
+    let range = |start_at, end_at| {
+        agreed_time_core::format_range(
+            &agreed_time_core::TimeRange::new(start_at, end_at),
+            &time_zone,
+        )
+        .unwrap_or_default()
+    };
+    let instant = |at| agreed_time_core::format_instant(at, &time_zone).unwrap_or_default();
+
+    DisplayTimes {
+        event_slot_ranges: event_slots
+            .iter()
+            .map(|slot| local_range(slot.start_at, slot.end_at, &time_zone))
+            .collect(),
+        final_slot_ranges: final_slots
+            .iter()
+            .map(|slot| local_range(slot.start_at, slot.end_at, &time_zone))
+            .collect(),
+        event_slots: event_slots
+            .iter()
+            .map(|slot| range(slot.start_at, slot.end_at))
+            .collect(),
+        final_slots: final_slots
+            .iter()
+            .map(|slot| range(slot.start_at, slot.end_at))
+            .collect(),
+        reveal_results_at: reveal_results_at.map(instant),
+        expires_at: instant(expires_at),
+        time_zone,
+    }
+}
+
+/// Locks the event row for the rest of the transaction and rejects archived events.
+pub(crate) async fn lock_event_by_organizer_token(
+    conn: &mut PgConnection,
+    organizer_token: &str,
+) -> AppResult<Uuid> {
+    let (event_id, state) = db::events::lock_by_organizer_token(conn, organizer_token)
+        .await?
+        .ok_or_else(|| AppError::NotFound)?;
+
+    ensure_not_archived(state)?;
+    Ok(event_id)
+}
+
+/// Merges overlapping or touching ranges, but only with ranges of the same
+/// `availability_level`; the result is sorted by start. An omitted level is
+/// the same as `available`, and `available` ranges come back without one.
+fn merge_time_ranges(ranges: Vec<TimeRangeRequest>) -> Vec<TimeRangeRequest> {
+    let mut by_level: BTreeMap<AvailabilityLevel, Vec<agreed_time_core::TimeRange>> =
+        BTreeMap::new();
+    for range in ranges {
+        by_level
+            .entry(range.level())
+            .or_default()
+            .push(range.into());
+    }
+
+    let mut merged: Vec<TimeRangeRequest> = by_level
+        .into_iter()
+        .flat_map(|(level, ranges)| {
+            agreed_time_core::merge_time_ranges(ranges)
+                .into_iter()
+                .map(move |range| TimeRangeRequest {
+                    availability_level: (level != AvailabilityLevel::Available).then_some(level),
+                    ..range.into()
+                })
+        })
+        .collect();
+    merged.sort_by_key(|range| (range.start_at, range.level()));
     merged
 }
 
-pub async fn create_event(
-    State(pool): State<PgPool>,
-    Json(payload): Json<CreateEventRequest>,
-) -> AppResult<Json<CreateEventResponse>> {
-    // Validate input
-    if payload.title.trim().is_empty() || payload.title.len() > 100 {
-        return Err(AppError::BadRequest(
-            "Title is required and must be less than 100 characters".to_string(),
-        ));
+/// A moment can carry only one answer: ranges of different levels may touch
+/// but not overlap.
+fn validate_availability_levels(ranges: &[TimeRangeRequest]) -> AppResult<()> {
+    let merged = merge_time_ranges(ranges.to_vec());
+    for (i, a) in merged.iter().enumerate() {
+        let overlaps = merged[i + 1..]
+            .iter()
+            .take_while(|b| b.start_at < a.end_at)
+            .any(|b| b.level() != a.level());
+        if overlaps {
+            return Err(AppError::BadRequest(
+                "Availability ranges with different levels must not overlap".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Loose shape check only; deliverability is the mail server's problem.
+pub(crate) fn validate_email(email: &Option<String>) -> AppResult<()> {
+    if let Some(email) = email {
+        let valid = email.len() <= 254
+            && !email.chars().any(char::is_whitespace)
+            && email
+                .split_once('@')
+                .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+        if !valid {
+            return Err(AppError::BadRequest("Invalid email address".to_string()));
+        }
     }
+    Ok(())
+}
 
-    if let Some(ref desc) = payload.description
-        && desc.len() > 1000
+pub(crate) fn validate_locale(templates: &Templates, locale: &Option<String>) -> AppResult<()> {
+    if let Some(locale) = locale
+        && !templates.supports(locale)
     {
-        return Err(AppError::BadRequest(
-            "Description must be less than 1000 characters".to_string(),
-        ));
+        return Err(AppError::BadRequest(format!(
+            "Unsupported locale (available: {})",
+            templates.locales().collect::<Vec<_>>().join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Same limit as the `webhook_url` column.
+const MAX_WEBHOOK_URL_LENGTH: usize = 2048;
+
+async fn validate_webhook_url(url: &Option<String>) -> AppResult<()> {
+    if let Some(url) = url {
+        let parsed = reqwest::Url::parse(url)
+            .ok()
+            .filter(|parsed| {
+                url.len() <= MAX_WEBHOOK_URL_LENGTH
+                    && matches!(parsed.scheme(), "http" | "https")
+                    && parsed.host().is_some()
+            })
+            .ok_or_else(|| AppError::BadRequest("Invalid webhook URL".to_string()))?;
+        ensure_public_url(&parsed).await?;
+    }
+    Ok(())
+}
+
+/// Refuses URLs whose host resolves to a loopback, private or link-local
+/// address. A host that doesn't resolve yet is let through; delivery checks
+/// again before every post.
+async fn ensure_public_url(url: &reqwest::Url) -> AppResult<()> {
+    match outbound::resolve(url, false).await {
+        Err(OutboundError::ForbiddenHost) => Err(AppError::BadRequest(
+            "Webhook URL must point to a public address".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn validate_chat_webhook_url(
+    notifier: &dyn notifications::Notifier,
+    url: &Option<String>,
+) -> AppResult<()> {
+    if let Some(url) = url {
+        notifications::validate_url(notifier, url)
+            .await
+            .map_err(AppError::BadRequest)?;
+    }
+    Ok(())
+}
+
+fn validate_password(password: &Option<String>) -> AppResult<()> {
+    if password
+        .as_ref()
+        .is_some_and(|password| password.len() > passwords::MAX_LENGTH)
+    {
+        return Err(AppError::BadRequest(format!(
+            "Password must be at most {} characters",
+            passwords::MAX_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// A quorum of one would close on the organizer's own availability; 0
+/// (update only) turns the automatic close off.
+fn validate_close_at_quorum(quorum: Option<i32>, allow_off: bool) -> AppResult<()> {
+    match quorum {
+        None => Ok(()),
+        Some(0) if allow_off => Ok(()),
+        Some(quorum) if (2..=MAX_PARTICIPANTS as i32).contains(&quorum) => Ok(()),
+        Some(_) => Err(AppError::BadRequest(format!(
+            "Quorum must be between 2 and {} participants",
+            MAX_PARTICIPANTS
+        ))),
+    }
+}
+
+fn validate_days(label: &str, days: Option<i32>, max: i64) -> AppResult<()> {
+    match days {
+        Some(days) if !(1..=max).contains(&i64::from(days)) => Err(AppError::BadRequest(format!(
+            "{} must be between 1 and {} days",
+            label, max
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Calendar days a copied event may move its candidate times by.
+pub(crate) fn validate_offset_days(offset_days: i32) -> AppResult<()> {
+    if i64::from(offset_days).abs() > MAX_RANGE_SPAN_DAYS {
+        return Err(AppError::BadRequest(format!(
+            "Offset must be between -{0} and {0} days",
+            MAX_RANGE_SPAN_DAYS
+        )));
     }
+    Ok(())
+}
+
+/// Weekly slots placed in the reference week of `time_zone` (UTC when unset).
+fn weekly_ranges(
+    slots: Vec<WeeklySlot>,
+    time_zone: Option<&str>,
+) -> AppResult<Vec<TimeRangeRequest>> {
+    let time_zone = time_zone.unwrap_or("UTC");
+    let slots: Vec<agreed_time_core::WeeklySlot> = slots.into_iter().map(Into::into).collect();
+    let ranges = agreed_time_core::to_reference_ranges(&slots, time_zone)
+        .ok_or_else(|| AppError::BadRequest(format!("Unknown time zone: {}", time_zone)))?;
+    Ok(ranges.into_iter().map(Into::into).collect())
+}
+
+/// The weekday view of a weekly event's slots; empty for date events.
+pub(crate) fn weekly_view(
+    slot_kind: SlotKind,
+    event_slots: &[EventSlot],
+    time_zone: Option<&str>,
+) -> Vec<WeeklySlot> {
+    if slot_kind != SlotKind::Weekly {
+        return Vec::new();
+    }
+    let ranges: Vec<agreed_time_core::TimeRange> = event_slots
+        .iter()
+        .map(|slot| agreed_time_core::TimeRange::new(slot.start_at, slot.end_at))
+        .collect();
+    agreed_time_core::from_reference_ranges(&ranges, time_zone.unwrap_or("UTC"))
+        .unwrap_or_default()
+        .into_iter()
+        .map(Into::into)
+        .collect()
+}
+
+/// Slot length of a date poll: suggestions and quorum then count whole days.
+const DATE_POLL_SLOT_MINUTES: i32 = 24 * 60;
+
+/// Whole local days in `time_zone` (UTC when unset), merged.
+fn day_ranges(dates: &[NaiveDate], time_zone: Option<&str>) -> AppResult<Vec<TimeRangeRequest>> {
+    let time_zone = time_zone.unwrap_or("UTC");
+    let mut ranges = Vec::with_capacity(dates.len());
+    for &date in dates {
+        let day = agreed_time_core::day_range(date, time_zone)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown time zone: {}", time_zone)))?;
+        ranges.push(day.into());
+    }
+    Ok(merge_time_ranges(ranges))
+}
 
-    if payload.organizer_name.trim().is_empty() || payload.organizer_name.len() > 50 {
+/// Adds the days ticked in a date poll to the submitted ranges.
+fn with_ticked_dates(
+    mut availabilities: Vec<TimeRangeRequest>,
+    dates: &[NaiveDate],
+    poll_type: PollType,
+    time_zone: Option<&str>,
+) -> AppResult<Vec<TimeRangeRequest>> {
+    if dates.is_empty() {
+        return Ok(availabilities);
+    }
+    if poll_type != PollType::Date {
         return Err(AppError::BadRequest(
-            "Organizer name is required and must be less than 50 characters".to_string(),
+            "Dates can only be ticked in a date poll".to_string(),
         ));
     }
+    availabilities.extend(day_ranges(dates, time_zone)?);
+    validate_availability_levels(&availabilities)?;
+    Ok(availabilities)
+}
+
+/// The event's locked slots; submissions may not cover them.
+async fn locked_slot_ranges(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> AppResult<Vec<agreed_time_core::TimeRange>> {
+    Ok(db::events::locked_slots(conn, event_id).await?)
+}
+
+/// Cuts `locked` out of the submitted ranges, keeping each piece's level.
+fn clip_locked(
+    ranges: Vec<TimeRangeRequest>,
+    locked: &[agreed_time_core::TimeRange],
+) -> Vec<TimeRangeRequest> {
+    if locked.is_empty() {
+        return ranges;
+    }
+    ranges
+        .into_iter()
+        .flat_map(|range| {
+            let level = range.availability_level;
+            agreed_time_core::subtract_time_ranges(vec![range.into()], locked)
+                .into_iter()
+                .map(move |piece| TimeRangeRequest {
+                    start_at: piece.start_at,
+                    end_at: piece.end_at,
+                    availability_level: level,
+                })
+        })
+        .collect()
+}
+
+/// The candidate dates of a date poll; empty for other polls.
+pub(crate) fn poll_dates(
+    poll_type: PollType,
+    event_slots: &[EventSlot],
+    time_zone: Option<&str>,
+) -> Vec<NaiveDate> {
+    if poll_type != PollType::Date {
+        return Vec::new();
+    }
+    let ranges: Vec<agreed_time_core::TimeRange> = event_slots
+        .iter()
+        .map(|slot| agreed_time_core::TimeRange::new(slot.start_at, slot.end_at))
+        .collect();
+    agreed_time_core::covered_dates(&ranges, time_zone.unwrap_or("UTC")).unwrap_or_default()
+}
+
+/// Per-date tally of a date poll, over confirmed participants.
+fn date_votes(
+    dates: &[NaiveDate],
+    participants: &[ParticipantAvailability],
+    time_zone: Option<&str>,
+) -> Vec<DateVotes> {
+    let participants: Vec<agreed_time_core::ParticipantRanges> = participants
+        .iter()
+        .map(|participant| {
+            let mut available = Vec::new();
+            let mut if_need_be = Vec::new();
+            for range in &participant.availabilities {
+                match range.level() {
+                    AvailabilityLevel::Available => available.push(range.clone().into()),
+                    AvailabilityLevel::IfNeedBe => if_need_be.push(range.clone().into()),
+                    AvailabilityLevel::Unavailable => {}
+                }
+            }
+            agreed_time_core::ParticipantRanges {
+                name: participant.name.clone(),
+                is_organizer: participant.is_organizer,
+                availabilities: available,
+                if_need_be,
+                buffer_minutes: 0,
+                role: participant.role.clone(),
+            }
+        })
+        .collect();
+    agreed_time_core::date_votes(dates, &participants, time_zone.unwrap_or("UTC"))
+        .unwrap_or_default()
+        .into_iter()
+        .map(Into::into)
+        .collect()
+}
 
-    if payload.time_slots.is_empty() {
+/// Buffers are capped at 4 hours; anything larger would erase most availability.
+fn validate_buffer_minutes(buffer_minutes: Option<i32>) -> AppResult<i32> {
+    let buffer_minutes = buffer_minutes.unwrap_or(0);
+    if !(0..=MAX_BUFFER_MINUTES).contains(&buffer_minutes) {
         return Err(AppError::BadRequest(
-            "At least one time slot is required".to_string(),
+            "Buffer must be between 0 and 240 minutes".to_string(),
         ));
     }
+    Ok(buffer_minutes)
+}
 
-    for slot in &payload.time_slots {
-        if slot.start_at >= slot.end_at {
+/// Fields shared by new and updated availability submissions; returns the
+/// time zone in its canonical spelling.
+fn validate_participant_fields(
+    name: &str,
+    comment: Option<&str>,
+    time_zone: Option<&str>,
+    email: &Option<String>,
+    availabilities: &[TimeRangeRequest],
+) -> AppResult<Option<String>> {
+    let mut validator = Validator::new();
+    validator.required_text(
+        "participant_name",
+        "Participant name",
+        name,
+        MAX_NAME_LENGTH,
+    );
+    validator.optional_text("comment", "Comment", comment, MAX_COMMENT_LENGTH);
+    let time_zone = validator.time_zone("time_zone", time_zone);
+    validator.check("email", validate_email(email));
+    validator.time_ranges("availabilities", availabilities);
+    validator.finish()?;
+    validate_availability_levels(availabilities)?;
+    Ok(time_zone)
+}
+
+/// Role names are unique within an event and each role needs at least one person.
+fn validate_roles(roles: &[EventRole]) -> AppResult<()> {
+    if roles.len() > MAX_ROLES {
+        return Err(AppError::BadRequest(format!(
+            "At most {} roles are allowed",
+            MAX_ROLES
+        )));
+    }
+    let mut seen = HashSet::new();
+    for role in roles {
+        let name = role.name.trim();
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
             return Err(AppError::BadRequest(
-                "Invalid time range: start must be before end".to_string(),
+                "Role name is required and must be less than 50 characters".to_string(),
+            ));
+        }
+        if !(1..=MAX_PARTICIPANTS).contains(&(role.required as i64)) {
+            return Err(AppError::BadRequest(
+                "A role must require between 1 and 10 participants".to_string(),
+            ));
+        }
+        if !seen.insert(name) {
+            return Err(AppError::BadRequest(format!("Duplicate role: {}", name)));
+        }
+    }
+    Ok(())
+}
+
+fn validate_invitees(invitees: &[String]) -> AppResult<()> {
+    if invitees.len() > MAX_INVITEES {
+        return Err(AppError::BadRequest(format!(
+            "At most {} invitees are allowed",
+            MAX_INVITEES
+        )));
+    }
+    let mut seen = HashSet::new();
+    for email in invitees {
+        validate_email(&Some(email.clone()))?;
+        if !seen.insert(email.to_lowercase()) {
+            return Err(AppError::BadRequest(format!(
+                "Duplicate invitee: {}",
+                email
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// A quorum needs at least one person; required names are unique.
+fn validate_finalization_rules(rules: &FinalizationRules) -> AppResult<()> {
+    if let Some(min) = rules.min_participants
+        && !(1..=MAX_PARTICIPANTS as i32).contains(&min)
+    {
+        return Err(AppError::BadRequest(format!(
+            "Minimum participants must be between 1 and {}",
+            MAX_PARTICIPANTS
+        )));
+    }
+    if rules.required_participants.len() > MAX_PARTICIPANTS as usize {
+        return Err(AppError::BadRequest(format!(
+            "At most {} required participants are allowed",
+            MAX_PARTICIPANTS
+        )));
+    }
+    let mut seen = HashSet::new();
+    for name in &rules.required_participants {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(AppError::BadRequest(
+                "Required participant names must be 1 to 50 characters".to_string(),
             ));
         }
+        if !seen.insert(name) {
+            return Err(AppError::BadRequest(format!(
+                "Duplicate required participant: {}",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// How a slot attended by `attendees` falls short of `rules`; empty when it
+/// conforms.
+fn rule_violations(rules: &FinalizationRules, attendees: &[&str]) -> Vec<String> {
+    let mut violations = Vec::new();
+    if let Some(min) = rules.min_participants
+        && attendees.len() < min.max(0) as usize
+    {
+        violations.push(format!(
+            "needs {} participants, has {}",
+            min,
+            attendees.len()
+        ));
+    }
+    for name in &rules.required_participants {
+        if !attendees.contains(&name.as_str()) {
+            violations.push(format!("{} can't attend", name));
+        }
+    }
+    violations
+}
+
+/// Participants pick one of the event's roles; `None` fills no role.
+async fn validate_participant_role(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    role: Option<&str>,
+) -> AppResult<()> {
+    if let Some(role) = role
+        && !db::events::has_role(conn, event_id, role).await?
+    {
+        return Err(AppError::BadRequest(format!("Unknown role: {}", role)));
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/ticket",
+    tag = "events",
+    responses(
+        (status = 200, description = "Ticket for `POST /events` from the request's origin", body = CreationTicketResponse),
+        (status = 401, description = "Origin missing or not allowed", body = ErrorResponse),
+        (status = 404, description = "This deployment doesn't require tickets", body = ErrorResponse)
+    )
+)]
+pub async fn issue_creation_ticket(
+    State(config): State<Arc<Config>>,
+    State(clock): State<SharedClock>,
+    headers: HeaderMap,
+) -> AppResult<Json<CreationTicketResponse>> {
+    let secret = config
+        .creation_ticket_secret
+        .as_deref()
+        .ok_or(AppError::NotFound)?;
+    let origin = request_origin(&headers)
+        .filter(|origin| {
+            config
+                .allowed_origins
+                .iter()
+                .any(|allowed| allowed == origin)
+        })
+        .ok_or(AppError::Unauthorized)?;
+
+    let now = clock.now();
+    Ok(Json(CreationTicketResponse {
+        ticket: tickets::issue(secret, origin, now),
+        expires_at: now + chrono::Duration::seconds(config.creation_ticket_ttl_secs as i64),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events",
+    tag = "events",
+    params(("X-Creation-Ticket" = Option<String>, Header, description = "Required when the deployment sets `CREATION_TICKET_SECRET`")),
+    request_body = CreateEventRequest,
+    responses(
+        (status = 200, description = "Event created", body = CreateEventResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 403, description = "Creation ticket or captcha missing or invalid", body = ErrorResponse)
+    )
+)]
+#[allow(clippy::too_many_arguments)] // one per extractor
+pub async fn create_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(config): State<Arc<Config>>,
+    State(keys): State<SharedKeyring>,
+    State(templates): State<SharedTemplates>,
+    bearer: Option<CurrentUser>,
+    headers: HeaderMap,
+    Json(mut payload): Json<CreateEventRequest>,
+) -> AppResult<Json<CreateEventResponse>> {
+    if let Some(secret) = config.creation_ticket_secret.as_deref() {
+        let ticket = headers
+            .get(tickets::HEADER)
+            .and_then(|value| value.to_str().ok());
+        let valid = match (ticket, request_origin(&headers)) {
+            (Some(ticket), Some(origin)) => tickets::verify(
+                secret,
+                ticket,
+                origin,
+                clock.now(),
+                chrono::Duration::seconds(config.creation_ticket_ttl_secs as i64),
+            ),
+            _ => false,
+        };
+        if !valid {
+            return Err(AppError::CreationTicketRequired);
+        }
+    }
+    captcha::check(&config, payload.captcha_token.as_deref()).await?;
+
+    let mut validator = Validator::new();
+    validator.required_text("title", "Title", &payload.title, MAX_TITLE_LENGTH);
+    validator.optional_text(
+        "description",
+        "Description",
+        payload.description.as_deref(),
+        MAX_DESCRIPTION_LENGTH,
+    );
+    validator.required_text(
+        "organizer_name",
+        "Organizer name",
+        &payload.organizer_name,
+        MAX_NAME_LENGTH,
+    );
+    payload.time_zone = validator.time_zone("time_zone", payload.time_zone.as_deref());
+    validator.check("roles", validate_roles(&payload.roles));
+    validator.check(
+        "finalization_rules",
+        validate_finalization_rules(&payload.finalization_rules),
+    );
+    validator.check(
+        "webhook_url",
+        validate_webhook_url(&payload.webhook_url).await,
+    );
+    validator.check(
+        "slack_webhook_url",
+        validate_chat_webhook_url(&notifications::Slack, &payload.slack_webhook_url).await,
+    );
+    validator.check(
+        "discord_webhook_url",
+        validate_chat_webhook_url(&notifications::Discord, &payload.discord_webhook_url).await,
+    );
+    validator.check(
+        "chat_webhook_url",
+        validate_chat_webhook_url(&notifications::GenericJson, &payload.chat_webhook_url).await,
+    );
+    validator.check("password", validate_password(&payload.password));
+    validator.check(
+        "close_at_quorum",
+        validate_close_at_quorum(payload.close_at_quorum, false),
+    );
+    validator.check("organizer_email", validate_email(&payload.organizer_email));
+    validator.check("invitees", validate_invitees(&payload.invitees));
+    validator.check("locale", validate_locale(&templates, &payload.locale));
+    validator.check(
+        "retention_days",
+        validate_days(
+            "Retention",
+            payload.retention_days,
+            config.max_retention_days,
+        ),
+    );
+    validator.finish()?;
+
+    let slot_kind = payload.slot_kind.unwrap_or_default();
+    let poll_type = payload.poll_type.unwrap_or_default();
+    if poll_type == PollType::Datetime && !payload.dates.is_empty() {
+        return Err(AppError::BadRequest(
+            "Dates need poll_type date".to_string(),
+        ));
     }
+    let slots_field = match (slot_kind, poll_type) {
+        (SlotKind::Weekly, _) => "weekly_slots",
+        (SlotKind::Dates, PollType::Date) => "dates",
+        (SlotKind::Dates, PollType::Datetime) => "time_slots",
+    };
+    let time_slots = match slot_kind {
+        SlotKind::Dates => {
+            if !payload.weekly_slots.is_empty() {
+                return Err(AppError::BadRequest(
+                    "Weekly slots need slot_kind weekly".to_string(),
+                ));
+            }
+            match poll_type {
+                PollType::Datetime => payload.time_slots,
+                PollType::Date => {
+                    if !payload.time_slots.is_empty() {
+                        return Err(AppError::BadRequest(
+                            "Date polls take dates instead of time_slots".to_string(),
+                        ));
+                    }
+                    day_ranges(&payload.dates, payload.time_zone.as_deref())?
+                }
+            }
+        }
+        SlotKind::Weekly => {
+            if poll_type == PollType::Date {
+                return Err(AppError::BadRequest(
+                    "Weekly events cannot be date polls".to_string(),
+                ));
+            }
+            if !payload.time_slots.is_empty() {
+                return Err(AppError::BadRequest(
+                    "Weekly events take weekly_slots instead of time_slots".to_string(),
+                ));
+            }
+            weekly_ranges(payload.weekly_slots, payload.time_zone.as_deref())?
+        }
+    };
 
-    let slot_duration = payload.slot_duration.unwrap_or(60);
+    let mut validator = Validator::new();
+    if time_slots.is_empty() {
+        validator.add(slots_field, "At least one time slot is required");
+    }
+    validator.time_ranges(slots_field, &time_slots);
+    let slot_duration = match poll_type {
+        PollType::Datetime => payload.slot_duration.unwrap_or(60),
+        PollType::Date => DATE_POLL_SLOT_MINUTES,
+    };
     if slot_duration <= 0 {
+        validator.add("slot_duration", "Slot duration must be positive");
+    }
+    validator.finish()?;
+
+    let chat_webhooks = [
+        &payload.slack_webhook_url,
+        &payload.discord_webhook_url,
+        &payload.chat_webhook_url,
+    ];
+    if config.demo_mode
+        && (payload.webhook_url.is_some() || chat_webhooks.iter().any(|url| url.is_some()))
+    {
         return Err(AppError::BadRequest(
-            "Slot duration must be positive".to_string(),
+            "Webhooks are disabled in demo mode".to_string(),
         ));
     }
 
     let mut transaction = pool.begin().await?;
 
+    let organizer_account_id = match &payload.organizer_account_token {
+        Some(token) => Some(organizers::account_id(&mut transaction, token).await?),
+        None => None,
+    };
+    // A stale or missing session just leaves the event unowned
+    let user_id = auth::request_user(&mut transaction, bearer.as_ref(), &headers, clock.now())
+        .await?
+        .map(|user| user.id);
     let event_id = Uuid::new_v4();
-    let public_token = generate_token();
-    let organizer_token = generate_token();
-    let current_time = Utc::now();
+    let webhook_secret = payload
+        .webhook_url
+        .as_ref()
+        .map(|_| format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()));
+    let results_visibility = payload.results_visibility.unwrap_or_default();
+    let password_hash = payload
+        .password
+        .as_deref()
+        .filter(|password| !password.is_empty())
+        .map(passwords::hash);
+    let current_time = clock.now();
+    let retention_days = payload
+        .retention_days
+        .unwrap_or(config.default_retention_days() as i32);
+    // Demo events get a fixed expiry that `/extend` refuses to move
+    let expires_at = if config.demo_mode {
+        current_time + chrono::Duration::hours(cleanup::DEMO_RETENTION_HOURS)
+    } else {
+        current_time + chrono::Duration::days(retention_days.into())
+    };
 
     let organizer_name = payload.organizer_name.clone();
 
     // 1. Insert Event (without organizer_name)
-    sqlx::query_as!(
-        Event,
-        r#"
-        INSERT INTO events (
-            id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at
-        )
-        VALUES (
-            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
-        )
-        RETURNING id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at
-        "#,
-        event_id,
-        public_token,
-        organizer_token,
-        payload.title,
-        payload.description,
-        "open",
-        payload.time_zone,
+    let new_event = db::events::NewEvent {
+        id: event_id,
+        title: &payload.title,
+        description: payload.description.as_deref(),
+        time_zone: payload.time_zone.as_deref(),
         slot_duration,
-        current_time,
-        current_time
-    )
-    .fetch_one(&mut *transaction)
-    .await?;
+        slot_kind,
+        poll_type,
+        results_visibility,
+        reveal_results_at: payload.reveal_results_at,
+        blind_until_closed: payload.blind_until_closed.unwrap_or(false),
+        anonymous_results: payload.anonymous_results.unwrap_or(false),
+        close_at_quorum: payload.close_at_quorum,
+        password_hash: password_hash.as_deref(),
+        webhook_url: payload.webhook_url.as_deref(),
+        webhook_secret: keys.seal_opt(webhook_secret.as_deref()),
+        slack_webhook_url: payload.slack_webhook_url.as_deref(),
+        discord_webhook_url: payload.discord_webhook_url.as_deref(),
+        chat_webhook_url: payload.chat_webhook_url.as_deref(),
+        organizer_email: keys.seal_opt(payload.organizer_email.as_deref()),
+        locale: payload.locale.as_deref().unwrap_or(DEFAULT_LOCALE),
+        organizer_account_id,
+        user_id,
+        retention_days,
+        expires_at,
+        created_at: current_time,
+    };
+    let (public_token, organizer_token, results_token) = 'insert: {
+        for _ in 0..tokens::MAX_ATTEMPTS {
+            let public_token = tokens::generate();
+            let organizer_token = tokens::generate();
+            let results_token = tokens::generate();
+            let inserted = db::events::insert(
+                &mut transaction,
+                &new_event,
+                &public_token,
+                &organizer_token,
+                &results_token,
+            )
+            .await?;
+            if inserted {
+                break 'insert (public_token, organizer_token, results_token);
+            }
+        }
+        return Err(tokens::exhausted().into());
+    };
 
-    // 2. Event Slots
-    let merged_slots = merge_time_ranges(payload.time_slots);
-
-    for slot in &merged_slots {
-        sqlx::query!(
-            r#"
-            INSERT INTO event_slots (event_id, start_at, end_at)
-            VALUES ($1, $2, $3)
-            "#,
-            event_id,
-            slot.start_at,
-            slot.end_at
-        )
-        .execute(&mut *transaction)
+    db::events::insert_roles(&mut transaction, event_id, &payload.roles).await?;
+    db::events::insert_finalization_rules(&mut transaction, event_id, &payload.finalization_rules)
+        .await?;
+    db::events::insert_invitees(&mut transaction, event_id, &payload.invitees, current_time)
         .await?;
-    }
 
-    // 3. Create Organizer Participant (is_organizer = true)
-    let participant_id = sqlx::query_scalar!(
-        r#"
-        INSERT INTO participants (event_id, name, is_organizer)
-        VALUES ($1, $2, $3)
-        RETURNING id
-        "#,
+    // 2. Event Slots
+    let merged_slots = merge_time_ranges(time_slots);
+
+    db::events::insert_slots(
+        &mut transaction,
         event_id,
-        organizer_name,
-        true // is_organizer
+        merged_slots
+            .iter()
+            .map(|slot| (slot.start_at, slot.end_at, false)),
     )
-    .fetch_one(&mut *transaction)
     .await?;
 
+    // 3. Create Organizer Participant (is_organizer = true)
+    let participant_id =
+        db::participants::insert_organizer(&mut transaction, event_id, &organizer_name).await?;
+
     // 4. Organizer Availability
-    for slot in &merged_slots {
-        sqlx::query!(
-            r#"
-            INSERT INTO availabilities (participant_id, start_at, end_at)
-            VALUES ($1, $2, $3)
-            "#,
-            participant_id,
-            slot.start_at,
-            slot.end_at
-        )
-        .execute(&mut *transaction)
-        .await?;
-    }
+    db::availabilities::insert(&mut transaction, participant_id, &merged_slots).await?;
+    history::record(
+        &mut transaction,
+        participant_id,
+        history::PARTICIPANT,
+        clock.now(),
+    )
+    .await?;
+    audit::record(
+        &mut transaction,
+        event_id,
+        "created",
+        "organizer",
+        None,
+        current_time,
+    )
+    .await?;
 
     transaction.commit().await?;
 
@@ -177,552 +1017,3966 @@ pub async fn create_event(
         id: event_id,
         public_token,
         organizer_token,
+        results_token,
+        webhook_secret,
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/events/{public_token}",
+    tag = "events",
+    params(
+        ("public_token" = String, Path, description = "Public event token"),
+        DisplayQuery,
+        ("X-Display-Timezone" = Option<String>, Header, description = "IANA zone to render `display` times in; `?tz=` takes precedence")
+    ),
+    responses(
+        (status = 200, description = "Event details for participants", body = EventResponse),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 400, description = "Unknown display time zone", body = ErrorResponse),
+        (status = 403, description = "Event password missing or wrong", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
 pub async fn get_event(
     State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
     Path(public_token): Path<String>,
-) -> AppResult<Json<EventResponse>> {
-    // 1. Fetch Event
-    let event = sqlx::query_as!(
-        Event,
-        r#"
-        SELECT id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at
-        FROM events
-        WHERE public_token = $1
-        "#,
-        public_token
-    )
-    .fetch_optional(&pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound)?;
-
-    // 2. Fetch Organizer Name
-    let organizer_name = sqlx::query_scalar!(
-        r#"
-        SELECT name
-        FROM participants
-        WHERE event_id = $1 AND is_organizer = true
-        LIMIT 1
-        "#,
-        event.id
-    )
-    .fetch_one(&pool)
-    .await?;
-
-    // 3. Fetch Event Slots
-    let event_slots = sqlx::query_as!(
-        EventSlot,
-        r#"
-        SELECT id, event_id, start_at, end_at
-        FROM event_slots
-        WHERE event_id = $1
-        ORDER BY start_at
-        "#,
-        event.id
-    )
-    .fetch_all(&pool)
-    .await?;
-
-    Ok(Json(EventResponse {
-        id: event.id,
-        title: event.title,
-        description: event.description,
-        time_zone: event.time_zone,
-        slot_duration: event.slot_duration,
-        state: event.state,
-        event_slots,
-        organizer_name,
-    }))
+    Query(display): Query<DisplayQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    read_event(
+        &pool,
+        &cache,
+        clock.now(),
+        &public_token,
+        display.tz.as_deref(),
+        &headers,
+    )
+    .await
+}
+
+/// `get_event` past the extractors, over any [`EventStore`].
+async fn read_event<S: EventStore>(
+    store: &S,
+    cache: &ResponseCache,
+    now: DateTime<Utc>,
+    public_token: &str,
+    tz: Option<&str>,
+    headers: &HeaderMap,
+) -> AppResult<Response> {
+    let PublicEvent {
+        event,
+        password_hash,
+        version,
+        ..
+    } = store
+        .public_event(public_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    ensure_password(password_hash.as_deref(), password_header(headers))?;
+    let display_zone = display_time_zone(tz, headers)?;
+
+    let variant = display_zone.clone().unwrap_or_default();
+    let etag = version.etag(now, &[&variant, &etag::countdown_bucket(now)]);
+    if etag::is_fresh(headers, &etag) {
+        return Ok(etag::not_modified(etag));
+    }
+
+    // Archived events are rarely read and never change; keep the room for live ones
+    let cacheable = event.state != EventState::Archived;
+    // Copies tagged in an earlier countdown window are rebuilt
+    if let Some((_, mut response)) = cache
+        .event(event.id, &variant, now)
+        .filter(|(cached, _)| cacheable && *cached == etag)
+    {
+        // Countdowns run on from when the copy was built
+        response.seconds_until_deadline =
+            response.reveal_results_at.map(|at| seconds_until(at, now));
+        response.seconds_until_expiry = seconds_until(response.expires_at, now);
+        return Ok(etag::with_etag(etag, Json(response)));
+    }
+
+    let details = store.event_details(event.id).await?;
+    let mut response = event_response(event, details, now);
+    response.display = display_zone.map(|zone| {
+        display_times(
+            zone,
+            &response.event_slots,
+            &[],
+            response.reveal_results_at,
+            response.expires_at,
+        )
+    });
+    if cacheable {
+        cache.store_event(response.id, variant, etag.clone(), &response, now);
+    }
+    Ok(etag::with_etag(etag, Json(response)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/events/{public_token}/availability",
+    tag = "participants",
+    params(("public_token" = String, Path, description = "Public event token")),
+    request_body = SubmitAvailabilityRequest,
+    responses(
+        (status = 200, description = "Availability stored; `pending` when the event is full and the organizer has to approve", body = SubmitAvailabilityResponse),
+        (status = 400, description = "Invalid input, or both the event and its waitlist are full", body = ErrorResponse),
+        (status = 403, description = "Event password or captcha missing or wrong", body = ErrorResponse),
+        (status = 404, description = "Event or participant token not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse),
+        (status = 429, description = "Too many submissions to this event from this client", body = ErrorResponse)
+    )
+)]
+#[allow(clippy::too_many_arguments)] // one per extractor
 pub async fn submit_availability(
     State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    State(config): State<Arc<Config>>,
+    State(keys): State<SharedKeyring>,
+    State(templates): State<SharedTemplates>,
+    State(hub): State<SharedHub>,
     Path(public_token): Path<String>,
-    Json(payload): Json<SubmitAvailabilityRequest>,
+    headers: HeaderMap,
+    Json(mut payload): Json<SubmitAvailabilityRequest>,
 ) -> AppResult<Json<SubmitAvailabilityResponse>> {
-    // Validate participant name
-    if payload.participant_name.trim().is_empty() || payload.participant_name.len() > 50 {
-        return Err(AppError::BadRequest(
-            "Participant name is required and must be less than 50 characters".to_string(),
-        ));
+    let buffer_minutes = validate_submission(&mut payload)?;
+    payload.comment = keys.seal_opt(payload.comment.as_deref());
+
+    // Before locking the event, as the provider may take a while
+    captcha::check(&config, payload.captcha_token.as_deref()).await?;
+
+    let mut transaction = pool.begin().await?;
+    let event = lock_submission_target(&mut transaction, &public_token).await?;
+    let participant_name = payload.participant_name.clone();
+
+    let (response, frame) = save_submission(
+        &mut transaction,
+        &event,
+        payload,
+        buffer_minutes,
+        password_header(&headers),
+        Verifier::new(&config, &templates).as_ref(),
+        clock.now(),
+    )
+    .await?;
+    notifications::enqueue(
+        &mut transaction,
+        event.id,
+        notifications::Activity::Responded {
+            participant_name: &participant_name,
+        },
+        config.public_url.as_deref(),
+        clock.now(),
+    )
+    .await?;
+    quorum::close_if_reached(
+        &mut transaction,
+        event.id,
+        &templates,
+        config.public_url.as_deref(),
+        clock.now(),
+    )
+    .await?;
+
+    transaction.commit().await?;
+    cache.invalidate(event.id);
+
+    if let Some(frame) = frame {
+        hub.publish(event.id, frame);
     }
 
-    if let Some(ref comment) = payload.comment
-        && comment.len() > 500
-    {
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{public_token}/availability/batch",
+    tag = "participants",
+    params(("public_token" = String, Path, description = "Public event token")),
+    request_body = Vec<SubmitAvailabilityRequest>,
+    responses(
+        (status = 200, description = "One result per entry, in request order; failed entries leave the others stored", body = BatchSubmitAvailabilityResponse),
+        (status = 400, description = "Empty batch or more than 20 entries", body = ErrorResponse),
+        (status = 403, description = "Captcha missing or wrong", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse),
+        (status = 429, description = "Too many submissions to this event from this client", body = ErrorResponse)
+    )
+)]
+#[allow(clippy::too_many_arguments)] // one per extractor
+pub async fn submit_availability_batch(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    State(config): State<Arc<Config>>,
+    State(keys): State<SharedKeyring>,
+    State(templates): State<SharedTemplates>,
+    State(hub): State<SharedHub>,
+    Path(public_token): Path<String>,
+    headers: HeaderMap,
+    Json(entries): Json<Vec<SubmitAvailabilityRequest>>,
+) -> AppResult<Json<BatchSubmitAvailabilityResponse>> {
+    if entries.is_empty() || entries.len() > MAX_BATCH_SUBMISSIONS {
+        return Err(AppError::BadRequest(format!(
+            "A batch needs between 1 and {} entries",
+            MAX_BATCH_SUBMISSIONS
+        )));
+    }
+
+    // One solved captcha covers the whole batch
+    captcha::check(&config, entries[0].captcha_token.as_deref()).await?;
+
+    let mut transaction = pool.begin().await?;
+    let event = lock_submission_target(&mut transaction, &public_token).await?;
+    ensure_not_archived(event.state)?;
+
+    let verifier = Verifier::new(&config, &templates);
+    let mut results = Vec::with_capacity(entries.len());
+    let mut frames = Vec::new();
+    for (index, mut payload) in entries.into_iter().enumerate() {
+        // Each entry gets a savepoint, so a failed one leaves the rest intact
+        let mut savepoint = transaction.begin().await?;
+        let participant_name = payload.participant_name.clone();
+        let saved = match validate_submission(&mut payload) {
+            Ok(buffer_minutes) => {
+                payload.comment = keys.seal_opt(payload.comment.as_deref());
+                save_submission(
+                    &mut savepoint,
+                    &event,
+                    payload,
+                    buffer_minutes,
+                    password_header(&headers),
+                    verifier.as_ref(),
+                    clock.now(),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        results.push(match saved {
+            Ok((response, frame)) => {
+                notifications::enqueue(
+                    &mut savepoint,
+                    event.id,
+                    notifications::Activity::Responded {
+                        participant_name: &participant_name,
+                    },
+                    config.public_url.as_deref(),
+                    clock.now(),
+                )
+                .await?;
+                savepoint.commit().await?;
+                frames.extend(frame);
+                BatchSubmissionResult {
+                    index,
+                    participant_token: Some(response.participant_token),
+                    status: Some(response.status),
+                    error: None,
+                }
+            }
+            Err(AppError::Database(e)) => return Err(AppError::Database(e)),
+            Err(e) => {
+                savepoint.rollback().await?;
+                BatchSubmissionResult {
+                    index,
+                    participant_token: None,
+                    status: None,
+                    error: Some(e.body()),
+                }
+            }
+        });
+    }
+    quorum::close_if_reached(
+        &mut transaction,
+        event.id,
+        &templates,
+        config.public_url.as_deref(),
+        clock.now(),
+    )
+    .await?;
+
+    transaction.commit().await?;
+    cache.invalidate(event.id);
+
+    for frame in frames {
+        hub.publish(event.id, frame);
+    }
+
+    Ok(Json(BatchSubmitAvailabilityResponse { results }))
+}
+
+/// Checks a submission's fields and normalizes its time zone; returns the
+/// buffer to store.
+fn validate_submission(payload: &mut SubmitAvailabilityRequest) -> AppResult<i32> {
+    payload.time_zone = validate_participant_fields(
+        &payload.participant_name,
+        payload.comment.as_deref(),
+        payload.time_zone.as_deref(),
+        &payload.email,
+        &payload.availabilities,
+    )?;
+    validate_buffer_minutes(payload.buffer_minutes)
+}
+
+async fn lock_submission_target(
+    conn: &mut PgConnection,
+    public_token: &str,
+) -> AppResult<SubmissionTarget> {
+    db::events::lock_for_submission(conn, public_token)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// Stores one validated submission and, through `verifier`, asks to confirm
+/// a new address. The caller commits, then publishes the returned frame;
+/// waitlisted participants get none.
+async fn save_submission(
+    conn: &mut PgConnection,
+    event: &SubmissionTarget,
+    payload: SubmitAvailabilityRequest,
+    buffer_minutes: i32,
+    header_password: Option<&str>,
+    verifier: Option<&Verifier<'_>>,
+    now: DateTime<Utc>,
+) -> AppResult<(SubmitAvailabilityResponse, Option<RealtimeMessage>)> {
+    ensure_password(
+        event.password_hash.as_deref(),
+        header_password.or(payload.password.as_deref()),
+    )?;
+    ensure_not_archived(event.state)?;
+    let availabilities = with_ticked_dates(
+        payload.availabilities,
+        &payload.dates,
+        PollType::from_db(&event.poll_type),
+        event.time_zone.as_deref(),
+    )?;
+    let event_id = event.id;
+    let availabilities = clip_locked(
+        availabilities,
+        &locked_slot_ranges(&mut *conn, event_id).await?,
+    );
+    validate_participant_role(&mut *conn, event_id, payload.role.as_deref()).await?;
+
+    let answer = db::participants::Answer {
+        name: &payload.participant_name,
+        comment: payload.comment.as_deref(),
+        buffer_minutes,
+        time_zone: payload.time_zone.as_deref(),
+        email: payload.email.as_deref(),
+        role: payload.role.as_deref(),
+    };
+    let updated = payload.participant_token.is_some();
+    let (id, participant_token, status, hidden) = match payload.participant_token {
+        Some(token) => {
+            let participant = db::participants::lock_by_token(&mut *conn, event_id, token)
+                .await?
+                .ok_or_else(|| AppError::NotFound)?;
+            db::participants::update_answer(&mut *conn, participant.id, &answer, now).await?;
+            (
+                participant.id,
+                participant.token,
+                participant.status,
+                participant.is_hidden,
+            )
+        }
+        None => {
+            // Past the participant limit, newcomers wait for the organizer
+            // until the waitlist is full as well
+            let counts = db::participants::status_counts(&mut *conn, event_id).await?;
+            let status = if counts.confirmed < MAX_PARTICIPANTS {
+                ParticipantStatus::Confirmed
+            } else if counts.pending < MAX_WAITLIST {
+                ParticipantStatus::Pending
+            } else {
+                return Err(AppError::ParticipantLimitReached(MAX_PARTICIPANTS));
+            };
+
+            // Always insert, allowing duplicate names; the returned token is the edit credential
+            let (id, token) =
+                db::participants::insert(&mut *conn, event_id, &answer, status).await?;
+            (id, token, status, false)
+        }
+    };
+
+    if let Some(verifier) = verifier {
+        verifier.request(&mut *conn, id, now).await?;
+    }
+
+    db::availabilities::delete_for_participant(&mut *conn, id).await?;
+
+    let merged_availabilities = merge_time_ranges(availabilities);
+
+    db::availabilities::insert(&mut *conn, id, &merged_availabilities).await?;
+    history::record(&mut *conn, id, history::PARTICIPANT, now).await?;
+    // Resubmitting with the edit token is the same as editing the response
+    let (action, details) = if updated {
+        ("participant_updated", None)
+    } else {
+        ("availability_submitted", Some(status.as_str()))
+    };
+    audit::record(
+        &mut *conn,
+        event_id,
+        action,
+        &payload.participant_name,
+        details,
+        now,
+    )
+    .await?;
+
+    webhooks::enqueue(
+        &mut *conn,
+        event_id,
+        WebhookEvent::ParticipantSubmitted,
+        json!({
+            "participant_name": payload.participant_name,
+            "role": payload.role,
+            "updated": updated,
+            "status": status.as_str(),
+        }),
+        now,
+    )
+    .await?;
+
+    // The waitlist stays out of the live grid until approved, and whoever
+    // the organizer hid stays out of it for good
+    let frame = (status == ParticipantStatus::Confirmed && !hidden).then_some(
+        RealtimeMessage::Availability {
+            participant_name: payload.participant_name,
+            role: payload.role,
+            availabilities: merged_availabilities,
+        },
+    );
+
+    Ok((
+        SubmitAvailabilityResponse {
+            participant_token,
+            status,
+        },
+        frame,
+    ))
+}
+
+/// Calendar dates each slot covers in `time_zone`. Unknown zones yield no labels.
+fn local_slot_dates(event_slots: &[EventSlot], time_zone: &str) -> Vec<SlotLocalDates> {
+    event_slots
+        .iter()
+        .filter_map(|slot| {
+            let range = agreed_time_core::TimeRange::new(slot.start_at, slot.end_at);
+            agreed_time_core::local_date_span(&range, time_zone).map(|span| SlotLocalDates {
+                slot_id: slot.id,
+                start_date: span.start_date,
+                end_date: span.end_date,
+            })
+        })
+        .collect()
+}
+
+async fn fetch_event_results_data<S: EventStore>(
+    store: &S,
+    event_id: Uuid,
+    event_time_zone: Option<&str>,
+) -> AppResult<(Vec<EventSlot>, Vec<ParticipantAvailability>, i64)> {
+    let event_slots = store.event_slots(event_id).await?;
+    let confirmed = store.confirmed_participants(event_id).await?;
+    let total_participants = confirmed.len() as i64;
+    let participants = confirmed
+        .into_iter()
+        .map(|participant| participant_availability(participant, &event_slots, event_time_zone))
+        .collect();
+    Ok((event_slots, participants, total_participants))
+}
+
+fn participant_availability(
+    participant: ConfirmedParticipant,
+    event_slots: &[EventSlot],
+    event_time_zone: Option<&str>,
+) -> ParticipantAvailability {
+    let slot_dates = local_slot_dates(
+        event_slots,
+        participant
+            .time_zone
+            .as_deref()
+            .or(event_time_zone)
+            .unwrap_or("UTC"),
+    );
+    ParticipantAvailability {
+        id: participant.id,
+        name: participant.name,
+        is_organizer: participant.is_organizer,
+        comment: participant.comment,
+        buffer_minutes: participant.buffer_minutes,
+        time_zone: participant.time_zone,
+        slot_dates,
+        availabilities: participant.ranges,
+        rsvp_status: participant.rsvp_status,
+        role: participant.role,
+        email_verified: participant.email_verified,
+        display_order: participant.display_order,
+        is_hidden: participant.is_hidden,
+    }
+}
+
+/// Decrypts the comments of participants about to be shown; the results
+/// reads leave them sealed.
+fn open_comments(keys: &Keyring, participants: &mut [ParticipantAvailability]) -> AppResult<()> {
+    for participant in participants {
+        participant.comment = keys.open_opt(participant.comment.take())?;
+    }
+    Ok(())
+}
+
+/// Drops the participants the organizer hid; every public read goes
+/// through this, the organizer's own views don't.
+fn visible(participants: Vec<ParticipantAvailability>) -> Vec<ParticipantAvailability> {
+    participants
+        .into_iter()
+        .filter(|participant| !participant.is_hidden)
+        .collect()
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{public_token}/export.ics",
+    tag = "events",
+    params(("public_token" = String, Path, description = "Public event token")),
+    responses(
+        (status = 200, description = "Final slots, or every candidate slot before finalization, as iCalendar", body = String, content_type = "text/calendar"),
+        (status = 403, description = "Event password missing or wrong", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Weekly events have no dates", body = ErrorResponse)
+    )
+)]
+pub async fn export_event_ics(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Path(public_token): Path<String>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    read_calendar(&pool, clock.now(), &public_token, &headers).await
+}
+
+/// `export_event_ics` past the extractors, over any [`EventStore`].
+async fn read_calendar<S: EventStore>(
+    store: &S,
+    now: DateTime<Utc>,
+    public_token: &str,
+    headers: &HeaderMap,
+) -> AppResult<Response> {
+    let PublicEvent {
+        event,
+        password_hash,
+        slot_kind,
+        ..
+    } = store
+        .public_event(public_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    ensure_password(password_hash.as_deref(), password_header(headers))?;
+
+    // Reference-week dates would land in the past of any calendar
+    if slot_kind == SlotKind::Weekly {
+        return Err(AppError::Conflict(
+            "Weekly events have no calendar dates to export".to_string(),
+        ));
+    }
+
+    let details = store.event_details(event.id).await?;
+    let final_slots = store.final_slots(event.id).await?;
+    let confirmed = !final_slots.is_empty();
+    let slots: Vec<ics::CalendarSlot> = if confirmed {
+        final_slots
+            .into_iter()
+            .map(|slot| ics::CalendarSlot {
+                id: slot.id,
+                start_at: slot.start_at,
+                end_at: slot.end_at,
+            })
+            .collect()
+    } else {
+        details
+            .slots
+            .into_iter()
+            .map(|slot| ics::CalendarSlot {
+                id: slot.id,
+                start_at: slot.start_at,
+                end_at: slot.end_at,
+            })
+            .collect()
+    };
+
+    let body = ics::render(
+        &ics::CalendarEvent {
+            public_token: &event.public_token,
+            title: &event.title,
+            description: event.description.as_deref(),
+            organizer_name: &details.organizer_name,
+            time_zone: event.time_zone.as_deref(),
+            confirmed,
+        },
+        &slots,
+        now,
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"event.ics\"",
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{public_token}/results",
+    tag = "events",
+    params(
+        ("public_token" = String, Path, description = "Public event token"),
+        ResultsQuery,
+        ("X-Display-Timezone" = Option<String>, Header, description = "IANA zone to render `display` times in; `?tz=` takes precedence")
+    ),
+    responses(
+        (status = 200, description = "Aggregated participant availability", body = EventResultsResponse),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 400, description = "Unknown display time zone", body = ErrorResponse),
+        (status = 401, description = "Results are private and the results token is missing or wrong", body = ErrorResponse),
+        (status = 403, description = "Event password missing or wrong, or `RESULTS_LOCKED` until the caller has submitted (`after_submit`) or the event has closed (`after_close`)", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_event_results(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    State(keys): State<SharedKeyring>,
+    Path(public_token): Path<String>,
+    Query(query): Query<ResultsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    read_results(
+        &pool,
+        &cache,
+        &keys,
+        clock.now(),
+        &public_token,
+        &query,
+        &headers,
+    )
+    .await
+}
+
+/// `get_event_results` past the extractors, over any [`EventStore`].
+async fn read_results<S: EventStore>(
+    store: &S,
+    cache: &ResponseCache,
+    keys: &Keyring,
+    now: DateTime<Utc>,
+    public_token: &str,
+    query: &ResultsQuery,
+    headers: &HeaderMap,
+) -> AppResult<Response> {
+    let PublicEvent {
+        event,
+        password_hash,
+        slot_kind,
+        poll_type,
+        version,
+    } = store
+        .public_event(public_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    ensure_password(password_hash.as_deref(), password_header(headers))?;
+    let display_zone = display_time_zone(query.tz.as_deref(), headers)?;
+    let view = check_results_access(
+        store,
+        event.id,
+        query.results_token.as_deref(),
+        query.participant_token,
+        now,
+    )
+    .await?;
+    let variant = format!("{:?}:{}", view, display_zone.as_deref().unwrap_or_default());
+    let cacheable = event.state != EventState::Archived;
+    if let Some((etag, response)) = cache.results(event.id, &variant, now).filter(|_| cacheable) {
+        if etag::is_fresh(headers, &etag) {
+            return Ok(etag::not_modified(etag));
+        }
+        return Ok(etag::with_etag(etag, Json(response)));
+    }
+    let etag = version.etag(now, &[&variant]);
+    if etag::is_fresh(headers, &etag) {
+        return Ok(etag::not_modified(etag));
+    }
+
+    let (event_slots, participants, _) =
+        fetch_event_results_data(store, event.id, event.time_zone.as_deref()).await?;
+    let mut participants = visible(participants);
+    open_comments(keys, &mut participants)?;
+    let total_participants = participants.len() as i64;
+
+    let final_slots = store.final_slots(event.id).await?;
+    let weekly_slots = weekly_view(slot_kind, &event_slots, event.time_zone.as_deref());
+    let dates = poll_dates(poll_type, &event_slots, event.time_zone.as_deref());
+    let results = public_results(
+        view,
+        participants,
+        &dates,
+        event.slot_duration,
+        event.time_zone.as_deref(),
+    );
+    let display = display_zone.map(|zone| {
+        display_times(
+            zone,
+            &event_slots,
+            &final_slots,
+            version.reveal_results_at,
+            version.expires_at,
+        )
+    });
+
+    let response = EventResultsResponse {
+        id: event.id,
+        title: event.title,
+        description: event.description,
+        time_zone: event.time_zone,
+        slot_duration: event.slot_duration,
+        state: event.state,
+        event_slots,
+        slot_kind,
+        weekly_slots,
+        poll_type,
+        dates,
+        final_slots,
+        participants: results.participants,
+        date_votes: results.date_votes,
+        slot_counts: results.slot_counts,
+        total_participants,
+        results_hidden: view == ResultsView::Hidden,
+        anonymous_results: view == ResultsView::Anonymous,
+        display,
+    };
+    if cacheable {
+        cache.store_results(response.id, variant, etag.clone(), &response, now);
+    }
+    Ok(etag::with_etag(etag, Json(response)))
+}
+
+/// What `/results` shows a public caller.
+#[derive(Default)]
+struct PublicResults {
+    participants: Vec<ParticipantAvailability>,
+    date_votes: Vec<DateVotes>,
+    slot_counts: Vec<SlotCount>,
+}
+
+/// Shapes the stored results for `view`: nothing while hidden, and for
+/// anonymous results the counts without the rows and names behind them.
+fn public_results(
+    view: ResultsView,
+    mut participants: Vec<ParticipantAvailability>,
+    dates: &[NaiveDate],
+    slot_duration: i32,
+    time_zone: Option<&str>,
+) -> PublicResults {
+    if view == ResultsView::Hidden {
+        return PublicResults::default();
+    }
+    // Whether someone left an address is the organizer's business
+    for participant in &mut participants {
+        participant.email_verified = None;
+    }
+    let mut date_votes = date_votes(dates, &participants, time_zone);
+    let slot_counts = agreed_time_core::bucket_availability(
+        &participants.iter().map(core_ranges).collect::<Vec<_>>(),
+        slot_duration,
+    )
+    .into_iter()
+    .map(SlotCount::from)
+    .collect();
+    if view == ResultsView::Anonymous {
+        for votes in &mut date_votes {
+            anonymize(&mut votes.participants);
+            anonymize(&mut votes.if_need_be);
+        }
+        return PublicResults {
+            participants: Vec::new(),
+            date_votes,
+            slot_counts,
+        };
+    }
+    PublicResults {
+        participants,
+        date_votes,
+        slot_counts,
+    }
+}
+
+/// Replaces names public callers of anonymous results would otherwise see,
+/// keeping how many there are.
+fn anonymize(names: &mut [String]) {
+    for name in names {
+        *name = ANONYMOUS_NAME.to_string();
+    }
+}
+
+fn anonymize_suggestion(suggestion: &mut SlotSuggestion) {
+    anonymize(&mut suggestion.participants);
+    anonymize(&mut suggestion.if_need_be);
+    anonymize(&mut suggestion.missing);
+    for role in &mut suggestion.roles {
+        anonymize(&mut role.filled);
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{public_token}/suggestions",
+    tag = "events",
+    params(
+        ("public_token" = String, Path, description = "Public event token"),
+        SuggestionsQuery,
+        ("X-Display-Timezone" = Option<String>, Header, description = "IANA zone to add each window's `display` in; `?tz=` takes precedence")
+    ),
+    responses(
+        (status = 200, description = "Best windows by participant overlap", body = EventSuggestionsResponse),
+        (status = 400, description = "Unknown display time zone", body = ErrorResponse),
+        (status = 401, description = "Results are private and the results token is missing or wrong", body = ErrorResponse),
+        (status = 403, description = "Event password missing or wrong, or `RESULTS_LOCKED` until the caller has submitted (`after_submit`) or the event has closed (`after_close`)", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_event_suggestions(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Path(public_token): Path<String>,
+    Query(query): Query<SuggestionsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<EventSuggestionsResponse>> {
+    read_suggestions(&pool, clock.now(), &public_token, &query, &headers)
+        .await
+        .map(Json)
+}
+
+/// `get_event_suggestions` past the extractors, over any [`EventStore`].
+async fn read_suggestions<S: EventStore>(
+    store: &S,
+    now: DateTime<Utc>,
+    public_token: &str,
+    query: &SuggestionsQuery,
+    headers: &HeaderMap,
+) -> AppResult<EventSuggestionsResponse> {
+    let limit = query.limit.unwrap_or(5).clamp(1, MAX_SUGGESTIONS);
+    let display_zone = display_time_zone(query.tz.as_deref(), headers)?;
+
+    let PublicEvent {
+        event,
+        password_hash,
+        ..
+    } = store
+        .public_event(public_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    ensure_password(password_hash.as_deref(), password_header(headers))?;
+
+    let view = check_results_access(
+        store,
+        event.id,
+        query.results_token.as_deref(),
+        query.participant_token,
+        now,
+    )
+    .await?;
+    if view == ResultsView::Hidden {
+        return Ok(EventSuggestionsResponse {
+            slot_duration: event.slot_duration,
+            suggestions: Vec::new(),
+        });
+    }
+
+    let details = store.event_details(event.id).await?;
+    let (_, participants, _) =
+        fetch_event_results_data(store, event.id, event.time_zone.as_deref()).await?;
+    let participants = visible(participants);
+    let mut suggestions = suggest_windows(
+        &participants,
+        event.slot_duration,
+        limit,
+        &details.roles,
+        &details.finalization_rules,
+    );
+    for suggestion in &mut suggestions {
+        if view == ResultsView::Anonymous {
+            anonymize_suggestion(suggestion);
+        }
+        if let Some(zone) = &display_zone {
+            suggestion.display = Some(local_range(suggestion.start_at, suggestion.end_at, zone));
+        }
+    }
+
+    Ok(EventSuggestionsResponse {
+        slot_duration: event.slot_duration,
+        suggestions,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{public_token}/summary",
+    tag = "events",
+    params(
+        ("public_token" = String, Path, description = "Public event token"),
+        SummaryQuery
+    ),
+    responses(
+        (status = 200, description = "Compact digest of slots, best candidates and respondents", content(
+            (EventSummary = "application/json"),
+            (String = "text/markdown")
+        )),
+        (status = 401, description = "Results are private and the results token is missing or wrong", body = ErrorResponse),
+        (status = 403, description = "Event password missing or wrong, or `RESULTS_LOCKED` until the caller has submitted (`after_submit`) or the event has closed (`after_close`)", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_event_summary(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Path(public_token): Path<String>,
+    Query(query): Query<SummaryQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    read_summary(&pool, clock.now(), &public_token, &query, &headers).await
+}
+
+/// `get_event_summary` past the extractors, over any [`EventStore`].
+async fn read_summary<S: EventStore>(
+    store: &S,
+    now: DateTime<Utc>,
+    public_token: &str,
+    query: &SummaryQuery,
+    headers: &HeaderMap,
+) -> AppResult<Response> {
+    let PublicEvent {
+        event,
+        password_hash,
+        ..
+    } = store
+        .public_event(public_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    ensure_password(password_hash.as_deref(), password_header(headers))?;
+
+    let view = check_results_access(
+        store,
+        event.id,
+        query.results_token.as_deref(),
+        query.participant_token,
+        now,
+    )
+    .await?;
+
+    let details = store.event_details(event.id).await?;
+    let (event_slots, participants, _) =
+        fetch_event_results_data(store, event.id, event.time_zone.as_deref()).await?;
+    let participants = if view == ResultsView::Hidden {
+        Vec::new()
+    } else {
+        visible(participants)
+    };
+    let final_slots = store.final_slots(event.id).await?;
+
+    let mut digest = EventSummary {
+        best_candidates: suggest_windows(
+            &participants,
+            event.slot_duration,
+            3,
+            &details.roles,
+            &details.finalization_rules,
+        ),
+        title: event.title,
+        state: event.state,
+        time_zone: event.time_zone,
+        slot_duration: event.slot_duration,
+        slots: event_slots
+            .into_iter()
+            .map(|slot| TimeRangeRequest {
+                start_at: slot.start_at,
+                end_at: slot.end_at,
+                availability_level: None,
+            })
+            .collect(),
+        final_slots: final_slots
+            .into_iter()
+            .map(|slot| TimeRangeRequest {
+                start_at: slot.start_at,
+                end_at: slot.end_at,
+                availability_level: None,
+            })
+            .collect(),
+        without_availability: participants
+            .iter()
+            .filter(|p| {
+                p.availabilities
+                    .iter()
+                    .all(|range| range.level() == AvailabilityLevel::Unavailable)
+            })
+            .map(|p| p.name.clone())
+            .collect(),
+        respondents: participants.into_iter().map(|p| p.name).collect(),
+        results_hidden: view == ResultsView::Hidden,
+    };
+    if view == ResultsView::Anonymous {
+        digest
+            .best_candidates
+            .iter_mut()
+            .for_each(anonymize_suggestion);
+        anonymize(&mut digest.respondents);
+        anonymize(&mut digest.without_availability);
+    }
+
+    Ok(match query.format {
+        SummaryFormat::Json => Json(digest).into_response(),
+        SummaryFormat::Markdown => (
+            [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+            summary::render_markdown(&digest),
+        )
+            .into_response(),
+    })
+}
+
+fn core_ranges(p: &ParticipantAvailability) -> agreed_time_core::ParticipantRanges {
+    let ranges_at = |level: AvailabilityLevel| {
+        p.availabilities
+            .iter()
+            .filter(|range| range.level() == level)
+            .cloned()
+            .map(Into::into)
+            .collect()
+    };
+    agreed_time_core::ParticipantRanges {
+        name: p.name.clone(),
+        is_organizer: p.is_organizer,
+        availabilities: ranges_at(AvailabilityLevel::Available),
+        if_need_be: ranges_at(AvailabilityLevel::IfNeedBe),
+        buffer_minutes: p.buffer_minutes,
+        role: p.role.clone(),
+    }
+}
+
+fn suggest_windows(
+    participants: &[ParticipantAvailability],
+    slot_duration: i32,
+    limit: usize,
+    roles: &[EventRole],
+    rules: &FinalizationRules,
+) -> Vec<SlotSuggestion> {
+    let participants: Vec<agreed_time_core::ParticipantRanges> =
+        participants.iter().map(core_ranges).collect();
+    let requirements: Vec<agreed_time_core::RoleRequirement> = roles
+        .iter()
+        .map(|role| agreed_time_core::RoleRequirement {
+            role: role.name.clone(),
+            required: role.required.max(0) as usize,
+        })
+        .collect();
+
+    agreed_time_core::suggest_with_roles(&participants, slot_duration, limit, &requirements)
+        .into_iter()
+        .map(|suggestion| {
+            let mut suggestion = SlotSuggestion::from(suggestion);
+            let attendees: Vec<&str> = suggestion
+                .participants
+                .iter()
+                .chain(&suggestion.if_need_be)
+                .map(String::as_str)
+                .collect();
+            suggestion.meets_rules = rule_violations(rules, &attendees).is_empty();
+            suggestion
+        })
+        .collect()
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/organizer/{organizer_token}",
+    tag = "organizer",
+    params(
+        ("organizer_token" = String, Path, description = "Organizer token"),
+        DisplayQuery,
+        ("X-Display-Timezone" = Option<String>, Header, description = "IANA zone to render `display` times in; `?tz=` takes precedence")
+    ),
+    responses(
+        (status = 200, description = "Organizer view of the event", body = OrganizerEventResponse),
+        (status = 400, description = "Unknown display time zone", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_organizer_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(keys): State<SharedKeyring>,
+    Path(organizer_token): Path<String>,
+    Query(display): Query<DisplayQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<OrganizerEventResponse>> {
+    let display_zone = display_time_zone(display.tz.as_deref(), &headers)?;
+    let event = db::events::find_by_organizer_token(&pool, &organizer_token)
+        .await?
+        .ok_or_else(|| AppError::NotFound)?;
+
+    let (event_slots, mut participants, total_participants) =
+        fetch_event_results_data(&pool, event.id, event.time_zone.as_deref()).await?;
+    open_comments(&keys, &mut participants)?;
+
+    let final_slots = db::events::final_slots(&pool, event.id).await?;
+    let now = clock.now();
+
+    let edit_lock = db::edit_locks::current(&pool, event.id, now).await?;
+    let access = db::events::organizer_settings(&pool, event.id).await?;
+
+    let expires_at = access.expires_at;
+    let slot_kind = access.slot_kind;
+    let weekly_slots = weekly_view(slot_kind, &event_slots, event.time_zone.as_deref());
+    let poll_type = access.poll_type;
+    let dates = poll_dates(poll_type, &event_slots, event.time_zone.as_deref());
+    let date_votes = date_votes(&dates, &participants, event.time_zone.as_deref());
+    let display = display_zone.map(|zone| {
+        display_times(
+            zone,
+            &event_slots,
+            &final_slots,
+            access.reveal_results_at,
+            expires_at,
+        )
+    });
+
+    Ok(Json(OrganizerEventResponse {
+        id: event.id,
+        public_token: event.public_token,
+        organizer_token: event.organizer_token,
+        title: event.title,
+        description: event.description,
+        time_zone: event.time_zone,
+        slot_duration: event.slot_duration,
+        state: event.state,
+        event_slots,
+        slot_kind,
+        weekly_slots,
+        poll_type,
+        dates,
+        final_slots,
+        participants,
+        date_votes,
+        total_participants,
+        edit_lock,
+        results_visibility: access.results_visibility,
+        results_token: access.results_token,
+        reveal_results_at: access.reveal_results_at,
+        blind_until_closed: access.blind_until_closed,
+        anonymous_results: access.anonymous_results,
+        roles: db::events::roles(&pool, event.id).await?,
+        finalization_rules: db::events::finalization_rules(&pool, event.id).await?,
+        close_at_quorum: access.close_at_quorum,
+        webhook_url: access.webhook_url,
+        webhook_secret: keys.open_opt(access.webhook_secret)?,
+        slack_webhook_url: access.slack_webhook_url,
+        discord_webhook_url: access.discord_webhook_url,
+        chat_webhook_url: access.chat_webhook_url,
+        password_protected: access.password_protected,
+        organizer_email: keys.open_opt(access.organizer_email)?,
+        invitees: db::events::invitees(&pool, event.id).await?,
+        deletion_notice: access.deletion_notice,
+        retention_days: access.retention_days,
+        locale: access.locale,
+        created_at: event.created_at,
+        seconds_until_deadline: access.reveal_results_at.map(|at| seconds_until(at, now)),
+        expires_at,
+        seconds_until_expiry: seconds_until(expires_at, now),
+        display,
+    }))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/events/{organizer_token}",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    request_body = UpdateEventRequest,
+    responses(
+        (status = 200, description = "Event updated", body = EventResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Slots of a finalized event cannot change, or the event is archived", body = ErrorResponse)
+    )
+)]
+#[allow(clippy::too_many_arguments)] // one per extractor
+pub async fn update_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    State(config): State<Arc<Config>>,
+    State(keys): State<SharedKeyring>,
+    State(templates): State<SharedTemplates>,
+    Path(organizer_token): Path<String>,
+    Json(mut payload): Json<UpdateEventRequest>,
+) -> AppResult<Json<EventResponse>> {
+    let mut validator = Validator::new();
+    if let Some(ref title) = payload.title {
+        validator.required_text("title", "Title", title, MAX_TITLE_LENGTH);
+    }
+    validator.optional_text(
+        "description",
+        "Description",
+        payload.description.as_deref(),
+        MAX_DESCRIPTION_LENGTH,
+    );
+    payload.time_zone = validator.time_zone("time_zone", payload.time_zone.as_deref());
+    validator.finish()?;
+    validate_password(&payload.password)?;
+    validate_close_at_quorum(payload.close_at_quorum, true)?;
+    // An empty address clears it
+    validate_email(
+        &payload
+            .organizer_email
+            .clone()
+            .filter(|email| !email.is_empty()),
+    )?;
+    validate_locale(&templates, &payload.locale)?;
+    if let Some(ref rules) = payload.finalization_rules {
+        validate_finalization_rules(rules)?;
+    }
+    if let Some(ref roles) = payload.roles {
+        validate_roles(roles)?;
+    }
+
+    if payload.slot_duration.is_some_and(|d| d <= 0) {
+        return Err(AppError::BadRequest(
+            "Slot duration must be positive".to_string(),
+        ));
+    }
+
+    for slot in payload.add_slots.iter().chain(&payload.remove_slots) {
+        if slot.start_at >= slot.end_at {
+            return Err(AppError::BadRequest(
+                "Invalid time range: start must be before end".to_string(),
+            ));
+        }
+    }
+
+    let mut transaction = pool.begin().await?;
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+    let now = clock.now();
+
+    let mut changed = Vec::new();
+    if payload.title.is_some() {
+        changed.push("title");
+    }
+    if payload.description.is_some() {
+        changed.push("description");
+    }
+    if payload.time_zone.is_some() {
+        changed.push("time_zone");
+    }
+    if payload.slot_duration.is_some() {
+        changed.push("slot_duration");
+    }
+    if payload.results_visibility.is_some() {
+        changed.push("results_visibility");
+    }
+    if payload.reveal_results_at.is_some() || payload.blind_until_closed.is_some() {
+        changed.push("blind_poll");
+    }
+    if payload.password.is_some() {
+        changed.push("password");
+    }
+    if payload.close_at_quorum.is_some() {
+        changed.push("close_at_quorum");
+    }
+    if payload.organizer_email.is_some() {
+        changed.push("organizer_email");
+    }
+    if payload.deletion_notice.is_some() {
+        changed.push("deletion_notice");
+    }
+    if payload.locale.is_some() {
+        changed.push("locale");
+    }
+
+    let current = db::events::slot_settings(&mut transaction, event_id).await?;
+    let poll_type = current.poll_type;
+    if poll_type != PollType::Date
+        && (!payload.add_dates.is_empty() || !payload.remove_dates.is_empty())
+    {
+        return Err(AppError::BadRequest("Dates need a date poll".to_string()));
+    }
+    let (add_slots, remove_slots) = match current.slot_kind {
+        SlotKind::Dates => {
+            if !payload.add_weekly_slots.is_empty() || !payload.remove_weekly_slots.is_empty() {
+                return Err(AppError::BadRequest(
+                    "Weekly slots need a weekly event".to_string(),
+                ));
+            }
+            match poll_type {
+                PollType::Datetime => (payload.add_slots, payload.remove_slots),
+                PollType::Date => {
+                    if !payload.add_slots.is_empty() || !payload.remove_slots.is_empty() {
+                        return Err(AppError::BadRequest(
+                            "Date polls take add_dates and remove_dates".to_string(),
+                        ));
+                    }
+                    // Days are stored as instants of the old zone's midnights
+                    if payload.time_zone.is_some() && payload.time_zone != current.time_zone
+                        || payload
+                            .slot_duration
+                            .is_some_and(|d| d != current.slot_duration)
+                    {
+                        return Err(AppError::BadRequest(
+                            "The time zone and slot duration of a date poll cannot change"
+                                .to_string(),
+                        ));
+                    }
+                    (
+                        day_ranges(&payload.add_dates, current.time_zone.as_deref())?,
+                        day_ranges(&payload.remove_dates, current.time_zone.as_deref())?,
+                    )
+                }
+            }
+        }
+        SlotKind::Weekly => {
+            if !payload.add_slots.is_empty() || !payload.remove_slots.is_empty() {
+                return Err(AppError::BadRequest(
+                    "Weekly events take add_weekly_slots and remove_weekly_slots".to_string(),
+                ));
+            }
+            // The slots are stored as instants of the old zone's reference week
+            if payload.time_zone.is_some() && payload.time_zone != current.time_zone {
+                return Err(AppError::BadRequest(
+                    "The time zone of a weekly event cannot change".to_string(),
+                ));
+            }
+            (
+                weekly_ranges(payload.add_weekly_slots, current.time_zone.as_deref())?,
+                weekly_ranges(payload.remove_weekly_slots, current.time_zone.as_deref())?,
+            )
+        }
+    };
+
+    if !add_slots.is_empty() || !remove_slots.is_empty() {
+        if current.state == EventState::Finalized {
+            return Err(AppError::Conflict(
+                "Slots of a finalized event cannot change".to_string(),
+            ));
+        }
+
+        replace_event_slots(&mut transaction, event_id, add_slots, remove_slots, now).await?;
+        changed.push("slots");
+    }
+
+    if let Some(roles) = &payload.roles {
+        db::events::replace_roles(&mut transaction, event_id, roles).await?;
+        let names: Vec<String> = roles.iter().map(|r| r.name.trim().to_string()).collect();
+        db::participants::clear_roles_except(&mut transaction, event_id, &names).await?;
+        changed.push("roles");
+    }
+    if let Some(rules) = &payload.finalization_rules {
+        db::events::replace_finalization_rules(&mut transaction, event_id, rules).await?;
+        changed.push("finalization_rules");
+    }
+
+    let changes = db::events::EventChanges {
+        title: payload.title.as_deref().map(str::trim),
+        description: payload.description.as_deref(),
+        time_zone: payload.time_zone.as_deref(),
+        slot_duration: payload.slot_duration,
+        results_visibility: payload.results_visibility,
+        reveal_results_at: payload.reveal_results_at,
+        blind_until_closed: payload.blind_until_closed,
+        password_hash: payload.password.as_deref().map(|password| match password {
+            "" => String::new(),
+            password => passwords::hash(password),
+        }),
+        close_at_quorum: payload.close_at_quorum,
+        // An empty address clears it and stays empty
+        organizer_email: payload.organizer_email.as_deref().map(|email| match email {
+            "" => String::new(),
+            email => keys.seal(email),
+        }),
+        deletion_notice: payload.deletion_notice,
+        locale: payload.locale.as_deref(),
+    };
+    db::events::update(&mut transaction, event_id, &changes, now).await?;
+
+    audit::record(
+        &mut transaction,
+        event_id,
+        "updated",
+        "organizer",
+        Some(&changed.join(",")),
+        now,
+    )
+    .await?;
+
+    // A lowered quorum may already be met
+    if payload.close_at_quorum.is_some() {
+        quorum::close_if_reached(
+            &mut transaction,
+            event_id,
+            &templates,
+            config.public_url.as_deref(),
+            now,
+        )
+        .await?;
+    }
+
+    transaction.commit().await?;
+    cache.invalidate(event_id);
+
+    let event = db::events::find(&pool, event_id).await?;
+
+    let details = db::events::details(&pool, event.id).await?;
+    Ok(Json(event_response(event, details, clock.now())))
+}
+
+/// Applies slot additions and removals, then trims every participant's
+/// availability to the new slots. New slots are also added to the
+/// organizer's availability, as on creation, except where locked. Locked time
+/// stays locked in its own rows, even where slots merge. Every participant
+/// whose availability changed gets a history version.
+async fn replace_event_slots(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    add_slots: Vec<TimeRangeRequest>,
+    remove_slots: Vec<TimeRangeRequest>,
+    now: DateTime<Utc>,
+) -> AppResult<()> {
+    let existing = db::events::slot_ranges(&mut *conn, event_id).await?;
+    let locked = locked_slot_ranges(conn, event_id).await?;
+
+    let added: Vec<agreed_time_core::TimeRange> = add_slots.into_iter().map(Into::into).collect();
+    let removed: Vec<agreed_time_core::TimeRange> =
+        remove_slots.into_iter().map(Into::into).collect();
+    let slots = agreed_time_core::subtract_time_ranges(
+        existing.into_iter().chain(added.iter().copied()).collect(),
+        &removed,
+    );
+    if slots.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one time slot is required".to_string(),
+        ));
+    }
+
+    db::events::delete_slots(&mut *conn, event_id).await?;
+    let unlocked_slots = agreed_time_core::subtract_time_ranges(slots.clone(), &locked);
+    let locked_slots = agreed_time_core::intersect_time_ranges(&slots, &locked);
+    db::events::insert_slots(
+        &mut *conn,
+        event_id,
+        unlocked_slots
+            .iter()
+            .map(|slot| (slot.start_at, slot.end_at, false))
+            .chain(
+                locked_slots
+                    .iter()
+                    .map(|slot| (slot.start_at, slot.end_at, true)),
+            ),
+    )
+    .await?;
+
+    let mut changed = BTreeSet::new();
+    if !added.is_empty() {
+        let organizer_id = db::participants::organizer_id(&mut *conn, event_id).await?;
+        changed.insert(organizer_id);
+        let offered = agreed_time_core::subtract_time_ranges(added, &removed);
+        let offered: Vec<TimeRangeRequest> =
+            agreed_time_core::subtract_time_ranges(offered, &locked)
+                .into_iter()
+                .map(TimeRangeRequest::from)
+                .collect();
+        db::availabilities::insert(&mut *conn, organizer_id, &offered).await?;
+    }
+
+    // Rewrite only the availability rows that fall partly or fully outside the slots
+    let availabilities = db::availabilities::for_event(&mut *conn, event_id).await?;
+
+    let mut clipped = Vec::new();
+    let mut kept_by_participant: BTreeMap<i64, Vec<TimeRangeRequest>> = BTreeMap::new();
+    for row in availabilities {
+        let kept = agreed_time_core::intersect_time_ranges(&[row.range], &slots);
+        if kept == [row.range] {
+            continue;
+        }
+        changed.insert(row.participant_id);
+        clipped.push(row.id);
+        let level = row.level;
+        kept_by_participant
+            .entry(row.participant_id)
+            .or_default()
+            .extend(kept.into_iter().map(|piece| TimeRangeRequest {
+                start_at: piece.start_at,
+                end_at: piece.end_at,
+                availability_level: Some(level),
+            }));
+    }
+    db::availabilities::delete(&mut *conn, &clipped).await?;
+    for (participant_id, kept) in &kept_by_participant {
+        db::availabilities::insert(&mut *conn, *participant_id, kept).await?;
+    }
+
+    for participant_id in changed {
+        history::record(conn, participant_id, history::ORGANIZER, now).await?;
+    }
+
+    Ok(())
+}
+
+/// Whole seconds from `now` until `at`, never negative. Countdowns use this
+/// so clients need not trust their own clock.
+fn seconds_until(at: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    (at - now).num_seconds().max(0)
+}
+
+/// Participant-facing view of an event, as returned by `GET /events/{public_token}`.
+fn event_response(event: Event, details: EventDetails, now: DateTime<Utc>) -> EventResponse {
+    let weekly_slots = weekly_view(
+        details.slot_kind,
+        &details.slots,
+        event.time_zone.as_deref(),
+    );
+    let dates = poll_dates(
+        details.poll_type,
+        &details.slots,
+        event.time_zone.as_deref(),
+    );
+
+    EventResponse {
+        id: event.id,
+        title: event.title,
+        description: event.description,
+        time_zone: event.time_zone,
+        slot_duration: event.slot_duration,
+        state: event.state,
+        event_slots: details.slots,
+        slot_kind: details.slot_kind,
+        weekly_slots,
+        poll_type: details.poll_type,
+        dates,
+        organizer_name: details.organizer_name,
+        results_visibility: details.results_visibility,
+        reveal_results_at: details.reveal_results_at,
+        blind_until_closed: details.blind_until_closed,
+        anonymous_results: details.anonymous_results,
+        roles: details.roles,
+        finalization_rules: details.finalization_rules,
+        close_at_quorum: details.close_at_quorum,
+        seconds_until_deadline: details.reveal_results_at.map(|at| seconds_until(at, now)),
+        expires_at: details.expires_at,
+        seconds_until_expiry: seconds_until(details.expires_at, now),
+        display: None,
+    }
+}
+
+/// Blind polls hide results until `reveal_results_at` has passed and, with
+/// `blind_until_closed`, the event is no longer open. Events with neither
+/// option are always revealed. `db::reveal` mirrors this in SQL.
+pub(crate) fn results_revealed(
+    state: EventState,
+    reveal_results_at: Option<DateTime<Utc>>,
+    blind_until_closed: bool,
+    now: DateTime<Utc>,
+) -> bool {
+    reveal_results_at.is_none_or(|at| at <= now)
+        && !(blind_until_closed && state == EventState::Open)
+}
+
+/// How much of an event's results a public caller gets to see. The
+/// organizer view always has everything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ResultsView {
+    /// Blind poll not yet revealed (see [`results_revealed`])
+    Hidden,
+    /// `anonymous_results`: counts, but nobody's name
+    Anonymous,
+    Full,
+}
+
+/// Enforces `results_visibility`. The results token always gets through;
+/// private results need it (401), while `after_submit` and `after_close`
+/// answer 403 `RESULTS_LOCKED` until the caller has submitted or the event
+/// has closed. Submitting availability is unaffected.
+pub(crate) async fn check_results_access<S: EventStore>(
+    store: &S,
+    event_id: Uuid,
+    results_token: Option<&str>,
+    participant_token: Option<Uuid>,
+    now: DateTime<Utc>,
+) -> AppResult<ResultsView> {
+    let access = store.results_access(event_id).await?;
+
+    let has_results_token = results_token
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), access.results_token.as_bytes()));
+    if !has_results_token {
+        match access.results_visibility {
+            ResultsVisibility::Public => {}
+            ResultsVisibility::Private => return Err(AppError::Unauthorized),
+            ResultsVisibility::AfterSubmit => {
+                let submitted = match participant_token {
+                    Some(token) => store.has_participant(event_id, token).await?,
+                    None => false,
+                };
+                if !submitted {
+                    return Err(AppError::ResultsLocked(
+                        "Results are shown once you have submitted your availability".to_string(),
+                    ));
+                }
+            }
+            ResultsVisibility::AfterClose => {
+                if access.state == EventState::Open {
+                    return Err(AppError::ResultsLocked(
+                        "Results are shown once the event is closed".to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(
+        if !results_revealed(
+            access.state,
+            access.reveal_results_at,
+            access.blind_until_closed,
+            now,
+        ) {
+            ResultsView::Hidden
+        } else if access.anonymous_results {
+            ResultsView::Anonymous
+        } else {
+            ResultsView::Full
+        },
+    )
+}
+
+#[utoipa::path(
+    delete,
+    path = "/events/{organizer_token}",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 204, description = "Event moved to the trash; restorable until purged"),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<StatusCode> {
+    // Archived events may be deleted too: this is a privacy escape hatch,
+    // not an edit. The purge job removes the rows for good.
+    let now = clock.now();
+    let mut transaction = pool.begin().await?;
+
+    let event_id = db::events::trash(&mut transaction, &organizer_token, now)
+        .await?
+        .ok_or_else(|| AppError::NotFound)?;
+
+    audit::record(
+        &mut transaction,
+        event_id,
+        "deleted",
+        "organizer",
+        None,
+        now,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/restore",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 200, description = "Event restored from the trash", body = EventResponse),
+        (status = 404, description = "No trashed event with this token", body = ErrorResponse),
+        (status = 409, description = "Event was trashed because it expired", body = ErrorResponse)
+    )
+)]
+pub async fn restore_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<Json<EventResponse>> {
+    let now = clock.now();
+    let mut transaction = pool.begin().await?;
+
+    let (event_id, expires_at) = db::events::lock_trashed(&mut transaction, &organizer_token)
+        .await?
+        .ok_or_else(|| AppError::NotFound)?;
+
+    // The retention job would trash it again within the hour
+    if expires_at < now {
+        return Err(AppError::Conflict(
+            "Expired events cannot be restored".to_string(),
+        ));
+    }
+
+    let event = db::events::restore(&mut transaction, event_id).await?;
+
+    audit::record(
+        &mut transaction,
+        event.id,
+        "restored",
+        "organizer",
+        None,
+        now,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    let details = db::events::details(&pool, event.id).await?;
+    Ok(Json(event_response(event, details, clock.now())))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/clone",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    request_body(content = Option<CloneEventRequest>, description = "Optional; without `offset_days` the slots are copied as they are"),
+    responses(
+        (status = 200, description = "New open event with the same settings and slots, fresh tokens and only the organizer as participant", body = CreateEventResponse),
+        (status = 400, description = "Invalid offset, or an offset for a weekly event", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn clone_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(config): State<Arc<Config>>,
+    State(keys): State<SharedKeyring>,
+    Path(organizer_token): Path<String>,
+    payload: Option<Json<CloneEventRequest>>,
+) -> AppResult<Json<CreateEventResponse>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let offset_days = payload.offset_days.unwrap_or(0);
+    let mut validator = Validator::new();
+    validator.check("offset_days", validate_offset_days(offset_days));
+    validator.finish()?;
+
+    let mut transaction = pool.begin().await?;
+    let source = db::events::clone_source(&mut transaction, &organizer_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // Weekly slots live in a reference week; moving them by days would
+    // change the weekday instead of the date
+    if offset_days != 0 && source.slot_kind == SlotKind::Weekly {
+        return Err(AppError::BadRequest(
+            "Weekly events repeat every week; offset_days doesn't apply".to_string(),
+        ));
+    }
+    let time_zone = source.time_zone.as_deref().unwrap_or("UTC");
+    let slots: Vec<agreed_time_core::TimeRange> = source
+        .slots
+        .iter()
+        .map(|slot| {
+            agreed_time_core::shift_days(slot, offset_days.into(), time_zone).unwrap_or(*slot)
+        })
+        .collect();
+
+    let now = clock.now();
+    let expires_at = if config.demo_mode {
+        now + chrono::Duration::hours(cleanup::DEMO_RETENTION_HOURS)
+    } else {
+        now + chrono::Duration::days(source.retention_days.into())
+    };
+    let webhook_secret = source
+        .webhook_url
+        .as_ref()
+        .map(|_| format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()));
+    let event_id = Uuid::new_v4();
+
+    let copy = db::events::EventCopy {
+        id: event_id,
+        source_id: source.id,
+        webhook_secret: keys.seal_opt(webhook_secret.as_deref()),
+        offset_days,
+        expires_at,
+        created_at: now,
+    };
+    let (public_token, organizer_token, results_token) = 'insert: {
+        for _ in 0..tokens::MAX_ATTEMPTS {
+            let public_token = tokens::generate();
+            let organizer_token = tokens::generate();
+            let results_token = tokens::generate();
+            if db::events::insert_copy(
+                &mut transaction,
+                &copy,
+                &public_token,
+                &organizer_token,
+                &results_token,
+            )
+            .await?
+            {
+                break 'insert (public_token, organizer_token, results_token);
+            }
+        }
+        return Err(tokens::exhausted().into());
+    };
+
+    let participant_id =
+        db::participants::insert_organizer(&mut transaction, event_id, &source.organizer_name)
+            .await?;
+    // As on create, the organizer offers every candidate slot
+    db::events::insert_slots(
+        &mut transaction,
+        event_id,
+        slots.iter().map(|slot| (slot.start_at, slot.end_at, false)),
+    )
+    .await?;
+    let offered: Vec<TimeRangeRequest> = slots
+        .iter()
+        .map(|slot| TimeRangeRequest {
+            start_at: slot.start_at,
+            end_at: slot.end_at,
+            availability_level: None,
+        })
+        .collect();
+    db::availabilities::insert(&mut transaction, participant_id, &offered).await?;
+    history::record(&mut transaction, participant_id, history::PARTICIPANT, now).await?;
+    audit::record(
+        &mut transaction,
+        event_id,
+        "created",
+        "organizer",
+        Some("cloned"),
+        now,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(Json(CreateEventResponse {
+        id: event_id,
+        public_token,
+        organizer_token,
+        results_token,
+        webhook_secret,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/extend",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    request_body(content = Option<ExtendEventRequest>, description = "Optional; without `days` the event is kept for another `retention_days` from now"),
+    responses(
+        (status = 200, description = "Event kept longer; see `expires_at`", body = EventResponse),
+        (status = 400, description = "Invalid number of days", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Demo deployments don't extend events", body = ErrorResponse)
+    )
+)]
+pub async fn extend_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    State(config): State<Arc<Config>>,
+    Path(organizer_token): Path<String>,
+    payload: Option<Json<ExtendEventRequest>>,
+) -> AppResult<Json<EventResponse>> {
+    if config.demo_mode {
+        return Err(AppError::Conflict(
+            "Events can't be extended in demo mode".to_string(),
+        ));
+    }
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let mut validator = Validator::new();
+    validator.check(
+        "days",
+        validate_days("Extension", payload.days, config.max_retention_days),
+    );
+    validator.finish()?;
+
+    let now = clock.now();
+    let mut transaction = pool.begin().await?;
+
+    let (current_expiry, retention_days) =
+        db::events::lock_retention(&mut transaction, &organizer_token)
+            .await?
+            .ok_or_else(|| AppError::NotFound)?;
+
+    let requested = match payload.days {
+        Some(days) => current_expiry.max(now) + chrono::Duration::days(days.into()),
+        None => now + chrono::Duration::days(retention_days.into()),
+    };
+    // Never further out than a fresh event could ask for, but never sooner
+    // than already promised
+    let expires_at = requested
+        .min(now + chrono::Duration::days(config.max_retention_days))
+        .max(current_expiry);
+
+    // Archived events are read-only but still expire, so they can be kept too.
+    // Clearing the notice mark announces the new expiry in time as well.
+    let event = db::events::extend(&mut transaction, &organizer_token, expires_at).await?;
+
+    audit::record(
+        &mut transaction,
+        event.id,
+        "extended",
+        "organizer",
+        Some(&format!(
+            "{} -> {}",
+            current_expiry.to_rfc3339(),
+            expires_at.to_rfc3339()
+        )),
+        now,
+    )
+    .await?;
+
+    transaction.commit().await?;
+    cache.invalidate(event.id);
+
+    let details = db::events::details(&pool, event.id).await?;
+    Ok(Json(event_response(event, details, now)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/close",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 200, description = "Event closed", body = EventResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
+    )
+)]
+pub async fn close_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    State(config): State<Arc<Config>>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<Json<EventResponse>> {
+    let mut transaction = pool.begin().await?;
+    let (event_id, previous_state) =
+        db::events::lock_by_organizer_token(&mut transaction, &organizer_token)
+            .await?
+            .ok_or_else(|| AppError::NotFound)?;
+    ensure_not_archived(previous_state)?;
+
+    let event = db::events::close(&mut transaction, event_id, clock.now()).await?;
+
+    // Closing twice, or a finalized event, is not news
+    if previous_state == EventState::Open {
+        audit::record(
+            &mut transaction,
+            event_id,
+            "closed",
+            "organizer",
+            None,
+            clock.now(),
+        )
+        .await?;
+        webhooks::enqueue(
+            &mut transaction,
+            event_id,
+            WebhookEvent::EventClosed,
+            json!({}),
+            clock.now(),
+        )
+        .await?;
+        notifications::enqueue(
+            &mut transaction,
+            event_id,
+            notifications::Activity::Closed,
+            config.public_url.as_deref(),
+            clock.now(),
+        )
+        .await?;
+    }
+
+    transaction.commit().await?;
+    cache.invalidate(event_id);
+
+    let details = db::events::details(&pool, event.id).await?;
+    Ok(Json(event_response(event, details, clock.now())))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/finalize",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    request_body = FinalizeEventRequest,
+    responses(
+        (status = 200, description = "Event finalized", body = FinalizeEventResponse),
+        (status = 400, description = "Invalid slots", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
+    )
+)]
+pub async fn finalize_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    State(templates): State<SharedTemplates>,
+    State(config): State<Arc<Config>>,
+    Path(organizer_token): Path<String>,
+    Json(payload): Json<FinalizeEventRequest>,
+) -> AppResult<Json<FinalizeEventResponse>> {
+    if payload.slots.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one final slot is required".to_string(),
+        ));
+    }
+
+    if payload.slots.len() > MAX_FINAL_SLOTS {
+        return Err(AppError::BadRequest(
+            "Too many final slots (max 20)".to_string(),
+        ));
+    }
+
+    for slot in &payload.slots {
+        if slot.start_at >= slot.end_at {
+            return Err(AppError::BadRequest(
+                "Invalid time range: start must be before end".to_string(),
+            ));
+        }
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+
+    // Final slots must fall inside the times the organizer offered
+    let offered = db::events::slot_ranges(&mut transaction, event_id).await?;
+
+    let merged = merge_time_ranges(payload.slots);
+    for slot in &merged {
+        let candidate = agreed_time_core::TimeRange::new(slot.start_at, slot.end_at);
+        if !offered.iter().any(|o| o.contains(&candidate)) {
+            return Err(AppError::BadRequest(
+                "Final slots must be within the event's time slots".to_string(),
+            ));
+        }
+    }
+
+    let rules = db::events::finalization_rules(&pool, event_id).await?;
+    if !payload.force && !rules.is_empty() {
+        let (_, participants, _) = fetch_event_results_data(&pool, event_id, None).await?;
+        let participants: Vec<agreed_time_core::ParticipantRanges> =
+            participants.iter().map(core_ranges).collect();
+        for slot in &merged {
+            let range = agreed_time_core::TimeRange::new(slot.start_at, slot.end_at);
+            let attendees: Vec<&str> = participants
+                .iter()
+                .filter(|p| agreed_time_core::scheduler::fit(p, &range).is_some())
+                .map(|p| p.name.as_str())
+                .collect();
+            let violations = rule_violations(&rules, &attendees);
+            if !violations.is_empty() {
+                return Err(AppError::Conflict(format!(
+                    "{} misses the finalization rules ({}); send force: true to finalize anyway",
+                    agreed_time_core::format_range(&range, "UTC").unwrap_or_default(),
+                    violations.join(", ")
+                )));
+            }
+        }
+    }
+
+    // Re-finalizing replaces the previous decision
+    db::events::replace_final_slots(&mut transaction, event_id, &merged).await?;
+    let event = db::events::finalize(&mut transaction, event_id, clock.now()).await?;
+
+    // A weekly decision has no dates to put in a calendar
+    let mut invitations_queued = 0;
+    if let Some(organizer_email) = &config.mail_from
+        && payload.send_invitations.unwrap_or(true)
+        && event.slot_kind == SlotKind::Dates
+    {
+        let slots: Vec<(DateTime<Utc>, DateTime<Utc>)> =
+            merged.iter().map(|r| (r.start_at, r.end_at)).collect();
+        invitations_queued = enqueue_invitations(
+            &mut transaction,
+            event_id,
+            event.final_revision,
+            &slots,
+            organizer_email,
+            &templates,
+            clock.now(),
+        )
+        .await?;
+    }
+
+    webhooks::enqueue(
+        &mut transaction,
+        event_id,
+        WebhookEvent::EventFinalized,
+        json!({
+            "revision": event.final_revision,
+            "final_slots": merged
+                .iter()
+                .map(|slot| json!({ "start_at": slot.start_at, "end_at": slot.end_at }))
+                .collect::<Vec<_>>(),
+        }),
+        clock.now(),
+    )
+    .await?;
+    notifications::enqueue(
+        &mut transaction,
+        event_id,
+        notifications::Activity::Finalized,
+        config.public_url.as_deref(),
+        clock.now(),
+    )
+    .await?;
+    audit::record(
+        &mut transaction,
+        event_id,
+        "finalized",
+        "organizer",
+        Some(&format!("revision {}", event.final_revision)),
+        clock.now(),
+    )
+    .await?;
+
+    transaction.commit().await?;
+    cache.invalidate(event_id);
+    let state = event.state;
+
+    let final_slots = db::events::final_slots(&pool, event_id).await?;
+
+    Ok(Json(FinalizeEventResponse {
+        state,
+        final_slots,
+        invitations_queued,
+    }))
+}
+
+/// Queues an iTIP REQUEST for every participant who confirmed an email
+/// address; returns how many.
+async fn enqueue_invitations(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    revision: i32,
+    slots: &[(DateTime<Utc>, DateTime<Utc>)],
+    organizer_email: &str,
+    templates: &Templates,
+    now: DateTime<Utc>,
+) -> AppResult<usize> {
+    let event = db::events::invitation_details(&mut *conn, event_id).await?;
+    let organizer_name = db::participants::organizer_name(&mut *conn, event_id).await?;
+    let invitees = db::participants::attendees(&mut *conn, event_id).await?;
+
+    let invitation = itip::Invitation {
+        public_token: &event.public_token,
+        title: &event.title,
+        description: event.description.as_deref(),
+        organizer_name: &organizer_name,
+        organizer_email,
+        time_zone: event.time_zone.as_deref(),
+        revision,
+        slots,
+    };
+    let mail = templates.render(
+        Notification::Invitation,
+        &event.locale,
+        &Params::new().text("title", event.title.as_str()).list(
+            "slots",
+            slots
+                .iter()
+                .map(|(start_at, end_at)| summary::format_span(*start_at, *end_at))
+                .collect(),
+        ),
+    );
+
+    for invitee in &invitees {
+        let calendar = itip::render_request(
+            &invitation,
+            &itip::Attendee {
+                name: &invitee.name,
+                email: &invitee.email,
+            },
+            now,
+        );
+        mail::enqueue(
+            &mut *conn,
+            mail::NewMail {
+                event_id: Some(event_id),
+                participant_id: Some(invitee.id),
+                recipient: &invitee.email,
+                subject: &mail.subject,
+                body: &mail.text,
+                html: Some(&mail.html),
+                calendar: Some(("REQUEST", &calendar)),
+            },
+        )
+        .await?;
+    }
+
+    Ok(invitees.len())
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{organizer_token}/invitations",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 200, description = "Delivery of each participant's latest calendar invitation", body = InvitationListResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn list_invitations(
+    State(pool): State<PgPool>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<Json<InvitationListResponse>> {
+    let (event_id, revision) = db::events::revision_by_organizer_token(&pool, &organizer_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // Same invitees as `enqueue_invitations`, each with their newest REQUEST
+    let rows = db::participants::invitations(&pool, event_id).await?;
+
+    let invitations = rows
+        .into_iter()
+        .map(|row| {
+            let status = match (row.attempts, row.sent_at) {
+                (None, _) => InvitationStatus::NotSent,
+                (Some(_), Some(_)) => InvitationStatus::Sent,
+                (Some(attempts), None) if attempts >= mail::MAX_ATTEMPTS => {
+                    InvitationStatus::Failed
+                }
+                (Some(_), None) => InvitationStatus::Queued,
+            };
+            InvitationDelivery {
+                participant_id: row.id,
+                participant_name: row.name,
+                email: row.email,
+                status,
+                attempts: row.attempts.unwrap_or(0),
+                last_error: row.last_error,
+                queued_at: row.queued_at,
+                sent_at: row.sent_at,
+                rsvp_status: row.rsvp_status,
+            }
+        })
+        .collect();
+
+    Ok(Json(InvitationListResponse {
+        revision,
+        invitations,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/remind",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 200, description = "Reminders queued", body = RemindParticipantsResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "No reminder channel configured, or the event is archived", body = ErrorResponse),
+        (status = 429, description = "Daily reminder limit reached for this event", body = ErrorResponse)
+    )
+)]
+pub async fn remind_participants(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(templates): State<SharedTemplates>,
+    State(config): State<Arc<Config>>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<Json<RemindParticipantsResponse>> {
+    // Email is the only channel so far
+    if config.mail_from.is_none() {
+        return Err(AppError::Conflict(
+            "No reminder channel is configured on this server".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+    let now = clock.now();
+
+    let recent =
+        db::reminders::count_since(&mut transaction, event_id, now - chrono::Duration::days(1))
+            .await?;
+    if recent >= MAX_REMINDERS_PER_DAY {
+        return Err(AppError::TooManyRequests(
+            "Reminders were already sent for this event today".to_string(),
+        ));
+    }
+
+    let event = db::events::reminder_details(&mut transaction, event_id).await?;
+    let finalized = event.state == EventState::Finalized;
+
+    let recipients = db::reminders::recipients(&mut transaction, event_id, finalized).await?;
+
+    // Invitees are asked for availability only, and only while no
+    // participant holding the address has answered or is reminded above
+    let invitees = if finalized {
+        Vec::new()
+    } else {
+        db::reminders::silent_invitees(&mut transaction, event_id).await?
+    };
+
+    let notification = if finalized {
+        Notification::RsvpReminder
+    } else {
+        Notification::AvailabilityReminder
+    };
+    let mail = templates.render(
+        notification,
+        &event.locale,
+        &Params::new().text("title", event.title.as_str()),
+    );
+
+    let addresses = recipients
+        .iter()
+        .map(|recipient| recipient.email.as_str())
+        .chain(invitees.iter().map(String::as_str));
+    for address in addresses {
+        mail::enqueue(
+            &mut transaction,
+            mail::NewMail {
+                event_id: Some(event_id),
+                participant_id: None,
+                recipient: address,
+                subject: &mail.subject,
+                body: &mail.text,
+                html: Some(&mail.html),
+                calendar: None,
+            },
+        )
+        .await?;
+    }
+
+    // An empty batch does not use up the day's reminder
+    let queued = recipients.len() + invitees.len();
+    if queued > 0 {
+        db::reminders::record(
+            &mut transaction,
+            event_id,
+            queued as i32,
+            invitees.len() as i32,
+            now,
+        )
+        .await?;
+        audit::record(
+            &mut transaction,
+            event_id,
+            "reminders_sent",
+            "organizer",
+            Some(&queued.to_string()),
+            now,
+        )
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(Json(RemindParticipantsResponse {
+        queued,
+        recipients: recipients.into_iter().map(|r| r.name).collect(),
+        invitees: invitees.len(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{organizer_token}/reminders",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 200, description = "Reminder batches sent for the event", body = ReminderLogResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn list_reminders(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<Json<ReminderLogResponse>> {
+    let event_id = db::events::id_by_organizer_token(&pool, &organizer_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let reminders = db::reminders::batches(&pool, event_id).await?;
+
+    // The batch that used up the rolling day's allowance decides the wait
+    let window = chrono::Duration::days(1);
+    let now = clock.now();
+    let next_reminder_at = reminders
+        .get(MAX_REMINDERS_PER_DAY as usize - 1)
+        .map(|batch| batch.sent_at + window)
+        .filter(|at| *at > now);
+
+    Ok(Json(ReminderLogResponse {
+        reminders,
+        next_reminder_at,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/archive",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 200, description = "Event archived; it is now read-only", body = ArchiveEventResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Event is not finalized or already archived", body = ErrorResponse)
+    )
+)]
+pub async fn archive_event(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<Json<ArchiveEventResponse>> {
+    let mut transaction = pool.begin().await?;
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+
+    let now = clock.now();
+    let state = db::events::archive(&mut transaction, event_id, now)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Only finalized events can be archived".to_string()))?;
+
+    audit::record(
+        &mut transaction,
+        event_id,
+        "archived",
+        "organizer",
+        None,
+        now,
+    )
+    .await?;
+
+    transaction.commit().await?;
+    cache.invalidate(event_id);
+
+    Ok(Json(ArchiveEventResponse { state }))
+}
+
+/// Lease length for the slot-editing lock; holders renew by re-posting.
+pub(crate) const EDIT_LOCK_TTL_SECONDS: i64 = 120;
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/edit-lock",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    request_body = AcquireEditLockRequest,
+    responses(
+        (status = 200, description = "Lease acquired or renewed", body = EditLock),
+        (status = 400, description = "Invalid holder name", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Another co-host holds the lease, or the event is archived", body = ErrorResponse)
+    )
+)]
+pub async fn acquire_edit_lock(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Path(organizer_token): Path<String>,
+    Json(payload): Json<AcquireEditLockRequest>,
+) -> AppResult<Json<EditLock>> {
+    let holder_name = payload.holder_name.trim();
+    if holder_name.is_empty() || holder_name.len() > MAX_NAME_LENGTH {
+        return Err(AppError::BadRequest(
+            "Holder name is required and must be less than 50 characters".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+
+    let now = clock.now();
+    let current = db::edit_locks::current(&mut *transaction, event_id, now).await?;
+
+    // Renewing keeps the original acquisition time
+    let acquired_at = match current {
+        Some(lock) if lock.holder_name != holder_name => {
+            return Err(AppError::Conflict(format!(
+                "{} is currently editing",
+                lock.holder_name
+            )));
+        }
+        Some(lock) => lock.acquired_at,
+        None => now,
+    };
+    let expires_at = now + chrono::Duration::seconds(EDIT_LOCK_TTL_SECONDS);
+
+    let lock = db::edit_locks::hold(
+        &mut transaction,
+        event_id,
+        holder_name,
+        acquired_at,
+        expires_at,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(Json(lock))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/events/{organizer_token}/edit-lock",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 204, description = "Lease released"),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
+    )
+)]
+pub async fn release_edit_lock(
+    State(pool): State<PgPool>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<StatusCode> {
+    let mut transaction = pool.begin().await?;
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+
+    db::edit_locks::release(&mut transaction, event_id).await?;
+
+    transaction.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// How long a claim link stays redeemable.
+pub(crate) const TRANSFER_CLAIM_TTL_HOURS: i64 = 72;
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/transfer",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 200, description = "Claim link issued", body = TransferOwnershipResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
+    )
+)]
+pub async fn transfer_ownership(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<Json<TransferOwnershipResponse>> {
+    let mut transaction = pool.begin().await?;
+
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+
+    // Only the latest claim link is valid
+    db::transfers::revoke_pending(&mut transaction, event_id).await?;
+
+    let now = clock.now();
+    let expires_at = now + chrono::Duration::hours(TRANSFER_CLAIM_TTL_HOURS);
+
+    let claim_token = 'insert: {
+        for _ in 0..tokens::MAX_ATTEMPTS {
+            let claim_token = tokens::generate();
+            if db::transfers::insert(&mut transaction, event_id, &claim_token, expires_at, now)
+                .await?
+            {
+                break 'insert claim_token;
+            }
+        }
+        return Err(tokens::exhausted().into());
+    };
+
+    audit::record(
+        &mut transaction,
+        event_id,
+        "ownership_transfer_requested",
+        "organizer",
+        None,
+        now,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(Json(TransferOwnershipResponse {
+        claim_token,
+        expires_at,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/claim/{claim_token}",
+    tag = "organizer",
+    params(("claim_token" = String, Path, description = "One-time claim token")),
+    request_body = ClaimOwnershipRequest,
+    responses(
+        (status = 200, description = "Caller is now the organizer", body = ClaimOwnershipResponse),
+        (status = 400, description = "Invalid organizer name", body = ErrorResponse),
+        (status = 404, description = "Claim link unknown, used or expired", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
+    )
+)]
+pub async fn claim_ownership(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    Path(claim_token): Path<String>,
+    payload: Option<Json<ClaimOwnershipRequest>>,
+) -> AppResult<Json<ClaimOwnershipResponse>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+
+    if let Some(ref name) = payload.organizer_name
+        && (name.trim().is_empty() || name.len() > MAX_NAME_LENGTH)
+    {
+        return Err(AppError::BadRequest(
+            "Organizer name is required and must be less than 50 characters".to_string(),
+        ));
+    }
+
+    let now = clock.now();
+    let mut transaction = pool.begin().await?;
+
+    let (transfer_id, event_id) =
+        db::transfers::lock_redeemable(&mut transaction, &claim_token, now)
+            .await?
+            .ok_or_else(|| AppError::NotFound)?;
+
+    let state = db::events::lock_by_id(&mut transaction, event_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound)?;
+    ensure_not_archived(state)?;
+
+    db::transfers::redeem(&mut transaction, transfer_id, now).await?;
+
+    // Mint a new organizer token; the old one stops resolving immediately
+    let (organizer_token, public_token) = 'update: {
+        for _ in 0..tokens::MAX_ATTEMPTS {
+            let organizer_token = tokens::generate();
+            if let Some(public_token) = db::events::rotate_organizer_token(
+                &mut transaction,
+                event_id,
+                &organizer_token,
+                now,
+            )
+            .await?
+            {
+                break 'update (organizer_token, public_token);
+            }
+        }
+        return Err(tokens::exhausted().into());
+    };
+
+    if let Some(ref name) = payload.organizer_name {
+        db::participants::rename_organizer(&mut transaction, event_id, name.trim(), now).await?;
+    }
+
+    audit::record(
+        &mut transaction,
+        event_id,
+        "ownership_transferred",
+        payload.organizer_name.as_deref().unwrap_or("new organizer"),
+        None,
+        now,
+    )
+    .await?;
+
+    transaction.commit().await?;
+    cache.invalidate(event_id);
+
+    Ok(Json(ClaimOwnershipResponse {
+        public_token,
+        organizer_token,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{public_token}/participants/{participant_token}",
+    tag = "participants",
+    params(
+        ("public_token" = String, Path, description = "Public event token"),
+        ("participant_token" = Uuid, Path, description = "Participant edit token")
+    ),
+    responses(
+        (status = 200, description = "Participant submission", body = ParticipantResponse),
+        (status = 404, description = "Event or participant not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_participant(
+    State(pool): State<PgPool>,
+    State(keys): State<SharedKeyring>,
+    Path((public_token, participant_token)): Path<(String, Uuid)>,
+) -> AppResult<Json<ParticipantResponse>> {
+    // 1. Verify Event exists
+    let event_id = db::events::id_by_public_token(&pool, &public_token)
+        .await?
+        .ok_or_else(|| AppError::NotFound)?;
+
+    // 2. Fetch Participant using TOKEN (ensure it belongs to this event)
+    let participant = db::participants::find_by_token(&pool, event_id, participant_token)
+        .await?
+        .ok_or_else(|| AppError::NotFound)?;
+
+    // 3. Fetch Availabilities using internal ID
+    let availabilities = db::availabilities::for_participant(&pool, participant.id).await?;
+
+    Ok(Json(ParticipantResponse {
+        participant_token, // Corrected field name
+        name: participant.name,
+        comment: keys.open_opt(participant.comment)?,
+        buffer_minutes: participant.buffer_minutes,
+        time_zone: participant.time_zone,
+        availabilities,
+        email: participant.email,
+        rsvp_status: participant.rsvp_status,
+        role: participant.role,
+        status: participant.status,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/events/{public_token}/participants/{participant_token}",
+    tag = "participants",
+    params(
+        ("public_token" = String, Path, description = "Public event token"),
+        ("participant_token" = Uuid, Path, description = "Participant edit token")
+    ),
+    request_body = UpdateParticipantRequest,
+    responses(
+        (status = 200, description = "Participant updated"),
+        (status = 400, description = "Invalid input or event closed", body = ErrorResponse),
+        (status = 404, description = "Event or participant not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
+    )
+)]
+#[allow(clippy::too_many_arguments)] // one per extractor
+pub async fn update_participant(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    State(config): State<Arc<Config>>,
+    State(keys): State<SharedKeyring>,
+    State(templates): State<SharedTemplates>,
+    State(hub): State<SharedHub>,
+    Path((public_token, participant_token)): Path<(String, Uuid)>,
+    Json(mut payload): Json<UpdateParticipantRequest>,
+) -> AppResult<()> {
+    payload.time_zone = validate_participant_fields(
+        &payload.participant_name,
+        payload.comment.as_deref(),
+        payload.time_zone.as_deref(),
+        &payload.email,
+        &payload.availabilities,
+    )?;
+    let buffer_minutes = validate_buffer_minutes(payload.buffer_minutes)?;
+
+    let mut transaction = pool.begin().await?;
+
+    // 1. Verify Event
+    let event = lock_submission_target(&mut transaction, &public_token).await?;
+
+    ensure_not_archived(event.state)?;
+    if event.state != EventState::Open {
+        return Err(AppError::BadRequest(
+            "Cannot update participation for a closed event".to_string(),
+        ));
+    }
+
+    // 2. Verify Participant ownership using TOKEN and get internal ID
+    let participant =
+        db::participants::lock_by_token(&mut transaction, event.id, participant_token)
+            .await?
+            .ok_or_else(|| AppError::NotFound)?;
+
+    let id = participant.id;
+    let confirmed = participant.status == ParticipantStatus::Confirmed;
+    validate_participant_role(&mut transaction, event.id, payload.role.as_deref()).await?;
+
+    // 3. Update Participant details; a changed address needs confirming again
+    let comment = keys.seal_opt(payload.comment.as_deref());
+    let answer = db::participants::Answer {
+        name: &payload.participant_name,
+        comment: comment.as_deref(),
+        buffer_minutes,
+        time_zone: payload.time_zone.as_deref(),
+        email: payload.email.as_deref(),
+        role: payload.role.as_deref(),
+    };
+    db::participants::update_answer(&mut transaction, id, &answer, clock.now()).await?;
+    if let Some(verifier) = Verifier::new(&config, &templates) {
+        verifier.request(&mut transaction, id, clock.now()).await?;
+    }
+
+    // 4. Update Availabilities (using internal ID)
+    db::availabilities::delete_for_participant(&mut transaction, id).await?;
+
+    let availabilities = with_ticked_dates(
+        payload.availabilities,
+        &payload.dates,
+        PollType::from_db(&event.poll_type),
+        event.time_zone.as_deref(),
+    )?;
+    let availabilities = clip_locked(
+        availabilities,
+        &locked_slot_ranges(&mut transaction, event.id).await?,
+    );
+    let merged = merge_time_ranges(availabilities);
+    db::availabilities::insert(&mut transaction, id, &merged).await?;
+    history::record(&mut transaction, id, history::PARTICIPANT, clock.now()).await?;
+    audit::record(
+        &mut transaction,
+        event.id,
+        "participant_updated",
+        &payload.participant_name,
+        None,
+        clock.now(),
+    )
+    .await?;
+
+    quorum::close_if_reached(
+        &mut transaction,
+        event.id,
+        &templates,
+        config.public_url.as_deref(),
+        clock.now(),
+    )
+    .await?;
+
+    transaction.commit().await?;
+    cache.invalidate(event.id);
+
+    if confirmed && !participant.is_hidden {
+        hub.publish(
+            event.id,
+            RealtimeMessage::Availability {
+                participant_name: payload.participant_name,
+                role: payload.role,
+                availabilities: merged,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/participants/verify-email",
+    tag = "participants",
+    params(VerifyEmailQuery),
+    responses(
+        (status = 200, description = "Address confirmed; invitations and reminders will reach it", body = EmailVerifiedResponse),
+        (status = 404, description = "Link unknown, already used or expired, or the address was changed since", body = ErrorResponse)
+    )
+)]
+pub async fn verify_participant_email(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Query(query): Query<VerifyEmailQuery>,
+) -> AppResult<Json<EmailVerifiedResponse>> {
+    let mut transaction = pool.begin().await?;
+    let confirmed = verification::confirm(&mut transaction, &query.token, clock.now())
+        .await?
+        .ok_or(AppError::NotFound)?;
+    transaction.commit().await?;
+
+    Ok(Json(EmailVerifiedResponse {
+        email: confirmed.email,
+        title: confirmed.title,
+        public_token: confirmed.public_token,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{organizer_token}/participants/{participant_id}/history",
+    tag = "organizer",
+    params(
+        ("organizer_token" = String, Path, description = "Organizer token"),
+        ("participant_id" = i64, Path, description = "`id` of a participant in the organizer view")
+    ),
+    responses(
+        (status = 200, description = "Availability versions with what each added and removed, newest first", body = AvailabilityHistoryResponse),
+        (status = 404, description = "Event or participant not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_participant_history(
+    State(pool): State<PgPool>,
+    Path((organizer_token, participant_id)): Path<(String, i64)>,
+) -> AppResult<Json<AvailabilityHistoryResponse>> {
+    let participant_name =
+        db::participants::name_for_organizer(&pool, &organizer_token, participant_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+    Ok(Json(AvailabilityHistoryResponse {
+        participant_name,
+        versions: history::versions(&pool, participant_id).await?,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{organizer_token}/audit",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 200, description = "What happened to the event and who did it, newest first", body = AuditLogResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_event_audit(
+    State(pool): State<PgPool>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<Json<AuditLogResponse>> {
+    let event_id = db::events::id_by_organizer_token(&pool, &organizer_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(AuditLogResponse {
+        entries: audit::entries(&pool, event_id).await?,
+    }))
+}
+
+async fn fetch_waitlist(
+    conn: &mut PgConnection,
+    keys: &Keyring,
+    event_id: Uuid,
+) -> AppResult<WaitlistResponse> {
+    let confirmed = db::participants::status_counts(&mut *conn, event_id)
+        .await?
+        .confirmed;
+    let mut pending = db::participants::waitlist(&mut *conn, event_id).await?;
+    for entry in &mut pending {
+        entry.comment = keys.open_opt(entry.comment.take())?;
+    }
+
+    Ok(WaitlistResponse {
+        confirmed,
+        max_participants: MAX_PARTICIPANTS,
+        pending,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/{organizer_token}/waitlist",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    responses(
+        (status = 200, description = "Submissions waiting for approval, oldest first", body = WaitlistResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_waitlist(
+    State(pool): State<PgPool>,
+    State(keys): State<SharedKeyring>,
+    Path(organizer_token): Path<String>,
+) -> AppResult<Json<WaitlistResponse>> {
+    let event_id = db::events::id_by_organizer_token(&pool, &organizer_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let mut conn = pool.acquire().await?;
+    Ok(Json(fetch_waitlist(&mut conn, &keys, event_id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{organizer_token}/waitlist/{participant_id}",
+    tag = "organizer",
+    params(
+        ("organizer_token" = String, Path, description = "Organizer token"),
+        ("participant_id" = i64, Path, description = "`id` of a waitlist entry")
+    ),
+    request_body = ModerateParticipantRequest,
+    responses(
+        (status = 200, description = "Decision applied; the remaining waitlist", body = WaitlistResponse),
+        (status = 400, description = "`replaces` is not a confirmed participant, or given with `reject`", body = ErrorResponse),
+        (status = 404, description = "Event or waitlist entry not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
+    )
+)]
+#[allow(clippy::too_many_arguments)] // one per extractor
+pub async fn moderate_participant(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    State(config): State<Arc<Config>>,
+    State(keys): State<SharedKeyring>,
+    State(templates): State<SharedTemplates>,
+    State(hub): State<SharedHub>,
+    Path((organizer_token, participant_id)): Path<(String, i64)>,
+    Json(payload): Json<ModerateParticipantRequest>,
+) -> AppResult<Json<WaitlistResponse>> {
+    let mut transaction = pool.begin().await?;
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+
+    let (participant_name, participant_role) =
+        db::participants::find_pending(&mut transaction, event_id, participant_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+    // The approved participant's ranges, for the live grid
+    let approved = match payload.decision {
+        ModerationDecision::Reject => {
+            if payload.replaces.is_some() {
+                return Err(AppError::BadRequest(
+                    "replaces only applies when approving".to_string(),
+                ));
+            }
+            db::participants::delete(&mut transaction, participant_id).await?;
+            None
+        }
+        ModerationDecision::Approve => {
+            if let Some(replaces) = payload.replaces
+                && !db::participants::delete_confirmed(&mut transaction, event_id, replaces).await?
+            {
+                return Err(AppError::BadRequest(
+                    "replaces must be a confirmed participant other than the organizer".to_string(),
+                ));
+            }
+            db::participants::confirm(&mut transaction, participant_id, clock.now()).await?;
+            quorum::close_if_reached(
+                &mut transaction,
+                event_id,
+                &templates,
+                config.public_url.as_deref(),
+                clock.now(),
+            )
+            .await?;
+
+            Some(db::availabilities::for_participant(&mut *transaction, participant_id).await?)
+        }
+    };
+
+    let waitlist = fetch_waitlist(&mut transaction, &keys, event_id).await?;
+
+    transaction.commit().await?;
+    cache.invalidate(event_id);
+
+    if let Some(availabilities) = approved {
+        hub.publish(
+            event_id,
+            RealtimeMessage::Availability {
+                participant_name,
+                role: participant_role,
+                availabilities,
+            },
+        );
+    }
+
+    Ok(Json(waitlist))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/events/{organizer_token}/participants/{participant_id}",
+    tag = "organizer",
+    params(
+        ("organizer_token" = String, Path, description = "Organizer token"),
+        ("participant_id" = i64, Path, description = "`id` of a participant or waitlist entry")
+    ),
+    responses(
+        (status = 204, description = "Participant removed with their availability"),
+        (status = 400, description = "The organizer cannot be removed", body = ErrorResponse),
+        (status = 404, description = "Event or participant not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
+    )
+)]
+pub async fn delete_participant(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    State(hub): State<SharedHub>,
+    Path((organizer_token, participant_id)): Path<(String, i64)>,
+) -> AppResult<StatusCode> {
+    let mut transaction = pool.begin().await?;
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
+
+    let participant =
+        db::participants::find_for_removal(&mut transaction, event_id, participant_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+    if participant.is_organizer {
         return Err(AppError::BadRequest(
-            "Comment must be less than 500 characters".to_string(),
+            "The organizer cannot be removed".to_string(),
         ));
     }
 
-    // Validate time ranges
-    for range in &payload.availabilities {
-        if range.start_at >= range.end_at {
-            return Err(AppError::BadRequest(
-                "Invalid time range: start must be before end".to_string(),
-            ));
-        }
-    }
+    db::participants::delete(&mut transaction, participant_id).await?;
+
+    let now = clock.now();
+    audit::record(
+        &mut transaction,
+        event_id,
+        "participant_removed",
+        "organizer",
+        Some(&participant.name),
+        now,
+    )
+    .await?;
+    webhooks::enqueue(
+        &mut transaction,
+        event_id,
+        WebhookEvent::ParticipantRemoved,
+        json!({
+            "participant_name": participant.name,
+            "status": participant.status,
+        }),
+        now,
+    )
+    .await?;
+
+    transaction.commit().await?;
+    cache.invalidate(event_id);
 
-    let mut transaction = pool.begin().await?;
+    // The waitlist never reached the live grid
+    if participant.status == ParticipantStatus::Confirmed {
+        hub.publish(
+            event_id,
+            RealtimeMessage::ParticipantRemoved {
+                participant_name: participant.name,
+            },
+        );
+    }
 
-    let event_id = sqlx::query_scalar!(
-        "SELECT id FROM events WHERE public_token = $1",
-        public_token
-    )
-    .fetch_optional(&mut *transaction)
-    .await?
-    .ok_or_else(|| AppError::NotFound)?;
+    Ok(StatusCode::NO_CONTENT)
+}
 
-    // Check participant limit
-    let count = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM participants WHERE event_id = $1",
-        event_id
+#[utoipa::path(
+    put,
+    path = "/events/{organizer_token}/participants/order",
+    tag = "organizer",
+    params(("organizer_token" = String, Path, description = "Organizer token")),
+    request_body = ParticipantOrderRequest,
+    responses(
+        (status = 200, description = "Preferences saved; participants in their new order", body = ParticipantOrderResponse),
+        (status = 400, description = "Unknown or repeated participant", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
     )
-    .fetch_one(&mut *transaction)
-    .await?
-    .unwrap_or(0);
+)]
+pub async fn update_participant_order(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    Path(organizer_token): Path<String>,
+    Json(payload): Json<ParticipantOrderRequest>,
+) -> AppResult<Json<ParticipantOrderResponse>> {
+    let mut transaction = pool.begin().await?;
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
 
-    if count >= 10 {
-        return Err(AppError::ParticipantLimitReached(10));
+    // The waitlist never shows up in results, so there is nothing to arrange
+    let participant_ids: HashSet<i64> = db::participants::confirmed_ids(&mut transaction, event_id)
+        .await?
+        .into_iter()
+        .collect();
+    for (field, ids) in [("order", &payload.order), ("hidden", &payload.hidden)] {
+        let mut seen = HashSet::new();
+        for &id in ids {
+            if !participant_ids.contains(&id) {
+                return Err(AppError::BadRequest(format!("Unknown participant {}", id)));
+            }
+            if !seen.insert(id) {
+                return Err(AppError::BadRequest(format!(
+                    "Participant {} is listed twice in `{}`",
+                    id, field
+                )));
+            }
+        }
     }
 
-    // Insert new participant (Always insert, allowing duplicates)
-    // We need to return both id (for internal FK) and token (for external client)
-    let participant = sqlx::query!(
-        "INSERT INTO participants (event_id, name, is_organizer, comment) VALUES ($1, $2, $3, $4) RETURNING id, token",
+    db::participants::set_display_preferences(
+        &mut transaction,
+        event_id,
+        &payload.order,
+        &payload.hidden,
+    )
+    .await?;
+    // Moves the ETag of public reads, which never see the columns themselves
+    db::events::touch(&mut transaction, event_id, clock.now()).await?;
+    audit::record(
+        &mut transaction,
         event_id,
-        payload.participant_name,
-        false, // Default is not organizer
-        payload.comment
+        "participants_arranged",
+        "organizer",
+        Some(&format!(
+            "{} pinned, {} hidden",
+            payload.order.len(),
+            payload.hidden.len()
+        )),
+        clock.now(),
     )
-    .fetch_one(&mut *transaction)
     .await?;
+    transaction.commit().await?;
+    cache.invalidate(event_id);
 
-    let id = participant.id;
-    let participant_token = participant.token;
+    let participants = db::participants::confirmed_with_availability(&pool, event_id)
+        .await?
+        .into_iter()
+        .map(|participant| ParticipantDisplay {
+            id: participant.id,
+            name: participant.name,
+            display_order: participant.display_order,
+            is_hidden: participant.is_hidden,
+        })
+        .collect();
+    Ok(Json(ParticipantOrderResponse { participants }))
+}
 
-    sqlx::query!("DELETE FROM availabilities WHERE participant_id = $1", id)
-        .execute(&mut *transaction)
-        .await?;
+#[utoipa::path(
+    patch,
+    path = "/events/{organizer_token}/slots/{slot_id}",
+    tag = "organizer",
+    params(
+        ("organizer_token" = String, Path, description = "Organizer token"),
+        ("slot_id" = i64, Path, description = "`id` from `event_slots`")
+    ),
+    request_body = UpdateSlotRequest,
+    responses(
+        (status = 200, description = "Slot updated", body = EventSlot),
+        (status = 404, description = "Event or slot not found", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
+    )
+)]
+pub async fn update_slot(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(cache): State<SharedResponseCache>,
+    Path((organizer_token, slot_id)): Path<(String, i64)>,
+    Json(payload): Json<UpdateSlotRequest>,
+) -> AppResult<Json<EventSlot>> {
+    let mut transaction = pool.begin().await?;
+    let event_id = lock_event_by_organizer_token(&mut transaction, &organizer_token).await?;
 
-    let merged_availabilities = merge_time_ranges(payload.availabilities);
+    // Responses already given stay; only new ones are clipped
+    let slot = db::events::set_slot_locked(&mut transaction, event_id, slot_id, payload.locked)
+        .await?
+        .ok_or(AppError::NotFound)?;
 
-    for range in merged_availabilities {
-        sqlx::query!(
-            "INSERT INTO availabilities (participant_id, start_at, end_at) VALUES ($1, $2, $3)",
-            id,
-            range.start_at,
-            range.end_at
-        )
-        .execute(&mut *transaction)
-        .await?;
-    }
+    let now = clock.now();
+    db::events::touch(&mut transaction, event_id, now).await?;
+    audit::record(
+        &mut transaction,
+        event_id,
+        if slot.locked {
+            "slot_locked"
+        } else {
+            "slot_unlocked"
+        },
+        "organizer",
+        Some(&summary::format_span(slot.start_at, slot.end_at)),
+        now,
+    )
+    .await?;
 
     transaction.commit().await?;
+    cache.invalidate(event_id);
 
-    Ok(Json(SubmitAvailabilityResponse { participant_token }))
+    Ok(Json(slot))
 }
 
-async fn fetch_event_results_data(
-    pool: &PgPool,
-    event_id: Uuid,
-) -> AppResult<(Vec<EventSlot>, Vec<ParticipantAvailability>, i64)> {
-    let event_slots = sqlx::query_as!(
-        EventSlot,
-        r#"
-        SELECT id, event_id, start_at, end_at
-        FROM event_slots
-        WHERE event_id = $1
-        ORDER BY start_at
-        "#,
-        event_id
+#[utoipa::path(
+    post,
+    path = "/events/batch-check",
+    tag = "events",
+    request_body = BatchCheckStatusRequest,
+    responses(
+        (status = 200, description = "State per known public token", body = BatchCheckStatusResponse),
+        (status = 400, description = "Too many tokens", body = ErrorResponse)
     )
-    .fetch_all(pool)
-    .await?;
+)]
+pub async fn check_events_status(
+    State(pool): State<PgPool>,
+    Json(payload): Json<BatchCheckStatusRequest>,
+) -> AppResult<Json<BatchCheckStatusResponse>> {
+    if payload.tokens.len() > MAX_BATCH_TOKENS {
+        return Err(AppError::BadRequest(
+            "Too many tokens to check (max 50)".to_string(),
+        ));
+    }
 
-    struct Row {
-        name: String,
-        is_organizer: bool,
-        comment: Option<String>, // Add comment field
-        start_at: Option<DateTime<Utc>>,
-        end_at: Option<DateTime<Utc>>,
+    if payload.tokens.is_empty() {
+        return Ok(Json(BatchCheckStatusResponse {
+            statuses: std::collections::HashMap::new(),
+        }));
     }
 
-    let rows = sqlx::query_as!(
-        Row,
-        r#"
-        SELECT p.name, p.is_organizer, p.comment, a.start_at, a.end_at
-        FROM participants p
-        LEFT JOIN availabilities a ON p.id = a.participant_id
-        WHERE p.event_id = $1
-        ORDER BY p.is_organizer DESC, p.created_at ASC, a.start_at
-        "#,
-        event_id
-    )
-    .fetch_all(pool)
-    .await?;
+    let statuses = db::events::states_by_public_token(&pool, &payload.tokens)
+        .await?
+        .into_iter()
+        .collect();
 
-    // We need to keep track of is_organizer and comment per participant
-    struct ParticipantData {
-        is_organizer: bool,
-        comment: Option<String>, // Add comment field
-        ranges: Vec<TimeRangeRequest>,
-    }
+    Ok(Json(BatchCheckStatusResponse { statuses }))
+}
 
-    let mut participants_map: std::collections::HashMap<String, ParticipantData> =
-        std::collections::HashMap::new();
+#[utoipa::path(
+    post,
+    path = "/events/conflicts",
+    tag = "organizer",
+    request_body = ConflictCheckRequest,
+    responses(
+        (status = 200, description = "Candidates of each event with the times they would double-book", body = ConflictCheckResponse),
+        (status = 400, description = "Too many tokens", body = ErrorResponse)
+    )
+)]
+pub async fn check_event_conflicts(
+    State(pool): State<PgPool>,
+    Json(payload): Json<ConflictCheckRequest>,
+) -> AppResult<Json<ConflictCheckResponse>> {
+    if payload.organizer_tokens.len() > MAX_BATCH_TOKENS {
+        return Err(AppError::BadRequest(
+            "Too many tokens to check (max 50)".to_string(),
+        ));
+    }
+    let limit = payload.limit.unwrap_or(5).clamp(1, MAX_SUGGESTIONS);
 
-    // Order needs to be preserved as fetched (Organizer first)
-    // HashMap doesn't preserve order. We should use a Vec and look up by index?
-    // Or just collect unique names in order first.
-    let mut participant_names: Vec<String> = Vec::new();
+    let rows = db::events::organized(&pool, &payload.organizer_tokens).await?;
 
-    for row in rows {
-        if !participants_map.contains_key(&row.name) {
-            participants_map.insert(
-                row.name.clone(),
-                ParticipantData {
-                    is_organizer: row.is_organizer,
-                    comment: row.comment.clone(), // Set comment
-                    ranges: Vec::new(),
-                },
-            );
-            participant_names.push(row.name.clone());
+    // Request order decides whose best time wins when two clash
+    let mut seen = HashSet::new();
+    let mut events = Vec::new();
+    for token in &payload.organizer_tokens {
+        if !seen.insert(token) {
+            continue;
         }
+        let Some(row) = rows.iter().find(|row| &row.organizer_token == token) else {
+            continue;
+        };
 
-        if let (Some(start), Some(end)) = (row.start_at, row.end_at)
-            && let Some(data) = participants_map.get_mut(&row.name)
-        {
-            data.ranges.push(TimeRangeRequest {
-                start_at: start,
-                end_at: end,
-            });
-        }
+        let final_slots = db::events::final_slots(&pool, row.id).await?;
+        // The organizer sees results even while a blind poll hides them
+        let suggestions = if row.state == EventState::Finalized {
+            Vec::new()
+        } else {
+            let (_, participants, _) =
+                fetch_event_results_data(&pool, row.id, row.time_zone.as_deref()).await?;
+            suggest_windows(
+                &participants,
+                row.slot_duration,
+                limit,
+                &db::events::roles(&pool, row.id).await?,
+                &db::events::finalization_rules(&pool, row.id).await?,
+            )
+        };
+        events.push((row, final_slots, suggestions));
     }
 
-    let total_participants = participants_map.len() as i64;
+    let as_range = |start_at, end_at| agreed_time_core::TimeRange::new(start_at, end_at);
+    let candidates: Vec<Vec<agreed_time_core::TimeRange>> = events
+        .iter()
+        .map(|(_, _, suggestions)| {
+            suggestions
+                .iter()
+                .map(|s| as_range(s.start_at, s.end_at))
+                .collect()
+        })
+        .collect();
+    let fixed: Vec<agreed_time_core::TimeRange> = events
+        .iter()
+        .flat_map(|(_, final_slots, _)| final_slots.iter().map(|f| as_range(f.start_at, f.end_at)))
+        .collect();
+    let choice = agreed_time_core::compatible_choice(&candidates, &fixed);
+
+    let response_events = events
+        .iter()
+        .enumerate()
+        .map(|(index, (row, final_slots, suggestions))| {
+            let candidates = suggestions
+                .iter()
+                .enumerate()
+                .map(|(candidate, suggestion)| {
+                    let range = as_range(suggestion.start_at, suggestion.end_at);
+                    let mut conflicts = Vec::new();
+                    for (other, (other_row, other_final, other_suggestions)) in
+                        events.iter().enumerate()
+                    {
+                        if other == index {
+                            continue;
+                        }
+                        let taken = other_final
+                            .iter()
+                            .map(|f| (f.start_at, f.end_at, true))
+                            .chain(
+                                other_suggestions
+                                    .iter()
+                                    .map(|s| (s.start_at, s.end_at, false)),
+                            );
+                        for (start_at, end_at, finalized) in taken {
+                            if range.overlaps(&as_range(start_at, end_at)) {
+                                conflicts.push(ConflictingTime {
+                                    public_token: other_row.public_token.clone(),
+                                    start_at,
+                                    end_at,
+                                    finalized,
+                                });
+                            }
+                        }
+                    }
+                    CandidateConflicts {
+                        suggestion: suggestion.clone(),
+                        conflicts,
+                        recommended: choice
+                            .as_ref()
+                            .is_some_and(|picks| picks[index] == Some(candidate)),
+                    }
+                })
+                .collect();
 
-    let participants: Vec<ParticipantAvailability> = participant_names
-        .into_iter()
-        .map(|name| {
-            let data = participants_map.remove(&name).unwrap();
-            ParticipantAvailability {
-                name,
-                is_organizer: data.is_organizer,
-                comment: data.comment, // Pass comment
-                availabilities: data.ranges,
+            EventConflicts {
+                public_token: row.public_token.clone(),
+                title: row.title.clone(),
+                state: row.state,
+                final_slots: final_slots.clone(),
+                candidates,
             }
         })
         .collect();
 
-    Ok((event_slots, participants, total_participants))
-}
-
-pub async fn get_event_results(
-    State(pool): State<PgPool>,
-    Path(public_token): Path<String>,
-) -> AppResult<Json<EventResultsResponse>> {
-    let event = sqlx::query_as!(
-        Event,
-        r#"
-        SELECT id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at
-        FROM events
-        WHERE public_token = $1
-        "#,
-        public_token
-    )
-    .fetch_optional(&pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound)?;
-
-    let (event_slots, participants, total_participants) =
-        fetch_event_results_data(&pool, event.id).await?;
-
-    Ok(Json(EventResultsResponse {
-        id: event.id,
-        title: event.title,
-        description: event.description,
-        time_zone: event.time_zone,
-        slot_duration: event.slot_duration,
-        state: event.state,
-        event_slots,
-        participants,
-        total_participants,
+    Ok(Json(ConflictCheckResponse {
+        events: response_events,
+        compatible: choice.is_some(),
     }))
 }
 
-pub async fn get_organizer_event(
+#[utoipa::path(
+    post,
+    path = "/participants/batch-check",
+    tag = "participants",
+    request_body = BatchCheckParticipantsRequest,
+    responses(
+        (status = 200, description = "Event and name per valid participant token", body = BatchCheckParticipantsResponse),
+        (status = 400, description = "Too many tokens", body = ErrorResponse)
+    )
+)]
+pub async fn check_participants_status(
     State(pool): State<PgPool>,
-    Path(organizer_token): Path<String>,
-) -> AppResult<Json<OrganizerEventResponse>> {
-    let event = sqlx::query_as!(
-        Event,
-        r#"
-        SELECT id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at
-        FROM events
-        WHERE organizer_token = $1
-        "#,
-        organizer_token
-    )
-    .fetch_optional(&pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound)?;
-
-    let (event_slots, participants, total_participants) =
-        fetch_event_results_data(&pool, event.id).await?;
-
-    Ok(Json(OrganizerEventResponse {
-        id: event.id,
-        public_token: event.public_token,
-        organizer_token: event.organizer_token,
-        title: event.title,
-        description: event.description,
-        time_zone: event.time_zone,
-        slot_duration: event.slot_duration,
-        state: event.state,
-        event_slots,
-        participants,
-        total_participants,
-        created_at: event.created_at,
-    }))
-}
+    Json(payload): Json<BatchCheckParticipantsRequest>,
+) -> AppResult<Json<BatchCheckParticipantsResponse>> {
+    if payload.tokens.len() > MAX_BATCH_TOKENS {
+        return Err(AppError::BadRequest(
+            "Too many tokens to check (max 50)".to_string(),
+        ));
+    }
 
-pub async fn close_event(
-    State(pool): State<PgPool>,
-    Path(organizer_token): Path<String>,
-) -> AppResult<Json<EventResponse>> {
-    let event = sqlx::query_as!(
-        Event,
-        r#"
-        UPDATE events
-        SET state = 'closed', updated_at = NOW()
-        WHERE organizer_token = $1
-        RETURNING id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at
-        "#,
-        organizer_token
-    )
-    .fetch_optional(&pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound)?;
-
-    // Need to fetch organizer name separately now
-    let organizer_name = sqlx::query_scalar!(
-        r#"
-        SELECT name
-        FROM participants
-        WHERE event_id = $1 AND is_organizer = true
-        LIMIT 1
-        "#,
-        event.id
-    )
-    .fetch_one(&pool)
-    .await?;
-
-    let event_slots = sqlx::query_as!(
-        EventSlot,
-        r#"
-        SELECT id, event_id, start_at, end_at
-        FROM event_slots
-        WHERE event_id = $1
-        ORDER BY start_at
-        "#,
-        event.id
-    )
-    .fetch_all(&pool)
-    .await?;
-
-    Ok(Json(EventResponse {
-        id: event.id,
-        title: event.title,
-        description: event.description,
-        time_zone: event.time_zone,
-        slot_duration: event.slot_duration,
-        state: event.state,
-        event_slots,
-        organizer_name,
-    }))
-}
+    // Stored links may be stale or corrupted; skip anything that isn't a UUID
+    let tokens: Vec<Uuid> = payload
+        .tokens
+        .iter()
+        .filter_map(|t| Uuid::parse_str(t).ok())
+        .collect();
 
-pub async fn get_participant(
-    State(pool): State<PgPool>,
-    Path((public_token, participant_token)): Path<(String, Uuid)>,
-) -> AppResult<Json<ParticipantResponse>> {
-    // 1. Verify Event exists
-    let event = sqlx::query!(
-        "SELECT id FROM events WHERE public_token = $1",
-        public_token
-    )
-    .fetch_optional(&pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound)?;
+    if tokens.is_empty() {
+        return Ok(Json(BatchCheckParticipantsResponse {
+            participants: std::collections::HashMap::new(),
+        }));
+    }
 
-    // 2. Fetch Participant using TOKEN (ensure it belongs to this event)
-    let participant = sqlx::query!(
-        "SELECT id, name, comment FROM participants WHERE token = $1 AND event_id = $2",
-        participant_token,
-        event.id
-    )
-    .fetch_optional(&pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound)?;
+    let rows = db::participants::participations(&pool, &tokens, false).await?;
 
-    // 3. Fetch Availabilities using internal ID
-    let availabilities = sqlx::query_as!(
-        TimeRangeRequest,
-        r#"
-        SELECT start_at, end_at
-        FROM availabilities
-        WHERE participant_id = $1
-        ORDER BY start_at
-        "#,
-        participant.id
-    )
-    .fetch_all(&pool)
-    .await?;
+    let mut participants = std::collections::HashMap::new();
+    for row in rows {
+        participants.insert(
+            row.token.to_string(),
+            ParticipantTokenStatus {
+                public_token: row.public_token,
+                name: row.name,
+                state: row.state,
+            },
+        );
+    }
 
-    Ok(Json(ParticipantResponse {
-        participant_token, // Corrected field name
-        name: participant.name,
-        comment: participant.comment,
-        availabilities,
-    }))
+    Ok(Json(BatchCheckParticipantsResponse { participants }))
 }
 
-pub async fn update_participant(
+#[utoipa::path(
+    get,
+    path = "/participants/aggregate",
+    tag = "participants",
+    params(ParticipantAggregateQuery),
+    responses(
+        (status = 200, description = "One person's availability across their polls", body = ParticipantAggregateResponse),
+        (status = 400, description = "Too many tokens", body = ErrorResponse)
+    )
+)]
+pub async fn aggregate_participant_availability(
     State(pool): State<PgPool>,
-    Path((public_token, participant_token)): Path<(String, Uuid)>,
-    Json(payload): Json<UpdateParticipantRequest>,
-) -> AppResult<()> {
-    // Validate inputs
-    if payload.participant_name.trim().is_empty() || payload.participant_name.len() > 50 {
+    Query(query): Query<ParticipantAggregateQuery>,
+) -> AppResult<Json<ParticipantAggregateResponse>> {
+    let requested: Vec<&str> = query
+        .tokens
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect();
+    if requested.len() > MAX_BATCH_TOKENS {
         return Err(AppError::BadRequest(
-            "Participant name is required and must be less than 50 characters".to_string(),
+            "Too many tokens to check (max 50)".to_string(),
         ));
     }
+    let mut tokens: Vec<Uuid> = Vec::new();
+    for token in requested.iter().filter_map(|t| Uuid::parse_str(t).ok()) {
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
 
-    if let Some(ref comment) = payload.comment
-        && comment.len() > 500
-    {
-        return Err(AppError::BadRequest(
-            "Comment must be less than 500 characters".to_string(),
+    // Weekly events have no dates to line up
+    let rows = db::participants::participations(&pool, &tokens, true).await?;
+
+    let as_range = |start_at, end_at| agreed_time_core::TimeRange::new(start_at, end_at);
+    let mut events = Vec::new();
+    let mut offered = Vec::new();
+    let mut busy = Vec::new();
+    for token in &tokens {
+        let Some(row) = rows.iter().find(|row| &row.token == token) else {
+            continue;
+        };
+        let availabilities = db::availabilities::for_participant(&pool, row.id).await?;
+        let final_slots = db::events::final_slots(&pool, row.event_id).await?;
+
+        offered.push(agreed_time_core::merge_time_ranges(
+            availabilities
+                .iter()
+                .map(|a| as_range(a.start_at, a.end_at))
+                .collect(),
         ));
+        busy.extend(final_slots.iter().map(|f| as_range(f.start_at, f.end_at)));
+        events.push(AggregatedParticipation {
+            participant_token: row.token.to_string(),
+            public_token: row.public_token.clone(),
+            title: row.title.clone(),
+            name: row.name.clone(),
+            state: row.state,
+            availabilities,
+            final_slots,
+        });
     }
 
-    for range in &payload.availabilities {
-        if range.start_at >= range.end_at {
-            return Err(AppError::BadRequest("Invalid time range".to_string()));
+    let busy = agreed_time_core::merge_time_ranges(busy);
+    let free = agreed_time_core::subtract_time_ranges(
+        agreed_time_core::merge_time_ranges(offered.concat()),
+        &busy,
+    );
+
+    // Within each doubly offered region, split wherever one poll's offer
+    // starts or ends so every span names exactly the polls that offered it
+    let mut overlaps: Vec<AvailabilityOverlap> = Vec::new();
+    let doubled = agreed_time_core::covered_by_at_least(&agreed_time_core::coverage(&offered), 2);
+    for region in doubled {
+        let mut cuts: Vec<DateTime<Utc>> = offered
+            .iter()
+            .flatten()
+            .flat_map(|r| [r.start_at, r.end_at])
+            .filter(|t| region.start_at < *t && *t < region.end_at)
+            .chain([region.start_at, region.end_at])
+            .collect();
+        cuts.sort();
+        cuts.dedup();
+        for pair in cuts.windows(2) {
+            let span = as_range(pair[0], pair[1]);
+            let public_tokens: Vec<String> = offered
+                .iter()
+                .zip(&events)
+                .filter(|(ranges, _)| ranges.iter().any(|r| r.contains(&span)))
+                .map(|(_, event)| event.public_token.clone())
+                .collect();
+            match overlaps.last_mut() {
+                Some(last)
+                    if last.end_at == span.start_at && last.public_tokens == public_tokens =>
+                {
+                    last.end_at = span.end_at;
+                }
+                _ => overlaps.push(AvailabilityOverlap {
+                    start_at: span.start_at,
+                    end_at: span.end_at,
+                    public_tokens,
+                }),
+            }
         }
     }
 
-    let mut transaction = pool.begin().await?;
+    let plain = |ranges: Vec<agreed_time_core::TimeRange>| {
+        ranges
+            .into_iter()
+            .map(|r| TimeRangeRequest {
+                start_at: r.start_at,
+                end_at: r.end_at,
+                availability_level: None,
+            })
+            .collect()
+    };
+    Ok(Json(ParticipantAggregateResponse {
+        events,
+        free: plain(free),
+        busy: plain(busy),
+        overlaps,
+    }))
+}
 
-    // 1. Verify Event
-    let event = sqlx::query!(
-        "SELECT id, state FROM events WHERE public_token = $1",
-        public_token
-    )
-    .fetch_optional(&mut *transaction)
-    .await?
-    .ok_or_else(|| AppError::NotFound)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::events::ResultsAccess;
+    use chrono::TimeZone;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
 
-    if event.state == "closed" {
-        return Err(AppError::BadRequest(
-            "Cannot update participation for a closed event".to_string(),
-        ));
+    const PUBLIC_TOKEN: &str = "abcdefghijkl";
+
+    /// One event in memory; counts the reads past the lookup, so tests can
+    /// tell which requests were answered early.
+    struct MockStore {
+        password_hash: Option<String>,
+        results_visibility: ResultsVisibility,
+        participant_token: Uuid,
+        reads: AtomicUsize,
     }
 
-    // 2. Verify Participant ownership using TOKEN and get internal ID
-    let participant = sqlx::query!(
-        "SELECT id FROM participants WHERE token = $1 AND event_id = $2",
-        participant_token,
-        event.id
-    )
-    .fetch_optional(&mut *transaction)
-    .await?
-    .ok_or_else(|| AppError::NotFound)?;
+    impl MockStore {
+        fn new() -> Self {
+            MockStore {
+                password_hash: None,
+                results_visibility: ResultsVisibility::Public,
+                participant_token: Uuid::new_v4(),
+                reads: AtomicUsize::new(0),
+            }
+        }
 
-    let id = participant.id;
+        fn at(hour: u32) -> DateTime<Utc> {
+            Utc.with_ymd_and_hms(2030, 1, 1, hour, 0, 0).unwrap()
+        }
 
-    // 3. Update Participant details
-    sqlx::query!(
-        "UPDATE participants SET name = $1, comment = $2, updated_at = NOW() WHERE id = $3",
-        payload.participant_name,
-        payload.comment,
-        id
-    )
-    .execute(&mut *transaction)
-    .await?;
+        fn slot() -> EventSlot {
+            EventSlot {
+                id: 1,
+                event_id: Uuid::nil(),
+                start_at: Self::at(9),
+                end_at: Self::at(12),
+                locked: false,
+            }
+        }
 
-    // 4. Update Availabilities (using internal ID)
-    sqlx::query!("DELETE FROM availabilities WHERE participant_id = $1", id)
-        .execute(&mut *transaction)
-        .await?;
+        fn read(&self) {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+        }
+    }
 
-    let merged = merge_time_ranges(payload.availabilities);
-    for range in merged {
-        sqlx::query!(
-            "INSERT INTO availabilities (participant_id, start_at, end_at) VALUES ($1, $2, $3)",
-            id,
-            range.start_at,
-            range.end_at
-        )
-        .execute(&mut *transaction)
-        .await?;
+    impl EventStore for MockStore {
+        async fn public_event(
+            &self,
+            public_token: &str,
+        ) -> Result<Option<PublicEvent>, sqlx::Error> {
+            Ok((public_token == PUBLIC_TOKEN).then(|| PublicEvent {
+                event: Event {
+                    id: Uuid::nil(),
+                    public_token: PUBLIC_TOKEN.to_string(),
+                    organizer_token: "organizer123".to_string(),
+                    title: "Standup".to_string(),
+                    description: None,
+                    state: EventState::Open,
+                    time_zone: Some("UTC".to_string()),
+                    slot_duration: 60,
+                    created_at: Self::at(0),
+                    updated_at: Self::at(0),
+                },
+                password_hash: self.password_hash.clone(),
+                slot_kind: SlotKind::Dates,
+                poll_type: PollType::Datetime,
+                version: etag::EventVersion {
+                    updated_at: Self::at(0),
+                    expires_at: Self::at(0) + chrono::Duration::days(30),
+                    reveal_results_at: None,
+                    participants_updated_at: Some(Self::at(0)),
+                    participants: 2,
+                },
+            }))
+        }
+
+        async fn event_details(&self, _: Uuid) -> Result<EventDetails, sqlx::Error> {
+            self.read();
+            Ok(EventDetails {
+                results_visibility: self.results_visibility,
+                reveal_results_at: None,
+                blind_until_closed: false,
+                anonymous_results: false,
+                close_at_quorum: None,
+                expires_at: Self::at(0) + chrono::Duration::days(30),
+                slot_kind: SlotKind::Dates,
+                poll_type: PollType::Datetime,
+                organizer_name: "Alice".to_string(),
+                slots: vec![Self::slot()],
+                roles: Vec::new(),
+                finalization_rules: FinalizationRules::default(),
+            })
+        }
+
+        async fn results_access(&self, _: Uuid) -> Result<ResultsAccess, sqlx::Error> {
+            Ok(ResultsAccess {
+                state: EventState::Open,
+                results_visibility: self.results_visibility,
+                results_token: "results-token".to_string(),
+                reveal_results_at: None,
+                blind_until_closed: false,
+                anonymous_results: false,
+            })
+        }
+
+        async fn event_slots(&self, _: Uuid) -> Result<Vec<EventSlot>, sqlx::Error> {
+            self.read();
+            Ok(vec![Self::slot()])
+        }
+
+        async fn final_slots(&self, _: Uuid) -> Result<Vec<FinalSlot>, sqlx::Error> {
+            Ok(Vec::new())
+        }
+
+        async fn confirmed_participants(
+            &self,
+            _: Uuid,
+        ) -> Result<Vec<ConfirmedParticipant>, sqlx::Error> {
+            let participant = |id, name: &str, is_organizer| ConfirmedParticipant {
+                id,
+                name: name.to_string(),
+                is_organizer,
+                comment: None,
+                buffer_minutes: 0,
+                time_zone: None,
+                rsvp_status: None,
+                role: None,
+                email_verified: None,
+                display_order: None,
+                is_hidden: false,
+                ranges: vec![TimeRangeRequest {
+                    start_at: Self::at(10),
+                    end_at: Self::at(11),
+                    availability_level: None,
+                }],
+            };
+            Ok(vec![
+                participant(1, "Alice", true),
+                participant(2, "Bob", false),
+            ])
+        }
+
+        async fn has_participant(&self, _: Uuid, token: Uuid) -> Result<bool, sqlx::Error> {
+            Ok(token == self.participant_token)
+        }
     }
 
-    transaction.commit().await?;
+    fn no_cache() -> ResponseCache {
+        ResponseCache::new(Duration::ZERO)
+    }
 
-    Ok(())
-}
+    fn results_query(results_token: Option<&str>, participant_token: Option<Uuid>) -> ResultsQuery {
+        ResultsQuery {
+            results_token: results_token.map(str::to_string),
+            participant_token,
+            tz: None,
+        }
+    }
 
-pub async fn check_events_status(
-    State(pool): State<PgPool>,
-    Json(payload): Json<BatchCheckStatusRequest>,
-) -> AppResult<Json<BatchCheckStatusResponse>> {
-    if payload.tokens.len() > 50 {
-        return Err(AppError::BadRequest(
-            "Too many tokens to check (max 50)".to_string(),
-        ));
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
     }
 
-    if payload.tokens.is_empty() {
-        return Ok(Json(BatchCheckStatusResponse {
-            statuses: std::collections::HashMap::new(),
-        }));
+    #[tokio::test]
+    async fn test_read_event_builds_the_view_and_revalidates_without_reads() {
+        let store = MockStore::new();
+        let cache = no_cache();
+        let now = MockStore::at(8);
+
+        let response = read_event(&store, &cache, now, PUBLIC_TOKEN, None, &HeaderMap::new())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG].clone();
+        let event = body(response).await;
+        assert_eq!(event["title"], "Standup");
+        assert_eq!(event["organizer_name"], "Alice");
+        assert_eq!(event["event_slots"].as_array().unwrap().len(), 1);
+        assert_eq!(event["seconds_until_expiry"], 30 * 86400 - 8 * 3600);
+        assert_eq!(store.reads.load(Ordering::SeqCst), 1);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag);
+        let response = read_event(&store, &cache, now, PUBLIC_TOKEN, None, &headers)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(store.reads.load(Ordering::SeqCst), 1);
     }
 
-    struct EventStatus {
-        public_token: String,
-        state: String,
+    #[tokio::test]
+    async fn test_read_event_checks_token_password_and_zone_first() {
+        let store = MockStore {
+            password_hash: Some("$argon2id$not-a-real-hash".to_string()),
+            ..MockStore::new()
+        };
+        let cache = no_cache();
+        let now = MockStore::at(8);
+
+        let missing = read_event(&store, &cache, now, "unknown", None, &HeaderMap::new()).await;
+        assert!(matches!(missing, Err(AppError::NotFound)));
+        let locked = read_event(&store, &cache, now, PUBLIC_TOKEN, None, &HeaderMap::new()).await;
+        assert!(matches!(locked, Err(AppError::PasswordRequired)));
+        let open = MockStore::new();
+        let unknown_zone = read_event(
+            &open,
+            &cache,
+            now,
+            PUBLIC_TOKEN,
+            Some("Mars/Olympus"),
+            &HeaderMap::new(),
+        )
+        .await;
+        assert!(matches!(unknown_zone, Err(AppError::BadRequest(_))));
+        assert_eq!(store.reads.load(Ordering::SeqCst), 0);
+        assert_eq!(open.reads.load(Ordering::SeqCst), 0);
     }
 
-    let rows = sqlx::query_as!(
-        EventStatus,
-        r#"
-        SELECT public_token, state 
-        FROM events 
-        WHERE public_token = ANY($1)
-        "#,
-        &payload.tokens
-    )
-    .fetch_all(&pool)
-    .await?;
+    #[tokio::test]
+    async fn test_read_results_enforces_visibility() {
+        let cache = no_cache();
+        let now = MockStore::at(8);
+        let headers = HeaderMap::new();
 
-    let mut statuses = std::collections::HashMap::new();
-    for row in rows {
-        statuses.insert(row.public_token, row.state);
+        let private = MockStore {
+            results_visibility: ResultsVisibility::Private,
+            ..MockStore::new()
+        };
+        let denied = read_results(
+            &private,
+            &cache,
+            &Keyring::default(),
+            now,
+            PUBLIC_TOKEN,
+            &results_query(None, None),
+            &headers,
+        )
+        .await;
+        assert!(matches!(denied, Err(AppError::Unauthorized)));
+        assert_eq!(private.reads.load(Ordering::SeqCst), 0);
+        let response = read_results(
+            &private,
+            &cache,
+            &Keyring::default(),
+            now,
+            PUBLIC_TOKEN,
+            &results_query(Some("results-token"), None),
+            &headers,
+        )
+        .await
+        .unwrap();
+        let results = body(response).await;
+        assert_eq!(results["total_participants"], 2);
+        assert_eq!(results["participants"][1]["name"], "Bob");
+
+        let after_submit = MockStore {
+            results_visibility: ResultsVisibility::AfterSubmit,
+            ..MockStore::new()
+        };
+        let locked = read_results(
+            &after_submit,
+            &cache,
+            &Keyring::default(),
+            now,
+            PUBLIC_TOKEN,
+            &results_query(None, Some(Uuid::new_v4())),
+            &headers,
+        )
+        .await;
+        assert!(matches!(locked, Err(AppError::ResultsLocked(_))));
+        let response = read_results(
+            &after_submit,
+            &cache,
+            &Keyring::default(),
+            now,
+            PUBLIC_TOKEN,
+            &results_query(None, Some(after_submit.participant_token)),
+            &headers,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
     }
 
-    Ok(Json(BatchCheckStatusResponse { statuses }))
-}
+    #[tokio::test]
+    async fn test_calendar_suggestions_and_summary_read_through_the_store() {
+        let now = MockStore::at(8);
+        let headers = HeaderMap::new();
+        let store = MockStore {
+            results_visibility: ResultsVisibility::Private,
+            ..MockStore::new()
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::TimeZone;
+        let response = read_calendar(&store, now, PUBLIC_TOKEN, &headers)
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let calendar = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(calendar.contains("DTSTART:20300101T090000Z\r\n"));
+        assert!(calendar.contains("Alice"));
+
+        let mut query = SuggestionsQuery {
+            limit: None,
+            results_token: None,
+            participant_token: None,
+            tz: None,
+        };
+        let denied = read_suggestions(&store, now, PUBLIC_TOKEN, &query, &headers).await;
+        assert!(matches!(denied, Err(AppError::Unauthorized)));
+        query.results_token = Some("results-token".to_string());
+        let suggestions = read_suggestions(&store, now, PUBLIC_TOKEN, &query, &headers)
+            .await
+            .unwrap();
+        assert_eq!(suggestions.suggestions[0].start_at, MockStore::at(10));
+        assert_eq!(
+            suggestions.suggestions[0].participants,
+            vec!["Alice", "Bob"]
+        );
+
+        let query = SummaryQuery {
+            format: SummaryFormat::Json,
+            results_token: Some("results-token".to_string()),
+            participant_token: None,
+        };
+        let response = read_summary(&store, now, PUBLIC_TOKEN, &query, &headers)
+            .await
+            .unwrap();
+        let summary = body(response).await;
+        assert_eq!(summary["respondents"], json!(["Alice", "Bob"]));
+        assert_eq!(summary["slots"].as_array().unwrap().len(), 1);
+    }
 
     #[test]
     fn test_merge_time_ranges_no_overlap() {
@@ -735,10 +4989,12 @@ mod tests {
             TimeRangeRequest {
                 start_at: t1_start,
                 end_at: t1_end,
+                availability_level: None,
             },
             TimeRangeRequest {
                 start_at: t2_start,
                 end_at: t2_end,
+                availability_level: None,
             },
         ];
 
@@ -757,10 +5013,12 @@ mod tests {
             TimeRangeRequest {
                 start_at: t1_start,
                 end_at: t1_end,
+                availability_level: None,
             },
             TimeRangeRequest {
                 start_at: t2_start,
                 end_at: t2_end,
+                availability_level: None,
             },
         ];
 
@@ -769,4 +5027,33 @@ mod tests {
         assert_eq!(merged[0].start_at, t1_start);
         assert_eq!(merged[0].end_at, t2_end);
     }
+
+    #[test]
+    fn test_merge_time_ranges_keeps_levels_apart() {
+        let at = |secs| Utc.timestamp_opt(secs, 0).unwrap();
+        let range = |start, end, availability_level| TimeRangeRequest {
+            start_at: at(start),
+            end_at: at(end),
+            availability_level,
+        };
+
+        let merged = merge_time_ranges(vec![
+            range(1000, 2000, None),
+            range(1500, 2500, Some(AvailabilityLevel::Available)),
+            range(2500, 3000, Some(AvailabilityLevel::IfNeedBe)),
+            range(3000, 4000, Some(AvailabilityLevel::IfNeedBe)),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!((merged[0].start_at, merged[0].end_at), (at(1000), at(2500)));
+        assert_eq!(merged[0].availability_level, None);
+        assert_eq!((merged[1].start_at, merged[1].end_at), (at(2500), at(4000)));
+        assert_eq!(merged[1].level(), AvailabilityLevel::IfNeedBe);
+        assert!(validate_availability_levels(&merged).is_ok());
+
+        let overlapping = vec![
+            range(1000, 2000, None),
+            range(1500, 2500, Some(AvailabilityLevel::Unavailable)),
+        ];
+        assert!(validate_availability_levels(&overlapping).is_err());
+    }
 }
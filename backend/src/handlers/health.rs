@@ -1,9 +1,134 @@
-use axum::Json;
-use serde_json::{Value, json};
-
-pub async fn health_check() -> Json<Value> {
-    Json(json!({
-        "status": "ok",
-        "service": "agreed-time-backend"
-    }))
+use axum::{
+    Json,
+    extract::State,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use crate::{clock::SharedClock, db};
+
+/// Readiness answers within this even when the pool is waiting on a dead database.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HealthResponse {
+    pub status: String,
+    pub service: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// `ready` or `unavailable`
+    pub status: String,
+    /// `SELECT 1` succeeded
+    pub database: bool,
+    /// Every migration built into this binary has been applied
+    pub migrations: bool,
+    /// Versions still to apply; empty when the database can't be checked
+    pub pending_migrations: Vec<i64>,
+    /// Connection pool usage after the checks. Informational: a pool that
+    /// stays exhausted fails the `SELECT 1` check once `DB_ACQUIRE_TIMEOUT_SECS`
+    /// or the probe's own 2s run out.
+    pub pool: db::PoolStats,
+}
+
+/// Kept for existing load balancer checks; the same as `/health/live`.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "Service is up", body = HealthResponse))
+)]
+pub async fn health_check() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "ok".to_string(),
+        service: "agreed-time-backend".to_string(),
+    })
+}
+
+/// The process is up and serving. Doesn't touch the database, so an outage
+/// there makes instances unready rather than getting them restarted.
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses((status = 200, description = "Process is up", body = HealthResponse))
+)]
+pub async fn liveness() -> Json<HealthResponse> {
+    health_check().await
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Database reachable and migrated", body = ReadinessResponse),
+        (status = 503, description = "Database unreachable or migrations pending", body = ReadinessResponse)
+    )
+)]
+pub async fn readiness(State(pool): State<PgPool>) -> (StatusCode, Json<ReadinessResponse>) {
+    let database = matches!(
+        tokio::time::timeout(
+            READINESS_TIMEOUT,
+            sqlx::query_scalar!("SELECT 1").fetch_one(&pool)
+        )
+        .await,
+        Ok(Ok(_))
+    );
+    let pending = if database {
+        tokio::time::timeout(READINESS_TIMEOUT, db::pending_migrations(&pool))
+            .await
+            .ok()
+            .and_then(Result::ok)
+    } else {
+        None
+    };
+    let migrations = pending.as_ref().is_some_and(Vec::is_empty);
+    let ready = database && migrations;
+
+    (
+        if ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        },
+        Json(ReadinessResponse {
+            status: if ready { "ready" } else { "unavailable" }.to_string(),
+            database,
+            migrations,
+            pending_migrations: pending.unwrap_or_default(),
+            pool: db::PoolStats::of(&pool),
+        }),
+    )
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TimeResponse {
+    pub now: DateTime<Utc>,
+    /// `now` as Unix milliseconds, for offsetting `Date.now()` directly
+    pub unix_millis: i64,
+}
+
+/// The server's clock, so countdowns can correct for client clock drift.
+#[utoipa::path(
+    get,
+    path = "/time",
+    tag = "health",
+    responses((status = 200, description = "Current server time (never cached)", body = TimeResponse))
+)]
+pub async fn server_time(State(clock): State<SharedClock>) -> impl IntoResponse {
+    let now = clock.now();
+    (
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(TimeResponse {
+            now,
+            unix_millis: now.timestamp_millis(),
+        }),
+    )
 }
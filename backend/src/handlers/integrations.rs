@@ -0,0 +1,242 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::HeaderMap,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::{fmt, sync::Arc};
+use utoipa::ToSchema;
+
+use crate::{
+    clock::SharedClock,
+    config::{CaldavImport, Config},
+    db::audit,
+    error::{AppError, AppResult, ErrorResponse, FieldError},
+    handlers::events::{ensure_not_archived, ensure_password, password_header},
+    integrations::{
+        caldav::{self, CaldavError, Credentials},
+        itip,
+    },
+    middleware::AdminActor,
+    models::{AvailabilityLevel, EventState, SlotKind, TimeRangeRequest},
+    validation::Validator,
+};
+
+/// Longest calendar URL accepted, as for webhooks.
+const MAX_CALDAV_URL_LENGTH: usize = 2048;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ItipReplyResponse {
+    pub public_token: String,
+    pub participant_name: String,
+    pub rsvp_status: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/integrations/itip/reply",
+    tag = "integrations",
+    request_body(content = String, content_type = "text/calendar", description = "iCalendar object with METHOD:REPLY"),
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "RSVP recorded against the participant", body = ItipReplyResponse),
+        (status = 400, description = "Not a REPLY for one of our invitations", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin key", body = ErrorResponse),
+        (status = 404, description = "No participant with that address on the event", body = ErrorResponse),
+        (status = 409, description = "Event is archived", body = ErrorResponse)
+    )
+)]
+pub async fn itip_reply(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Extension(actor): Extension<AdminActor>,
+    body: String,
+) -> AppResult<Json<ItipReplyResponse>> {
+    let reply = itip::parse_reply(&body)
+        .map_err(|e| AppError::BadRequest(format!("Invalid iTIP reply: {}", e)))?;
+
+    let mut transaction = pool.begin().await?;
+
+    let event = sqlx::query!(
+        r#"SELECT id, state AS "state: EventState" FROM events WHERE public_token = $1 AND deleted_at IS NULL FOR UPDATE"#,
+        reply.public_token
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or_else(|| AppError::NotFound)?;
+
+    ensure_not_archived(event.state)?;
+
+    let now = clock.now();
+    let participant_name = sqlx::query_scalar!(
+        r#"
+        UPDATE participants
+        SET rsvp_status = $1, rsvp_at = $2
+        WHERE event_id = $3 AND LOWER(email) = LOWER($4)
+        RETURNING name
+        "#,
+        reply.partstat.as_str(),
+        now,
+        event.id,
+        reply.attendee_email
+    )
+    .fetch_all(&mut *transaction)
+    .await?
+    .into_iter()
+    .next()
+    .ok_or_else(|| AppError::NotFound)?;
+
+    audit::record(
+        &mut transaction,
+        event.id,
+        "rsvp_received",
+        &actor.0,
+        Some(reply.partstat.as_str()),
+        now,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(Json(ItipReplyResponse {
+        public_token: reply.public_token,
+        participant_name,
+        rsvp_status: reply.partstat.as_str().to_string(),
+    }))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CaldavFreeBusyRequest {
+    /// Calendar collection, e.g. `https://dav.example.com/calendars/bob/work/`
+    pub url: String,
+    pub username: Option<String>,
+    /// Sent to the CalDAV server once; never stored
+    pub password: Option<String>,
+}
+
+// Hand-written so the password never reaches a log line.
+impl fmt::Debug for CaldavFreeBusyRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CaldavFreeBusyRequest")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CaldavFreeBusyResponse {
+    /// The open slots the calendar leaves free, plus `if_need_be` ranges
+    /// only blocked by tentative events; ready to submit as `availabilities`
+    pub availabilities: Vec<TimeRangeRequest>,
+    /// Busy periods the calendar reported within the event's window
+    pub busy_periods: usize,
+}
+
+#[utoipa::path(
+    post,
+    path = "/events/{public_token}/caldav/freebusy",
+    tag = "integrations",
+    params(
+        ("public_token" = String, Path, description = "Public event token"),
+        ("X-Event-Password" = Option<String>, Header, description = "Required when the event has a password")
+    ),
+    request_body = CaldavFreeBusyRequest,
+    responses(
+        (status = 200, description = "Suggested availability; nothing is saved", body = CaldavFreeBusyResponse),
+        (status = 400, description = "Invalid or refused URL, rejected credentials, or a weekly event", body = ErrorResponse),
+        (status = 403, description = "Event password missing or wrong", body = ErrorResponse),
+        (status = 404, description = "Event not found, or CalDAV import is off", body = ErrorResponse),
+        (status = 502, description = "The CalDAV server failed or sent no usable free/busy data", body = ErrorResponse)
+    )
+)]
+pub async fn caldav_free_busy(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    Path(public_token): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<CaldavFreeBusyRequest>,
+) -> AppResult<Json<CaldavFreeBusyResponse>> {
+    let allow_private = match config.caldav_import {
+        CaldavImport::Off => return Err(AppError::NotFound),
+        CaldavImport::Public => false,
+        CaldavImport::Any => true,
+    };
+    let mut validator = Validator::new();
+    validator.required_text("url", "Calendar URL", &payload.url, MAX_CALDAV_URL_LENGTH);
+    validator.finish()?;
+
+    let event = sqlx::query!(
+        "SELECT id, slot_kind, password_hash FROM events WHERE public_token = $1 AND deleted_at IS NULL",
+        public_token
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    ensure_password(event.password_hash.as_deref(), password_header(&headers))?;
+    if SlotKind::from_db(&event.slot_kind) == SlotKind::Weekly {
+        return Err(AppError::BadRequest(
+            "Weekly events have no dates to compare with a calendar".to_string(),
+        ));
+    }
+
+    // Locked slots take no answers, so there is nothing to suggest there
+    let slots = sqlx::query_as!(
+        agreed_time_core::TimeRange,
+        "SELECT start_at, end_at FROM event_slots WHERE event_id = $1 AND NOT locked ORDER BY start_at",
+        event.id
+    )
+    .fetch_all(&pool)
+    .await?;
+    let (Some(first), Some(end_at)) = (slots.first(), slots.iter().map(|slot| slot.end_at).max())
+    else {
+        return Ok(Json(CaldavFreeBusyResponse {
+            availabilities: Vec::new(),
+            busy_periods: 0,
+        }));
+    };
+    let window = agreed_time_core::TimeRange::new(first.start_at, end_at);
+
+    let credentials = payload.username.as_deref().map(|username| Credentials {
+        username,
+        password: payload.password.as_deref().unwrap_or_default(),
+    });
+    let busy = caldav::fetch_free_busy(payload.url.trim(), credentials, window, allow_private)
+        .await
+        .map_err(|e| match e {
+            CaldavError::InvalidUrl | CaldavError::ForbiddenHost => {
+                AppError::Validation(vec![FieldError {
+                    field: "url".to_string(),
+                    message: format!("Calendar URL refused: {}", e),
+                }])
+            }
+            CaldavError::Unauthorized => {
+                AppError::BadRequest("The CalDAV server rejected the credentials".to_string())
+            }
+            e => AppError::CalendarUnavailable(format!("CalDAV free/busy failed: {}", e)),
+        })?;
+    let busy_periods = busy
+        .iter()
+        .filter(|period| {
+            period.range.start_at < window.end_at && window.start_at < period.range.end_at
+        })
+        .count();
+
+    let (free, if_need_be) = caldav::free_ranges(&slots, &busy);
+    let mut availabilities: Vec<TimeRangeRequest> = free
+        .into_iter()
+        .map(Into::into)
+        .chain(if_need_be.into_iter().map(|range| TimeRangeRequest {
+            availability_level: Some(AvailabilityLevel::IfNeedBe),
+            ..range.into()
+        }))
+        .collect();
+    availabilities.sort_by_key(|range| range.start_at);
+
+    Ok(Json(CaldavFreeBusyResponse {
+        availabilities,
+        busy_periods,
+    }))
+}
@@ -1,2 +1,14 @@
+pub mod admin;
+pub mod assignments;
+pub mod auth;
+pub mod capabilities;
+pub mod comments;
+pub mod demo;
+pub mod docs;
 pub mod events;
 pub mod health;
+pub mod integrations;
+pub mod organizers;
+pub mod realtime;
+pub mod templates;
+pub mod timezones;
@@ -0,0 +1,176 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, PgPool};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    clock::SharedClock,
+    error::{AppError, AppResult, ErrorResponse},
+    models::EventState,
+    tokens,
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizerAccountResponse {
+    /// Pass as `organizer_account_token` when creating events; anyone holding
+    /// it can list their organizer links
+    pub account_token: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OrganizerEventsQuery {
+    /// Page size (default 20, at most 100)
+    pub limit: Option<i64>,
+    /// Events to skip
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizerEventSummary {
+    pub public_token: String,
+    pub organizer_token: String,
+    pub title: String,
+    pub state: EventState,
+    /// Confirmed participants, organizer included
+    pub total_participants: i64,
+    /// Waiting for approval
+    pub pending_participants: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizerEventsResponse {
+    /// Newest first; trashed events are left out
+    pub events: Vec<OrganizerEventSummary>,
+    /// Events across all pages
+    pub total: i64,
+}
+
+/// The account behind `token`; 400 when there is none, as for other
+/// references in a creation payload.
+pub(crate) async fn account_id(conn: &mut PgConnection, token: &str) -> AppResult<Uuid> {
+    sqlx::query_scalar!("SELECT id FROM organizer_accounts WHERE token = $1", token)
+        .fetch_optional(conn)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Unknown organizer account token".to_string()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizers",
+    tag = "organizer",
+    responses(
+        (status = 200, description = "Account created", body = OrganizerAccountResponse)
+    )
+)]
+pub async fn create_organizer_account(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+) -> AppResult<Json<OrganizerAccountResponse>> {
+    let now = clock.now();
+    for _ in 0..tokens::MAX_ATTEMPTS {
+        let token = tokens::generate();
+        let inserted = sqlx::query_scalar!(
+            r#"
+            INSERT INTO organizer_accounts (id, token, created_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            RETURNING id
+            "#,
+            Uuid::new_v4(),
+            token,
+            now
+        )
+        .fetch_optional(&pool)
+        .await?;
+        if inserted.is_some() {
+            return Ok(Json(OrganizerAccountResponse {
+                account_token: token,
+                created_at: now,
+            }));
+        }
+    }
+    Err(tokens::exhausted().into())
+}
+
+#[utoipa::path(
+    get,
+    path = "/organizers/{account_token}/events",
+    tag = "organizer",
+    params(
+        ("account_token" = String, Path, description = "Organizer account token"),
+        OrganizerEventsQuery
+    ),
+    responses(
+        (status = 200, description = "The account's events", body = OrganizerEventsResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse)
+    )
+)]
+pub async fn list_organizer_events(
+    State(pool): State<PgPool>,
+    Path(account_token): Path<String>,
+    Query(query): Query<OrganizerEventsQuery>,
+) -> AppResult<Json<OrganizerEventsResponse>> {
+    let account_id = sqlx::query_scalar!(
+        "SELECT id FROM organizer_accounts WHERE token = $1",
+        account_token
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(Json(
+        event_page(&pool, Some(account_id), None, &query).await?,
+    ))
+}
+
+/// One page of the events of an organizer account or a logged-in user.
+pub(crate) async fn event_page(
+    pool: &PgPool,
+    account_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    query: &OrganizerEventsQuery,
+) -> AppResult<OrganizerEventsResponse> {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM events
+        WHERE (organizer_account_id = $1 OR user_id = $2) AND deleted_at IS NULL
+        "#,
+        account_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let events = sqlx::query_as!(
+        OrganizerEventSummary,
+        r#"
+        SELECT e.public_token, e.organizer_token, e.title, e.state AS "state: EventState", e.created_at,
+               COUNT(p.id) FILTER (WHERE p.status = 'confirmed') AS "total_participants!",
+               COUNT(p.id) FILTER (WHERE p.status = 'pending') AS "pending_participants!"
+        FROM events e
+        LEFT JOIN participants p ON p.event_id = e.id
+        WHERE (e.organizer_account_id = $1 OR e.user_id = $2) AND e.deleted_at IS NULL
+        GROUP BY e.id
+        ORDER BY e.created_at DESC, e.id
+        LIMIT $3 OFFSET $4
+        "#,
+        account_id,
+        user_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(OrganizerEventsResponse { events, total })
+}
@@ -0,0 +1,142 @@
+use axum::{
+    extract::{
+        Path, Query, State,
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code},
+    },
+    http::HeaderMap,
+    response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use sqlx::PgPool;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+use crate::{
+    clock::SharedClock,
+    db::{EventStore, events::PublicEvent},
+    error::{AppError, AppResult, ErrorResponse},
+    handlers::events::{ResultsView, check_results_access, ensure_password, password_header},
+    models::{ResultsQuery, SocketQuery},
+    realtime::{RealtimeMessage, SharedHub},
+    validation::MAX_NAME_LENGTH,
+};
+
+#[utoipa::path(
+    get,
+    path = "/events/{public_token}/ws",
+    tag = "events",
+    params(
+        ("public_token" = String, Path, description = "Public event token"),
+        ResultsQuery,
+        SocketQuery
+    ),
+    responses(
+        (status = 101, description = "WebSocket streaming `RealtimeMessage` JSON frames; clients may send `presence` frames", body = RealtimeMessage),
+        (status = 401, description = "Results are private and the results token is missing or wrong", body = ErrorResponse),
+        (status = 403, description = "Event password missing or wrong, or `RESULTS_LOCKED` until the caller has submitted (`after_submit`) or the event has closed (`after_close`)", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+#[allow(clippy::too_many_arguments)] // one per extractor
+pub async fn event_socket(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(hub): State<SharedHub>,
+    Path(public_token): Path<String>,
+    Query(query): Query<ResultsQuery>,
+    Query(socket): Query<SocketQuery>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> AppResult<Response> {
+    let PublicEvent {
+        event,
+        password_hash,
+        ..
+    } = pool
+        .public_event(&public_token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    // Checked before the upgrade, so a refused handshake never subscribes
+    ensure_password(
+        password_hash.as_deref(),
+        password_header(&headers).or(socket.password.as_deref()),
+    )?;
+    let event_id = event.id;
+
+    // Availability deltas are results with names; blind polls only get
+    // presence and comments until revealed, and clients reconnect after the
+    // reveal. Anonymous results never get them.
+    let view = check_results_access(
+        &pool,
+        event_id,
+        query.results_token.as_deref(),
+        query.participant_token,
+        clock.now(),
+    )
+    .await?;
+
+    Ok(upgrade.on_upgrade(move |socket| run_socket(socket, hub, event_id, view)))
+}
+
+async fn run_socket(socket: WebSocket, hub: SharedHub, event_id: Uuid, view: ResultsView) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut updates = hub.subscribe(event_id);
+    // Announced as no longer editing when the socket goes away
+    let mut editing_as: Option<String> = None;
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(
+                    RealtimeMessage::Availability { .. }
+                    | RealtimeMessage::ParticipantRemoved { .. },
+                ) if view != ResultsView::Full => {}
+                Ok(message) => {
+                    let text = serde_json::to_string(&message).expect("messages serialize");
+                    if sender.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Socket on event {} skipped {} messages", event_id, skipped);
+                }
+                // Only on shutdown, since the channel lives while we subscribe
+                Err(RecvError::Closed) => {
+                    let _ = sender
+                        .send(Message::Close(Some(CloseFrame {
+                            code: close_code::AWAY,
+                            reason: "server shutting down".into(),
+                        })))
+                        .await;
+                    break;
+                }
+            },
+            incoming = receiver.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    if let Ok(RealtimeMessage::Presence { name, editing }) =
+                        serde_json::from_str(&text)
+                        && !name.trim().is_empty()
+                        && name.len() <= MAX_NAME_LENGTH
+                    {
+                        editing_as = editing.then(|| name.clone());
+                        hub.publish(event_id, RealtimeMessage::Presence { name, editing });
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    drop(updates);
+    if let Some(name) = editing_as {
+        hub.publish(
+            event_id,
+            RealtimeMessage::Presence {
+                name,
+                editing: false,
+            },
+        );
+    }
+    hub.release(event_id);
+}
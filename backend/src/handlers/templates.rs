@@ -0,0 +1,283 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::HeaderMap,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    auth::CurrentUser,
+    clock::SharedClock,
+    config::Config,
+    db,
+    encryption::SharedKeyring,
+    error::{AppError, AppResult, ErrorResponse},
+    handlers::events::{create_event, poll_dates, validate_offset_days, weekly_view},
+    integrations::templates::SharedTemplates,
+    models::{
+        CreateEventFromTemplateRequest, CreateEventRequest, CreateEventResponse,
+        CreateTemplateRequest, EventSlot, PollType, ResultsVisibility, SlotKind, TemplateResponse,
+        TemplateStructure, TimeRangeRequest,
+    },
+    tokens,
+    validation::{MAX_NAME_LENGTH, MAX_TITLE_LENGTH, Validator},
+};
+
+#[utoipa::path(
+    post,
+    path = "/templates",
+    tag = "organizer",
+    request_body = CreateTemplateRequest,
+    responses(
+        (status = 200, description = "Template saved; share `template_token` to reuse it", body = TemplateResponse),
+        (status = 400, description = "Missing or too long name", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse)
+    )
+)]
+pub async fn create_template(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    Json(payload): Json<CreateTemplateRequest>,
+) -> AppResult<Json<TemplateResponse>> {
+    let mut validator = Validator::new();
+    validator.required_text("name", "Template name", &payload.name, MAX_TITLE_LENGTH);
+    validator.finish()?;
+
+    let event = sqlx::query!(
+        r#"
+        SELECT id, title, description, time_zone, slot_duration, slot_kind, poll_type,
+               results_visibility, blind_until_closed, anonymous_results, close_at_quorum,
+               locale, retention_days
+        FROM events
+        WHERE organizer_token = $1 AND deleted_at IS NULL
+        "#,
+        payload.organizer_token
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let event_slots = sqlx::query_as!(
+        EventSlot,
+        "SELECT id, event_id, start_at, end_at, locked FROM event_slots WHERE event_id = $1 ORDER BY start_at",
+        event.id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    // Slots are kept in the form `POST /events` takes for the event's kind
+    let slot_kind = SlotKind::from_db(&event.slot_kind);
+    let poll_type = PollType::from_db(&event.poll_type);
+    let time_zone = event.time_zone.as_deref();
+    let time_slots = if slot_kind == SlotKind::Dates && poll_type == PollType::Datetime {
+        event_slots
+            .iter()
+            .map(|slot| TimeRangeRequest {
+                start_at: slot.start_at,
+                end_at: slot.end_at,
+                availability_level: None,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let structure = TemplateStructure {
+        title: event.title,
+        description: event.description,
+        slot_duration: event.slot_duration,
+        slot_kind,
+        poll_type,
+        time_slots,
+        weekly_slots: weekly_view(slot_kind, &event_slots, time_zone),
+        dates: poll_dates(poll_type, &event_slots, time_zone),
+        results_visibility: ResultsVisibility::from_db(&event.results_visibility),
+        blind_until_closed: event.blind_until_closed,
+        anonymous_results: event.anonymous_results,
+        roles: db::events::roles(&pool, event.id).await?,
+        finalization_rules: db::events::finalization_rules(&pool, event.id).await?,
+        close_at_quorum: event.close_at_quorum,
+        locale: event.locale,
+        retention_days: event.retention_days,
+        time_zone: event.time_zone,
+    };
+    let stored = serde_json::to_value(&structure).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
+    let name = payload.name.trim().to_string();
+    let (template_token, created_at) = 'insert: {
+        for _ in 0..tokens::MAX_ATTEMPTS {
+            let template_token = tokens::generate();
+            let inserted = sqlx::query_scalar!(
+                r#"
+                INSERT INTO templates (id, template_token, name, structure, created_at)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT DO NOTHING
+                RETURNING created_at
+                "#,
+                Uuid::new_v4(),
+                template_token,
+                name,
+                stored,
+                clock.now()
+            )
+            .fetch_optional(&pool)
+            .await?;
+            // The stored timestamp, so the response matches later reads
+            if let Some(created_at) = inserted {
+                break 'insert (template_token, created_at);
+            }
+        }
+        return Err(tokens::exhausted().into());
+    };
+
+    Ok(Json(TemplateResponse {
+        template_token,
+        name,
+        created_at,
+        structure,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/templates/{template_token}",
+    tag = "events",
+    params(("template_token" = String, Path, description = "Template token")),
+    responses(
+        (status = 200, description = "Saved structure", body = TemplateResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_template(
+    State(pool): State<PgPool>,
+    Path(template_token): Path<String>,
+) -> AppResult<Json<TemplateResponse>> {
+    Ok(Json(fetch_template(&pool, &template_token).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/templates/{template_token}/events",
+    tag = "events",
+    params(
+        ("template_token" = String, Path, description = "Template token"),
+        ("X-Creation-Ticket" = Option<String>, Header, description = "Required when the deployment sets `CREATION_TICKET_SECRET`")
+    ),
+    request_body = CreateEventFromTemplateRequest,
+    responses(
+        (status = 200, description = "Event created from the template", body = CreateEventResponse),
+        (status = 400, description = "Invalid input, or an offset for a weekly template", body = ErrorResponse),
+        (status = 403, description = "Creation ticket or captcha missing or invalid", body = ErrorResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse)
+    )
+)]
+#[allow(clippy::too_many_arguments)] // one per extractor
+pub async fn create_event_from_template(
+    State(pool): State<PgPool>,
+    State(clock): State<SharedClock>,
+    State(config): State<Arc<Config>>,
+    State(keys): State<SharedKeyring>,
+    State(mail_templates): State<SharedTemplates>,
+    bearer: Option<CurrentUser>,
+    Path(template_token): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateEventFromTemplateRequest>,
+) -> AppResult<Json<CreateEventResponse>> {
+    let offset_days = payload.offset_days.unwrap_or(0);
+    let mut validator = Validator::new();
+    validator.required_text(
+        "organizer_name",
+        "Organizer name",
+        &payload.organizer_name,
+        MAX_NAME_LENGTH,
+    );
+    validator.check("offset_days", validate_offset_days(offset_days));
+    validator.finish()?;
+
+    let structure = fetch_template(&pool, &template_token).await?.structure;
+    if offset_days != 0 && structure.slot_kind == SlotKind::Weekly {
+        return Err(AppError::BadRequest(
+            "Weekly events repeat every week; offset_days doesn't apply".to_string(),
+        ));
+    }
+    let time_zone = structure.time_zone.as_deref().unwrap_or("UTC");
+    let time_slots = structure
+        .time_slots
+        .into_iter()
+        .map(|slot| {
+            let range = slot.clone().into();
+            agreed_time_core::shift_days(&range, offset_days.into(), time_zone)
+                .map(Into::into)
+                .unwrap_or(slot)
+        })
+        .collect();
+    let dates = structure
+        .dates
+        .into_iter()
+        .map(|date| date + chrono::Duration::days(offset_days.into()))
+        .collect();
+
+    // Everything else is checked exactly as if the client had sent it
+    let request = CreateEventRequest {
+        title: structure.title,
+        description: structure.description,
+        organizer_name: payload.organizer_name,
+        time_zone: structure.time_zone,
+        slot_duration: Some(structure.slot_duration),
+        time_slots,
+        slot_kind: Some(structure.slot_kind),
+        weekly_slots: structure.weekly_slots,
+        poll_type: Some(structure.poll_type),
+        dates,
+        results_visibility: Some(structure.results_visibility),
+        reveal_results_at: None,
+        blind_until_closed: Some(structure.blind_until_closed),
+        anonymous_results: Some(structure.anonymous_results),
+        roles: structure.roles,
+        finalization_rules: structure.finalization_rules,
+        webhook_url: None,
+        slack_webhook_url: None,
+        discord_webhook_url: None,
+        chat_webhook_url: None,
+        password: None,
+        close_at_quorum: structure.close_at_quorum,
+        organizer_email: None,
+        invitees: Vec::new(),
+        locale: Some(structure.locale),
+        organizer_account_token: None,
+        retention_days: Some(structure.retention_days),
+        captcha_token: payload.captcha_token,
+    };
+    create_event(
+        State(pool),
+        State(clock),
+        State(config),
+        State(keys),
+        State(mail_templates),
+        bearer,
+        headers,
+        Json(request),
+    )
+    .await
+}
+
+async fn fetch_template(pool: &PgPool, template_token: &str) -> AppResult<TemplateResponse> {
+    let row = sqlx::query!(
+        "SELECT template_token, name, structure, created_at FROM templates WHERE template_token = $1",
+        template_token
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    // Written by `create_template` only, so a parse failure means a corrupt row
+    let structure: TemplateStructure =
+        serde_json::from_value(row.structure).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+    Ok(TemplateResponse {
+        template_token: row.template_token,
+        name: row.name,
+        created_at: row.created_at,
+        structure,
+    })
+}
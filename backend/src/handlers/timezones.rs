@@ -0,0 +1,70 @@
+use axum::{Json, extract::State, http::header, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::clock::SharedClock;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TimeZoneInfo {
+    /// IANA name, as stored on events and participants
+    pub name: String,
+    /// Seconds ahead of UTC at `at`, daylight saving included
+    pub utc_offset_seconds: i32,
+    /// The same offset written `+05:30`
+    pub utc_offset: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TimeZonesResponse {
+    /// When the offsets were taken
+    pub at: DateTime<Utc>,
+    /// Alphabetical
+    pub time_zones: Vec<TimeZoneInfo>,
+}
+
+fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.unsigned_abs() / 60;
+    format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// Zones accepted as `time_zone`, for pickers.
+#[utoipa::path(
+    get,
+    path = "/timezones",
+    tag = "health",
+    responses((status = 200, description = "Every supported zone with its current UTC offset (cached for an hour)", body = TimeZonesResponse))
+)]
+pub async fn list_time_zones(State(clock): State<SharedClock>) -> impl IntoResponse {
+    let at = clock.now();
+    let time_zones = agreed_time_core::time_zone_names()
+        .into_iter()
+        .filter_map(|name| {
+            let offset = agreed_time_core::utc_offset_seconds(name, at)?;
+            Some(TimeZoneInfo {
+                name: name.to_string(),
+                utc_offset_seconds: offset,
+                utc_offset: format_offset(offset),
+            })
+        })
+        .collect();
+
+    (
+        // Offsets only move at DST transitions
+        [(header::CACHE_CONTROL, "public, max-age=3600")],
+        Json(TimeZonesResponse { at, time_zones }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_offset() {
+        assert_eq!(format_offset(0), "+00:00");
+        assert_eq!(format_offset(19800), "+05:30");
+        assert_eq!(format_offset(-12600), "-03:30");
+    }
+}
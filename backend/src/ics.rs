@@ -0,0 +1,237 @@
+//! iCalendar (RFC 5545) rendering for event exports, plus the line-level
+//! reader the integrations parse incoming calendars with.
+//!
+//! Times are written in UTC; the event's IANA zone is advertised through
+//! `X-WR-TIMEZONE` so calendar apps display slots in the organizer's zone
+//! without us having to emit full `VTIMEZONE` rule sets.
+
+use chrono::{DateTime, Utc};
+
+const PRODID: &str = "-//agreed-time//Event Export//EN";
+
+/// Lines longer than this many octets must be folded (RFC 5545 §3.1).
+const MAX_LINE_OCTETS: usize = 75;
+
+pub struct CalendarEvent<'a> {
+    pub public_token: &'a str,
+    pub title: &'a str,
+    pub description: Option<&'a str>,
+    pub organizer_name: &'a str,
+    pub time_zone: Option<&'a str>,
+    /// `true` when the slots are the organizer's final choice rather than candidates
+    pub confirmed: bool,
+}
+
+pub struct CalendarSlot {
+    pub id: i64,
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+}
+
+pub fn render(event: &CalendarEvent, slots: &[CalendarSlot], stamp: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(event.title)),
+    ];
+    if let Some(tz) = event.time_zone {
+        lines.push(format!("X-WR-TIMEZONE:{}", escape_text(tz)));
+    }
+
+    let status = if event.confirmed {
+        "CONFIRMED"
+    } else {
+        "TENTATIVE"
+    };
+    for slot in slots {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:{}-{}@agreed-time",
+            event.public_token, slot.id
+        ));
+        lines.push(format!("DTSTAMP:{}", format_utc(stamp)));
+        lines.push(format!("DTSTART:{}", format_utc(slot.start_at)));
+        lines.push(format!("DTEND:{}", format_utc(slot.end_at)));
+        lines.push(format!("SUMMARY:{}", escape_text(event.title)));
+        if let Some(description) = event.description.filter(|d| !d.is_empty()) {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        lines.push(format!(
+            "ORGANIZER;CN=\"{}\":mailto:organizer@agreed-time.invalid",
+            escape_param(event.organizer_name)
+        ));
+        lines.push(format!("STATUS:{}", status));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_line(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+pub(crate) fn format_utc(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes a TEXT value (RFC 5545 §3.3.11).
+pub(crate) fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Quoted parameter values cannot contain DQUOTE or control characters.
+pub(crate) fn escape_param(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| *c != '"' && !c.is_control())
+        .collect()
+}
+
+/// Splits a content line into 75-octet chunks without breaking UTF-8 sequences.
+pub(crate) fn fold_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            // The leading space counts towards the next line's limit
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out
+}
+
+/// Joins folded continuation lines (RFC 5545 §3.1).
+pub(crate) fn unfold(calendar: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in calendar.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ if raw.is_empty() => {}
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+pub(crate) struct ContentLine {
+    pub name: String,
+    params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl ContentLine {
+    /// `NAME;PARAM=value;PARAM="quoted:value":VALUE`
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let mut in_quotes = false;
+        let colon = line.char_indices().find_map(|(i, c)| match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                None
+            }
+            ':' if !in_quotes => Some(i),
+            _ => None,
+        })?;
+
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+        let mut parts = head.split(';');
+        let name = parts.next()?.trim().to_ascii_uppercase();
+        let params = parts
+            .filter_map(|param| param.split_once('='))
+            .map(|(key, value)| {
+                (
+                    key.trim().to_ascii_uppercase(),
+                    value.trim().trim_matches('"').to_string(),
+                )
+            })
+            .collect();
+
+        Some(ContentLine {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    pub(crate) fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event() -> CalendarEvent<'static> {
+        CalendarEvent {
+            public_token: "abc",
+            title: "Team sync",
+            description: Some("Agenda; notes, etc.\nBring snacks"),
+            organizer_name: "Alice",
+            time_zone: Some("Asia/Tokyo"),
+            confirmed: true,
+        }
+    }
+
+    #[test]
+    fn test_render_final_slot() {
+        let slot = CalendarSlot {
+            id: 7,
+            start_at: Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap(),
+            end_at: Utc.with_ymd_and_hms(2030, 1, 1, 10, 0, 0).unwrap(),
+        };
+        let stamp = Utc.with_ymd_and_hms(2029, 12, 1, 0, 0, 0).unwrap();
+        let ics = render(&event(), &[slot], stamp);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:abc-7@agreed-time\r\n"));
+        assert!(ics.contains("DTSTART:20300101T090000Z\r\n"));
+        assert!(ics.contains("DTEND:20300101T100000Z\r\n"));
+        assert!(ics.contains("DTSTAMP:20291201T000000Z\r\n"));
+        assert!(ics.contains("X-WR-TIMEZONE:Asia/Tokyo\r\n"));
+        assert!(ics.contains("DESCRIPTION:Agenda\\; notes\\, etc.\\nBring snacks\r\n"));
+        assert!(ics.contains("ORGANIZER;CN=\"Alice\":"));
+        assert!(ics.contains("STATUS:CONFIRMED\r\n"));
+    }
+
+    #[test]
+    fn test_fold_long_lines() {
+        let long = format!("SUMMARY:{}", "日本語".repeat(20));
+        let folded = fold_line(&long);
+
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), long);
+    }
+
+    #[test]
+    fn test_escape_param_strips_quotes() {
+        assert_eq!(escape_param("Al\"ice"), "Alice");
+    }
+}
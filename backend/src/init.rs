@@ -0,0 +1,232 @@
+//! First-run setup used by the `init` subcommand.
+//!
+//! Collects the few settings a deployment needs (from flags, prompts or
+//! defaults), creates and migrates the database, mints an admin API key and
+//! writes everything to the env file `Config::from_env` reads.
+
+use std::{
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use rand::distributions::{Alphanumeric, DistString};
+use sqlx::{PgPool, Postgres, migrate::MigrateDatabase};
+
+pub const ADMIN_KEY_LENGTH: usize = 40;
+
+const DEFAULT_DATABASE_URL: &str = "postgres://localhost/agreed_time";
+const DEFAULT_PORT: u16 = 3000;
+const DEFAULT_PUBLIC_URL: &str = "http://localhost:4321";
+
+/// Settings given as flags. Missing ones are prompted for on a terminal and
+/// defaulted otherwise, or always with `assume_defaults`.
+#[derive(Debug, Default)]
+pub struct InitOptions {
+    pub env_file: PathBuf,
+    pub database_url: Option<String>,
+    pub port: Option<u16>,
+    /// Where the frontend is served; becomes `ALLOWED_ORIGINS`
+    pub public_url: Option<String>,
+    pub mail_from: Option<String>,
+    pub smtp_url: Option<String>,
+    pub assume_defaults: bool,
+    /// Replace an existing env file
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    pub database_url: String,
+    pub port: u16,
+    pub public_url: String,
+    pub mail_from: Option<String>,
+    pub smtp_url: Option<String>,
+    pub admin_api_key: String,
+}
+
+#[derive(Debug)]
+pub struct InitReport {
+    pub env_file: PathBuf,
+    pub settings: Settings,
+    pub database_created: bool,
+}
+
+impl InitReport {
+    pub fn render(&self) -> String {
+        let settings = &self.settings;
+        let mut out = format!(
+            "Wrote {} and {} the database.\n\nNext steps:\n",
+            self.env_file.display(),
+            if self.database_created {
+                "created and migrated"
+            } else {
+                "migrated"
+            }
+        );
+        let mut steps = Vec::new();
+        if self.env_file != Path::new(".env") {
+            steps.push(format!(
+                "Copy {} to .env in the directory the server runs from, or export its variables",
+                self.env_file.display()
+            ));
+        }
+        steps.push("Start the API: agreed-time-backend serve".to_string());
+        steps.push(format!(
+            "Serve the frontend from {} with its /api proxied to port {}",
+            settings.public_url, settings.port
+        ));
+        steps.push(format!(
+            "Check the admin API: curl -H \"Authorization: Bearer {}\" http://localhost:{}/admin/jobs",
+            settings.admin_api_key, settings.port
+        ));
+        if settings.mail_from.is_none() {
+            steps.push(
+                "Set MAIL_FROM (and SMTP_URL) to send calendar invitations and reminders"
+                    .to_string(),
+            );
+        }
+        for (i, step) in steps.iter().enumerate() {
+            out.push_str(&format!("  {}. {}\n", i + 1, step));
+        }
+        out.push_str("\nADMIN_API_KEY is only stored in the env file; keep it secret.\n");
+        out
+    }
+}
+
+pub fn generate_admin_key() -> String {
+    Alphanumeric.sample_string(&mut rand::thread_rng(), ADMIN_KEY_LENGTH)
+}
+
+/// Env file in the layout of `.env.example`; settings left out keep their
+/// defaults.
+pub fn render_env(settings: &Settings) -> String {
+    format!(
+        "# Written by `agreed-time-backend init`; see .env.example for every setting\n\
+         DATABASE_URL={}\n\
+         PORT={}\n\
+         HOST=0.0.0.0\n\
+         ALLOWED_ORIGINS={}\n\
+         # Bearer key for /admin/*\n\
+         ADMIN_API_KEY={}\n\
+         # Calendar invitations are disabled when MAIL_FROM is empty; mail is only logged when SMTP_URL is empty\n\
+         SMTP_URL={}\n\
+         MAIL_FROM={}\n\
+         # Email login links point here\n\
+         PUBLIC_URL={}\n",
+        settings.database_url,
+        settings.port,
+        settings.public_url,
+        settings.admin_api_key,
+        settings.smtp_url.as_deref().unwrap_or(""),
+        settings.mail_from.as_deref().unwrap_or(""),
+        settings.public_url,
+    )
+}
+
+fn ask(label: &str, default: &str) -> io::Result<String> {
+    print!("{} [{}]: ", label, default);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+fn resolve(options: &InitOptions) -> anyhow::Result<Settings> {
+    let interactive = !options.assume_defaults && io::stdin().is_terminal();
+    let value = |given: &Option<String>, label: &str, default: &str| -> io::Result<String> {
+        match given {
+            Some(value) => Ok(value.clone()),
+            None if interactive => ask(label, default),
+            None => Ok(default.to_string()),
+        }
+    };
+    let optional = |given: &Option<String>, label: &str| -> io::Result<Option<String>> {
+        Ok(Some(value(given, label, "")?).filter(|v| !v.is_empty()))
+    };
+
+    Ok(Settings {
+        database_url: value(&options.database_url, "Database URL", DEFAULT_DATABASE_URL)?,
+        port: match options.port {
+            Some(port) => port,
+            None => value(&None, "API port", &DEFAULT_PORT.to_string())?.parse()?,
+        },
+        public_url: value(
+            &options.public_url,
+            "Frontend URL (allowed CORS origin)",
+            DEFAULT_PUBLIC_URL,
+        )?,
+        mail_from: optional(&options.mail_from, "Sender address for mail (empty: none)")?,
+        smtp_url: optional(&options.smtp_url, "SMTP URL (empty: log mail only)")?,
+        admin_api_key: generate_admin_key(),
+    })
+}
+
+/// Creates the database if missing and applies every migration, then writes
+/// the env file, so a failed run leaves no half-configured deployment behind.
+pub async fn run(options: InitOptions) -> anyhow::Result<InitReport> {
+    if options.env_file.exists() && !options.force {
+        anyhow::bail!(
+            "{} already exists; pass --force to replace it",
+            options.env_file.display()
+        );
+    }
+
+    let settings = resolve(&options)?;
+
+    let database_created = !Postgres::database_exists(&settings.database_url).await?;
+    if database_created {
+        Postgres::create_database(&settings.database_url).await?;
+    }
+    let pool = PgPool::connect(&settings.database_url).await?;
+    crate::db::MIGRATOR.run(&pool).await?;
+    pool.close().await;
+
+    std::fs::write(&options.env_file, render_env(&settings))?;
+    // The file holds the admin key and possibly database and SMTP passwords
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&options.env_file, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(InitReport {
+        env_file: options.env_file,
+        settings,
+        database_created,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_env_round_trips_through_dotenv() {
+        let settings = Settings {
+            database_url: "postgres://app:secret@db/agreed_time".to_string(),
+            port: 8080,
+            public_url: "https://meet.example.com".to_string(),
+            mail_from: Some("polls@example.com".to_string()),
+            smtp_url: None,
+            admin_api_key: generate_admin_key(),
+        };
+        let rendered = render_env(&settings);
+        let vars: std::collections::HashMap<String, String> =
+            dotenvy::from_read_iter(rendered.as_bytes())
+                .map(|item| item.unwrap())
+                .collect();
+
+        assert_eq!(vars["DATABASE_URL"], settings.database_url);
+        assert_eq!(vars["PORT"], "8080");
+        assert_eq!(vars["ALLOWED_ORIGINS"], "https://meet.example.com");
+        assert_eq!(vars["PUBLIC_URL"], "https://meet.example.com");
+        assert_eq!(vars["ADMIN_API_KEY"].len(), ADMIN_KEY_LENGTH);
+        assert_eq!(vars["MAIL_FROM"], "polls@example.com");
+        assert_eq!(vars["SMTP_URL"], "");
+    }
+}
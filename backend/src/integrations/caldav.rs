@@ -0,0 +1,352 @@
+//! CalDAV free/busy import (RFC 4791 §7.10).
+//!
+//! A participant hands over their calendar collection URL and credentials;
+//! we send one `free-busy-query` REPORT for the event's window and turn the
+//! `VFREEBUSY` answer into ranges that prefill their grid. Credentials are
+//! only used for that request and never stored or logged.
+//!
+//! The server makes the request, so it goes through
+//! [`outbound`](crate::integrations::outbound): unless the deployment allows
+//! private networks (`CALDAV_IMPORT=any`), hosts resolving to loopback,
+//! private or link-local addresses are refused, and redirects are never
+//! followed.
+
+use std::time::Duration;
+
+use agreed_time_core::{TimeRange, subtract_time_ranges};
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::{
+    ics::{ContentLine, format_utc, unfold},
+    integrations::outbound::{self, OutboundError},
+};
+
+/// A server that doesn't answer within this is reported as unavailable.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Free/busy answers are small; anything bigger is not one.
+pub const MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+
+pub struct Credentials<'a> {
+    pub username: &'a str,
+    pub password: &'a str,
+}
+
+/// A period the calendar reports as taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusyPeriod {
+    pub range: TimeRange,
+    /// `FBTYPE=BUSY-TENTATIVE`: only tentatively accepted events
+    pub tentative: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum CaldavError {
+    #[error("not an http(s) URL with a host and without embedded credentials")]
+    InvalidUrl,
+    #[error("host resolves to a loopback, private or link-local address")]
+    ForbiddenHost,
+    #[error("the server rejected the credentials")]
+    Unauthorized,
+    #[error("the server could not be reached: {0}")]
+    Unreachable(String),
+    #[error("the server answered {0}")]
+    Status(u16),
+    #[error("the response is larger than {} bytes", MAX_RESPONSE_BYTES)]
+    TooLarge,
+    #[error("invalid free/busy response: {0}")]
+    Malformed(&'static str),
+}
+
+/// Busy periods of the calendar at `url` overlapping `window`.
+pub async fn fetch_free_busy(
+    url: &str,
+    credentials: Option<Credentials<'_>>,
+    window: TimeRange,
+    allow_private: bool,
+) -> Result<Vec<BusyPeriod>, CaldavError> {
+    let parsed = reqwest::Url::parse(url).map_err(|_| CaldavError::InvalidUrl)?;
+    // Userinfo would end up in error messages; credentials go in their own fields
+    if !matches!(parsed.scheme(), "http" | "https")
+        || !parsed.username().is_empty()
+        || parsed.password().is_some()
+    {
+        return Err(CaldavError::InvalidUrl);
+    }
+    let target = outbound::resolve(&parsed, allow_private)
+        .await
+        .map_err(|e| match e {
+            OutboundError::InvalidUrl => CaldavError::InvalidUrl,
+            OutboundError::ForbiddenHost => CaldavError::ForbiddenHost,
+            OutboundError::Unresolvable(reason) => CaldavError::Unreachable(reason),
+        })?;
+
+    let client = outbound::pinned_client(&target, REQUEST_TIMEOUT)
+        .map_err(|e| CaldavError::Unreachable(e.without_url().to_string()))?;
+    let method = reqwest::Method::from_bytes(b"REPORT").expect("REPORT is a valid method");
+    let mut request = client
+        .request(method, parsed)
+        .header("Depth", "1")
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/xml; charset=utf-8",
+        )
+        .body(query_body(window));
+    if let Some(credentials) = credentials {
+        request = request.basic_auth(credentials.username, Some(credentials.password));
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| CaldavError::Unreachable(e.without_url().to_string()))?;
+
+    match response.status().as_u16() {
+        200..=299 => {}
+        401 | 403 => return Err(CaldavError::Unauthorized),
+        status => return Err(CaldavError::Status(status)),
+    }
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_RESPONSE_BYTES as u64)
+    {
+        return Err(CaldavError::TooLarge);
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| CaldavError::Unreachable(e.without_url().to_string()))?
+    {
+        if body.len() + chunk.len() > MAX_RESPONSE_BYTES {
+            return Err(CaldavError::TooLarge);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let calendar = String::from_utf8(body).map_err(|_| CaldavError::Malformed("not UTF-8"))?;
+
+    parse_free_busy(&calendar)
+}
+
+fn query_body(window: TimeRange) -> String {
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<C:free-busy-query xmlns:C="urn:ietf:params:xml:ns:caldav">"#,
+            r#"<C:time-range start="{}" end="{}"/>"#,
+            r#"</C:free-busy-query>"#
+        ),
+        format_utc(window.start_at),
+        format_utc(window.end_at)
+    )
+}
+
+/// Reads the `FREEBUSY` properties of every `VFREEBUSY` in `calendar`.
+/// `FBTYPE=FREE` periods are skipped; unknown types count as busy.
+pub fn parse_free_busy(calendar: &str) -> Result<Vec<BusyPeriod>, CaldavError> {
+    let mut in_free_busy = false;
+    let mut seen = false;
+    let mut periods = Vec::new();
+
+    for line in unfold(calendar) {
+        let Some(property) = ContentLine::parse(&line) else {
+            continue;
+        };
+        match property.name.as_str() {
+            "BEGIN" if property.value.eq_ignore_ascii_case("VFREEBUSY") => {
+                in_free_busy = true;
+                seen = true;
+            }
+            "END" if property.value.eq_ignore_ascii_case("VFREEBUSY") => in_free_busy = false,
+            "FREEBUSY" if in_free_busy => {
+                let tentative = match property.param("FBTYPE") {
+                    Some(kind) if kind.eq_ignore_ascii_case("FREE") => continue,
+                    Some(kind) => kind.eq_ignore_ascii_case("BUSY-TENTATIVE"),
+                    None => false,
+                };
+                for period in property.value.split(',') {
+                    periods.push(BusyPeriod {
+                        range: parse_period(period.trim())?,
+                        tentative,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !seen {
+        return Err(CaldavError::Malformed("no VFREEBUSY component"));
+    }
+    Ok(periods)
+}
+
+/// `start/end` or `start/duration`, both in UTC as RFC 5545 requires here.
+fn parse_period(period: &str) -> Result<TimeRange, CaldavError> {
+    let (start, end) = period
+        .split_once('/')
+        .ok_or(CaldavError::Malformed("FREEBUSY period without '/'"))?;
+    let start_at = parse_utc(start)?;
+    let end_at = if end.starts_with(['P', '+', '-']) {
+        start_at + parse_duration(end)?
+    } else {
+        parse_utc(end)?
+    };
+    if end_at <= start_at {
+        return Err(CaldavError::Malformed(
+            "FREEBUSY period ends before it starts",
+        ));
+    }
+    Ok(TimeRange::new(start_at, end_at))
+}
+
+fn parse_utc(value: &str) -> Result<DateTime<Utc>, CaldavError> {
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+        .map(|at| at.and_utc())
+        .map_err(|_| CaldavError::Malformed("FREEBUSY time is not UTC date-time"))
+}
+
+/// `P1W`, `PT1H30M`, `P1DT12H` (RFC 5545 §3.3.6).
+fn parse_duration(value: &str) -> Result<chrono::Duration, CaldavError> {
+    const INVALID: CaldavError = CaldavError::Malformed("invalid FREEBUSY duration");
+
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let value = value.strip_prefix('P').ok_or(INVALID)?;
+    let mut seconds: i64 = 0;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in value.chars() {
+        let unit = match c {
+            '0'..='9' => {
+                number.push(c);
+                continue;
+            }
+            'T' if !in_time && number.is_empty() => {
+                in_time = true;
+                continue;
+            }
+            'W' if !in_time => 7 * 86_400,
+            'D' if !in_time => 86_400,
+            'H' if in_time => 3_600,
+            'M' if in_time => 60,
+            'S' if in_time => 1,
+            _ => return Err(INVALID),
+        };
+        let count: i64 = number.parse().map_err(|_| INVALID)?;
+        seconds = count
+            .checked_mul(unit)
+            .and_then(|part| seconds.checked_add(part))
+            .ok_or(INVALID)?;
+        number.clear();
+    }
+    if !number.is_empty() || seconds == 0 {
+        return Err(INVALID);
+    }
+    Ok(chrono::Duration::seconds(if negative {
+        -seconds
+    } else {
+        seconds
+    }))
+}
+
+/// Splits `slots` into the parts the calendar leaves free and those only
+/// blocked by tentative events, which suit `if_need_be`.
+pub fn free_ranges(slots: &[TimeRange], busy: &[BusyPeriod]) -> (Vec<TimeRange>, Vec<TimeRange>) {
+    let periods = |tentative: bool| -> Vec<TimeRange> {
+        busy.iter()
+            .filter(|period| period.tentative == tentative)
+            .map(|period| period.range)
+            .collect()
+    };
+
+    let open = subtract_time_ranges(slots.to_vec(), &periods(false));
+    let free = subtract_time_ranges(open.clone(), &periods(true));
+    let if_need_be = subtract_time_ranges(open, &free);
+    (free, if_need_be)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2030, 1, 7, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_free_busy() {
+        let calendar = concat!(
+            "BEGIN:VCALENDAR\r\n",
+            "VERSION:2.0\r\n",
+            "BEGIN:VFREEBUSY\r\n",
+            "DTSTART:20300107T000000Z\r\n",
+            "FREEBUSY;FBTYPE=BUSY:20300107T090000Z/20300107T100000Z,20300107T1300\r\n",
+            " 00Z/PT1H30M\r\n",
+            "FREEBUSY;FBTYPE=BUSY-TENTATIVE:20300107T110000Z/PT30M\r\n",
+            "FREEBUSY;FBTYPE=FREE:20300107T120000Z/PT1H\r\n",
+            "FREEBUSY:20300107T160000Z/P1D\r\n",
+            "END:VFREEBUSY\r\n",
+            "END:VCALENDAR\r\n",
+        );
+        let busy = |start, end, tentative| BusyPeriod {
+            range: TimeRange::new(start, end),
+            tentative,
+        };
+        assert_eq!(
+            parse_free_busy(calendar).unwrap(),
+            vec![
+                busy(at(9, 0), at(10, 0), false),
+                busy(at(13, 0), at(14, 30), false),
+                busy(at(11, 0), at(11, 30), true),
+                busy(at(16, 0), at(16, 0) + chrono::Duration::days(1), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_free_busy_rejects_other_content() {
+        assert_eq!(
+            parse_free_busy("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n"),
+            Err(CaldavError::Malformed("no VFREEBUSY component"))
+        );
+        for period in [
+            "20300107T090000/PT1H",
+            "20300107T090000Z/PT",
+            "20300107T090000Z/P1H",
+            "20300107T090000Z/20300107T080000Z",
+        ] {
+            let calendar = format!(
+                "BEGIN:VFREEBUSY\r\nFREEBUSY:{}\r\nEND:VFREEBUSY\r\n",
+                period
+            );
+            assert!(parse_free_busy(&calendar).is_err(), "{}", period);
+        }
+    }
+
+    #[test]
+    fn test_free_ranges_marks_tentative_time_if_need_be() {
+        let slots = [TimeRange::new(at(9, 0), at(13, 0))];
+        let busy = [
+            BusyPeriod {
+                range: TimeRange::new(at(10, 0), at(11, 0)),
+                tentative: false,
+            },
+            BusyPeriod {
+                range: TimeRange::new(at(10, 30), at(12, 0)),
+                tentative: true,
+            },
+        ];
+        let (free, if_need_be) = free_ranges(&slots, &busy);
+        assert_eq!(
+            free,
+            vec![
+                TimeRange::new(at(9, 0), at(10, 0)),
+                TimeRange::new(at(12, 0), at(13, 0)),
+            ]
+        );
+        assert_eq!(if_need_be, vec![TimeRange::new(at(11, 0), at(12, 0))]);
+    }
+}
@@ -0,0 +1,276 @@
+//! iTIP (RFC 5546) scheduling messages on top of the iCalendar writer.
+//!
+//! Finalizing an event sends each invitee a `METHOD:REQUEST` so their
+//! calendar client offers native accept/decline; the client answers with a
+//! `METHOD:REPLY` to the organizer address, which is fed back through
+//! [`parse_reply`]. Every final slot is its own VEVENT whose UID is derived
+//! from the event's public token and the slot's position, so a later
+//! finalize (with a higher SEQUENCE) updates the same calendar entries.
+
+use chrono::{DateTime, Utc};
+
+use crate::ics::{ContentLine, escape_param, escape_text, fold_line, format_utc, unfold};
+
+const PRODID: &str = "-//agreed-time//Scheduling//EN";
+const UID_SUFFIX: &str = "@agreed-time";
+
+pub struct Invitation<'a> {
+    pub public_token: &'a str,
+    pub title: &'a str,
+    pub description: Option<&'a str>,
+    pub organizer_name: &'a str,
+    /// Address replies are sent to
+    pub organizer_email: &'a str,
+    pub time_zone: Option<&'a str>,
+    /// Incremented on every finalize; becomes the VEVENT SEQUENCE
+    pub revision: i32,
+    pub slots: &'a [(DateTime<Utc>, DateTime<Utc>)],
+}
+
+pub struct Attendee<'a> {
+    pub name: &'a str,
+    pub email: &'a str,
+}
+
+/// Renders a REQUEST addressed to a single attendee. Each invitee gets their
+/// own copy so addresses are never disclosed to other participants.
+pub fn render_request(
+    invitation: &Invitation,
+    attendee: &Attendee,
+    stamp: DateTime<Utc>,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:REQUEST".to_string(),
+    ];
+    if let Some(tz) = invitation.time_zone {
+        lines.push(format!("X-WR-TIMEZONE:{}", escape_text(tz)));
+    }
+
+    for (index, (start_at, end_at)) in invitation.slots.iter().enumerate() {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", slot_uid(invitation.public_token, index)));
+        lines.push(format!("SEQUENCE:{}", invitation.revision));
+        lines.push(format!("DTSTAMP:{}", format_utc(stamp)));
+        lines.push(format!("DTSTART:{}", format_utc(*start_at)));
+        lines.push(format!("DTEND:{}", format_utc(*end_at)));
+        lines.push(format!("SUMMARY:{}", escape_text(invitation.title)));
+        if let Some(description) = invitation.description.filter(|d| !d.is_empty()) {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        lines.push(format!(
+            "ORGANIZER;CN=\"{}\":mailto:{}",
+            escape_param(invitation.organizer_name),
+            invitation.organizer_email
+        ));
+        lines.push(format!(
+            "ATTENDEE;CN=\"{}\";ROLE=REQ-PARTICIPANT;PARTSTAT=NEEDS-ACTION;RSVP=TRUE:mailto:{}",
+            escape_param(attendee.name),
+            attendee.email
+        ));
+        lines.push("STATUS:CONFIRMED".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_line(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn slot_uid(public_token: &str, index: usize) -> String {
+    format!("{}-final-{}{}", public_token, index, UID_SUFFIX)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartStat {
+    Accepted,
+    Declined,
+    Tentative,
+}
+
+impl PartStat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PartStat::Accepted => "accepted",
+            PartStat::Declined => "declined",
+            PartStat::Tentative => "tentative",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Reply {
+    pub public_token: String,
+    pub attendee_email: String,
+    pub partstat: PartStat,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ReplyError {
+    #[error("not an iTIP REPLY")]
+    NotAReply,
+    #[error("missing {0}")]
+    Missing(&'static str),
+    #[error("UID was not issued by this service")]
+    ForeignUid,
+    #[error("unsupported PARTSTAT {0}")]
+    UnsupportedPartStat(String),
+}
+
+/// Extracts the attendee's answer from a REPLY. Only the first VEVENT is
+/// read: all VEVENTs of one invitation share the same attendee and answer.
+pub fn parse_reply(calendar: &str) -> Result<Reply, ReplyError> {
+    let lines = unfold(calendar);
+
+    let mut is_reply = false;
+    let mut in_event = false;
+    let mut uid = None;
+    let mut attendee = None;
+
+    for line in &lines {
+        let Some(property) = ContentLine::parse(line) else {
+            continue;
+        };
+        match property.name.as_str() {
+            "METHOD" => is_reply = property.value.eq_ignore_ascii_case("REPLY"),
+            "BEGIN" if property.value.eq_ignore_ascii_case("VEVENT") => in_event = true,
+            "END" if property.value.eq_ignore_ascii_case("VEVENT") => break,
+            "UID" if in_event => uid = Some(property.value.clone()),
+            "ATTENDEE" if in_event && attendee.is_none() => attendee = Some(property),
+            _ => {}
+        }
+    }
+
+    if !is_reply {
+        return Err(ReplyError::NotAReply);
+    }
+    let uid = uid.ok_or(ReplyError::Missing("UID"))?;
+    let attendee = attendee.ok_or(ReplyError::Missing("ATTENDEE"))?;
+
+    let public_token = uid
+        .strip_suffix(UID_SUFFIX)
+        .and_then(|rest| rest.rsplit_once("-final-"))
+        .filter(|(_, index)| index.parse::<usize>().is_ok())
+        .map(|(token, _)| token.to_string())
+        .ok_or(ReplyError::ForeignUid)?;
+
+    let attendee_email = attendee
+        .value
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map(|_| attendee.value[7..].trim().to_string())
+        .filter(|email| !email.is_empty())
+        .ok_or(ReplyError::Missing("ATTENDEE mailto address"))?;
+
+    let partstat = match attendee.param("PARTSTAT") {
+        Some(value) if value.eq_ignore_ascii_case("ACCEPTED") => PartStat::Accepted,
+        Some(value) if value.eq_ignore_ascii_case("DECLINED") => PartStat::Declined,
+        Some(value) if value.eq_ignore_ascii_case("TENTATIVE") => PartStat::Tentative,
+        Some(value) => return Err(ReplyError::UnsupportedPartStat(value.to_string())),
+        None => return Err(ReplyError::Missing("PARTSTAT")),
+    };
+
+    Ok(Reply {
+        public_token,
+        attendee_email,
+        partstat,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn invitation<'a>(slots: &'a [(DateTime<Utc>, DateTime<Utc>)]) -> Invitation<'a> {
+        Invitation {
+            public_token: "pub-tok",
+            title: "Kickoff",
+            description: None,
+            organizer_name: "Alice",
+            organizer_email: "schedule@example.com",
+            time_zone: Some("UTC"),
+            revision: 2,
+            slots,
+        }
+    }
+
+    #[test]
+    fn test_render_request() {
+        let slots = [(
+            Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2030, 1, 1, 10, 0, 0).unwrap(),
+        )];
+        let ics = render_request(
+            &invitation(&slots),
+            &Attendee {
+                name: "Bob",
+                email: "bob@example.com",
+            },
+            Utc.with_ymd_and_hms(2029, 12, 1, 0, 0, 0).unwrap(),
+        );
+
+        assert!(ics.contains("METHOD:REQUEST\r\n"));
+        assert!(ics.contains("UID:pub-tok-final-0@agreed-time\r\n"));
+        assert!(ics.contains("SEQUENCE:2\r\n"));
+        assert!(ics.contains("ORGANIZER;CN=\"Alice\":mailto:schedule@example.com\r\n"));
+        // Folded because it exceeds 75 octets
+        assert!(
+            ics.replace("\r\n ", "")
+                .contains("ATTENDEE;CN=\"Bob\";ROLE=REQ-PARTICIPANT;PARTSTAT=NEEDS-ACTION;RSVP=TRUE:mailto:bob@example.com\r\n")
+        );
+    }
+
+    #[test]
+    fn test_parse_reply_roundtrip_folded() {
+        let reply = "BEGIN:VCALENDAR\r\nMETHOD:REPLY\r\nBEGIN:VEVENT\r\nUID:pub-tok-final-0@agreed-time\r\n\
+                     ATTENDEE;CN=\"Bob: the builder\";PARTSTAT=ACCEPTED:mailto:b\r\n ob@example.com\r\n\
+                     END:VEVENT\r\nEND:VCALENDAR\r\n";
+
+        assert_eq!(
+            parse_reply(reply),
+            Ok(Reply {
+                public_token: "pub-tok".to_string(),
+                attendee_email: "bob@example.com".to_string(),
+                partstat: PartStat::Accepted,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_reply_rejects_request_and_foreign_uid() {
+        let request = "BEGIN:VCALENDAR\nMETHOD:REQUEST\nBEGIN:VEVENT\nUID:x-final-0@agreed-time\nEND:VEVENT\n";
+        assert_eq!(parse_reply(request), Err(ReplyError::NotAReply));
+
+        let foreign = "METHOD:REPLY\nBEGIN:VEVENT\nUID:abc@google.com\n\
+                       ATTENDEE;PARTSTAT=DECLINED:mailto:bob@example.com\nEND:VEVENT\n";
+        assert_eq!(parse_reply(foreign), Err(ReplyError::ForeignUid));
+    }
+
+    #[test]
+    fn test_parse_reply_partstat() {
+        let reply = |partstat: &str| {
+            format!(
+                "METHOD:REPLY\nBEGIN:VEVENT\nUID:t-final-1@agreed-time\n\
+                 ATTENDEE;PARTSTAT={}:MAILTO:bob@example.com\nEND:VEVENT\n",
+                partstat
+            )
+        };
+
+        assert_eq!(
+            parse_reply(&reply("tentative")).unwrap().partstat,
+            PartStat::Tentative
+        );
+        assert_eq!(
+            parse_reply(&reply("DELEGATED")),
+            Err(ReplyError::UnsupportedPartStat("DELEGATED".to_string()))
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Transactional email outbox.
+//!
+//! Handlers call [`enqueue`] inside the transaction that caused the mail, so
+//! a rolled-back change never sends anything. A background task drains the
+//! outbox through a [`Mailer`] with [`deliver_pending`], retrying failures up
+//! to [`MAX_ATTEMPTS`] times.
+
+use std::future::Future;
+
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, MultiPart, SinglePart, header::ContentType},
+};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{clock::Clock, config::Config};
+
+/// Deliveries are abandoned after this many failed attempts.
+pub const MAX_ATTEMPTS: i32 = 5;
+
+/// Rows sent per [`deliver_pending`] call.
+const BATCH_SIZE: i64 = 50;
+
+#[derive(Debug, Clone)]
+pub struct OutgoingMail {
+    pub id: i64,
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+    /// Sent as an alternative to `body` when present
+    pub html: Option<String>,
+    /// iCalendar attachment, sent as a `text/calendar` alternative part
+    pub calendar: Option<String>,
+    /// iTIP method of `calendar` (e.g. `REQUEST`)
+    pub calendar_method: Option<String>,
+}
+
+pub trait Mailer: Send + Sync {
+    fn send(&self, mail: &OutgoingMail) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(smtp_url: &str, from: &str) -> anyhow::Result<Self> {
+        Ok(SmtpMailer {
+            transport: AsyncSmtpTransport::<Tokio1Executor>::from_url(smtp_url)?.build(),
+            from: from.parse()?,
+        })
+    }
+
+    fn build(&self, mail: &OutgoingMail) -> Result<Message, String> {
+        let builder = Message::builder()
+            .from(self.from.clone())
+            .to(mail.recipient.parse().map_err(|e| format!("{}", e))?)
+            .subject(&mail.subject);
+
+        if mail.html.is_none() && mail.calendar.is_none() {
+            return builder.body(mail.body.clone()).map_err(|e| e.to_string());
+        }
+
+        let mut alternatives =
+            MultiPart::alternative().singlepart(SinglePart::plain(mail.body.clone()));
+        if let Some(html) = &mail.html {
+            alternatives = alternatives.singlepart(SinglePart::html(html.clone()));
+        }
+        if let Some(calendar) = &mail.calendar {
+            let method = mail.calendar_method.as_deref().unwrap_or("PUBLISH");
+            let content_type =
+                ContentType::parse(&format!("text/calendar; charset=utf-8; method={}", method))
+                    .map_err(|e| e.to_string())?;
+            alternatives = alternatives.singlepart(
+                SinglePart::builder()
+                    .header(content_type)
+                    .body(calendar.clone()),
+            );
+        }
+        let message = builder.multipart(alternatives);
+        message.map_err(|e| e.to_string())
+    }
+}
+
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: &OutgoingMail) -> Result<(), String> {
+        let message = self.build(mail)?;
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Development fallback when `SMTP_URL` is unset: mail is logged, not sent.
+pub struct LogMailer;
+
+impl Mailer for LogMailer {
+    async fn send(&self, mail: &OutgoingMail) -> Result<(), String> {
+        tracing::info!(
+            "Mail to {} (not sent, SMTP_URL unset): {}",
+            mail.recipient,
+            mail.subject
+        );
+        Ok(())
+    }
+}
+
+pub enum ConfiguredMailer {
+    Smtp(Box<SmtpMailer>),
+    Log(LogMailer),
+}
+
+impl ConfiguredMailer {
+    /// `None` when `MAIL_FROM` is unset, i.e. mail is disabled entirely.
+    pub fn from_config(config: &Config) -> anyhow::Result<Option<Self>> {
+        let Some(from) = &config.mail_from else {
+            return Ok(None);
+        };
+        Ok(Some(match &config.smtp_url {
+            Some(url) => ConfiguredMailer::Smtp(Box::new(SmtpMailer::new(url, from)?)),
+            None => ConfiguredMailer::Log(LogMailer),
+        }))
+    }
+}
+
+impl Mailer for ConfiguredMailer {
+    async fn send(&self, mail: &OutgoingMail) -> Result<(), String> {
+        match self {
+            ConfiguredMailer::Smtp(mailer) => mailer.send(mail).await,
+            ConfiguredMailer::Log(mailer) => mailer.send(mail).await,
+        }
+    }
+}
+
+pub struct NewMail<'a> {
+    /// `None` for account mail such as login links
+    pub event_id: Option<Uuid>,
+    /// The invitee, for calendar invitations; their delivery is reported
+    /// per participant
+    pub participant_id: Option<i64>,
+    pub recipient: &'a str,
+    pub subject: &'a str,
+    pub body: &'a str,
+    pub html: Option<&'a str>,
+    /// `(method, calendar)` for an iTIP attachment
+    pub calendar: Option<(&'a str, &'a str)>,
+}
+
+pub async fn enqueue(conn: &mut PgConnection, mail: NewMail<'_>) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO email_outbox (event_id, participant_id, recipient, subject, body, html, calendar_method, calendar)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        mail.event_id,
+        mail.participant_id,
+        mail.recipient,
+        mail.subject,
+        mail.body,
+        mail.html,
+        mail.calendar.map(|(method, _)| method),
+        mail.calendar.map(|(_, calendar)| calendar)
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DeliveryReport {
+    pub sent: u64,
+    pub failed: u64,
+}
+
+/// Sends one batch of pending mail. Rows are locked with `SKIP LOCKED` so
+/// several instances can drain the outbox concurrently. Mail for trashed
+/// events is held back and goes out if the event is restored.
+pub async fn deliver_pending<M: Mailer>(
+    pool: &PgPool,
+    mailer: &M,
+    clock: &dyn Clock,
+) -> Result<DeliveryReport, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+
+    let pending = sqlx::query_as!(
+        OutgoingMail,
+        r#"
+        SELECT id, recipient, subject, body, html, calendar, calendar_method
+        FROM email_outbox
+        WHERE sent_at IS NULL AND attempts < $1
+          AND NOT EXISTS (
+              SELECT 1 FROM events e WHERE e.id = email_outbox.event_id AND e.deleted_at IS NOT NULL
+          )
+        ORDER BY id
+        LIMIT $2
+        FOR UPDATE SKIP LOCKED
+        "#,
+        MAX_ATTEMPTS,
+        BATCH_SIZE
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    let mut report = DeliveryReport::default();
+    for mail in &pending {
+        match mailer.send(mail).await {
+            Ok(()) => {
+                sqlx::query!(
+                    "UPDATE email_outbox SET sent_at = $1, attempts = attempts + 1, last_error = NULL WHERE id = $2",
+                    clock.now(),
+                    mail.id
+                )
+                .execute(&mut *transaction)
+                .await?;
+                report.sent += 1;
+            }
+            Err(error) => {
+                tracing::warn!("Mail {} to {} failed: {}", mail.id, mail.recipient, error);
+                sqlx::query!(
+                    "UPDATE email_outbox SET attempts = attempts + 1, last_error = $1 WHERE id = $2",
+                    error,
+                    mail.id
+                )
+                .execute(&mut *transaction)
+                .await?;
+                report.failed += 1;
+            }
+        }
+    }
+
+    transaction.commit().await?;
+
+    Ok(report)
+}
@@ -0,0 +1,9 @@
+//! Outbound and inbound integrations with external systems.
+
+pub mod caldav;
+pub mod itip;
+pub mod mail;
+pub mod notifications;
+pub mod outbound;
+pub mod templates;
+pub mod webhooks;
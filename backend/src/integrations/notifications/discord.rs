@@ -0,0 +1,118 @@
+//! Discord webhook messages.
+
+use serde_json::{Value, json};
+
+use super::{Activity, Notifier, Summary};
+
+/// `webhook_deliveries.channel` of Discord messages.
+pub const CHANNEL: &str = "discord";
+
+/// Channel webhooks are created under either host; anything else is refused
+/// so the field can't be used to make the server post elsewhere.
+pub const URL_PREFIXES: [&str; 2] = [
+    "https://discord.com/api/webhooks/",
+    "https://discordapp.com/api/webhooks/",
+];
+
+/// Embed accent, the frontend's `film-accent` cyan.
+const COLOR: u32 = 0x0891b2;
+
+pub struct Discord;
+
+impl Notifier for Discord {
+    fn channel(&self) -> &'static str {
+        CHANNEL
+    }
+
+    fn accepts(&self, url: &reqwest::Url) -> bool {
+        URL_PREFIXES
+            .iter()
+            .any(|prefix| url.as_str().starts_with(prefix))
+    }
+
+    fn requirement(&self) -> String {
+        format!("Discord webhook URL must start with {}", URL_PREFIXES[0])
+    }
+
+    /// The headline as `content`, slots in an embed titled after the event
+    /// and linking to the results. Mentions are disabled so a name like
+    /// `@everyone` stays text.
+    fn message(&self, activity: Activity<'_>, summary: &Summary) -> Value {
+        let mut embed = json!({
+            "title": truncate(&summary.title, 256),
+            "color": COLOR,
+        });
+        let lines = summary.slot_lines(activity);
+        if !lines.is_empty() {
+            embed["description"] = Value::from(lines.join("\n"));
+        }
+        if let Some(link) = &summary.results_link {
+            embed["url"] = Value::from(link.as_str());
+        }
+
+        json!({
+            "content": summary.headline(activity, |name| format!("**{}**", escape(name))),
+            "embeds": [embed],
+            "allowed_mentions": { "parse": [] },
+        })
+    }
+}
+
+/// Backslash-escapes Discord's markdown characters.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '*' | '_' | '~' | '`' | '|' | '>' | '#' | '[' | ']'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Embed titles are capped by Discord, which rejects longer ones outright.
+fn truncate(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::notifications::test_summary;
+
+    #[test]
+    fn test_message_escapes_names_and_disables_mentions() {
+        let message = Discord.message(
+            Activity::Responded {
+                participant_name: "@everyone *Bob*",
+            },
+            &test_summary(),
+        );
+        assert_eq!(
+            message["content"],
+            "**@everyone \\*Bob\\*** responded to **Q&A <sync\\>** (3 responses so far)"
+        );
+        assert_eq!(message["allowed_mentions"]["parse"], json!([]));
+        let embed = &message["embeds"][0];
+        assert_eq!(embed["title"], "Q&A <sync>");
+        assert_eq!(embed["url"], "https://meet.example.com/event/abc/result");
+        assert_eq!(
+            embed["description"],
+            "Best slot so far: Tue 2030-01-01 09:00–10:00 UTC (2 of 3 available)"
+        );
+    }
+
+    #[test]
+    fn test_accepts_only_discord_webhooks() {
+        let accepts = |url: &str| Discord.accepts(&reqwest::Url::parse(url).unwrap());
+        assert!(accepts("https://discord.com/api/webhooks/1/abc"));
+        assert!(accepts("https://discordapp.com/api/webhooks/1/abc"));
+        assert!(!accepts(
+            "https://discord.com.example.com/api/webhooks/1/abc"
+        ));
+        assert!(!accepts("https://example.com/api/webhooks/1/abc"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Plain JSON messages for chat bridges and bots without a dedicated
+//! adapter.
+
+use serde_json::{Value, json};
+
+use super::{Activity, Notifier, Summary};
+
+/// `webhook_deliveries.channel` of generic JSON messages.
+pub const CHANNEL: &str = "chat";
+
+pub struct GenericJson;
+
+impl Notifier for GenericJson {
+    fn channel(&self) -> &'static str {
+        CHANNEL
+    }
+
+    /// Any `http(s)` host, as for `webhook_url`.
+    fn accepts(&self, url: &reqwest::Url) -> bool {
+        matches!(url.scheme(), "http" | "https") && url.host().is_some()
+    }
+
+    fn requirement(&self) -> String {
+        "Invalid chat webhook URL".to_string()
+    }
+
+    /// The rendered `text` for services that just relay it, and the same
+    /// content as fields for anything that formats its own.
+    fn message(&self, activity: Activity<'_>, summary: &Summary) -> Value {
+        let mut lines = vec![summary.headline(activity, str::to_string)];
+        lines.extend(summary.slot_lines(activity));
+        if let Some(link) = &summary.results_link {
+            lines.push(link.clone());
+        }
+        let slots: Vec<Value> = summary
+            .slots
+            .iter()
+            .map(|(range, count)| {
+                json!({
+                    "start_at": range.start_at,
+                    "end_at": range.end_at,
+                    "available": count,
+                })
+            })
+            .collect();
+
+        json!({
+            "type": activity.webhook_event().as_str(),
+            "text": lines.join("\n"),
+            "title": summary.title,
+            "participant_name": match activity {
+                Activity::Responded { participant_name } => Some(participant_name),
+                _ => None,
+            },
+            "respondents": summary.respondents,
+            "final": matches!(activity, Activity::Finalized),
+            "slots": slots,
+            "results_url": summary.results_link,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::notifications::test_summary;
+
+    #[test]
+    fn test_message_has_text_and_fields() {
+        let message = GenericJson.message(Activity::Closed, &test_summary());
+        assert_eq!(
+            message["text"],
+            "Q&A <sync> is closed for responses\n\
+             Best slot so far: Tue 2030-01-01 09:00–10:00 UTC (2 of 3 available)\n\
+             https://meet.example.com/event/abc/result"
+        );
+        assert_eq!(message["type"], "event.closed");
+        assert_eq!(message["participant_name"], Value::Null);
+        assert_eq!(message["final"], false);
+        assert_eq!(message["slots"][0]["start_at"], "2030-01-01T09:00:00Z");
+        assert_eq!(message["slots"][0]["available"], 2);
+    }
+}
@@ -0,0 +1,324 @@
+//! Chat messages on event activity.
+//!
+//! Each event picks its chat services by setting their webhook URLs: Slack
+//! (`slack_webhook_url`), Discord (`discord_webhook_url`) or any service
+//! taking plain JSON (`chat_webhook_url`). Someone responding, the event
+//! closing and the event being finalized each queue one message per
+//! configured service, naming the best slot so far (or the final one) and,
+//! with `PUBLIC_URL` set, linking to the results. Messages ride the webhook
+//! queue under the notifier's `channel`, so they share its retries and
+//! delivery log but go out unsigned.
+
+pub mod discord;
+pub mod generic;
+pub mod slack;
+
+use agreed_time_core::{ParticipantRanges, TimeRange};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    integrations::{
+        outbound::{self, OutboundError},
+        webhooks::WebhookEvent,
+    },
+    models::{ResultsVisibility, SlotKind},
+};
+
+pub use discord::Discord;
+pub use generic::GenericJson;
+pub use slack::Slack;
+
+/// Same limit as the webhook URL columns.
+pub const MAX_URL_LENGTH: usize = 2048;
+
+/// A chat service messages can be posted to.
+pub trait Notifier: Sync {
+    /// `webhook_deliveries.channel` of its messages
+    fn channel(&self) -> &'static str;
+
+    /// Whether the organizer may point the event at `url`
+    fn accepts(&self, url: &reqwest::Url) -> bool;
+
+    /// Why a refused URL was refused
+    fn requirement(&self) -> String;
+
+    /// The JSON body posted for `activity`
+    fn message(&self, activity: Activity<'_>, summary: &Summary) -> Value;
+}
+
+/// Checks a webhook URL against what `notifier` accepts, and refuses hosts
+/// resolving to a loopback, private or link-local address as webhooks do. A
+/// host that doesn't resolve yet is let through; delivery checks again.
+pub async fn validate_url(notifier: &dyn Notifier, url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url)
+        .ok()
+        .filter(|parsed| url.len() <= MAX_URL_LENGTH && notifier.accepts(parsed))
+        .ok_or_else(|| notifier.requirement())?;
+    match outbound::resolve(&parsed, false).await {
+        Err(OutboundError::ForbiddenHost) => {
+            Err("Chat webhook URL must point to a public address".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Activity<'a> {
+    Responded { participant_name: &'a str },
+    Closed,
+    Finalized,
+}
+
+impl Activity<'_> {
+    pub fn webhook_event(&self) -> WebhookEvent {
+        match self {
+            Activity::Responded { .. } => WebhookEvent::ParticipantSubmitted,
+            Activity::Closed => WebhookEvent::EventClosed,
+            Activity::Finalized => WebhookEvent::EventFinalized,
+        }
+    }
+}
+
+/// What a message says about the event, gathered inside the transaction.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub title: String,
+    /// Confirmed participants, the organizer included
+    pub respondents: usize,
+    /// Final slots once finalized, otherwise the best window so far with its
+    /// available count; empty while a blind poll is hidden
+    pub slots: Vec<(TimeRange, Option<usize>)>,
+    pub time_zone: String,
+    pub weekly: bool,
+    pub results_link: Option<String>,
+}
+
+impl Summary {
+    /// The first line of every message, with `emphasize` applied to the
+    /// user-supplied names in the service's own markup.
+    pub fn headline(&self, activity: Activity<'_>, emphasize: impl Fn(&str) -> String) -> String {
+        let title = emphasize(&self.title);
+        match activity {
+            Activity::Responded { participant_name } => format!(
+                "{} responded to {} ({} {} so far)",
+                emphasize(participant_name),
+                title,
+                self.respondents,
+                if self.respondents == 1 {
+                    "response"
+                } else {
+                    "responses"
+                }
+            ),
+            Activity::Closed => format!("{} is closed for responses", title),
+            Activity::Finalized => format!("{} is finalized", title),
+        }
+    }
+
+    /// One line per slot, e.g. "Best slot so far: Tue 2030-01-01 09:00–10:00
+    /// UTC (2 of 3 available)".
+    pub fn slot_lines(&self, activity: Activity<'_>) -> Vec<String> {
+        let label = match activity {
+            Activity::Finalized => "Final time",
+            _ => "Best slot so far",
+        };
+        self.slots
+            .iter()
+            .map(|(range, count)| {
+                let mut line = format!("{}: {}", label, self.format_slot(range));
+                if let Some(count) = count {
+                    line.push_str(&format!(" ({} of {} available)", count, self.respondents));
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// Weekly events have no dates, only weekdays of the reference week.
+    pub fn format_slot(&self, range: &TimeRange) -> String {
+        let formatted = if self.weekly {
+            agreed_time_core::from_reference_ranges(std::slice::from_ref(range), &self.time_zone)
+                .and_then(|slots| slots.into_iter().next())
+                .map(|slot| {
+                    format!(
+                        "{} {}–{} {}",
+                        slot.weekday,
+                        slot.start_time.format("%H:%M"),
+                        slot.end_time.format("%H:%M"),
+                        self.time_zone
+                    )
+                })
+        } else {
+            agreed_time_core::format_range(range, &self.time_zone)
+        };
+        formatted.unwrap_or_else(|| crate::summary::format_span(range.start_at, range.end_at))
+    }
+}
+
+/// Queues a message for each chat service the event has a webhook for; a
+/// no-op when it has none.
+pub async fn enqueue(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    activity: Activity<'_>,
+    public_url: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let event = sqlx::query!(
+        r#"
+        SELECT title, public_token, results_token, results_visibility, anonymous_results,
+               time_zone, slot_duration, slot_kind,
+               slack_webhook_url, discord_webhook_url, chat_webhook_url,
+               (reveal_results_at IS NULL OR reveal_results_at <= $2)
+                   AND NOT (blind_until_closed AND state = 'open') AS "revealed!"
+        FROM events WHERE id = $1
+        "#,
+        event_id,
+        now
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let targets: Vec<(&dyn Notifier, String)> = [
+        (&Slack as &dyn Notifier, event.slack_webhook_url),
+        (&Discord, event.discord_webhook_url),
+        (&GenericJson, event.chat_webhook_url),
+    ]
+    .into_iter()
+    .filter_map(|(notifier, url)| url.map(|url| (notifier, url)))
+    .collect();
+    if targets.is_empty() {
+        return Ok(());
+    }
+
+    let participants = ranges(conn, event_id).await?;
+    let slots = if matches!(activity, Activity::Finalized) {
+        sqlx::query!(
+            "SELECT start_at, end_at FROM final_slots WHERE event_id = $1 ORDER BY start_at",
+            event_id
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|slot| (TimeRange::new(slot.start_at, slot.end_at), None))
+        .collect()
+    } else if event.revealed {
+        agreed_time_core::suggest(&participants, event.slot_duration, 1)
+            .into_iter()
+            .map(|best| (TimeRange::new(best.start_at, best.end_at), Some(best.count)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    // Whoever can read the channel gets the same link the organizer shares
+    let results_link = public_url.map(|base| {
+        let mut link = format!("{}/event/{}/result", base, event.public_token);
+        if ResultsVisibility::from_db(&event.results_visibility) == ResultsVisibility::Private {
+            link.push_str(&format!("?results_token={}", event.results_token));
+        }
+        link
+    });
+    // Anonymous results keep names to the organizer view
+    let activity = match activity {
+        Activity::Responded { .. } if event.anonymous_results => Activity::Responded {
+            participant_name: "Someone",
+        },
+        activity => activity,
+    };
+    let summary = Summary {
+        title: event.title,
+        respondents: participants.len(),
+        slots,
+        time_zone: event.time_zone.unwrap_or_else(|| "UTC".to_string()),
+        weekly: SlotKind::from_db(&event.slot_kind) == SlotKind::Weekly,
+        results_link,
+    };
+
+    for (notifier, url) in targets {
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_deliveries (event_id, event_type, url, payload, next_attempt_at, channel)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            event_id,
+            activity.webhook_event().as_str(),
+            url,
+            notifier.message(activity, &summary).to_string(),
+            now,
+            notifier.channel()
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Confirmed participants' ranges, as `db::quorum` reads them.
+async fn ranges(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<Vec<ParticipantRanges>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT p.id, p.name, p.is_organizer, p.buffer_minutes,
+               a.start_at AS "start_at?", a.end_at AS "end_at?", a.availability_level AS "availability_level?"
+        FROM participants p
+        LEFT JOIN availabilities a ON p.id = a.participant_id
+        WHERE p.event_id = $1 AND p.status = 'confirmed'
+        ORDER BY p.is_organizer DESC, p.created_at ASC, p.id, a.start_at
+        "#,
+        event_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let mut participants: Vec<(i64, ParticipantRanges)> = Vec::new();
+    for row in rows {
+        if participants.last().map(|(id, _)| *id) != Some(row.id) {
+            participants.push((
+                row.id,
+                ParticipantRanges {
+                    name: row.name,
+                    is_organizer: row.is_organizer,
+                    availabilities: Vec::new(),
+                    if_need_be: Vec::new(),
+                    buffer_minutes: row.buffer_minutes,
+                    role: None,
+                },
+            ));
+        }
+        if let (Some((_, participant)), Some(start_at), Some(end_at)) =
+            (participants.last_mut(), row.start_at, row.end_at)
+        {
+            let range = TimeRange::new(start_at, end_at);
+            match row.availability_level.as_deref() {
+                Some("if_need_be") => participant.if_need_be.push(range),
+                Some("unavailable") => {}
+                _ => participant.availabilities.push(range),
+            }
+        }
+    }
+    Ok(participants.into_iter().map(|(_, p)| p).collect())
+}
+
+#[cfg(test)]
+pub(crate) fn test_summary() -> Summary {
+    Summary {
+        title: "Q&A <sync>".to_string(),
+        respondents: 3,
+        slots: vec![(
+            TimeRange::new(
+                "2030-01-01T09:00:00Z".parse().unwrap(),
+                "2030-01-01T10:00:00Z".parse().unwrap(),
+            ),
+            Some(2),
+        )],
+        time_zone: "UTC".to_string(),
+        weekly: false,
+        results_link: Some("https://meet.example.com/event/abc/result".to_string()),
+    }
+}
@@ -0,0 +1,98 @@
+//! Slack incoming-webhook messages.
+
+use serde_json::{Value, json};
+
+use super::{Activity, Notifier, Summary};
+
+/// `webhook_deliveries.channel` of Slack messages.
+pub const CHANNEL: &str = "slack";
+
+/// Incoming webhooks all live on this host; anything else is refused so the
+/// field can't be used to make the server post elsewhere.
+pub const URL_PREFIX: &str = "https://hooks.slack.com/";
+
+pub struct Slack;
+
+impl Notifier for Slack {
+    fn channel(&self) -> &'static str {
+        CHANNEL
+    }
+
+    fn accepts(&self, url: &reqwest::Url) -> bool {
+        url.as_str().starts_with(URL_PREFIX)
+    }
+
+    fn requirement(&self) -> String {
+        format!("Slack webhook URL must start with {}", URL_PREFIX)
+    }
+
+    /// `text` for notifications, and blocks with the same content laid out
+    /// for the channel.
+    fn message(&self, activity: Activity<'_>, summary: &Summary) -> Value {
+        let headline = summary.headline(activity, |name| format!("*{}*", escape(name)));
+        let mut lines = vec![headline.clone()];
+        lines.extend(summary.slot_lines(activity));
+
+        let mut blocks = vec![json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": lines.join("\n") },
+        })];
+        if let Some(link) = &summary.results_link {
+            blocks.push(json!({
+                "type": "context",
+                "elements": [{ "type": "mrkdwn", "text": format!("<{}|View results>", link) }],
+            }));
+        }
+
+        json!({ "text": headline, "blocks": blocks })
+    }
+}
+
+/// Slack reads `&`, `<` and `>` as markup in message text.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::notifications::test_summary;
+
+    #[test]
+    fn test_message_names_best_slot_and_links_results() {
+        let message = Slack.message(
+            Activity::Responded {
+                participant_name: "Bob",
+            },
+            &test_summary(),
+        );
+        assert_eq!(
+            message["text"],
+            "*Bob* responded to *Q&amp;A &lt;sync&gt;* (3 responses so far)"
+        );
+        let section = message["blocks"][0]["text"]["text"].as_str().unwrap();
+        assert!(
+            section
+                .ends_with("Best slot so far: Tue 2030-01-01 09:00–10:00 UTC (2 of 3 available)")
+        );
+        assert_eq!(
+            message["blocks"][1]["elements"][0]["text"],
+            "<https://meet.example.com/event/abc/result|View results>"
+        );
+    }
+
+    #[test]
+    fn test_weekly_slots_have_no_dates() {
+        let summary = Summary {
+            weekly: true,
+            results_link: None,
+            ..test_summary()
+        };
+        let message = Slack.message(Activity::Finalized, &summary);
+        let section = message["blocks"][0]["text"]["text"].as_str().unwrap();
+        assert!(section.ends_with("Final time: Tue 09:00–10:00 UTC (2 of 3 available)"));
+        assert_eq!(message["blocks"].as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,125 @@
+//! Requests the server makes to URLs users typed in.
+//!
+//! CalDAV imports, webhooks and chat messages all go to an address an
+//! organizer or participant supplied. [`resolve`] looks the host up once and
+//! refuses loopback, private and link-local answers; [`pinned_client`] then
+//! connects to exactly the address that was checked and never follows
+//! redirects, so neither a second lookup nor a `Location` header can steer
+//! the request into the deployment's own network.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum OutboundError {
+    #[error("not an http(s) URL with a host")]
+    InvalidUrl,
+    #[error("host resolves to a loopback, private or link-local address")]
+    ForbiddenHost,
+    #[error("the host could not be resolved: {0}")]
+    Unresolvable(String),
+}
+
+/// The host of a URL and the address it was checked at.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub host: String,
+    pub addr: SocketAddr,
+}
+
+/// Resolves the host of `url`, refusing it when any of its addresses is not
+/// public unless `allow_private` is set.
+pub async fn resolve(url: &reqwest::Url, allow_private: bool) -> Result<Target, OutboundError> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(OutboundError::InvalidUrl);
+    }
+    let host = url.host_str().ok_or(OutboundError::InvalidUrl)?;
+    let port = url
+        .port_or_known_default()
+        .ok_or(OutboundError::InvalidUrl)?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| OutboundError::Unresolvable(e.to_string()))?
+        .collect();
+    let addr = *addrs
+        .first()
+        .ok_or_else(|| OutboundError::Unresolvable("host has no address".to_string()))?;
+    if !allow_private && addrs.iter().any(|addr| !is_public(addr.ip())) {
+        return Err(OutboundError::ForbiddenHost);
+    }
+
+    Ok(Target {
+        host: host.to_string(),
+        addr,
+    })
+}
+
+/// A client that only talks to `target`: pinned to the address just checked,
+/// so a second lookup can't swap it, and without redirects.
+pub fn pinned_client(target: &Target, timeout: Duration) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve(&target.host, target.addr)
+        .build()
+}
+
+/// Addresses any client on the internet could reach as well.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_v4(mapped),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    // 0.0.0.0/8 and the carrier-grade NAT range 100.64.0.0/10
+    let reserved = first == 0 || (first == 100 && (64..128).contains(&second));
+    !(reserved
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public() {
+        for ip in ["93.184.216.34", "2606:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "192.168.0.10",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+}
@@ -0,0 +1,581 @@
+//! Notification email templates.
+//!
+//! Every [`Notification`] has a subject, a plain-text body and an HTML body
+//! per locale, rendered into the HTML `layout.html`. English and Japanese are
+//! compiled in from `templates/mail`. `MAIL_TEMPLATE_DIR` points at a
+//! directory with the same layout (`<locale>/<notification>.{subject,txt,html}`
+//! and `layout.html`) to override any of them or add locales; files it lacks
+//! fall back to the built-in locale, then to English.
+//!
+//! Templates only substitute `{{placeholder}}`s, escaping values in HTML.
+//! They are checked when loaded, so a misspelt placeholder stops the server
+//! at startup rather than reaching a mailbox. MJML sources have to be
+//! compiled to HTML (`mjml -s`) before they are deployed.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context;
+
+pub type SharedTemplates = Arc<Templates>;
+
+/// Used for events without a locale and for anything a locale lacks.
+pub const DEFAULT_LOCALE: &str = "en";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Notification {
+    Invitation,
+    AvailabilityReminder,
+    RsvpReminder,
+    ResultsRevealed,
+    QuorumClosed,
+    DeletionNotice,
+    MagicLink,
+    EmailVerification,
+}
+
+impl Notification {
+    pub const ALL: [Notification; 8] = [
+        Notification::Invitation,
+        Notification::AvailabilityReminder,
+        Notification::RsvpReminder,
+        Notification::ResultsRevealed,
+        Notification::QuorumClosed,
+        Notification::DeletionNotice,
+        Notification::MagicLink,
+        Notification::EmailVerification,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Notification::Invitation => "invitation",
+            Notification::AvailabilityReminder => "availability_reminder",
+            Notification::RsvpReminder => "rsvp_reminder",
+            Notification::ResultsRevealed => "results_revealed",
+            Notification::QuorumClosed => "quorum_closed",
+            Notification::DeletionNotice => "deletion_notice",
+            Notification::MagicLink => "magic_link",
+            Notification::EmailVerification => "email_verification",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Notification::ALL.into_iter().find(|n| n.as_str() == name)
+    }
+
+    /// Placeholders its templates may use.
+    fn placeholders(&self) -> &'static [&'static str] {
+        match self {
+            Notification::Invitation => &["title", "slots"],
+            Notification::AvailabilityReminder
+            | Notification::RsvpReminder
+            | Notification::ResultsRevealed => &["title"],
+            Notification::QuorumClosed => &["title", "time", "count", "available"],
+            Notification::DeletionNotice => &["title", "deleted_at", "extend_days"],
+            Notification::MagicLink => &["link", "minutes"],
+            Notification::EmailVerification => &["title", "link", "hours"],
+        }
+    }
+
+    /// Made-up values for previews.
+    pub fn sample(&self) -> Params {
+        let params = Params::new().text("title", "Team offsite");
+        match self {
+            Notification::Invitation => params.list(
+                "slots",
+                vec![
+                    "2030-01-07 09:00–12:00 UTC".to_string(),
+                    "2030-01-08 09:00–12:00 UTC".to_string(),
+                ],
+            ),
+            Notification::AvailabilityReminder
+            | Notification::RsvpReminder
+            | Notification::ResultsRevealed => params,
+            Notification::QuorumClosed => params
+                .text("time", "2030-01-07 09:00–10:00 UTC")
+                .text("count", "3")
+                .text("available", "Alice, Bob, Carol"),
+            Notification::DeletionNotice => params
+                .text("deleted_at", "2030-01-07 09:00 UTC")
+                .text("extend_days", "7"),
+            Notification::MagicLink => params
+                .text("link", "https://meet.example.com/auth/verify?token=abc")
+                .text("minutes", "15"),
+            Notification::EmailVerification => params
+                .text(
+                    "link",
+                    "https://meet.example.com/participants/verify-email?token=abc",
+                )
+                .text("hours", "72"),
+        }
+    }
+}
+
+const LAYOUT_PLACEHOLDERS: &[&str] = &["lang", "subject", "content"];
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Text(String),
+    /// Bullet lines in text, a `<ul>` in HTML and comma-separated in subjects
+    List(Vec<String>),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Params(Vec<(&'static str, Value)>);
+
+impl Params {
+    pub fn new() -> Self {
+        Params::default()
+    }
+
+    pub fn text(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.0.push((name, Value::Text(value.into())));
+        self
+    }
+
+    pub fn list(mut self, name: &'static str, items: Vec<String>) -> Self {
+        self.0.push((name, Value::List(items)));
+        self
+    }
+
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.0.iter().find(|(n, _)| *n == name).map(|(_, v)| v)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendered {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Subject,
+    Text,
+    Html,
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Literal(String),
+    Placeholder(String),
+}
+
+#[derive(Debug, Clone)]
+struct Template(Vec<Part>);
+
+impl Template {
+    /// Fails on an unclosed `{{` or a placeholder not in `allowed`.
+    fn parse(source: &str, allowed: &[&str]) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut rest = source;
+        while let Some(open) = rest.find("{{") {
+            if open > 0 {
+                parts.push(Part::Literal(rest[..open].to_string()));
+            }
+            let Some(close) = rest[open..].find("}}") else {
+                return Err("unclosed {{".to_string());
+            };
+            let name = rest[open + 2..open + close].trim();
+            if !allowed.contains(&name) {
+                return Err(format!(
+                    "unknown placeholder {{{{{}}}}} (expected one of: {})",
+                    name,
+                    allowed.join(", ")
+                ));
+            }
+            parts.push(Part::Placeholder(name.to_string()));
+            rest = &rest[open + close + 2..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_string()));
+        }
+        Ok(Template(parts))
+    }
+
+    fn render(&self, value: impl Fn(&str) -> String) -> String {
+        let mut out = String::new();
+        for part in &self.0 {
+            match part {
+                Part::Literal(literal) => out.push_str(literal),
+                Part::Placeholder(name) => out.push_str(&value(name)),
+            }
+        }
+        out
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn format_value(value: Option<&Value>, format: Format) -> String {
+    match (value, format) {
+        (None, _) => String::new(),
+        (Some(Value::Text(text)), Format::Html) => escape_html(text),
+        (Some(Value::Text(text)), _) => text.clone(),
+        (Some(Value::List(items)), Format::Subject) => items.join(", "),
+        (Some(Value::List(items)), Format::Text) => items
+            .iter()
+            .map(|item| format!("- {}", item))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        (Some(Value::List(items)), Format::Html) => format!(
+            "<ul>{}</ul>",
+            items
+                .iter()
+                .map(|item| format!("<li>{}</li>", escape_html(item)))
+                .collect::<String>()
+        ),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TemplateSet {
+    subject: Template,
+    text: Template,
+    html: Template,
+}
+
+macro_rules! builtin {
+    ($locale:literal, $name:literal) => {
+        [
+            include_str!(concat!(
+                "../../templates/mail/",
+                $locale,
+                "/",
+                $name,
+                ".subject"
+            )),
+            include_str!(concat!(
+                "../../templates/mail/",
+                $locale,
+                "/",
+                $name,
+                ".txt"
+            )),
+            include_str!(concat!(
+                "../../templates/mail/",
+                $locale,
+                "/",
+                $name,
+                ".html"
+            )),
+        ]
+    };
+}
+
+macro_rules! builtin_locale {
+    ($locale:literal) => {
+        (
+            $locale,
+            [
+                builtin!($locale, "invitation"),
+                builtin!($locale, "availability_reminder"),
+                builtin!($locale, "rsvp_reminder"),
+                builtin!($locale, "results_revealed"),
+                builtin!($locale, "quorum_closed"),
+                builtin!($locale, "deletion_notice"),
+                builtin!($locale, "magic_link"),
+                builtin!($locale, "email_verification"),
+            ],
+        )
+    };
+}
+
+/// Sources in the order of [`Notification::ALL`].
+const BUILTIN: [(&str, [[&str; 3]; 8]); 2] = [builtin_locale!("en"), builtin_locale!("ja")];
+
+const BUILTIN_LAYOUT: &str = include_str!("../../templates/mail/layout.html");
+
+const EXTENSIONS: [&str; 3] = ["subject", "txt", "html"];
+
+pub struct Templates {
+    layout: Template,
+    locales: BTreeMap<String, BTreeMap<Notification, TemplateSet>>,
+}
+
+impl Default for Templates {
+    /// The built-in templates only.
+    fn default() -> Self {
+        Templates::load(None).expect("built-in mail templates are valid")
+    }
+}
+
+impl Templates {
+    /// Built-in templates overridden by those in `dir`.
+    pub fn load(dir: Option<&Path>) -> anyhow::Result<Self> {
+        let mut layout = Template::parse(BUILTIN_LAYOUT, LAYOUT_PLACEHOLDERS)
+            .map_err(|e| anyhow::anyhow!("built-in layout.html: {}", e))?;
+        let mut locales = BTreeMap::new();
+        for (locale, sources) in BUILTIN {
+            let mut sets = BTreeMap::new();
+            for (notification, [subject, text, html]) in Notification::ALL.into_iter().zip(sources)
+            {
+                let parse = |source: &str| {
+                    Template::parse(source, notification.placeholders()).map_err(|e| {
+                        anyhow::anyhow!("built-in {}/{}: {}", locale, notification.as_str(), e)
+                    })
+                };
+                sets.insert(
+                    notification,
+                    TemplateSet {
+                        subject: parse(subject.trim_end())?,
+                        text: parse(text)?,
+                        html: parse(html)?,
+                    },
+                );
+            }
+            locales.insert(locale.to_string(), sets);
+        }
+
+        let Some(dir) = dir else {
+            return Ok(Templates { layout, locales });
+        };
+
+        let layout_path = dir.join("layout.html");
+        if layout_path.exists() {
+            layout = read(&layout_path, LAYOUT_PLACEHOLDERS)?;
+        }
+
+        for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let locale = entry.file_name().to_string_lossy().into_owned();
+            let mut sets = locales
+                .get(&locale)
+                .or_else(|| locales.get(DEFAULT_LOCALE))
+                .cloned()
+                .expect("the default locale is built in");
+            for notification in Notification::ALL {
+                let set = sets
+                    .get_mut(&notification)
+                    .expect("every notification is built in");
+                for extension in EXTENSIONS {
+                    let path: PathBuf =
+                        entry
+                            .path()
+                            .join(format!("{}.{}", notification.as_str(), extension));
+                    if !path.exists() {
+                        continue;
+                    }
+                    let template = read(&path, notification.placeholders())?;
+                    match extension {
+                        "subject" => set.subject = template,
+                        "txt" => set.text = template,
+                        _ => set.html = template,
+                    }
+                }
+            }
+            locales.insert(locale, sets);
+        }
+
+        Ok(Templates { layout, locales })
+    }
+
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.locales.keys().map(String::as_str)
+    }
+
+    pub fn supports(&self, locale: &str) -> bool {
+        self.locales.contains_key(locale)
+    }
+
+    /// Renders `notification` in `locale`, or in English when the locale is unknown.
+    pub fn render(&self, notification: Notification, locale: &str, params: &Params) -> Rendered {
+        let (locale, sets) = self
+            .locales
+            .get_key_value(locale)
+            .or_else(|| self.locales.get_key_value(DEFAULT_LOCALE))
+            .expect("the default locale is built in");
+        let set = &sets[&notification];
+
+        let subject = set
+            .subject
+            .render(|name| format_value(params.get(name), Format::Subject));
+        let text = set
+            .text
+            .render(|name| format_value(params.get(name), Format::Text));
+        let content = set
+            .html
+            .render(|name| format_value(params.get(name), Format::Html));
+        let html = self.layout.render(|name| match name {
+            "lang" => escape_html(locale),
+            "subject" => escape_html(&subject),
+            _ => content.trim_end().to_string(),
+        });
+
+        Rendered {
+            subject,
+            text,
+            html,
+        }
+    }
+}
+
+fn read(path: &Path, allowed: &[&str]) -> anyhow::Result<Template> {
+    let source = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let source = if path.extension().is_some_and(|e| e == "subject") {
+        source.trim_end()
+    } else {
+        &source
+    };
+    Template::parse(source, allowed).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_templates_render_every_notification() {
+        let templates = Templates::default();
+        assert_eq!(templates.locales().collect::<Vec<_>>(), vec!["en", "ja"]);
+        for locale in ["en", "ja"] {
+            for notification in Notification::ALL {
+                let rendered = templates.render(notification, locale, &notification.sample());
+                // Login links belong to no poll
+                if notification != Notification::MagicLink {
+                    assert!(
+                        rendered.subject.contains("Team offsite"),
+                        "{:?}",
+                        notification
+                    );
+                    assert!(rendered.text.contains("Team offsite"));
+                }
+                assert!(
+                    rendered
+                        .html
+                        .contains(&format!("<html lang=\"{}\">", locale))
+                );
+                assert!(!rendered.text.contains("{{") && !rendered.html.contains("{{"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_invitation_snapshot() {
+        let rendered = Templates::default().render(
+            Notification::Invitation,
+            "en",
+            &Notification::Invitation.sample(),
+        );
+        assert_eq!(rendered.subject, "Invitation: Team offsite");
+        assert_eq!(
+            rendered.text,
+            "Team offsite has been scheduled for:\n\
+             - 2030-01-07 09:00–12:00 UTC\n\
+             - 2030-01-08 09:00–12:00 UTC\n\
+             \n\
+             Open the attached invitation to add it to your calendar.\n"
+        );
+        assert!(
+            rendered
+                .html
+                .contains("<title>Invitation: Team offsite</title>")
+        );
+        assert!(rendered.html.contains(
+            "<p><strong>Team offsite</strong> has been scheduled for:</p>\n\
+             <ul><li>2030-01-07 09:00–12:00 UTC</li><li>2030-01-08 09:00–12:00 UTC</li></ul>\n\
+             <p>Open the attached invitation to add it to your calendar.</p>\n</div>"
+        ));
+    }
+
+    #[test]
+    fn test_deletion_notice_snapshot_ja() {
+        let rendered = Templates::default().render(
+            Notification::DeletionNotice,
+            "ja",
+            &Notification::DeletionNotice.sample(),
+        );
+        assert_eq!(rendered.subject, "Team offsiteはまもなく削除されます");
+        assert_eq!(
+            rendered.text,
+            "投票「Team offsite」と全員の回答は2030-01-07 09:00 UTCに削除されます。\
+             残す場合は、主催者用リンクを開いてカレンダーファイルを書き出すか、\
+             投票を7日延長してください。\n"
+        );
+    }
+
+    #[test]
+    fn test_values_are_escaped_in_html_only() {
+        let params = Params::new().text("title", "<b>Tom & Jerry</b>");
+        let rendered = Templates::default().render(Notification::ResultsRevealed, "xx", &params);
+        assert_eq!(
+            rendered.subject,
+            "Results are now visible for <b>Tom & Jerry</b>"
+        );
+        assert!(
+            rendered
+                .html
+                .contains("<strong>&lt;b&gt;Tom &amp; Jerry&lt;/b&gt;</strong>")
+        );
+        assert!(rendered.html.contains("<html lang=\"en\">"));
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_and_unclosed_placeholders() {
+        assert!(Template::parse("Hi {{ title }}", &["title"]).is_ok());
+        assert!(
+            Template::parse("Hi {{titel}}", &["title"])
+                .unwrap_err()
+                .contains("{{titel}}")
+        );
+        assert!(Template::parse("Hi {{title", &["title"]).is_err());
+    }
+
+    #[test]
+    fn test_directory_overrides_and_adds_locales() {
+        let dir = std::env::temp_dir().join(format!("mail-templates-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("en")).unwrap();
+        fs::create_dir_all(dir.join("fr")).unwrap();
+        fs::write(
+            dir.join("layout.html"),
+            "<main lang=\"{{lang}}\">{{content}}</main>",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("en/results_revealed.subject"),
+            "Peek at {{title}}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("fr/results_revealed.subject"),
+            "Résultats de {{title}}",
+        )
+        .unwrap();
+
+        let templates = Templates::load(Some(&dir)).unwrap();
+        let params = Params::new().text("title", "Picnic");
+        let en = templates.render(Notification::ResultsRevealed, "en", &params);
+        assert_eq!(en.subject, "Peek at Picnic");
+        assert!(en.html.starts_with("<main lang=\"en\"><p>"));
+        let fr = templates.render(Notification::ResultsRevealed, "fr", &params);
+        assert_eq!(fr.subject, "Résultats de Picnic");
+        // Files the new locale lacks come from English
+        assert!(fr.text.starts_with("Everyone's availability for Picnic"));
+        assert!(templates.supports("fr") && templates.supports("ja"));
+
+        fs::write(dir.join("fr/invitation.txt"), "{{title}} {{organizer}}").unwrap();
+        let error = Templates::load(Some(&dir)).err().unwrap().to_string();
+        assert!(
+            error.contains("invitation.txt") && error.contains("{{organizer}}"),
+            "{}",
+            error
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,341 @@
+//! Signed webhook callbacks on event activity.
+//!
+//! Works like the mail outbox: handlers call [`enqueue`] inside the
+//! transaction that caused the callback, and a background task posts pending
+//! deliveries with [`deliver_pending`]. Failures are retried with exponential
+//! backoff up to [`MAX_ATTEMPTS`] times; `webhook_deliveries` doubles as the
+//! delivery log.
+//!
+//! Every POST carries [`SIGNATURE_HEADER`]: `sha256=` followed by the hex
+//! HMAC-SHA256 of the raw body keyed with the event's webhook secret. Chat
+//! messages queued by `integrations::notifications` share the table under their own
+//! `channel` and go out unsigned.
+
+use std::{future::Future, time::Duration};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    clock::Clock,
+    encryption::Keyring,
+    integrations::{mail::DeliveryReport, outbound},
+};
+
+/// Deliveries are abandoned after this many failed attempts.
+pub const MAX_ATTEMPTS: i32 = 6;
+
+/// First retry delay; doubled after every further failure.
+pub const BASE_BACKOFF_SECONDS: i64 = 60;
+
+pub const SIGNATURE_HEADER: &str = "X-AgreedTime-Signature";
+pub const EVENT_HEADER: &str = "X-AgreedTime-Event";
+pub const DELIVERY_HEADER: &str = "X-AgreedTime-Delivery";
+
+/// `webhook_deliveries.channel` of signed organizer callbacks.
+pub const CHANNEL: &str = "webhook";
+
+/// Rows posted per [`deliver_pending`] call.
+const BATCH_SIZE: i64 = 50;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long claimed rows stay out of other instances' batches; longer than a
+/// whole batch of timed-out posts.
+const CLAIM_SECONDS: i64 = 15 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    ParticipantSubmitted,
+    ParticipantRemoved,
+    EventClosed,
+    EventFinalized,
+    EventExpiringSoon,
+    EventPurged,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::ParticipantSubmitted => "participant.submitted",
+            WebhookEvent::ParticipantRemoved => "participant.removed",
+            WebhookEvent::EventClosed => "event.closed",
+            WebhookEvent::EventFinalized => "event.finalized",
+            WebhookEvent::EventExpiringSoon => "event.expiring_soon",
+            WebhookEvent::EventPurged => "event.purged",
+        }
+    }
+}
+
+/// Value of [`SIGNATURE_HEADER`] for `body`.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Queues a callback when the event has a webhook; a no-op otherwise.
+pub async fn enqueue(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    kind: WebhookEvent,
+    data: Value,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let event = sqlx::query!(
+        "SELECT public_token, webhook_url FROM events WHERE id = $1",
+        event_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let Some(url) = event.webhook_url else {
+        return Ok(());
+    };
+
+    let payload = json!({
+        "type": kind.as_str(),
+        "public_token": event.public_token,
+        "occurred_at": now,
+        "data": data,
+    });
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_deliveries (event_id, event_type, url, payload, next_attempt_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        event_id,
+        kind.as_str(),
+        url,
+        payload.to_string(),
+        now
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Queues `event.purged` for an event about to be hard-deleted. The row is
+/// not tied to the event, so it survives the purge that cascades the event's
+/// other deliveries, and keeps the secret it is signed with.
+pub async fn enqueue_purged(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    data: Value,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let event = sqlx::query!(
+        "SELECT public_token, webhook_url, webhook_secret FROM events WHERE id = $1",
+        event_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let Some(url) = event.webhook_url else {
+        return Ok(());
+    };
+
+    let kind = WebhookEvent::EventPurged;
+    let payload = json!({
+        "type": kind.as_str(),
+        "public_token": event.public_token,
+        "occurred_at": now,
+        "data": data,
+    });
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_deliveries (event_id, event_type, url, payload, next_attempt_at, webhook_secret)
+        VALUES (NULL, $1, $2, $3, $4, $5)
+        "#,
+        kind.as_str(),
+        url,
+        payload.to_string(),
+        now,
+        event.webhook_secret
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct OutgoingWebhook {
+    pub id: i64,
+    pub url: String,
+    pub event_type: String,
+    pub payload: String,
+    /// [`SIGNATURE_HEADER`] value; `None` for chat messages
+    pub signature: Option<String>,
+}
+
+pub trait WebhookSender: Send + Sync {
+    fn post(&self, webhook: &OutgoingWebhook) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+/// Posts over HTTP through [`outbound`]: every delivery resolves the host
+/// again and is refused when it now points at a private address.
+pub struct HttpSender;
+
+impl WebhookSender for HttpSender {
+    async fn post(&self, webhook: &OutgoingWebhook) -> Result<(), String> {
+        let url = reqwest::Url::parse(&webhook.url).map_err(|e| e.to_string())?;
+        let target = outbound::resolve(&url, false)
+            .await
+            .map_err(|e| e.to_string())?;
+        // A redirect would re-post the signed body somewhere unvetted
+        let client =
+            outbound::pinned_client(&target, REQUEST_TIMEOUT).map_err(|e| e.to_string())?;
+        let mut request = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &webhook.event_type)
+            .header(DELIVERY_HEADER, webhook.id.to_string());
+        if let Some(signature) = &webhook.signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+        let response = request
+            .body(webhook.payload.clone())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", response.status()))
+        }
+    }
+}
+
+/// Delay before the next attempt once `attempts` have failed.
+pub fn backoff(attempts: i32) -> chrono::Duration {
+    chrono::Duration::seconds(BASE_BACKOFF_SECONDS << (attempts - 1).clamp(0, 16))
+}
+
+/// Posts one batch of due deliveries. Rows are claimed with `SKIP LOCKED`
+/// and pushed [`CLAIM_SECONDS`] into the future in a statement of their own,
+/// so several instances can drain the queue concurrently without holding
+/// locks while they post; outcomes are recorded afterwards in one short
+/// transaction. A claim left behind by a crashed instance simply comes due
+/// again. Callbacks for trashed events are held back and go out if the event
+/// is restored; those that outlived their event (`event.purged`) go out
+/// regardless.
+pub async fn deliver_pending<S: WebhookSender>(
+    pool: &PgPool,
+    sender: &S,
+    keys: &Keyring,
+    clock: &dyn Clock,
+) -> Result<DeliveryReport, sqlx::Error> {
+    let now = clock.now();
+
+    let mut claimed = sqlx::query!(
+        r#"
+        WITH due AS (
+            SELECT d.id, COALESCE(d.webhook_secret, e.webhook_secret) AS webhook_secret
+            FROM webhook_deliveries d
+            LEFT JOIN events e ON e.id = d.event_id
+            WHERE d.delivered_at IS NULL AND d.attempts < $1 AND d.next_attempt_at <= $2
+              AND (d.event_id IS NULL OR e.deleted_at IS NULL)
+            ORDER BY d.id
+            LIMIT $3
+            FOR UPDATE OF d SKIP LOCKED
+        )
+        UPDATE webhook_deliveries d
+        SET next_attempt_at = $4
+        FROM due
+        WHERE d.id = due.id
+        RETURNING d.id, d.url, d.event_type, d.payload, d.attempts, d.channel, due.webhook_secret
+        "#,
+        MAX_ATTEMPTS,
+        now,
+        BATCH_SIZE,
+        now + chrono::Duration::seconds(CLAIM_SECONDS)
+    )
+    .fetch_all(pool)
+    .await?;
+    claimed.sort_by_key(|row| row.id);
+
+    let mut outcomes = Vec::with_capacity(claimed.len());
+    for row in claimed {
+        let outcome = match keys.open_opt(row.webhook_secret) {
+            Ok(secret) => {
+                let webhook = OutgoingWebhook {
+                    id: row.id,
+                    signature: (row.channel == CHANNEL)
+                        .then(|| sign(secret.as_deref().unwrap_or_default(), &row.payload)),
+                    url: row.url.clone(),
+                    event_type: row.event_type,
+                    payload: row.payload,
+                };
+                sender.post(&webhook).await
+            }
+            // Sealed with a key that is no longer configured
+            Err(error) => Err(error.to_string()),
+        };
+        if let Err(error) = &outcome {
+            tracing::warn!("Webhook {} to {} failed: {}", row.id, row.url, error);
+        }
+        outcomes.push((row.id, row.attempts, outcome));
+    }
+
+    let mut report = DeliveryReport::default();
+    let mut transaction = pool.begin().await?;
+    for (id, attempts, outcome) in outcomes {
+        match outcome {
+            Ok(()) => {
+                sqlx::query!(
+                    "UPDATE webhook_deliveries SET delivered_at = $1, attempts = attempts + 1, last_error = NULL WHERE id = $2",
+                    now,
+                    id
+                )
+                .execute(&mut *transaction)
+                .await?;
+                report.sent += 1;
+            }
+            Err(error) => {
+                sqlx::query!(
+                    r#"
+                    UPDATE webhook_deliveries
+                    SET attempts = attempts + 1, last_error = $1, next_attempt_at = $2
+                    WHERE id = $3
+                    "#,
+                    error,
+                    now + backoff(attempts + 1),
+                    id
+                )
+                .execute(&mut *transaction)
+                .await?;
+                report.failed += 1;
+            }
+        }
+    }
+    transaction.commit().await?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_matches_known_hmac() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        assert_eq!(backoff(1), chrono::Duration::minutes(1));
+        assert_eq!(backoff(2), chrono::Duration::minutes(2));
+        assert_eq!(backoff(4), chrono::Duration::minutes(8));
+    }
+}
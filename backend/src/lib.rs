@@ -1,8 +1,30 @@
 // Library exports for testing
+pub mod auth;
+pub mod cache;
+pub mod captcha;
+pub mod clock;
 pub mod config;
 pub mod db;
+pub mod encryption;
 pub mod error;
+pub mod etag;
 pub mod handlers;
+pub mod ics;
+pub mod init;
+pub mod integrations;
+pub mod listeners;
+pub mod metrics;
 pub mod middleware;
 pub mod models;
+pub mod openapi;
+pub mod passwords;
+pub mod realtime;
 pub mod routes;
+pub mod scheduler;
+pub mod security;
+pub mod simulate;
+pub mod state;
+pub mod summary;
+pub mod tickets;
+pub mod tokens;
+pub mod validation;
@@ -0,0 +1,55 @@
+//! Binding the sockets `Config::listeners` describes.
+
+use std::{io, net::SocketAddr};
+
+use socket2::{Domain, Socket, Type};
+use tokio::net::TcpListener;
+
+const BACKLOG: i32 = 1024;
+
+/// Binds `addr` (`host:port`, resolved if needed). IPv6 sockets are
+/// v6-only, so `[::]:3000` and `0.0.0.0:3000` can be listed side by side
+/// instead of the former silently claiming IPv4 as well.
+pub async fn bind(addr: &str) -> io::Result<TcpListener> {
+    let addr = tokio::net::lookup_host(addr).await?.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::AddrNotAvailable,
+            format!("{} did not resolve", addr),
+        )
+    })?;
+    bind_addr(addr)
+}
+
+fn bind_addr(addr: SocketAddr) -> io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    // Restarts shouldn't wait out TIME_WAIT connections of the old process
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(BACKLOG)?;
+    TcpListener::from_std(socket.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ipv4_and_ipv6_share_a_port() {
+        let v4 = bind("127.0.0.1:0").await.unwrap();
+        let port = v4.local_addr().unwrap().port();
+        let v6 = match bind(&format!("[::1]:{}", port)).await {
+            Ok(listener) => listener,
+            Err(e) if e.kind() == io::ErrorKind::AddrNotAvailable => {
+                eprintln!("Skipping: no IPv6 loopback ({})", e);
+                return;
+            }
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(v6.local_addr().unwrap().port(), port);
+        assert!(bind(&format!("127.0.0.1:{}", port)).await.is_err());
+    }
+}
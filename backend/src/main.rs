@@ -1,8 +1,18 @@
-use agreed_time_backend::config::Config;
-use agreed_time_backend::middleware::{RateLimitLayer, SecurityHeadersLayer};
-use std::{net::SocketAddr, time::Duration};
+use agreed_time_backend::config::{Config, Listener, LogFormat, Plane};
+use agreed_time_backend::middleware::{
+    ClientAddrLayer, RateLimitLayer, RedisRateLimitLayer, RequestLogLayer, SecurityHeadersLayer,
+    SubmissionRateLimitLayer,
+};
+use agreed_time_backend::scheduler::{self, next_tick};
+use agreed_time_backend::security::AbuseLayer;
+use agreed_time_backend::state::AppState;
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+use tokio::{sync::watch, task::JoinSet};
 
-use axum::http::{HeaderValue, Method};
+use axum::{
+    Router,
+    http::{HeaderValue, Method},
+};
 use clap::{Parser, Subcommand};
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -16,70 +26,427 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Set up a new deployment: write an env file with a fresh admin API key,
+    /// create the database if missing and run migrations. Prompts for
+    /// settings not given as flags when run in a terminal
+    Init {
+        /// Env file to write
+        #[arg(long, default_value = ".env")]
+        env_file: PathBuf,
+        #[arg(long)]
+        database_url: Option<String>,
+        #[arg(long)]
+        port: Option<u16>,
+        /// Where the frontend is served, allowed as CORS origin
+        #[arg(long)]
+        public_url: Option<String>,
+        /// Sender address for invitations and reminders
+        #[arg(long)]
+        mail_from: Option<String>,
+        #[arg(long)]
+        smtp_url: Option<String>,
+        /// Use defaults instead of prompting
+        #[arg(long)]
+        yes: bool,
+        /// Replace an existing env file
+        #[arg(long)]
+        force: bool,
+    },
     /// Run database migrations
     Migrate,
     /// Run the API server
     Serve,
+    /// Replay a scripted scenario and report latencies and invariant violations
+    Simulate {
+        /// Path to the scenario JSON file
+        #[arg(long)]
+        scenario: PathBuf,
+        /// Target a running server instead of an in-process router
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+    /// Copy a sanitized sample of events (names scrambled, comments, emails
+    /// and tokens replaced) from DATABASE_URL into another, migrated database
+    CloneEvents {
+        /// Database to copy into, e.g. staging
+        #[arg(long)]
+        target_database_url: String,
+        /// Most recent events to copy
+        #[arg(long, default_value_t = 100)]
+        limit: i64,
+        /// Only events created at or after this RFC 3339 time
+        #[arg(long)]
+        since: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    /// Trash expired events and purge the trash now, listing what goes
+    Cleanup {
+        /// Only list what would be trashed and purged
+        #[arg(long)]
+        dry_run: bool,
+        /// Also trash events created more than N days ago, whatever their expiry
+        #[arg(long)]
+        older_than_days: Option<i64>,
+    },
+    /// Print counts of events by state, participants, availability and
+    /// upcoming expirations
+    Stats,
+    /// Dump one event (trashed ones too) with its participants, availability
+    /// and audit log, without tokens or secrets
+    Export {
+        /// Public or organizer token
+        #[arg(long)]
+        event: String,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+    },
+    /// Re-encrypt webhook secrets, organizer emails and comments with a new
+    /// data key. Run it once the servers read with DATA_ENCRYPTION_KEY set to
+    /// the new key and DATA_ENCRYPTION_KEY_PREVIOUS to the old one
+    RotateKeys {
+        /// Key the values are sealed with now; omit when they are plaintext
+        #[arg(long)]
+        old_key: Option<String>,
+        /// Key to seal them with
+        #[arg(long)]
+        new_key: String,
+        /// Rows per query
+        #[arg(long, default_value_t = 500)]
+        batch_size: i64,
+    },
+    /// Background jobs of `serve`
+    Jobs {
+        #[command(subcommand)]
+        command: JobsCommand,
+    },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ExportFormat {
+    /// Everything, pretty-printed
+    Json,
+    /// One row per availability range
+    Csv,
+}
+
+#[derive(Subcommand)]
+enum JobsCommand {
+    /// List the jobs this configuration registers
+    List,
+    /// Run one job now, recorded in job_runs like a scheduled run
+    Run {
+        /// Job name, e.g. cleanup.trash_expired
+        name: String,
+    },
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // Parse CLI arguments
+    let cli = Cli::parse();
+
+    // Load configuration first, since it picks the log format
+    let config = Config::from_env()?;
+
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "agreed_time_backend=debug,tower_http=debug".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with((config.log_format == LogFormat::Text).then(tracing_subscriber::fmt::layer))
+        .with(
+            (config.log_format == LogFormat::Json)
+                .then(|| tracing_subscriber::fmt::layer().json().flatten_event(true)),
+        )
         .init();
 
-    // Parse CLI arguments
-    let cli = Cli::parse();
-
-    // Load configuration
-    let config = Config::from_env()?;
     tracing::info!("Configuration loaded: {:?}", config);
 
     // Create database pool (lazy - won't connect until first query)
-    let pool = agreed_time_backend::db::create_pool_lazy(&config.database_url);
+    let pool = agreed_time_backend::db::create_pool_lazy(&config.database_url, &config.pool);
     tracing::info!("Database connection pool created (lazy)");
 
     match cli.command.unwrap_or(Commands::Serve) {
+        Commands::Init {
+            env_file,
+            database_url,
+            port,
+            public_url,
+            mail_from,
+            smtp_url,
+            yes,
+            force,
+        } => {
+            let report = agreed_time_backend::init::run(agreed_time_backend::init::InitOptions {
+                env_file,
+                database_url,
+                port,
+                public_url,
+                mail_from,
+                smtp_url,
+                assume_defaults: yes,
+                force,
+            })
+            .await?;
+            println!("{}", report.render());
+        }
         Commands::Migrate => {
             tracing::info!("Running database migrations...");
-            sqlx::migrate!("./migrations")
-                .run(&pool)
-                .await
-                .expect("Failed to run database migrations");
-            tracing::info!("Database migrations applied successfully!");
+            let applied = agreed_time_backend::db::migrate(&pool).await?;
+            tracing::info!("Applied {} database migrations", applied.len());
+        }
+        Commands::Simulate { scenario, base_url } => {
+            use agreed_time_backend::simulate::{self, Scenario, Target};
+
+            let scenario: Scenario = serde_json::from_str(&std::fs::read_to_string(&scenario)?)?;
+            let target = match base_url {
+                Some(url) => Target::http(&url),
+                None => Target::InProcess(agreed_time_backend::routes::create_router(pool)),
+            };
+
+            tracing::info!(
+                "Running scenario {}",
+                scenario.name.as_deref().unwrap_or("(unnamed)")
+            );
+            let report = simulate::run(&scenario, &target).await?;
+            println!("{}", report.render());
+
+            if !report.is_ok() {
+                anyhow::bail!("{} invariant violation(s)", report.violations.len());
+            }
+        }
+        Commands::CloneEvents {
+            target_database_url,
+            limit,
+            since,
+        } => {
+            use agreed_time_backend::db::clone::{CloneOptions, clone_events};
+
+            if target_database_url == config.database_url {
+                anyhow::bail!("The target database must differ from DATABASE_URL");
+            }
+            let target =
+                agreed_time_backend::db::create_pool_lazy(&target_database_url, &config.pool);
+            let report = clone_events(
+                &pool,
+                &target,
+                agreed_time_backend::clock::system().as_ref(),
+                &CloneOptions { limit, since },
+            )
+            .await?;
+            tracing::info!(
+                "Cloned {} events with {} participants",
+                report.events,
+                report.participants
+            );
+        }
+        Commands::Cleanup {
+            dry_run,
+            older_than_days,
+        } => {
+            use agreed_time_backend::db::cleanup::{CleanupOptions, run_cleanup};
+
+            if older_than_days.is_some_and(|days| days < 1) {
+                anyhow::bail!("--older-than-days must be at least 1");
+            }
+            let report = run_cleanup(
+                &pool,
+                agreed_time_backend::clock::system().as_ref(),
+                &CleanupOptions {
+                    dry_run,
+                    older_than: older_than_days.map(chrono::Duration::days),
+                },
+            )
+            .await?;
+            print!("{}", report.render());
+        }
+        Commands::Stats => {
+            let stats = agreed_time_backend::handlers::admin::collect_stats(
+                &pool,
+                agreed_time_backend::clock::system().as_ref(),
+            )
+            .await?;
+            print!("{}", stats.render());
+        }
+        Commands::Export { event, format } => {
+            let Some(export) = agreed_time_backend::db::export::export_event(
+                &pool,
+                &agreed_time_backend::encryption::Keyring::from_config(&config),
+                &event,
+            )
+            .await?
+            else {
+                anyhow::bail!("No event with token {}", event);
+            };
+            match format {
+                ExportFormat::Json => println!("{}", serde_json::to_string_pretty(&export)?),
+                ExportFormat::Csv => print!("{}", export.to_csv()),
+            }
+        }
+        Commands::RotateKeys {
+            old_key,
+            new_key,
+            batch_size,
+        } => {
+            use agreed_time_backend::encryption::{DataKey, Keyring};
+
+            if batch_size < 1 {
+                anyhow::bail!("--batch-size must be at least 1");
+            }
+            let keys = Keyring::new(
+                Some(new_key.parse::<DataKey>()?),
+                old_key.as_deref().map(str::parse::<DataKey>).transpose()?,
+            );
+            let report =
+                agreed_time_backend::db::keys::rotate_keys(&pool, &keys, batch_size).await?;
+            print!("{}", report.render());
+        }
+        Commands::Jobs { command } => {
+            let templates = Arc::new(
+                agreed_time_backend::integrations::templates::Templates::load(
+                    config.mail_template_dir.as_deref(),
+                )?,
+            );
+            let scheduler = scheduler::app_jobs(
+                pool,
+                agreed_time_backend::clock::system(),
+                &config,
+                templates,
+            )?;
+            match command {
+                JobsCommand::List => {
+                    for name in scheduler.names() {
+                        println!("{}", name);
+                    }
+                }
+                JobsCommand::Run { name } => match scheduler.run(&name).await {
+                    Some(Ok(rows)) => println!("{}: {} rows affected", name, rows),
+                    Some(Err(e)) => anyhow::bail!("{} failed: {}", name, e),
+                    None => anyhow::bail!(
+                        "Unknown job {}; known jobs: {}",
+                        name,
+                        scheduler.names().join(", ")
+                    ),
+                },
+            }
         }
         Commands::Serve => {
-            // Start background task for auto-deletion
-            let pool_for_cleanup = pool.clone();
-            tokio::spawn(async move {
-                // Run every hour
-                let mut interval = tokio::time::interval(Duration::from_secs(3600));
-                loop {
-                    interval.tick().await;
-                    tracing::info!("Running auto-deletion task...");
-
-                    match agreed_time_backend::db::cleanup::delete_expired_events(&pool_for_cleanup)
-                        .await
+            // Before anything queries the schema; replicas take turns on the lock
+            if config.auto_migrate {
+                let applied = agreed_time_backend::db::migrate(&pool).await?;
+                tracing::info!("Applied {} pending database migrations", applied.len());
+            } else {
+                match agreed_time_backend::db::pending_migrations(&pool).await {
+                    Ok(pending) if pending.is_empty() => {}
+                    Ok(pending) => tracing::warn!(
+                        "{} database migrations are pending ({:?}); run `migrate` or set AUTO_MIGRATE=true",
+                        pending.len(),
+                        pending
+                    ),
+                    Err(e) => tracing::warn!(
+                        "Could not check database migrations ({}); run `migrate` or set AUTO_MIGRATE=true",
+                        e
+                    ),
+                }
+            }
+
+            // Flipped to true on SIGTERM/SIGINT; background tasks finish their
+            // current run and stop, servers stop accepting and drain
+            let (shutdown_tx, shutdown_rx) = watch::channel(false);
+            let shutdown_timeout = Duration::from_secs(config.shutdown_timeout_secs);
+            let mut tasks = JoinSet::new();
+
+            // A broken override fails the start rather than the first mail
+            let templates = Arc::new(
+                agreed_time_backend::integrations::templates::Templates::load(
+                    config.mail_template_dir.as_deref(),
+                )?,
+            );
+
+            // Expiry warnings, retention, pruning, mail and webhook delivery
+            let clock = agreed_time_backend::clock::system();
+            scheduler::app_jobs(pool.clone(), clock.clone(), &config, templates.clone())?
+                .spawn(&mut tasks, &shutdown_rx);
+
+            // Refresh business gauges served at /admin/metrics
+            let metrics = agreed_time_backend::metrics::SharedMetrics::default();
+            let pool_for_metrics = pool.clone();
+            let clock_for_metrics = clock.clone();
+            let metrics_for_collector = metrics.clone();
+            let mut shutdown = shutdown_rx.clone();
+            tasks.spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(60));
+                while next_tick(&mut interval, &mut shutdown).await {
+                    match agreed_time_backend::metrics::collect(
+                        &pool_for_metrics,
+                        clock_for_metrics.as_ref(),
+                    )
+                    .await
                     {
-                        Ok(count) => {
-                            if count > 0 {
-                                tracing::info!("Deleted {} expired events", count);
-                            }
+                        Ok(snapshot) => {
+                            *metrics_for_collector.write().unwrap() = Some(snapshot);
                         }
                         Err(e) => {
-                            tracing::error!("Error in auto-deletion task: {:?}", e);
+                            tracing::error!("Error in metrics collector task: {:?}", e);
                         }
                     }
                 }
             });
 
-            // Setup Rate Limiter
-            let rate_limit_layer = RateLimitLayer::new();
+            // Setup Rate Limiter; the per-event submission limiter sits outside
+            // it so rejected submissions don't use up the global allowance
+            let counters = agreed_time_backend::metrics::SharedCounters::default();
+            let rate_limit_layer =
+                RateLimitLayer::from_config(&config, clock.clone()).with_counters(counters.clone());
+            let redis_rate_limit_layer = RedisRateLimitLayer::from_config(&config, clock.clone())
+                .await?
+                .map(|layer| layer.with_counters(counters.clone()));
+            if redis_rate_limit_layer.is_some() {
+                tracing::info!("Rate limits are shared through Redis");
+            }
+
+            // Forget clients whose windows have ended, so the limiters only
+            // hold recently active ones between capacity evictions
+            let limiters = (rate_limit_layer.clone(), redis_rate_limit_layer.clone());
+            let mut shutdown = shutdown_rx.clone();
+            tasks.spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(60));
+                while next_tick(&mut interval, &mut shutdown).await {
+                    let evicted = match &limiters {
+                        (_, Some(redis)) => redis.evict_expired(),
+                        (memory, None) => memory.evict_expired(),
+                    };
+                    if evicted > 0 {
+                        tracing::debug!("Rate limiter forgot {} idle clients", evicted);
+                    }
+                }
+            });
+            let client_addr_layer = ClientAddrLayer::new(&config);
+            let submission_limit_layer =
+                SubmissionRateLimitLayer::new(&config, clock.clone(), counters.clone());
+
+            // Bans reach every replica through the database; reloading them
+            // each minute also picks up expiries and bans issued elsewhere
+            let bans = agreed_time_backend::security::SharedBans::default();
+            let abuse_layer = AbuseLayer::new(&config, pool.clone(), clock.clone(), bans.clone());
+            let pool_for_bans = pool.clone();
+            let clock_for_bans = clock.clone();
+            let bans_for_refresh = bans.clone();
+            let mut shutdown = shutdown_rx.clone();
+            tasks.spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(60));
+                while next_tick(&mut interval, &mut shutdown).await {
+                    if let Err(e) = agreed_time_backend::security::refresh_bans(
+                        &pool_for_bans,
+                        clock_for_bans.as_ref(),
+                        &bans_for_refresh,
+                    )
+                    .await
+                    {
+                        tracing::error!("Error in ban refresh task: {:?}", e);
+                    }
+                }
+            });
 
             // Setup CORS
             let cors = CorsLayer::new()
@@ -90,32 +457,202 @@ async fn main() -> anyhow::Result<()> {
                         .map(|origin| origin.parse::<HeaderValue>().unwrap())
                         .collect::<Vec<HeaderValue>>(),
                 )
-                .allow_methods([Method::GET, Method::POST, Method::PUT])
+                .allow_methods([
+                    Method::GET,
+                    Method::POST,
+                    Method::PUT,
+                    Method::PATCH,
+                    Method::DELETE,
+                ])
                 .allow_headers([
                     axum::http::header::ACCEPT,
                     axum::http::header::AUTHORIZATION,
                     axum::http::header::CONTENT_TYPE,
+                    axum::http::header::IF_NONE_MATCH,
+                    axum::http::HeaderName::from_static(agreed_time_backend::passwords::HEADER),
+                    axum::http::HeaderName::from_static(agreed_time_backend::tickets::HEADER),
+                    axum::http::HeaderName::from_static(
+                        agreed_time_backend::handlers::events::DISPLAY_TIMEZONE_HEADER,
+                    ),
+                ])
+                .expose_headers([
+                    axum::http::header::ETAG,
+                    axum::http::HeaderName::from_static(
+                        agreed_time_backend::middleware::REQUEST_ID_HEADER,
+                    ),
+                    axum::http::HeaderName::from_static(
+                        agreed_time_backend::middleware::DEMO_HEADER,
+                    ),
                 ])
                 .allow_credentials(true);
 
-            // Create router
-            let app = agreed_time_backend::routes::create_router(pool)
-                .layer(rate_limit_layer)
-                .layer(SecurityHeadersLayer)
-                .layer(cors);
+            // One router per listener. The limiter layers are cloned, so a
+            // client's counts are shared across listeners
+            let state = AppState::new(pool.clone())
+                .with_clock(clock)
+                .with_config(config.clone())
+                .with_metrics(metrics)
+                .with_counters(counters)
+                .with_templates(templates)
+                .with_bans(bans);
+
+            // Upgraded sockets are not part of the drain; end them directly
+            let hub = state.hub.clone();
+            let mut shutdown = shutdown_rx.clone();
+            tasks.spawn(async move {
+                let _ = shutdown.wait_for(|stopping| *stopping).await;
+                hub.close_all();
+            });
+            let apps = config
+                .listeners
+                .iter()
+                .map(|listener| {
+                    let app = agreed_time_backend::routes::create_router_for(
+                        listener.plane,
+                        state.clone(),
+                    );
+                    let app = match listener.plane {
+                        // Operators only: no browser origins, no client limits
+                        Plane::Internal => app.layer(SecurityHeadersLayer),
+                        Plane::All | Plane::Public => {
+                            let app = match &redis_rate_limit_layer {
+                                Some(layer) => app.layer(layer.clone()),
+                                None => app.layer(rate_limit_layer.clone()),
+                            };
+                            app.layer(submission_limit_layer.clone())
+                                .layer(abuse_layer.clone())
+                                .layer(SecurityHeadersLayer)
+                                .layer(cors.clone())
+                        }
+                    };
+                    // Outermost, so rejections by the layers above are logged
+                    // too, under the client address resolved around them
+                    (
+                        listener.clone(),
+                        app.layer(RequestLogLayer).layer(client_addr_layer.clone()),
+                    )
+                })
+                .collect();
+
+            let served = serve_listeners(apps, &shutdown_tx, shutdown_timeout).await;
+            // Also stops the tasks when a listener failed to bind
+            shutdown_tx.send_replace(true);
 
-            // Start server
-            let addr = config.addr();
-            tracing::info!("Starting server on {}", addr);
+            let stopped = tokio::time::timeout(shutdown_timeout, async {
+                while tasks.join_next().await.is_some() {}
+            })
+            .await;
+            if stopped.is_err() {
+                tracing::warn!(
+                    "Background tasks still running after {:?}, cancelling them",
+                    shutdown_timeout
+                );
+                tasks.abort_all();
+            }
+            pool.close().await;
+            tracing::info!("Shutdown complete");
 
-            let listener = tokio::net::TcpListener::bind(&addr).await?;
-            axum::serve(
-                listener,
+            served?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Binds every listener before serving any, so a taken port fails startup,
+/// then runs them until a shutdown signal arrives or one stops. A server
+/// missing one of its addresses is treated as down rather than left
+/// half-reachable. Either way `shutdown` is flipped and every server stops
+/// accepting, with up to `drain_timeout` for in-flight requests to finish.
+async fn serve_listeners(
+    apps: Vec<(Listener, Router)>,
+    shutdown: &watch::Sender<bool>,
+    drain_timeout: Duration,
+) -> anyhow::Result<()> {
+    let mut bound = Vec::with_capacity(apps.len());
+    for (listener, app) in apps {
+        let socket = agreed_time_backend::listeners::bind(&listener.addr)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to bind {}: {}", listener.addr, e))?;
+        tracing::info!(
+            "Serving {:?} routes on {}",
+            listener.plane,
+            socket.local_addr()?
+        );
+        bound.push((listener, socket, app));
+    }
+
+    let mut servers = JoinSet::new();
+    for (listener, socket, app) in bound {
+        let mut stopping = shutdown.subscribe();
+        servers.spawn(async move {
+            let result = axum::serve(
+                socket,
                 app.into_make_service_with_connect_info::<SocketAddr>(),
             )
-            .await?;
+            .with_graceful_shutdown(async move {
+                let _ = stopping.wait_for(|stopping| *stopping).await;
+            })
+            .await;
+            (listener, result)
+        });
+    }
+
+    let result = tokio::select! {
+        stopped = servers.join_next() => match stopped {
+            Some(Ok((listener, Ok(())))) => Err(anyhow::anyhow!("listener {} stopped", listener.addr)),
+            Some(Ok((listener, Err(e)))) => {
+                Err(anyhow::anyhow!("listener {} failed: {}", listener.addr, e))
+            }
+            Some(Err(e)) => Err(e.into()),
+            None => Ok(()),
+        },
+        _ = shutdown_signal() => {
+            tracing::info!("Shutdown signal received, draining connections");
+            Ok(())
+        }
+    };
+
+    shutdown.send_replace(true);
+    let drained = tokio::time::timeout(drain_timeout, async {
+        while let Some(stopped) = servers.join_next().await {
+            if let Ok((listener, Err(e))) = stopped {
+                tracing::error!("Listener {} failed while draining: {}", listener.addr, e);
+            }
         }
+    })
+    .await;
+    if drained.is_err() {
+        tracing::warn!(
+            "Requests still in flight after {:?}, closing their connections",
+            drain_timeout
+        );
+        servers.abort_all();
     }
 
-    Ok(())
+    result
+}
+
+/// Resolves on Ctrl+C, or SIGTERM as sent by container runtimes.
+async fn shutdown_signal() {
+    let interrupt = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to listen for Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => {}
+        _ = terminate => {}
+    }
 }
@@ -0,0 +1,340 @@
+//! Business-level gauges in the Prometheus text exposition format.
+//!
+//! A background task calls [`collect`] periodically and stores the snapshot
+//! in [`SharedMetrics`]; `GET /metrics` only renders the latest snapshot so
+//! scrapes never hit the database. Request-path events are counted in
+//! [`RequestCounters`] as they happen.
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::{clock::Clock, db::PoolStats, integrations::mail::MAX_ATTEMPTS, models::EventState};
+
+/// Open events whose first candidate slot starts within this window and
+/// that nobody but the organizer answered yet are reported as at risk.
+pub const AT_RISK_HOURS: i64 = 48;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BusinessMetrics {
+    pub collected_at: DateTime<Utc>,
+    pub events_by_state: BTreeMap<String, i64>,
+    pub events_at_risk: i64,
+    /// Soft-deleted events awaiting the purge job
+    pub events_trashed: i64,
+    pub outbox_pending: i64,
+    pub outbox_failed: i64,
+}
+
+pub type SharedMetrics = Arc<RwLock<Option<BusinessMetrics>>>;
+
+/// Monotonic in-process counters; they restart at zero with the process,
+/// which Prometheus `counter` semantics tolerate.
+#[derive(Debug, Default)]
+pub struct RequestCounters {
+    /// Availability submissions rejected by the per-event limiter
+    pub submissions_rate_limited: AtomicU64,
+    pub rate_limit: RateLimitCounters,
+}
+
+#[derive(Debug, Default)]
+pub struct RouteCounts {
+    pub allowed: AtomicU64,
+    pub limited: AtomicU64,
+}
+
+/// Decisions of the per-client rate limiter, by route group.
+#[derive(Debug, Default)]
+pub struct RateLimitCounters {
+    pub default: RouteCounts,
+    pub create_event: RouteCounts,
+    pub health: RouteCounts,
+    /// Clients dropped to stay under `RATE_LIMIT_MAX_CLIENTS`
+    pub evicted: AtomicU64,
+    /// Clients currently tracked in memory; a gauge
+    pub clients: AtomicU64,
+}
+
+impl RateLimitCounters {
+    fn routes(&self) -> [(&'static str, &RouteCounts); 3] {
+        [
+            ("default", &self.default),
+            ("create_event", &self.create_event),
+            ("health", &self.health),
+        ]
+    }
+}
+
+pub type SharedCounters = Arc<RequestCounters>;
+
+pub async fn collect(pool: &PgPool, clock: &dyn Clock) -> Result<BusinessMetrics, sqlx::Error> {
+    let now = clock.now();
+
+    let mut events_by_state: BTreeMap<String, i64> = EventState::ALL
+        .into_iter()
+        .map(|state| (state.as_str().to_string(), 0))
+        .collect();
+    let rows = sqlx::query!(
+        r#"SELECT state, COUNT(*) AS "count!" FROM events WHERE deleted_at IS NULL GROUP BY state"#
+    )
+    .fetch_all(pool)
+    .await?;
+    for row in rows {
+        events_by_state.insert(row.state, row.count);
+    }
+
+    let events_at_risk = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!"
+        FROM events e
+        WHERE e.state = 'open'
+          AND e.deleted_at IS NULL
+          AND (SELECT MIN(s.start_at) FROM event_slots s WHERE s.event_id = e.id) BETWEEN $1 AND $2
+          AND NOT EXISTS (
+              SELECT 1 FROM participants p WHERE p.event_id = e.id AND p.is_organizer = false
+          )
+        "#,
+        now,
+        now + Duration::hours(AT_RISK_HOURS)
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let events_trashed = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM events WHERE deleted_at IS NOT NULL"#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let outbox = sqlx::query!(
+        r#"
+        SELECT
+            COUNT(*) FILTER (WHERE attempts < $1) AS "pending!",
+            COUNT(*) FILTER (WHERE attempts >= $1) AS "failed!"
+        FROM email_outbox
+        WHERE sent_at IS NULL
+        "#,
+        MAX_ATTEMPTS
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(BusinessMetrics {
+        collected_at: now,
+        events_by_state,
+        events_at_risk,
+        events_trashed,
+        outbox_pending: outbox.pending,
+        outbox_failed: outbox.failed,
+    })
+}
+
+pub fn render(metrics: &BusinessMetrics) -> String {
+    let mut out = String::new();
+
+    gauge_header(
+        &mut out,
+        "agreed_time_events",
+        "Events by lifecycle state, excluding trashed ones",
+    );
+    for (state, count) in &metrics.events_by_state {
+        let _ = writeln!(out, "agreed_time_events{{state=\"{}\"}} {}", state, count);
+    }
+
+    gauge(
+        &mut out,
+        "agreed_time_events_at_risk",
+        "Open events starting within 48h with no responses besides the organizer",
+        metrics.events_at_risk,
+    );
+    gauge(
+        &mut out,
+        "agreed_time_events_trashed",
+        "Soft-deleted events awaiting purge",
+        metrics.events_trashed,
+    );
+    gauge(
+        &mut out,
+        "agreed_time_email_outbox_pending",
+        "Queued emails not yet delivered",
+        metrics.outbox_pending,
+    );
+    gauge(
+        &mut out,
+        "agreed_time_email_outbox_failed",
+        "Emails abandoned after exhausting delivery attempts",
+        metrics.outbox_failed,
+    );
+    gauge(
+        &mut out,
+        "agreed_time_metrics_collected_timestamp_seconds",
+        "Unix time of the last collection",
+        metrics.collected_at.timestamp(),
+    );
+
+    out
+}
+
+pub fn render_counters(counters: &RequestCounters) -> String {
+    let mut out = String::new();
+
+    counter(
+        &mut out,
+        "agreed_time_submissions_rate_limited_total",
+        "Availability submissions rejected by the per-event, per-IP limiter",
+        counters.submissions_rate_limited.load(Ordering::Relaxed),
+    );
+
+    let rate_limit = &counters.rate_limit;
+    counter_header(
+        &mut out,
+        "agreed_time_rate_limit_requests_total",
+        "Requests checked by the per-client rate limiter, by route group and outcome",
+    );
+    for (route, counts) in rate_limit.routes() {
+        for (outcome, value) in [("allowed", &counts.allowed), ("limited", &counts.limited)] {
+            let _ = writeln!(
+                out,
+                "agreed_time_rate_limit_requests_total{{route=\"{}\",outcome=\"{}\"}} {}",
+                route,
+                outcome,
+                value.load(Ordering::Relaxed)
+            );
+        }
+    }
+    counter(
+        &mut out,
+        "agreed_time_rate_limit_evictions_total",
+        "Clients the rate limiter forgot early to stay under its capacity",
+        rate_limit.evicted.load(Ordering::Relaxed),
+    );
+    gauge(
+        &mut out,
+        "agreed_time_rate_limit_clients",
+        "Clients the in-memory rate limiter is tracking",
+        rate_limit.clients.load(Ordering::Relaxed) as i64,
+    );
+
+    out
+}
+
+/// Connection pool gauges, read from the pool at scrape time.
+pub fn render_pool(stats: &PoolStats) -> String {
+    let mut out = String::new();
+
+    gauge_header(
+        &mut out,
+        "agreed_time_db_pool_connections",
+        "Open database connections, by whether a query holds them",
+    );
+    for (state, value) in [("idle", stats.idle), ("in_use", stats.in_use)] {
+        let _ = writeln!(
+            out,
+            "agreed_time_db_pool_connections{{state=\"{}\"}} {}",
+            state, value
+        );
+    }
+    gauge(
+        &mut out,
+        "agreed_time_db_pool_max_connections",
+        "Connections the pool may open (DB_MAX_CONNECTIONS)",
+        stats.max_connections as i64,
+    );
+
+    out
+}
+
+fn counter_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    counter_header(out, name, help);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn gauge_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: i64) {
+    gauge_header(out, name, help);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render_exposition_format() {
+        let metrics = BusinessMetrics {
+            collected_at: Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap(),
+            events_by_state: BTreeMap::from([("open".to_string(), 3), ("closed".to_string(), 1)]),
+            events_at_risk: 2,
+            events_trashed: 4,
+            outbox_pending: 5,
+            outbox_failed: 0,
+        };
+
+        let text = render(&metrics);
+        assert!(text.contains("# TYPE agreed_time_events gauge\n"));
+        assert!(text.contains(
+            "agreed_time_events{state=\"closed\"} 1\nagreed_time_events{state=\"open\"} 3\n"
+        ));
+        assert!(text.contains("\nagreed_time_events_at_risk 2\n"));
+        assert!(text.contains("\nagreed_time_events_trashed 4\n"));
+        assert!(text.contains("\nagreed_time_email_outbox_pending 5\n"));
+        assert!(text.contains("\nagreed_time_metrics_collected_timestamp_seconds 1893456000\n"));
+    }
+
+    #[test]
+    fn test_render_counters() {
+        let counters = RequestCounters::default();
+        counters
+            .submissions_rate_limited
+            .fetch_add(2, Ordering::Relaxed);
+        counters
+            .rate_limit
+            .create_event
+            .limited
+            .fetch_add(3, Ordering::Relaxed);
+        counters.rate_limit.clients.store(7, Ordering::Relaxed);
+
+        let text = render_counters(&counters);
+        assert!(text.contains("# TYPE agreed_time_submissions_rate_limited_total counter\n"));
+        assert!(text.contains("\nagreed_time_submissions_rate_limited_total 2\n"));
+        assert!(text.contains(
+            "\nagreed_time_rate_limit_requests_total{route=\"create_event\",outcome=\"limited\"} 3\n"
+        ));
+        assert!(text.contains(
+            "\nagreed_time_rate_limit_requests_total{route=\"health\",outcome=\"allowed\"} 0\n"
+        ));
+        assert!(text.contains("\nagreed_time_rate_limit_clients 7\n"));
+    }
+
+    #[test]
+    fn test_render_pool() {
+        let text = render_pool(&PoolStats {
+            max_connections: 10,
+            open: 4,
+            idle: 1,
+            in_use: 3,
+        });
+        assert!(text.contains("# TYPE agreed_time_db_pool_connections gauge\n"));
+        assert!(text.contains(
+            "agreed_time_db_pool_connections{state=\"idle\"} 1\nagreed_time_db_pool_connections{state=\"in_use\"} 3\n"
+        ));
+        assert!(text.contains("\nagreed_time_db_pool_max_connections 10\n"));
+    }
+}
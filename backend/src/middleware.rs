@@ -1,33 +1,249 @@
 use axum::{
-    extract::{Request, connect_info::ConnectInfo},
-    http::StatusCode,
+    extract::{MatchedPath, Request, State, connect_info::ConnectInfo},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
     response::{IntoResponse, Response},
 };
 use futures::future::BoxFuture;
 use std::{
-    collections::HashMap,
-    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
-    sync::{Arc, Mutex},
+    collections::{BTreeMap, HashMap},
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex, atomic::Ordering},
     task::{Context, Poll},
     time::{Duration, Instant},
 };
 use tower::{Layer, Service};
+use tracing::Instrument;
+use uuid::Uuid;
 
-// Rate limiting configuration
-const RATE_LIMIT_DURATION: Duration = Duration::from_secs(60); // 1 minute
-const MAX_REQUESTS_PER_DURATION: u32 = 60; // 60 requests per minute
+use crate::{
+    clock::{self, SharedClock},
+    config::{Config, RateLimit, TrustedProxy},
+    error::AppError,
+    metrics::SharedCounters,
+};
+
+/// Routes with their own `RATE_LIMIT_*` setting; each group counts requests
+/// separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RouteGroup {
+    Default,
+    CreateEvent,
+    Health,
+}
+
+impl RouteGroup {
+    fn as_str(self) -> &'static str {
+        match self {
+            RouteGroup::Default => "default",
+            RouteGroup::CreateEvent => "create_event",
+            RouteGroup::Health => "health",
+        }
+    }
+
+    fn of(req: &Request) -> Self {
+        match (req.method(), req.uri().path()) {
+            (&Method::POST, "/events") => RouteGroup::CreateEvent,
+            (&Method::POST, path) if is_template_instantiation(path) => RouteGroup::CreateEvent,
+            (&Method::GET, "/health" | "/health/live" | "/health/ready") => RouteGroup::Health,
+            _ => RouteGroup::Default,
+        }
+    }
+}
+
+/// `POST /templates/{template_token}/events` creates events like `POST /events`.
+fn is_template_instantiation(path: &str) -> bool {
+    path.strip_prefix("/templates/")
+        .and_then(|rest| rest.strip_suffix("/events"))
+        .is_some_and(|token| !token.is_empty() && !token.contains('/'))
+}
+
+/// Fixed window of one route group and client.
+struct ClientWindow {
+    started: Instant,
+    count: u32,
+    /// Position in [`ClientWindows::recency`]
+    used: u64,
+}
+
+type ClientKey = (RouteGroup, IpAddr);
+
+/// Windows plus a least-recently-used index, so the oldest client can be
+/// dropped when the map is full without scanning it.
+#[derive(Default)]
+struct ClientWindows {
+    windows: HashMap<ClientKey, ClientWindow>,
+    recency: BTreeMap<u64, ClientKey>,
+    next_use: u64,
+}
+
+impl ClientWindows {
+    fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    fn next_use(&mut self) -> u64 {
+        self.next_use += 1;
+        self.next_use
+    }
+
+    fn remove(&mut self, key: &ClientKey) {
+        if let Some(window) = self.windows.remove(key) {
+            self.recency.remove(&window.used);
+        }
+    }
+
+    fn remove_least_recent(&mut self) {
+        if let Some((_, key)) = self.recency.pop_first() {
+            self.windows.remove(&key);
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct RateLimitLayer {
-    // Store rate limit state: (last_request_time, request_count_in_window)
-    clients: Arc<Mutex<HashMap<SocketAddr, (Instant, u32)>>>,
+    clients: Arc<Mutex<ClientWindows>>,
+    default: Option<RateLimit>,
+    create_event: Option<RateLimit>,
+    health: Option<RateLimit>,
+    max_clients: usize,
+    clock: SharedClock,
+    counters: SharedCounters,
 }
 
 impl RateLimitLayer {
     pub fn new() -> Self {
+        Self::with_clock(clock::system())
+    }
+
+    /// Default limits for every route.
+    pub fn with_clock(clock: SharedClock) -> Self {
+        Self::from_config(&Config::default(), clock)
+    }
+
+    pub fn from_config(config: &Config, clock: SharedClock) -> Self {
         RateLimitLayer {
-            clients: Arc::new(Mutex::new(HashMap::new())),
+            clients: Arc::new(Mutex::new(ClientWindows::default())),
+            default: config.rate_limit_default,
+            create_event: config.rate_limit_create_event,
+            health: config.rate_limit_health,
+            max_clients: config.rate_limit_max_clients.max(1),
+            clock,
+            counters: SharedCounters::default(),
+        }
+    }
+
+    /// Reports allowed and limited requests and tracked clients through `counters`.
+    pub fn with_counters(mut self, counters: SharedCounters) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    fn limit(&self, group: RouteGroup) -> Option<RateLimit> {
+        match group {
+            RouteGroup::Default => self.default,
+            RouteGroup::CreateEvent => self.create_event,
+            RouteGroup::Health => self.health,
+        }
+    }
+
+    /// Counts one request; true when the client is over the group's limit.
+    fn should_limit(&self, group: RouteGroup, ip: IpAddr) -> bool {
+        let limited = self.check(group, ip);
+        self.record(group, limited);
+        limited
+    }
+
+    fn record(&self, group: RouteGroup, limited: bool) {
+        let counts = &self.counters.rate_limit;
+        let route = match group {
+            RouteGroup::Default => &counts.default,
+            RouteGroup::CreateEvent => &counts.create_event,
+            RouteGroup::Health => &counts.health,
+        };
+        if limited {
+            route.limited.fetch_add(1, Ordering::Relaxed);
+        } else {
+            route.allowed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn check(&self, group: RouteGroup, ip: IpAddr) -> bool {
+        let Some(limit) = self.limit(group) else {
+            return false;
+        };
+        let mut clients = self.clients.lock().unwrap();
+        let now = self.clock.instant();
+        let key = (group, ip);
+        let used = clients.next_use();
+
+        let limited = if let Some(window) = clients.windows.get_mut(&key) {
+            let previous = std::mem::replace(&mut window.used, used);
+            let limited = if now.duration_since(window.started) > limit.window {
+                // Reset counter if window expired
+                window.started = now;
+                window.count = 1;
+                false
+            } else if window.count >= limit.requests {
+                true
+            } else {
+                // Increment count within window
+                window.count += 1;
+                false
+            };
+            clients.recency.remove(&previous);
+            clients.recency.insert(used, key);
+            limited
+        } else {
+            // First request from this IP; make room by dropping the client
+            // seen longest ago, whose window has most likely expired anyway
+            if clients.len() >= self.max_clients {
+                clients.remove_least_recent();
+                self.counters
+                    .rate_limit
+                    .evicted
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+            clients.windows.insert(
+                key,
+                ClientWindow {
+                    started: now,
+                    count: 1,
+                    used,
+                },
+            );
+            clients.recency.insert(used, key);
+            limit.requests == 0
+        };
+        self.counters
+            .rate_limit
+            .clients
+            .store(clients.len() as u64, Ordering::Relaxed);
+        limited
+    }
+
+    /// Forgets clients whose window has ended; they would start a fresh one
+    /// on their next request anyway. Returns how many were dropped.
+    pub fn evict_expired(&self) -> usize {
+        let now = self.clock.instant();
+        let mut clients = self.clients.lock().unwrap();
+        let expired: Vec<ClientKey> = clients
+            .windows
+            .iter()
+            .filter(|((group, _), window)| {
+                self.limit(*group)
+                    .is_none_or(|limit| now.duration_since(window.started) > limit.window)
+            })
+            .map(|(key, _)| *key)
+            .collect();
+        for key in &expired {
+            clients.remove(key);
         }
+        self.counters
+            .rate_limit
+            .clients
+            .store(clients.len() as u64, Ordering::Relaxed);
+        expired.len()
     }
 }
 
@@ -43,7 +259,7 @@ impl<S> Layer<S> for RateLimitLayer {
     fn layer(&self, inner: S) -> Self::Service {
         RateLimitService {
             inner,
-            clients: self.clients.clone(),
+            limiter: self.clone(),
         }
     }
 }
@@ -51,7 +267,7 @@ impl<S> Layer<S> for RateLimitLayer {
 #[derive(Clone)]
 pub struct RateLimitService<S> {
     inner: S,
-    clients: Arc<Mutex<HashMap<SocketAddr, (Instant, u32)>>>,
+    limiter: RateLimitLayer,
 }
 
 impl<S> Service<Request> for RateLimitService<S>
@@ -68,63 +284,379 @@ where
     }
 
     fn call(&mut self, req: Request) -> Self::Future {
-        // Extract IP (Simplified logic for middleware)
-        // If ConnectInfo is missing (e.g. in tests without proper setup), we fallback to a loopback.
-        // In real Axum run, ConnectInfo is injected by the router.
-        let peer_addr = if let Some(conn_info) = req.extensions().get::<ConnectInfo<SocketAddr>>() {
-            let mut extracted_ip = conn_info.0;
-
-            // Check X-Forwarded-For
-            if let Some(x_forwarded_for) = req.headers().get("x-forwarded-for")
-                && let Ok(ip_str) = x_forwarded_for.to_str()
-                && let Some(client_ip) = ip_str.split(',').next()
-                && let Ok(ip_addr) = client_ip.trim().parse::<Ipv4Addr>()
-            {
-                extracted_ip = SocketAddr::V4(SocketAddrV4::new(ip_addr, conn_info.0.port()));
-            }
-            extracted_ip
-        } else {
-            // Fallback for when ConnectInfo is missing (shouldn't happen in prod if configured right)
-            SocketAddr::from(([127, 0, 0, 1], 0))
+        if self
+            .limiter
+            .should_limit(RouteGroup::of(&req), client_addr(&req).ip())
+        {
+            let fut = async move { Ok(StatusCode::TOO_MANY_REQUESTS.into_response()) };
+            return Box::pin(fut);
+        }
+
+        let fut = self.inner.call(req);
+        Box::pin(fut)
+    }
+}
+
+/// Token bucket per key: `capacity` tokens, refilled continuously at
+/// `capacity / window`. Returns 1 when a token was taken.
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local window_ms = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'at')
+local tokens = tonumber(bucket[1]) or capacity
+local at = tonumber(bucket[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - at) * capacity / window_ms)
+local allowed = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'at', now)
+redis.call('PEXPIRE', KEYS[1], window_ms)
+return allowed
+"#;
+
+/// [`RateLimitLayer`] with its state in Redis, so every replica behind a load
+/// balancer draws from the same allowance. Uses a token bucket per route
+/// group and client IP rather than fixed windows, which a shared store makes
+/// cheap. Requests fall back to the in-memory limiter while Redis is
+/// unreachable rather than failing.
+#[derive(Clone)]
+pub struct RedisRateLimitLayer {
+    redis: redis::aio::ConnectionManager,
+    script: Arc<redis::Script>,
+    fallback: RateLimitLayer,
+}
+
+impl RedisRateLimitLayer {
+    /// `None` without a `redis_url`; fails when Redis can't be reached at startup.
+    pub async fn from_config(config: &Config, clock: SharedClock) -> anyhow::Result<Option<Self>> {
+        let Some(redis_url) = config.redis_url.as_deref() else {
+            return Ok(None);
         };
+        let client = redis::Client::open(redis_url)?;
+        Ok(Some(RedisRateLimitLayer {
+            redis: redis::aio::ConnectionManager::new(client).await?,
+            script: Arc::new(redis::Script::new(TOKEN_BUCKET_SCRIPT)),
+            fallback: RateLimitLayer::from_config(config, clock),
+        }))
+    }
 
-        let should_limit = {
-            let mut clients = self.clients.lock().unwrap();
-            let now = Instant::now();
-
-            if let Some((last_req_time, count)) = clients.get_mut(&peer_addr) {
-                if now.duration_since(*last_req_time) > RATE_LIMIT_DURATION {
-                    // Reset counter if window expired
-                    *last_req_time = now;
-                    *count = 1;
-                    false
-                } else if *count >= MAX_REQUESTS_PER_DURATION {
-                    true
-                } else {
-                    // Increment count within window
-                    *count += 1;
-                    false
-                }
-            } else {
-                // First request from this IP
-                clients.insert(peer_addr, (now, 1));
-                false
+    /// Takes one token; true when the bucket is empty.
+    async fn should_limit(&self, group: RouteGroup, ip: IpAddr) -> bool {
+        let Some(limit) = self.fallback.limit(group) else {
+            return false;
+        };
+        let allowed: redis::RedisResult<i64> = self
+            .script
+            .key(format!("agreed_time:rate_limit:{}:{}", group.as_str(), ip))
+            .arg(limit.requests)
+            .arg(limit.window.as_millis().max(1) as u64)
+            .arg(self.fallback.clock.now().timestamp_millis())
+            .invoke_async(&mut self.redis.clone())
+            .await;
+        let limited = match allowed {
+            Ok(allowed) => allowed == 0,
+            Err(e) => {
+                tracing::warn!("Redis rate limiter unavailable, limiting in memory: {}", e);
+                self.fallback.check(group, ip)
             }
         };
+        self.fallback.record(group, limited);
+        limited
+    }
 
-        if should_limit {
-            let fut = async move { Ok(StatusCode::TOO_MANY_REQUESTS.into_response()) };
-            return Box::pin(fut);
+    /// Reports allowed and limited requests through `counters`.
+    pub fn with_counters(mut self, counters: SharedCounters) -> Self {
+        self.fallback = self.fallback.with_counters(counters);
+        self
+    }
+
+    /// Evicts expired clients of the in-memory fallback.
+    pub fn evict_expired(&self) -> usize {
+        self.fallback.evict_expired()
+    }
+}
+
+impl<S> Layer<S> for RedisRateLimitLayer {
+    type Service = RedisRateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RedisRateLimitService {
+            inner,
+            limiter: self.clone(),
         }
+    }
+}
 
-        let fut = self.inner.call(req);
-        Box::pin(fut)
+#[derive(Clone)]
+pub struct RedisRateLimitService<S> {
+    inner: S,
+    limiter: RedisRateLimitLayer,
+}
+
+impl<S> Service<Request> for RedisRateLimitService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        // The service polled ready is the one that must handle the request
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            if limiter
+                .should_limit(RouteGroup::of(&req), client_addr(&req).ip())
+                .await
+            {
+                return Ok(StatusCode::TOO_MANY_REQUESTS.into_response());
+            }
+            inner.call(req).await
+        })
+    }
+}
+
+/// Client address of a request as [`ClientAddrLayer`] resolved it, else the
+/// connecting peer. `X-Forwarded-For` never counts on its own.
+// If ConnectInfo is missing (e.g. in tests without proper setup), we fallback to a loopback.
+// In real Axum run, ConnectInfo is injected by the router.
+pub(crate) fn client_addr(req: &Request) -> SocketAddr {
+    if let Some(ClientAddr(addr)) = req.extensions().get::<ClientAddr>() {
+        *addr
+    } else if let Some(ConnectInfo(addr)) = req.extensions().get::<ConnectInfo<SocketAddr>>() {
+        *addr
+    } else {
+        // Fallback for when ConnectInfo is missing (shouldn't happen in prod if configured right)
+        SocketAddr::from(([127, 0, 0, 1], 0))
+    }
+}
+
+/// The client a request came from, as [`ClientAddrLayer`] resolved it.
+#[derive(Debug, Clone, Copy)]
+struct ClientAddr(SocketAddr);
+
+/// Resolves once per request the client address the limiters, the abuse
+/// layer, the admin audit trail and the logs key on. The connecting peer
+/// counts unless it is one of `TRUSTED_PROXIES`; then `X-Forwarded-For` is
+/// read from the right, past any further trusted hops, since everything
+/// left of what our own proxies appended is whatever the client sent.
+#[derive(Clone, Default)]
+pub struct ClientAddrLayer {
+    trusted: Arc<[TrustedProxy]>,
+}
+
+impl ClientAddrLayer {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            trusted: config.trusted_proxies.clone().into(),
+        }
+    }
+
+    fn resolve(&self, req: &Request) -> Option<SocketAddr> {
+        let ConnectInfo(peer) = *req.extensions().get::<ConnectInfo<SocketAddr>>()?;
+        let trusted = |ip: IpAddr| self.trusted.iter().any(|proxy| proxy.contains(ip));
+        if !trusted(peer.ip()) {
+            return Some(peer);
+        }
+        let mut client = peer.ip();
+        for hop in req
+            .headers()
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+        {
+            let Ok(ip) = hop.trim().parse::<IpAddr>() else {
+                break;
+            };
+            client = ip;
+            if !trusted(ip) {
+                break;
+            }
+        }
+        Some(SocketAddr::new(client, peer.port()))
+    }
+}
+
+impl<S> Layer<S> for ClientAddrLayer {
+    type Service = ClientAddrService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientAddrService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ClientAddrService<S> {
+    inner: S,
+    layer: ClientAddrLayer,
+}
+
+impl<S> Service<Request> for ClientAddrService<S>
+where
+    S: Service<Request>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        if let Some(addr) = self.layer.resolve(&req) {
+            req.extensions_mut().insert(ClientAddr(addr));
+        }
+        self.inner.call(req)
+    }
+}
+
+/// Beyond this many tracked (event, IP) windows, expired ones are swept.
+const SUBMISSION_SWEEP_THRESHOLD: usize = 10_000;
+
+type SubmissionWindows = Arc<Mutex<HashMap<(String, IpAddr), (Instant, u32)>>>;
+
+/// Limits `POST /events/{public_token}/availability` per (event, client IP).
+/// A batch (`.../availability/batch`) counts as one submission.
+///
+/// Applied outside [`RateLimitLayer`], so submissions rejected here never
+/// count against the client's global allowance: flooding one event does not
+/// lock legitimate users behind the same IP out of the rest of the API, and
+/// a single client cannot add bogus participants faster than the limit.
+#[derive(Clone)]
+pub struct SubmissionRateLimitLayer {
+    windows: SubmissionWindows,
+    limit: u32,
+    window: Duration,
+    clock: SharedClock,
+    counters: SharedCounters,
+}
+
+impl SubmissionRateLimitLayer {
+    pub fn new(config: &Config, clock: SharedClock, counters: SharedCounters) -> Self {
+        SubmissionRateLimitLayer {
+            windows: Arc::new(Mutex::new(HashMap::new())),
+            limit: config.submission_rate_limit,
+            window: Duration::from_secs(config.submission_rate_window_secs),
+            clock,
+            counters,
+        }
+    }
+
+    /// Counts one submission; returns the time until the window resets when
+    /// the client is over the limit.
+    fn check(&self, token: &str, ip: IpAddr) -> Option<Duration> {
+        let now = self.clock.instant();
+        let mut windows = self.windows.lock().unwrap();
+
+        if windows.len() >= SUBMISSION_SWEEP_THRESHOLD {
+            windows.retain(|_, (started, _)| now.duration_since(*started) <= self.window);
+        }
+
+        let (started, count) = windows.entry((token.to_string(), ip)).or_insert((now, 0));
+        if now.duration_since(*started) > self.window {
+            *started = now;
+            *count = 0;
+        }
+        if *count >= self.limit {
+            return Some(self.window.saturating_sub(now.duration_since(*started)));
+        }
+        *count += 1;
+        None
+    }
+}
+
+impl<S> Layer<S> for SubmissionRateLimitLayer {
+    type Service = SubmissionRateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SubmissionRateLimitService {
+            inner,
+            limiter: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SubmissionRateLimitService<S> {
+    inner: S,
+    limiter: SubmissionRateLimitLayer,
+}
+
+/// Public token when `req` is an availability submission, single or batch.
+fn submission_token(req: &Request) -> Option<&str> {
+    if req.method() != Method::POST {
+        return None;
+    }
+    let mut segments = req.uri().path().strip_prefix("/events/")?.split('/');
+    let token = segments.next().filter(|t| !t.is_empty())?;
+    (segments.next() == Some("availability")
+        && matches!(segments.next(), None | Some("batch"))
+        && segments.next().is_none())
+    .then_some(token)
+}
+
+impl<S> Service<Request> for SubmissionRateLimitService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        if self.limiter.limit > 0
+            && let Some(token) = submission_token(&req)
+            && let Some(retry_after) = self.limiter.check(token, client_addr(&req).ip())
+        {
+            self.limiter
+                .counters
+                .submissions_rate_limited
+                .fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(token, "Availability submissions rate limited");
+
+            let mut response = AppError::TooManyRequests(
+                "Too many submissions to this event; try again later".to_string(),
+            )
+            .into_response();
+            response.headers_mut().insert(
+                header::RETRY_AFTER,
+                HeaderValue::from(retry_after.as_secs().max(1)),
+            );
+            return Box::pin(async move { Ok(response) });
+        }
+
+        Box::pin(self.inner.call(req))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{clock::TestClock, config::DEFAULT_RATE_LIMIT};
     use axum::body::Body;
     use axum::http::{Request, StatusCode};
     use tower::ServiceExt; // for oneshot
@@ -142,8 +674,8 @@ mod tests {
 
         let ip = SocketAddr::from(([127, 0, 0, 1], 12345));
 
-        // Send MAX_REQUESTS_PER_DURATION requests (Allowed)
-        for _ in 0..MAX_REQUESTS_PER_DURATION {
+        // Send DEFAULT_RATE_LIMIT.requests requests (Allowed)
+        for _ in 0..DEFAULT_RATE_LIMIT.requests {
             let mut req = Request::builder().body(Body::empty()).unwrap();
             req.extensions_mut().insert(ConnectInfo(ip));
             let res = rate_limit_service
@@ -169,24 +701,86 @@ mod tests {
         assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
     }
 
+    #[tokio::test]
+    async fn test_rate_limit_ignores_client_port() {
+        let layer = RateLimitLayer::new();
+        let service = tower::service_fn(handle_request);
+        let mut rate_limit_service = layer.layer(service);
+
+        // Each request arrives on a new connection, so from a new port
+        let mut status = StatusCode::OK;
+        for port in 0..=DEFAULT_RATE_LIMIT.requests {
+            let mut req = Request::builder().body(Body::empty()).unwrap();
+            req.extensions_mut().insert(ConnectInfo(SocketAddr::from((
+                [127, 0, 0, 1],
+                40000 + port as u16,
+            ))));
+            status = rate_limit_service
+                .ready()
+                .await
+                .unwrap()
+                .call(req)
+                .await
+                .unwrap()
+                .status();
+        }
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
     #[tokio::test]
     async fn test_rate_limit_reset_after_duration() {
-        // Skip as discussed
+        let clock = TestClock::default();
+        let layer = RateLimitLayer::with_clock(Arc::new(clock.clone()));
+        let service = tower::service_fn(handle_request);
+        let mut rate_limit_service = layer.layer(service);
+
+        let ip = SocketAddr::from(([127, 0, 0, 1], 12345));
+
+        // Exhaust the window
+        for _ in 0..=DEFAULT_RATE_LIMIT.requests {
+            let mut req = Request::builder().body(Body::empty()).unwrap();
+            req.extensions_mut().insert(ConnectInfo(ip));
+            rate_limit_service
+                .ready()
+                .await
+                .unwrap()
+                .call(req)
+                .await
+                .unwrap();
+        }
+
+        // Once the window has passed, the client is allowed again
+        clock.advance(DEFAULT_RATE_LIMIT.window + Duration::from_secs(1));
+
+        let mut req = Request::builder().body(Body::empty()).unwrap();
+        req.extensions_mut().insert(ConnectInfo(ip));
+        let res = rate_limit_service
+            .ready()
+            .await
+            .unwrap()
+            .call(req)
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
     }
 
     #[tokio::test]
     async fn test_x_forwarded_for() {
-        let layer = RateLimitLayer::new();
+        let config = Config {
+            trusted_proxies: vec!["10.0.0.0/8".parse().unwrap()],
+            ..Config::default()
+        };
         let service = tower::service_fn(handle_request);
-        let mut rate_limit_service = layer.layer(service);
+        let mut rate_limit_service =
+            ClientAddrLayer::new(&config).layer(RateLimitLayer::new().layer(service));
 
         // Direct IP (Load Balancer)
         let lb_ip = SocketAddr::from(([10, 0, 0, 1], 12345));
         // Real Client IP
         let client_ip = "203.0.113.195";
 
-        // Send MAX_REQUESTS_PER_DURATION requests from client_ip
-        for _ in 0..MAX_REQUESTS_PER_DURATION {
+        // Send DEFAULT_RATE_LIMIT.requests requests from client_ip
+        for _ in 0..DEFAULT_RATE_LIMIT.requests {
             let mut req = Request::builder()
                 .header("x-forwarded-for", client_ip)
                 .body(Body::empty())
@@ -233,6 +827,406 @@ mod tests {
             .unwrap();
         assert_eq!(res_other.status(), StatusCode::OK);
     }
+
+    fn forwarded(peer: SocketAddr, forwarded_for: &str) -> Request<Body> {
+        let mut req = Request::builder()
+            .header("x-forwarded-for", forwarded_for)
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(ConnectInfo(peer));
+        req
+    }
+
+    #[tokio::test]
+    async fn test_x_forwarded_for_needs_a_trusted_peer() {
+        let config = Config {
+            trusted_proxies: vec!["10.0.0.0/8".parse().unwrap()],
+            ..Config::default()
+        };
+        let layer = ClientAddrLayer::new(&config);
+        let client = |req: &Request<Body>| layer.resolve(req).unwrap().ip().to_string();
+
+        // Straight from the internet: the header is the client's own claim
+        let direct = SocketAddr::from(([203, 0, 113, 7], 5000));
+        assert_eq!(client(&forwarded(direct, "198.51.100.1")), "203.0.113.7");
+
+        // Through our proxies, the rightmost hop they didn't add themselves
+        let lb = SocketAddr::from(([10, 0, 0, 1], 5000));
+        assert_eq!(
+            client(&forwarded(lb, "198.51.100.1, 203.0.113.195, 10.0.0.2")),
+            "203.0.113.195"
+        );
+        assert_eq!(client(&forwarded(lb, "garbage")), "10.0.0.1");
+
+        // Changing the header doesn't give an untrusted client a new allowance
+        let mut service =
+            layer.layer(RateLimitLayer::new().layer(tower::service_fn(handle_request)));
+        for i in 0..=DEFAULT_RATE_LIMIT.requests {
+            let res = service
+                .ready()
+                .await
+                .unwrap()
+                .call(forwarded(direct, &format!("198.51.100.{}", i % 250)))
+                .await
+                .unwrap();
+            let expected = if i < DEFAULT_RATE_LIMIT.requests {
+                StatusCode::OK
+            } else {
+                StatusCode::TOO_MANY_REQUESTS
+            };
+            assert_eq!(res.status(), expected);
+        }
+    }
+
+    fn submission(token: &str, ip: SocketAddr) -> Request<Body> {
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .uri(format!("/events/{}/availability", token))
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(ConnectInfo(ip));
+        req
+    }
+
+    fn submission_limiter(
+        clock: &TestClock,
+        counters: &SharedCounters,
+    ) -> SubmissionRateLimitLayer {
+        let config = Config {
+            submission_rate_limit: 3,
+            submission_rate_window_secs: 600,
+            ..Config::default()
+        };
+        SubmissionRateLimitLayer::new(&config, Arc::new(clock.clone()), counters.clone())
+    }
+
+    #[tokio::test]
+    async fn test_submission_limit_is_per_event_and_ip() {
+        let clock = TestClock::default();
+        let counters = SharedCounters::default();
+        let mut service =
+            submission_limiter(&clock, &counters).layer(tower::service_fn(handle_request));
+        let ip = SocketAddr::from(([203, 0, 113, 7], 40000));
+        let other_ip = SocketAddr::from(([203, 0, 113, 8], 40000));
+
+        for _ in 0..3 {
+            let res = service
+                .ready()
+                .await
+                .unwrap()
+                .call(submission("abc", ip))
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+        let res = service
+            .ready()
+            .await
+            .unwrap()
+            .call(submission("abc", ip))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers()[header::RETRY_AFTER], "600");
+        assert_eq!(counters.submissions_rate_limited.load(Ordering::Relaxed), 1);
+
+        // Other events, other clients and other routes are unaffected
+        let res = service
+            .ready()
+            .await
+            .unwrap()
+            .call(submission("xyz", ip))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = service
+            .ready()
+            .await
+            .unwrap()
+            .call(submission("abc", other_ip))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let mut req = Request::builder()
+            .uri("/events/abc/results")
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(ConnectInfo(ip));
+        let res = service.ready().await.unwrap().call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        clock.advance(Duration::from_secs(601));
+        let res = service
+            .ready()
+            .await
+            .unwrap()
+            .call(submission("abc", ip))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_batch_submission_counts_as_one() {
+        let request = |method: Method, uri: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+        for (method, uri, token) in [
+            (Method::POST, "/events/abc/availability", Some("abc")),
+            (Method::POST, "/events/abc/availability/batch", Some("abc")),
+            (Method::POST, "/events/abc/availability/batch/x", None),
+            (Method::POST, "/events/abc/availability/other", None),
+            (Method::GET, "/events/abc/availability/batch", None),
+        ] {
+            assert_eq!(submission_token(&request(method, uri)), token, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn test_template_events_count_as_event_creation() {
+        let group = |method: Method, uri: &str| {
+            RouteGroup::of(
+                &Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+        assert_eq!(
+            group(Method::POST, "/templates/abc/events"),
+            RouteGroup::CreateEvent
+        );
+        assert_eq!(group(Method::POST, "/templates"), RouteGroup::Default);
+        assert_eq!(group(Method::GET, "/templates/abc"), RouteGroup::Default);
+        assert_eq!(
+            group(Method::POST, "/templates/abc/x/events"),
+            RouteGroup::Default
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_submissions_spare_global_allowance() {
+        let clock = TestClock::default();
+        let counters = SharedCounters::default();
+        let mut service = tower::ServiceBuilder::new()
+            .layer(submission_limiter(&clock, &counters))
+            .layer(RateLimitLayer::with_clock(Arc::new(clock.clone())))
+            .service(tower::service_fn(handle_request));
+        let ip = SocketAddr::from(([127, 0, 0, 1], 12345));
+
+        for _ in 0..DEFAULT_RATE_LIMIT.requests {
+            service
+                .ready()
+                .await
+                .unwrap()
+                .call(submission("abc", ip))
+                .await
+                .unwrap();
+        }
+
+        // Only the 3 accepted submissions were counted globally
+        for _ in 0..DEFAULT_RATE_LIMIT.requests - 3 {
+            let mut req = Request::builder().body(Body::empty()).unwrap();
+            req.extensions_mut().insert(ConnectInfo(ip));
+            let res = service.ready().await.unwrap().call(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    fn request(method: Method, uri: &str, ip: SocketAddr) -> Request<Body> {
+        let mut req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(ConnectInfo(ip));
+        req
+    }
+
+    #[tokio::test]
+    async fn test_route_limits_are_separate() {
+        let config = Config {
+            rate_limit_default: Some(RateLimit {
+                requests: 3,
+                window: Duration::from_secs(60),
+            }),
+            rate_limit_create_event: Some(RateLimit {
+                requests: 1,
+                window: Duration::from_secs(60),
+            }),
+            rate_limit_health: None,
+            ..Config::default()
+        };
+        let clock = TestClock::default();
+        let mut service = RateLimitLayer::from_config(&config, Arc::new(clock.clone()))
+            .layer(tower::service_fn(handle_request));
+        let ip = SocketAddr::from(([127, 0, 0, 1], 12345));
+        let mut status = async |method: Method, uri: &str| {
+            service
+                .ready()
+                .await
+                .unwrap()
+                .call(request(method, uri, ip))
+                .await
+                .unwrap()
+                .status()
+        };
+
+        assert_eq!(status(Method::POST, "/events").await, StatusCode::OK);
+        assert_eq!(
+            status(Method::POST, "/events").await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+
+        // Other routes have their own allowance, health checks none at all
+        for _ in 0..3 {
+            assert_eq!(status(Method::GET, "/events/abc").await, StatusCode::OK);
+        }
+        assert_eq!(
+            status(Method::GET, "/events/abc").await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        for _ in 0..10 {
+            assert_eq!(status(Method::GET, "/health").await, StatusCode::OK);
+        }
+    }
+
+    #[test]
+    fn test_client_cap_evicts_least_recently_seen() {
+        let config = Config {
+            rate_limit_default: Some(RateLimit {
+                requests: 1,
+                window: Duration::from_secs(60),
+            }),
+            rate_limit_max_clients: 2,
+            ..Config::default()
+        };
+        let counters = SharedCounters::default();
+        let limiter = RateLimitLayer::from_config(&config, Arc::new(TestClock::default()))
+            .with_counters(counters.clone());
+        let client = |n: u8| IpAddr::from([10, 0, 0, n]);
+
+        assert!(!limiter.should_limit(RouteGroup::Default, client(1)));
+        assert!(!limiter.should_limit(RouteGroup::Default, client(2)));
+        // Client 1 is seen again, so client 2 is the one to go
+        assert!(limiter.should_limit(RouteGroup::Default, client(1)));
+        assert!(!limiter.should_limit(RouteGroup::Default, client(3)));
+        assert!(limiter.should_limit(RouteGroup::Default, client(1)));
+        assert!(!limiter.should_limit(RouteGroup::Default, client(2)));
+
+        let counts = &counters.rate_limit;
+        assert_eq!(counts.evicted.load(Ordering::Relaxed), 2);
+        assert_eq!(counts.clients.load(Ordering::Relaxed), 2);
+        assert_eq!(counts.default.allowed.load(Ordering::Relaxed), 4);
+        assert_eq!(counts.default.limited.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_evict_expired_keeps_active_windows() {
+        let clock = TestClock::default();
+        let limiter = RateLimitLayer::with_clock(Arc::new(clock.clone()));
+        let client = |n: u8| IpAddr::from([10, 0, 0, n]);
+
+        limiter.should_limit(RouteGroup::Default, client(1));
+        clock.advance(DEFAULT_RATE_LIMIT.window);
+        limiter.should_limit(RouteGroup::Health, client(2));
+        assert_eq!(limiter.evict_expired(), 0);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(limiter.evict_expired(), 1);
+        assert_eq!(limiter.clients.lock().unwrap().len(), 1);
+        assert_eq!(limiter.clients.lock().unwrap().recency.len(), 1);
+    }
+}
+
+/// Response header carrying the ID of the request, also included in error
+/// bodies so users can quote it.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// ID of the request being handled, for code running inside
+/// [`RequestLogLayer`]. `None` elsewhere, e.g. in background jobs.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Gives every request a UUID v7 `request_id`, records it on a tracing span
+/// around the handler and in the `X-Request-Id` response header, and logs
+/// one `request` event per response with method, route, status, latency and
+/// client IP. With `LOG_FORMAT=json` that event is one JSON object.
+///
+/// Routes are logged as their template (`/events/{public_token}`) rather
+/// than the path, which would put capability tokens into the logs.
+#[derive(Clone, Default)]
+pub struct RequestLogLayer;
+
+impl<S> Layer<S> for RequestLogLayer {
+    type Service = RequestLogService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestLogService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestLogService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for RequestLogService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let request_id = Uuid::now_v7().to_string();
+        let method = req.method().clone();
+        let route = req
+            .extensions()
+            .get::<MatchedPath>()
+            .map_or("unmatched", |path| path.as_str())
+            .to_string();
+        let client_ip = client_addr(&req).ip();
+        let span = tracing::info_span!("request", request_id = %request_id);
+        let started = Instant::now();
+
+        let fut = REQUEST_ID
+            .scope(request_id.clone(), self.inner.call(req))
+            .instrument(span.clone());
+        Box::pin(async move {
+            let mut res: Response = fut.await?;
+            tracing::info!(
+                parent: &span,
+                request_id = %request_id,
+                method = %method,
+                route = %route,
+                status = res.status().as_u16(),
+                latency_ms = started.elapsed().as_secs_f64() * 1000.0,
+                client_ip = %client_ip,
+                "request"
+            );
+            if let Ok(value) = HeaderValue::from_str(&request_id) {
+                res.headers_mut().insert(REQUEST_ID_HEADER, value);
+            }
+            Ok(res)
+        })
+    }
 }
 
 #[derive(Clone, Default)]
@@ -280,3 +1274,56 @@ where
         })
     }
 }
+
+/// Set on every response of a `DEMO_MODE` deployment.
+pub const DEMO_HEADER: &str = "x-agreed-time-demo";
+
+/// Marks responses as coming from the public sandbox, so API clients and
+/// screenshots can't pass it off as the real service.
+pub async fn demo_watermark(mut response: Response) -> Response {
+    response.headers_mut().insert(
+        DEMO_HEADER,
+        HeaderValue::from_static("public demo; events are deleted 24 hours after creation"),
+    );
+    response
+}
+
+/// Who performed an admin request; inserted by `require_admin_key`.
+#[derive(Debug, Clone)]
+pub struct AdminActor(pub String);
+
+/// Guards `/admin/*`: requires `Authorization: Bearer <ADMIN_API_KEY>`.
+/// When no key is configured the admin surface behaves as if it didn't exist.
+pub async fn require_admin_key(
+    State(config): State<Arc<Config>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(expected) = config.admin_api_key.as_deref() else {
+        return AppError::NotFound.into_response();
+    };
+
+    let provided = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match provided {
+        Some(key) if constant_time_eq(key.as_bytes(), expected.as_bytes()) => {}
+        _ => return AppError::Unauthorized.into_response(),
+    }
+
+    let peer = client_addr(&req).ip();
+    req.extensions_mut()
+        .insert(AdminActor(format!("admin@{}", peer)));
+
+    next.run(req).await
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
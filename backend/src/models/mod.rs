@@ -1,7 +1,10 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::error::ErrorResponse;
+
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 
 pub struct Event {
@@ -11,105 +14,1007 @@ pub struct Event {
     pub title: String,
     pub description: Option<String>,
     // pub organizer_name: String, // Removed from DB model
-    pub state: String,
+    pub state: EventState,
     pub time_zone: Option<String>,
     pub slot_duration: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Lifecycle of an event, stored by its lowercase name in `events.state`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum EventState {
+    Open,
+    Closed,
+    Finalized,
+    /// Read-only; set by the organizer or the auto-archive once finalized
+    Archived,
+}
+
+impl EventState {
+    pub const ALL: [EventState; 4] = [
+        EventState::Open,
+        EventState::Closed,
+        EventState::Finalized,
+        EventState::Archived,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventState::Open => "open",
+            EventState::Closed => "closed",
+            EventState::Finalized => "finalized",
+            EventState::Archived => "archived",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct TimeRangeRequest {
     pub start_at: DateTime<Utc>,
     pub end_at: DateTime<Utc>,
+    /// Availability ranges only; `available` when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub availability_level: Option<AvailabilityLevel>,
+}
+
+impl TimeRangeRequest {
+    pub fn level(&self) -> AvailabilityLevel {
+        self.availability_level.unwrap_or_default()
+    }
+}
+
+impl From<TimeRangeRequest> for agreed_time_core::TimeRange {
+    fn from(range: TimeRangeRequest) -> Self {
+        agreed_time_core::TimeRange::new(range.start_at, range.end_at)
+    }
+}
+
+impl From<agreed_time_core::TimeRange> for TimeRangeRequest {
+    fn from(range: agreed_time_core::TimeRange) -> Self {
+        TimeRangeRequest {
+            start_at: range.start_at,
+            end_at: range.end_at,
+            availability_level: None,
+        }
+    }
+}
+
+/// How a participant marked an availability range (Yes / Maybe / No).
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AvailabilityLevel {
+    #[default]
+    Available,
+    /// Counted at half weight when ranking windows
+    IfNeedBe,
+    /// An explicit "no"; never counted
+    Unavailable,
+}
+
+impl AvailabilityLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AvailabilityLevel::Available => "available",
+            AvailabilityLevel::IfNeedBe => "if_need_be",
+            AvailabilityLevel::Unavailable => "unavailable",
+        }
+    }
+
+    /// Unknown stored values are treated as unavailable so they never inflate counts.
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "available" => AvailabilityLevel::Available,
+            "if_need_be" => AvailabilityLevel::IfNeedBe,
+            _ => AvailabilityLevel::Unavailable,
+        }
+    }
+}
+
+/// Whether a participant counts towards results. Submissions past the
+/// participant cap wait for the organizer to approve them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ParticipantStatus {
+    #[default]
+    Confirmed,
+    /// On the waitlist; left out of results, suggestions and mail
+    Pending,
+}
+
+impl ParticipantStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParticipantStatus::Confirmed => "confirmed",
+            ParticipantStatus::Pending => "pending",
+        }
+    }
+
+    /// Unknown stored values are treated as pending so they never inflate counts.
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "confirmed" => ParticipantStatus::Confirmed,
+            _ => ParticipantStatus::Pending,
+        }
+    }
+}
+
+/// Who may read aggregated results (results, suggestions, summary).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultsVisibility {
+    /// Anyone with the public link
+    #[default]
+    #[serde(alias = "always")]
+    Public,
+    /// Only holders of the results token; submission stays open to the public link
+    #[serde(alias = "organizer_only")]
+    Private,
+    /// Participants who have submitted (`?participant_token=`), so earlier
+    /// answers can't anchor theirs
+    AfterSubmit,
+    /// Everyone, once the event is no longer open
+    AfterClose,
+}
+
+impl ResultsVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResultsVisibility::Public => "public",
+            ResultsVisibility::Private => "private",
+            ResultsVisibility::AfterSubmit => "after_submit",
+            ResultsVisibility::AfterClose => "after_close",
+        }
+    }
+
+    /// Unknown stored values are treated as private so they never leak results.
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "public" => ResultsVisibility::Public,
+            "after_submit" => ResultsVisibility::AfterSubmit,
+            "after_close" => ResultsVisibility::AfterClose,
+            _ => ResultsVisibility::Private,
+        }
+    }
+}
+
+/// How an event's candidate times are expressed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SlotKind {
+    /// Absolute date ranges
+    #[default]
+    Dates,
+    /// Weekdays and times of day, e.g. "Mondays 18:00–20:00"; slots and
+    /// availability live in the reference week of `agreed_time_core::weekly`
+    Weekly,
+}
+
+impl SlotKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlotKind::Dates => "dates",
+            SlotKind::Weekly => "weekly",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "weekly" => SlotKind::Weekly,
+            _ => SlotKind::Dates,
+        }
+    }
+}
+
+/// Whether participants pick times or whole days.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PollType {
+    /// Time ranges
+    #[default]
+    Datetime,
+    /// Whole dates; each one is stored as the slot from local midnight to
+    /// midnight in the event's zone
+    Date,
+}
+
+impl PollType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PollType::Datetime => "datetime",
+            PollType::Date => "date",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "date" => PollType::Date,
+            _ => PollType::Datetime,
+        }
+    }
+}
+
+/// Who can make one candidate date of a date poll.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct DateVotes {
+    pub date: NaiveDate,
+    /// Participants available that day
+    pub count: usize,
+    pub participants: Vec<String>,
+    /// Participants available that day only if need be
+    pub if_need_be: Vec<String>,
+}
+
+impl From<agreed_time_core::DateVotes> for DateVotes {
+    fn from(votes: agreed_time_core::DateVotes) -> Self {
+        DateVotes {
+            date: votes.date,
+            count: votes.count,
+            participants: votes.participants,
+            if_need_be: votes.if_need_be,
+        }
+    }
+}
+
+/// How many people can make one `slot_duration` cell.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SlotCount {
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    pub available: usize,
+    pub if_need_be: usize,
+}
+
+impl From<agreed_time_core::SlotBucket> for SlotCount {
+    fn from(bucket: agreed_time_core::SlotBucket) -> Self {
+        SlotCount {
+            start_at: bucket.start_at,
+            end_at: bucket.end_at,
+            available: bucket.count,
+            if_need_be: bucket.if_need_be.len(),
+        }
+    }
+}
+
+/// A recurring window in the event's time zone.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub struct WeeklySlot {
+    #[schema(value_type = String, example = "Mon")]
+    pub weekday: Weekday,
+    #[schema(value_type = String, example = "18:00:00")]
+    pub start_time: NaiveTime,
+    /// At or before `start_time` means the next day, so `00:00:00` is midnight
+    #[schema(value_type = String, example = "20:00:00")]
+    pub end_time: NaiveTime,
+}
+
+impl From<WeeklySlot> for agreed_time_core::WeeklySlot {
+    fn from(slot: WeeklySlot) -> Self {
+        agreed_time_core::WeeklySlot {
+            weekday: slot.weekday,
+            start_time: slot.start_time,
+            end_time: slot.end_time,
+        }
+    }
+}
+
+impl From<agreed_time_core::WeeklySlot> for WeeklySlot {
+    fn from(slot: agreed_time_core::WeeklySlot) -> Self {
+        WeeklySlot {
+            weekday: slot.weekday,
+            start_time: slot.start_time,
+            end_time: slot.end_time,
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A role every suggested window must staff, e.g. 2 reviewers.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+pub struct EventRole {
+    pub name: String,
+    /// Participants with this role needed per window (at least 1)
+    pub required: i32,
+}
+
+/// What a final slot must offer; `finalize` refuses slots that fall short
+/// unless forced. Attending means available or available if need be for the
+/// whole slot.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, ToSchema)]
+pub struct FinalizationRules {
+    /// Attendees needed, the organizer included
+    pub min_participants: Option<i32>,
+    /// Names that must be among the attendees
+    #[serde(default)]
+    pub required_participants: Vec<String>,
+}
+
+impl FinalizationRules {
+    pub fn is_empty(&self) -> bool {
+        self.min_participants.is_none() && self.required_participants.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateEventRequest {
     pub title: String,
     pub description: Option<String>,
     pub organizer_name: String,
     pub time_zone: Option<String>,
     pub slot_duration: Option<i32>,
+    /// Candidate ranges of a `dates` event
+    #[serde(default)]
     pub time_slots: Vec<TimeRangeRequest>,
+    /// Defaults to `dates`
+    pub slot_kind: Option<SlotKind>,
+    /// Candidate windows of a `weekly` event, in `time_zone` (UTC when unset)
+    #[serde(default)]
+    pub weekly_slots: Vec<WeeklySlot>,
+    /// Defaults to `datetime`
+    pub poll_type: Option<PollType>,
+    /// Candidate dates of a `date` poll, in `time_zone` (UTC when unset);
+    /// replaces `time_slots`
+    #[serde(default)]
+    pub dates: Vec<NaiveDate>,
+    /// Defaults to `public`
+    pub results_visibility: Option<ResultsVisibility>,
+    /// Blind poll: results show only the participant count until this time
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    /// Blind poll: results show only the participant count while the event is open
+    pub blind_until_closed: Option<bool>,
+    /// Results show counts per slot instead of who answered what; only the
+    /// organizer view has names. Can't be changed later.
+    pub anonymous_results: Option<bool>,
+    /// Suggestions only include windows where every role can be filled
+    #[serde(default)]
+    pub roles: Vec<EventRole>,
+    /// Quorum for final slots; suggestions flag windows that meet it
+    #[serde(default)]
+    pub finalization_rules: FinalizationRules,
+    /// `http(s)` URL receiving signed callbacks on event activity
+    pub webhook_url: Option<String>,
+    /// Slack incoming webhook (`https://hooks.slack.com/...`) told about
+    /// responses, closing and finalizing
+    pub slack_webhook_url: Option<String>,
+    /// Discord channel webhook (`https://discord.com/api/webhooks/...`) told
+    /// about the same activity
+    pub discord_webhook_url: Option<String>,
+    /// `http(s)` URL receiving the same activity as plain, unsigned JSON
+    pub chat_webhook_url: Option<String>,
+    /// Passphrase participants must send to view, answer or read results
+    pub password: Option<String>,
+    /// Close the event as soon as this many participants are available in one cell
+    pub close_at_quorum: Option<i32>,
+    /// Gets service notices such as the warning before retention deletion;
+    /// never shown to participants
+    pub organizer_email: Option<String>,
+    /// Addresses of people expected to answer; `/remind` mails those that
+    /// haven't yet. Only the organizer view shows them
+    #[serde(default)]
+    pub invitees: Vec<String>,
+    /// Language of the event's notification mails; defaults to `en`
+    pub locale: Option<String>,
+    /// From `POST /organizers`; lists the event on that account's dashboard
+    pub organizer_account_token: Option<String>,
+    /// Days until the event is trashed, and what `/extend` adds again; 1 to
+    /// `limits.max_retention_days`, defaults to `limits.retention_days`
+    pub retention_days: Option<i32>,
+    /// Solved widget token; required when the deployment sets `CAPTCHA_PROVIDER`
+    #[serde(default)]
+    pub captcha_token: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Partial update; omitted fields are left unchanged.
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct UpdateEventRequest {
+    pub title: Option<String>,
+    /// An empty string clears the description
+    pub description: Option<String>,
+    pub time_zone: Option<String>,
+    pub slot_duration: Option<i32>,
+    /// Candidate ranges to add; merged with the existing slots
+    #[serde(default)]
+    pub add_slots: Vec<TimeRangeRequest>,
+    /// Candidate ranges to remove; overlapping availability is trimmed
+    #[serde(default)]
+    pub remove_slots: Vec<TimeRangeRequest>,
+    /// `add_slots` for weekly events
+    #[serde(default)]
+    pub add_weekly_slots: Vec<WeeklySlot>,
+    /// `remove_slots` for weekly events
+    #[serde(default)]
+    pub remove_weekly_slots: Vec<WeeklySlot>,
+    /// `add_slots` for date polls
+    #[serde(default)]
+    pub add_dates: Vec<NaiveDate>,
+    /// `remove_slots` for date polls
+    #[serde(default)]
+    pub remove_dates: Vec<NaiveDate>,
+    pub results_visibility: Option<ResultsVisibility>,
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    pub blind_until_closed: Option<bool>,
+    /// Replaces every role; participants holding a dropped role keep their
+    /// answer without a role
+    pub roles: Option<Vec<EventRole>>,
+    /// Replaces the rules; `{}` removes them
+    pub finalization_rules: Option<FinalizationRules>,
+    /// New passphrase; an empty string removes it
+    pub password: Option<String>,
+    /// 0 turns the automatic close off
+    pub close_at_quorum: Option<i32>,
+    /// An empty string clears it
+    pub organizer_email: Option<String>,
+    /// Mail the organizer before the event is deleted for retention
+    pub deletion_notice: Option<bool>,
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreationTicketResponse {
+    /// Send as `X-Creation-Ticket` on `POST /events` from the same origin
+    pub ticket: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateEventResponse {
     pub id: Uuid,
     pub public_token: String,
     pub organizer_token: String,
+    /// Unlocks results when `results_visibility` is `private`
+    pub results_token: String,
+    /// HMAC key for webhook signatures; `None` without a `webhook_url`
+    pub webhook_secret: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct EventSlot {
     pub id: i64,
     pub event_id: Uuid,
     pub start_at: DateTime<Utc>,
     pub end_at: DateTime<Utc>,
+    /// Closed by the organizer; new responses are clipped around it
+    pub locked: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateSlotRequest {
+    pub locked: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct EventResponse {
     pub id: Uuid,
     pub title: String,
     pub description: Option<String>,
     pub time_zone: Option<String>,
     pub slot_duration: i32,
-    pub state: String,
+    pub state: EventState,
     pub event_slots: Vec<EventSlot>,
+    pub slot_kind: SlotKind,
+    /// `event_slots` as weekdays and times of day; empty unless `weekly`
+    pub weekly_slots: Vec<WeeklySlot>,
+    pub poll_type: PollType,
+    /// Candidate dates of a `date` poll; empty otherwise
+    pub dates: Vec<NaiveDate>,
     pub organizer_name: String, // Computed field
+    pub results_visibility: ResultsVisibility,
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    pub blind_until_closed: bool,
+    /// Public results leave out names; see `CreateEventRequest::anonymous_results`
+    pub anonymous_results: bool,
+    /// Roles to pick from when responding; empty for an ordinary poll
+    pub roles: Vec<EventRole>,
+    pub finalization_rules: FinalizationRules,
+    /// Participants available in one cell that close the event automatically
+    pub close_at_quorum: Option<i32>,
+    /// Seconds until `reveal_results_at` by the server clock; 0 once passed,
+    /// null without one
+    pub seconds_until_deadline: Option<i64>,
+    /// When the retention job moves the event to the trash
+    pub expires_at: DateTime<Utc>,
+    /// Seconds until `expires_at` by the server clock; 0 once passed
+    pub seconds_until_expiry: i64,
+    /// Local renderings of the times above; null unless the request sets
+    /// `?tz=` or `X-Display-Timezone`
+    pub display: Option<DisplayTimes>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Times of an event response written out in the zone the client asked for
+/// (`Tue 2030-01-01 10:00–12:00 CET`), for clients that can't convert
+/// zones themselves.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DisplayTimes {
+    pub time_zone: String,
+    /// One per `event_slots` entry, in the same order
+    pub event_slots: Vec<String>,
+    /// One per `final_slots` entry; empty in the public view
+    pub final_slots: Vec<String>,
+    pub reveal_results_at: Option<String>,
+    pub expires_at: String,
+    /// `event_slots` on the zone's wall clock, in the same order
+    pub event_slot_ranges: Vec<LocalRange>,
+    /// `final_slots` on the zone's wall clock, in the same order
+    pub final_slot_ranges: Vec<LocalRange>,
+}
+
+/// A range on the wall clock of the zone a client asked for.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LocalRange {
+    /// With the zone's UTC offset at that moment (`2030-07-01T14:00:00+02:00`),
+    /// so the date and time read as local while the instant stays exact
+    pub start_at: DateTime<FixedOffset>,
+    pub end_at: DateTime<FixedOffset>,
+    /// `Mon 2030-07-01 14:00–16:00 CEST`
+    pub label: String,
+}
+
+/// Zone to render times in; overrides `X-Display-Timezone`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct DisplayQuery {
+    /// IANA zone, e.g. `Europe/Paris`
+    pub tz: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SubmitAvailabilityRequest {
+    /// Token from an earlier submission; when set, that response is replaced
+    /// instead of creating a new participant. Names never identify participants.
+    #[serde(default)]
+    pub participant_token: Option<Uuid>,
     pub participant_name: String,
+    #[serde(default)]
     pub availabilities: Vec<TimeRangeRequest>,
+    /// Whole days ticked in a date poll, added to `availabilities`
+    #[serde(default)]
+    pub dates: Vec<NaiveDate>,
     pub comment: Option<String>,
+    /// Minutes needed free before and after a meeting (default 0)
+    #[serde(default)]
+    pub buffer_minutes: Option<i32>,
+    /// IANA zone the participant views the poll in
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    /// Address for the calendar invitation and reminders, used once confirmed
+    /// through the mailed link; never shown publicly
+    #[serde(default)]
+    pub email: Option<String>,
+    /// One of the event's `roles`
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Event passphrase, for clients that cannot set the `X-Event-Password` header
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Solved widget token; required when the deployment sets `CAPTCHA_PROVIDER`
+    #[serde(default)]
+    pub captcha_token: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SubmitAvailabilityResponse {
     pub participant_token: Uuid,
+    /// `pending` when the event was full and the organizer has to approve
+    pub status: ParticipantStatus,
+}
+
+/// Outcome of one entry of a batch submission.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchSubmissionResult {
+    /// Position of the entry in the request
+    pub index: usize,
+    /// Set when the entry was stored
+    pub participant_token: Option<Uuid>,
+    pub status: Option<ParticipantStatus>,
+    /// Why the entry was skipped, as a single submission would have answered
+    pub error: Option<ErrorResponse>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchSubmitAvailabilityResponse {
+    /// One per entry, in request order
+    pub results: Vec<BatchSubmissionResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ParticipantResponse {
     pub participant_token: Uuid,
     pub name: String,
     pub comment: Option<String>,
+    pub buffer_minutes: i32,
+    pub time_zone: Option<String>,
     pub availabilities: Vec<TimeRangeRequest>,
+    pub email: Option<String>,
+    /// Latest calendar reply: `accepted`, `declined` or `tentative`
+    pub rsvp_status: Option<String>,
+    pub role: Option<String>,
+    pub status: ParticipantStatus,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateParticipantRequest {
     pub participant_name: String,
+    #[serde(default)]
     pub availabilities: Vec<TimeRangeRequest>,
+    /// Whole days ticked in a date poll, added to `availabilities`
+    #[serde(default)]
+    pub dates: Vec<NaiveDate>,
     pub comment: Option<String>,
+    #[serde(default)]
+    pub buffer_minutes: Option<i32>,
+    /// IANA zone the participant views the poll in
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    /// Address for the calendar invitation and reminders; a new one has to be
+    /// confirmed again. Never shown publicly
+    #[serde(default)]
+    pub email: Option<String>,
+    /// One of the event's `roles`
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct VerifyEmailQuery {
+    /// Token from the mailed link
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EmailVerifiedResponse {
+    pub email: String,
+    /// The event the address was left for
+    pub title: String,
+    pub public_token: String,
+}
+
+/// An event slot expressed as calendar dates in one participant's zone.
+/// `start_date != end_date` when the slot crosses local midnight.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SlotLocalDates {
+    pub slot_id: i64,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ParticipantAvailability {
+    /// Identifies the participant in organizer endpoints such as the
+    /// availability history
+    pub id: i64,
     pub name: String,
     pub is_organizer: bool, // Add this to help frontend identify organizer
     pub comment: Option<String>,
+    pub buffer_minutes: i32,
     pub availabilities: Vec<TimeRangeRequest>,
+    pub time_zone: Option<String>,
+    /// Local dates each event slot covers in this participant's zone
+    pub slot_dates: Vec<SlotLocalDates>,
+    /// Latest calendar reply: `accepted`, `declined` or `tentative`
+    pub rsvp_status: Option<String>,
+    /// Role picked when responding
+    pub role: Option<String>,
+    /// Organizer view only: whether the participant confirmed the address
+    /// they left; absent when they left none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+    /// Position the organizer pinned the participant at, from 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_order: Option<i32>,
+    /// Organizer view only: left out of public results, suggestions and
+    /// summaries
+    #[serde(default)]
+    pub is_hidden: bool,
+}
+
+/// The organizer's display preferences for the participant list. Each call
+/// replaces the previous ones.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ParticipantOrderRequest {
+    /// Participant ids pinned to the top in this order; everyone else
+    /// follows by arrival
+    #[serde(default)]
+    pub order: Vec<i64>,
+    /// Participant ids to keep out of public results; their answers stay
+    /// stored and visible to the organizer
+    #[serde(default)]
+    pub hidden: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ParticipantOrderResponse {
+    /// Confirmed participants in display order
+    pub participants: Vec<ParticipantDisplay>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ParticipantDisplay {
+    pub id: i64,
+    pub name: String,
+    pub display_order: Option<i32>,
+    pub is_hidden: bool,
+}
+
+/// One saved state of a participant's availability.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct AvailabilityVersion {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    /// `participant` for their own edits, `organizer` when slot changes trimmed it
+    pub source: String,
+    pub availabilities: Vec<TimeRangeRequest>,
+    /// Compared with the previous version; everything for the first one
+    pub added: Vec<TimeRangeRequest>,
+    pub removed: Vec<TimeRangeRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AvailabilityHistoryResponse {
+    pub participant_name: String,
+    /// Newest first
+    pub versions: Vec<AvailabilityVersion>,
+}
+
+/// Something that happened to an event.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuditEntry {
+    pub id: i64,
+    /// e.g. `created`, `availability_submitted`, `participant_updated`,
+    /// `closed`, `finalized`, `extended`
+    pub action: String,
+    /// `organizer`, `system`, `quorum`, or the participant's name
+    pub actor: String,
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuditLogResponse {
+    /// Newest first
+    pub entries: Vec<AuditEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateCommentRequest {
+    /// Returned when submitting availability; the comment is signed with
+    /// that participant's name
+    pub participant_token: Uuid,
+    pub body: String,
+}
+
+/// One message in an event's discussion.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EventComment {
+    pub id: i64,
+    /// The author's current name, so renaming also renames their comments
+    pub author_name: String,
+    pub is_organizer: bool,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CommentListResponse {
+    /// Oldest first
+    pub comments: Vec<EventComment>,
+    /// Comments across all pages
+    pub total: i64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CommentsQuery {
+    /// Page size (default 50, at most 200)
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Someone who submitted after the event was full.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WaitlistEntry {
+    pub id: i64,
+    pub name: String,
+    pub comment: Option<String>,
+    pub role: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WaitlistResponse {
+    /// Confirmed participants, organizer included
+    pub confirmed: i64,
+    /// Submissions beyond this are put on the waitlist
+    pub max_participants: i64,
+    /// Oldest first
+    pub pending: Vec<WaitlistEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationDecision {
+    Approve,
+    Reject,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ModerateParticipantRequest {
+    pub decision: ModerationDecision,
+    /// With `approve`: a confirmed participant who dropped out, removed to
+    /// make room. Without it, approving raises the event past the cap.
+    #[serde(default)]
+    pub replaces: Option<i64>,
+}
+
+/// A time the organizer committed to when finalizing the event.
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
+pub struct FinalSlot {
+    pub id: i64,
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FinalizeEventRequest {
+    pub slots: Vec<TimeRangeRequest>,
+    /// Finalize even where a slot misses the event's `finalization_rules`
+    #[serde(default)]
+    pub force: bool,
+    /// Mail a calendar invitation to every participant who confirmed an address;
+    /// defaults to `true`. Needs `MAIL_FROM` and a date event.
+    pub send_invitations: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FinalizeEventResponse {
+    pub state: EventState,
+    pub final_slots: Vec<FinalSlot>,
+    /// Invitations put in the mail outbox by this finalize; delivery is
+    /// reported by `GET /events/{organizer_token}/invitations`
+    pub invitations_queued: usize,
+}
+
+/// Where a participant's latest calendar invitation stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InvitationStatus {
+    /// None was sent: mail is off, the event was finalized without
+    /// invitations, or the address came in afterwards
+    NotSent,
+    /// In the outbox, possibly after failed attempts
+    Queued,
+    Sent,
+    /// Given up after `MAX_ATTEMPTS` failed attempts
+    Failed,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InvitationDelivery {
+    pub participant_id: i64,
+    pub participant_name: String,
+    pub email: String,
+    pub status: InvitationStatus,
+    pub attempts: i32,
+    /// Why the last attempt failed
+    pub last_error: Option<String>,
+    pub queued_at: Option<DateTime<Utc>>,
+    pub sent_at: Option<DateTime<Utc>>,
+    /// Latest calendar reply: `accepted`, `declined` or `tentative`
+    pub rsvp_status: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InvitationListResponse {
+    /// Finalize count; invitations carry it as their `SEQUENCE`
+    pub revision: i32,
+    /// Confirmed participants with a confirmed address, in order of answering
+    pub invitations: Vec<InvitationDelivery>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct AssignRequest {
+    /// Attendees per slot; omitted means no limit
+    pub capacity: Option<i32>,
+    /// Slots each participant attends (default 1)
+    pub sessions_per_participant: Option<i32>,
+}
+
+/// The organizer's edited assignment; slots left out end up empty.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateAssignmentRequest {
+    pub slots: Vec<SlotAssignmentInput>,
+    /// Lock the assignment in; it can no longer be changed afterwards
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SlotAssignmentInput {
+    pub final_slot_id: i64,
+    pub participant_ids: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AssignmentResponse {
+    /// `None` while the assignment is still a draft
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub slots: Vec<SlotAssignment>,
+    /// Participants not attending any slot
+    pub unassigned: Vec<AssignmentParticipant>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SlotAssignment {
+    pub final_slot_id: i64,
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    pub attendees: Vec<AssignedParticipant>,
+    /// Staffing of each event role among the attendees
+    pub roles: Vec<RoleCoverage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AssignmentParticipant {
+    pub participant_id: i64,
+    pub name: String,
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AssignedParticipant {
+    pub participant_id: i64,
+    pub name: String,
+    pub role: Option<String>,
+    /// How well the participant can make the slot; edits may assign
+    /// someone who is `unavailable`
+    pub availability_level: AvailabilityLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct EventResultsResponse {
     pub id: Uuid,
     pub title: String,
     pub description: Option<String>,
     pub time_zone: Option<String>,
     pub slot_duration: i32,
-    pub state: String,
+    pub state: EventState,
     pub event_slots: Vec<EventSlot>,
+    pub slot_kind: SlotKind,
+    /// See [`EventResponse::weekly_slots`]
+    pub weekly_slots: Vec<WeeklySlot>,
+    pub poll_type: PollType,
+    /// See [`EventResponse::dates`]
+    pub dates: Vec<NaiveDate>,
+    /// Empty until the organizer finalizes
+    pub final_slots: Vec<FinalSlot>,
+    /// Empty while `results_hidden` and for `anonymous_results` events
     pub participants: Vec<ParticipantAvailability>,
+    /// One per `dates` entry; empty while `results_hidden`. Names read
+    /// "Anonymous" for `anonymous_results` events.
+    pub date_votes: Vec<DateVotes>,
+    /// Per `slot_duration` cell someone marked, in time order; empty while
+    /// `results_hidden`
+    pub slot_counts: Vec<SlotCount>,
     pub total_participants: i64,
+    /// Blind poll not yet revealed: only `total_participants` is filled in
+    pub results_hidden: bool,
+    pub anonymous_results: bool,
+    /// See [`EventResponse::display`]
+    pub display: Option<DisplayTimes>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct OrganizerEventResponse {
     pub id: Uuid,
     pub public_token: String,
@@ -118,19 +1023,436 @@ pub struct OrganizerEventResponse {
     pub description: Option<String>,
     pub time_zone: Option<String>,
     pub slot_duration: i32,
-    pub state: String,
+    pub state: EventState,
     pub event_slots: Vec<EventSlot>,
+    pub slot_kind: SlotKind,
+    /// See [`EventResponse::weekly_slots`]
+    pub weekly_slots: Vec<WeeklySlot>,
+    pub poll_type: PollType,
+    /// See [`EventResponse::dates`]
+    pub dates: Vec<NaiveDate>,
+    /// Empty until the organizer finalizes
+    pub final_slots: Vec<FinalSlot>,
     pub participants: Vec<ParticipantAvailability>,
+    /// See [`EventResultsResponse::date_votes`]
+    pub date_votes: Vec<DateVotes>,
     pub total_participants: i64,
+    /// Co-host currently editing slots; `None` when no unexpired lease exists
+    pub edit_lock: Option<EditLock>,
+    pub results_visibility: ResultsVisibility,
+    pub results_token: String,
+    pub reveal_results_at: Option<DateTime<Utc>>,
+    pub blind_until_closed: bool,
+    pub anonymous_results: bool,
+    pub roles: Vec<EventRole>,
+    pub finalization_rules: FinalizationRules,
+    pub close_at_quorum: Option<i32>,
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+    pub slack_webhook_url: Option<String>,
+    pub discord_webhook_url: Option<String>,
+    pub chat_webhook_url: Option<String>,
+    /// Participants need the event passphrase
+    pub password_protected: bool,
+    pub organizer_email: Option<String>,
+    /// Addresses registered on creation; `/remind` mails those that haven't answered
+    pub invitees: Vec<String>,
+    /// Whether `organizer_email` is warned before retention deletion
+    pub deletion_notice: bool,
+    /// Chosen at creation; `/extend` keeps the event this long from then
+    pub retention_days: i32,
+    /// Language of notification mails
+    pub locale: String,
     pub created_at: DateTime<Utc>,
+    /// See [`EventResponse::seconds_until_deadline`]
+    pub seconds_until_deadline: Option<i64>,
+    pub expires_at: DateTime<Utc>,
+    pub seconds_until_expiry: i64,
+    /// See [`EventResponse::display`]
+    pub display: Option<DisplayTimes>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchCheckStatusRequest {
     pub tokens: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchCheckStatusResponse {
-    pub statuses: std::collections::HashMap<String, String>,
+    pub statuses: std::collections::HashMap<String, EventState>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchCheckParticipantsRequest {
+    pub tokens: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ParticipantTokenStatus {
+    pub public_token: String,
+    pub name: String,
+    pub state: EventState,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchCheckParticipantsResponse {
+    /// Keyed by participant token; unknown or malformed tokens are omitted
+    pub participants: std::collections::HashMap<String, ParticipantTokenStatus>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ParticipantAggregateQuery {
+    /// Comma-separated participant tokens, at most 50; unknown ones are skipped
+    pub tokens: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AggregatedParticipation {
+    pub participant_token: String,
+    pub public_token: String,
+    pub title: String,
+    pub name: String,
+    pub state: EventState,
+    pub availabilities: Vec<TimeRangeRequest>,
+    pub final_slots: Vec<FinalSlot>,
+}
+
+/// A span where the participant offered the same time in several polls.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AvailabilityOverlap {
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    /// Public tokens of the polls offered this span
+    pub public_tokens: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ParticipantAggregateResponse {
+    /// In request order; weekly polls are left out as their slots have no dates
+    pub events: Vec<AggregatedParticipation>,
+    /// Times offered in any poll, minus the busy ones
+    pub free: Vec<TimeRangeRequest>,
+    /// Final slots of the polls, merged
+    pub busy: Vec<TimeRangeRequest>,
+    /// Times offered in more than one poll, which can only go to one of them
+    pub overlaps: Vec<AvailabilityOverlap>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ConflictCheckRequest {
+    /// Organizer tokens of the events to plan together; unknown ones are skipped
+    pub organizer_tokens: Vec<String>,
+    /// Candidates considered per undecided event (default 5)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ConflictCheckResponse {
+    /// In request order; archived events are left out
+    pub events: Vec<EventConflicts>,
+    /// Whether the recommended candidates avoid double-booking; `false`
+    /// when no combination does, and then nothing is recommended
+    pub compatible: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventConflicts {
+    pub public_token: String,
+    pub title: String,
+    pub state: EventState,
+    /// Times a finalized event is committed to; candidates must avoid them
+    pub final_slots: Vec<FinalSlot>,
+    /// Best first; empty once finalized
+    pub candidates: Vec<CandidateConflicts>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CandidateConflicts {
+    pub suggestion: SlotSuggestion,
+    /// Candidates and final slots of the other events this one overlaps
+    pub conflicts: Vec<ConflictingTime>,
+    /// Part of the best combination that double-books nothing
+    pub recommended: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ConflictingTime {
+    pub public_token: String,
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    /// A final slot rather than a candidate
+    pub finalized: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ArchiveEventResponse {
+    pub state: EventState,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RemindParticipantsResponse {
+    pub queued: usize,
+    /// Names of the reminded participants; addresses are never returned
+    pub recipients: Vec<String>,
+    /// Registered invitees among `queued` who haven't answered yet
+    pub invitees: usize,
+}
+
+/// One organizer-triggered reminder batch.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReminderBatch {
+    pub sent_at: DateTime<Utc>,
+    /// Mails queued, invitees included
+    pub recipient_count: i32,
+    pub invitee_count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReminderLogResponse {
+    /// Newest first
+    pub reminders: Vec<ReminderBatch>,
+    /// When `/remind` may send again; `None` when it may now
+    pub next_reminder_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TransferOwnershipResponse {
+    /// One-time token for the claim link; share it with the new organizer
+    pub claim_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct ClaimOwnershipRequest {
+    /// Renames the organizer participant to the new owner
+    pub organizer_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ClaimOwnershipResponse {
+    pub public_token: String,
+    /// Freshly minted; the previous organizer token stops working
+    pub organizer_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct CloneEventRequest {
+    /// Calendar days to move every candidate slot by, keeping local times
+    /// in the event's zone; may be negative. Not allowed for weekly events.
+    pub offset_days: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateTemplateRequest {
+    /// The event whose structure is saved
+    pub organizer_token: String,
+    pub name: String,
+}
+
+/// What a template keeps of its event: title, settings and candidate
+/// times. Participants, passwords, webhooks and contact addresses are left
+/// behind.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TemplateStructure {
+    pub title: String,
+    pub description: Option<String>,
+    pub time_zone: Option<String>,
+    pub slot_duration: i32,
+    pub slot_kind: SlotKind,
+    pub poll_type: PollType,
+    /// Candidate ranges of a `datetime` poll on dates
+    #[serde(default)]
+    pub time_slots: Vec<TimeRangeRequest>,
+    /// Candidate windows of a `weekly` event
+    #[serde(default)]
+    pub weekly_slots: Vec<WeeklySlot>,
+    /// Candidate days of a `date` poll
+    #[serde(default)]
+    pub dates: Vec<NaiveDate>,
+    pub results_visibility: ResultsVisibility,
+    pub blind_until_closed: bool,
+    pub anonymous_results: bool,
+    #[serde(default)]
+    pub roles: Vec<EventRole>,
+    #[serde(default)]
+    pub finalization_rules: FinalizationRules,
+    pub close_at_quorum: Option<i32>,
+    pub locale: String,
+    pub retention_days: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TemplateResponse {
+    /// Share to let others create events from the template
+    pub template_token: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub structure: TemplateStructure,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateEventFromTemplateRequest {
+    pub organizer_name: String,
+    /// Calendar days to move the candidate times by, keeping local times in
+    /// the template's zone; may be negative. Not allowed for weekly events.
+    pub offset_days: Option<i32>,
+    /// Solved widget token; required when the deployment sets `CAPTCHA_PROVIDER`
+    #[serde(default)]
+    pub captcha_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct ExtendEventRequest {
+    /// Pushes the current expiry out by this many days, 1 to
+    /// `limits.max_retention_days`; the result is capped at that many days
+    /// from now
+    pub days: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AcquireEditLockRequest {
+    pub holder_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EditLock {
+    pub holder_name: String,
+    pub acquired_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SuggestionsQuery {
+    /// Number of windows to return (default 5, max 20)
+    pub limit: Option<usize>,
+    /// Required when the event's results are private
+    pub results_token: Option<String>,
+    /// Caller's participant token, for `after_submit` results
+    pub participant_token: Option<Uuid>,
+    /// IANA zone to add wall-clock times in; overrides `X-Display-Timezone`
+    pub tz: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ResultsQuery {
+    /// Required when the event's results are private
+    pub results_token: Option<String>,
+    /// Caller's participant token, for `after_submit` results
+    pub participant_token: Option<Uuid>,
+    /// IANA zone to render `display` in; overrides `X-Display-Timezone`
+    pub tz: Option<String>,
+}
+
+/// The passphrase for `/ws`, whose handshake browsers can't add headers to.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SocketQuery {
+    /// Event password when the event has one; `X-Event-Password` works too
+    pub password: Option<String>,
+}
+
+/// A window of consecutive `slot_duration` cells shared by the same participants.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SlotSuggestion {
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    pub count: usize,
+    /// Names available for the whole window, organizer first
+    pub participants: Vec<String>,
+    /// Names available for the whole window only if need be
+    pub if_need_be: Vec<String>,
+    pub missing: Vec<String>,
+    pub organizer_available: bool,
+    /// Ranking key: 2 per available participant, 1 per "if need be"
+    pub score: usize,
+    /// Who fills each of the event's roles; empty when it has none
+    pub roles: Vec<RoleCoverage>,
+    /// The window satisfies the event's `finalization_rules`; always true
+    /// without rules
+    pub meets_rules: bool,
+    /// The window in the zone from `?tz=` or `X-Display-Timezone`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<LocalRange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RoleCoverage {
+    pub role: String,
+    pub required: usize,
+    /// Participants with the role available (or if need be) for the whole window
+    pub filled: Vec<String>,
+}
+
+impl From<agreed_time_core::Suggestion> for SlotSuggestion {
+    fn from(suggestion: agreed_time_core::Suggestion) -> Self {
+        SlotSuggestion {
+            start_at: suggestion.start_at,
+            end_at: suggestion.end_at,
+            count: suggestion.count,
+            participants: suggestion.explanation.available,
+            if_need_be: suggestion.explanation.if_need_be,
+            missing: suggestion.explanation.missing,
+            organizer_available: suggestion.explanation.score.organizer_available,
+            score: suggestion.explanation.score.score,
+            roles: suggestion
+                .explanation
+                .roles
+                .into_iter()
+                .map(|coverage| RoleCoverage {
+                    role: coverage.role,
+                    required: coverage.required,
+                    filled: coverage.filled,
+                })
+                .collect(),
+            meets_rules: true,
+            display: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventSuggestionsResponse {
+    pub slot_duration: i32,
+    /// Best first: most participants, then earliest, then shortest
+    pub suggestions: Vec<SlotSuggestion>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryFormat {
+    Markdown,
+    #[default]
+    Json,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SummaryQuery {
+    /// `json` (default) or `markdown`
+    #[serde(default)]
+    #[param(inline)]
+    pub format: SummaryFormat,
+    /// Required when the event's results are private
+    pub results_token: Option<String>,
+    /// Caller's participant token, for `after_submit` results
+    pub participant_token: Option<Uuid>,
+}
+
+/// Compact digest of an event for assistants and bots relaying poll status.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventSummary {
+    pub title: String,
+    pub state: EventState,
+    pub time_zone: Option<String>,
+    pub slot_duration: i32,
+    /// Candidate ranges offered by the organizer
+    pub slots: Vec<TimeRangeRequest>,
+    /// Empty until the organizer finalizes
+    pub final_slots: Vec<TimeRangeRequest>,
+    /// Top 3 windows, same ranking as `/suggestions`
+    pub best_candidates: Vec<SlotSuggestion>,
+    pub respondents: Vec<String>,
+    /// Respondents who submitted no available or "if need be" time at all
+    pub without_availability: Vec<String>,
+    /// Blind poll not yet revealed: candidates and respondents are left empty
+    pub results_hidden: bool,
 }
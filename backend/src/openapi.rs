@@ -0,0 +1,233 @@
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
+
+use crate::{
+    db,
+    error::{ErrorResponse, FieldError},
+    handlers, models, realtime,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "AgreedTime API",
+        description = "Group scheduling by capability links"
+    ),
+    paths(
+        handlers::health::health_check,
+        handlers::health::liveness,
+        handlers::health::readiness,
+        handlers::health::server_time,
+        handlers::capabilities::get_capabilities,
+        handlers::timezones::list_time_zones,
+        handlers::events::create_event,
+        handlers::events::issue_creation_ticket,
+        handlers::events::check_events_status,
+        handlers::events::check_event_conflicts,
+        handlers::events::get_event,
+        handlers::events::submit_availability,
+        handlers::events::submit_availability_batch,
+        handlers::events::get_event_results,
+        handlers::events::update_event,
+        handlers::events::delete_event,
+        handlers::events::restore_event,
+        handlers::events::clone_event,
+        handlers::events::extend_event,
+        handlers::events::close_event,
+        handlers::events::remind_participants,
+        handlers::events::list_reminders,
+        handlers::events::finalize_event,
+        handlers::events::list_invitations,
+        handlers::realtime::event_socket,
+        handlers::assignments::get_assignment,
+        handlers::assignments::propose_assignment,
+        handlers::assignments::update_assignment,
+        handlers::events::get_event_suggestions,
+        handlers::events::get_event_summary,
+        handlers::events::export_event_ics,
+        handlers::events::archive_event,
+        handlers::events::acquire_edit_lock,
+        handlers::events::release_edit_lock,
+        handlers::events::transfer_ownership,
+        handlers::events::claim_ownership,
+        handlers::events::get_organizer_event,
+        handlers::events::get_participant,
+        handlers::events::update_participant,
+        handlers::events::verify_participant_email,
+        handlers::events::get_participant_history,
+        handlers::events::get_event_audit,
+        handlers::comments::create_comment,
+        handlers::comments::list_comments,
+        handlers::templates::create_template,
+        handlers::templates::get_template,
+        handlers::templates::create_event_from_template,
+        handlers::events::get_waitlist,
+        handlers::events::moderate_participant,
+        handlers::events::delete_participant,
+        handlers::events::update_participant_order,
+        handlers::events::update_slot,
+        handlers::events::check_participants_status,
+        handlers::events::aggregate_participant_availability,
+        handlers::admin::list_events,
+        handlers::admin::search_events,
+        handlers::admin::force_delete_event,
+        handlers::admin::rate_limits,
+        handlers::admin::run_cleanup,
+        handlers::admin::stats,
+        handlers::admin::list_bans,
+        handlers::admin::create_ban,
+        handlers::admin::delete_ban,
+        handlers::admin::business_metrics,
+        handlers::admin::job_runs,
+        handlers::admin::preview_mail_template,
+        handlers::integrations::itip_reply,
+        handlers::integrations::caldav_free_busy,
+        handlers::demo::reset_demo,
+        handlers::organizers::create_organizer_account,
+        handlers::organizers::list_organizer_events,
+        handlers::auth::request_magic_link,
+        handlers::auth::verify_magic_link,
+        handlers::auth::get_current_user,
+        handlers::auth::logout,
+        handlers::auth::issue_token,
+        handlers::auth::list_user_events,
+    ),
+    components(schemas(
+        ErrorResponse,
+        FieldError,
+        handlers::health::HealthResponse,
+        handlers::health::ReadinessResponse,
+        db::PoolStats,
+        handlers::health::TimeResponse,
+        handlers::capabilities::CapabilitiesResponse,
+        handlers::capabilities::Limits,
+        handlers::capabilities::Integrations,
+        handlers::capabilities::Deprecation,
+        handlers::timezones::TimeZoneInfo,
+        handlers::timezones::TimeZonesResponse,
+        models::TimeRangeRequest,
+        models::WeeklySlot,
+        models::CreateEventRequest,
+        models::UpdateEventRequest,
+        models::UpdateSlotRequest,
+        models::CreateEventResponse,
+        models::CreationTicketResponse,
+        models::EventSlot,
+        models::EventResponse,
+        models::SubmitAvailabilityRequest,
+        models::SubmitAvailabilityResponse,
+        models::BatchSubmissionResult,
+        models::BatchSubmitAvailabilityResponse,
+        models::ParticipantResponse,
+        models::UpdateParticipantRequest,
+        models::EmailVerifiedResponse,
+        models::SlotLocalDates,
+        models::DisplayTimes,
+        models::LocalRange,
+        models::ParticipantAvailability,
+        models::ParticipantDisplay,
+        models::ParticipantOrderRequest,
+        models::ParticipantOrderResponse,
+        models::AvailabilityVersion,
+        models::AvailabilityHistoryResponse,
+        models::SlotCount,
+        models::AuditEntry,
+        models::AuditLogResponse,
+        models::CreateCommentRequest,
+        models::EventComment,
+        models::CommentListResponse,
+        models::ParticipantStatus,
+        models::EventState,
+        models::WaitlistEntry,
+        models::WaitlistResponse,
+        models::ModerationDecision,
+        models::ModerateParticipantRequest,
+        models::FinalSlot,
+        models::FinalizeEventRequest,
+        models::FinalizeEventResponse,
+        models::InvitationStatus,
+        models::InvitationDelivery,
+        models::InvitationListResponse,
+        models::ConflictCheckRequest,
+        models::ConflictCheckResponse,
+        models::EventConflicts,
+        models::CandidateConflicts,
+        models::ConflictingTime,
+        realtime::RealtimeMessage,
+        models::AssignRequest,
+        models::UpdateAssignmentRequest,
+        models::SlotAssignmentInput,
+        models::AssignmentResponse,
+        models::SlotAssignment,
+        models::AssignmentParticipant,
+        models::AssignedParticipant,
+        models::EventResultsResponse,
+        models::ArchiveEventResponse,
+        models::RemindParticipantsResponse,
+        models::ReminderBatch,
+        models::ReminderLogResponse,
+        models::SlotSuggestion,
+        models::EventSuggestionsResponse,
+        models::EventSummary,
+        models::CloneEventRequest,
+        models::CreateTemplateRequest,
+        models::TemplateStructure,
+        models::TemplateResponse,
+        models::CreateEventFromTemplateRequest,
+        models::ExtendEventRequest,
+        models::AcquireEditLockRequest,
+        models::EditLock,
+        models::TransferOwnershipResponse,
+        models::ClaimOwnershipRequest,
+        models::ClaimOwnershipResponse,
+        models::OrganizerEventResponse,
+        models::BatchCheckStatusRequest,
+        models::BatchCheckStatusResponse,
+        models::BatchCheckParticipantsRequest,
+        models::ParticipantTokenStatus,
+        models::BatchCheckParticipantsResponse,
+        models::AggregatedParticipation,
+        models::AvailabilityOverlap,
+        models::ParticipantAggregateResponse,
+        handlers::admin::AdminEventSummary,
+        handlers::admin::AdminEventSearchResponse,
+        handlers::admin::AdminEventListResponse,
+        handlers::admin::AdminRouteLimit,
+        handlers::admin::AdminRateLimitsResponse,
+        handlers::admin::AdminCleanupResponse,
+        handlers::admin::AdminStatsResponse,
+        handlers::admin::AdminBan,
+        handlers::admin::AdminBansResponse,
+        handlers::admin::CreateBanRequest,
+        handlers::admin::AdminJobRun,
+        handlers::admin::AdminJobStatus,
+        handlers::admin::AdminJobsResponse,
+        handlers::admin::AdminMailPreview,
+        handlers::integrations::ItipReplyResponse,
+        handlers::integrations::CaldavFreeBusyRequest,
+        handlers::integrations::CaldavFreeBusyResponse,
+        handlers::demo::DemoResetResponse,
+        handlers::organizers::OrganizerAccountResponse,
+        handlers::organizers::OrganizerEventSummary,
+        handlers::organizers::OrganizerEventsResponse,
+        handlers::auth::MagicLinkRequest,
+        handlers::auth::UserResponse,
+        handlers::auth::TokenResponse,
+    )),
+    modifiers(&SecuritySchemes)
+)]
+pub struct ApiDoc;
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "admin_key",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Event passphrases.
+//!
+//! Stored as argon2id PHC strings (algorithm, parameters and salt included),
+//! so the parameters can change later without invalidating existing hashes.
+
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+
+/// Header carrying the passphrase on participant requests.
+pub const HEADER: &str = "x-event-password";
+
+pub const MAX_LENGTH: usize = 128;
+
+pub fn hash(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("argon2 with default parameters hashes any input")
+        .to_string()
+}
+
+/// False for a wrong password and for a hash that doesn't parse.
+pub fn verify(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|parsed| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_round_trip() {
+        let hashed = hash("correct horse");
+        assert!(hashed.starts_with("$argon2id$"));
+        assert!(verify("correct horse", &hashed));
+        assert!(!verify("battery staple", &hashed));
+        assert!(!verify("correct horse", "not a hash"));
+        assert_ne!(hashed, hash("correct horse"));
+    }
+}
@@ -0,0 +1,90 @@
+//! Live updates for everyone looking at the same event grid.
+//!
+//! [`EventHub`] keeps one broadcast channel per event while at least one
+//! socket is connected. Handlers publish after their transaction commits;
+//! nothing is stored, so a socket that falls behind skips what it missed and
+//! clients refetch results when they reconnect.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::{EventComment, TimeRangeRequest};
+
+/// Messages buffered per event before slow sockets start skipping.
+pub const CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RealtimeMessage {
+    /// A participant saved their availability; replaces their previous ranges
+    Availability {
+        participant_name: String,
+        role: Option<String>,
+        availabilities: Vec<TimeRangeRequest>,
+    },
+    /// The organizer removed a participant along with their ranges
+    ParticipantRemoved { participant_name: String },
+    /// Someone posted to the event's discussion
+    Comment(EventComment),
+    /// Someone started or stopped filling in the grid. The only message
+    /// clients may send.
+    Presence { name: String, editing: bool },
+}
+
+#[derive(Default)]
+pub struct EventHub {
+    channels: Mutex<HashMap<Uuid, broadcast::Sender<RealtimeMessage>>>,
+}
+
+pub type SharedHub = Arc<EventHub>;
+
+impl EventHub {
+    pub fn subscribe(&self, event_id: Uuid) -> broadcast::Receiver<RealtimeMessage> {
+        self.channels
+            .lock()
+            .unwrap()
+            .entry(event_id)
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Sends to every socket on the event; a no-op when nobody is connected.
+    pub fn publish(&self, event_id: Uuid, message: RealtimeMessage) {
+        if let Some(sender) = self.channels.lock().unwrap().get(&event_id) {
+            // Only fails when the last receiver went away in the meantime
+            let _ = sender.send(message);
+        }
+    }
+
+    /// Drops the event's channel once its last socket has gone; call after
+    /// dropping the receiver.
+    pub fn release(&self, event_id: Uuid) {
+        let mut channels = self.channels.lock().unwrap();
+        if channels
+            .get(&event_id)
+            .is_some_and(|sender| sender.receiver_count() == 0)
+        {
+            channels.remove(&event_id);
+        }
+    }
+
+    /// Drops every channel, so each socket sees its updates end and closes.
+    /// Used on shutdown, since upgraded connections outlive the server.
+    pub fn close_all(&self) {
+        self.channels.lock().unwrap().clear();
+    }
+
+    pub fn connections(&self, event_id: Uuid) -> usize {
+        self.channels
+            .lock()
+            .unwrap()
+            .get(&event_id)
+            .map_or(0, |sender| sender.receiver_count())
+    }
+}
@@ -1,24 +1,129 @@
 use axum::{
     Router,
-    routing::{get, post},
+    middleware::{from_fn_with_state, map_response},
+    routing::{delete, get, patch, post, put},
 };
 use sqlx::PgPool;
 
-use crate::handlers;
+use crate::{
+    auth::JwtLayer,
+    config::Plane,
+    handlers,
+    middleware::{demo_watermark, require_admin_key},
+    state::AppState,
+};
 
 pub fn create_router(pool: PgPool) -> Router {
+    create_router_with_state(AppState::new(pool))
+}
+
+pub fn create_router_with_state(state: AppState) -> Router {
+    create_router_for(Plane::All, state)
+}
+
+/// Routes served by a listener on `plane`.
+pub fn create_router_for(plane: Plane, state: AppState) -> Router {
+    // Admin routes keep their own bearer key and never see user tokens
+    let jwt = JwtLayer::new(state.config.jwt_secret.as_deref(), state.clock.clone());
+    let router = match plane {
+        Plane::All => public_router().layer(jwt).merge(admin_router(&state)),
+        Plane::Public => public_router().layer(jwt),
+        Plane::Internal => admin_router(&state).merge(probe_router()),
+    };
+    let router = if state.config.demo_mode {
+        router.layer(map_response(demo_watermark))
+    } else {
+        router
+    };
+    router.with_state(state)
+}
+
+/// Health checks and API docs, served on every plane.
+fn probe_router() -> Router<AppState> {
     Router::new()
         .route("/health", get(handlers::health::health_check))
+        .route("/health/live", get(handlers::health::liveness))
+        .route("/health/ready", get(handlers::health::readiness))
+        .route("/time", get(handlers::health::server_time))
+        .route("/openapi.json", get(handlers::docs::openapi_spec))
+        .route("/docs", get(handlers::docs::swagger_ui))
+}
+
+/// Everything browsers and API clients use.
+pub fn public_router() -> Router<AppState> {
+    probe_router()
+        .route(
+            "/capabilities",
+            get(handlers::capabilities::get_capabilities),
+        )
+        .route("/timezones", get(handlers::timezones::list_time_zones))
         .route("/events", post(handlers::events::create_event))
+        .route(
+            "/events/ticket",
+            post(handlers::events::issue_creation_ticket),
+        )
         .route(
             "/events/batch-check",
             post(handlers::events::check_events_status),
         )
-        .route("/events/{public_token}", get(handlers::events::get_event))
+        .route(
+            "/events/conflicts",
+            post(handlers::events::check_event_conflicts),
+        )
+        .route("/templates", post(handlers::templates::create_template))
+        .route(
+            "/templates/{template_token}",
+            get(handlers::templates::get_template),
+        )
+        .route(
+            "/templates/{template_token}/events",
+            post(handlers::templates::create_event_from_template),
+        )
+        .route(
+            "/events/{public_token}/caldav/freebusy",
+            post(handlers::integrations::caldav_free_busy),
+        )
+        .route(
+            "/participants/batch-check",
+            post(handlers::events::check_participants_status),
+        )
+        .route(
+            "/organizers",
+            post(handlers::organizers::create_organizer_account),
+        )
+        .route(
+            "/organizers/{account_token}/events",
+            get(handlers::organizers::list_organizer_events),
+        )
+        .route("/auth/magic-link", post(handlers::auth::request_magic_link))
+        .route("/auth/verify", get(handlers::auth::verify_magic_link))
+        .route("/auth/me", get(handlers::auth::get_current_user))
+        .route("/auth/logout", post(handlers::auth::logout))
+        .route("/auth/token", post(handlers::auth::issue_token))
+        .route("/auth/events", get(handlers::auth::list_user_events))
+        .route(
+            "/participants/aggregate",
+            get(handlers::events::aggregate_participant_availability),
+        )
+        // PATCH and DELETE take the organizer token; axum needs one parameter name per segment
+        .route(
+            "/events/{public_token}",
+            get(handlers::events::get_event)
+                .patch(handlers::events::update_event)
+                .delete(handlers::events::delete_event),
+        )
         .route(
             "/events/{public_token}/availability",
             post(handlers::events::submit_availability),
         )
+        .route(
+            "/events/{public_token}/availability/batch",
+            post(handlers::events::submit_availability_batch),
+        )
+        .route(
+            "/events/{public_token}/ws",
+            get(handlers::realtime::event_socket),
+        )
         .route(
             "/events/{public_token}/results",
             get(handlers::events::get_event_results),
@@ -27,13 +132,141 @@ pub fn create_router(pool: PgPool) -> Router {
             "/events/{organizer_token}/close",
             post(handlers::events::close_event),
         )
+        .route(
+            "/events/{organizer_token}/restore",
+            post(handlers::events::restore_event),
+        )
+        .route(
+            "/events/{organizer_token}/clone",
+            post(handlers::events::clone_event),
+        )
+        .route(
+            "/events/{organizer_token}/extend",
+            post(handlers::events::extend_event),
+        )
+        .route(
+            "/events/{organizer_token}/remind",
+            post(handlers::events::remind_participants),
+        )
+        .route(
+            "/events/{organizer_token}/reminders",
+            get(handlers::events::list_reminders),
+        )
+        .route(
+            "/events/{organizer_token}/finalize",
+            post(handlers::events::finalize_event),
+        )
+        .route(
+            "/events/{organizer_token}/invitations",
+            get(handlers::events::list_invitations),
+        )
+        .route(
+            "/events/{organizer_token}/assign",
+            get(handlers::assignments::get_assignment)
+                .post(handlers::assignments::propose_assignment)
+                .put(handlers::assignments::update_assignment),
+        )
+        .route(
+            "/events/{public_token}/suggestions",
+            get(handlers::events::get_event_suggestions),
+        )
+        .route(
+            "/events/{public_token}/summary",
+            get(handlers::events::get_event_summary),
+        )
+        .route(
+            "/events/{public_token}/export.ics",
+            get(handlers::events::export_event_ics),
+        )
+        .route(
+            "/events/{organizer_token}/archive",
+            post(handlers::events::archive_event),
+        )
+        .route(
+            "/events/{organizer_token}/edit-lock",
+            post(handlers::events::acquire_edit_lock).delete(handlers::events::release_edit_lock),
+        )
+        .route(
+            "/events/{organizer_token}/audit",
+            get(handlers::events::get_event_audit),
+        )
+        .route(
+            "/events/{organizer_token}/transfer",
+            post(handlers::events::transfer_ownership),
+        )
+        .route(
+            "/events/claim/{claim_token}",
+            post(handlers::events::claim_ownership),
+        )
         .route(
             "/events/organizer/{organizer_token}",
             get(handlers::events::get_organizer_event),
         )
+        .route(
+            "/events/{organizer_token}/participants/order",
+            put(handlers::events::update_participant_order),
+        )
+        // DELETE takes the organizer token and a participant id
         .route(
             "/events/{public_token}/participants/{participant_token}",
-            get(handlers::events::get_participant).put(handlers::events::update_participant),
+            get(handlers::events::get_participant)
+                .put(handlers::events::update_participant)
+                .delete(handlers::events::delete_participant),
+        )
+        .route(
+            "/participants/verify-email",
+            get(handlers::events::verify_participant_email),
+        )
+        .route(
+            "/events/{organizer_token}/slots/{slot_id}",
+            patch(handlers::events::update_slot),
+        )
+        // Takes the organizer token and a participant id, like PATCH above
+        .route(
+            "/events/{public_token}/participants/{participant_token}/history",
+            get(handlers::events::get_participant_history),
+        )
+        .route(
+            "/events/{public_token}/comments",
+            get(handlers::comments::list_comments).post(handlers::comments::create_comment),
+        )
+        .route(
+            "/events/{public_token}/waitlist",
+            get(handlers::events::get_waitlist),
+        )
+        .route(
+            "/events/{public_token}/waitlist/{participant_token}",
+            post(handlers::events::moderate_participant),
+        )
+}
+
+/// Support/operations endpoints under `/admin`, all behind the admin key.
+pub fn admin_router(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/admin/events", get(handlers::admin::list_events))
+        .route("/admin/events/search", get(handlers::admin::search_events))
+        .route(
+            "/admin/events/{public_token}",
+            delete(handlers::admin::force_delete_event),
+        )
+        .route("/admin/rate-limits", get(handlers::admin::rate_limits))
+        .route("/admin/cleanup", post(handlers::admin::run_cleanup))
+        .route("/admin/stats", get(handlers::admin::stats))
+        .route(
+            "/admin/bans",
+            get(handlers::admin::list_bans).post(handlers::admin::create_ban),
+        )
+        .route("/admin/bans/{ip}", delete(handlers::admin::delete_ban))
+        .route("/admin/metrics", get(handlers::admin::business_metrics))
+        .route("/admin/jobs", get(handlers::admin::job_runs))
+        .route(
+            "/admin/mail-templates/{notification}/preview",
+            get(handlers::admin::preview_mail_template),
+        )
+        .route(
+            "/integrations/itip/reply",
+            post(handlers::integrations::itip_reply),
         )
-        .with_state(pool)
+        .route("/demo/reset", post(handlers::demo::reset_demo))
+        .route_layer(from_fn_with_state(state.clone(), require_admin_key))
 }
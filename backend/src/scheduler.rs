@@ -0,0 +1,242 @@
+//! Background jobs of `serve`. Each registered job runs on its own interval,
+//! each run delayed by up to a tenth of it so replicas don't all hit the
+//! database at once, and in its own task so a panic only fails that run.
+//! Every run is recorded in `job_runs` (see [`jobs::track`]), and
+//! `jobs run <name>` runs one by hand.
+
+use std::{any::Any, fmt::Display, future::Future, sync::Arc, time::Duration};
+
+use futures::future::BoxFuture;
+use rand::Rng;
+use sqlx::PgPool;
+use tokio::{
+    sync::watch,
+    task::JoinSet,
+    time::{Interval, MissedTickBehavior},
+};
+
+use crate::{
+    auth,
+    clock::SharedClock,
+    config::Config,
+    db::{
+        cleanup,
+        jobs::{self, JobOutcome},
+        reveal,
+    },
+    encryption::Keyring,
+    integrations::{mail, templates::SharedTemplates, webhooks},
+};
+
+pub const HOURLY: Duration = Duration::from_secs(3600);
+
+/// What every job gets to work with.
+#[derive(Clone)]
+pub struct JobContext {
+    pub pool: PgPool,
+    pub clock: SharedClock,
+}
+
+type Task = Arc<dyn Fn(JobContext) -> BoxFuture<'static, Result<u64, String>> + Send + Sync>;
+
+struct Job {
+    name: &'static str,
+    every: Duration,
+    task: Task,
+}
+
+pub struct Scheduler {
+    context: JobContext,
+    jobs: Vec<Job>,
+}
+
+impl Scheduler {
+    pub fn new(pool: PgPool, clock: SharedClock) -> Self {
+        Self {
+            context: JobContext { pool, clock },
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Adds a job run every `every`. Names are the `job_runs.job` values, so
+    /// keep them stable; registering a name twice replaces the first job.
+    pub fn register<F, Fut, T, E>(&mut self, name: &'static str, every: Duration, task: F)
+    where
+        F: Fn(JobContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: JobOutcome + Send,
+        E: Display,
+    {
+        let task: Task = Arc::new(move |context| {
+            let run = task(context);
+            Box::pin(async move {
+                match run.await {
+                    Ok(outcome) => {
+                        let rows = outcome.affected_rows();
+                        if rows > 0 {
+                            tracing::info!("{}: {}", name, outcome.describe());
+                        }
+                        Ok(rows)
+                    }
+                    Err(e) => Err(e.to_string()),
+                }
+            })
+        });
+        self.jobs.retain(|job| job.name != name);
+        self.jobs.push(Job { name, every, task });
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.jobs.iter().map(|job| job.name).collect()
+    }
+
+    /// Runs the job called `name` once now; `None` when there is none.
+    pub async fn run(&self, name: &str) -> Option<Result<u64, String>> {
+        let job = self.jobs.iter().find(|job| job.name == name)?;
+        Some(run_tracked(&self.context, job.name, &job.task).await)
+    }
+
+    /// One task per job, each starting with a run and stopping once
+    /// `shutdown` flips to true; a running job is finished first.
+    pub fn spawn(self, tasks: &mut JoinSet<()>, shutdown: &watch::Receiver<bool>) {
+        for job in self.jobs {
+            let context = self.context.clone();
+            let mut shutdown = shutdown.clone();
+            tasks.spawn(async move {
+                let mut interval = tokio::time::interval(job.every);
+                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                while next_tick(&mut interval, &mut shutdown).await {
+                    let jitter = job.every.mul_f64(rand::thread_rng().gen_range(0.0..0.1));
+                    tokio::select! {
+                        _ = tokio::time::sleep(jitter) => {}
+                        _ = shutdown.wait_for(|stopping| *stopping) => break,
+                    }
+                    // Failures are logged and kept in job_runs; the next tick retries
+                    let _ = run_tracked(&context, job.name, &job.task).await;
+                }
+            });
+        }
+    }
+}
+
+/// Runs `task` in a task of its own so a panic is recorded as a failed run
+/// instead of ending the job's loop.
+async fn run_tracked(context: &JobContext, name: &str, task: &Task) -> Result<u64, String> {
+    let run = tokio::spawn(task(context.clone()));
+    let result = jobs::track(&context.pool, context.clock.as_ref(), name, async {
+        match run.await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => Err(format!("panicked: {}", panic_message(e.into_panic()))),
+            Err(e) => Err(e.to_string()),
+        }
+    })
+    .await;
+    if let Err(e) = &result {
+        tracing::error!("Error in {} job: {}", name, e);
+    }
+    result
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown cause".to_string())
+}
+
+/// The jobs `serve` runs for this configuration: expiry warnings, retention
+/// and pruning hourly, mail and webhook delivery every 30 seconds, and the
+/// reveal announcements every minute.
+pub fn app_jobs(
+    pool: PgPool,
+    clock: SharedClock,
+    config: &Config,
+    templates: SharedTemplates,
+) -> anyhow::Result<Scheduler> {
+    let mut scheduler = Scheduler::new(pool, clock);
+    let keys = Arc::new(Keyring::from_config(config));
+
+    if config.deletion_notice_hours > 0 {
+        let notice = chrono::Duration::hours(config.deletion_notice_hours);
+        // Warning organizers of the retention deletion needs mail
+        if config.mail_from.is_some() {
+            let templates = templates.clone();
+            let keys = keys.clone();
+            scheduler.register(jobs::DELETION_NOTICE, HOURLY, move |cx| {
+                let templates = templates.clone();
+                let keys = keys.clone();
+                async move {
+                    cleanup::notify_upcoming_deletions(
+                        &cx.pool,
+                        cx.clock.as_ref(),
+                        &templates,
+                        &keys,
+                        notice,
+                    )
+                    .await
+                }
+            });
+        }
+        scheduler.register(jobs::EXPIRY_WEBHOOKS, HOURLY, move |cx| async move {
+            cleanup::announce_upcoming_deletions(&cx.pool, cx.clock.as_ref(), notice).await
+        });
+    }
+    scheduler.register(jobs::TRASH_EXPIRED, HOURLY, |cx| async move {
+        cleanup::delete_expired_events(&cx.pool, cx.clock.as_ref()).await
+    });
+    scheduler.register(jobs::PURGE_TRASHED, HOURLY, |cx| async move {
+        cleanup::purge_deleted_events(&cx.pool, cx.clock.as_ref()).await
+    });
+    scheduler.register(jobs::ARCHIVE_FINISHED, HOURLY, |cx| async move {
+        cleanup::archive_finished_events(&cx.pool, cx.clock.as_ref()).await
+    });
+    scheduler.register(jobs::PRUNE_JOB_RUNS, HOURLY, |cx| async move {
+        jobs::prune_job_runs(&cx.pool, cx.clock.as_ref()).await
+    });
+    scheduler.register(jobs::PRUNE_LOGINS, HOURLY, |cx| async move {
+        auth::prune_expired(&cx.pool, cx.clock.as_ref()).await
+    });
+
+    // Drain the email outbox when mail is configured
+    if let Some(mailer) = mail::ConfiguredMailer::from_config(config)? {
+        let mailer = Arc::new(mailer);
+        scheduler.register(jobs::MAIL_DELIVERY, Duration::from_secs(30), move |cx| {
+            let mailer = mailer.clone();
+            async move { mail::deliver_pending(&cx.pool, mailer.as_ref(), cx.clock.as_ref()).await }
+        });
+    }
+
+    // Failures back off inside deliver_pending
+    scheduler.register(jobs::WEBHOOK_DELIVERY, Duration::from_secs(30), move |cx| {
+        let keys = keys.clone();
+        async move {
+            webhooks::deliver_pending(&cx.pool, &webhooks::HttpSender, &keys, cx.clock.as_ref())
+                .await
+        }
+    });
+
+    // Announce blind polls whose results have just become visible
+    let notify_reveal = config.mail_from.is_some();
+    scheduler.register(jobs::RESULTS_REVEAL, Duration::from_secs(60), move |cx| {
+        let templates = templates.clone();
+        async move {
+            reveal::notify_revealed_results(&cx.pool, cx.clock.as_ref(), &templates, notify_reveal)
+                .await
+        }
+    });
+
+    Ok(scheduler)
+}
+
+/// Waits for the next tick of a background task. Returns false once shutdown
+/// was requested, so tasks stop between runs rather than in the middle of one.
+pub async fn next_tick(interval: &mut Interval, shutdown: &mut watch::Receiver<bool>) -> bool {
+    if *shutdown.borrow() {
+        return false;
+    }
+    tokio::select! {
+        _ = interval.tick() => true,
+        _ = shutdown.wait_for(|stopping| *stopping) => false,
+    }
+}
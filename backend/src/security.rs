@@ -0,0 +1,289 @@
+//! Abuse detection and IP bans.
+//!
+//! [`AbuseLayer`] counts, per client IP, the events it created and the
+//! requests rejected as invalid (400/422) within `ABUSE_WINDOW_SECS`. A
+//! client crossing `ABUSE_MAX_EVENTS` or `ABUSE_MAX_FAILURES` is banned for
+//! `ABUSE_BAN_SECS`: every further request gets 403 `BANNED`. Bans live in
+//! `ip_bans` so they survive restarts and reach every replica, which reloads
+//! them each minute through [`refresh_bans`]; operators manage them under
+//! `/admin/bans`.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex, RwLock},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::Request,
+    http::{Method, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use sqlx::PgPool;
+use tower::{Layer, Service};
+
+use crate::{
+    clock::{Clock, SharedClock},
+    config::Config,
+    error::AppError,
+    middleware::client_addr,
+};
+
+/// `banned_by` of bans the detector issued.
+pub const AUTO_BAN: &str = "auto";
+
+/// Active bans by client IP, with their expiry.
+pub type SharedBans = Arc<RwLock<HashMap<IpAddr, DateTime<Utc>>>>;
+
+/// When `ip` is banned at `now`, the time the ban ends.
+pub fn banned_until(bans: &SharedBans, ip: IpAddr, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    bans.read()
+        .unwrap()
+        .get(&ip)
+        .copied()
+        .filter(|until| *until > now)
+}
+
+/// Stores a ban, extending an existing one rather than shortening it, and
+/// applies it to this replica right away.
+pub async fn ban(
+    pool: &PgPool,
+    bans: &SharedBans,
+    ip: IpAddr,
+    reason: &str,
+    banned_by: &str,
+    now: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<DateTime<Utc>, sqlx::Error> {
+    let until = sqlx::query_scalar!(
+        r#"
+        INSERT INTO ip_bans (ip, reason, banned_by, created_at, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (ip) DO UPDATE SET
+            reason = EXCLUDED.reason,
+            banned_by = EXCLUDED.banned_by,
+            created_at = EXCLUDED.created_at,
+            expires_at = GREATEST(ip_bans.expires_at, EXCLUDED.expires_at)
+        RETURNING expires_at
+        "#,
+        ip.to_string(),
+        reason,
+        banned_by,
+        now,
+        until
+    )
+    .fetch_one(pool)
+    .await?;
+    bans.write().unwrap().insert(ip, until);
+    Ok(until)
+}
+
+/// Lifts a ban; false when `ip` wasn't banned.
+pub async fn unban(pool: &PgPool, bans: &SharedBans, ip: IpAddr) -> Result<bool, sqlx::Error> {
+    let removed = sqlx::query!("DELETE FROM ip_bans WHERE ip = $1", ip.to_string())
+        .execute(pool)
+        .await?
+        .rows_affected();
+    bans.write().unwrap().remove(&ip);
+    Ok(removed > 0)
+}
+
+/// Drops expired bans and reloads the rest, picking up bans issued by other
+/// replicas. Returns how many are active.
+pub async fn refresh_bans(
+    pool: &PgPool,
+    clock: &dyn Clock,
+    bans: &SharedBans,
+) -> Result<usize, sqlx::Error> {
+    let now = clock.now();
+    sqlx::query!("DELETE FROM ip_bans WHERE expires_at <= $1", now)
+        .execute(pool)
+        .await?;
+    let rows = sqlx::query!("SELECT ip, expires_at FROM ip_bans")
+        .fetch_all(pool)
+        .await?;
+
+    let active: HashMap<IpAddr, DateTime<Utc>> = rows
+        .into_iter()
+        .filter_map(|row| Some((row.ip.parse().ok()?, row.expires_at)))
+        .collect();
+    let count = active.len();
+    *bans.write().unwrap() = active;
+    Ok(count)
+}
+
+/// Beyond this many tracked clients, those whose window ended are swept.
+const SWEEP_THRESHOLD: usize = 10_000;
+
+#[derive(Clone, Copy)]
+enum Offense {
+    CreatedEvent,
+    Rejected,
+}
+
+struct Activity {
+    started: Instant,
+    events: u32,
+    failures: u32,
+}
+
+/// Counts what each client does and bans those over the thresholds. Must
+/// sit outside the router so it sees final response statuses.
+#[derive(Clone)]
+pub struct AbuseLayer {
+    pool: PgPool,
+    clock: SharedClock,
+    bans: SharedBans,
+    activity: Arc<Mutex<HashMap<IpAddr, Activity>>>,
+    max_events: u32,
+    max_failures: u32,
+    window: Duration,
+    ban_duration: chrono::Duration,
+}
+
+impl AbuseLayer {
+    pub fn new(config: &Config, pool: PgPool, clock: SharedClock, bans: SharedBans) -> Self {
+        AbuseLayer {
+            pool,
+            clock,
+            bans,
+            activity: Arc::new(Mutex::new(HashMap::new())),
+            max_events: config.abuse_max_events,
+            max_failures: config.abuse_max_failures,
+            window: Duration::from_secs(config.abuse_window_secs),
+            ban_duration: chrono::Duration::seconds(config.abuse_ban_secs as i64),
+        }
+    }
+
+    /// Counts one offense; the ban reason once the client is over a limit.
+    fn record(&self, ip: IpAddr, offense: Offense) -> Option<String> {
+        let now = self.clock.instant();
+        let mut activity = self.activity.lock().unwrap();
+
+        if activity.len() >= SWEEP_THRESHOLD {
+            activity.retain(|_, seen| now.duration_since(seen.started) <= self.window);
+        }
+
+        let seen = activity.entry(ip).or_insert(Activity {
+            started: now,
+            events: 0,
+            failures: 0,
+        });
+        if now.duration_since(seen.started) > self.window {
+            *seen = Activity {
+                started: now,
+                events: 0,
+                failures: 0,
+            };
+        }
+        let reason = match offense {
+            Offense::CreatedEvent => {
+                seen.events += 1;
+                (self.max_events > 0 && seen.events > self.max_events).then(|| {
+                    format!(
+                        "created more than {} events within {}s",
+                        self.max_events,
+                        self.window.as_secs()
+                    )
+                })
+            }
+            Offense::Rejected => {
+                seen.failures += 1;
+                (self.max_failures > 0 && seen.failures > self.max_failures).then(|| {
+                    format!(
+                        "more than {} rejected requests within {}s",
+                        self.max_failures,
+                        self.window.as_secs()
+                    )
+                })
+            }
+        };
+        if reason.is_some() {
+            // Start over once the ban ends
+            activity.remove(&ip);
+        }
+        reason
+    }
+}
+
+impl<S> Layer<S> for AbuseLayer {
+    type Service = AbuseService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AbuseService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AbuseService<S> {
+    inner: S,
+    layer: AbuseLayer,
+}
+
+impl<S> Service<Request> for AbuseService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let ip = client_addr(&req).ip();
+        if let Some(until) = banned_until(&self.layer.bans, ip, self.layer.clock.now()) {
+            return Box::pin(async move { Ok(AppError::Banned(until).into_response()) });
+        }
+
+        let creates_event = req.method() == Method::POST && req.uri().path() == "/events";
+        let layer = self.layer.clone();
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            let res = fut.await?;
+            let offense = match res.status() {
+                StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+                    Some(Offense::Rejected)
+                }
+                status if creates_event && status.is_success() => Some(Offense::CreatedEvent),
+                _ => None,
+            };
+            if let Some(reason) = offense.and_then(|offense| layer.record(ip, offense)) {
+                let now = layer.clock.now();
+                match ban(
+                    &layer.pool,
+                    &layer.bans,
+                    ip,
+                    &reason,
+                    AUTO_BAN,
+                    now,
+                    now + layer.ban_duration,
+                )
+                .await
+                {
+                    Ok(until) => tracing::warn!(%ip, %until, reason, "Banned client"),
+                    Err(e) => {
+                        // Still keep the client out of this replica
+                        layer
+                            .bans
+                            .write()
+                            .unwrap()
+                            .insert(ip, now + layer.ban_duration);
+                        tracing::error!(%ip, "Failed to store ban: {:?}", e);
+                    }
+                }
+            }
+            Ok(res)
+        })
+    }
+}
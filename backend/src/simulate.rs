@@ -0,0 +1,409 @@
+//! Scenario replay used by the `simulate` subcommand.
+//!
+//! A scenario is a JSON file describing a sequence of API calls. Each step is
+//! sent either to a live server (`--base-url`) or to an in-process router
+//! backed by the configured database. Latencies are collected per operation
+//! and a handful of invariants are checked along the way.
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+use tower::ServiceExt;
+
+#[derive(Debug, Deserialize)]
+pub struct Scenario {
+    pub name: Option<String>,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Step {
+    #[serde(flatten)]
+    pub op: Operation,
+    /// Number of times to send this step (default 1).
+    #[serde(default = "default_repeat")]
+    pub repeat: u32,
+    /// Expected HTTP status. When unset any 2xx is accepted.
+    pub expect_status: Option<u16>,
+}
+
+fn default_repeat() -> u32 {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    CreateEvent { body: Value },
+    SubmitAvailability { body: Value },
+    GetEvent,
+    GetResults,
+    GetOrganizerEvent,
+    CloseEvent,
+}
+
+impl Operation {
+    fn label(&self) -> &'static str {
+        match self {
+            Operation::CreateEvent { .. } => "create_event",
+            Operation::SubmitAvailability { .. } => "submit_availability",
+            Operation::GetEvent => "get_event",
+            Operation::GetResults => "get_results",
+            Operation::GetOrganizerEvent => "get_organizer_event",
+            Operation::CloseEvent => "close_event",
+        }
+    }
+}
+
+pub enum Target {
+    InProcess(Router),
+    Http {
+        client: reqwest::Client,
+        base_url: String,
+    },
+}
+
+impl Target {
+    pub fn http(base_url: &str) -> Self {
+        Target::Http {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+    ) -> anyhow::Result<(StatusCode, Value)> {
+        match self {
+            Target::InProcess(router) => {
+                let mut builder = Request::builder().method(method).uri(path);
+                let body = match body {
+                    Some(json) => {
+                        builder = builder.header("content-type", "application/json");
+                        Body::from(serde_json::to_vec(json)?)
+                    }
+                    None => Body::empty(),
+                };
+                let response = router.clone().oneshot(builder.body(body)?).await?;
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+                Ok((status, parse_body(&bytes)))
+            }
+            Target::Http { client, base_url } => {
+                let mut request = client.request(method, format!("{}{}", base_url, path));
+                if let Some(json) = body {
+                    request = request.json(json);
+                }
+                let response = request.send().await?;
+                let status = response.status();
+                let bytes = response.bytes().await?;
+                Ok((status, parse_body(&bytes)))
+            }
+        }
+    }
+}
+
+/// Replaces `{i}` in every string of `body` with the repeat iteration, so
+/// repeated submissions can carry distinct participant names.
+fn substitute(body: &Value, iteration: u32) -> Value {
+    match body {
+        Value::String(s) => Value::String(s.replace("{i}", &iteration.to_string())),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| substitute(v, iteration)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), substitute(v, iteration)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn parse_body(bytes: &[u8]) -> Value {
+    serde_json::from_slice(bytes).unwrap_or(Value::Null)
+}
+
+#[derive(Debug, Default)]
+pub struct OperationStats {
+    pub latencies: Vec<Duration>,
+    pub failures: u32,
+}
+
+impl OperationStats {
+    fn percentile(&self, p: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        let mut sorted = self.latencies.clone();
+        sorted.sort();
+        let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+        sorted[idx]
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub stats: BTreeMap<&'static str, OperationStats>,
+    pub violations: Vec<String>,
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!(
+            "{:<22} {:>6} {:>6} {:>10} {:>10} {:>10}\n",
+            "operation", "count", "fail", "p50", "p95", "max"
+        ));
+        for (label, stats) in &self.stats {
+            out.push_str(&format!(
+                "{:<22} {:>6} {:>6} {:>10.2?} {:>10.2?} {:>10.2?}\n",
+                label,
+                stats.latencies.len(),
+                stats.failures,
+                stats.percentile(0.5),
+                stats.percentile(0.95),
+                stats.percentile(1.0),
+            ));
+        }
+        if self.violations.is_empty() {
+            out.push_str("\nNo invariant violations.\n");
+        } else {
+            out.push_str(&format!(
+                "\n{} invariant violation(s):\n",
+                self.violations.len()
+            ));
+            for violation in &self.violations {
+                out.push_str(&format!("  - {}\n", violation));
+            }
+        }
+        out
+    }
+}
+
+#[derive(Default)]
+struct Session {
+    public_token: Option<String>,
+    organizer_token: Option<String>,
+    accepted_submissions: i64,
+    closed: bool,
+}
+
+pub async fn run(scenario: &Scenario, target: &Target) -> anyhow::Result<Report> {
+    let mut report = Report::default();
+    let mut session = Session::default();
+
+    for (index, step) in scenario.steps.iter().enumerate() {
+        let label = step.op.label();
+        for iteration in 0..step.repeat {
+            let (method, path, body) = match &step.op {
+                Operation::CreateEvent { body } => (
+                    Method::POST,
+                    "/events".to_string(),
+                    Some(substitute(body, iteration)),
+                ),
+                Operation::SubmitAvailability { body } => (
+                    Method::POST,
+                    format!("/events/{}/availability", session.public_token()?),
+                    Some(substitute(body, iteration)),
+                ),
+                Operation::GetEvent => (
+                    Method::GET,
+                    format!("/events/{}", session.public_token()?),
+                    None,
+                ),
+                Operation::GetResults => (
+                    Method::GET,
+                    format!("/events/{}/results", session.public_token()?),
+                    None,
+                ),
+                Operation::GetOrganizerEvent => (
+                    Method::GET,
+                    format!("/events/organizer/{}", session.organizer_token()?),
+                    None,
+                ),
+                Operation::CloseEvent => (
+                    Method::POST,
+                    format!("/events/{}/close", session.organizer_token()?),
+                    None,
+                ),
+            };
+
+            let started = Instant::now();
+            let (status, response) = target.send(method, &path, body.as_ref()).await?;
+            let stats = report.stats.entry(label).or_default();
+            stats.latencies.push(started.elapsed());
+
+            let status_ok = match step.expect_status {
+                Some(expected) => status.as_u16() == expected,
+                None => status.is_success(),
+            };
+            if !status_ok {
+                stats.failures += 1;
+                report.violations.push(format!(
+                    "step {} ({}): unexpected status {}",
+                    index, label, status
+                ));
+            }
+            if status.is_server_error() {
+                report.violations.push(format!(
+                    "step {} ({}): server error {}",
+                    index, label, status
+                ));
+            }
+
+            if status.is_success() {
+                session.observe(index, &step.op, &response, &mut report.violations);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+impl Session {
+    fn public_token(&self) -> anyhow::Result<&str> {
+        self.public_token
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("scenario must create an event before using it"))
+    }
+
+    fn organizer_token(&self) -> anyhow::Result<&str> {
+        self.organizer_token
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("scenario must create an event before using it"))
+    }
+
+    fn observe(
+        &mut self,
+        index: usize,
+        op: &Operation,
+        response: &Value,
+        violations: &mut Vec<String>,
+    ) {
+        match op {
+            Operation::CreateEvent { .. } => {
+                self.public_token = response["public_token"].as_str().map(str::to_string);
+                self.organizer_token = response["organizer_token"].as_str().map(str::to_string);
+                self.accepted_submissions = 0;
+                self.closed = false;
+                if self.public_token.is_none() || self.organizer_token.is_none() {
+                    violations.push(format!("step {}: create_event returned no tokens", index));
+                }
+            }
+            Operation::SubmitAvailability { .. } => {
+                self.accepted_submissions += 1;
+                if response["participant_token"].as_str().is_none() {
+                    violations.push(format!(
+                        "step {}: submission returned no participant_token",
+                        index
+                    ));
+                }
+            }
+            Operation::GetResults | Operation::GetOrganizerEvent => {
+                // Organizer participant + every accepted submission
+                let expected = 1 + self.accepted_submissions;
+                let total = response["total_participants"].as_i64();
+                if total != Some(expected) {
+                    violations.push(format!(
+                        "step {}: total_participants {:?}, expected {}",
+                        index, total, expected
+                    ));
+                }
+                let listed = response["participants"].as_array().map(Vec::len);
+                let hidden = response["results_hidden"].as_bool().unwrap_or(false);
+                if !hidden && listed.map(|n| n as i64) != total {
+                    violations.push(format!(
+                        "step {}: participants list length {:?} disagrees with total {:?}",
+                        index, listed, total
+                    ));
+                }
+                self.check_state(index, response, violations);
+            }
+            Operation::GetEvent => self.check_state(index, response, violations),
+            Operation::CloseEvent => {
+                self.closed = true;
+                self.check_state(index, response, violations);
+            }
+        }
+    }
+
+    fn check_state(&self, index: usize, response: &Value, violations: &mut Vec<String>) {
+        let expected = if self.closed { "closed" } else { "open" };
+        if response["state"].as_str() != Some(expected) {
+            violations.push(format!(
+                "step {}: state {:?}, expected {:?}",
+                index, response["state"], expected
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scenario() {
+        let scenario: Scenario = serde_json::from_str(
+            r#"{
+                "name": "smoke",
+                "steps": [
+                    {"op": "create_event", "body": {"title": "t"}},
+                    {"op": "submit_availability", "body": {}, "repeat": 3},
+                    {"op": "get_results", "expect_status": 200}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(scenario.steps.len(), 3);
+        assert_eq!(scenario.steps[0].repeat, 1);
+        assert_eq!(scenario.steps[1].repeat, 3);
+        assert!(matches!(scenario.steps[2].op, Operation::GetResults));
+        assert_eq!(scenario.steps[2].expect_status, Some(200));
+    }
+
+    #[test]
+    fn test_substitute_iteration() {
+        let body = serde_json::json!({"participant_name": "Guest {i}", "nested": ["{i}"]});
+        let out = substitute(&body, 4);
+        assert_eq!(out["participant_name"], "Guest 4");
+        assert_eq!(out["nested"][0], "4");
+    }
+
+    #[test]
+    fn test_results_invariant_detects_missing_participant() {
+        let mut session = Session {
+            public_token: Some("p".to_string()),
+            organizer_token: Some("o".to_string()),
+            accepted_submissions: 2,
+            closed: false,
+        };
+        let mut violations = Vec::new();
+        let response = serde_json::json!({
+            "state": "open",
+            "total_participants": 2,
+            "participants": [{}, {}],
+        });
+
+        session.observe(0, &Operation::GetResults, &response, &mut violations);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].contains("expected 3"));
+    }
+}
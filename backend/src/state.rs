@@ -0,0 +1,155 @@
+use axum::extract::FromRef;
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    cache::{ResponseCache, SharedResponseCache},
+    clock::{self, SharedClock},
+    config::Config,
+    encryption::{Keyring, SharedKeyring},
+    integrations::templates::{SharedTemplates, Templates},
+    metrics::{SharedCounters, SharedMetrics},
+    realtime::SharedHub,
+    security::SharedBans,
+};
+
+/// Shared application state handed to every handler.
+///
+/// Handlers that only need the database keep extracting `State<PgPool>`;
+/// the `FromRef` impls below pull individual pieces out of this struct.
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: PgPool,
+    pub clock: SharedClock,
+    pub config: Arc<Config>,
+    /// Latest business-metrics snapshot, filled by the collector task
+    pub metrics: SharedMetrics,
+    /// Request-path counters, shared with the middleware that increments them
+    pub counters: SharedCounters,
+    /// Per-event broadcast channels behind `GET /events/{public_token}/ws`
+    pub hub: SharedHub,
+    /// Notification mail templates, loaded once at startup
+    pub templates: SharedTemplates,
+    /// Active IP bans, enforced by `security::AbuseLayer`
+    pub bans: SharedBans,
+    /// Recently built event views, sized by `Config::response_cache_ttl_secs`
+    pub cache: SharedResponseCache,
+    /// Column encryption keys from `Config::data_encryption_key`
+    pub keys: SharedKeyring,
+}
+
+impl AppState {
+    pub fn new(pool: PgPool) -> Self {
+        let config = Config::default();
+        AppState {
+            pool,
+            clock: clock::system(),
+            cache: response_cache(&config),
+            keys: Arc::new(Keyring::from_config(&config)),
+            config: Arc::new(config),
+            metrics: SharedMetrics::default(),
+            counters: SharedCounters::default(),
+            hub: SharedHub::default(),
+            templates: Arc::new(Templates::default()),
+            bans: SharedBans::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.cache = response_cache(&config);
+        self.keys = Arc::new(Keyring::from_config(&config));
+        self.config = Arc::new(config);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: SharedMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn with_counters(mut self, counters: SharedCounters) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    pub fn with_templates(mut self, templates: SharedTemplates) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    pub fn with_bans(mut self, bans: SharedBans) -> Self {
+        self.bans = bans;
+        self
+    }
+}
+
+fn response_cache(config: &Config) -> SharedResponseCache {
+    Arc::new(ResponseCache::new(Duration::from_secs(
+        config.response_cache_ttl_secs,
+    )))
+}
+
+impl FromRef<AppState> for PgPool {
+    fn from_ref(state: &AppState) -> Self {
+        state.pool.clone()
+    }
+}
+
+impl FromRef<AppState> for SharedClock {
+    fn from_ref(state: &AppState) -> Self {
+        state.clock.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<Config> {
+    fn from_ref(state: &AppState) -> Self {
+        state.config.clone()
+    }
+}
+
+impl FromRef<AppState> for SharedMetrics {
+    fn from_ref(state: &AppState) -> Self {
+        state.metrics.clone()
+    }
+}
+
+impl FromRef<AppState> for SharedCounters {
+    fn from_ref(state: &AppState) -> Self {
+        state.counters.clone()
+    }
+}
+
+impl FromRef<AppState> for SharedHub {
+    fn from_ref(state: &AppState) -> Self {
+        state.hub.clone()
+    }
+}
+
+impl FromRef<AppState> for SharedBans {
+    fn from_ref(state: &AppState) -> Self {
+        state.bans.clone()
+    }
+}
+
+impl FromRef<AppState> for SharedTemplates {
+    fn from_ref(state: &AppState) -> Self {
+        state.templates.clone()
+    }
+}
+
+impl FromRef<AppState> for SharedResponseCache {
+    fn from_ref(state: &AppState) -> Self {
+        state.cache.clone()
+    }
+}
+
+impl FromRef<AppState> for SharedKeyring {
+    fn from_ref(state: &AppState) -> Self {
+        state.keys.clone()
+    }
+}
@@ -0,0 +1,139 @@
+//! Markdown rendering of [`EventSummary`] for chat assistants.
+//!
+//! Output is deliberately terse: one line per slot or candidate, UTC times
+//! without seconds, and no decoration beyond headings and bullets.
+
+use chrono::{DateTime, Utc};
+
+use crate::models::{EventSummary, TimeRangeRequest};
+
+pub fn render_markdown(summary: &EventSummary) -> String {
+    let mut out = format!("# {}\n", summary.title);
+    out.push_str(&format!(
+        "State: {} | Zone: {} | Slot: {} min | Respondents: {}\n",
+        summary.state.as_str(),
+        summary.time_zone.as_deref().unwrap_or("UTC"),
+        summary.slot_duration,
+        summary.respondents.len()
+    ));
+
+    if !summary.final_slots.is_empty() {
+        out.push_str("\n## Final\n");
+        for slot in &summary.final_slots {
+            out.push_str(&format!("- {}\n", format_range(slot)));
+        }
+    }
+
+    out.push_str("\n## Best candidates\n");
+    if summary.results_hidden {
+        out.push_str("- hidden until results are revealed\n");
+    } else if summary.best_candidates.is_empty() {
+        out.push_str("- none yet\n");
+    }
+    for candidate in &summary.best_candidates {
+        out.push_str(&format!(
+            "- {} — {}/{} ({})",
+            format_span(candidate.start_at, candidate.end_at),
+            candidate.count,
+            candidate.count + candidate.if_need_be.len() + candidate.missing.len(),
+            candidate.participants.join(", ")
+        ));
+        if !candidate.if_need_be.is_empty() {
+            out.push_str(&format!(
+                "; if need be: {}",
+                candidate.if_need_be.join(", ")
+            ));
+        }
+        if !candidate.missing.is_empty() {
+            out.push_str(&format!("; missing: {}", candidate.missing.join(", ")));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("\n## Slots\n");
+    for slot in &summary.slots {
+        out.push_str(&format!("- {}\n", format_range(slot)));
+    }
+
+    if !summary.without_availability.is_empty() {
+        out.push_str(&format!(
+            "\nNo availability: {}\n",
+            summary.without_availability.join(", ")
+        ));
+    }
+
+    out
+}
+
+fn format_range(range: &TimeRangeRequest) -> String {
+    format_span(range.start_at, range.end_at)
+}
+
+/// `2030-01-01 09:00–11:00 UTC`, or both dates when the span crosses midnight.
+pub(crate) fn format_span(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    if start.date_naive() == end.date_naive() {
+        format!(
+            "{}–{} UTC",
+            start.format("%Y-%m-%d %H:%M"),
+            end.format("%H:%M")
+        )
+    } else {
+        format!(
+            "{}–{} UTC",
+            start.format("%Y-%m-%d %H:%M"),
+            end.format("%Y-%m-%d %H:%M")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{EventState, SlotSuggestion};
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2030, 1, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let summary = EventSummary {
+            title: "Standup".to_string(),
+            state: EventState::Open,
+            time_zone: Some("Europe/Paris".to_string()),
+            slot_duration: 30,
+            slots: vec![TimeRangeRequest {
+                start_at: at(1, 22),
+                end_at: at(2, 1),
+                availability_level: None,
+            }],
+            final_slots: vec![],
+            best_candidates: vec![SlotSuggestion {
+                start_at: at(1, 22),
+                end_at: at(1, 23),
+                count: 2,
+                participants: vec!["Org".to_string(), "Ann".to_string()],
+                if_need_be: vec![],
+                missing: vec!["Ben".to_string()],
+                organizer_available: true,
+                score: 4,
+                roles: vec![],
+                meets_rules: true,
+                display: None,
+            }],
+            respondents: vec!["Org".to_string(), "Ann".to_string(), "Ben".to_string()],
+            without_availability: vec!["Ben".to_string()],
+            results_hidden: false,
+        };
+
+        let md = render_markdown(&summary);
+        assert!(md.starts_with(
+            "# Standup\nState: open | Zone: Europe/Paris | Slot: 30 min | Respondents: 3\n"
+        ));
+        assert!(md.contains("- 2030-01-01 22:00–23:00 UTC — 2/3 (Org, Ann); missing: Ben\n"));
+        assert!(md.contains("- 2030-01-01 22:00–2030-01-02 01:00 UTC\n"));
+        assert!(md.contains("No availability: Ben\n"));
+        assert!(!md.contains("## Final"));
+    }
+}
@@ -0,0 +1,100 @@
+//! Event creation tickets.
+//!
+//! When `CREATION_TICKET_SECRET` is set, `POST /events` needs a ticket from
+//! `POST /events/ticket`, which is only handed to pages served from an
+//! allowed origin. A ticket is `<issued_at>.<signature>`: the unix time it
+//! was issued and an HMAC-SHA256 over the origin and that time. Nothing is
+//! stored, so any replica sharing the secret accepts it until it expires.
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Header carrying the ticket on `POST /events`.
+pub const HEADER: &str = "x-creation-ticket";
+
+/// Tolerated clock difference between replicas for tickets issued "later".
+const MAX_CLOCK_SKEW: Duration = Duration::seconds(30);
+
+fn mac(secret: &str, origin: &str, issued_at: i64) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}\n{}", origin, issued_at).as_bytes());
+    mac
+}
+
+pub fn issue(secret: &str, origin: &str, now: DateTime<Utc>) -> String {
+    let issued_at = now.timestamp();
+    let signature = mac(secret, origin, issued_at).finalize().into_bytes();
+    format!("{}.{}", issued_at, hex::encode(signature))
+}
+
+/// False for a ticket signed for another origin or secret, one older than
+/// `ttl`, and anything that doesn't parse.
+pub fn verify(secret: &str, ticket: &str, origin: &str, now: DateTime<Utc>, ttl: Duration) -> bool {
+    let Some((issued_at, signature)) = ticket.split_once('.') else {
+        return false;
+    };
+    let (Ok(issued_at), Ok(signature)) = (issued_at.parse::<i64>(), hex::decode(signature)) else {
+        return false;
+    };
+    let Some(issued) = DateTime::from_timestamp(issued_at, 0) else {
+        return false;
+    };
+    if issued > now + MAX_CLOCK_SKEW || now - issued > ttl {
+        return false;
+    }
+    mac(secret, origin, issued_at)
+        .verify_slice(&signature)
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const ORIGIN: &str = "https://meet.example.com";
+
+    #[test]
+    fn test_verify_checks_origin_secret_and_age() {
+        let now = Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+        let ttl = Duration::minutes(5);
+        let ticket = issue("secret", ORIGIN, now);
+
+        assert!(verify("secret", &ticket, ORIGIN, now, ttl));
+        assert!(verify("secret", &ticket, ORIGIN, now + ttl, ttl));
+        assert!(!verify(
+            "secret",
+            &ticket,
+            ORIGIN,
+            now + ttl + Duration::seconds(1),
+            ttl
+        ));
+        assert!(!verify("secret", &ticket, "https://evil.example", now, ttl));
+        assert!(!verify("other", &ticket, ORIGIN, now, ttl));
+        // Issued by a replica whose clock runs far ahead
+        assert!(!verify(
+            "secret",
+            &ticket,
+            ORIGIN,
+            now - Duration::minutes(1),
+            ttl
+        ));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_tickets() {
+        let now = Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+        let ttl = Duration::minutes(5);
+        let ticket = issue("secret", ORIGIN, now);
+        let (_, signature) = ticket.split_once('.').unwrap();
+
+        // Moving the issue time forward invalidates the signature
+        let renewed = format!("{}.{}", now.timestamp() + 60, signature);
+        assert!(!verify("secret", &renewed, ORIGIN, now, ttl));
+        for garbage in ["", "abc", "1.zz", ".", "99999999999999999999.00"] {
+            assert!(!verify("secret", garbage, ORIGIN, now, ttl));
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Capability tokens for share links.
+//!
+//! Tokens are [`TOKEN_LENGTH`] random base62 characters (~71 bits) from the
+//! OS-seeded thread RNG. Events created before these existed carry 36-char
+//! UUID tokens; the columns are plain text, so lookups match both alike.
+
+use rand::distributions::{Alphanumeric, DistString};
+
+pub const TOKEN_LENGTH: usize = 12;
+
+/// Fresh tokens tried before giving up on an insert that keeps colliding.
+pub const MAX_ATTEMPTS: usize = 5;
+
+pub fn generate() -> String {
+    Alphanumeric.sample_string(&mut rand::thread_rng(), TOKEN_LENGTH)
+}
+
+/// Error for an insert that still collided after [`MAX_ATTEMPTS`] fresh
+/// tokens. Inserts use `ON CONFLICT DO NOTHING` and retry on no row.
+pub fn exhausted() -> sqlx::Error {
+    sqlx::Error::Protocol(format!("no unique token after {} attempts", MAX_ATTEMPTS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_is_short_base62() {
+        let token = generate();
+        assert_eq!(token.len(), TOKEN_LENGTH);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(token, generate());
+    }
+}
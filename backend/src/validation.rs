@@ -0,0 +1,197 @@
+//! Input validation shared by the handlers.
+//!
+//! A [`Validator`] collects every problem with a payload instead of stopping
+//! at the first one; [`Validator::finish`] turns them into 400
+//! `VALIDATION_FAILED`, with one [`FieldError`] per problem in `details` so
+//! clients can mark the offending fields. Lengths count characters, as the
+//! `VARCHAR` columns do.
+
+use crate::{
+    error::{AppError, AppResult, FieldError},
+    models::TimeRangeRequest,
+};
+
+/// Limits below are also advertised by `GET /capabilities`.
+pub(crate) const MAX_TITLE_LENGTH: usize = 100;
+pub(crate) const MAX_DESCRIPTION_LENGTH: usize = 1000;
+pub(crate) const MAX_NAME_LENGTH: usize = 50;
+pub(crate) const MAX_COMMENT_LENGTH: usize = 500;
+/// Candidate slots of an event, or ranges of one availability submission.
+pub(crate) const MAX_TIME_RANGES: usize = 500;
+/// From the earliest start to the latest end of a set of ranges.
+pub(crate) const MAX_RANGE_SPAN_DAYS: i64 = 366;
+
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Records the message of a failed check under `field`, so the
+    /// single-value validators elsewhere report like the ones here.
+    pub fn check(&mut self, field: &str, result: AppResult<()>) {
+        match result {
+            Ok(()) => {}
+            Err(AppError::BadRequest(message)) => self.add(field, message),
+            Err(other) => self.add(field, other.to_string()),
+        }
+    }
+
+    /// Non-blank and at most `max` characters.
+    pub fn required_text(&mut self, field: &str, label: &str, value: &str, max: usize) {
+        if value.trim().is_empty() || value.chars().count() > max {
+            self.add(
+                field,
+                format!(
+                    "{} is required and must be at most {} characters",
+                    label, max
+                ),
+            );
+        }
+    }
+
+    pub fn optional_text(&mut self, field: &str, label: &str, value: Option<&str>, max: usize) {
+        if value.is_some_and(|value| value.chars().count() > max) {
+            self.add(
+                field,
+                format!("{} must be at most {} characters", label, max),
+            );
+        }
+    }
+
+    /// An IANA zone name when given, returned in its canonical spelling
+    /// (`asia/taipei` becomes `Asia/Taipei`) for storing.
+    pub fn time_zone(&mut self, field: &str, time_zone: Option<&str>) -> Option<String> {
+        let tz = time_zone?;
+        match agreed_time_core::normalize_time_zone(tz) {
+            Some(name) => Some(name.to_string()),
+            None => {
+                self.add(field, format!("Unknown time zone: {}", tz));
+                None
+            }
+        }
+    }
+
+    /// Each range starts before it ends, there are at most
+    /// [`MAX_TIME_RANGES`] and together they span at most
+    /// [`MAX_RANGE_SPAN_DAYS`]. Problems with single ranges are reported
+    /// as `field[index]`.
+    pub fn time_ranges(&mut self, field: &str, ranges: &[TimeRangeRequest]) {
+        if ranges.len() > MAX_TIME_RANGES {
+            self.add(
+                field,
+                format!("At most {} time ranges are allowed", MAX_TIME_RANGES),
+            );
+            return;
+        }
+        for (index, range) in ranges.iter().enumerate() {
+            if range.start_at >= range.end_at {
+                self.add(
+                    &format!("{}[{}]", field, index),
+                    "Invalid time range: start must be before end",
+                );
+            }
+        }
+        let start = ranges.iter().map(|range| range.start_at).min();
+        let end = ranges.iter().map(|range| range.end_at).max();
+        if let (Some(start), Some(end)) = (start, end)
+            && end - start > chrono::Duration::days(MAX_RANGE_SPAN_DAYS)
+        {
+            self.add(
+                field,
+                format!("Time ranges must lie within {} days", MAX_RANGE_SPAN_DAYS),
+            );
+        }
+    }
+
+    pub fn finish(self) -> AppResult<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(self.errors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn range(start_day: u32, end_day: u32) -> TimeRangeRequest {
+        TimeRangeRequest {
+            start_at: Utc.with_ymd_and_hms(2030, 1, start_day, 9, 0, 0).unwrap(),
+            end_at: Utc.with_ymd_and_hms(2030, 1, end_day, 9, 0, 0).unwrap(),
+            availability_level: None,
+        }
+    }
+
+    fn fields(validator: Validator) -> Vec<String> {
+        match validator.finish() {
+            Ok(()) => Vec::new(),
+            Err(AppError::Validation(errors)) => errors.into_iter().map(|e| e.field).collect(),
+            Err(other) => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_collects_every_problem() {
+        let mut validator = Validator::new();
+        validator.required_text("title", "Title", "   ", MAX_TITLE_LENGTH);
+        validator.required_text("name", "Name", "Alice", MAX_NAME_LENGTH);
+        validator.optional_text("comment", "Comment", Some(&"x".repeat(501)), 500);
+        validator.time_zone("time_zone", Some("Mars/Olympus"));
+        validator.time_ranges("time_slots", &[range(1, 2), range(3, 3)]);
+        validator.check("email", Err(AppError::BadRequest("Invalid".to_string())));
+
+        assert_eq!(
+            fields(validator),
+            ["title", "comment", "time_zone", "time_slots[1]", "email"]
+        );
+    }
+
+    #[test]
+    fn test_time_zone_is_normalized() {
+        let mut validator = Validator::new();
+        assert_eq!(
+            validator.time_zone("time_zone", Some("europe/paris")),
+            Some("Europe/Paris".to_string())
+        );
+        assert_eq!(validator.time_zone("time_zone", None), None);
+        assert!(fields(validator).is_empty());
+    }
+
+    #[test]
+    fn test_lengths_count_characters() {
+        let mut validator = Validator::new();
+        // 50 characters, 150 bytes
+        validator.required_text("name", "Name", &"日".repeat(50), MAX_NAME_LENGTH);
+        validator.optional_text("comment", "Comment", None, MAX_COMMENT_LENGTH);
+        assert!(fields(validator).is_empty());
+    }
+
+    #[test]
+    fn test_time_ranges_limit_count_and_span() {
+        let mut validator = Validator::new();
+        let mut far = range(1, 2);
+        far.end_at = far.start_at + chrono::Duration::days(MAX_RANGE_SPAN_DAYS + 1);
+        validator.time_ranges("availabilities", &[range(1, 2), far]);
+        validator.time_ranges("time_slots", &vec![range(1, 2); MAX_TIME_RANGES + 1]);
+        assert_eq!(fields(validator), ["availabilities", "time_slots"]);
+
+        let mut validator = Validator::new();
+        validator.time_ranges("time_slots", &[]);
+        assert!(fields(validator).is_empty());
+    }
+}
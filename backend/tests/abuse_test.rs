@@ -0,0 +1,230 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::{Clock, TestClock},
+    config::Config,
+    routes::create_router_with_state,
+    security::{self, AbuseLayer, SharedBans},
+    state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use chrono::{TimeZone, Utc};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+const ADMIN_KEY: &str = "test-admin-key";
+
+/// The router wrapped in the abuse layer, as `main` serves it. Requests
+/// carry no peer address, so every client is 127.0.0.1.
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>) -> (Router, SharedBans) {
+    let config = Config {
+        admin_api_key: Some(ADMIN_KEY.to_string()),
+        abuse_max_events: 2,
+        abuse_max_failures: 3,
+        abuse_window_secs: 3600,
+        abuse_ban_secs: 600,
+        ..Config::default()
+    };
+    let state = AppState::new(pool.clone())
+        .with_clock(clock.clone())
+        .with_config(config.clone());
+    let bans = state.bans.clone();
+    let app =
+        create_router_with_state(state).layer(AbuseLayer::new(&config, pool, clock, bans.clone()));
+    (app, bans)
+}
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    admin_key: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let bearer = admin_key.map(|key| format!("Bearer {}", key));
+    let headers: Vec<(&str, &str)> = bearer
+        .iter()
+        .map(|bearer| ("Authorization", bearer.as_str()))
+        .collect();
+    common::send_with_headers(app, method, uri, &headers, body).await
+}
+
+fn event_body(title: &str) -> Value {
+    json!({
+        "title": title,
+        "description": null,
+        "organizer_name": "Alice",
+        "time_slots": [
+            { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+        ]
+    })
+}
+
+#[sqlx::test]
+async fn test_event_flood_bans_client(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(
+        Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap(),
+    ));
+    let (app, bans) = create_test_app(pool.clone(), clock.clone());
+
+    for title in ["One", "Two", "Three"] {
+        let (status, _) = send(&app, "POST", "/events", None, Some(event_body(title))).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    // The third event crossed the limit
+    let (status, body) = send(&app, "GET", "/health", None, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "BANNED");
+
+    let row = sqlx::query!("SELECT ip, banned_by, expires_at FROM ip_bans")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.ip, "127.0.0.1");
+    assert_eq!(row.banned_by, security::AUTO_BAN);
+    assert_eq!(
+        row.expires_at,
+        Utc.with_ymd_and_hms(2030, 1, 1, 0, 10, 0).unwrap()
+    );
+
+    // A restart reloads the ban from the database
+    let reloaded = SharedBans::default();
+    assert_eq!(
+        security::refresh_bans(&pool, clock.as_ref(), &reloaded)
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(security::banned_until(&reloaded, "127.0.0.1".parse().unwrap(), clock.now()).is_some());
+
+    // Bans lapse on their own
+    clock.advance(Duration::from_secs(601));
+    let (status, _) = send(&app, "GET", "/health", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    security::refresh_bans(&pool, clock.as_ref(), &bans)
+        .await
+        .unwrap();
+    let remaining = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM ip_bans"#)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+}
+
+#[sqlx::test]
+async fn test_rejected_requests_ban_client(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(
+        Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap(),
+    ));
+    let (app, _) = create_test_app(pool, clock.clone());
+
+    for _ in 0..3 {
+        let (status, _) = send(&app, "POST", "/events", None, Some(event_body(""))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    // A new window forgives earlier failures
+    clock.advance(Duration::from_secs(3601));
+    for _ in 0..3 {
+        let (status, _) = send(&app, "POST", "/events", None, Some(event_body(""))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+    let (status, _) = send(&app, "POST", "/events", None, Some(event_body(""))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(&app, "POST", "/events", None, Some(event_body("Ok"))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "BANNED");
+}
+
+#[sqlx::test]
+async fn test_admin_manages_bans(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(
+        Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap(),
+    ));
+    let (app, bans) = create_test_app(pool.clone(), clock.clone());
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/admin/bans",
+        Some(ADMIN_KEY),
+        Some(json!({ "ip": "not-an-ip" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/admin/bans",
+        Some(ADMIN_KEY),
+        Some(json!({ "ip": "2001:db8::1", "reason": "scraping", "duration_secs": 60 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["ip"], "2001:db8::1");
+    assert_eq!(body["reason"], "scraping");
+    assert_eq!(body["expires_at"], "2030-01-01T00:01:00Z");
+    assert!(security::banned_until(&bans, "2001:db8::1".parse().unwrap(), clock.now()).is_some());
+
+    // Without a duration the configured one applies
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/admin/bans",
+        Some(ADMIN_KEY),
+        Some(json!({ "ip": "192.0.2.7" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["expires_at"], "2030-01-01T00:10:00Z");
+
+    let (status, body) = send(&app, "GET", "/admin/bans", Some(ADMIN_KEY), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let listed: Vec<&str> = body["bans"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|ban| ban["ip"].as_str().unwrap())
+        .collect();
+    assert_eq!(listed, ["2001:db8::1", "192.0.2.7"]);
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        "/admin/bans/2001:db8::1",
+        Some(ADMIN_KEY),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(security::banned_until(&bans, "2001:db8::1".parse().unwrap(), clock.now()).is_none());
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        "/admin/bans/2001:db8::1",
+        Some(ADMIN_KEY),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, "GET", "/admin/bans", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let logged = sqlx::query_scalar!(
+        "SELECT action FROM admin_access_log WHERE action LIKE 'bans.%' ORDER BY id"
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        logged,
+        ["bans.create", "bans.create", "bans.list", "bans.delete"]
+    );
+}
@@ -0,0 +1,224 @@
+mod common;
+
+use agreed_time_backend::{config::Config, routes::create_router_with_state, state::AppState};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use common::{create_event, send_request};
+use serde_json::json;
+use sqlx::PgPool;
+
+const ADMIN_KEY: &str = "test-admin-key";
+
+fn create_test_app(pool: PgPool, admin_api_key: Option<&str>) -> Router {
+    let config = Config {
+        admin_api_key: admin_api_key.map(str::to_string),
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_config(config))
+}
+
+fn admin_get(uri: &str, key: Option<&str>) -> Request<Body> {
+    admin_request("GET", uri, key)
+}
+
+fn admin_request(method: &str, uri: &str, key: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(key) = key {
+        builder = builder.header("Authorization", format!("Bearer {}", key));
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[sqlx::test]
+async fn test_admin_routes_hidden_without_key(pool: PgPool) {
+    let app = create_test_app(pool, None);
+
+    let (status, _) = send_request(
+        &app,
+        admin_get("/admin/events/search?title=team", Some(ADMIN_KEY)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_admin_requires_valid_key(pool: PgPool) {
+    let app = create_test_app(pool, Some(ADMIN_KEY));
+
+    let (status, _) = send_request(&app, admin_get("/admin/events/search?title=team", None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = send_request(
+        &app,
+        admin_get("/admin/events/search?title=team", Some("wrong-key")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn test_search_events_by_token_prefix_and_title(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some(ADMIN_KEY));
+
+    let created = create_event(
+        &app,
+        json!({ "title": "Quarterly Planning", "organizer_name": "Organizer" }),
+    )
+    .await;
+    create_event(
+        &app,
+        json!({ "title": "Team Lunch", "organizer_name": "Organizer" }),
+    )
+    .await;
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    // Organizer token prefix finds the event and hands back its links
+    let (status, body) = send_request(
+        &app,
+        admin_get(
+            &format!(
+                "/admin/events/search?token_prefix={}",
+                &organizer_token[..8]
+            ),
+            Some(ADMIN_KEY),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let events = body["events"].as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["organizer_token"], organizer_token);
+    assert_eq!(events[0]["organizer_name"], "Organizer");
+
+    // Title fragment is case-insensitive
+    let (_, body) = send_request(
+        &app,
+        admin_get("/admin/events/search?title=planning", Some(ADMIN_KEY)),
+    )
+    .await;
+    assert_eq!(body["events"].as_array().unwrap().len(), 1);
+
+    // Every lookup is recorded
+    let logged =
+        sqlx::query_scalar!("SELECT COUNT(*) FROM admin_access_log WHERE action = 'events.search'")
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+            .unwrap_or(0);
+    assert_eq!(logged, 2);
+}
+
+#[sqlx::test]
+async fn test_search_events_requires_filter(pool: PgPool) {
+    let app = create_test_app(pool, Some(ADMIN_KEY));
+
+    let (status, _) = send_request(&app, admin_get("/admin/events/search", Some(ADMIN_KEY))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send_request(
+        &app,
+        admin_get("/admin/events/search?token_prefix=ab", Some(ADMIN_KEY)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_list_and_force_delete_events(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some(ADMIN_KEY));
+
+    let kept = create_event(
+        &app,
+        json!({ "title": "Kept", "organizer_name": "Organizer" }),
+    )
+    .await;
+    let doomed = create_event(
+        &app,
+        json!({ "title": "Doomed", "organizer_name": "Organizer" }),
+    )
+    .await;
+    let organizer_token = kept["organizer_token"].as_str().unwrap();
+    let (status, _) = send_request(
+        &app,
+        admin_request("POST", &format!("/events/{}/close", organizer_token), None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send_request(&app, admin_get("/admin/events", Some(ADMIN_KEY))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 2);
+
+    let (_, body) = send_request(
+        &app,
+        admin_get("/admin/events?state=closed&trashed=false", Some(ADMIN_KEY)),
+    )
+    .await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["events"][0]["title"], "Kept");
+
+    let (_, body) = send_request(
+        &app,
+        admin_get("/admin/events?limit=1&offset=1", Some(ADMIN_KEY)),
+    )
+    .await;
+    assert_eq!(body["events"].as_array().unwrap().len(), 1);
+    assert_eq!(body["total"], 2);
+
+    let uri = format!("/admin/events/{}", doomed["public_token"].as_str().unwrap());
+    let (status, _) = send_request(&app, admin_request("DELETE", &uri, Some(ADMIN_KEY))).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send_request(&app, admin_request("DELETE", &uri, Some(ADMIN_KEY))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (_, body) = send_request(&app, admin_get("/admin/events", Some(ADMIN_KEY))).await;
+    assert_eq!(body["total"], 1);
+
+    let deletes =
+        sqlx::query_scalar!("SELECT COUNT(*) FROM admin_access_log WHERE action = 'events.delete'")
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+            .unwrap_or(0);
+    assert_eq!(deletes, 1);
+}
+
+#[sqlx::test]
+async fn test_cleanup_rate_limits_and_stats(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some(ADMIN_KEY));
+    create_event(
+        &app,
+        json!({ "title": "Fresh", "organizer_name": "Organizer" }),
+    )
+    .await;
+
+    let (status, body) = send_request(
+        &app,
+        admin_request("POST", "/admin/cleanup", Some(ADMIN_KEY)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({ "trashed": 0, "purged": 0, "archived": 0 }));
+    let (_, jobs) = send_request(&app, admin_get("/admin/jobs", Some(ADMIN_KEY))).await;
+    assert_eq!(jobs["runs"].as_array().unwrap().len(), 3);
+
+    let (status, body) = send_request(&app, admin_get("/admin/rate-limits", Some(ADMIN_KEY))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["store"], "memory");
+    assert_eq!(body["routes"][0]["group"], "default");
+    assert_eq!(body["routes"][0]["requests"], 60);
+    assert_eq!(body["routes"][0]["window_secs"], 60);
+
+    let (status, body) = send_request(&app, admin_get("/admin/stats", Some(ADMIN_KEY))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["events_by_state"]["open"], 1);
+    assert_eq!(body["events_created_last_24h"], 1);
+    assert_eq!(body["participants"], 0);
+    assert_eq!(body["availabilities"], 1);
+    assert_eq!(body["events_expiring_next_24h"], 0);
+    assert_eq!(body["events_expiring_next_7d"], 1);
+    assert_eq!(body["users"], 0);
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send, submit};
+use serde_json::json;
+use sqlx::PgPool;
+
+/// Alice offers 9–11; Bob can make 9–10, Carol 9–10 only if need be.
+async fn create_answered_event(app: &Router, anonymous_results: Option<bool>) -> (String, String) {
+    let (public_token, organizer_token) = create_event_tokens(
+        app,
+        json!({
+            "title": "Salary review",
+            "anonymous_results": anonymous_results,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    for (name, level) in [("Bob", "available"), ("Carol", "if_need_be")] {
+        submit(
+            app,
+            &public_token,
+            json!({
+                "participant_name": name,
+                "comment": "private note",
+                "availabilities": [{
+                    "start_at": "2030-01-01T09:00:00Z",
+                    "end_at": "2030-01-01T10:00:00Z",
+                    "availability_level": level
+                }]
+            }),
+        )
+        .await;
+    }
+    (public_token, organizer_token)
+}
+
+#[sqlx::test]
+async fn test_anonymous_results_show_counts_without_names(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = create_answered_event(&app, Some(true)).await;
+
+    let (status, event) = send(&app, "GET", &format!("/events/{}", public_token), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["anonymous_results"], true);
+
+    let (status, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results["anonymous_results"], true);
+    assert_eq!(results["results_hidden"], false);
+    assert_eq!(results["total_participants"], 3);
+    assert_eq!(results["participants"], json!([]));
+    assert_eq!(
+        results["slot_counts"],
+        json!([
+            {
+                "start_at": "2030-01-01T09:00:00Z",
+                "end_at": "2030-01-01T10:00:00Z",
+                "available": 2,
+                "if_need_be": 1
+            },
+            {
+                "start_at": "2030-01-01T10:00:00Z",
+                "end_at": "2030-01-01T11:00:00Z",
+                "available": 1,
+                "if_need_be": 0
+            }
+        ])
+    );
+    let dump = results.to_string();
+    for secret in ["Bob", "Carol", "private note"] {
+        assert!(!dump.contains(secret), "{} leaked", secret);
+    }
+
+    let (_, suggestions) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/suggestions", public_token),
+        json!({}),
+    )
+    .await;
+    let best = &suggestions["suggestions"][0];
+    assert_eq!(best["count"], 2);
+    assert_eq!(best["participants"], json!(["Anonymous", "Anonymous"]));
+    assert_eq!(best["if_need_be"], json!(["Anonymous"]));
+
+    let (_, summary) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/summary", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(
+        summary["respondents"],
+        json!(["Anonymous", "Anonymous", "Anonymous"])
+    );
+    assert!(!summary.to_string().contains("Bob"));
+
+    // The organizer still sees who said what
+    let (status, organizer) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(organizer["anonymous_results"], true);
+    let names: Vec<&str> = organizer["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Alice", "Bob", "Carol"]);
+    assert_eq!(organizer["participants"][1]["comment"], "private note");
+}
+
+#[sqlx::test]
+async fn test_named_results_by_default(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_answered_event(&app, None).await;
+
+    let (_, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(results["anonymous_results"], false);
+    assert_eq!(results["participants"].as_array().unwrap().len(), 3);
+    assert_eq!(results["slot_counts"][0]["available"], 2);
+    assert_eq!(results["slot_counts"][0]["if_need_be"], 1);
+
+    let (_, suggestions) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/suggestions", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(
+        suggestions["suggestions"][0]["participants"],
+        json!(["Alice", "Bob"])
+    );
+}
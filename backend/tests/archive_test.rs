@@ -0,0 +1,164 @@
+mod common;
+
+use agreed_time_backend::{clock::TestClock, db::cleanup::archive_finished_events};
+use axum::{Router, http::StatusCode};
+use chrono::{TimeZone, Utc};
+use common::{create_event, create_event_tokens, send, submit};
+use serde_json::json;
+use sqlx::PgPool;
+
+/// Creates an event with one participant and finalizes 10:00–11:00 UTC on 2030-01-01.
+async fn create_finalized_event(app: &Router) -> (String, String, String) {
+    let (public_token, organizer_token) =
+        create_event_tokens(app, json!({ "organizer_name": "Organizer" })).await;
+    let submitted = submit(
+        app,
+        &public_token,
+        json!({
+            "participant_name": "Guest",
+            "availabilities": [
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    let participant_token = submitted["participant_token"].as_str().unwrap().to_string();
+
+    let (status, _) = send(
+        app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        Some(json!({
+            "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    (public_token, organizer_token, participant_token)
+}
+
+#[sqlx::test]
+async fn test_archived_event_rejects_mutations(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let (public_token, organizer_token, participant_token) = create_finalized_event(&app).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/archive", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["state"], "archived");
+
+    let availability = json!({
+        "participant_name": "Late",
+        "availabilities": [
+            { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+        ],
+        "comment": null
+    });
+    let mutations = [
+        (
+            "POST",
+            format!("/events/{}/availability", public_token),
+            Some(availability.clone()),
+        ),
+        (
+            "PUT",
+            format!(
+                "/events/{}/participants/{}",
+                public_token, participant_token
+            ),
+            Some(availability),
+        ),
+        ("POST", format!("/events/{}/close", organizer_token), None),
+        (
+            "POST",
+            format!("/events/{}/finalize", organizer_token),
+            Some(json!({
+                "slots": [{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }]
+            })),
+        ),
+        ("POST", format!("/events/{}/archive", organizer_token), None),
+        (
+            "POST",
+            format!("/events/{}/edit-lock", organizer_token),
+            Some(json!({ "holder_name": "Organizer" })),
+        ),
+        (
+            "POST",
+            format!("/events/{}/transfer", organizer_token),
+            None,
+        ),
+    ];
+    for (method, uri, body) in mutations {
+        let (status, body) = send(&app, method, &uri, body).await;
+        assert_eq!(status, StatusCode::CONFLICT, "{} {}", method, uri);
+        assert_eq!(body["code"], "ARCHIVED", "{} {}", method, uri);
+    }
+
+    // Reads and exports stay available
+    let (status, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results["state"], "archived");
+    assert_eq!(results["final_slots"].as_array().unwrap().len(), 1);
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/export.ics", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_only_finalized_events_can_be_archived(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let created = create_event(&app, json!({ "title": "Still open" })).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!(
+            "/events/{}/archive",
+            created["organizer_token"].as_str().unwrap()
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "CONFLICT");
+}
+
+#[sqlx::test]
+async fn test_scheduled_archive_after_final_slot_ends(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool.clone());
+    let (public_token, _, _) = create_finalized_event(&app).await;
+
+    // Final slot ends 2030-01-01T11:00Z; not yet a day past it
+    let clock = TestClock::new(Utc.with_ymd_and_hms(2030, 1, 2, 10, 0, 0).unwrap());
+    assert_eq!(archive_finished_events(&pool, &clock).await.unwrap(), 0);
+
+    clock.set(Utc.with_ymd_and_hms(2030, 1, 2, 12, 0, 0).unwrap());
+    assert_eq!(archive_finished_events(&pool, &clock).await.unwrap(), 1);
+
+    let (_, event) = send(&app, "GET", &format!("/events/{}", public_token), None).await;
+    assert_eq!(event["state"], "archived");
+
+    let actors: Vec<String> =
+        sqlx::query_scalar("SELECT actor FROM event_audit_log WHERE action = 'archived'")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(actors, vec!["system"]);
+}
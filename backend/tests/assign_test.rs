@@ -0,0 +1,221 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send, submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// Two interview slots at 09:00 and 10:00, each needing one host. The
+/// organizer (always available) has no role.
+async fn create_interview_event(app: &Router) -> (String, String) {
+    let (public_token, organizer_token) = create_event_tokens(
+        app,
+        json!({
+            "title": "Interviews",
+            "organizer_name": "Coordinator",
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ],
+            "roles": [{ "name": "host", "required": 1 }]
+        }),
+    )
+    .await;
+    for (name, end) in [
+        ("Hana", "2030-01-01T10:00:00Z"),
+        ("Hugo", "2030-01-01T11:00:00Z"),
+    ] {
+        submit(
+            app,
+            &public_token,
+            json!({
+                "participant_name": name,
+                "role": "host",
+                "availabilities": [{ "start_at": "2030-01-01T09:00:00Z", "end_at": end }]
+            }),
+        )
+        .await;
+    }
+    (public_token, organizer_token)
+}
+
+async fn finalize_both_slots(app: &Router, organizer_token: &str) {
+    let (status, _) = send(
+        app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        json!({
+            "slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" },
+                { "start_at": "2030-01-01T10:30:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+fn attendee_names(slot: &Value) -> Vec<&str> {
+    slot["attendees"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["name"].as_str().unwrap())
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_assign_requires_finalized_event(pool: PgPool) {
+    let app = create_router(pool);
+    let (_, organizer_token) = create_interview_event(&app).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/assign", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = send(&app, "POST", "/events/nope/assign", json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_assign_staffs_every_slot(pool: PgPool) {
+    let app = create_router(pool);
+    let (_, organizer_token) = create_interview_event(&app).await;
+    finalize_both_slots(&app, &organizer_token).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/assign", organizer_token),
+        json!({ "capacity": 2 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["confirmed_at"], Value::Null);
+
+    // Hana can only host at 09:00, so Hugo takes the later slot
+    let slots = body["slots"].as_array().unwrap();
+    assert_eq!(slots.len(), 2);
+    assert!(attendee_names(&slots[0]).contains(&"Hana"));
+    assert!(attendee_names(&slots[1]).contains(&"Hugo"));
+    for slot in slots {
+        assert_eq!(slot["roles"][0]["filled"].as_array().unwrap().len(), 1);
+        for attendee in slot["attendees"].as_array().unwrap() {
+            assert_eq!(attendee["availability_level"], "available");
+        }
+    }
+    assert_eq!(body["unassigned"], json!([]));
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/assign", organizer_token),
+        json!({ "capacity": 0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_edit_and_confirm_assignment(pool: PgPool) {
+    let app = create_router(pool);
+    let (_, organizer_token) = create_interview_event(&app).await;
+    finalize_both_slots(&app, &organizer_token).await;
+
+    let (_, proposed) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/assign", organizer_token),
+        json!({}),
+    )
+    .await;
+    let late_slot = proposed["slots"][1]["final_slot_id"].as_i64().unwrap();
+    let hana = proposed["slots"][0]["attendees"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["name"] == "Hana")
+        .unwrap()["participant_id"]
+        .as_i64()
+        .unwrap();
+
+    // Moving Hana to a slot she can't make is allowed but flagged
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/events/{}/assign", organizer_token),
+        json!({ "slots": [{ "final_slot_id": late_slot, "participant_ids": [hana] }] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["slots"][0]["attendees"], json!([]));
+    assert_eq!(
+        body["slots"][1]["attendees"][0]["availability_level"],
+        "unavailable"
+    );
+    assert_eq!(body["unassigned"].as_array().unwrap().len(), 2);
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/events/{}/assign", organizer_token),
+        json!({ "slots": [{ "final_slot_id": late_slot, "participant_ids": [hana, hana] }] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/events/{}/assign", organizer_token),
+        json!({ "slots": [{ "final_slot_id": late_slot, "participant_ids": [-1] }] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/events/{}/assign", organizer_token),
+        json!({ "slots": [{ "final_slot_id": late_slot, "participant_ids": [hana] }], "confirm": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["confirmed_at"].is_string());
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/assign", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/assign", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(attendee_names(&body["slots"][1]), vec!["Hana"]);
+
+    // Re-finalizing starts over
+    finalize_both_slots(&app, &organizer_token).await;
+    let (_, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/assign", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(body["confirmed_at"], Value::Null);
+    assert_eq!(body["unassigned"].as_array().unwrap().len(), 3);
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use agreed_time_backend::{clock::TestClock, routes::create_router_with_state, state::AppState};
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use common::send;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn now() -> DateTime<Utc> {
+    "2029-12-01T00:00:00Z".parse().unwrap()
+}
+
+#[sqlx::test]
+async fn test_audit_log_records_organizer_and_participant_actions(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_router_with_state(AppState::new(pool).with_clock(clock.clone()));
+    let tick = || clock.advance(Duration::from_secs(60));
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Offsite",
+            "organizer_name": "Alice",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    tick();
+    let availability = json!([
+        { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }
+    ]);
+    let (status, submitted) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({ "participant_name": "Carol", "availabilities": availability }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let participant_token = submitted["participant_token"].as_str().unwrap();
+
+    tick();
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!(
+            "/events/{}/participants/{}",
+            public_token, participant_token
+        ),
+        json!({ "participant_name": "Carol J", "availabilities": availability }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    tick();
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    // Closing again changes nothing, so it is not logged
+    send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+
+    tick();
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        json!({
+            "slots": [{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    tick();
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/extend", organizer_token),
+        json!({ "days": 3 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/audit", organizer_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let entries = body["entries"].as_array().unwrap();
+    let summary: Vec<(&str, &str, Option<&str>)> = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry["action"].as_str().unwrap(),
+                entry["actor"].as_str().unwrap(),
+                entry["details"].as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                "extended",
+                "organizer",
+                Some("2029-12-08T00:00:00+00:00 -> 2029-12-11T00:00:00+00:00")
+            ),
+            ("finalized", "organizer", Some("revision 1")),
+            ("closed", "organizer", None),
+            ("participant_updated", "Carol J", None),
+            ("availability_submitted", "Carol", Some("confirmed")),
+            ("created", "organizer", None),
+        ]
+    );
+    assert_eq!(entries[5]["created_at"], "2029-12-01T00:00:00Z");
+    assert_eq!(entries[4]["created_at"], "2029-12-01T00:01:00Z");
+}
+
+#[sqlx::test]
+async fn test_audit_log_needs_organizer_token(pool: PgPool) {
+    let app = create_router_with_state(AppState::new(pool));
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Offsite",
+            "organizer_name": "Alice",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let public_token = created["public_token"].as_str().unwrap();
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/audit", public_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
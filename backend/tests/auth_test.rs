@@ -0,0 +1,342 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock, config::Config, routes::create_router_with_state, state::AppState,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use chrono::{TimeZone, Utc};
+use common::event_body;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt; // for `oneshot`
+
+const JWT_SECRET: &str = "test-jwt-secret";
+
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>, login: bool) -> Router {
+    let config = Config {
+        mail_from: login.then(|| "polls@example.com".to_string()),
+        public_url: login.then(|| "https://meet.example.com".to_string()),
+        jwt_secret: login.then(|| JWT_SECRET.to_string()),
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_clock(clock).with_config(config))
+}
+
+/// Sends a request with optional credentials, a session cookie or a
+/// `Bearer …` token; returns the status, the `Set-Cookie` header and the
+/// JSON body.
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    credentials: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Option<String>, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    match credentials {
+        Some(bearer) if bearer.starts_with("Bearer ") => {
+            request = request.header(header::AUTHORIZATION, bearer);
+        }
+        Some(cookie) => request = request.header(header::COOKIE, cookie),
+        None => {}
+    }
+    let body = match body {
+        Some(body) => {
+            request = request.header("Content-Type", "application/json");
+            Body::from(serde_json::to_vec(&body).unwrap())
+        }
+        None => Body::empty(),
+    };
+    let response = app
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let set_cookie = response
+        .headers()
+        .get(header::SET_COOKIE)
+        .map(|value| value.to_str().unwrap().to_string());
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        set_cookie,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+/// Tokens of the login links mailed so far, oldest first.
+async fn mailed_tokens(pool: &PgPool) -> Vec<String> {
+    sqlx::query_scalar!("SELECT body FROM email_outbox WHERE event_id IS NULL ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .iter()
+        .map(|body| {
+            let (_, rest) = body.split_once("/auth/verify?token=").unwrap();
+            rest.chars()
+                .take_while(char::is_ascii_alphanumeric)
+                .collect()
+        })
+        .collect()
+}
+
+async fn create_event(app: &Router, title: &str, cookie: Option<&str>) -> Value {
+    let (status, _, body) = send(
+        app,
+        "POST",
+        "/events",
+        cookie,
+        Some(event_body(json!({ "title": title }))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    body
+}
+
+#[sqlx::test]
+async fn test_magic_link_login_keeps_events(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(
+        Utc.with_ymd_and_hms(2029, 12, 1, 12, 0, 0).unwrap(),
+    ));
+    let app = create_test_app(pool.clone(), clock, true);
+
+    let (status, _, _) = send(
+        &app,
+        "POST",
+        "/auth/magic-link",
+        None,
+        Some(json!({ "email": " Alice@Example.com " })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let recipient = sqlx::query_scalar!("SELECT recipient FROM email_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(recipient, "alice@example.com");
+    let token = mailed_tokens(&pool).await.remove(0);
+
+    let verify = format!("/auth/verify?token={}", token);
+    let (status, set_cookie, user) = send(&app, "GET", &verify, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["email"], "alice@example.com");
+    let set_cookie = set_cookie.unwrap();
+    assert!(set_cookie.contains("HttpOnly"));
+    let cookie = set_cookie.split(';').next().unwrap().to_string();
+
+    // Links are single-use
+    let (status, _, _) = send(&app, "GET", &verify, None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _, me) = send(&app, "GET", "/auth/me", Some(&cookie), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(me["email"], "alice@example.com");
+
+    let owned = create_event(&app, "Owned", Some(&cookie)).await;
+    create_event(&app, "Anonymous", None).await;
+
+    let (status, _, page) = send(&app, "GET", "/auth/events", Some(&cookie), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["events"][0]["title"], "Owned");
+    assert_eq!(
+        page["events"][0]["organizer_token"],
+        owned["organizer_token"]
+    );
+
+    // A second login finds the same user and its events
+    send(
+        &app,
+        "POST",
+        "/auth/magic-link",
+        None,
+        Some(json!({ "email": "alice@example.com" })),
+    )
+    .await;
+    let token = mailed_tokens(&pool).await.remove(1);
+    let (_, set_cookie, _) = send(
+        &app,
+        "GET",
+        &format!("/auth/verify?token={}", token),
+        None,
+        None,
+    )
+    .await;
+    let other_cookie = set_cookie.unwrap().split(';').next().unwrap().to_string();
+    let (_, _, page) = send(&app, "GET", "/auth/events", Some(&other_cookie), None).await;
+    assert_eq!(page["total"], 1);
+
+    let (status, set_cookie, _) = send(&app, "POST", "/auth/logout", Some(&cookie), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(set_cookie.unwrap().contains("Max-Age=0"));
+    let (status, _, _) = send(&app, "GET", "/auth/me", Some(&cookie), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, _) = send(&app, "GET", "/auth/events", Some(&cookie), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn test_magic_link_limits(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(
+        Utc.with_ymd_and_hms(2029, 12, 1, 12, 0, 0).unwrap(),
+    ));
+
+    let disabled = create_test_app(pool.clone(), clock.clone(), false);
+    let (status, _, _) = send(
+        &disabled,
+        "POST",
+        "/auth/magic-link",
+        None,
+        Some(json!({ "email": "alice@example.com" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let app = create_test_app(pool.clone(), clock.clone(), true);
+    let (status, _, _) = send(
+        &app,
+        "POST",
+        "/auth/magic-link",
+        None,
+        Some(json!({ "email": "not-an-address" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Past three pending links the answer stays the same but nothing is sent
+    for _ in 0..4 {
+        let (status, _, _) = send(
+            &app,
+            "POST",
+            "/auth/magic-link",
+            None,
+            Some(json!({ "email": "alice@example.com" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+    }
+    let tokens = mailed_tokens(&pool).await;
+    assert_eq!(tokens.len(), 3);
+
+    clock.advance(Duration::from_secs(16 * 60));
+    let (status, _, _) = send(
+        &app,
+        "GET",
+        &format!("/auth/verify?token={}", tokens[0]),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Expired links no longer count against the address
+    send(
+        &app,
+        "POST",
+        "/auth/magic-link",
+        None,
+        Some(json!({ "email": "alice@example.com" })),
+    )
+    .await;
+    assert_eq!(mailed_tokens(&pool).await.len(), 4);
+}
+
+#[sqlx::test]
+async fn test_login_link_opens_a_frontend_page(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(Utc::now()));
+    let app = create_test_app(pool.clone(), clock, true);
+    send(
+        &app,
+        "POST",
+        "/auth/magic-link",
+        None,
+        Some(json!({ "email": "alice@example.com" })),
+    )
+    .await;
+
+    let body = sqlx::query_scalar!("SELECT body FROM email_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let link = body
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("https://meet.example.com"))
+        .unwrap();
+    assert!(link.starts_with("/auth/verify?token="), "{}", link);
+    assert!(common::frontend_has_page(link), "{}", link);
+}
+
+/// Logs `email` in through a mailed link and returns the session cookie.
+async fn log_in(app: &Router, pool: &PgPool, email: &str) -> String {
+    send(
+        app,
+        "POST",
+        "/auth/magic-link",
+        None,
+        Some(json!({ "email": email })),
+    )
+    .await;
+    let token = mailed_tokens(pool).await.pop().unwrap();
+    let (_, set_cookie, _) = send(
+        app,
+        "GET",
+        &format!("/auth/verify?token={}", token),
+        None,
+        None,
+    )
+    .await;
+    set_cookie.unwrap().split(';').next().unwrap().to_string()
+}
+
+#[sqlx::test]
+async fn test_bearer_tokens(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(
+        Utc.with_ymd_and_hms(2029, 12, 1, 12, 0, 0).unwrap(),
+    ));
+    let app = create_test_app(pool.clone(), clock.clone(), true);
+    let cookie = log_in(&app, &pool, "alice@example.com").await;
+
+    let (status, _, _) = send(&app, "POST", "/auth/token", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, issued) = send(&app, "POST", "/auth/token", Some(&cookie), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(issued["expires_at"], "2029-12-01T13:00:00Z");
+    let bearer = format!("Bearer {}", issued["token"].as_str().unwrap());
+
+    let (status, _, me) = send(&app, "GET", "/auth/me", Some(&bearer), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(me["email"], "alice@example.com");
+
+    // A token can't mint another one
+    let (status, _, _) = send(&app, "POST", "/auth/token", Some(&bearer), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    create_event(&app, "Via API", Some(&bearer)).await;
+    let (_, _, page) = send(&app, "GET", "/auth/events", Some(&cookie), None).await;
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["events"][0]["title"], "Via API");
+
+    // A bad token is refused even where authentication is optional
+    let (status, _, _) = send(&app, "GET", "/capabilities", Some("Bearer nonsense"), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    clock.advance(Duration::from_secs(2 * 3600));
+    let (status, _, _) = send(&app, "GET", "/auth/me", Some(&bearer), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Without a key, tokens aren't issued and the header is ignored
+    let disabled = create_test_app(pool.clone(), clock, false);
+    let (status, _, _) = send(&disabled, "POST", "/auth/token", Some(&cookie), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = send(&disabled, "GET", "/capabilities", Some(&bearer), None).await;
+    assert_eq!(status, StatusCode::OK);
+}
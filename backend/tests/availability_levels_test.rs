@@ -0,0 +1,161 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::http::StatusCode;
+use common::{create_event_tokens, send, try_submit};
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn test_levels_are_stored_and_merged_per_level(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+
+    let (status, submitted) = try_submit(
+        &app,
+        &public_token,
+        json!({
+            "participant_name": "Ann",
+            "availabilities": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" },
+                { "start_at": "2030-01-01T09:30:00Z", "end_at": "2030-01-01T10:00:00Z", "availability_level": "available" },
+                // Touches the "yes" range but must not merge into it
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z", "availability_level": "if_need_be" },
+                { "start_at": "2030-01-01T11:00:00Z", "end_at": "2030-01-01T12:00:00Z", "availability_level": "unavailable" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, participant) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/{}/participants/{}",
+            public_token,
+            submitted["participant_token"].as_str().unwrap()
+        ),
+        json!({}),
+    )
+    .await;
+    let levels: Vec<(&str, &str)> = participant["availabilities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["start_at"].as_str().unwrap(),
+                r["availability_level"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        levels,
+        vec![
+            ("2030-01-01T09:00:00Z", "available"),
+            ("2030-01-01T10:00:00Z", "if_need_be"),
+            ("2030-01-01T11:00:00Z", "unavailable"),
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn test_overlapping_levels_are_rejected(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+
+    let (status, _) = try_submit(
+        &app,
+        &public_token,
+        json!({
+            "participant_name": "Ann",
+            "availabilities": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T11:00:00Z" },
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T12:00:00Z", "availability_level": "unavailable" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_suggestions_weight_if_need_be(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+
+    for name in ["Ann", "Ben"] {
+        let (status, _) = try_submit(
+            &app,
+            &public_token,
+            json!({
+                "participant_name": name,
+                "availabilities": [
+                    { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z", "availability_level": "unavailable" },
+                    { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z", "availability_level": "if_need_be" }
+                ]
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, _) = try_submit(
+        &app,
+        &public_token,
+        json!({
+            "participant_name": "Cat",
+            "availabilities": [{ "start_at": "2030-01-01T11:00:00Z", "end_at": "2030-01-01T12:00:00Z" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/suggestions", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let suggestions = body["suggestions"].as_array().unwrap();
+
+    // 10:00: organizer + two "maybe"s (2+1+1) beats 11:00: organizer + Cat
+    // (2+2) on start time; 09:00 has only the organizer, whose "no"s don't count
+    let ranked: Vec<(&str, u64)> = suggestions
+        .iter()
+        .map(|s| {
+            (
+                s["start_at"].as_str().unwrap(),
+                s["score"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        ranked,
+        vec![
+            ("2030-01-01T10:00:00Z", 4),
+            ("2030-01-01T11:00:00Z", 4),
+            ("2030-01-01T09:00:00Z", 2),
+        ]
+    );
+    assert_eq!(suggestions[0]["count"], 1);
+    assert_eq!(suggestions[0]["if_need_be"], json!(["Ann", "Ben"]));
+    assert_eq!(suggestions[0]["missing"], json!(["Cat"]));
+    assert_eq!(suggestions[2]["missing"], json!(["Ann", "Ben", "Cat"]));
+
+    let (_, summary) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/summary", public_token),
+        json!({}),
+    )
+    .await;
+    assert!(
+        summary["without_availability"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+}
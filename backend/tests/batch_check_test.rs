@@ -1,6 +1,7 @@
 use agreed_time_backend::models::{
-    BatchCheckStatusRequest, BatchCheckStatusResponse, CreateEventRequest, CreateEventResponse,
-    TimeRangeRequest,
+    BatchCheckParticipantsRequest, BatchCheckParticipantsResponse, BatchCheckStatusRequest,
+    BatchCheckStatusResponse, CreateEventRequest, CreateEventResponse, EventState,
+    SubmitAvailabilityRequest, SubmitAvailabilityResponse, TimeRangeRequest,
 };
 use axum::{
     Router,
@@ -32,7 +33,30 @@ async fn test_batch_check_status(pool: PgPool) {
             time_slots: vec![TimeRangeRequest {
                 start_at: Utc::now() + Duration::hours(1),
                 end_at: Utc::now() + Duration::hours(2),
+                availability_level: None,
             }],
+            results_visibility: None,
+            reveal_results_at: None,
+            blind_until_closed: None,
+            anonymous_results: None,
+            roles: vec![],
+            finalization_rules: Default::default(),
+            webhook_url: None,
+            slack_webhook_url: None,
+            discord_webhook_url: None,
+            chat_webhook_url: None,
+            password: None,
+            close_at_quorum: None,
+            organizer_email: None,
+            invitees: Vec::new(),
+            slot_kind: None,
+            weekly_slots: vec![],
+            poll_type: None,
+            dates: vec![],
+            locale: None,
+            organizer_account_token: None,
+            retention_days: None,
+            captcha_token: None,
         };
 
         let response = app
@@ -89,7 +113,7 @@ async fn test_batch_check_status(pool: PgPool) {
     assert_eq!(body.statuses.len(), 3);
     for i in 0..3 {
         assert!(body.statuses.contains_key(&tokens[i]));
-        assert_eq!(body.statuses.get(&tokens[i]).unwrap(), "open");
+        assert_eq!(body.statuses.get(&tokens[i]).unwrap(), &EventState::Open);
     }
     // Should NOT contain the fake token
     assert!(!body.statuses.contains_key(&fake_token));
@@ -119,3 +143,124 @@ async fn test_batch_check_limit(pool: PgPool) {
     // Should fail with BadRequest
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
+
+#[sqlx::test]
+async fn test_batch_check_participants(pool: PgPool) {
+    let app = create_test_app(pool.clone()).await;
+
+    // 1. Create an event and one participant
+    let req = CreateEventRequest {
+        title: "Participant Check".to_string(),
+        description: None,
+        organizer_name: "Test Organizer".to_string(),
+        time_zone: None,
+        slot_duration: None,
+        time_slots: vec![TimeRangeRequest {
+            start_at: Utc::now() + Duration::hours(1),
+            end_at: Utc::now() + Duration::hours(2),
+            availability_level: None,
+        }],
+        results_visibility: None,
+        reveal_results_at: None,
+        blind_until_closed: None,
+        anonymous_results: None,
+        roles: vec![],
+        finalization_rules: Default::default(),
+        webhook_url: None,
+        slack_webhook_url: None,
+        discord_webhook_url: None,
+        chat_webhook_url: None,
+        password: None,
+        close_at_quorum: None,
+        organizer_email: None,
+        invitees: Vec::new(),
+        slot_kind: None,
+        weekly_slots: vec![],
+        poll_type: None,
+        dates: vec![],
+        locale: None,
+        organizer_account_token: None,
+        retention_days: None,
+        captcha_token: None,
+    };
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/events")
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_string(&req).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let event: CreateEventResponse = serde_json::from_slice(&body_bytes).unwrap();
+
+    let submit = SubmitAvailabilityRequest {
+        participant_name: "Guest".to_string(),
+        availabilities: vec![],
+        comment: None,
+        buffer_minutes: None,
+        time_zone: None,
+        participant_token: None,
+        email: None,
+        role: None,
+        password: None,
+        dates: vec![],
+        captcha_token: None,
+    };
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/events/{}/availability", event.public_token))
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_string(&submit).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let submitted: SubmitAvailabilityResponse = serde_json::from_slice(&body_bytes).unwrap();
+    let participant_token = submitted.participant_token.to_string();
+
+    // 2. Check the real token alongside an unknown and a malformed one
+    let check_req = BatchCheckParticipantsRequest {
+        tokens: vec![
+            participant_token.clone(),
+            uuid::Uuid::new_v4().to_string(),
+            "not-a-uuid".to_string(),
+        ],
+    };
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/participants/batch-check")
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_string(&check_req).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: BatchCheckParticipantsResponse = serde_json::from_slice(&body_bytes).unwrap();
+
+    // 3. Only the real token comes back, with its event and name
+    assert_eq!(body.participants.len(), 1);
+    let status = &body.participants[&participant_token];
+    assert_eq!(status.public_token, event.public_token);
+    assert_eq!(status.name, "Guest");
+    assert_eq!(status.state, EventState::Open);
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::http::StatusCode;
+use common::{create_event_tokens, send};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+fn entry(name: &str) -> Value {
+    json!({
+        "participant_name": name,
+        "availabilities": [
+            { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }
+        ]
+    })
+}
+
+#[sqlx::test]
+async fn test_batch_stores_valid_entries_and_reports_the_rest(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+    let (other_event, _) = create_event_tokens(&app, json!({})).await;
+    let (_, outsider) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", other_event),
+        entry("Eve"),
+    )
+    .await;
+
+    let mut foreign = entry("Mallory");
+    foreign["participant_token"] = outsider["participant_token"].clone();
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability/batch", public_token),
+        json!([entry("Bob"), entry("  "), foreign, entry("Carol")]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+
+    for (index, result) in results.iter().enumerate() {
+        assert_eq!(result["index"], index);
+    }
+    assert_eq!(results[0]["status"], "confirmed");
+    assert!(results[0]["participant_token"].is_string());
+    assert!(results[0]["error"].is_null());
+    assert_eq!(results[1]["error"]["code"], "VALIDATION_FAILED");
+    assert_eq!(
+        results[1]["error"]["details"][0]["field"],
+        "participant_name"
+    );
+    assert!(results[1]["participant_token"].is_null());
+    assert_eq!(results[2]["error"]["code"], "NOT_FOUND");
+    assert_eq!(results[3]["status"], "confirmed");
+
+    let (_, event_results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        json!({}),
+    )
+    .await;
+    let names: Vec<&str> = event_results["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Alice", "Bob", "Carol"]);
+
+    // Stored entries can be edited with their token like any other response
+    let bob_token = results[0]["participant_token"].as_str().unwrap();
+    let (status, participant) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/participants/{}", public_token, bob_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(participant["name"], "Bob");
+}
+
+#[sqlx::test]
+async fn test_batch_size_limits(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+    let uri = format!("/events/{}/availability/batch", public_token);
+
+    let (status, _) = send(&app, "POST", &uri, json!([])).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let too_many: Vec<Value> = (0..21).map(|i| entry(&format!("P{}", i))).collect();
+    let (status, _) = send(&app, "POST", &uri, json!(too_many)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Past the participant limit entries go to the waitlist, then fail
+    let full: Vec<Value> = (0..20).map(|i| entry(&format!("P{}", i))).collect();
+    let (status, body) = send(&app, "POST", &uri, json!(full)).await;
+    assert_eq!(status, StatusCode::OK);
+    let statuses: Vec<Value> = body["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["status"].clone())
+        .collect();
+    assert_eq!(statuses[8], "confirmed");
+    assert_eq!(statuses[9], "pending");
+    assert_eq!(statuses[18], "pending");
+    assert!(statuses[19].is_null());
+    assert_eq!(
+        body["results"][19]["error"]["code"],
+        "PARTICIPANT_LIMIT_REACHED"
+    );
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/events/unknown/availability/batch",
+        json!([entry("Bob")]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock, db::reveal::notify_revealed_results, integrations::templates::Templates,
+    routes::create_router_with_state, state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use chrono::{DateTime, Utc};
+use common::{create_event_tokens, send, submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>) -> Router {
+    create_router_with_state(AppState::new(pool).with_clock(clock))
+}
+
+fn now() -> DateTime<Utc> {
+    "2029-12-01T00:00:00Z".parse().unwrap()
+}
+
+/// Creates a blind poll and adds Bob, who leaves an email address.
+async fn create_blind_poll(app: &Router, mut blind: Value) -> (String, String) {
+    blind["title"] = json!("Retro");
+    let (public_token, organizer_token) = create_event_tokens(app, blind).await;
+    submit(
+        app,
+        &public_token,
+        json!({
+            "email": "bob@example.com",
+            "availabilities": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    (public_token, organizer_token)
+}
+
+async fn assert_hidden(app: &Router, public_token: &str, hidden: bool) {
+    let (status, results) = send(
+        app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results["results_hidden"], hidden);
+    assert_eq!(results["total_participants"], 2);
+    let listed = results["participants"].as_array().unwrap().len();
+    assert_eq!(listed, if hidden { 0 } else { 2 });
+
+    let (_, suggestions) = send(
+        app,
+        "GET",
+        &format!("/events/{}/suggestions", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(
+        suggestions["suggestions"].as_array().unwrap().is_empty(),
+        hidden
+    );
+
+    let (_, summary) = send(
+        app,
+        "GET",
+        &format!("/events/{}/summary", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(summary["results_hidden"], hidden);
+    assert_eq!(
+        summary["respondents"].as_array().unwrap().is_empty(),
+        hidden
+    );
+}
+
+#[sqlx::test]
+async fn test_results_hidden_until_reveal_time(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool, clock.clone());
+    let (public_token, organizer_token) =
+        create_blind_poll(&app, json!({ "reveal_results_at": "2029-12-02T00:00:00Z" })).await;
+
+    let (_, event) = send(&app, "GET", &format!("/events/{}", public_token), json!({})).await;
+    assert_eq!(event["reveal_results_at"], "2029-12-02T00:00:00Z");
+    assert_eq!(event["blind_until_closed"], false);
+
+    assert_hidden(&app, &public_token, true).await;
+
+    // The organizer always sees everyone
+    let (_, organizer) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(organizer["participants"].as_array().unwrap().len(), 2);
+
+    clock.advance(Duration::from_secs(24 * 3600));
+    assert_hidden(&app, &public_token, false).await;
+}
+
+#[sqlx::test]
+async fn test_results_hidden_until_closed(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool, clock.clone());
+    let (public_token, organizer_token) =
+        create_blind_poll(&app, json!({ "blind_until_closed": true })).await;
+
+    assert_hidden(&app, &public_token, true).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_hidden(&app, &public_token, false).await;
+}
+
+#[sqlx::test]
+async fn test_reveal_notifies_participants_once(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock.clone());
+    create_blind_poll(&app, json!({ "reveal_results_at": "2029-12-02T00:00:00Z" })).await;
+    // Ordinary polls are never announced
+    create_blind_poll(&app, json!({})).await;
+    sqlx::query!("UPDATE participants SET email_verified_at = NOW() WHERE email IS NOT NULL")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        notify_revealed_results(&pool, clock.as_ref(), &Templates::default(), true)
+            .await
+            .unwrap(),
+        0
+    );
+
+    clock.advance(Duration::from_secs(24 * 3600));
+    assert_eq!(
+        notify_revealed_results(&pool, clock.as_ref(), &Templates::default(), true)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        notify_revealed_results(&pool, clock.as_ref(), &Templates::default(), true)
+            .await
+            .unwrap(),
+        0
+    );
+
+    let queued = sqlx::query!("SELECT recipient, subject FROM email_outbox")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].recipient, "bob@example.com");
+    assert!(queued[0].subject.contains("Retro"));
+}
+
+#[sqlx::test]
+async fn test_update_resets_reveal_notification(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock.clone());
+    let (public_token, organizer_token) =
+        create_blind_poll(&app, json!({ "reveal_results_at": "2029-11-30T00:00:00Z" })).await;
+    assert_hidden(&app, &public_token, false).await;
+    assert_eq!(
+        notify_revealed_results(&pool, clock.as_ref(), &Templates::default(), false)
+            .await
+            .unwrap(),
+        1
+    );
+
+    let (status, updated) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        json!({ "reveal_results_at": "2029-12-03T00:00:00Z" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["reveal_results_at"], "2029-12-03T00:00:00Z");
+    assert_hidden(&app, &public_token, true).await;
+
+    clock.advance(Duration::from_secs(3 * 24 * 3600));
+    assert_eq!(
+        notify_revealed_results(&pool, clock.as_ref(), &Templates::default(), false)
+            .await
+            .unwrap(),
+        1
+    );
+}
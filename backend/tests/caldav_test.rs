@@ -0,0 +1,214 @@
+mod common;
+
+use agreed_time_backend::{
+    config::{CaldavImport, Config},
+    routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{
+    Router,
+    http::{HeaderMap, Method, StatusCode, header},
+    response::IntoResponse,
+    routing::any,
+};
+use common::{create_event_tokens, send};
+use serde_json::json;
+use sqlx::PgPool;
+use std::future::IntoFuture;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+const FREE_BUSY: &str = concat!(
+    "BEGIN:VCALENDAR\r\n",
+    "VERSION:2.0\r\n",
+    "METHOD:REPLY\r\n",
+    "BEGIN:VFREEBUSY\r\n",
+    "FREEBUSY;FBTYPE=BUSY:20300107T100000Z/PT1H\r\n",
+    "FREEBUSY;FBTYPE=BUSY-TENTATIVE:20300107T103000Z/20300107T120000Z\r\n",
+    "END:VFREEBUSY\r\n",
+    "END:VCALENDAR\r\n",
+);
+
+/// Stands in for a calendar server: `/cal/` answers free/busy queries from
+/// bob:secret, `/broken/` always fails. Keeps the last query body.
+async fn serve_calendar(last_query: Arc<Mutex<Option<String>>>) -> String {
+    let calendar = Router::new()
+        .route(
+            "/cal/",
+            any(
+                move |method: Method, headers: HeaderMap, body: String| async move {
+                    // base64("bob:secret")
+                    if headers.get(header::AUTHORIZATION).map(|v| v.as_bytes())
+                        != Some(b"Basic Ym9iOnNlY3JldA==")
+                    {
+                        return StatusCode::UNAUTHORIZED.into_response();
+                    }
+                    if method.as_str() != "REPORT"
+                        || headers.get("Depth").map(|v| v.as_bytes()) != Some(b"1")
+                    {
+                        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+                    }
+                    *last_query.lock().unwrap() = Some(body);
+                    ([(header::CONTENT_TYPE, "text/calendar")], FREE_BUSY).into_response()
+                },
+            ),
+        )
+        .route(
+            "/broken/",
+            any(|| async { StatusCode::INTERNAL_SERVER_ERROR }),
+        );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(axum::serve(listener, calendar).into_future());
+    format!("http://{}", addr)
+}
+
+fn create_test_app(pool: PgPool, caldav_import: CaldavImport) -> Router {
+    let config = Config {
+        caldav_import,
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_config(config))
+}
+
+/// Creates Alice's event and returns its free/busy import route.
+async fn freebusy_uri(app: &Router) -> String {
+    let (public_token, _) = create_event_tokens(
+        app,
+        json!({
+            "title": "Team sync",
+            "time_slots": [
+                { "start_at": "2030-01-07T09:00:00Z", "end_at": "2030-01-07T13:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    format!("/events/{}/caldav/freebusy", public_token)
+}
+
+#[sqlx::test]
+async fn test_free_busy_prefills_availability(pool: PgPool) {
+    let last_query = Arc::new(Mutex::new(None));
+    let server = serve_calendar(last_query.clone()).await;
+    let app = create_test_app(pool, CaldavImport::Any);
+    let uri = freebusy_uri(&app).await;
+
+    let (_, capabilities) = send(&app, "GET", "/capabilities", None).await;
+    assert_eq!(capabilities["integrations"]["caldav_import"], true);
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &uri,
+        Some(json!({
+            "url": format!("{}/cal/", server),
+            "username": "bob",
+            "password": "secret"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["busy_periods"], 2);
+    assert_eq!(
+        body["availabilities"],
+        json!([
+            { "start_at": "2030-01-07T09:00:00Z", "end_at": "2030-01-07T10:00:00Z" },
+            { "start_at": "2030-01-07T11:00:00Z", "end_at": "2030-01-07T12:00:00Z", "availability_level": "if_need_be" },
+            { "start_at": "2030-01-07T12:00:00Z", "end_at": "2030-01-07T13:00:00Z" }
+        ])
+    );
+    let query = last_query.lock().unwrap().clone().unwrap();
+    assert!(query.contains("free-busy-query"), "{}", query);
+    assert!(
+        query.contains(r#"start="20300107T090000Z" end="20300107T130000Z""#),
+        "{}",
+        query
+    );
+
+    // Suggestions are submitted like any other availability
+    let (status, _) = send(
+        &app,
+        "POST",
+        &uri.replace("/caldav/freebusy", "/availability"),
+        Some(json!({ "participant_name": "Bob", "availabilities": body["availabilities"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_free_busy_errors(pool: PgPool) {
+    let server = serve_calendar(Arc::new(Mutex::new(None))).await;
+    let app = create_test_app(pool, CaldavImport::Any);
+    let uri = freebusy_uri(&app).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &uri,
+        Some(json!({ "url": format!("{}/cal/", server), "username": "bob", "password": "guess" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(!body["error"].as_str().unwrap().contains("guess"));
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &uri,
+        Some(json!({ "url": format!("{}/broken/", server) })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert_eq!(body["code"], "CALENDAR_UNAVAILABLE");
+
+    for url in [
+        "ftp://example.com/cal/".to_string(),
+        server.replace("http://", "http://bob:secret@"),
+    ] {
+        let (status, body) = send(&app, "POST", &uri, Some(json!({ "url": url }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", url);
+        assert_eq!(body["details"][0]["field"], "url");
+    }
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/events/nope/caldav/freebusy",
+        Some(json!({ "url": format!("{}/cal/", server) })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_free_busy_refuses_private_hosts(pool: PgPool) {
+    let server = serve_calendar(Arc::new(Mutex::new(None))).await;
+    let app = create_test_app(pool, CaldavImport::Public);
+    let uri = freebusy_uri(&app).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &uri,
+        Some(json!({ "url": format!("{}/cal/", server), "username": "bob", "password": "secret" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"][0]["field"], "url");
+}
+
+#[sqlx::test]
+async fn test_free_busy_is_off_by_default(pool: PgPool) {
+    let app = create_test_app(pool, CaldavImport::Off);
+    let uri = freebusy_uri(&app).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &uri,
+        Some(json!({ "url": "https://dav.example.com/cal/" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,56 @@
+use agreed_time_backend::{config::Config, routes::create_router_with_state, state::AppState};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt; // for `oneshot`
+
+async fn get_capabilities(app: &Router) -> Value {
+    let request = Request::builder()
+        .uri("/capabilities")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[sqlx::test]
+async fn test_capabilities_defaults(pool: PgPool) {
+    let app = create_router_with_state(AppState::new(pool));
+
+    let body = get_capabilities(&app).await;
+    assert_eq!(body["api_versions"][0], "1");
+    assert_eq!(body["poll_types"][0], "time_ranges");
+    assert_eq!(body["limits"]["max_participants"], 10);
+    assert_eq!(body["limits"]["max_waitlist"], 10);
+    assert_eq!(body["limits"]["max_title_length"], 100);
+    assert_eq!(body["limits"]["retention_days"], 7);
+    assert_eq!(body["integrations"]["ics_export"], true);
+    assert_eq!(body["integrations"]["email_invitations"], false);
+    assert_eq!(body["integrations"]["itip_replies"], false);
+    assert_eq!(body["integrations"]["admin_api"], false);
+    assert_eq!(body["integrations"]["caldav_import"], false);
+    assert_eq!(body["deprecations"].as_array().unwrap().len(), 0);
+}
+
+#[sqlx::test]
+async fn test_capabilities_reflect_config(pool: PgPool) {
+    let config = Config {
+        admin_api_key: Some("key".to_string()),
+        mail_from: Some("agreed-time@example.com".to_string()),
+        ..Config::default()
+    };
+    let app = create_router_with_state(AppState::new(pool).with_config(config));
+
+    let body = get_capabilities(&app).await;
+    assert_eq!(body["integrations"]["email_invitations"], true);
+    assert_eq!(body["integrations"]["itip_replies"], true);
+    assert_eq!(body["integrations"]["admin_api"], true);
+}
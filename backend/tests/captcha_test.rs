@@ -0,0 +1,114 @@
+mod common;
+
+use agreed_time_backend::{
+    captcha::CaptchaProvider, config::Config, routes::create_router_with_state, state::AppState,
+};
+use axum::{Form, Json, Router, http::StatusCode, routing::post};
+use common::send;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::future::IntoFuture;
+use tokio::net::TcpListener;
+
+const SECRET: &str = "captcha-secret";
+
+/// Stands in for the provider's `siteverify`: accepts `pass` once the
+/// secret matches.
+async fn serve_provider() -> String {
+    let provider = Router::new().route(
+        "/siteverify",
+        post(|Form(form): Form<HashMap<String, String>>| async move {
+            let success = form.get("secret").map(String::as_str) == Some(SECRET)
+                && form.get("response").map(String::as_str) == Some("pass");
+            Json(json!({
+                "success": success,
+                "error-codes": if success { vec![] } else { vec!["invalid-input-response"] }
+            }))
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(axum::serve(listener, provider).into_future());
+    format!("http://{}/siteverify", addr)
+}
+
+fn create_test_app(pool: PgPool, verify_url: Option<String>) -> Router {
+    let config = Config {
+        captcha_provider: Some(CaptchaProvider::Turnstile),
+        captcha_secret: Some(SECRET.to_string()),
+        captcha_verify_url: verify_url,
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_config(config))
+}
+
+fn event_body(captcha_token: Option<&str>) -> Value {
+    json!({
+        "title": "Team sync",
+        "description": null,
+        "organizer_name": "Alice",
+        "time_slots": [
+            { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+        ],
+        "captcha_token": captcha_token
+    })
+}
+
+fn availability_body(captcha_token: Option<&str>) -> Value {
+    json!({
+        "participant_name": "Bob",
+        "availabilities": [
+            { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }
+        ],
+        "captcha_token": captcha_token
+    })
+}
+
+#[sqlx::test]
+async fn test_captcha_guards_creation_and_submission(pool: PgPool) {
+    let app = create_test_app(pool, Some(serve_provider().await));
+
+    let (status, body) = send(&app, "GET", "/capabilities", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["integrations"]["captcha"], "turnstile");
+
+    for token in [None, Some(""), Some("fail")] {
+        let (status, body) = send(&app, "POST", "/events", Some(event_body(token))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], "CAPTCHA_FAILED");
+    }
+
+    let (status, body) = send(&app, "POST", "/events", Some(event_body(Some("pass")))).await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = body["public_token"].as_str().unwrap().to_string();
+    let uri = format!("/events/{}/availability", public_token);
+
+    let (status, body) = send(&app, "POST", &uri, Some(availability_body(None))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "CAPTCHA_FAILED");
+
+    let (status, _) = send(&app, "POST", &uri, Some(availability_body(Some("pass")))).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_unreachable_provider_fails_closed(pool: PgPool) {
+    // Nothing listens on the discard port
+    let app = create_test_app(pool, Some("http://127.0.0.1:9/siteverify".to_string()));
+
+    let (status, body) = send(&app, "POST", "/events", Some(event_body(Some("pass")))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "CAPTCHA_FAILED");
+}
+
+#[sqlx::test]
+async fn test_captcha_off_by_default(pool: PgPool) {
+    let app = create_router_with_state(AppState::new(pool));
+
+    let (_, body) = send(&app, "GET", "/capabilities", None).await;
+    assert_eq!(body["integrations"]["captcha"], Value::Null);
+
+    let (status, _) = send(&app, "POST", "/events", Some(event_body(None))).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,167 @@
+use agreed_time_backend::{
+    clock::{Clock, TestClock},
+    db::cleanup::{CleanupOptions, run_cleanup},
+};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+fn now() -> DateTime<Utc> {
+    "2029-12-01T00:00:00Z".parse().unwrap()
+}
+
+async fn insert_event(
+    pool: &PgPool,
+    title: &str,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    deleted_at: Option<DateTime<Utc>>,
+) -> String {
+    let public_token = Uuid::new_v4().simple().to_string()[..12].to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO events (id, public_token, organizer_token, title, state, time_zone, slot_duration, created_at, updated_at, expires_at, deleted_at)
+        VALUES ($1, $2, $3, $4, 'open', 'UTC', 60, $5, $5, $6, $7)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&public_token)
+    .bind(Uuid::new_v4().to_string())
+    .bind(title)
+    .bind(created_at)
+    .bind(expires_at)
+    .bind(deleted_at)
+    .execute(pool)
+    .await
+    .unwrap();
+    public_token
+}
+
+async fn live_titles(pool: &PgPool) -> Vec<String> {
+    sqlx::query_scalar("SELECT title FROM events WHERE deleted_at IS NULL ORDER BY title")
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn test_dry_run_lists_without_deleting(pool: PgPool) {
+    let clock = TestClock::new(now());
+    let expired = insert_event(
+        &pool,
+        "Expired",
+        now() - Duration::days(8),
+        now() - Duration::days(1),
+        None,
+    )
+    .await;
+    insert_event(
+        &pool,
+        "Fresh",
+        now() - Duration::days(1),
+        now() + Duration::days(6),
+        None,
+    )
+    .await;
+    let stale = insert_event(
+        &pool,
+        "Stale trash",
+        now() - Duration::days(30),
+        now() - Duration::days(20),
+        Some(now() - Duration::days(10)),
+    )
+    .await;
+
+    let report = run_cleanup(
+        &pool,
+        &clock,
+        &CleanupOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.trashed.len(), 1);
+    assert_eq!(report.trashed[0].public_token, expired);
+    assert_eq!(report.purged.len(), 1);
+    assert_eq!(report.purged[0].public_token, stale);
+    let rendered = report.render();
+    assert!(
+        rendered.starts_with("Would trash 1 events\n"),
+        "{}",
+        rendered
+    );
+    assert!(rendered.contains(&expired));
+    assert!(rendered.contains("Would purge 1 trashed events\n"));
+
+    // Nothing changed
+    assert_eq!(live_titles(&pool).await, vec!["Expired", "Fresh"]);
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(total, 3);
+    let runs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM job_runs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(runs, 0);
+}
+
+#[sqlx::test]
+async fn test_older_than_trashes_early_and_purges(pool: PgPool) {
+    let clock = TestClock::new(now());
+    insert_event(
+        &pool,
+        "Long retention",
+        now() - Duration::days(20),
+        now() + Duration::days(70),
+        None,
+    )
+    .await;
+    insert_event(
+        &pool,
+        "Recent",
+        now() - Duration::days(2),
+        now() + Duration::days(5),
+        None,
+    )
+    .await;
+    insert_event(
+        &pool,
+        "Stale trash",
+        now() - Duration::days(30),
+        now() - Duration::days(20),
+        Some(now() - Duration::days(10)),
+    )
+    .await;
+
+    let report = run_cleanup(
+        &pool,
+        &clock,
+        &CleanupOptions {
+            dry_run: false,
+            older_than: Some(Duration::days(14)),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.trashed.len(), 1);
+    assert_eq!(report.trashed[0].title, "Long retention");
+    assert_eq!(report.purged.len(), 1);
+    assert!(report.render().starts_with("Trashed 1 events\n"));
+
+    assert_eq!(live_titles(&pool).await, vec!["Recent"]);
+    let trashed_at: Option<DateTime<Utc>> =
+        sqlx::query_scalar("SELECT deleted_at FROM events WHERE title = 'Long retention'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(trashed_at, Some(clock.now()));
+    let purged: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events WHERE title = 'Stale trash'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(purged, 0);
+}
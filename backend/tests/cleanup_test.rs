@@ -1,4 +1,5 @@
-use agreed_time_backend::db::cleanup::delete_expired_events;
+use agreed_time_backend::clock::{Clock, TestClock};
+use agreed_time_backend::db::cleanup::{delete_expired_events, purge_deleted_events};
 use chrono::{Duration, Utc};
 use sqlx::postgres::PgPoolOptions;
 use std::env;
@@ -20,15 +21,17 @@ async fn test_delete_expired_events() {
         .await
         .expect("Failed to connect to DB");
 
+    let clock = TestClock::new(Utc::now());
+
     // 1. Create Expired Event (8 days ago)
     let expired_event_id = Uuid::new_v4();
-    let old_time = Utc::now() - Duration::days(8);
+    let old_time = clock.now() - Duration::days(8);
 
     // Updated Schema: No organizer_name, Added slot_duration
     sqlx::query!(
         r#"
-        INSERT INTO events (id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at)
-        VALUES ($1, $2, $3, 'Expired Event', NULL, 'open', 'UTC', 60, $4, $4)
+        INSERT INTO events (id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at, expires_at)
+        VALUES ($1, $2, $3, 'Expired Event', NULL, 'open', 'UTC', 60, $4, $4, $4::timestamptz + INTERVAL '7 days')
         "#,
         expired_event_id,
         Uuid::new_v4().to_string(),
@@ -41,12 +44,12 @@ async fn test_delete_expired_events() {
 
     // 2. Create Active Event (1 day ago)
     let active_event_id = Uuid::new_v4();
-    let recent_time = Utc::now() - Duration::days(1);
+    let recent_time = clock.now() - Duration::days(1);
 
     sqlx::query!(
         r#"
-        INSERT INTO events (id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at)
-        VALUES ($1, $2, $3, 'Active Event', NULL, 'open', 'UTC', 60, $4, $4)
+        INSERT INTO events (id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at, expires_at)
+        VALUES ($1, $2, $3, 'Active Event', NULL, 'open', 'UTC', 60, $4, $4, $4::timestamptz + INTERVAL '7 days')
         "#,
         active_event_id,
         Uuid::new_v4().to_string(),
@@ -58,19 +61,32 @@ async fn test_delete_expired_events() {
     .expect("Failed to insert active event");
 
     // 3. Run Cleanup
-    let deleted_count = delete_expired_events(&pool).await.expect("Cleanup failed");
+    let deleted_count = delete_expired_events(&pool, &clock)
+        .await
+        .expect("Cleanup failed");
 
     // 4. Verify
     // Note: deleted_count might be > 1 if other junk exists in DB.
     assert!(deleted_count >= 1);
 
+    let expired_visible = sqlx::query!(
+        "SELECT id FROM events WHERE id = $1 AND deleted_at IS NULL",
+        expired_event_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .unwrap()
+    .is_some();
+
+    assert!(!expired_visible, "Expired event should be trashed");
+
     let expired_exists = sqlx::query!("SELECT id FROM events WHERE id = $1", expired_event_id)
         .fetch_optional(&pool)
         .await
         .unwrap()
         .is_some();
 
-    assert!(!expired_exists, "Expired event should be deleted");
+    assert!(expired_exists, "Trashed event should be kept until purged");
 
     let active_exists = sqlx::query!("SELECT id FROM events WHERE id = $1", active_event_id)
         .fetch_optional(&pool)
@@ -86,3 +102,84 @@ async fn test_delete_expired_events() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn test_delete_expired_events_follows_clock() {
+    let _ = dotenvy::dotenv();
+    let database_url = match env::var("DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => {
+            eprintln!("Skipping cleanup test: DATABASE_URL not set");
+            return;
+        }
+    };
+
+    let pool = PgPoolOptions::new()
+        .connect(&database_url)
+        .await
+        .expect("Failed to connect to DB");
+
+    let clock = TestClock::new(Utc::now());
+    let event_id = Uuid::new_v4();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO events (id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at, expires_at)
+        VALUES ($1, $2, $3, 'Aging Event', NULL, 'open', 'UTC', 60, $4, $4, $4::timestamptz + INTERVAL '7 days')
+        "#,
+        event_id,
+        Uuid::new_v4().to_string(),
+        Uuid::new_v4().to_string(),
+        clock.now()
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to insert event");
+
+    let exists = |pool: sqlx::PgPool| async move {
+        sqlx::query!(
+            "SELECT id FROM events WHERE id = $1 AND deleted_at IS NULL",
+            event_id
+        )
+        .fetch_optional(&pool)
+        .await
+        .unwrap()
+        .is_some()
+    };
+
+    // Six days later the event is still retained
+    clock.advance(std::time::Duration::from_secs(6 * 24 * 3600));
+    delete_expired_events(&pool, &clock).await.unwrap();
+    assert!(exists(pool.clone()).await, "Event should survive 6 days");
+
+    // Past the retention window it is trashed
+    clock.advance(std::time::Duration::from_secs(2 * 24 * 3600));
+    delete_expired_events(&pool, &clock).await.unwrap();
+    assert!(
+        !exists(pool.clone()).await,
+        "Event should be trashed after 8 days"
+    );
+
+    let stored = |pool: sqlx::PgPool| async move {
+        sqlx::query!("SELECT id FROM events WHERE id = $1", event_id)
+            .fetch_optional(&pool)
+            .await
+            .unwrap()
+            .is_some()
+    };
+
+    // The trash keeps it for the grace period, then it is purged for good
+    clock.advance(std::time::Duration::from_secs(6 * 24 * 3600));
+    purge_deleted_events(&pool, &clock).await.unwrap();
+    assert!(
+        stored(pool.clone()).await,
+        "Trashed event should survive 6 days"
+    );
+
+    clock.advance(std::time::Duration::from_secs(2 * 24 * 3600));
+    purge_deleted_events(&pool, &clock).await.unwrap();
+    assert!(
+        !stored(pool.clone()).await,
+        "Trashed event should be purged after the grace period"
+    );
+}
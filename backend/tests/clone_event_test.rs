@@ -0,0 +1,186 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event, send, submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// A Paris standup the week before the clocks change, with Bob answered.
+async fn create_standup(app: &Router) -> Value {
+    let created = create_event(
+        app,
+        json!({
+            "title": "Sprint 12 planning",
+            "description": "Bring your estimates",
+            "time_zone": "Europe/Paris",
+            "slot_duration": 30,
+            "time_slots": [
+                { "start_at": "2030-03-25T08:00:00Z", "end_at": "2030-03-25T09:00:00Z" }
+            ],
+            "results_visibility": "private",
+            "roles": [{ "name": "facilitator", "required": 1 }],
+            "finalization_rules": { "min_participants": 2 },
+            "close_at_quorum": 4
+        }),
+    )
+    .await;
+    submit(
+        app,
+        created["public_token"].as_str().unwrap(),
+        json!({
+            "availabilities": [
+                { "start_at": "2030-03-25T08:00:00Z", "end_at": "2030-03-25T08:30:00Z" }
+            ]
+        }),
+    )
+    .await;
+    created
+}
+
+async fn organizer_view(app: &Router, organizer_token: &str) -> Value {
+    let (status, view) = send(
+        app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    view
+}
+
+#[sqlx::test]
+async fn test_clone_copies_settings_and_shifts_slots(pool: PgPool) {
+    let app = create_router(pool);
+    let source = create_standup(&app).await;
+    let source_organizer_token = source["organizer_token"].as_str().unwrap();
+
+    let (status, clone) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/clone", source_organizer_token),
+        Some(json!({ "offset_days": 7 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    for token in ["public_token", "organizer_token", "results_token"] {
+        assert_ne!(clone[token], source[token], "{}", token);
+    }
+
+    let view = organizer_view(&app, clone["organizer_token"].as_str().unwrap()).await;
+    assert_eq!(view["title"], "Sprint 12 planning");
+    assert_eq!(view["description"], "Bring your estimates");
+    assert_eq!(view["time_zone"], "Europe/Paris");
+    assert_eq!(view["slot_duration"], 30);
+    assert_eq!(view["state"], "open");
+    assert_eq!(view["results_visibility"], "private");
+    assert_eq!(view["results_token"], clone["results_token"]);
+    assert_eq!(
+        view["roles"],
+        json!([{ "name": "facilitator", "required": 1 }])
+    );
+    assert_eq!(view["finalization_rules"]["min_participants"], 2);
+    assert_eq!(view["close_at_quorum"], 4);
+
+    // Still 09:00 in Paris, now after the switch to summer time
+    assert_eq!(view["event_slots"].as_array().unwrap().len(), 1);
+    assert_eq!(view["event_slots"][0]["start_at"], "2030-04-01T07:00:00Z");
+    assert_eq!(view["event_slots"][0]["end_at"], "2030-04-01T08:00:00Z");
+
+    let names: Vec<&str> = view["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Alice"]);
+    assert_eq!(
+        view["participants"][0]["availabilities"][0]["start_at"],
+        "2030-04-01T07:00:00Z"
+    );
+
+    let (_, audit) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/{}/audit",
+            clone["organizer_token"].as_str().unwrap()
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(audit["entries"].as_array().unwrap().len(), 1);
+    assert_eq!(audit["entries"][0]["action"], "created");
+    assert_eq!(audit["entries"][0]["details"], "cloned");
+
+    // The source keeps its participants and slots
+    let original = organizer_view(&app, source_organizer_token).await;
+    assert_eq!(original["participants"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        original["event_slots"][0]["start_at"],
+        "2030-03-25T08:00:00Z"
+    );
+}
+
+#[sqlx::test]
+async fn test_clone_without_body_keeps_the_slots(pool: PgPool) {
+    let app = create_router(pool);
+    let source = create_standup(&app).await;
+
+    let (status, clone) = send(
+        &app,
+        "POST",
+        &format!(
+            "/events/{}/clone",
+            source["organizer_token"].as_str().unwrap()
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let view = organizer_view(&app, clone["organizer_token"].as_str().unwrap()).await;
+    assert_eq!(view["event_slots"][0]["start_at"], "2030-03-25T08:00:00Z");
+}
+
+#[sqlx::test]
+async fn test_clone_rejects_bad_offsets(pool: PgPool) {
+    let app = create_router(pool);
+    let source = create_standup(&app).await;
+    let uri = format!(
+        "/events/{}/clone",
+        source["organizer_token"].as_str().unwrap()
+    );
+
+    let (status, error) = send(&app, "POST", &uri, Some(json!({ "offset_days": 400 }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["details"][0]["field"], "offset_days");
+
+    let weekly = create_event(
+        &app,
+        json!({
+            "slot_kind": "weekly",
+            "weekly_slots": [{ "weekday": "Mon", "start_time": "09:00", "end_time": "10:00" }],
+            "time_slots": []
+        }),
+    )
+    .await;
+    let weekly_uri = format!(
+        "/events/{}/clone",
+        weekly["organizer_token"].as_str().unwrap()
+    );
+    let (status, _) = send(&app, "POST", &weekly_uri, Some(json!({ "offset_days": 7 }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, "POST", &weekly_uri, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Public tokens don't clone
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/clone", source["public_token"].as_str().unwrap()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
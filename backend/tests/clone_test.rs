@@ -0,0 +1,163 @@
+mod common;
+
+use agreed_time_backend::clock::TestClock;
+use agreed_time_backend::db::clone::{CloneOptions, CloneReport, clone_events};
+use agreed_time_backend::routes::create_router;
+use axum::http::StatusCode;
+use chrono::Utc;
+use common::send;
+use serde_json::json;
+use sqlx::PgPool;
+
+// Source and target are the same test database here; the copy is told
+// apart by its fresh tokens.
+#[sqlx::test]
+async fn test_clone_events_sanitizes_copy(pool: PgPool) {
+    let app = create_router(pool.clone());
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Alice's surprise party",
+            "description": "Don't tell Alice",
+            "organizer_name": "Bob Smith",
+            "time_zone": "Europe/Berlin",
+            "slot_duration": 30,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ],
+            "roles": [{ "name": "host", "required": 1 }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({
+            "participant_name": "Carol Jones",
+            "comment": "Call me at 555-0100",
+            "email": "carol@example.com",
+            "role": "host",
+            "availabilities": [
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" },
+                { "start_at": "2030-01-01T11:00:00Z", "end_at": "2030-01-01T12:00:00Z", "availability_level": "if_need_be" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        json!({
+            "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/assign", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let clock = TestClock::new(Utc::now());
+    let report = clone_events(
+        &pool,
+        &pool,
+        &clock,
+        &CloneOptions {
+            limit: 10,
+            since: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        report,
+        CloneReport {
+            events: 1,
+            participants: 2
+        }
+    );
+
+    let copy = sqlx::query!(
+        r#"
+        SELECT id, public_token, title, description, time_zone, slot_duration, state
+        FROM events
+        WHERE public_token <> $1
+        "#,
+        public_token
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(!copy.title.contains("Alice"));
+    assert_eq!(copy.description, None);
+    assert_eq!(copy.time_zone.as_deref(), Some("Europe/Berlin"));
+    assert_eq!(copy.slot_duration, 30);
+    assert_eq!(copy.state, "finalized");
+
+    let participants = sqlx::query!(
+        "SELECT name, comment, email, role FROM participants WHERE event_id = $1 ORDER BY is_organizer DESC, id",
+        copy.id
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    let names: Vec<&str> = participants.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["Organizer", "Participant 1"]);
+    assert!(
+        participants
+            .iter()
+            .all(|p| p.comment.is_none() && p.email.is_none())
+    );
+    assert_eq!(participants[1].role.as_deref(), Some("host"));
+
+    // Same shape as the original: the copied results read the same
+    let (_, original) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        json!({}),
+    )
+    .await;
+    let (status, copied) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", copy.public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(copied["event_slots"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        copied["participants"][1]["availabilities"],
+        original["participants"][1]["availabilities"]
+    );
+    assert_eq!(copied["final_slots"].as_array().unwrap().len(), 1);
+
+    let assigned = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!"
+        FROM slot_assignments sa
+        JOIN final_slots fs ON fs.id = sa.final_slot_id
+        WHERE fs.event_id = $1
+        "#,
+        copy.id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(assigned, 2);
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use agreed_time_backend::{clock::TestClock, routes::create_router_with_state, state::AppState};
+use axum::{Router, http::StatusCode};
+use chrono::{DateTime, Utc};
+use common::{create_event_tokens, submission_body};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    password: Option<&str>,
+    body: Value,
+) -> (StatusCode, Value) {
+    let headers: Vec<(&str, &str)> = password
+        .map(|password| ("X-Event-Password", password))
+        .into_iter()
+        .collect();
+    common::send_with_headers(app, method, uri, &headers, body).await
+}
+
+fn now() -> DateTime<Utc> {
+    "2029-12-01T00:00:00Z".parse().unwrap()
+}
+
+async fn join(app: &Router, public_token: &str, name: &str, password: Option<&str>) -> String {
+    let (status, submitted) = send(
+        app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        password,
+        submission_body(json!({ "participant_name": name })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    submitted["participant_token"].as_str().unwrap().to_string()
+}
+
+#[sqlx::test]
+async fn test_comments_are_listed_oldest_first_in_pages(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_router_with_state(AppState::new(pool).with_clock(clock.clone()));
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+    let carol = join(&app, &public_token, "Carol", None).await;
+    let dave = join(&app, &public_token, "Dave", None).await;
+    let uri = format!("/events/{}/comments", public_token);
+
+    let (status, posted) = send(
+        &app,
+        "POST",
+        &uri,
+        None,
+        json!({
+            "participant_token": carol,
+            "body": "  Tuesday works only after 6pm  "
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(posted["author_name"], "Carol");
+    assert_eq!(posted["is_organizer"], false);
+    assert_eq!(posted["body"], "Tuesday works only after 6pm");
+    assert_eq!(posted["created_at"], "2029-12-01T00:00:00Z");
+
+    for (token, body) in [(&dave, "Same here"), (&carol, "Then 7pm?")] {
+        clock.advance(Duration::from_secs(60));
+        let (status, _) = send(
+            &app,
+            "POST",
+            &uri,
+            None,
+            json!({ "participant_token": token, "body": body }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (status, page) = send(&app, "GET", &uri, None, Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["total"], 3);
+    let bodies: Vec<&str> = page["comments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|comment| comment["body"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        bodies,
+        vec!["Tuesday works only after 6pm", "Same here", "Then 7pm?"]
+    );
+
+    let (_, page) = send(
+        &app,
+        "GET",
+        &format!("{}?limit=1&offset=1", uri),
+        None,
+        Value::Null,
+    )
+    .await;
+    assert_eq!(page["total"], 3);
+    assert_eq!(page["comments"].as_array().unwrap().len(), 1);
+    assert_eq!(page["comments"][0]["author_name"], "Dave");
+    assert_eq!(page["comments"][0]["created_at"], "2029-12-01T00:01:00Z");
+}
+
+#[sqlx::test]
+async fn test_comment_validation(pool: PgPool) {
+    let app = create_router_with_state(AppState::new(pool));
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+    let carol = join(&app, &public_token, "Carol", None).await;
+    let uri = format!("/events/{}/comments", public_token);
+
+    for body in ["   ".to_string(), "é".repeat(501)] {
+        let (status, error) = send(
+            &app,
+            "POST",
+            &uri,
+            None,
+            json!({ "participant_token": carol, "body": body }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], "VALIDATION_FAILED");
+        assert_eq!(error["details"][0]["field"], "body");
+    }
+    let (status, _) = send(
+        &app,
+        "POST",
+        &uri,
+        None,
+        json!({ "participant_token": carol, "body": "é".repeat(500) }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Another event's participant can't post here
+    let (other_event, _) = create_event_tokens(&app, json!({})).await;
+    let outsider = join(&app, &other_event, "Eve", None).await;
+    let (status, _) = send(
+        &app,
+        "POST",
+        &uri,
+        None,
+        json!({ "participant_token": outsider, "body": "Hi" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, "GET", "/events/unknown/comments", None, Value::Null).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_comments_need_the_event_password(pool: PgPool) {
+    let app = create_router_with_state(AppState::new(pool));
+    let (public_token, _) = create_event_tokens(&app, json!({ "password": "secret" })).await;
+    let carol = join(&app, &public_token, "Carol", Some("secret")).await;
+    let uri = format!("/events/{}/comments", public_token);
+    let comment = json!({ "participant_token": carol, "body": "Hi" });
+
+    let (status, _) = send(&app, "POST", &uri, None, comment.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, "GET", &uri, Some("wrong"), Value::Null).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(&app, "POST", &uri, Some("secret"), comment).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, page) = send(&app, "GET", &uri, Some("secret"), Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["total"], 1);
+}
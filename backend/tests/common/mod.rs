@@ -0,0 +1,162 @@
+//! Request helpers and the event and participant factories shared by the
+//! integration tests; each test file pulls them in with `mod common;`.
+#![allow(dead_code)]
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::{Value, json};
+use tower::ServiceExt; // for `oneshot`
+
+/// A request with a JSON `body`, or an empty one for `None`.
+pub fn json_request(
+    method: &str,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: impl Into<Option<Value>>,
+) -> Request<Body> {
+    let mut request = Request::builder().method(method).uri(uri);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let body = match body.into() {
+        Some(body) => {
+            request = request.header("Content-Type", "application/json");
+            Body::from(serde_json::to_vec(&body).unwrap())
+        }
+        None => Body::empty(),
+    };
+    request.body(body).unwrap()
+}
+
+/// Sends `request` through the router; the body comes back as JSON, or
+/// `Value::Null` when it isn't any.
+pub async fn send_request(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+/// `method uri` with a JSON body (a `Value`, `Some(value)` or `None`).
+pub async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    body: impl Into<Option<Value>>,
+) -> (StatusCode, Value) {
+    send_request(app, json_request(method, uri, &[], body)).await
+}
+
+/// [`send`] with extra request headers.
+pub async fn send_with_headers(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: impl Into<Option<Value>>,
+) -> (StatusCode, Value) {
+    send_request(app, json_request(method, uri, headers, body)).await
+}
+
+/// Whether the frontend has a page at the path of `link` (query dropped),
+/// following Astro's file routing under `frontend/src/pages`.
+pub fn frontend_has_page(link: &str) -> bool {
+    let path = link.split('?').next().unwrap().trim_end_matches('/');
+    let pages = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../frontend/src/pages");
+    pages.join(format!("{}.astro", &path[1..])).is_file()
+        || pages.join(&path[1..]).join("index.astro").is_file()
+}
+
+/// `defaults` with every field of `fields` (an object) set over it.
+fn merged(mut defaults: Value, fields: Value) -> Value {
+    if let Value::Object(fields) = fields {
+        for (key, value) in fields {
+            defaults[key] = value;
+        }
+    }
+    defaults
+}
+
+/// `POST /events` body: Alice's "Standup", one UTC slot on 2030-01-01
+/// 09:00–12:00 in hour cells, with `fields` set over it. Date polls and
+/// weekly events clear the slot with `"time_slots": []`.
+pub fn event_body(fields: Value) -> Value {
+    merged(
+        json!({
+            "title": "Standup",
+            "description": null,
+            "organizer_name": "Alice",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        }),
+        fields,
+    )
+}
+
+/// `POST /events` with [`event_body`], for tests that expect a refusal.
+pub async fn try_create_event(app: &Router, fields: Value) -> (StatusCode, Value) {
+    send(app, "POST", "/events", event_body(fields)).await
+}
+
+/// [`try_create_event`] that must succeed; returns the created event.
+pub async fn create_event(app: &Router, fields: Value) -> Value {
+    let (status, created) = try_create_event(app, fields).await;
+    assert_eq!(status, StatusCode::OK, "{}", created);
+    created
+}
+
+/// [`create_event`]'s public and organizer tokens.
+pub async fn create_event_tokens(app: &Router, fields: Value) -> (String, String) {
+    let created = create_event(app, fields).await;
+    (
+        created["public_token"].as_str().unwrap().to_string(),
+        created["organizer_token"].as_str().unwrap().to_string(),
+    )
+}
+
+/// `POST /events/{public_token}/availability` body: Bob, available
+/// 09:00–10:00 on the [`event_body`] day, no comment, with `fields` set over
+/// it.
+pub fn submission_body(fields: Value) -> Value {
+    merged(
+        json!({
+            "participant_name": "Bob",
+            "availabilities": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }
+            ],
+            "comment": null
+        }),
+        fields,
+    )
+}
+
+/// Answers the event with [`submission_body`], for tests that expect a
+/// refusal.
+pub async fn try_submit(app: &Router, public_token: &str, fields: Value) -> (StatusCode, Value) {
+    send(
+        app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        submission_body(fields),
+    )
+    .await
+}
+
+/// [`try_submit`] that must succeed; returns the submission, with the
+/// participant token.
+pub async fn submit(app: &Router, public_token: &str, fields: Value) -> Value {
+    let (status, submitted) = try_submit(app, public_token, fields).await;
+    assert_eq!(status, StatusCode::OK, "{}", submitted);
+    submitted
+}
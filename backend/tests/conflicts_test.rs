@@ -0,0 +1,105 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send, submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+fn recommended(event: &Value) -> Vec<&str> {
+    event["candidates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|c| c["recommended"] == true)
+        .map(|c| c["suggestion"]["start_at"].as_str().unwrap())
+        .collect()
+}
+
+/// Event over 09:00-11:00 where one guest can only make 09:00-10:00, so
+/// that hour is its best candidate. Returns `(public_token, organizer_token)`.
+async fn create_answered_event(app: &Router, title: &str) -> (String, String) {
+    let (public_token, organizer_token) = create_event_tokens(
+        app,
+        json!({
+            "title": title,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    submit(app, &public_token, json!({ "participant_name": "Guest" })).await;
+    (public_token, organizer_token)
+}
+
+#[sqlx::test]
+async fn test_conflicts_across_events(pool: PgPool) {
+    let app = create_router(pool);
+    let (first_public, first) = create_answered_event(&app, "Standup").await;
+    let (_, second) = create_answered_event(&app, "Retro").await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/events/conflicts",
+        json!({ "organizer_tokens": [first, second, "unknown"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["compatible"], true);
+    let events = body["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["title"], "Standup");
+
+    // Both want 09:00; the first event listed keeps it
+    let best = &events[1]["candidates"][0];
+    assert_eq!(best["suggestion"]["start_at"], "2030-01-01T09:00:00Z");
+    assert_eq!(best["conflicts"][0]["public_token"], first_public.as_str());
+    assert_eq!(best["conflicts"][0]["finalized"], false);
+    assert_eq!(recommended(&events[0]), vec!["2030-01-01T09:00:00Z"]);
+    assert_eq!(recommended(&events[1]), vec!["2030-01-01T10:00:00Z"]);
+
+    // A finalized event blocks its final slot for everyone else
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", second),
+        json!({
+            "slots": [{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = send(
+        &app,
+        "POST",
+        "/events/conflicts",
+        json!({ "organizer_tokens": [first, second] }),
+    )
+    .await;
+    let events = body["events"].as_array().unwrap();
+    assert_eq!(events[1]["candidates"], json!([]));
+    assert_eq!(events[1]["final_slots"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        events[0]["candidates"][0]["conflicts"][0]["finalized"],
+        true
+    );
+    assert_eq!(recommended(&events[0]), vec!["2030-01-01T10:00:00Z"]);
+}
+
+#[sqlx::test]
+async fn test_conflicts_rejects_too_many_tokens(pool: PgPool) {
+    let app = create_router(pool);
+    let tokens: Vec<String> = (0..51).map(|i| format!("token-{}", i)).collect();
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/events/conflicts",
+        json!({ "organizer_tokens": tokens }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
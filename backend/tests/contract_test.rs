@@ -0,0 +1,455 @@
+use agreed_time_backend::openapi::ApiDoc;
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt; // for `oneshot`
+use utoipa::OpenApi;
+
+/// Sends a request through the router and checks the response against the
+/// OpenAPI document: the status must be documented for the operation and the
+/// body must validate against the documented schema.
+struct ContractClient {
+    app: Router,
+    spec: Value,
+}
+
+impl ContractClient {
+    fn new(pool: PgPool) -> Self {
+        ContractClient {
+            app: agreed_time_backend::routes::create_router(pool),
+            spec: serde_json::to_value(ApiDoc::openapi()).unwrap(),
+        }
+    }
+
+    async fn call(
+        &self,
+        method: Method,
+        template: &str,
+        path: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut builder = Request::builder().method(method.clone()).uri(path);
+        let body = match body {
+            Some(json) => {
+                builder = builder.header("content-type", "application/json");
+                Body::from(serde_json::to_vec(&json).unwrap())
+            }
+            None => Body::empty(),
+        };
+
+        let response = self
+            .app
+            .clone()
+            .oneshot(builder.body(body).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = if bytes.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&bytes).unwrap()
+        };
+
+        self.assert_matches(&method, template, status, &json);
+        (status, json)
+    }
+
+    fn assert_matches(&self, method: &Method, template: &str, status: StatusCode, body: &Value) {
+        let operation = &self.spec["paths"][template][method.as_str().to_lowercase()];
+        assert!(
+            operation.is_object(),
+            "{} {} is not documented in the OpenAPI spec",
+            method,
+            template
+        );
+
+        let response = &operation["responses"][status.as_str()];
+        assert!(
+            response.is_object(),
+            "{} {} returned undocumented status {}",
+            method,
+            template,
+            status
+        );
+
+        let Some(schema) = response["content"]["application/json"].get("schema") else {
+            assert!(
+                body.is_null(),
+                "{} {} {} has no documented body but returned {}",
+                method,
+                template,
+                status,
+                body
+            );
+            return;
+        };
+
+        // Validate with the whole document as root so `#/components/...` refs resolve.
+        let mut root = self.spec.clone();
+        let root_obj = root.as_object_mut().unwrap();
+        for (key, value) in schema.as_object().unwrap() {
+            root_obj.insert(key.clone(), value.clone());
+        }
+
+        let validator = jsonschema::options()
+            .with_draft(jsonschema::Draft::Draft202012)
+            .should_validate_formats(true)
+            .build(&root)
+            .expect("OpenAPI schema should compile");
+
+        let errors: Vec<String> = validator
+            .iter_errors(body)
+            .map(|e| format!("{} at {}", e, e.instance_path))
+            .collect();
+        assert!(
+            errors.is_empty(),
+            "{} {} {} violates the contract: {:?}\nbody: {}",
+            method,
+            template,
+            status,
+            errors,
+            body
+        );
+    }
+}
+
+fn create_event_body() -> Value {
+    json!({
+        "title": "Contract Event",
+        "description": "Checked against the spec",
+        "organizer_name": "Organizer",
+        "time_zone": "UTC",
+        "slot_duration": 60,
+        "time_slots": [
+            { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+        ]
+    })
+}
+
+fn availability_body(name: &str) -> Value {
+    json!({
+        "participant_name": name,
+        "availabilities": [
+            { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+        ],
+        "comment": null
+    })
+}
+
+#[sqlx::test]
+async fn test_event_lifecycle_matches_contract(pool: PgPool) {
+    let client = ContractClient::new(pool);
+
+    client.call(Method::GET, "/health", "/health", None).await;
+    client.call(Method::GET, "/time", "/time", None).await;
+    client
+        .call(Method::GET, "/capabilities", "/capabilities", None)
+        .await;
+
+    let (status, created) = client
+        .call(
+            Method::POST,
+            "/events",
+            "/events",
+            Some(create_event_body()),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap().to_string();
+    let organizer_token = created["organizer_token"].as_str().unwrap().to_string();
+
+    client
+        .call(
+            Method::GET,
+            "/events/{public_token}",
+            &format!("/events/{}", public_token),
+            None,
+        )
+        .await;
+
+    let (status, submitted) = client
+        .call(
+            Method::POST,
+            "/events/{public_token}/availability",
+            &format!("/events/{}/availability", public_token),
+            Some(availability_body("Guest")),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let participant_token = submitted["participant_token"].as_str().unwrap().to_string();
+
+    let participant_path = format!(
+        "/events/{}/participants/{}",
+        public_token, participant_token
+    );
+    client
+        .call(
+            Method::GET,
+            "/events/{public_token}/participants/{participant_token}",
+            &participant_path,
+            None,
+        )
+        .await;
+    client
+        .call(
+            Method::PUT,
+            "/events/{public_token}/participants/{participant_token}",
+            &participant_path,
+            Some(availability_body("Guest (edited)")),
+        )
+        .await;
+
+    client
+        .call(
+            Method::GET,
+            "/events/{public_token}/results",
+            &format!("/events/{}/results", public_token),
+            None,
+        )
+        .await;
+    client
+        .call(
+            Method::GET,
+            "/events/{public_token}/summary",
+            &format!("/events/{}/summary", public_token),
+            None,
+        )
+        .await;
+    client
+        .call(
+            Method::GET,
+            "/events/{public_token}/suggestions",
+            &format!("/events/{}/suggestions", public_token),
+            None,
+        )
+        .await;
+    client
+        .call(
+            Method::GET,
+            "/events/organizer/{organizer_token}",
+            &format!("/events/organizer/{}", organizer_token),
+            None,
+        )
+        .await;
+    client
+        .call(
+            Method::POST,
+            "/events/batch-check",
+            "/events/batch-check",
+            Some(json!({ "tokens": [public_token] })),
+        )
+        .await;
+    client
+        .call(
+            Method::POST,
+            "/events/conflicts",
+            "/events/conflicts",
+            Some(json!({ "organizer_tokens": [organizer_token] })),
+        )
+        .await;
+    client
+        .call(
+            Method::PATCH,
+            "/events/{organizer_token}",
+            &format!("/events/{}", organizer_token),
+            Some(json!({ "title": "Contract Event (renamed)" })),
+        )
+        .await;
+    client
+        .call(
+            Method::POST,
+            "/events/{organizer_token}/finalize",
+            &format!("/events/{}/finalize", organizer_token),
+            Some(json!({
+                "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]
+            })),
+        )
+        .await;
+    client
+        .call(
+            Method::POST,
+            "/events/{organizer_token}/assign",
+            &format!("/events/{}/assign", organizer_token),
+            Some(json!({})),
+        )
+        .await;
+    client
+        .call(
+            Method::PUT,
+            "/events/{organizer_token}/assign",
+            &format!("/events/{}/assign", organizer_token),
+            Some(json!({ "slots": [] })),
+        )
+        .await;
+    client
+        .call(
+            Method::GET,
+            "/events/{organizer_token}/assign",
+            &format!("/events/{}/assign", organizer_token),
+            None,
+        )
+        .await;
+    client
+        .call(
+            Method::POST,
+            "/events/{organizer_token}/edit-lock",
+            &format!("/events/{}/edit-lock", organizer_token),
+            Some(json!({ "holder_name": "Organizer" })),
+        )
+        .await;
+    client
+        .call(
+            Method::GET,
+            "/events/organizer/{organizer_token}",
+            &format!("/events/organizer/{}", organizer_token),
+            None,
+        )
+        .await;
+    client
+        .call(
+            Method::DELETE,
+            "/events/{organizer_token}/edit-lock",
+            &format!("/events/{}/edit-lock", organizer_token),
+            None,
+        )
+        .await;
+    let (_, transfer) = client
+        .call(
+            Method::POST,
+            "/events/{organizer_token}/transfer",
+            &format!("/events/{}/transfer", organizer_token),
+            None,
+        )
+        .await;
+    let (status, claimed) = client
+        .call(
+            Method::POST,
+            "/events/claim/{claim_token}",
+            &format!(
+                "/events/claim/{}",
+                transfer["claim_token"].as_str().unwrap()
+            ),
+            Some(json!({ "organizer_name": "New Organizer" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let organizer_token = claimed["organizer_token"].as_str().unwrap().to_string();
+    client
+        .call(
+            Method::POST,
+            "/events/{organizer_token}/close",
+            &format!("/events/{}/close", organizer_token),
+            None,
+        )
+        .await;
+    client
+        .call(
+            Method::POST,
+            "/events/{organizer_token}/archive",
+            &format!("/events/{}/archive", organizer_token),
+            None,
+        )
+        .await;
+    let (status, _) = client
+        .call(
+            Method::POST,
+            "/events/{public_token}/availability",
+            &format!("/events/{}/availability", public_token),
+            Some(availability_body("Too late")),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = client
+        .call(
+            Method::DELETE,
+            "/events/{organizer_token}",
+            &format!("/events/{}", organizer_token),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = client
+        .call(
+            Method::POST,
+            "/events/{organizer_token}/restore",
+            &format!("/events/{}/restore", organizer_token),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_error_responses_match_contract(pool: PgPool) {
+    let client = ContractClient::new(pool);
+
+    let (status, _) = client
+        .call(
+            Method::GET,
+            "/events/{public_token}",
+            "/events/does-not-exist",
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let mut invalid = create_event_body();
+    invalid["title"] = json!("");
+    let (status, _) = client
+        .call(Method::POST, "/events", "/events", Some(invalid))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let tokens: Vec<String> = (0..51).map(|i| i.to_string()).collect();
+    let (status, _) = client
+        .call(
+            Method::POST,
+            "/events/batch-check",
+            "/events/batch-check",
+            Some(json!({ "tokens": tokens })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_spec_and_swagger_ui_are_served(pool: PgPool) {
+    let client = ContractClient::new(pool);
+
+    let request = Request::builder()
+        .uri("/openapi.json")
+        .body(Body::empty())
+        .unwrap();
+    let response = client.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let served: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(served, client.spec);
+
+    let request = Request::builder().uri("/docs").body(Body::empty()).unwrap();
+    let response = client.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(String::from_utf8_lossy(&bytes).contains(r#"url: "/openapi.json""#));
+}
+
+#[sqlx::test]
+#[should_panic(expected = "violates the contract")]
+async fn test_contract_detects_drift(pool: PgPool) {
+    let client = ContractClient::new(pool);
+
+    // `public_token` missing and `id` not a UUID
+    let drifted = json!({ "id": "not-a-uuid", "organizer_token": "x" });
+    client.assert_matches(&Method::POST, "/events", StatusCode::OK, &drifted);
+}
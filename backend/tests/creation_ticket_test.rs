@@ -0,0 +1,103 @@
+mod common;
+
+use agreed_time_backend::{config::Config, routes::create_router_with_state, state::AppState};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use common::send_request;
+use serde_json::json;
+use sqlx::PgPool;
+
+const FRONTEND: &str = "https://meet.example.com";
+
+fn create_test_app(pool: PgPool, secret: Option<&str>) -> Router {
+    let config = Config {
+        allowed_origins: vec![FRONTEND.to_string()],
+        creation_ticket_secret: secret.map(str::to_string),
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_config(config))
+}
+
+fn request_ticket(origin: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder().method("POST").uri("/events/ticket");
+    if let Some(origin) = origin {
+        builder = builder.header("Origin", origin);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+fn create_event(origin: Option<&str>, ticket: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/events")
+        .header("Content-Type", "application/json");
+    if let Some(origin) = origin {
+        builder = builder.header("Origin", origin);
+    }
+    if let Some(ticket) = ticket {
+        builder = builder.header("X-Creation-Ticket", ticket);
+    }
+    let body = json!({
+        "title": "Ticketed",
+        "description": null,
+        "organizer_name": "Alice",
+        "time_zone": "UTC",
+        "slot_duration": 60,
+        "time_slots": [
+            { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+        ]
+    });
+    builder
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn test_tickets_are_optional_by_default(pool: PgPool) {
+    let app = create_test_app(pool, None);
+
+    let (status, _) = send_request(&app, create_event(None, None)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send_request(&app, request_ticket(Some(FRONTEND))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_create_requires_ticket_for_same_origin(pool: PgPool) {
+    let app = create_test_app(pool, Some("ticket-secret"));
+
+    let (status, body) = send_request(&app, create_event(Some(FRONTEND), None)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "CREATION_TICKET_REQUIRED");
+
+    // Only the frontend's origin gets tickets
+    for origin in [None, Some("https://elsewhere.example")] {
+        let (status, _) = send_request(&app, request_ticket(origin)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    let (status, issued) = send_request(&app, request_ticket(Some(FRONTEND))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(issued["expires_at"].is_string());
+    let ticket = issued["ticket"].as_str().unwrap();
+
+    // Bound to the origin it was issued for
+    let (status, _) = send_request(&app, create_event(None, Some(ticket))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send_request(
+        &app,
+        create_event(Some("https://elsewhere.example"), Some(ticket)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send_request(&app, create_event(Some(FRONTEND), Some("1.00"))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, created) = send_request(&app, create_event(Some(FRONTEND), Some(ticket))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(created["public_token"].is_string());
+}
@@ -0,0 +1,226 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event, create_event_tokens, send, try_create_event};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// Three consecutive days and one more in Tokyo.
+async fn create_date_poll(app: &Router) -> (String, String) {
+    create_event_tokens(
+        app,
+        json!({
+            "title": "Team trip",
+            "time_zone": "Asia/Tokyo",
+            "poll_type": "date",
+            "dates": ["2030-01-02", "2030-01-03", "2030-01-04", "2030-01-10"],
+            "time_slots": []
+        }),
+    )
+    .await
+}
+
+async fn tick(app: &Router, public_token: &str, name: &str, body: Value) -> Value {
+    let mut body = body;
+    body["participant_name"] = json!(name);
+    let (status, submitted) = send(
+        app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        body,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", submitted);
+    submitted
+}
+
+fn counts(results: &Value) -> Vec<(String, u64)> {
+    results["date_votes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|votes| {
+            (
+                votes["date"].as_str().unwrap().to_string(),
+                votes["count"].as_u64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_date_poll_stores_whole_local_days(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_date_poll(&app).await;
+
+    let (status, event) = send(
+        &app,
+        "GET",
+        &format!("/events/{}", public_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["poll_type"], "date");
+    assert_eq!(event["slot_duration"], 1440);
+    assert_eq!(
+        event["dates"],
+        json!(["2030-01-02", "2030-01-03", "2030-01-04", "2030-01-10"])
+    );
+    // Consecutive days merge; days start at Tokyo midnight
+    assert_eq!(event["event_slots"].as_array().unwrap().len(), 2);
+    assert_eq!(event["event_slots"][0]["start_at"], "2030-01-01T15:00:00Z");
+    assert_eq!(event["event_slots"][0]["end_at"], "2030-01-04T15:00:00Z");
+}
+
+#[sqlx::test]
+async fn test_results_count_votes_per_date(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = create_date_poll(&app).await;
+
+    tick(
+        &app,
+        &public_token,
+        "Bob",
+        json!({ "dates": ["2030-01-03", "2030-01-04"] }),
+    )
+    .await;
+    let carol = tick(
+        &app,
+        &public_token,
+        "Carol",
+        json!({ "dates": ["2030-01-10"] }),
+    )
+    .await;
+    // Ranges and levels still work next to ticked dates
+    tick(
+        &app,
+        &public_token,
+        "Dave",
+        json!({
+            "dates": ["2030-01-03"],
+            "availabilities": [{
+                "start_at": "2030-01-03T15:00:00Z",
+                "end_at": "2030-01-04T15:00:00Z",
+                "availability_level": "if_need_be"
+            }]
+        }),
+    )
+    .await;
+
+    let (status, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    // The organizer offered, and so can make, every date
+    assert_eq!(
+        counts(&results),
+        vec![
+            ("2030-01-02".to_string(), 1),
+            ("2030-01-03".to_string(), 3),
+            ("2030-01-04".to_string(), 2),
+            ("2030-01-10".to_string(), 2),
+        ]
+    );
+    assert_eq!(results["date_votes"][2]["if_need_be"], json!(["Dave"]));
+
+    // Editing replaces the ticks
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!(
+            "/events/{}/participants/{}",
+            public_token,
+            carol["participant_token"].as_str().unwrap()
+        ),
+        json!({ "participant_name": "Carol", "dates": ["2030-01-02"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, organizer_view) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(organizer_view["date_votes"][0]["count"], 2);
+    assert_eq!(organizer_view["date_votes"][3]["count"], 1);
+}
+
+#[sqlx::test]
+async fn test_update_date_poll(pool: PgPool) {
+    let app = create_router(pool);
+    let (_, organizer_token) = create_date_poll(&app).await;
+    let uri = format!("/events/{}", organizer_token);
+
+    let (status, updated) = send(
+        &app,
+        "PATCH",
+        &uri,
+        json!({ "add_dates": ["2030-01-11"], "remove_dates": ["2030-01-03"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", updated);
+    assert_eq!(
+        updated["dates"],
+        json!(["2030-01-02", "2030-01-04", "2030-01-10", "2030-01-11"])
+    );
+
+    for body in [
+        json!({ "add_slots": [{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }] }),
+        json!({ "time_zone": "UTC" }),
+        json!({ "slot_duration": 60 }),
+    ] {
+        let (status, _) = send(&app, "PATCH", &uri, body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[sqlx::test]
+async fn test_dates_only_belong_to_date_polls(pool: PgPool) {
+    let app = create_router(pool);
+    let slots = json!([{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }]);
+
+    for fields in [
+        json!({ "dates": ["2030-01-02"], "time_slots": [] }),
+        json!({ "poll_type": "date", "dates": [], "time_slots": [] }),
+        json!({ "poll_type": "date", "dates": ["2030-01-02"], "time_slots": slots }),
+        json!({
+            "poll_type": "date",
+            "slot_kind": "weekly",
+            "weekly_slots": [{ "weekday": "Mon", "start_time": "09:00", "end_time": "10:00" }],
+            "time_slots": []
+        }),
+    ] {
+        let (status, body) = try_create_event(&app, fields).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    }
+
+    let created = create_event(&app, json!({ "title": "Sync", "time_slots": slots })).await;
+    let public_token = created["public_token"].as_str().unwrap();
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({ "participant_name": "Bob", "dates": ["2030-01-01"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(results["poll_type"], "datetime");
+    assert_eq!(results["date_votes"], json!([]));
+}
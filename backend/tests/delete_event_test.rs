@@ -0,0 +1,216 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock,
+    db::cleanup::{delete_expired_events, purge_deleted_events},
+    routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use chrono::{DateTime, Utc};
+use common::send;
+use serde_json::json;
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+
+const DAY: Duration = Duration::from_secs(24 * 3600);
+
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>) -> Router {
+    create_router_with_state(AppState::new(pool).with_clock(clock))
+}
+
+fn now() -> DateTime<Utc> {
+    "2029-12-01T00:00:00Z".parse().unwrap()
+}
+
+#[sqlx::test]
+async fn test_delete_restore_and_purge(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock.clone());
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Private",
+            "description": null,
+            "organizer_name": "Organizer",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({
+            "participant_name": "Guest",
+            "availabilities": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }
+            ],
+            "comment": null
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // The public token cannot delete
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/events/{}", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/events/{}", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    // Trashed events behave as not found everywhere
+    for uri in [
+        format!("/events/{}", public_token),
+        format!("/events/{}/results", public_token),
+        format!("/events/organizer/{}", organizer_token),
+    ] {
+        let (status, _) = send(&app, "GET", &uri, json!({})).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+    }
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, statuses) = send(
+        &app,
+        "POST",
+        "/events/batch-check",
+        json!({ "tokens": [public_token] }),
+    )
+    .await;
+    assert_eq!(statuses["statuses"], json!({}));
+
+    // Deleting twice is a 404
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/events/{}", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Restoring brings everything back
+    let restore_uri = format!("/events/{}/restore", organizer_token);
+    let (status, restored) = send(&app, "POST", &restore_uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(restored["title"], "Private");
+    let (status, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results["total_participants"], 2);
+
+    let (status, _) = send(&app, "POST", &restore_uri, json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Deleted again and left in the trash past the grace period: gone for good
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/events/{}", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    clock.advance(DAY * 6);
+    assert_eq!(
+        purge_deleted_events(&pool, clock.as_ref()).await.unwrap(),
+        0
+    );
+    clock.advance(DAY * 2);
+    assert_eq!(
+        purge_deleted_events(&pool, clock.as_ref()).await.unwrap(),
+        1
+    );
+
+    let leftovers = sqlx::query_scalar!(
+        r#"
+        SELECT (SELECT COUNT(*) FROM events)
+             + (SELECT COUNT(*) FROM event_slots)
+             + (SELECT COUNT(*) FROM participants)
+             + (SELECT COUNT(*) FROM availabilities) AS "count!"
+        "#
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(leftovers, 0);
+
+    let (status, _) = send(&app, "POST", &restore_uri, json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_expired_events_cannot_be_restored(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock.clone());
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Old",
+            "description": null,
+            "organizer_name": "Organizer",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    clock.advance(DAY * 8);
+    assert_eq!(
+        delete_expired_events(&pool, clock.as_ref()).await.unwrap(),
+        1
+    );
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!(
+            "/events/{}/restore",
+            created["organizer_token"].as_str().unwrap()
+        ),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
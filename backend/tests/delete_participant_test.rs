@@ -0,0 +1,154 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+async fn join(app: &Router, public_token: &str, name: &str) -> String {
+    let (status, submitted) = send(
+        app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        Some(json!({
+            "participant_name": name,
+            "availabilities": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }
+            ]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    submitted["participant_token"].as_str().unwrap().to_string()
+}
+
+/// Participant ids by name, from the organizer view.
+async fn participant_ids(app: &Router, organizer_token: &str) -> Vec<(String, i64)> {
+    let (status, view) = send(
+        app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    view["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| {
+            (
+                p["name"].as_str().unwrap().to_string(),
+                p["id"].as_i64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_organizer_removes_participant(pool: PgPool) {
+    let app = create_router(pool.clone());
+    let (public_token, organizer_token) = create_event_tokens(
+        &app,
+        json!({ "webhook_url": "https://hooks.example.com/agreed-time" }),
+    )
+    .await;
+    let spam_token = join(&app, &public_token, "Spam").await;
+    join(&app, &public_token, "Bob").await;
+
+    let ids = participant_ids(&app, &organizer_token).await;
+    let spam_id = ids.iter().find(|(name, _)| name == "Spam").unwrap().1;
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/events/{}/participants/{}", organizer_token, spam_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let names: Vec<String> = participant_ids(&app, &organizer_token)
+        .await
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(names, vec!["Alice", "Bob"]);
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM availabilities WHERE participant_id = $1")
+            .bind(spam_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(remaining, 0);
+
+    // Their link stops working and the removal is announced
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/participants/{}", public_token, spam_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let payload: String = sqlx::query_scalar(
+        "SELECT payload FROM webhook_deliveries WHERE event_type = 'participant.removed'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let webhook: Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(webhook["data"]["participant_name"], "Spam");
+
+    // Already gone
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/events/{}/participants/{}", organizer_token, spam_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_remove_participant_guards(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = create_event_tokens(
+        &app,
+        json!({ "webhook_url": "https://hooks.example.com/agreed-time" }),
+    )
+    .await;
+    join(&app, &public_token, "Bob").await;
+    let ids = participant_ids(&app, &organizer_token).await;
+    let organizer_id = ids.iter().find(|(name, _)| name == "Alice").unwrap().1;
+    let bob_id = ids.iter().find(|(name, _)| name == "Bob").unwrap().1;
+
+    let (status, body) = send(
+        &app,
+        "DELETE",
+        &format!("/events/{}/participants/{}", organizer_token, organizer_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "The organizer cannot be removed");
+
+    // Neither the public token nor another event's organizer token will do
+    let (_, other_organizer_token) = create_event_tokens(
+        &app,
+        json!({ "webhook_url": "https://hooks.example.com/agreed-time" }),
+    )
+    .await;
+    for token in [&public_token, &other_organizer_token] {
+        let (status, _) = send(
+            &app,
+            "DELETE",
+            &format!("/events/{}/participants/{}", token, bob_id),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,236 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock,
+    db::cleanup::{delete_expired_events, notify_upcoming_deletions},
+    encryption::Keyring,
+    integrations::templates::Templates,
+    routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use chrono::{DateTime, Utc};
+use common::{create_event, create_event_tokens, send};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>) -> Router {
+    create_router_with_state(AppState::new(pool).with_clock(clock))
+}
+
+fn now() -> DateTime<Utc> {
+    "2029-12-01T00:00:00Z".parse().unwrap()
+}
+
+fn notice() -> chrono::Duration {
+    chrono::Duration::hours(48)
+}
+
+async fn queued(pool: &PgPool) -> Vec<(String, String)> {
+    sqlx::query!("SELECT recipient, subject FROM email_outbox ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|mail| (mail.recipient, mail.subject))
+        .collect()
+}
+
+async fn trashed(pool: &PgPool, organizer_token: &str) -> bool {
+    sqlx::query_scalar!(
+        "SELECT deleted_at IS NOT NULL AS \"trashed!\" FROM events WHERE organizer_token = $1",
+        organizer_token
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn test_organizer_is_warned_once_before_deletion(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock.clone());
+    create_event(
+        &app,
+        json!({ "title": "Offsite", "organizer_email": "alice@example.com" }),
+    )
+    .await;
+    // Without an address there is nobody to warn
+    create_event(&app, json!({})).await;
+
+    clock.advance(Duration::from_secs(4 * 24 * 3600));
+    assert_eq!(
+        notify_upcoming_deletions(
+            &pool,
+            clock.as_ref(),
+            &Templates::default(),
+            &Keyring::default(),
+            notice()
+        )
+        .await
+        .unwrap(),
+        0
+    );
+
+    // Two days before the seven-day retention runs out
+    clock.advance(Duration::from_secs(24 * 3600));
+    assert_eq!(
+        notify_upcoming_deletions(
+            &pool,
+            clock.as_ref(),
+            &Templates::default(),
+            &Keyring::default(),
+            notice()
+        )
+        .await
+        .unwrap(),
+        1
+    );
+    assert_eq!(
+        notify_upcoming_deletions(
+            &pool,
+            clock.as_ref(),
+            &Templates::default(),
+            &Keyring::default(),
+            notice()
+        )
+        .await
+        .unwrap(),
+        0
+    );
+
+    let queued = queued(&pool).await;
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].0, "alice@example.com");
+    assert!(queued[0].1.contains("Offsite"));
+}
+
+#[sqlx::test]
+async fn test_organizer_can_opt_out_of_the_warning(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock.clone());
+    let (_, organizer_token) = create_event_tokens(
+        &app,
+        json!({ "title": "Offsite", "organizer_email": "alice@example.com" }),
+    )
+    .await;
+
+    let (status, updated) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        json!({ "deletion_notice": false }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", updated);
+
+    let (_, organizer_view) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(organizer_view["organizer_email"], "alice@example.com");
+    assert_eq!(organizer_view["deletion_notice"], false);
+
+    clock.advance(Duration::from_secs(6 * 24 * 3600));
+    assert_eq!(
+        notify_upcoming_deletions(
+            &pool,
+            clock.as_ref(),
+            &Templates::default(),
+            &Keyring::default(),
+            notice()
+        )
+        .await
+        .unwrap(),
+        0
+    );
+    assert!(queued(&pool).await.is_empty());
+}
+
+#[sqlx::test]
+async fn test_extend_postpones_deletion_and_rearms_the_warning(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock.clone());
+    let (_, organizer_token) = create_event_tokens(
+        &app,
+        json!({ "title": "Offsite", "organizer_email": "alice@example.com" }),
+    )
+    .await;
+
+    clock.advance(Duration::from_secs(6 * 24 * 3600));
+    assert_eq!(
+        notify_upcoming_deletions(
+            &pool,
+            clock.as_ref(),
+            &Templates::default(),
+            &Keyring::default(),
+            notice()
+        )
+        .await
+        .unwrap(),
+        1
+    );
+
+    let (status, extended) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/extend", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", extended);
+    assert_eq!(extended["expires_at"], "2029-12-14T00:00:00Z");
+
+    // The original expiry passes without the event being trashed
+    clock.advance(Duration::from_secs(2 * 24 * 3600));
+    delete_expired_events(&pool, clock.as_ref()).await.unwrap();
+    assert!(!trashed(&pool, &organizer_token).await);
+
+    // The new expiry is announced again
+    clock.advance(Duration::from_secs(3 * 24 * 3600));
+    assert_eq!(
+        notify_upcoming_deletions(
+            &pool,
+            clock.as_ref(),
+            &Templates::default(),
+            &Keyring::default(),
+            notice()
+        )
+        .await
+        .unwrap(),
+        1
+    );
+    assert_eq!(queued(&pool).await.len(), 2);
+
+    clock.advance(Duration::from_secs(3 * 24 * 3600));
+    delete_expired_events(&pool, clock.as_ref()).await.unwrap();
+    assert!(trashed(&pool, &organizer_token).await);
+}
+
+#[sqlx::test]
+async fn test_organizer_email_is_validated(pool: PgPool) {
+    let app = create_test_app(pool, Arc::new(TestClock::new(now())));
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Offsite",
+            "description": null,
+            "organizer_name": "Alice",
+            "organizer_email": "not-an-address",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,168 @@
+use agreed_time_backend::{
+    clock::TestClock, config::Config, db::cleanup, middleware::DEMO_HEADER,
+    routes::create_router_with_state, state::AppState,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{TimeZone, Utc};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt; // for `oneshot`
+
+const ADMIN_KEY: &str = "test-admin-key";
+
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>, demo_mode: bool) -> Router {
+    let config = Config {
+        admin_api_key: Some(ADMIN_KEY.to_string()),
+        demo_mode,
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_clock(clock).with_config(config))
+}
+
+/// Returns the status, the demo header and the JSON body.
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    admin_key: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Option<String>, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(key) = admin_key {
+        request = request.header("Authorization", format!("Bearer {}", key));
+    }
+    let body = match body {
+        Some(body) => {
+            request = request.header("Content-Type", "application/json");
+            Body::from(serde_json::to_vec(&body).unwrap())
+        }
+        None => Body::empty(),
+    };
+    let response = app
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let demo = response
+        .headers()
+        .get(DEMO_HEADER)
+        .map(|value| value.to_str().unwrap().to_string());
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        demo,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+fn event_body(webhook_url: Option<&str>) -> Value {
+    json!({
+        "title": "Sandbox",
+        "description": null,
+        "organizer_name": "Alice",
+        "time_slots": [
+            { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+        ],
+        "webhook_url": webhook_url
+    })
+}
+
+#[sqlx::test]
+async fn test_demo_mode_caps_and_restricts_events(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(
+        Utc.with_ymd_and_hms(2029, 12, 1, 12, 0, 0).unwrap(),
+    ));
+    let app = create_test_app(pool.clone(), clock.clone(), true);
+
+    let (status, demo, capabilities) = send(&app, "GET", "/capabilities", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(demo.is_some());
+    assert_eq!(capabilities["demo_mode"], true);
+    assert_eq!(capabilities["limits"]["demo_retention_hours"], 24);
+
+    let (status, demo, _) = send(
+        &app,
+        "POST",
+        "/events",
+        None,
+        Some(event_body(Some("https://hooks.example.com/x"))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    // Errors are watermarked too
+    assert!(demo.is_some());
+
+    let (status, _, created) = send(&app, "POST", "/events", None, Some(event_body(None))).await;
+    assert_eq!(status, StatusCode::OK);
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    let (_, _, event) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(event["expires_at"], "2029-12-02T12:00:00Z");
+
+    let (status, _, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/extend", organizer_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    clock.advance(Duration::from_secs(25 * 3600));
+    let trashed = cleanup::delete_expired_events(&pool, clock.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(trashed, 1);
+}
+
+#[sqlx::test]
+async fn test_demo_reset(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(
+        Utc.with_ymd_and_hms(2029, 12, 1, 12, 0, 0).unwrap(),
+    ));
+
+    // Outside demo mode the hook doesn't exist and nothing is watermarked
+    let regular = create_test_app(pool.clone(), clock.clone(), false);
+    let (status, demo, _) = send(&regular, "POST", "/demo/reset", Some(ADMIN_KEY), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(demo.is_none());
+
+    let app = create_test_app(pool.clone(), clock, true);
+    let (_, _, created) = send(&app, "POST", "/events", None, Some(event_body(None))).await;
+    send(&app, "POST", "/organizers", None, None).await;
+
+    let (status, _, _) = send(&app, "POST", "/demo/reset", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _, reset) = send(&app, "POST", "/demo/reset", Some(ADMIN_KEY), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(reset["events"], 1);
+    assert_eq!(reset["organizer_accounts"], 1);
+
+    let (status, _, _) = send(
+        &app,
+        "GET",
+        &format!("/events/{}", created["public_token"].as_str().unwrap()),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
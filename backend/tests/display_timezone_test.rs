@@ -0,0 +1,159 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    display_zone: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let headers: Vec<(&str, &str)> = display_zone
+        .map(|zone| ("X-Display-Timezone", zone))
+        .into_iter()
+        .collect();
+    common::send_with_headers(app, method, uri, &headers, body).await
+}
+
+#[sqlx::test]
+async fn test_display_timezone_header_adds_local_strings(pool: PgPool) {
+    let app = create_router(pool);
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        None,
+        Some(json!({
+            "title": "Standup",
+            "description": null,
+            "organizer_name": "Alice",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T11:00:00Z" },
+                { "start_at": "2030-01-01T22:00:00Z", "end_at": "2030-01-02T01:00:00Z" }
+            ]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_uri = format!("/events/{}", created["public_token"].as_str().unwrap());
+    let organizer_uri = format!(
+        "/events/organizer/{}",
+        created["organizer_token"].as_str().unwrap()
+    );
+
+    let (status, event) = send(&app, "GET", &public_uri, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(event["display"].is_null());
+
+    let (status, event) = send(&app, "GET", &public_uri, Some("Europe/Paris"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let display = &event["display"];
+    assert_eq!(display["time_zone"], "Europe/Paris");
+    assert_eq!(
+        display["event_slots"],
+        json!([
+            "Tue 2030-01-01 10:00–12:00 CET",
+            "Tue 2030-01-01 23:00 CET – Wed 2030-01-02 02:00 CET"
+        ])
+    );
+    assert!(display["reveal_results_at"].is_null());
+    // Depends on the real clock, so either side of the DST change
+    let expires_at = display["expires_at"].as_str().unwrap();
+    assert!(
+        expires_at.ends_with(" CET") || expires_at.ends_with(" CEST"),
+        "{}",
+        expires_at
+    );
+    // UTC instants are unchanged
+    assert_eq!(event["event_slots"][0]["start_at"], "2030-01-01T09:00:00Z");
+
+    let (status, organizer) =
+        send(&app, "GET", &organizer_uri, Some("America/New_York"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        organizer["display"]["event_slots"][0],
+        "Tue 2030-01-01 04:00–06:00 EST"
+    );
+
+    let (status, _) = send(&app, "GET", &public_uri, Some("Mars/Olympus"), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_tz_query_renders_slot_ranges_in_zone(pool: PgPool) {
+    let app = create_router(pool);
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        None,
+        Some(json!({
+            "title": "Planning",
+            "description": null,
+            "organizer_name": "Alice",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T11:00:00Z" },
+                { "start_at": "2030-07-01T09:00:00Z", "end_at": "2030-07-01T11:00:00Z" }
+            ]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap();
+
+    // Zone names are matched case-insensitively and win over the header
+    let uri = format!("/events/{}?tz=europe/berlin", public_token);
+    let (status, event) = send(&app, "GET", &uri, Some("Asia/Tokyo"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let display = &event["display"];
+    assert_eq!(display["time_zone"], "Europe/Berlin");
+    let ranges = &display["event_slot_ranges"];
+    assert_eq!(ranges[0]["start_at"], "2030-01-01T10:00:00+01:00");
+    assert_eq!(ranges[0]["end_at"], "2030-01-01T12:00:00+01:00");
+    assert_eq!(ranges[0]["label"], "Tue 2030-01-01 10:00–12:00 CET");
+    assert_eq!(ranges[1]["start_at"], "2030-07-01T11:00:00+02:00");
+
+    let uri = format!("/events/{}/results?tz=America/New_York", public_token);
+    let (status, results) = send(&app, "GET", &uri, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results["display"]["time_zone"], "America/New_York");
+    assert_eq!(
+        results["display"]["event_slot_ranges"][0]["start_at"],
+        "2030-01-01T04:00:00-05:00"
+    );
+
+    let uri = format!("/events/{}/results", public_token);
+    let (status, results) = send(&app, "GET", &uri, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(results["display"].is_null());
+
+    let uri = format!("/events/{}/suggestions?tz=Asia/Tokyo", public_token);
+    let (status, suggestions) = send(&app, "GET", &uri, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    for suggestion in suggestions["suggestions"].as_array().unwrap() {
+        assert!(
+            suggestion["display"]["start_at"]
+                .as_str()
+                .unwrap()
+                .ends_with("+09:00")
+        );
+    }
+    let uri = format!("/events/{}/suggestions", public_token);
+    let (status, suggestions) = send(&app, "GET", &uri, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    for suggestion in suggestions["suggestions"].as_array().unwrap() {
+        assert!(suggestion.get("display").is_none());
+    }
+
+    let uri = format!("/events/{}?tz=Mars/Olympus", public_token);
+    let (status, _) = send(&app, "GET", &uri, None, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -1,10 +1,17 @@
+use agreed_time_backend::cache::ResponseCache;
+use agreed_time_backend::config::Config;
+use agreed_time_backend::encryption::Keyring;
 use agreed_time_backend::handlers::events::submit_availability;
+use agreed_time_backend::integrations::templates::SharedTemplates;
 use agreed_time_backend::models::{SubmitAvailabilityRequest, TimeRangeRequest};
+use agreed_time_backend::realtime::SharedHub;
 use axum::Json;
 use axum::extract::{Path, State};
+use axum::http::HeaderMap;
 use chrono::{Duration, Utc};
 use sqlx::postgres::PgPoolOptions;
 use std::env;
+use std::sync::Arc;
 use uuid::Uuid;
 
 #[tokio::test]
@@ -34,10 +41,10 @@ async fn test_duplicate_participant_names_allowed() {
     sqlx::query!(
         r#"
         INSERT INTO events (
-            id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at
+            id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at, expires_at
         )
         VALUES (
-            $1, $2, $3, 'Duplicate Name Test Event', NULL, 'open', 'UTC', 60, $4, $4
+            $1, $2, $3, 'Duplicate Name Test Event', NULL, 'open', 'UTC', 60, $4, $4, $4::timestamptz + INTERVAL '7 days'
         )
         "#,
         event_id,
@@ -67,13 +74,29 @@ async fn test_duplicate_participant_names_allowed() {
         availabilities: vec![TimeRangeRequest {
             start_at: Utc::now(),
             end_at: Utc::now() + Duration::hours(1),
+            availability_level: None,
         }],
         comment: Some("I am the imposter Alice".to_string()),
+        buffer_minutes: None,
+        time_zone: None,
+        participant_token: None,
+        email: None,
+        role: None,
+        password: None,
+        dates: vec![],
+        captcha_token: None,
     };
 
     let result = submit_availability(
         State(pool.clone()),
+        State(agreed_time_backend::clock::system()),
+        State(Arc::new(ResponseCache::new(std::time::Duration::ZERO))),
+        State(Arc::new(Config::default())),
+        State(Arc::new(Keyring::default())),
+        State(SharedTemplates::default()),
+        State(SharedHub::default()),
         Path(public_token.clone()),
+        HeaderMap::new(),
         Json(payload_duplicate),
     )
     .await;
@@ -0,0 +1,108 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock, models::EditLock, routes::create_router_with_state, state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use chrono::Utc;
+use common::{create_event_tokens, send};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+
+async fn setup(pool: PgPool) -> (Router, TestClock, String) {
+    let clock = TestClock::new(Utc::now());
+    let app = create_router_with_state(AppState::new(pool).with_clock(Arc::new(clock.clone())));
+    let (_, organizer_token) = create_event_tokens(&app, json!({ "title": "Co-hosted" })).await;
+    (app, clock, organizer_token)
+}
+
+async fn acquire(app: &Router, organizer_token: &str, holder: &str) -> (StatusCode, Value) {
+    send(
+        app,
+        "POST",
+        &format!("/events/{}/edit-lock", organizer_token),
+        Some(json!({ "holder_name": holder })),
+    )
+    .await
+}
+
+async fn organizer_view(app: &Router, organizer_token: &str) -> Value {
+    let (status, body) = send(
+        app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    body
+}
+
+#[sqlx::test]
+async fn test_lock_surfaces_in_organizer_view(pool: PgPool) {
+    let (app, _, organizer_token) = setup(pool).await;
+
+    assert!(organizer_view(&app, &organizer_token).await["edit_lock"].is_null());
+
+    let (status, body) = acquire(&app, &organizer_token, "Alice").await;
+    assert_eq!(status, StatusCode::OK);
+    let lock: EditLock = serde_json::from_value(body).unwrap();
+    assert_eq!(lock.holder_name, "Alice");
+
+    let view = organizer_view(&app, &organizer_token).await;
+    assert_eq!(view["edit_lock"]["holder_name"], "Alice");
+}
+
+#[sqlx::test]
+async fn test_second_editor_conflicts_until_expiry(pool: PgPool) {
+    let (app, clock, organizer_token) = setup(pool).await;
+
+    let (status, _) = acquire(&app, &organizer_token, "Alice").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = acquire(&app, &organizer_token, "Bob").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "CONFLICT");
+    assert!(body["error"].as_str().unwrap().contains("Alice"));
+
+    clock.advance(Duration::from_secs(121));
+    assert!(organizer_view(&app, &organizer_token).await["edit_lock"].is_null());
+
+    let (status, _) = acquire(&app, &organizer_token, "Bob").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_renew_keeps_acquired_at(pool: PgPool) {
+    let (app, clock, organizer_token) = setup(pool).await;
+
+    let (_, first) = acquire(&app, &organizer_token, "Alice").await;
+    let first: EditLock = serde_json::from_value(first).unwrap();
+
+    clock.advance(Duration::from_secs(60));
+    let (status, renewed) = acquire(&app, &organizer_token, "Alice").await;
+    assert_eq!(status, StatusCode::OK);
+    let renewed: EditLock = serde_json::from_value(renewed).unwrap();
+
+    assert_eq!(renewed.acquired_at, first.acquired_at);
+    assert!(renewed.expires_at > first.expires_at);
+}
+
+#[sqlx::test]
+async fn test_release_frees_lock(pool: PgPool) {
+    let (app, _, organizer_token) = setup(pool).await;
+
+    acquire(&app, &organizer_token, "Alice").await;
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/events/{}/edit-lock", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, _) = acquire(&app, &organizer_token, "Bob").await;
+    assert_eq!(status, StatusCode::OK);
+}
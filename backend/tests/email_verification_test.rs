@@ -0,0 +1,186 @@
+mod common;
+
+use agreed_time_backend::{
+    config::Config, db::verification::MAX_PENDING_VERIFICATIONS, routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send, submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+const PUBLIC_URL: &str = "https://meet.example.com";
+
+fn create_test_app(pool: PgPool, public_url: Option<&str>) -> Router {
+    let config = Config {
+        mail_from: Some("agreed-time@example.com".to_string()),
+        public_url: public_url.map(str::to_string),
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_config(config))
+}
+
+/// Answers for Bob with `email`, replacing his earlier answer when a token
+/// is given; returns his participant token.
+async fn answer_with_email(
+    app: &Router,
+    public_token: &str,
+    token: Option<&str>,
+    email: &str,
+) -> String {
+    let submitted = submit(
+        app,
+        public_token,
+        json!({ "participant_token": token, "email": email, "availabilities": [] }),
+    )
+    .await;
+    submitted["participant_token"].as_str().unwrap().to_string()
+}
+
+/// Paths of the confirmation links mailed so far, oldest first.
+async fn mailed_links(pool: &PgPool) -> Vec<String> {
+    let bodies: Vec<String> = sqlx::query_scalar("SELECT body FROM email_outbox ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .unwrap();
+    bodies
+        .iter()
+        .filter_map(|body| body.split_whitespace().find(|w| w.starts_with(PUBLIC_URL)))
+        .map(|link| link[PUBLIC_URL.len()..].to_string())
+        .collect()
+}
+
+async fn bob_verified(app: &Router, organizer_token: &str) -> Value {
+    let (status, view) = send(
+        app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    view["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == "Bob")
+        .unwrap()["email_verified"]
+        .clone()
+}
+
+#[sqlx::test]
+async fn test_link_confirms_address(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some(PUBLIC_URL));
+    let (public_token, organizer_token) =
+        create_event_tokens(&app, json!({ "title": "Offsite" })).await;
+    answer_with_email(&app, &public_token, None, "bob@example.com").await;
+
+    let links = mailed_links(&pool).await;
+    assert_eq!(links.len(), 1);
+    assert!(links[0].starts_with("/participants/verify-email?token="));
+    assert_eq!(bob_verified(&app, &organizer_token).await, false);
+
+    // Unconfirmed addresses get no reminders
+    let remind = format!("/events/{}/remind", organizer_token);
+    let (status, body) = send(&app, "POST", &remind, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["recipients"], json!([]));
+
+    let (status, confirmed) = send(&app, "GET", &links[0], Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(confirmed["email"], "bob@example.com");
+    assert_eq!(confirmed["title"], "Offsite");
+    assert_eq!(confirmed["public_token"], public_token.as_str());
+    assert_eq!(bob_verified(&app, &organizer_token).await, true);
+
+    // Single use
+    let (status, _) = send(&app, "GET", &links[0], Value::Null).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = send(&app, "POST", &remind, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["recipients"], json!(["Bob"]));
+
+    // The organizer's business only
+    let (_, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        Value::Null,
+    )
+    .await;
+    let bob = results["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == "Bob")
+        .unwrap();
+    assert!(bob.get("email_verified").is_none());
+}
+
+#[sqlx::test]
+async fn test_link_opens_a_frontend_page(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some(PUBLIC_URL));
+    let (public_token, _) = create_event_tokens(&app, json!({ "title": "Offsite" })).await;
+    answer_with_email(&app, &public_token, None, "bob@example.com").await;
+
+    let links = mailed_links(&pool).await;
+    assert_eq!(links.len(), 1);
+    assert!(common::frontend_has_page(&links[0]), "{}", links[0]);
+}
+
+#[sqlx::test]
+async fn test_changed_address_needs_confirming_again(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some(PUBLIC_URL));
+    let (public_token, organizer_token) =
+        create_event_tokens(&app, json!({ "title": "Offsite" })).await;
+    let token = answer_with_email(&app, &public_token, None, "bob@example.com").await;
+    let (status, _) = send(&app, "GET", &mailed_links(&pool).await[0], Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Only the case changed: still confirmed, nothing mailed
+    answer_with_email(&app, &public_token, Some(&token), "Bob@Example.com").await;
+    assert_eq!(bob_verified(&app, &organizer_token).await, true);
+    assert_eq!(mailed_links(&pool).await.len(), 1);
+
+    answer_with_email(&app, &public_token, Some(&token), "bob@work.example").await;
+    assert_eq!(bob_verified(&app, &organizer_token).await, false);
+    let links = mailed_links(&pool).await;
+    assert_eq!(links.len(), 2);
+
+    // A link for the replaced address confirms nothing
+    answer_with_email(&app, &public_token, Some(&token), "bobby@example.com").await;
+    let (status, _) = send(&app, "GET", &links[1], Value::Null).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(bob_verified(&app, &organizer_token).await, false);
+
+    // Resubmitting resends until the participant holds the most unused
+    // links allowed; only the first one was spent
+    for _ in 0..MAX_PENDING_VERIFICATIONS {
+        answer_with_email(&app, &public_token, Some(&token), "bobby@example.com").await;
+    }
+    assert_eq!(
+        mailed_links(&pool).await.len() as i64,
+        1 + MAX_PENDING_VERIFICATIONS
+    );
+}
+
+#[sqlx::test]
+async fn test_no_link_without_public_url(pool: PgPool) {
+    let app = create_test_app(pool.clone(), None);
+    let (public_token, organizer_token) =
+        create_event_tokens(&app, json!({ "title": "Offsite" })).await;
+    answer_with_email(&app, &public_token, None, "bob@example.com").await;
+
+    assert!(mailed_links(&pool).await.is_empty());
+    assert_eq!(bob_verified(&app, &organizer_token).await, false);
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        "/participants/verify-email?token=unknown",
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,313 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock,
+    config::Config,
+    db::keys::rotate_keys,
+    encryption::{DataKey, Keyring},
+    integrations::webhooks::{OutgoingWebhook, WebhookSender, deliver_pending, sign},
+    routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use chrono::Utc;
+use common::{create_event, send, submit};
+use serde_json::json;
+use sqlx::PgPool;
+use std::sync::Mutex;
+
+fn app(pool: PgPool, key: &DataKey, previous: Option<&DataKey>) -> Router {
+    let config = Config {
+        data_encryption_key: Some(key.clone()),
+        data_encryption_key_previous: previous.cloned(),
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_config(config))
+}
+
+fn new_key() -> DataKey {
+    DataKey::generate().parse().unwrap()
+}
+
+/// An event with a webhook and an organizer email, answered by Bob with a
+/// comment that he also posts to the discussion. Returns the public token,
+/// the organizer token and the webhook secret.
+async fn create_sealed_event(app: &Router) -> (String, String, String) {
+    let created = create_event(
+        app,
+        json!({
+            "title": "Sealed",
+            "organizer_email": "alice@example.com",
+            "webhook_url": "https://hooks.example.com/agreed-time"
+        }),
+    )
+    .await;
+    let public_token = created["public_token"].as_str().unwrap().to_string();
+
+    let submitted = submit(app, &public_token, json!({ "comment": "Mornings only" })).await;
+    let (status, _) = send(
+        app,
+        "POST",
+        &format!("/events/{}/comments", public_token),
+        Some(json!({
+            "participant_token": submitted["participant_token"],
+            "body": "See you there"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    (
+        public_token,
+        created["organizer_token"].as_str().unwrap().to_string(),
+        created["webhook_secret"].as_str().unwrap().to_string(),
+    )
+}
+
+/// Every sealed value as stored, in a fixed order. Deliveries only carry a
+/// secret of their own once the event is purged.
+async fn stored_values(pool: &PgPool) -> Vec<String> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT value FROM (
+            SELECT 1 AS n, webhook_secret AS value FROM events
+            UNION ALL SELECT 2, organizer_email FROM events
+            UNION ALL SELECT 3, comment FROM participants WHERE comment IS NOT NULL
+            UNION ALL SELECT 4, body FROM event_comments
+        ) sealed
+        ORDER BY n
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+/// What the API shows of the sealed values.
+async fn readable_values(app: &Router, public_token: &str, organizer_token: &str) -> Vec<String> {
+    let (status, organizer) = send(
+        app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", organizer);
+    let (status, results) = send(
+        app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", results);
+    let (status, comments) = send(
+        app,
+        "GET",
+        &format!("/events/{}/comments", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", comments);
+
+    let bob = results["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|participant| participant["name"] == "Bob")
+        .unwrap();
+    [
+        &organizer["webhook_secret"],
+        &organizer["organizer_email"],
+        &bob["comment"],
+        &comments["comments"][0]["body"],
+    ]
+    .into_iter()
+    .map(|value| value.as_str().unwrap().to_string())
+    .collect()
+}
+
+struct RecordingSender {
+    posted: Mutex<Vec<OutgoingWebhook>>,
+}
+
+impl WebhookSender for RecordingSender {
+    async fn post(&self, webhook: &OutgoingWebhook) -> Result<(), String> {
+        self.posted.lock().unwrap().push(webhook.clone());
+        Ok(())
+    }
+}
+
+#[sqlx::test]
+async fn test_sealed_columns_are_encrypted_and_read_back(pool: PgPool) {
+    let key = new_key();
+    let app = app(pool.clone(), &key, None);
+    let (public_token, organizer_token, secret) = create_sealed_event(&app).await;
+
+    let stored = stored_values(&pool).await;
+    assert_eq!(stored.len(), 4);
+    let prefix = Keyring::new(Some(key.clone()), None)
+        .current_prefix()
+        .unwrap();
+    for value in &stored {
+        assert!(value.starts_with(&prefix), "{}", value);
+    }
+    assert!(!stored.iter().any(|value| value.contains("example.com")));
+
+    assert_eq!(
+        readable_values(&app, &public_token, &organizer_token).await,
+        vec![
+            secret.clone(),
+            "alice@example.com".to_string(),
+            "Mornings only".to_string(),
+            "See you there".to_string()
+        ]
+    );
+
+    // Webhooks are signed with the secret, not its ciphertext
+    let sender = RecordingSender {
+        posted: Mutex::new(Vec::new()),
+    };
+    let clock = TestClock::new(Utc::now());
+    let report = deliver_pending(&pool, &sender, &Keyring::new(Some(key), None), &clock)
+        .await
+        .unwrap();
+    assert_eq!(report.sent, 1);
+    let posted = sender.posted.lock().unwrap().clone();
+    assert_eq!(posted[0].signature, Some(sign(&secret, &posted[0].payload)));
+
+    // Without the key the values can't be opened
+    let (status, _) = send(
+        &create_router_with_state(AppState::new(pool)),
+        "GET",
+        &format!("/events/{}/comments", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[sqlx::test]
+async fn test_rotate_keys_reencrypts_with_the_new_key(pool: PgPool) {
+    let old = new_key();
+    let new = new_key();
+    let (public_token, organizer_token, _) =
+        create_sealed_event(&app(pool.clone(), &old, None)).await;
+    let before = stored_values(&pool).await;
+
+    // During the rotation the servers read both keys
+    let rotating = app(pool.clone(), &new, Some(&old));
+    let expected = readable_values(&rotating, &public_token, &organizer_token).await;
+
+    let keys = Keyring::new(Some(new.clone()), Some(old));
+    let report = rotate_keys(&pool, &keys, 2).await.unwrap();
+    assert_eq!(report.total(), 4);
+    assert!(report.render().starts_with("Re-encrypted 4 values"));
+
+    let after = stored_values(&pool).await;
+    let prefix = keys.current_prefix().unwrap();
+    for (before, after) in before.iter().zip(&after) {
+        assert_ne!(before, after);
+        assert!(after.starts_with(&prefix), "{}", after);
+    }
+
+    // The old key is no longer needed, and a second run has nothing left
+    let rotated = app(pool.clone(), &new, None);
+    assert_eq!(
+        readable_values(&rotated, &public_token, &organizer_token).await,
+        expected
+    );
+    let report = rotate_keys(&pool, &Keyring::new(Some(new), None), 2)
+        .await
+        .unwrap();
+    assert_eq!(report.total(), 0);
+}
+
+#[sqlx::test]
+async fn test_rotate_keys_seals_plaintext_and_refuses_unknown_keys(pool: PgPool) {
+    let unencrypted = create_router_with_state(AppState::new(pool.clone()));
+    let (public_token, organizer_token, _) = create_sealed_event(&unencrypted).await;
+    assert!(
+        stored_values(&pool)
+            .await
+            .contains(&"plain:alice@example.com".to_string())
+    );
+
+    let key = new_key();
+    let report = rotate_keys(&pool, &Keyring::new(Some(key.clone()), None), 500)
+        .await
+        .unwrap();
+    assert_eq!(report.total(), 4);
+    let encrypted = app(pool.clone(), &key, None);
+    assert_eq!(
+        readable_values(&encrypted, &public_token, &organizer_token).await[1],
+        "alice@example.com"
+    );
+
+    // Values under a key the run wasn't given stop it
+    let error = rotate_keys(&pool, &Keyring::new(Some(new_key()), None), 500)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("unknown key"), "{}", error);
+}
+
+#[sqlx::test]
+async fn test_comments_that_look_sealed_read_back_as_typed(pool: PgPool) {
+    let typed = "enc:v1:x:y";
+    for app in [
+        create_router_with_state(AppState::new(pool.clone())),
+        app(pool.clone(), &new_key(), None),
+    ] {
+        let (status, created) = send(
+            &app,
+            "POST",
+            "/events",
+            Some(json!({
+                "title": "Lookalike",
+                "organizer_name": "Alice",
+                "time_slots": [
+                    { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+                ]
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let public_token = created["public_token"].as_str().unwrap();
+        let (status, _) = send(
+            &app,
+            "POST",
+            &format!("/events/{}/availability", public_token),
+            Some(json!({
+                "participant_name": "Bob",
+                "availabilities": [
+                    { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }
+                ],
+                "comment": typed
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, results) = send(
+            &app,
+            "GET",
+            &format!("/events/{}/results", public_token),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", results);
+        assert_eq!(results["participants"][1]["comment"], typed);
+        let (status, organizer) = send(
+            &app,
+            "GET",
+            &format!(
+                "/events/organizer/{}",
+                created["organizer_token"].as_str().unwrap()
+            ),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", organizer);
+        assert_eq!(organizer["participants"][1]["comment"], typed);
+    }
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock, etag::COUNTDOWN_BUCKET_SECS, routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use chrono::{TimeZone, Utc};
+use common::{create_event, send};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+use tower::ServiceExt; // for `oneshot`
+
+/// An app whose clock starts a countdown window, so tags only move when a
+/// test moves the clock.
+fn create_test_app(pool: PgPool) -> (Router, Arc<TestClock>) {
+    let clock = Arc::new(TestClock::new(
+        Utc.with_ymd_and_hms(2029, 12, 1, 12, 0, 0).unwrap(),
+    ));
+    let app = create_router_with_state(AppState::new(pool).with_clock(clock.clone()));
+    (app, clock)
+}
+
+/// GETs `uri`, conditionally on `etag`; returns the status, the ETag and
+/// the Cache-Control header.
+async fn get(app: &Router, uri: &str, etag: Option<&str>) -> (StatusCode, String, String) {
+    let mut request = Request::builder().uri(uri);
+    if let Some(etag) = etag {
+        request = request.header(header::IF_NONE_MATCH, etag);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .map(|value: &header::HeaderValue| value.to_str().unwrap().to_string())
+            .unwrap_or_default()
+    };
+    (
+        response.status(),
+        header(header::ETAG),
+        header(header::CACHE_CONTROL),
+    )
+}
+
+#[sqlx::test]
+async fn test_event_reads_answer_not_modified_until_something_changes(pool: PgPool) {
+    let (app, _) = create_test_app(pool);
+    let created = create_event(&app, json!({})).await;
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    for uri in [
+        format!("/events/{}", public_token),
+        format!("/events/{}/results", public_token),
+    ] {
+        let (status, etag, cache_control) = get(&app, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(etag.starts_with("W/\""));
+        assert_eq!(cache_control, "private, no-cache");
+
+        let (status, repeated, _) = get(&app, &uri, Some(&etag)).await;
+        assert_eq!(status, StatusCode::NOT_MODIFIED);
+        assert_eq!(repeated, etag);
+        let (status, _, _) = get(&app, &uri, Some("W/\"stale\"")).await;
+        assert_eq!(status, StatusCode::OK);
+        // The display zone is part of the representation
+        let (status, _, _) = get(&app, &format!("{}?tz=Asia/Tokyo", uri), Some(&etag)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (_, event_etag, _) = get(&app, &format!("/events/{}", public_token), None).await;
+    let results_uri = format!("/events/{}/results", public_token);
+    let (_, results_etag, _) = get(&app, &results_uri, None).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({
+            "participant_name": "Bob",
+            "availabilities": [
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ],
+            "comment": null
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, fresh_etag, _) = get(&app, &results_uri, Some(&results_etag)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(fresh_etag, results_etag);
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _, _) = get(
+        &app,
+        &format!("/events/{}", public_token),
+        Some(&event_etag),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_not_modified_still_checks_access(pool: PgPool) {
+    let (app, _) = create_test_app(pool);
+    let created = create_event(&app, json!({})).await;
+    let public_token = created["public_token"].as_str().unwrap();
+
+    let (status, _, _) = get(&app, &format!("/events/{}", public_token), Some("*")).await;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    let (status, _, _) = get(&app, "/events/missing", Some("*")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_countdowns_stop_not_modified_once_the_window_passes(pool: PgPool) {
+    let (app, clock) = create_test_app(pool);
+    let created = create_event(&app, json!({})).await;
+    let uri = format!("/events/{}", created["public_token"].as_str().unwrap());
+    let results_uri = format!(
+        "/events/{}/results",
+        created["public_token"].as_str().unwrap()
+    );
+    let (_, first) = send(&app, "GET", &uri, Value::Null).await;
+    let (_, etag, _) = get(&app, &uri, None).await;
+    let (_, results_etag, _) = get(&app, &results_uri, None).await;
+
+    // Within the window the countdown a client holds is close enough
+    clock.advance(Duration::from_secs(COUNTDOWN_BUCKET_SECS as u64 - 1));
+    let (status, _, _) = get(&app, &uri, Some(&etag)).await;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+
+    clock.advance(Duration::from_secs(1));
+    let (status, fresh_etag, _) = get(&app, &uri, Some(&etag)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(fresh_etag, etag);
+    let (_, event) = send(&app, "GET", &uri, Value::Null).await;
+    assert_eq!(
+        event["seconds_until_expiry"].as_i64().unwrap(),
+        first["seconds_until_expiry"].as_i64().unwrap() - COUNTDOWN_BUCKET_SECS
+    );
+
+    // Results carry no countdowns, so their tag holds
+    let (status, _, _) = get(&app, &results_uri, Some(&results_etag)).await;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+}
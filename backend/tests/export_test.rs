@@ -0,0 +1,81 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use agreed_time_backend::{db::export::export_event, encryption::Keyring};
+use axum::http::StatusCode;
+use common::send;
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn test_export_event_leaves_out_tokens(pool: PgPool) {
+    let app = create_router(pool.clone());
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Team lunch",
+            "organizer_name": "Bob",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    let (status, submitted) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({
+            "participant_name": "Jones, Carol",
+            "availabilities": [
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let export = export_event(&pool, &Keyring::default(), public_token)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(export.event.title, "Team lunch");
+    assert_eq!(export.slots.len(), 1);
+    assert_eq!(export.participants.len(), 2);
+    assert!(export.participants[0].is_organizer);
+    assert_eq!(export.participants[1].availabilities.len(), 1);
+
+    let dump = serde_json::to_string(&export).unwrap();
+    assert!(!dump.contains(organizer_token));
+    if let Some(participant_token) = submitted["participant_token"].as_str() {
+        assert!(!dump.contains(participant_token));
+    }
+
+    let by_organizer = export_event(&pool, &Keyring::default(), organizer_token)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_organizer.event.id, export.event.id);
+
+    assert_eq!(
+        export.to_csv(),
+        "participant,is_organizer,status,role,start_at,end_at,availability_level\n\
+         Bob,true,confirmed,,2030-01-01T09:00:00+00:00,2030-01-01T12:00:00+00:00,available\n\
+         \"Jones, Carol\",false,confirmed,,2030-01-01T10:00:00+00:00,2030-01-01T11:00:00+00:00,available\n"
+    );
+
+    assert!(
+        export_event(&pool, &Keyring::default(), "nope")
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
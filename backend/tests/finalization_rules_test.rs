@@ -0,0 +1,155 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send, submit, try_create_event};
+use serde_json::json;
+use sqlx::PgPool;
+
+/// Alice offers 9–12, Bob can make 9–11 and Carol 10–12.
+async fn create_answered_event(app: &Router) -> (String, String) {
+    let (public_token, organizer_token) = create_event_tokens(
+        app,
+        json!({
+            "finalization_rules": { "min_participants": 3, "required_participants": ["Bob"] }
+        }),
+    )
+    .await;
+    for (name, start, end) in [
+        ("Bob", "2030-01-01T09:00:00Z", "2030-01-01T11:00:00Z"),
+        ("Carol", "2030-01-01T10:00:00Z", "2030-01-01T12:00:00Z"),
+    ] {
+        submit(
+            app,
+            &public_token,
+            json!({
+                "participant_name": name,
+                "availabilities": [{ "start_at": start, "end_at": end }]
+            }),
+        )
+        .await;
+    }
+    (public_token, organizer_token)
+}
+
+#[sqlx::test]
+async fn test_suggestions_flag_windows_meeting_the_rules(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = create_answered_event(&app).await;
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/suggestions", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let suggestions = body["suggestions"].as_array().unwrap();
+    assert!(!suggestions.is_empty());
+    for suggestion in suggestions {
+        let names = suggestion["participants"].as_array().unwrap();
+        let conforming = names.len() >= 3 && names.contains(&json!("Bob"));
+        assert_eq!(suggestion["meets_rules"], conforming, "{}", suggestion);
+    }
+    assert_eq!(suggestions[0]["start_at"], "2030-01-01T10:00:00Z");
+    assert_eq!(suggestions[0]["end_at"], "2030-01-01T11:00:00Z");
+    assert_eq!(suggestions[0]["meets_rules"], true);
+
+    let (_, organizer) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(
+        organizer["finalization_rules"],
+        json!({ "min_participants": 3, "required_participants": ["Bob"] })
+    );
+}
+
+#[sqlx::test]
+async fn test_finalize_blocks_slots_missing_the_rules_unless_forced(pool: PgPool) {
+    let app = create_router(pool);
+    let (_, organizer_token) = create_answered_event(&app).await;
+    let finalize_uri = format!("/events/{}/finalize", organizer_token);
+    let late = json!([{ "start_at": "2030-01-01T11:00:00Z", "end_at": "2030-01-01T12:00:00Z" }]);
+
+    let (status, error) = send(&app, "POST", &finalize_uri, json!({ "slots": late })).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let message = error["error"].as_str().unwrap();
+    assert!(
+        message.contains("needs 3 participants, has 2"),
+        "{}",
+        message
+    );
+    assert!(message.contains("Bob can't attend"), "{}", message);
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &finalize_uri,
+        json!({ "slots": late, "force": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["state"], "finalized");
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &finalize_uri,
+        json!({
+            "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Dropping the rules lets any slot through
+    let (status, body) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        json!({ "finalization_rules": {} }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["finalization_rules"],
+        json!({ "min_participants": null, "required_participants": [] })
+    );
+    let (status, _) = send(&app, "POST", &finalize_uri, json!({ "slots": late })).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_finalization_rules_validation(pool: PgPool) {
+    let app = create_router(pool);
+    for rules in [
+        json!({ "min_participants": 0 }),
+        json!({ "min_participants": 11 }),
+        json!({ "required_participants": ["  "] }),
+        json!({ "required_participants": ["Bob", "Bob"] }),
+    ] {
+        let (status, error) =
+            try_create_event(&app, json!({ "finalization_rules": rules.clone() })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", rules);
+        assert_eq!(error["details"][0]["field"], "finalization_rules");
+    }
+
+    let (status, created) = try_create_event(&app, json!({})).await;
+    assert_eq!(status, StatusCode::OK, "rules are optional");
+    let (_, event) = send(
+        &app,
+        "GET",
+        &format!("/events/{}", created["public_token"].as_str().unwrap()),
+        json!({}),
+    )
+    .await;
+    assert_eq!(
+        event["finalization_rules"]["required_participants"],
+        json!([])
+    );
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use agreed_time_backend::models::{EventResultsResponse, EventState, FinalizeEventResponse};
+use axum::http::StatusCode;
+use common::{create_event_tokens, send};
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn test_finalize_event(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let (public_token, organizer_token) = create_event_tokens(&app, json!({})).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        Some(json!({
+            "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let finalized: FinalizeEventResponse = serde_json::from_value(body).unwrap();
+    assert_eq!(finalized.state, EventState::Finalized);
+    assert_eq!(finalized.final_slots.len(), 1);
+
+    // Participants see the decision
+    let (_, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        None,
+    )
+    .await;
+    let results: EventResultsResponse = serde_json::from_value(body).unwrap();
+    assert_eq!(results.state, EventState::Finalized);
+    assert_eq!(results.final_slots.len(), 1);
+    assert_eq!(
+        results.final_slots[0].start_at.to_rfc3339(),
+        "2030-01-01T10:00:00+00:00"
+    );
+
+    // Re-finalizing replaces the previous choice
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        Some(json!({
+            "slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" },
+                { "start_at": "2030-01-01T11:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["final_slots"].as_array().unwrap().len(), 2);
+}
+
+#[sqlx::test]
+async fn test_finalize_rejects_slot_outside_event(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let (_, organizer_token) = create_event_tokens(&app, json!({})).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        Some(json!({
+            "slots": [{ "start_at": "2030-01-01T11:00:00Z", "end_at": "2030-01-01T13:00:00Z" }]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        Some(json!({ "slots": [] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_finalize_unknown_event(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/events/not-a-token/finalize",
+        Some(json!({
+            "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,79 @@
+use agreed_time_backend::{config::PoolConfig, db::create_pool_lazy, routes::create_router};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt; // for `oneshot`
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, Value) {
+    let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[sqlx::test]
+async fn test_ready_when_database_is_migrated(pool: PgPool) {
+    let app = create_router(pool);
+
+    let (status, body) = get(&app, "/health/live").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ok");
+
+    let (status, body) = get(&app, "/health/ready").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ready");
+    assert_eq!(body["database"], true);
+    assert_eq!(body["migrations"], true);
+    assert_eq!(body["pending_migrations"], json!([]));
+    // The probe itself opened a connection
+    let open = body["pool"]["open"].as_u64().unwrap();
+    assert!(open >= 1);
+    assert!(open <= body["pool"]["max_connections"].as_u64().unwrap());
+}
+
+#[sqlx::test]
+async fn test_not_ready_with_pending_migrations(pool: PgPool) {
+    let latest: i64 = sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM _sqlx_migrations WHERE version = $1")
+        .bind(latest)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let app = create_router(pool);
+
+    let (status, body) = get(&app, "/health/ready").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["database"], true);
+    assert_eq!(body["migrations"], false);
+    assert_eq!(body["pending_migrations"], json!([latest]));
+}
+
+#[tokio::test]
+async fn test_live_but_not_ready_without_database() {
+    // Nothing listens on port 1
+    let app = create_router(create_pool_lazy(
+        "postgres://postgres@127.0.0.1:1/agreed_time",
+        &PoolConfig::default(),
+    ));
+
+    let (status, _) = get(&app, "/health/live").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = get(&app, "/health/ready").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "unavailable");
+    assert_eq!(body["database"], false);
+}
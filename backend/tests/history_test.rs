@@ -0,0 +1,174 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::http::StatusCode;
+use common::send;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+fn range(start: &str, end: &str) -> Value {
+    json!({ "start_at": start, "end_at": end })
+}
+
+fn bounds(ranges: &Value) -> Vec<(String, String)> {
+    ranges
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["start_at"].as_str().unwrap().to_string(),
+                r["end_at"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_history_lists_versions_with_diffs(pool: PgPool) {
+    let app = create_router(pool);
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        Some(json!({
+            "title": "Planning",
+            "description": null,
+            "organizer_name": "Alice",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                range("2030-01-04T09:00:00Z", "2030-01-04T17:00:00Z"),
+                range("2030-01-05T09:00:00Z", "2030-01-05T12:00:00Z")
+            ]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    let (status, submitted) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        Some(json!({
+            "participant_name": "Bob",
+            "availabilities": [
+                range("2030-01-04T09:00:00Z", "2030-01-04T17:00:00Z"),
+                range("2030-01-05T09:00:00Z", "2030-01-05T12:00:00Z")
+            ],
+            "comment": null
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Bob drops Friday afternoon
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!(
+            "/events/{}/participants/{}",
+            public_token,
+            submitted["participant_token"].as_str().unwrap()
+        ),
+        Some(json!({
+            "participant_name": "Bob",
+            "availabilities": [
+                range("2030-01-04T09:00:00Z", "2030-01-04T13:00:00Z"),
+                range("2030-01-05T09:00:00Z", "2030-01-05T12:00:00Z")
+            ],
+            "comment": null
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // The organizer drops Saturday, trimming Bob's availability too
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        Some(json!({
+            "remove_slots": [range("2030-01-05T09:00:00Z", "2030-01-05T12:00:00Z")]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, organizer) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    let bob = organizer["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == "Bob")
+        .unwrap();
+
+    let (status, history) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/{}/participants/{}/history",
+            organizer_token, bob["id"]
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(history["participant_name"], "Bob");
+    let versions = history["versions"].as_array().unwrap();
+    assert_eq!(versions.len(), 3);
+
+    let trimmed = &versions[0];
+    assert_eq!(trimmed["source"], "organizer");
+    assert!(bounds(&trimmed["added"]).is_empty());
+    assert_eq!(
+        bounds(&trimmed["removed"]),
+        [(
+            "2030-01-05T09:00:00Z".to_string(),
+            "2030-01-05T12:00:00Z".to_string()
+        )]
+    );
+    assert_eq!(
+        bounds(&trimmed["availabilities"]),
+        [(
+            "2030-01-04T09:00:00Z".to_string(),
+            "2030-01-04T13:00:00Z".to_string()
+        )]
+    );
+
+    let edited = &versions[1];
+    assert_eq!(edited["source"], "participant");
+    assert_eq!(
+        bounds(&edited["removed"]),
+        [(
+            "2030-01-04T13:00:00Z".to_string(),
+            "2030-01-04T17:00:00Z".to_string()
+        )]
+    );
+    assert_eq!(edited["removed"][0]["availability_level"], "available");
+
+    // The first version adds everything
+    assert_eq!(bounds(&versions[2]["added"]).len(), 2);
+    assert!(bounds(&versions[2]["removed"]).is_empty());
+
+    // The public link can't read it
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/{}/participants/{}/history",
+            public_token, bob["id"]
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,99 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt; // for `oneshot`
+
+async fn post(app: &Router, uri: &str, body: Value) -> Value {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn export(app: &Router, public_token: &str) -> (StatusCode, Option<String>, String) {
+    let request = Request::builder()
+        .uri(format!("/events/{}/export.ics", public_token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|v| v.to_str().unwrap().to_string());
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(bytes.to_vec()).unwrap(),
+    )
+}
+
+#[sqlx::test]
+async fn test_export_candidates_then_final_slot(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let created = post(
+        &app,
+        "/events",
+        json!({
+            "title": "Quarterly review",
+            "description": "Bring numbers",
+            "organizer_name": "Alice",
+            "time_zone": "Europe/Berlin",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    let (status, content_type, ics) = export(&app, public_token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(content_type.unwrap().starts_with("text/calendar"));
+    // Candidate windows are exported as stored, one VEVENT per range
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    assert!(ics.contains("DTSTART:20300101T090000Z\r\n"));
+    assert!(ics.contains("DTEND:20300101T110000Z\r\n"));
+    assert!(ics.contains("STATUS:TENTATIVE\r\n"));
+    assert!(ics.contains("SUMMARY:Quarterly review\r\n"));
+    assert!(ics.contains("X-WR-TIMEZONE:Europe/Berlin\r\n"));
+    assert!(ics.contains("CN=\"Alice\""));
+
+    post(
+        &app,
+        &format!("/events/{}/finalize", organizer_token),
+        json!({
+            "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]
+        }),
+    )
+    .await;
+
+    let (_, _, ics) = export(&app, public_token).await;
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    assert!(ics.contains("DTSTART:20300101T100000Z\r\n"));
+    assert!(ics.contains("STATUS:CONFIRMED\r\n"));
+}
+
+#[sqlx::test]
+async fn test_export_unknown_event(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let (status, _, _) = export(&app, "missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
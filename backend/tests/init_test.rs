@@ -0,0 +1,61 @@
+use agreed_time_backend::init::{self, ADMIN_KEY_LENGTH, InitOptions};
+use sqlx::{PgPool, Postgres, migrate::MigrateDatabase};
+use std::env;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_init_creates_database_and_env_file() {
+    let _ = dotenvy::dotenv();
+    let base_url = match env::var("DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => {
+            eprintln!("Skipping init test: DATABASE_URL not set");
+            return;
+        }
+    };
+    let name = format!("agreed_time_init_{}", Uuid::new_v4().simple());
+    let database_url = format!("{}/{}", &base_url[..base_url.rfind('/').unwrap()], name);
+    let env_file = env::temp_dir().join(format!("{}.env", name));
+
+    let options = || InitOptions {
+        env_file: env_file.clone(),
+        database_url: Some(database_url.clone()),
+        public_url: Some("https://meet.example.com".to_string()),
+        assume_defaults: true,
+        ..InitOptions::default()
+    };
+    let report = init::run(options()).await.unwrap();
+    assert!(report.database_created);
+    assert_eq!(report.settings.port, 3000);
+    assert_eq!(report.settings.admin_api_key.len(), ADMIN_KEY_LENGTH);
+    assert!(report.render().contains(&report.settings.admin_api_key));
+
+    let written = std::fs::read_to_string(&env_file).unwrap();
+    assert!(written.contains(&format!("DATABASE_URL={}\n", database_url)));
+    assert!(written.contains("ALLOWED_ORIGINS=https://meet.example.com\n"));
+    assert!(written.contains(&format!(
+        "ADMIN_API_KEY={}\n",
+        report.settings.admin_api_key
+    )));
+
+    let pool = PgPool::connect(&database_url).await.unwrap();
+    let events = sqlx::query_scalar!("SELECT COUNT(*) FROM events")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(events, Some(0));
+    pool.close().await;
+
+    // An existing env file is kept unless forced; the database is reused
+    assert!(init::run(options()).await.is_err());
+    let report = init::run(InitOptions {
+        force: true,
+        ..options()
+    })
+    .await
+    .unwrap();
+    assert!(!report.database_created);
+
+    std::fs::remove_file(&env_file).unwrap();
+    Postgres::drop_database(&database_url).await.unwrap();
+}
@@ -0,0 +1,373 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock,
+    config::Config,
+    integrations::mail::{DeliveryReport, MAX_ATTEMPTS, Mailer, OutgoingMail, deliver_pending},
+    routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use common::{create_event_tokens, send_request, submit, try_submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Mutex;
+
+const ADMIN_KEY: &str = "itip-test-key";
+
+fn create_test_app(pool: PgPool, mail_from: Option<&str>) -> Router {
+    let config = Config {
+        admin_api_key: Some(ADMIN_KEY.to_string()),
+        mail_from: mail_from.map(str::to_string),
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_config(config))
+}
+
+fn json_request(method: &str, uri: &str, body: Value) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap()
+}
+
+fn reply_request(calendar: String, key: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/integrations/itip/reply")
+        .header("content-type", "text/calendar");
+    if let Some(key) = key {
+        builder = builder.header("authorization", format!("Bearer {}", key));
+    }
+    builder.body(Body::from(calendar)).unwrap()
+}
+
+/// Creates an event with Bob (confirmed email) and Carol (no email), then
+/// finalizes it.
+async fn finalized_event(app: &Router, pool: &PgPool) -> (String, String) {
+    let (public_token, organizer_token) =
+        create_event_tokens(app, json!({ "title": "Launch" })).await;
+    for (name, email) in [("Bob", Some("Bob@Example.com")), ("Carol", None)] {
+        submit(
+            app,
+            &public_token,
+            json!({
+                "participant_name": name,
+                "email": email,
+                "availabilities": [
+                    { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+                ]
+            }),
+        )
+        .await;
+    }
+    sqlx::query("UPDATE participants SET email_verified_at = NOW() WHERE email IS NOT NULL")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    finalize(app, &organizer_token).await;
+    (public_token, organizer_token)
+}
+
+async fn finalize(app: &Router, organizer_token: &str) {
+    let (status, _) = send_request(
+        app,
+        json_request(
+            "POST",
+            &format!("/events/{}/finalize", organizer_token),
+            json!({
+                "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]
+            }),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+fn reply(public_token: &str, email: &str, partstat: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nMETHOD:REPLY\r\nBEGIN:VEVENT\r\nUID:{}-final-0@agreed-time\r\n\
+         ATTENDEE;PARTSTAT={}:mailto:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+        public_token, partstat, email
+    )
+}
+
+#[sqlx::test]
+async fn test_finalize_queues_request_per_invitee(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some("schedule@example.com"));
+    let (_, organizer_token) = finalized_event(&app, &pool).await;
+
+    let queued: Vec<(String, Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT recipient, calendar_method, calendar FROM email_outbox ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        queued.len(),
+        1,
+        "only participants with an email are invited"
+    );
+    let (recipient, method, calendar) = &queued[0];
+    assert_eq!(recipient, "Bob@Example.com");
+    assert_eq!(method.as_deref(), Some("REQUEST"));
+    let calendar = calendar.as_deref().unwrap();
+    assert!(calendar.contains("METHOD:REQUEST\r\n"));
+    assert!(calendar.contains("SEQUENCE:1\r\n"));
+    assert!(calendar.contains("mailto:schedule@example.com"));
+
+    // Re-finalizing sends an update with a higher sequence
+    finalize(&app, &organizer_token).await;
+    let latest: String =
+        sqlx::query_scalar("SELECT calendar FROM email_outbox ORDER BY id DESC LIMIT 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(latest.contains("SEQUENCE:2\r\n"));
+}
+
+#[sqlx::test]
+async fn test_no_invitations_without_mail_from(pool: PgPool) {
+    let app = create_test_app(pool.clone(), None);
+    finalized_event(&app, &pool).await;
+
+    let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(queued, 0);
+}
+
+#[sqlx::test]
+async fn test_reply_records_rsvp(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some("schedule@example.com"));
+    let (public_token, _) = finalized_event(&app, &pool).await;
+
+    let (status, _) = send_request(
+        &app,
+        reply_request(reply(&public_token, "bob@example.com", "ACCEPTED"), None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Address matching ignores case
+    let (status, body) = send_request(
+        &app,
+        reply_request(
+            reply(&public_token, "bob@example.com", "ACCEPTED"),
+            Some(ADMIN_KEY),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["participant_name"], "Bob");
+    assert_eq!(body["rsvp_status"], "accepted");
+
+    let (_, results) = send_request(
+        &app,
+        Request::builder()
+            .uri(format!("/events/{}/results", public_token))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    let bob = results["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == "Bob")
+        .unwrap();
+    assert_eq!(bob["rsvp_status"], "accepted");
+    assert!(bob.get("email").is_none(), "emails are never public");
+
+    let (status, _) = send_request(
+        &app,
+        reply_request(
+            reply(&public_token, "stranger@example.com", "DECLINED"),
+            Some(ADMIN_KEY),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send_request(
+        &app,
+        reply_request("BEGIN:VCALENDAR\r\n".to_string(), Some(ADMIN_KEY)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_invalid_email_rejected(pool: PgPool) {
+    let app = create_test_app(pool, None);
+    let (public_token, _) = create_event_tokens(&app, json!({ "title": "Launch" })).await;
+
+    let (status, _) = try_submit(
+        &app,
+        &public_token,
+        json!({ "email": "not an email", "availabilities": [] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+struct RecordingMailer {
+    fail: bool,
+    sent: Mutex<Vec<String>>,
+}
+
+impl Mailer for RecordingMailer {
+    async fn send(&self, mail: &OutgoingMail) -> Result<(), String> {
+        if self.fail {
+            return Err("connection refused".to_string());
+        }
+        self.sent.lock().unwrap().push(mail.recipient.clone());
+        Ok(())
+    }
+}
+
+#[sqlx::test]
+async fn test_deliver_pending_marks_sent_and_retries(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some("schedule@example.com"));
+    finalized_event(&app, &pool).await;
+    let clock = TestClock::new(Utc::now());
+
+    let failing = RecordingMailer {
+        fail: true,
+        sent: Mutex::new(Vec::new()),
+    };
+    assert_eq!(
+        deliver_pending(&pool, &failing, &clock).await.unwrap(),
+        DeliveryReport { sent: 0, failed: 1 }
+    );
+
+    let working = RecordingMailer {
+        fail: false,
+        sent: Mutex::new(Vec::new()),
+    };
+    assert_eq!(
+        deliver_pending(&pool, &working, &clock).await.unwrap(),
+        DeliveryReport { sent: 1, failed: 0 }
+    );
+    assert_eq!(*working.sent.lock().unwrap(), vec!["Bob@Example.com"]);
+
+    // Nothing left to send
+    assert_eq!(
+        deliver_pending(&pool, &working, &clock).await.unwrap(),
+        DeliveryReport::default()
+    );
+
+    let (attempts, last_error): (i32, Option<String>) =
+        sqlx::query_as("SELECT attempts, last_error FROM email_outbox")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(attempts, 2);
+    assert!(last_error.is_none());
+    assert!(attempts < MAX_ATTEMPTS);
+}
+
+async fn invitations(app: &Router, organizer_token: &str) -> Vec<Value> {
+    let (status, body) = send_request(
+        app,
+        Request::builder()
+            .uri(format!("/events/{}/invitations", organizer_token))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    body["invitations"].as_array().unwrap().clone()
+}
+
+#[sqlx::test]
+async fn test_invitation_status_follows_delivery(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some("schedule@example.com"));
+    let (_, organizer_token) = finalized_event(&app, &pool).await;
+    let clock = TestClock::new(Utc::now());
+
+    let listed = invitations(&app, &organizer_token).await;
+    assert_eq!(
+        listed.len(),
+        1,
+        "participants without an email are left out"
+    );
+    assert_eq!(listed[0]["participant_name"], "Bob");
+    assert_eq!(listed[0]["status"], "queued");
+    assert_eq!(listed[0]["attempts"], 0);
+
+    let failing = RecordingMailer {
+        fail: true,
+        sent: Mutex::new(Vec::new()),
+    };
+    for _ in 0..MAX_ATTEMPTS {
+        deliver_pending(&pool, &failing, &clock).await.unwrap();
+    }
+    let listed = invitations(&app, &organizer_token).await;
+    assert_eq!(listed[0]["status"], "failed");
+    assert_eq!(listed[0]["attempts"], MAX_ATTEMPTS);
+    assert_eq!(listed[0]["last_error"], "connection refused");
+
+    // A new finalization queues a fresh invitation, which goes out
+    finalize(&app, &organizer_token).await;
+    let working = RecordingMailer {
+        fail: false,
+        sent: Mutex::new(Vec::new()),
+    };
+    deliver_pending(&pool, &working, &clock).await.unwrap();
+    let listed = invitations(&app, &organizer_token).await;
+    assert_eq!(listed[0]["status"], "sent");
+    assert!(listed[0]["sent_at"].is_string());
+}
+
+#[sqlx::test]
+async fn test_finalize_without_invitations(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Some("schedule@example.com"));
+    let (public_token, _) = finalized_event(&app, &pool).await;
+    let organizer_token: String =
+        sqlx::query_scalar("SELECT organizer_token FROM events WHERE public_token = $1")
+            .bind(&public_token)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    sqlx::query("DELETE FROM email_outbox")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, body) = send_request(
+        &app,
+        json_request(
+            "POST",
+            &format!("/events/{}/finalize", organizer_token),
+            json!({
+                "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }],
+                "send_invitations": false
+            }),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["invitations_queued"], 0);
+
+    let listed = invitations(&app, &organizer_token).await;
+    assert_eq!(listed[0]["status"], "not_sent");
+
+    let (status, _) = send_request(
+        &app,
+        Request::builder()
+            .uri("/events/no-such-token/invitations")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,174 @@
+use agreed_time_backend::{
+    clock::TestClock,
+    config::Config,
+    db::{cleanup, jobs},
+    routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+use tower::ServiceExt; // for `oneshot`
+
+const ADMIN_KEY: &str = "test-admin-key";
+
+fn at(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>) -> Router {
+    let config = Config {
+        admin_api_key: Some(ADMIN_KEY.to_string()),
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_clock(clock).with_config(config))
+}
+
+async fn get(app: &Router, uri: &str, key: Option<&str>) -> (StatusCode, Value) {
+    let mut request = Request::builder().method("GET").uri(uri);
+    if let Some(key) = key {
+        request = request.header("Authorization", format!("Bearer {}", key));
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[sqlx::test]
+async fn test_track_records_success_and_failure(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(at("2030-01-01T00:00:00Z")));
+
+    let trashed = jobs::track(
+        &pool,
+        clock.as_ref(),
+        jobs::TRASH_EXPIRED,
+        cleanup::delete_expired_events(&pool, clock.as_ref()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(trashed, 0);
+
+    clock.advance(Duration::from_secs(60));
+    let failed: Result<u64, String> =
+        jobs::track(&pool, clock.as_ref(), jobs::MAIL_DELIVERY, async {
+            Err("smtp unreachable".to_string())
+        })
+        .await;
+    assert_eq!(failed, Err("smtp unreachable".to_string()));
+
+    let runs = jobs::recent_runs(&pool, None, false, 10).await.unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0].job, jobs::MAIL_DELIVERY);
+    assert_eq!(runs[0].started_at, at("2030-01-01T00:01:00Z"));
+    assert_eq!(runs[0].error.as_deref(), Some("smtp unreachable"));
+    assert_eq!(runs[1].job, jobs::TRASH_EXPIRED);
+    assert_eq!(runs[1].affected_rows, 0);
+    assert!(runs[1].error.is_none());
+}
+
+#[sqlx::test]
+async fn test_jobs_endpoint_reports_latest_status_per_job(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(at("2030-01-01T00:00:00Z")));
+    let app = create_test_app(pool.clone(), clock.clone());
+
+    let ok: Result<u64, String> = Ok(3);
+    jobs::track(&pool, clock.as_ref(), jobs::WEBHOOK_DELIVERY, async { ok })
+        .await
+        .unwrap();
+    clock.advance(Duration::from_secs(30));
+    let err: Result<u64, String> = Err("connection refused".to_string());
+    jobs::track(&pool, clock.as_ref(), jobs::WEBHOOK_DELIVERY, async { err })
+        .await
+        .unwrap_err();
+    jobs::track(&pool, clock.as_ref(), jobs::ARCHIVE_FINISHED, async {
+        Ok::<u64, String>(1)
+    })
+    .await
+    .unwrap();
+
+    let (status, _) = get(&app, "/admin/jobs", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = get(&app, "/admin/jobs", Some(ADMIN_KEY)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["runs"].as_array().unwrap().len(), 3);
+
+    let statuses = body["jobs"].as_array().unwrap();
+    assert_eq!(statuses.len(), 2);
+    let archive = &statuses[0];
+    assert_eq!(archive["job"], jobs::ARCHIVE_FINISHED);
+    assert!(archive["last_error"].is_null());
+    let webhooks = &statuses[1];
+    assert_eq!(webhooks["job"], jobs::WEBHOOK_DELIVERY);
+    assert_eq!(
+        webhooks["last_started_at"]
+            .as_str()
+            .unwrap()
+            .parse::<DateTime<Utc>>()
+            .unwrap(),
+        at("2030-01-01T00:00:30Z")
+    );
+    assert_eq!(
+        webhooks["last_succeeded_at"]
+            .as_str()
+            .unwrap()
+            .parse::<DateTime<Utc>>()
+            .unwrap(),
+        at("2030-01-01T00:00:00Z")
+    );
+    assert_eq!(webhooks["last_error"], "connection refused");
+
+    let (status, body) = get(
+        &app,
+        "/admin/jobs?job=webhooks.deliver&failed=true",
+        Some(ADMIN_KEY),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let runs = body["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0]["error"], "connection refused");
+    assert_eq!(runs[0]["affected_rows"], 0);
+}
+
+#[sqlx::test]
+async fn test_prune_drops_runs_past_retention(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(at("2030-01-01T00:00:00Z")));
+
+    jobs::track(&pool, clock.as_ref(), jobs::RESULTS_REVEAL, async {
+        Ok::<u64, String>(0)
+    })
+    .await
+    .unwrap();
+    clock.advance(Duration::from_secs(
+        jobs::JOB_RUN_RETENTION_DAYS as u64 * 86400 + 1,
+    ));
+    jobs::track(&pool, clock.as_ref(), jobs::RESULTS_REVEAL, async {
+        Ok::<u64, String>(0)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+        jobs::prune_job_runs(&pool, clock.as_ref()).await.unwrap(),
+        1
+    );
+    let runs = jobs::recent_runs(&pool, None, false, 10).await.unwrap();
+    assert_eq!(runs.len(), 1);
+}
@@ -0,0 +1,51 @@
+use agreed_time_backend::{
+    config::{Config, Plane},
+    routes::create_router_for,
+    state::AppState,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use sqlx::PgPool;
+use tower::ServiceExt; // for `oneshot`
+
+async fn status(app: &Router, uri: &str) -> StatusCode {
+    let request = Request::builder()
+        .uri(uri)
+        .header("Authorization", "Bearer test-admin-key")
+        .body(Body::empty())
+        .unwrap();
+    app.clone().oneshot(request).await.unwrap().status()
+}
+
+fn router(pool: PgPool, plane: Plane) -> Router {
+    let config = Config {
+        admin_api_key: Some("test-admin-key".to_string()),
+        ..Config::default()
+    };
+    create_router_for(plane, AppState::new(pool).with_config(config))
+}
+
+#[sqlx::test]
+async fn test_planes_split_public_and_admin_routes(pool: PgPool) {
+    let all = router(pool.clone(), Plane::All);
+    let public = router(pool.clone(), Plane::Public);
+    let internal = router(pool, Plane::Internal);
+
+    for app in [&all, &public, &internal] {
+        assert_eq!(status(app, "/health").await, StatusCode::OK);
+    }
+
+    assert_eq!(status(&all, "/admin/jobs").await, StatusCode::OK);
+    assert_eq!(status(&internal, "/admin/jobs").await, StatusCode::OK);
+    assert_eq!(status(&public, "/admin/jobs").await, StatusCode::NOT_FOUND);
+
+    assert_eq!(status(&all, "/capabilities").await, StatusCode::OK);
+    assert_eq!(status(&public, "/capabilities").await, StatusCode::OK);
+    assert_eq!(
+        status(&internal, "/capabilities").await,
+        StatusCode::NOT_FOUND
+    );
+}
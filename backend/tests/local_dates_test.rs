@@ -0,0 +1,90 @@
+mod common;
+
+use agreed_time_backend::models::EventResultsResponse;
+use axum::http::StatusCode;
+use chrono::NaiveDate;
+use common::send;
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn test_results_label_slots_in_participant_zone(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+
+    // One full UTC day
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        Some(json!({
+            "title": "All-day poll",
+            "description": null,
+            "organizer_name": "Organizer",
+            "time_zone": "UTC",
+            "slot_duration": 1440,
+            "time_slots": [
+                { "start_at": "2030-01-02T00:00:00Z", "end_at": "2030-01-03T00:00:00Z" }
+            ]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap();
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        Some(json!({
+            "participant_name": "Remote",
+            "availabilities": [
+                { "start_at": "2030-01-02T00:00:00Z", "end_at": "2030-01-03T00:00:00Z" }
+            ],
+            "comment": null,
+            "time_zone": "America/New_York"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        None,
+    )
+    .await;
+    let results: EventResultsResponse = serde_json::from_value(results).unwrap();
+
+    let date = |d| NaiveDate::from_ymd_opt(2030, 1, d).unwrap();
+
+    // Organizer falls back to the event zone
+    let organizer = &results.participants[0];
+    assert_eq!(organizer.slot_dates[0].start_date, date(2));
+    assert_eq!(organizer.slot_dates[0].end_date, date(2));
+
+    // The UTC day starts the previous evening in New York
+    let remote = &results.participants[1];
+    assert_eq!(remote.time_zone.as_deref(), Some("America/New_York"));
+    assert_eq!(remote.slot_dates[0].start_date, date(1));
+    assert_eq!(remote.slot_dates[0].end_date, date(2));
+}
+
+#[sqlx::test]
+async fn test_unknown_participant_zone_rejected(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/events/any-token/availability",
+        Some(json!({
+            "participant_name": "Remote",
+            "availabilities": [],
+            "comment": null,
+            "time_zone": "Mars/Olympus"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
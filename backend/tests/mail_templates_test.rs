@@ -0,0 +1,185 @@
+mod common;
+
+use agreed_time_backend::{
+    config::Config, integrations::templates::Templates, routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use common::{create_event, try_create_event};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+const ADMIN_KEY: &str = "admin-secret";
+
+fn create_test_app(pool: PgPool, templates: Templates) -> Router {
+    let config = Config {
+        mail_from: Some("agreed-time@example.com".to_string()),
+        admin_api_key: Some(ADMIN_KEY.to_string()),
+        ..Config::default()
+    };
+    create_router_with_state(
+        AppState::new(pool)
+            .with_config(config)
+            .with_templates(Arc::new(templates)),
+    )
+}
+
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let bearer = format!("Bearer {}", ADMIN_KEY);
+    common::send_with_headers(app, method, uri, &[("Authorization", &bearer)], body).await
+}
+
+#[sqlx::test]
+async fn test_mail_is_rendered_in_the_event_locale(pool: PgPool) {
+    let app = create_test_app(pool.clone(), Templates::default());
+    let created = create_event(&app, json!({ "title": "Offsite", "locale": "ja" })).await;
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    let (status, organizer_view) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(organizer_view["locale"], "ja");
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!(
+            "/events/{}/availability",
+            created["public_token"].as_str().unwrap()
+        ),
+        Some(json!({
+            "participant_name": "Bob",
+            "email": "bob@example.com",
+            "availabilities": []
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    sqlx::query!("UPDATE participants SET email_verified_at = NOW() WHERE name = 'Bob'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/remind", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let queued = sqlx::query!("SELECT subject, body, html FROM email_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        queued.subject,
+        "リマインダー: Offsiteの都合を入力してください"
+    );
+    assert!(queued.body.starts_with("Offsiteでは"));
+    let html = queued.html.unwrap();
+    assert!(html.contains("<html lang=\"ja\">"));
+    assert!(html.contains("<strong>Offsite</strong>"));
+
+    // Switching the locale applies to the next mail
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        Some(json!({ "locale": "en" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        Some(json!({ "locale": "tlh" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_create_rejects_unsupported_locale(pool: PgPool) {
+    let app = create_test_app(pool, Templates::default());
+
+    let (status, body) = try_create_event(&app, json!({ "locale": "tlh" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Unsupported locale (available: en, ja)");
+
+    let created = create_event(&app, json!({})).await;
+    let (_, organizer_view) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/organizer/{}",
+            created["organizer_token"].as_str().unwrap()
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(organizer_view["locale"], "en");
+}
+
+#[sqlx::test]
+async fn test_admin_preview_uses_loaded_templates(pool: PgPool) {
+    let dir = std::env::temp_dir().join(format!("mail-preview-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(dir.join("en")).unwrap();
+    std::fs::write(
+        dir.join("en/deletion_notice.subject"),
+        "Acme Polls: {{title}} expires {{deleted_at}}",
+    )
+    .unwrap();
+    let app = create_test_app(pool, Templates::load(Some(&dir)).unwrap());
+    std::fs::remove_dir_all(dir).unwrap();
+
+    let (status, preview) = send(
+        &app,
+        "GET",
+        "/admin/mail-templates/deletion_notice/preview",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(preview["locale"], "en");
+    assert_eq!(
+        preview["subject"],
+        "Acme Polls: Team offsite expires 2030-01-07 09:00 UTC"
+    );
+    assert!(preview["html"].as_str().unwrap().contains("Team offsite"));
+
+    let (status, preview) = send(
+        &app,
+        "GET",
+        "/admin/mail-templates/invitation/preview?locale=ja",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(preview["subject"], "招待: Team offsite");
+    assert!(preview["text"].as_str().unwrap().contains("- 2030-01-07"));
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        "/admin/mail-templates/newsletter/preview",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(
+        &app,
+        "GET",
+        "/admin/mail-templates/invitation/preview?locale=tlh",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock, config::Config, metrics, routes::create_router_with_state, state::AppState,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{DateTime, Utc};
+use common::{create_event, send, submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tower::ServiceExt; // for `oneshot`
+
+const ADMIN_KEY: &str = "test-admin-key";
+
+fn at(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>) -> Router {
+    let config = Config {
+        admin_api_key: Some(ADMIN_KEY.to_string()),
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_clock(clock).with_config(config))
+}
+
+/// An event with a single 09:00–10:00 UTC slot on `day`.
+async fn create_event_on(app: &Router, day: &str) -> Value {
+    create_event(
+        app,
+        json!({
+            "time_slots": [{
+                "start_at": format!("{}T09:00:00Z", day),
+                "end_at": format!("{}T10:00:00Z", day)
+            }]
+        }),
+    )
+    .await
+}
+
+#[sqlx::test]
+async fn test_collect_counts_states_and_events_at_risk(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(at("2030-01-01T00:00:00Z")));
+    let app = create_test_app(pool.clone(), clock.clone());
+
+    // Starts within 48h, nobody answered: at risk
+    create_event_on(&app, "2030-01-02").await;
+    // Starts within 48h but has a response
+    let answered = create_event_on(&app, "2030-01-02").await;
+    submit(
+        &app,
+        answered["public_token"].as_str().unwrap(),
+        json!({
+            "availabilities": [
+                { "start_at": "2030-01-02T09:00:00Z", "end_at": "2030-01-02T10:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    // Far in the future
+    let later = create_event_on(&app, "2030-02-01").await;
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!(
+            "/events/{}/close",
+            later["organizer_token"].as_str().unwrap()
+        ),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let snapshot = metrics::collect(&pool, clock.as_ref()).await.unwrap();
+    assert_eq!(snapshot.collected_at, at("2030-01-01T00:00:00Z"));
+    assert_eq!(snapshot.events_by_state["open"], 2);
+    assert_eq!(snapshot.events_by_state["closed"], 1);
+    assert_eq!(snapshot.events_by_state["finalized"], 0);
+    assert_eq!(snapshot.events_at_risk, 1);
+    assert_eq!(snapshot.outbox_pending, 0);
+    assert_eq!(snapshot.outbox_failed, 0);
+}
+
+#[sqlx::test]
+async fn test_collect_counts_outbox_backlog(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(at("2030-01-01T00:00:00Z")));
+    let app = create_test_app(pool.clone(), clock.clone());
+    let created = create_event_on(&app, "2030-02-01").await;
+    let event_id: uuid::Uuid = created["id"].as_str().unwrap().parse().unwrap();
+
+    for attempts in [0, 2, 5] {
+        sqlx::query!(
+            "INSERT INTO email_outbox (event_id, recipient, subject, body, attempts) VALUES ($1, 'a@example.com', 's', 'b', $2)",
+            event_id,
+            attempts
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query!(
+        "INSERT INTO email_outbox (event_id, recipient, subject, body, attempts, sent_at) VALUES ($1, 'a@example.com', 's', 'b', 1, $2)",
+        event_id,
+        at("2030-01-01T00:00:00Z")
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let snapshot = metrics::collect(&pool, clock.as_ref()).await.unwrap();
+    assert_eq!(snapshot.outbox_pending, 2);
+    assert_eq!(snapshot.outbox_failed, 1);
+}
+
+#[sqlx::test]
+async fn test_metrics_endpoint_requires_admin_key_and_renders_text(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(at("2030-01-01T00:00:00Z")));
+    let app = create_test_app(pool, clock);
+    create_event_on(&app, "2030-01-02").await;
+
+    let unauthorized = Request::builder()
+        .uri("/admin/metrics")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(unauthorized).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let request = Request::builder()
+        .uri("/admin/metrics")
+        .header("Authorization", format!("Bearer {}", ADMIN_KEY))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(text.contains("agreed_time_events{state=\"open\"} 1\n"));
+    assert!(text.contains("\nagreed_time_events_at_risk 1\n"));
+    assert!(text.contains("\nagreed_time_db_pool_connections{state=\"in_use\"} "));
+    assert!(text.contains("\nagreed_time_db_pool_max_connections "));
+}
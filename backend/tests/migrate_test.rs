@@ -0,0 +1,22 @@
+use agreed_time_backend::db::{self, MIGRATOR};
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = false)]
+async fn test_migrate_applies_everything_once_across_replicas(pool: PgPool) {
+    let embedded: Vec<i64> = MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| migration.version)
+        .collect();
+
+    // Two replicas starting at once: one applies, the other waits on the lock
+    let (first, second) = tokio::join!(db::migrate(&pool), db::migrate(&pool));
+    let mut applied = first.unwrap();
+    applied.extend(second.unwrap());
+    applied.sort();
+    assert_eq!(applied, embedded);
+    assert!(db::pending_migrations(&pool).await.unwrap().is_empty());
+
+    // Nothing left the next time
+    assert!(db::migrate(&pool).await.unwrap().is_empty());
+}
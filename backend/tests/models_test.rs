@@ -14,7 +14,30 @@ fn test_create_event_request_serialization() {
         time_slots: vec![TimeRangeRequest {
             start_at: Utc::now(),
             end_at: Utc::now(),
+            availability_level: None,
         }],
+        results_visibility: None,
+        reveal_results_at: None,
+        blind_until_closed: None,
+        anonymous_results: None,
+        roles: vec![],
+        finalization_rules: Default::default(),
+        webhook_url: None,
+        slack_webhook_url: None,
+        discord_webhook_url: None,
+        chat_webhook_url: None,
+        password: None,
+        close_at_quorum: None,
+        organizer_email: None,
+        invitees: Vec::new(),
+        slot_kind: None,
+        weekly_slots: vec![],
+        poll_type: None,
+        dates: vec![],
+        locale: None,
+        organizer_account_token: None,
+        retention_days: None,
+        captcha_token: None,
     };
 
     let json = serde_json::to_string(&request).unwrap();
@@ -42,6 +65,28 @@ fn test_create_event_request_optional_fields() {
         time_zone: None,
         slot_duration: None, // Added field
         time_slots: vec![],
+        results_visibility: None,
+        reveal_results_at: None,
+        blind_until_closed: None,
+        anonymous_results: None,
+        roles: vec![],
+        finalization_rules: Default::default(),
+        webhook_url: None,
+        slack_webhook_url: None,
+        discord_webhook_url: None,
+        chat_webhook_url: None,
+        password: None,
+        close_at_quorum: None,
+        organizer_email: None,
+        invitees: Vec::new(),
+        slot_kind: None,
+        weekly_slots: vec![],
+        poll_type: None,
+        dates: vec![],
+        locale: None,
+        organizer_account_token: None,
+        retention_days: None,
+        captcha_token: None,
     };
 
     let json = serde_json::to_string(&request).unwrap();
@@ -60,6 +105,8 @@ fn test_create_event_response_serialization() {
         id: Uuid::new_v4(),
         public_token: "public123".to_string(),
         organizer_token: "organizer456".to_string(),
+        results_token: "results789".to_string(),
+        webhook_secret: None,
     };
 
     let json = serde_json::to_string(&response).unwrap();
@@ -81,8 +128,17 @@ fn test_submit_availability_request() {
         availabilities: vec![TimeRangeRequest {
             start_at: start,
             end_at: end,
+            availability_level: None,
         }],
-        comment: Some("I'm late".to_string()), // Added field
+        comment: Some("I'm late".to_string()), // Added field,
+        buffer_minutes: None,
+        time_zone: None,
+        participant_token: None,
+        email: None,
+        role: None,
+        password: None,
+        dates: vec![],
+        captcha_token: None,
     };
 
     let json = serde_json::to_string(&request).unwrap();
@@ -102,11 +158,13 @@ fn test_submit_availability_response_serialization() {
     let token = Uuid::new_v4();
     let response = SubmitAvailabilityResponse {
         participant_token: token,
+        status: ParticipantStatus::Pending,
     };
 
     let json = serde_json::to_string(&response).unwrap();
     assert!(json.contains("\"participant_token\":"));
     assert!(json.contains(&token.to_string()));
+    assert!(json.contains("\"status\":\"pending\""));
 
     let deserialized: SubmitAvailabilityResponse = serde_json::from_str(&json).unwrap();
     assert_eq!(deserialized.participant_token, token);
@@ -120,6 +178,12 @@ fn test_participant_response_serialization() {
         name: "Test User".to_string(),
         comment: Some("My comment".to_string()),
         availabilities: vec![],
+        buffer_minutes: 0,
+        time_zone: None,
+        email: None,
+        rsvp_status: None,
+        role: None,
+        status: ParticipantStatus::Confirmed,
     };
 
     let json = serde_json::to_string(&response).unwrap();
@@ -139,23 +203,51 @@ fn test_event_results_response_structure() {
         description: Some("Discuss Q1 plans".to_string()),
         time_zone: Some("America/New_York".to_string()),
         slot_duration: 60, // Added field
-        state: "open".to_string(),
+        state: EventState::Open,
         event_slots: vec![],
         participants: vec![
             ParticipantAvailability {
+                id: 1,
                 name: "Alice".to_string(),
                 is_organizer: true,                // Added field
                 comment: Some("Host".to_string()), // Added field
                 availabilities: vec![],
+                buffer_minutes: 0,
+                time_zone: None,
+                slot_dates: vec![],
+                rsvp_status: None,
+                role: None,
+                email_verified: None,
+                display_order: None,
+                is_hidden: false,
             },
             ParticipantAvailability {
+                id: 2,
                 name: "Bob".to_string(),
                 is_organizer: false, // Added field
                 comment: None,       // Added field
                 availabilities: vec![],
+                buffer_minutes: 0,
+                time_zone: None,
+                slot_dates: vec![],
+                rsvp_status: None,
+                role: None,
+                email_verified: None,
+                display_order: None,
+                is_hidden: false,
             },
         ],
         total_participants: 2,
+        final_slots: vec![],
+        results_hidden: false,
+        anonymous_results: false,
+        slot_counts: vec![],
+        slot_kind: SlotKind::Dates,
+        weekly_slots: vec![],
+        poll_type: PollType::Datetime,
+        dates: vec![],
+        date_votes: vec![],
+        display: None,
     };
 
     let json = serde_json::to_string(&response).unwrap();
@@ -188,11 +280,41 @@ fn test_organizer_event_response_includes_created_at() {
         description: None,
         time_zone: Some("UTC".to_string()),
         slot_duration: 60, // Added field
-        state: "open".to_string(),
+        state: EventState::Open,
         event_slots: vec![],
         participants: vec![],
         total_participants: 0,
         created_at: now,
+        final_slots: vec![],
+        edit_lock: None,
+        results_visibility: ResultsVisibility::Public,
+        results_token: "res789".to_string(),
+        reveal_results_at: None,
+        blind_until_closed: false,
+        anonymous_results: false,
+        roles: vec![],
+        finalization_rules: Default::default(),
+        webhook_url: None,
+        webhook_secret: None,
+        slack_webhook_url: None,
+        discord_webhook_url: None,
+        chat_webhook_url: None,
+        seconds_until_deadline: None,
+        expires_at: now,
+        seconds_until_expiry: 0,
+        password_protected: false,
+        close_at_quorum: None,
+        display: None,
+        organizer_email: None,
+        invitees: Vec::new(),
+        deletion_notice: true,
+        retention_days: 7,
+        slot_kind: SlotKind::Dates,
+        weekly_slots: vec![],
+        poll_type: PollType::Datetime,
+        dates: vec![],
+        date_votes: vec![],
+        locale: "en".to_string(),
     };
 
     let json = serde_json::to_string(&response).unwrap();
@@ -0,0 +1,307 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock,
+    config::Config,
+    encryption::Keyring,
+    integrations::webhooks::{HttpSender, OutgoingWebhook, WebhookSender, deliver_pending},
+    routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use chrono::Utc;
+use common::send;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::{Arc, Mutex};
+
+const SLACK_URL: &str = "https://hooks.slack.com/services/T000/B000/XXXX";
+const DISCORD_URL: &str = "https://discord.com/api/webhooks/1234/abcd";
+const CHAT_URL: &str = "https://bridge.example.com/hooks/scheduling";
+
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>) -> Router {
+    let config = Config {
+        public_url: Some("https://meet.example.com".to_string()),
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_clock(clock).with_config(config))
+}
+
+fn create_body(chat_webhooks: Value) -> Value {
+    let mut body = json!({
+        "title": "Standup",
+        "description": null,
+        "organizer_name": "Alice",
+        "time_zone": "UTC",
+        "slot_duration": 60,
+        "time_slots": [
+            { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+        ],
+        "results_visibility": "private"
+    });
+    body.as_object_mut()
+        .unwrap()
+        .extend(chat_webhooks.as_object().unwrap().clone());
+    body
+}
+
+async fn deliver(pool: &PgPool, clock: &TestClock) -> Vec<OutgoingWebhook> {
+    let sender = RecordingSender {
+        posted: Mutex::new(Vec::new()),
+    };
+    deliver_pending(pool, &sender, &Keyring::default(), clock)
+        .await
+        .unwrap();
+    sender.posted.into_inner().unwrap()
+}
+
+struct RecordingSender {
+    posted: Mutex<Vec<OutgoingWebhook>>,
+}
+
+impl WebhookSender for RecordingSender {
+    async fn post(&self, webhook: &OutgoingWebhook) -> Result<(), String> {
+        self.posted.lock().unwrap().push(webhook.clone());
+        Ok(())
+    }
+}
+
+#[sqlx::test]
+async fn test_slack_messages_on_activity(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(Utc::now()));
+    let app = create_test_app(pool.clone(), clock.clone());
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        create_body(json!({ "slack_webhook_url": SLACK_URL })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+    // Slack alone doesn't make the event signed
+    assert_eq!(created["webhook_secret"], Value::Null);
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({
+            "participant_name": "Bob",
+            "availabilities": [
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ],
+            "comment": null
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        json!({
+            "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let posted = deliver(&pool, &clock).await;
+    let types: Vec<&str> = posted.iter().map(|w| w.event_type.as_str()).collect();
+    assert_eq!(
+        types,
+        ["participant.submitted", "event.closed", "event.finalized"]
+    );
+    assert!(
+        posted
+            .iter()
+            .all(|w| w.url == SLACK_URL && w.signature.is_none())
+    );
+
+    let messages: Vec<Value> = posted
+        .iter()
+        .map(|w| serde_json::from_str(&w.payload).unwrap())
+        .collect();
+    assert_eq!(
+        messages[0]["text"],
+        "*Bob* responded to *Standup* (2 responses so far)"
+    );
+    let best = messages[0]["blocks"][0]["text"]["text"].as_str().unwrap();
+    assert!(best.contains("Best slot so far: Tue 2030-01-01 10:00–11:00 UTC (2 of 2 available)"));
+    // Private results: the link carries the results token
+    let link = messages[0]["blocks"][1]["elements"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert!(link.starts_with(&format!(
+        "<https://meet.example.com/event/{}/result?results_token=",
+        public_token
+    )));
+    assert_eq!(messages[1]["text"], "*Standup* is closed for responses");
+    let last = messages[2]["blocks"][0]["text"]["text"].as_str().unwrap();
+    assert!(last.ends_with("Final time: Tue 2030-01-01 10:00–11:00 UTC"));
+
+    let (_, organizer) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(organizer["slack_webhook_url"], SLACK_URL);
+}
+
+#[sqlx::test]
+async fn test_chat_webhook_urls_are_validated(pool: PgPool) {
+    let app = create_test_app(pool, Arc::new(TestClock::new(Utc::now())));
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/events",
+        create_body(json!({
+            "slack_webhook_url": "https://example.com/services/T000",
+            "discord_webhook_url": "https://discord.com.example.com/api/webhooks/1/a",
+            "chat_webhook_url": "ftp://bridge.example.com/hooks"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let fields: Vec<&str> = body["details"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|detail| detail["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            "slack_webhook_url",
+            "discord_webhook_url",
+            "chat_webhook_url"
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn test_chat_webhook_urls_must_be_public(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(Utc::now()));
+    let app = create_test_app(pool.clone(), clock.clone());
+
+    for url in [
+        "http://127.0.0.1:8080/hook",
+        "http://localhost/hook",
+        "http://169.254.169.254/latest/meta-data",
+        "http://[fd00::1]/hook",
+    ] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/events",
+            create_body(json!({ "chat_webhook_url": url })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", url);
+        assert_eq!(body["details"][0]["field"], "chat_webhook_url", "{}", url);
+        assert_eq!(
+            body["details"][0]["message"],
+            "Chat webhook URL must point to a public address"
+        );
+    }
+
+    // A host that only turns private after creation is caught on delivery
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        create_body(json!({ "chat_webhook_url": CHAT_URL })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    sqlx::query("UPDATE events SET chat_webhook_url = 'http://127.0.0.1:9/hook'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+    send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+
+    let report = deliver_pending(&pool, &HttpSender, &Keyring::default(), clock.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(report.failed, 1);
+    let error: Option<String> =
+        sqlx::query_scalar("SELECT last_error FROM webhook_deliveries WHERE channel = 'chat'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(error.unwrap().contains("private"));
+}
+
+#[sqlx::test]
+async fn test_discord_and_generic_messages(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(Utc::now()));
+    let app = create_test_app(pool.clone(), clock.clone());
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        create_body(json!({ "discord_webhook_url": DISCORD_URL, "chat_webhook_url": CHAT_URL })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let posted = deliver(&pool, &clock).await;
+    assert_eq!(posted.len(), 2);
+    assert!(posted.iter().all(|w| w.signature.is_none()));
+    let discord = posted.iter().find(|w| w.url == DISCORD_URL).unwrap();
+    let message: Value = serde_json::from_str(&discord.payload).unwrap();
+    assert_eq!(message["content"], "**Standup** is closed for responses");
+    assert_eq!(message["embeds"][0]["title"], "Standup");
+    let chat = posted.iter().find(|w| w.url == CHAT_URL).unwrap();
+    let message: Value = serde_json::from_str(&chat.payload).unwrap();
+    assert_eq!(message["type"], "event.closed");
+    assert_eq!(message["title"], "Standup");
+    assert!(
+        message["results_url"]
+            .as_str()
+            .unwrap()
+            .starts_with("https://meet.example.com/event/")
+    );
+
+    let (_, organizer) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(organizer["slack_webhook_url"], Value::Null);
+    assert_eq!(organizer["discord_webhook_url"], DISCORD_URL);
+    assert_eq!(organizer["chat_webhook_url"], CHAT_URL);
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::http::StatusCode;
+use common::{create_event, send, try_create_event};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn test_organizer_account_lists_its_events(pool: PgPool) {
+    let app = create_router(pool);
+
+    let (status, account) = send(&app, "POST", "/organizers", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let account_token = account["account_token"].as_str().unwrap().to_string();
+
+    for title in ["First", "Second", "Third"] {
+        create_event(
+            &app,
+            json!({ "title": title, "organizer_account_token": account_token }),
+        )
+        .await;
+    }
+    // Not on the dashboard
+    create_event(&app, json!({ "title": "Elsewhere" })).await;
+
+    let (status, page) = send(
+        &app,
+        "GET",
+        &format!("/organizers/{}/events?limit=2", account_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["total"], 3);
+    let events = page["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["state"], "open");
+    assert_eq!(events[0]["total_participants"], 1);
+    assert_eq!(events[0]["pending_participants"], 0);
+    assert!(events[0]["organizer_token"].is_string());
+
+    let (_, next) = send(
+        &app,
+        "GET",
+        &format!("/organizers/{}/events?limit=2&offset=2", account_token),
+        None,
+    )
+    .await;
+    let mut titles: Vec<Value> = events
+        .iter()
+        .chain(next["events"].as_array().unwrap())
+        .map(|event| event["title"].clone())
+        .collect();
+    titles.sort_by_key(|title| title.to_string());
+    assert_eq!(
+        titles,
+        vec![json!("First"), json!("Second"), json!("Third")]
+    );
+}
+
+#[sqlx::test]
+async fn test_unknown_organizer_account(pool: PgPool) {
+    let app = create_router(pool);
+
+    let (status, body) =
+        try_create_event(&app, json!({ "organizer_account_token": "unknown" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Unknown organizer account token");
+
+    let (status, _) = send(&app, "GET", "/organizers/unknown/events", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
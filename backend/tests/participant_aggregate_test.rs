@@ -0,0 +1,116 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send, submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// An event on 2030-01-01 09:00–17:00 UTC where Bob offers `start`–`end` o'clock.
+async fn join_event(app: &Router, title: &str, start: u32, end: u32) -> (String, String, String) {
+    let (public_token, organizer_token) = create_event_tokens(
+        app,
+        json!({
+            "title": title,
+            "time_slots": [{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T17:00:00Z" }]
+        }),
+    )
+    .await;
+    let submitted = submit(
+        app,
+        &public_token,
+        json!({
+            "availabilities": [{
+                "start_at": format!("2030-01-01T{:02}:00:00Z", start),
+                "end_at": format!("2030-01-01T{:02}:00:00Z", end)
+            }]
+        }),
+    )
+    .await;
+    (
+        public_token,
+        organizer_token,
+        submitted["participant_token"].as_str().unwrap().to_string(),
+    )
+}
+
+fn spans(ranges: &Value) -> Vec<(String, String)> {
+    ranges
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["start_at"].as_str().unwrap().to_string(),
+                r["end_at"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_aggregate_merges_polls_and_flags_overlaps(pool: PgPool) {
+    let app = create_router(pool);
+    let (standup, _, standup_bob) = join_event(&app, "Standup", 9, 12).await;
+    let (review, review_organizer, review_bob) = join_event(&app, "Review", 11, 14).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", review_organizer),
+        Some(json!({
+            "slots": [{ "start_at": "2030-01-01T13:00:00Z", "end_at": "2030-01-01T14:00:00Z" }]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, aggregate) = send(
+        &app,
+        "GET",
+        &format!(
+            "/participants/aggregate?tokens={},not-a-token,{},{}",
+            standup_bob,
+            review_bob,
+            uuid::Uuid::new_v4()
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", aggregate);
+
+    let events = aggregate["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["public_token"], standup);
+    assert_eq!(events[0]["name"], "Bob");
+    assert_eq!(events[1]["title"], "Review");
+    assert_eq!(events[1]["state"], "finalized");
+
+    let at = |hour: u32| format!("2030-01-01T{:02}:00:00Z", hour);
+    assert_eq!(spans(&aggregate["busy"]), vec![(at(13), at(14))]);
+    assert_eq!(spans(&aggregate["free"]), vec![(at(9), at(13))]);
+    assert_eq!(spans(&aggregate["overlaps"]), vec![(at(11), at(12))]);
+    assert_eq!(
+        aggregate["overlaps"][0]["public_tokens"],
+        json!([standup, review])
+    );
+}
+
+#[sqlx::test]
+async fn test_aggregate_token_limit(pool: PgPool) {
+    let app = create_router(pool);
+
+    let (status, aggregate) = send(&app, "GET", "/participants/aggregate?tokens=", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(aggregate["events"], json!([]));
+
+    let tokens: Vec<String> = (0..51).map(|_| uuid::Uuid::new_v4().to_string()).collect();
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/participants/aggregate?tokens={}", tokens.join(",")),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
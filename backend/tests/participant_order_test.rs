@@ -0,0 +1,156 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send, submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// An event organized by Alice that Ann, Ben and Cat answered, in that
+/// order. Returns the public and organizer tokens.
+async fn create_answered_event(app: &Router) -> (String, String) {
+    let (public_token, organizer_token) =
+        create_event_tokens(app, json!({ "title": "Offsite" })).await;
+    for name in ["Ann", "Ben", "Cat"] {
+        submit(app, &public_token, json!({ "participant_name": name })).await;
+    }
+    (public_token, organizer_token)
+}
+
+fn names(participants: &Value) -> Vec<&str> {
+    participants
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|participant| participant["name"].as_str().unwrap())
+        .collect()
+}
+
+/// Participant ids by name, from the organizer view.
+async fn participant_ids(app: &Router, organizer_token: &str) -> Vec<(String, i64)> {
+    let (status, organizer) = send(
+        app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    organizer["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|participant| {
+            (
+                participant["name"].as_str().unwrap().to_string(),
+                participant["id"].as_i64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+fn id_of(ids: &[(String, i64)], name: &str) -> i64 {
+    ids.iter().find(|(n, _)| n == name).unwrap().1
+}
+
+#[sqlx::test]
+async fn test_pinned_participants_lead_and_hidden_ones_leave_public_results(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = create_answered_event(&app).await;
+    let ids = participant_ids(&app, &organizer_token).await;
+
+    let (status, arranged) = send(
+        &app,
+        "PUT",
+        &format!("/events/{}/participants/order", organizer_token),
+        Some(json!({
+            "order": [id_of(&ids, "Cat"), id_of(&ids, "Ben")],
+            "hidden": [id_of(&ids, "Ann")]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", arranged);
+    assert_eq!(
+        names(&arranged["participants"]),
+        vec!["Cat", "Ben", "Alice", "Ann"]
+    );
+    assert_eq!(arranged["participants"][0]["display_order"], 1);
+    assert_eq!(arranged["participants"][2]["display_order"], Value::Null);
+    assert_eq!(arranged["participants"][3]["is_hidden"], true);
+
+    let (status, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&results["participants"]), vec!["Cat", "Ben", "Alice"]);
+    assert_eq!(results["total_participants"], 3);
+
+    // The organizer still sees Ann, flagged
+    let (_, organizer) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(
+        names(&organizer["participants"]),
+        vec!["Cat", "Ben", "Alice", "Ann"]
+    );
+    assert_eq!(organizer["participants"][3]["is_hidden"], true);
+    assert_eq!(
+        organizer["participants"][3]["availabilities"][0]["start_at"],
+        "2030-01-01T09:00:00Z"
+    );
+
+    // An empty body puts everyone back
+    let (status, arranged) = send(
+        &app,
+        "PUT",
+        &format!("/events/{}/participants/order", organizer_token),
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        names(&arranged["participants"]),
+        vec!["Alice", "Ann", "Ben", "Cat"]
+    );
+    let (_, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(results["total_participants"], 4);
+}
+
+#[sqlx::test]
+async fn test_participant_order_rejects_unknown_and_repeated_participants(pool: PgPool) {
+    let app = create_router(pool);
+    let (_, organizer_token) = create_answered_event(&app).await;
+    let (_, other_organizer_token) = create_answered_event(&app).await;
+    let ids = participant_ids(&app, &organizer_token).await;
+    let ann = id_of(&ids, "Ann");
+    let stranger = id_of(&participant_ids(&app, &other_organizer_token).await, "Ann");
+    let uri = format!("/events/{}/participants/order", organizer_token);
+
+    let (status, _) = send(&app, "PUT", &uri, Some(json!({ "order": [stranger] }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, "PUT", &uri, Some(json!({ "hidden": [ann, ann] }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        "/events/not-a-token/participants/order",
+        Some(json!({ "order": [ann] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
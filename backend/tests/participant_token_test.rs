@@ -0,0 +1,138 @@
+mod common;
+
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send, try_submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+fn submission(name: &str, start: &str, end: &str, token: Option<&str>) -> Value {
+    json!({
+        "participant_token": token,
+        "participant_name": name,
+        "availabilities": [{ "start_at": start, "end_at": end }],
+        "comment": null
+    })
+}
+
+async fn results(app: &Router, public_token: &str) -> Value {
+    let (status, body) = send(
+        app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    body
+}
+
+#[sqlx::test]
+async fn test_same_name_does_not_overwrite(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+
+    let (_, first) = try_submit(
+        &app,
+        &public_token,
+        submission("Sam", "2030-01-01T09:00:00Z", "2030-01-01T10:00:00Z", None),
+    )
+    .await;
+    let (_, second) = try_submit(
+        &app,
+        &public_token,
+        submission("Sam", "2030-01-01T11:00:00Z", "2030-01-01T12:00:00Z", None),
+    )
+    .await;
+    assert_ne!(first["participant_token"], second["participant_token"]);
+
+    // Both answers are listed separately rather than merged under one name
+    let body = results(&app, &public_token).await;
+    assert_eq!(body["total_participants"], 3);
+    let sams: Vec<&Value> = body["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|p| p["name"] == "Sam")
+        .collect();
+    assert_eq!(sams.len(), 2);
+    assert_eq!(sams[0]["availabilities"].as_array().unwrap().len(), 1);
+    assert_eq!(sams[1]["availabilities"].as_array().unwrap().len(), 1);
+}
+
+#[sqlx::test]
+async fn test_resubmit_with_token_replaces_response(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+
+    let (_, created) = try_submit(
+        &app,
+        &public_token,
+        submission("Sam", "2030-01-01T09:00:00Z", "2030-01-01T10:00:00Z", None),
+    )
+    .await;
+    let token = created["participant_token"].as_str().unwrap();
+
+    let (status, updated) = try_submit(
+        &app,
+        &public_token,
+        submission(
+            "Samantha",
+            "2030-01-01T10:00:00Z",
+            "2030-01-01T12:00:00Z",
+            Some(token),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["participant_token"], token);
+
+    let body = results(&app, &public_token).await;
+    assert_eq!(body["total_participants"], 2);
+    let participant = &body["participants"][1];
+    assert_eq!(participant["name"], "Samantha");
+    assert_eq!(
+        participant["availabilities"][0]["start_at"],
+        "2030-01-01T10:00:00Z"
+    );
+}
+
+#[sqlx::test]
+async fn test_resubmit_with_foreign_token_rejected(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let (event_a, _) = create_event_tokens(&app, json!({})).await;
+    let (event_b, _) = create_event_tokens(&app, json!({})).await;
+
+    let (_, created) = try_submit(
+        &app,
+        &event_a,
+        submission("Sam", "2030-01-01T09:00:00Z", "2030-01-01T10:00:00Z", None),
+    )
+    .await;
+    let token = created["participant_token"].as_str().unwrap();
+
+    let (status, _) = try_submit(
+        &app,
+        &event_b,
+        submission(
+            "Sam",
+            "2030-01-01T09:00:00Z",
+            "2030-01-01T10:00:00Z",
+            Some(token),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = try_submit(
+        &app,
+        &event_a,
+        submission(
+            "Sam",
+            "2030-01-01T09:00:00Z",
+            "2030-01-01T10:00:00Z",
+            Some("00000000-0000-0000-0000-000000000000"),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -1,12 +1,39 @@
+use agreed_time_backend::cache::ResponseCache;
+use agreed_time_backend::config::Config;
+use agreed_time_backend::encryption::Keyring;
 use agreed_time_backend::handlers::events::submit_availability;
-use agreed_time_backend::models::{SubmitAvailabilityRequest, TimeRangeRequest};
+use agreed_time_backend::integrations::templates::SharedTemplates;
+use agreed_time_backend::models::{ParticipantStatus, SubmitAvailabilityRequest, TimeRangeRequest};
+use agreed_time_backend::realtime::SharedHub;
 use axum::Json;
 use axum::extract::{Path, State};
+use axum::http::HeaderMap;
 use chrono::{Duration, Utc};
 use sqlx::postgres::PgPoolOptions;
 use std::env;
+use std::sync::Arc;
 use uuid::Uuid;
 
+fn guest_payload(name: &str) -> SubmitAvailabilityRequest {
+    SubmitAvailabilityRequest {
+        participant_name: name.to_string(),
+        availabilities: vec![TimeRangeRequest {
+            start_at: Utc::now(),
+            end_at: Utc::now() + Duration::hours(1),
+            availability_level: None,
+        }],
+        comment: None,
+        buffer_minutes: None,
+        time_zone: None,
+        participant_token: None,
+        email: None,
+        role: None,
+        password: None,
+        dates: vec![],
+        captcha_token: None,
+    }
+}
+
 #[tokio::test]
 async fn test_participant_limit() {
     let _ = dotenvy::dotenv();
@@ -32,10 +59,10 @@ async fn test_participant_limit() {
     sqlx::query!(
         r#"
         INSERT INTO events (
-            id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at
+            id, public_token, organizer_token, title, description, state, time_zone, slot_duration, created_at, updated_at, expires_at
         )
         VALUES (
-            $1, $2, $3, 'Limit Test Event', NULL, 'open', 'UTC', 60, $4, $4
+            $1, $2, $3, 'Limit Test Event', NULL, 'open', 'UTC', 60, $4, $4, $4::timestamptz + INTERVAL '7 days'
         )
         "#,
         event_id,
@@ -75,43 +102,71 @@ async fn test_participant_limit() {
 
     // 4. Try to add 10th participant via handler (Should Succeed)
     // Current count in DB is 9. Limit is 10.
-    let payload_10 = SubmitAvailabilityRequest {
-        participant_name: "Guest 10".to_string(),
-        availabilities: vec![TimeRangeRequest {
-            start_at: Utc::now(),
-            end_at: Utc::now() + Duration::hours(1),
-        }],
-        comment: None,
-    };
-
     let result_10 = submit_availability(
         State(pool.clone()),
+        State(agreed_time_backend::clock::system()),
+        State(Arc::new(ResponseCache::new(std::time::Duration::ZERO))),
+        State(Arc::new(Config::default())),
+        State(Arc::new(Keyring::default())),
+        State(SharedTemplates::default()),
+        State(SharedHub::default()),
         Path(public_token.clone()),
-        Json(payload_10),
+        HeaderMap::new(),
+        Json(guest_payload("Guest 10")),
     )
     .await;
 
     assert!(result_10.is_ok(), "10th participant should be allowed");
 
-    // 5. Try to add 11th participant via handler (Should Fail)
-    // Current count in DB is 10. Limit is 10.
-    let payload_11 = SubmitAvailabilityRequest {
-        participant_name: "Guest 11".to_string(),
-        availabilities: vec![TimeRangeRequest {
-            start_at: Utc::now(),
-            end_at: Utc::now() + Duration::hours(1),
-        }],
-        comment: None,
-    };
-
+    // 5. The 11th participant goes to the waitlist instead
     let result_11 = submit_availability(
         State(pool.clone()),
+        State(agreed_time_backend::clock::system()),
+        State(Arc::new(ResponseCache::new(std::time::Duration::ZERO))),
+        State(Arc::new(Config::default())),
+        State(Arc::new(Keyring::default())),
+        State(SharedTemplates::default()),
+        State(SharedHub::default()),
+        Path(public_token.clone()),
+        HeaderMap::new(),
+        Json(guest_payload("Guest 11")),
+    )
+    .await;
+
+    assert_eq!(
+        result_11
+            .expect("11th participant should be waitlisted")
+            .status,
+        ParticipantStatus::Pending
+    );
+
+    // 6. Once the waitlist is full as well, submissions are rejected
+    for i in 12..=20 {
+        sqlx::query!(
+            "INSERT INTO participants (event_id, name, is_organizer, status) VALUES ($1, $2, false, 'pending')",
+            event_id,
+            format!("Guest {}", i),
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to insert pending participant");
+    }
+
+    let result_21 = submit_availability(
+        State(pool.clone()),
+        State(agreed_time_backend::clock::system()),
+        State(Arc::new(ResponseCache::new(std::time::Duration::ZERO))),
+        State(Arc::new(Config::default())),
+        State(Arc::new(Keyring::default())),
+        State(SharedTemplates::default()),
+        State(SharedHub::default()),
         Path(public_token.clone()),
-        Json(payload_11),
+        HeaderMap::new(),
+        Json(guest_payload("Guest 21")),
     )
     .await;
 
-    match result_11 {
+    match result_21 {
         Ok(_) => panic!("Should have failed due to participant limit"),
         Err(e) => {
             assert_eq!(
@@ -123,7 +178,7 @@ async fn test_participant_limit() {
         }
     }
 
-    // 6. Cleanup
+    // 7. Cleanup
     sqlx::query!("DELETE FROM events WHERE id = $1", event_id)
         .execute(&pool)
         .await
@@ -0,0 +1,188 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, submission_body};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    password: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let headers: Vec<(&str, &str)> = password
+        .map(|password| ("X-Event-Password", password))
+        .into_iter()
+        .collect();
+    common::send_with_headers(app, method, uri, &headers, body).await
+}
+
+#[sqlx::test]
+async fn test_password_gates_participant_endpoints(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = create_event_tokens(
+        &app,
+        json!({ "title": "Book Club", "password": "open sesame" }),
+    )
+    .await;
+    let event_uri = format!("/events/{}", public_token);
+    let availability_uri = format!("/events/{}/availability", public_token);
+    let results_uri = format!("/events/{}/results", public_token);
+
+    for password in [None, Some("wrong")] {
+        let (status, body) = send(&app, "GET", &event_uri, password, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], "PASSWORD_REQUIRED");
+
+        let (status, body) = send(&app, "GET", &results_uri, password, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], "PASSWORD_REQUIRED");
+
+        let (status, body) = send(
+            &app,
+            "POST",
+            &availability_uri,
+            password,
+            Some(submission_body(json!({}))),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], "PASSWORD_REQUIRED");
+    }
+
+    let (status, event) = send(&app, "GET", &event_uri, Some("open sesame"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["title"], "Book Club");
+
+    // Header or body field
+    let (status, _) = send(
+        &app,
+        "POST",
+        &availability_uri,
+        Some("open sesame"),
+        Some(submission_body(json!({}))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        "POST",
+        &availability_uri,
+        None,
+        Some(submission_body(json!({ "password": "open sesame" }))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, results) = send(&app, "GET", &results_uri, Some("open sesame"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results["total_participants"], 3);
+
+    // The organizer link needs no password
+    let (status, organizer) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(organizer["password_protected"], true);
+}
+
+#[sqlx::test]
+async fn test_organizer_changes_and_removes_password(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = create_event_tokens(&app, json!({})).await;
+    let event_uri = format!("/events/{}", public_token);
+    let organizer_uri = format!("/events/{}", organizer_token);
+
+    let (status, _) = send(&app, "GET", &event_uri, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &organizer_uri,
+        None,
+        Some(json!({ "password": "hunter2" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, "GET", &event_uri, None, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, "GET", &event_uri, Some("hunter2"), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Other updates leave the password alone
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &organizer_uri,
+        None,
+        Some(json!({ "title": "Renamed" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, "GET", &event_uri, None, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &organizer_uri,
+        None,
+        Some(json!({ "password": "" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, "GET", &event_uri, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &organizer_uri,
+        None,
+        Some(json!({ "password": "x".repeat(129) })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+/// `uri` answers 403 `PASSWORD_REQUIRED` without the password or with a
+/// wrong one, and 200 with it.
+async fn assert_password_gated(app: &Router, uri: &str) {
+    for password in [None, Some("wrong")] {
+        let (status, body) = send(app, "GET", uri, password, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{}", uri);
+        assert_eq!(body["code"], "PASSWORD_REQUIRED");
+    }
+    let (status, _) = send(app, "GET", uri, Some("open sesame"), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", uri);
+}
+
+#[sqlx::test]
+async fn test_password_gates_suggestions(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_event_tokens(&app, json!({ "password": "open sesame" })).await;
+    assert_password_gated(&app, &format!("/events/{}/suggestions", public_token)).await;
+}
+
+#[sqlx::test]
+async fn test_password_gates_summary(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_event_tokens(&app, json!({ "password": "open sesame" })).await;
+    assert_password_gated(&app, &format!("/events/{}/summary", public_token)).await;
+}
+
+#[sqlx::test]
+async fn test_password_gates_calendar_export(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_event_tokens(&app, json!({ "password": "open sesame" })).await;
+    assert_password_gated(&app, &format!("/events/{}/export.ics", public_token)).await;
+}
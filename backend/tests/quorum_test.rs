@@ -0,0 +1,134 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event, send, submit, try_create_event};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// Answers for `name`, available from `start` to `end`, leaving an email
+/// address.
+async fn answer(app: &Router, public_token: &str, name: &str, start: &str, end: &str) {
+    submit(
+        app,
+        public_token,
+        json!({
+            "participant_name": name,
+            "availabilities": [{ "start_at": start, "end_at": end }],
+            "email": format!("{}@example.com", name.to_lowercase())
+        }),
+    )
+    .await;
+}
+
+#[sqlx::test]
+async fn test_submission_reaching_quorum_closes_event(pool: PgPool) {
+    let app = create_router(pool.clone());
+    let created = create_event(
+        &app,
+        json!({
+            "close_at_quorum": 3,
+            "webhook_url": "https://hooks.example.com/agreed-time"
+        }),
+    )
+    .await;
+    let public_token = created["public_token"].as_str().unwrap();
+
+    answer(
+        &app,
+        public_token,
+        "Bob",
+        "2030-01-01T10:00:00Z",
+        "2030-01-01T12:00:00Z",
+    )
+    .await;
+    let (_, event) = send(&app, "GET", &format!("/events/{}", public_token), None).await;
+    assert_eq!(event["state"], "open");
+    assert_eq!(event["close_at_quorum"], 3);
+    sqlx::query!("UPDATE participants SET email_verified_at = NOW() WHERE name = 'Bob'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    answer(
+        &app,
+        public_token,
+        "Carol",
+        "2030-01-01T11:00:00Z",
+        "2030-01-01T12:00:00Z",
+    )
+    .await;
+    let (_, event) = send(&app, "GET", &format!("/events/{}", public_token), None).await;
+    assert_eq!(event["state"], "closed");
+
+    let mails =
+        sqlx::query!("SELECT recipient, subject, body FROM email_outbox ORDER BY recipient")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    // Carol's address, left with the closing submission, isn't confirmed yet
+    assert_eq!(mails.len(), 1);
+    assert_eq!(mails[0].recipient, "bob@example.com");
+    assert_eq!(
+        mails[0].subject,
+        "Standup is closed: 2030-01-01 11:00–12:00 UTC"
+    );
+    assert!(mails[0].body.contains("Available: Alice, Bob, Carol"));
+
+    let payload: Value = serde_json::from_str(
+        &sqlx::query_scalar!(
+            "SELECT payload FROM webhook_deliveries WHERE event_type = 'event.closed'"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(payload["data"]["reason"], "quorum");
+    assert_eq!(payload["data"]["slot"]["start_at"], "2030-01-01T11:00:00Z");
+    assert_eq!(
+        payload["data"]["participants"],
+        json!(["Alice", "Bob", "Carol"])
+    );
+}
+
+#[sqlx::test]
+async fn test_lowering_quorum_closes_event(pool: PgPool) {
+    let app = create_router(pool);
+    let (status, _) = try_create_event(&app, json!({ "close_at_quorum": 1 })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let created = create_event(&app, json!({})).await;
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_uri = format!("/events/{}", created["organizer_token"].as_str().unwrap());
+
+    answer(
+        &app,
+        public_token,
+        "Bob",
+        "2030-01-01T09:00:00Z",
+        "2030-01-01T10:00:00Z",
+    )
+    .await;
+
+    let (status, event) = send(
+        &app,
+        "PATCH",
+        &organizer_uri,
+        Some(json!({ "close_at_quorum": 3 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["state"], "open");
+
+    let (status, event) = send(
+        &app,
+        "PATCH",
+        &organizer_uri,
+        Some(json!({ "close_at_quorum": 2 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["state"], "closed");
+    assert_eq!(event["close_at_quorum"], 2);
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use agreed_time_backend::routes::{create_router, create_router_with_state};
+use agreed_time_backend::state::AppState;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, submit};
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Serves `app` on a local port; requests through `app` itself share the hub.
+async fn serve(app: &Router) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(axum::serve(listener, app.clone()).into_future());
+    format!("ws://{}", addr)
+}
+
+async fn next_json(socket: &mut Socket) -> Value {
+    loop {
+        match socket.next().await.unwrap().unwrap() {
+            Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            _ => continue,
+        }
+    }
+}
+
+async fn announce(socket: &mut Socket, name: &str, editing: bool) {
+    let frame = json!({ "type": "presence", "name": name, "editing": editing });
+    socket
+        .send(Message::Text(frame.to_string().into()))
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn test_socket_pushes_presence_and_availability(pool: PgPool) {
+    let app = create_router(pool);
+    let base = serve(&app).await;
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+    let url = format!("{}/events/{}/ws", base, public_token);
+
+    let (mut bob, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (mut watcher, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    announce(&mut bob, "Bob", true).await;
+    assert_eq!(
+        next_json(&mut watcher).await,
+        json!({ "type": "presence", "name": "Bob", "editing": true })
+    );
+
+    submit(&app, &public_token, json!({})).await;
+    let delta = next_json(&mut watcher).await;
+    assert_eq!(delta["type"], "availability");
+    assert_eq!(delta["participant_name"], "Bob");
+    assert_eq!(
+        delta["availabilities"],
+        json!([{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }])
+    );
+
+    // Leaving mid-edit tells the others Bob stopped
+    bob.close(None).await.unwrap();
+    assert_eq!(
+        next_json(&mut watcher).await,
+        json!({ "type": "presence", "name": "Bob", "editing": false })
+    );
+}
+
+#[sqlx::test]
+async fn test_blind_poll_socket_only_gets_presence(pool: PgPool) {
+    let app = create_router(pool);
+    let base = serve(&app).await;
+    let (public_token, _) = create_event_tokens(&app, json!({ "blind_until_closed": true })).await;
+    let url = format!("{}/events/{}/ws", base, public_token);
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    // Wait until the socket is subscribed before submitting
+    announce(&mut socket, "Ann", true).await;
+    assert_eq!(next_json(&mut socket).await["type"], "presence");
+
+    submit(&app, &public_token, json!({ "participant_name": "Ann" })).await;
+    announce(&mut socket, "Ann", false).await;
+    assert_eq!(
+        next_json(&mut socket).await,
+        json!({ "type": "presence", "name": "Ann", "editing": false })
+    );
+}
+
+#[sqlx::test]
+async fn test_socket_requires_results_access(pool: PgPool) {
+    let app = create_router(pool);
+    let base = serve(&app).await;
+    let (public_token, _) =
+        create_event_tokens(&app, json!({ "results_visibility": "private" })).await;
+
+    let error = tokio_tungstenite::connect_async(format!("{}/events/{}/ws", base, public_token))
+        .await
+        .unwrap_err();
+    match error {
+        tokio_tungstenite::tungstenite::Error::Http(response) => {
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED)
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+
+    let error = tokio_tungstenite::connect_async(format!("{}/events/nope/ws", base))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        tokio_tungstenite::tungstenite::Error::Http(response) if response.status() == StatusCode::NOT_FOUND
+    ));
+}
+
+#[sqlx::test]
+async fn test_socket_requires_event_password(pool: PgPool) {
+    let app = create_router(pool);
+    let base = serve(&app).await;
+    let (public_token, _) = create_event_tokens(&app, json!({ "password": "open sesame" })).await;
+    let url = format!("{}/events/{}/ws", base, public_token);
+
+    for url in [url.clone(), format!("{}?password=wrong", url)] {
+        let error = tokio_tungstenite::connect_async(url).await.unwrap_err();
+        assert!(matches!(
+            error,
+            tokio_tungstenite::tungstenite::Error::Http(response) if response.status() == StatusCode::FORBIDDEN
+        ));
+    }
+
+    // Browsers can't set headers on the handshake, so the query works too
+    let (mut socket, _) =
+        tokio_tungstenite::connect_async(format!("{}?password=open%20sesame", url))
+            .await
+            .unwrap();
+    announce(&mut socket, "Ann", true).await;
+    assert_eq!(next_json(&mut socket).await["type"], "presence");
+}
+
+#[sqlx::test]
+async fn test_close_all_ends_sockets_with_going_away(pool: PgPool) {
+    let state = AppState::new(pool);
+    let hub = state.hub.clone();
+    let app = create_router_with_state(state);
+    let base = serve(&app).await;
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+
+    let (mut socket, _) =
+        tokio_tungstenite::connect_async(format!("{}/events/{}/ws", base, public_token))
+            .await
+            .unwrap();
+    // Wait until the socket is subscribed before closing
+    announce(&mut socket, "Ann", true).await;
+    assert_eq!(next_json(&mut socket).await["type"], "presence");
+
+    hub.close_all();
+    match socket.next().await.unwrap().unwrap() {
+        Message::Close(Some(frame)) => assert_eq!(u16::from(frame.code), 1001),
+        other => panic!("expected a close frame, got {:?}", other),
+    }
+}
@@ -0,0 +1,83 @@
+use agreed_time_backend::{
+    clock::TestClock,
+    config::{Config, RateLimit},
+    middleware::RedisRateLimitLayer,
+};
+use axum::{
+    Router,
+    body::Body,
+    extract::connect_info::ConnectInfo,
+    http::{Request, StatusCode},
+    routing::post,
+};
+use chrono::{DateTime, Utc};
+use std::{env, net::SocketAddr, sync::Arc, time::Duration};
+use tower::ServiceExt; // for `oneshot`
+use uuid::Uuid;
+
+async fn post_events(app: &Router, client: SocketAddr) -> StatusCode {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri("/events")
+        .body(Body::empty())
+        .unwrap();
+    request.extensions_mut().insert(ConnectInfo(client));
+    app.clone().oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn test_replicas_share_one_bucket() {
+    let _ = dotenvy::dotenv();
+    let redis_url = match env::var("REDIS_URL") {
+        Ok(url) => url,
+        Err(_) => {
+            eprintln!("Skipping Redis rate limit test: REDIS_URL not set");
+            return;
+        }
+    };
+
+    let config = Config {
+        redis_url: Some(redis_url),
+        rate_limit_create_event: Some(RateLimit {
+            requests: 2,
+            window: Duration::from_secs(60),
+        }),
+        ..Config::default()
+    };
+    let clock = Arc::new(TestClock::new(
+        "2030-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap(),
+    ));
+    let replica = || async {
+        let layer = RedisRateLimitLayer::from_config(&config, clock.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        Router::new()
+            .route("/events", post(|| async { StatusCode::OK }))
+            .layer(layer)
+    };
+    let (first, second) = (replica().await, replica().await);
+
+    // A fresh address per run so leftover buckets don't interfere
+    let octets = Uuid::new_v4().into_bytes();
+    let client = SocketAddr::from(([10, octets[0], octets[1], octets[2]], 40000));
+
+    assert_eq!(post_events(&first, client).await, StatusCode::OK);
+    assert_eq!(post_events(&second, client).await, StatusCode::OK);
+    assert_eq!(
+        post_events(&first, client).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(
+        post_events(&second, client).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    // Half a window refills one of the two tokens
+    clock.advance(Duration::from_secs(30));
+    assert_eq!(post_events(&second, client).await, StatusCode::OK);
+    assert_eq!(
+        post_events(&first, client).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock, config::Config, routes::create_router_with_state, state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use chrono::{DateTime, Utc};
+use common::{create_event_tokens, send, submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>, mail_from: Option<&str>) -> Router {
+    let config = Config {
+        mail_from: mail_from.map(str::to_string),
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_clock(clock).with_config(config))
+}
+
+fn now() -> DateTime<Utc> {
+    "2029-12-01T00:00:00Z".parse().unwrap()
+}
+
+/// Event with Bob (email, no availability), Carol (email, answered) and
+/// Dave (no email, no availability), plus the registered `invitees`.
+async fn create_answered_event(app: &Router, invitees: Value) -> (String, String) {
+    let (public_token, organizer_token) =
+        create_event_tokens(app, json!({ "title": "Offsite", "invitees": invitees })).await;
+    for (name, email, availabilities) in [
+        ("Bob", Some("bob@example.com"), json!([])),
+        (
+            "Carol",
+            Some("carol@example.com"),
+            json!([{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }]),
+        ),
+        ("Dave", None, json!([])),
+    ] {
+        submit(
+            app,
+            &public_token,
+            json!({
+                "participant_name": name,
+                "email": email,
+                "availabilities": availabilities
+            }),
+        )
+        .await;
+    }
+    (public_token, organizer_token)
+}
+
+/// Marks every address as confirmed, as following the mailed links would.
+async fn confirm_emails(pool: &PgPool) {
+    sqlx::query!("UPDATE participants SET email_verified_at = NOW() WHERE email IS NOT NULL")
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn test_remind_queues_mail_for_non_responders(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock.clone(), Some("agreed-time@example.com"));
+    let (_, organizer_token) = create_answered_event(&app, json!([])).await;
+    confirm_emails(&pool).await;
+    let uri = format!("/events/{}/remind", organizer_token);
+
+    let (status, body) = send(&app, "POST", &uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["queued"], 1);
+    assert_eq!(body["recipients"], json!(["Bob"]));
+
+    let queued = sqlx::query!("SELECT recipient, subject FROM email_outbox")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].recipient, "bob@example.com");
+    assert!(queued[0].subject.contains("Offsite"));
+
+    // Once per day
+    let (status, body) = send(&app, "POST", &uri, json!({})).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(body["code"], "TOO_MANY_REQUESTS");
+
+    clock.advance(Duration::from_secs(25 * 3600));
+    let (status, body) = send(&app, "POST", &uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["queued"], 1);
+}
+
+#[sqlx::test]
+async fn test_remind_after_finalize_targets_missing_rsvps(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock, Some("agreed-time@example.com"));
+    let (_, organizer_token) = create_answered_event(&app, json!([])).await;
+    confirm_emails(&pool).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        json!({
+            "slots": [{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    sqlx::query!("UPDATE participants SET rsvp_status = 'accepted' WHERE name = 'Carol'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/remind", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["recipients"], json!(["Bob"]));
+}
+
+#[sqlx::test]
+async fn test_remind_without_mail_or_recipients(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock.clone(), None);
+    let (_, organizer_token) = create_answered_event(&app, json!([])).await;
+    let uri = format!("/events/{}/remind", organizer_token);
+
+    let (status, _) = send(&app, "POST", &uri, json!({})).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Everyone with an address has answered: nothing queued, limit untouched
+    let app = create_test_app(pool.clone(), clock, Some("agreed-time@example.com"));
+    sqlx::query!("DELETE FROM participants WHERE name = 'Bob'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        let (status, body) = send(&app, "POST", &uri, json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["queued"], 0);
+    }
+}
+
+#[sqlx::test]
+async fn test_remind_mails_unanswered_invitees(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock, Some("agreed-time@example.com"));
+    // Bob is reminded as a participant and Carol answered; only Erin is left
+    let (_, organizer_token) = create_answered_event(
+        &app,
+        json!(["BOB@example.com", "carol@example.com", "erin@example.com"]),
+    )
+    .await;
+    confirm_emails(&pool).await;
+
+    let (status, event) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["invitees"].as_array().unwrap().len(), 3);
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/remind", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["queued"], 2);
+    assert_eq!(body["recipients"], json!(["Bob"]));
+    assert_eq!(body["invitees"], 1);
+
+    let recipients: Vec<String> =
+        sqlx::query_scalar!("SELECT recipient FROM email_outbox ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(recipients, ["bob@example.com", "erin@example.com"]);
+
+    let (status, log) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/reminders", organizer_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(log["reminders"].as_array().unwrap().len(), 1);
+    assert_eq!(log["reminders"][0]["recipient_count"], 2);
+    assert_eq!(log["reminders"][0]["invitee_count"], 1);
+    assert_eq!(log["next_reminder_at"], "2029-12-02T00:00:00Z");
+}
+
+#[sqlx::test]
+async fn test_invitees_are_validated(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool, clock, Some("agreed-time@example.com"));
+
+    for invitees in [
+        json!(["not-an-address"]),
+        json!(["erin@example.com", "Erin@example.com"]),
+    ] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/events",
+            json!({
+                "title": "Offsite",
+                "organizer_name": "Alice",
+                "time_slots": [
+                    { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+                ],
+                "invitees": invitees
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["details"][0]["field"], "invitees");
+    }
+}
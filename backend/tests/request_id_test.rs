@@ -0,0 +1,60 @@
+use agreed_time_backend::{middleware::RequestLogLayer, routes::create_router};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt; // for `oneshot`
+use uuid::Uuid;
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, Option<String>, Value) {
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let request_id = response
+        .headers()
+        .get("x-request-id")
+        .map(|value| value.to_str().unwrap().to_string());
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        request_id,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[sqlx::test]
+async fn test_responses_carry_request_id(pool: PgPool) {
+    let app = create_router(pool).layer(RequestLogLayer);
+
+    let (status, first, body) = get(&app, "/health").await;
+    assert_eq!(status, StatusCode::OK);
+    let first = first.expect("X-Request-Id header");
+    assert_eq!(Uuid::parse_str(&first).unwrap().get_version_num(), 7);
+    assert!(body.get("request_id").is_none());
+
+    // Errors repeat the ID in the body so users can report it
+    let (status, second, body) = get(&app, "/events/does-not-exist").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let second = second.expect("X-Request-Id header");
+    assert_ne!(first, second);
+    assert_eq!(body["code"], "NOT_FOUND");
+    assert_eq!(body["request_id"], second);
+}
+
+#[sqlx::test]
+async fn test_errors_outside_the_layer_have_no_request_id(pool: PgPool) {
+    let app = create_router(pool);
+
+    let (status, request_id, body) = get(&app, "/events/does-not-exist").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(request_id.is_none());
+    assert!(body.get("request_id").is_none());
+}
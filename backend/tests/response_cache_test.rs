@@ -0,0 +1,203 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock, config::Config, routes::create_router_with_state, state::AppState,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{TimeZone, Utc};
+use common::{create_event_tokens, send};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+use tower::ServiceExt; // for `oneshot`
+
+/// At the start of a countdown window, so the participant view's ETag
+/// holds for the whole test.
+fn clock() -> Arc<TestClock> {
+    Arc::new(TestClock::new(
+        Utc.with_ymd_and_hms(2029, 12, 1, 12, 0, 0).unwrap(),
+    ))
+}
+
+fn create_test_app(pool: PgPool, response_cache_ttl_secs: u64) -> Router {
+    let config = Config {
+        response_cache_ttl_secs,
+        ..Config::default()
+    };
+    create_router_with_state(AppState::new(pool).with_clock(clock()).with_config(config))
+}
+
+/// Renames the event behind the handlers' backs, so only a fresh read sees it.
+async fn rename(pool: &PgPool, public_token: &str, title: &str) {
+    sqlx::query("UPDATE events SET title = $1 WHERE public_token = $2")
+        .bind(title)
+        .bind(public_token)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn test_reads_are_cached_until_a_write(pool: PgPool) {
+    let app = create_test_app(pool.clone(), 60);
+    let (public_token, organizer_token) = create_event_tokens(&app, json!({})).await;
+    let event_uri = format!("/events/{}", public_token);
+    let results_uri = format!("/events/{}/results", public_token);
+
+    let (_, event) = send(&app, "GET", &event_uri, Value::Null).await;
+    assert_eq!(event["title"], "Standup");
+    let (_, results) = send(&app, "GET", &results_uri, Value::Null).await;
+    assert_eq!(results["title"], "Standup");
+
+    rename(&pool, &public_token, "Retro").await;
+    let (status, event) = send(&app, "GET", &event_uri, Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["title"], "Standup");
+    let (_, results) = send(&app, "GET", &results_uri, Value::Null).await;
+    assert_eq!(results["title"], "Standup");
+    // Another display zone is another entry
+    let (_, event) = send(
+        &app,
+        "GET",
+        &format!("{}?tz=Asia/Tokyo", event_uri),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(event["title"], "Retro");
+
+    // Submitting drops the event's entries
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({
+            "participant_name": "Bob",
+            "availabilities": [
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ],
+            "comment": null
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, event) = send(&app, "GET", &event_uri, Value::Null).await;
+    assert_eq!(event["title"], "Retro");
+    let (_, results) = send(&app, "GET", &results_uri, Value::Null).await;
+    assert_eq!(results["total_participants"], 2);
+
+    // So does closing
+    rename(&pool, &public_token, "Planning").await;
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, results) = send(&app, "GET", &results_uri, Value::Null).await;
+    assert_eq!(results["title"], "Planning");
+    assert_eq!(results["state"], "closed");
+}
+
+#[sqlx::test]
+async fn test_entries_expire_by_the_app_clock(pool: PgPool) {
+    let clock = clock();
+    let config = Config {
+        response_cache_ttl_secs: 30,
+        ..Config::default()
+    };
+    let app = create_router_with_state(
+        AppState::new(pool.clone())
+            .with_clock(clock.clone())
+            .with_config(config),
+    );
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+    let event_uri = format!("/events/{}", public_token);
+    send(&app, "GET", &event_uri, Value::Null).await;
+
+    rename(&pool, &public_token, "Retro").await;
+    clock.advance(Duration::from_secs(29));
+    let (_, event) = send(&app, "GET", &event_uri, Value::Null).await;
+    assert_eq!(event["title"], "Standup");
+
+    clock.advance(Duration::from_secs(1));
+    let (_, event) = send(&app, "GET", &event_uri, Value::Null).await;
+    assert_eq!(event["title"], "Retro");
+}
+
+#[sqlx::test]
+async fn test_cache_off_reads_every_time(pool: PgPool) {
+    let app = create_test_app(pool.clone(), 0);
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+    let event_uri = format!("/events/{}", public_token);
+
+    send(&app, "GET", &event_uri, Value::Null).await;
+    rename(&pool, &public_token, "Retro").await;
+    let (_, event) = send(&app, "GET", &event_uri, Value::Null).await;
+    assert_eq!(event["title"], "Retro");
+}
+
+#[sqlx::test]
+async fn test_cached_reads_still_check_the_password(pool: PgPool) {
+    let app = create_test_app(pool.clone(), 60);
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Standup",
+            "description": null,
+            "organizer_name": "Alice",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ],
+            "password": "hunter2"
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let event_uri = format!("/events/{}", created["public_token"].as_str().unwrap());
+
+    let request = Request::builder()
+        .uri(&event_uri)
+        .header("X-Event-Password", "hunter2")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (status, _) = send(&app, "GET", &event_uri, Value::Null).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test]
+async fn test_archived_events_are_not_cached(pool: PgPool) {
+    let app = create_test_app(pool.clone(), 60);
+    let (public_token, _) = create_event_tokens(&app, json!({})).await;
+    sqlx::query("UPDATE events SET state = 'archived' WHERE public_token = $1")
+        .bind(&public_token)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let event_uri = format!("/events/{}", public_token);
+    let results_uri = format!("/events/{}/results", public_token);
+
+    let (status, event) = send(&app, "GET", &event_uri, Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["state"], "archived");
+    let (status, _) = send(&app, "GET", &results_uri, Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+
+    rename(&pool, &public_token, "Retro").await;
+    let (_, event) = send(&app, "GET", &event_uri, Value::Null).await;
+    assert_eq!(event["title"], "Retro");
+    let (_, results) = send(&app, "GET", &results_uri, Value::Null).await;
+    assert_eq!(results["title"], "Retro");
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::http::StatusCode;
+use common::{create_event, send, submit};
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn test_private_results_require_results_token(pool: PgPool) {
+    let app = create_router(pool);
+    let created = create_event(&app, json!({ "results_visibility": "private" })).await;
+    let public_token = created["public_token"].as_str().unwrap();
+    let results_token = created["results_token"].as_str().unwrap();
+
+    // Submission stays open to the public link
+    submit(&app, public_token, json!({ "participant_name": "Guest" })).await;
+
+    let (_, event) = send(&app, "GET", &format!("/events/{}", public_token), json!({})).await;
+    assert_eq!(event["results_visibility"], "private");
+
+    for path in ["results", "suggestions", "summary"] {
+        let uri = format!("/events/{}/{}", public_token, path);
+        let (status, body) = send(&app, "GET", &uri, json!({})).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", path);
+        assert_eq!(body["code"], "UNAUTHORIZED");
+
+        let (status, _) = send(
+            &app,
+            "GET",
+            &format!(
+                "{}?results_token={}",
+                uri,
+                created["organizer_token"].as_str().unwrap()
+            ),
+            json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", path);
+
+        let (status, _) = send(
+            &app,
+            "GET",
+            &format!("{}?results_token={}", uri, results_token),
+            json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", path);
+    }
+
+    let (_, organizer) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/organizer/{}",
+            created["organizer_token"].as_str().unwrap()
+        ),
+        json!({}),
+    )
+    .await;
+    assert_eq!(organizer["results_visibility"], "private");
+    assert_eq!(organizer["results_token"], results_token);
+    assert_eq!(organizer["total_participants"], 2);
+}
+
+#[sqlx::test]
+async fn test_results_public_by_default_and_switchable(pool: PgPool) {
+    let app = create_router(pool);
+    let created = create_event(&app, json!({})).await;
+    let public_token = created["public_token"].as_str().unwrap();
+    let results_uri = format!("/events/{}/results", public_token);
+
+    let (status, _) = send(&app, "GET", &results_uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", created["organizer_token"].as_str().unwrap()),
+        json!({ "results_visibility": "private" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["results_visibility"], "private");
+
+    let (status, _) = send(&app, "GET", &results_uri, json!({})).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn test_after_submit_results_need_a_participant_token(pool: PgPool) {
+    let app = create_router(pool);
+    let created = create_event(&app, json!({ "results_visibility": "after_submit" })).await;
+    let public_token = created["public_token"].as_str().unwrap();
+
+    for path in ["results", "suggestions", "summary"] {
+        let uri = format!("/events/{}/{}", public_token, path);
+        let (status, body) = send(&app, "GET", &uri, json!({})).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{}", path);
+        assert_eq!(body["code"], "RESULTS_LOCKED");
+    }
+
+    let submitted = submit(&app, public_token, json!({ "participant_name": "Guest" })).await;
+    let participant_token = submitted["participant_token"].as_str().unwrap();
+
+    for path in ["results", "suggestions", "summary"] {
+        let uri = format!(
+            "/events/{}/{}?participant_token={}",
+            public_token, path, participant_token
+        );
+        let (status, _) = send(&app, "GET", &uri, json!({})).await;
+        assert_eq!(status, StatusCode::OK, "{}", path);
+    }
+
+    // Another event's participant hasn't answered this one
+    let other = create_event(&app, json!({})).await;
+    let outsider = submit(
+        &app,
+        other["public_token"].as_str().unwrap(),
+        json!({ "participant_name": "Eve" }),
+    )
+    .await;
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/{}/results?participant_token={}",
+            public_token,
+            outsider["participant_token"].as_str().unwrap()
+        ),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // The organizer's results link always works
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/{}/results?results_token={}",
+            public_token,
+            created["results_token"].as_str().unwrap()
+        ),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_after_close_results_locked_while_open(pool: PgPool) {
+    let app = create_router(pool);
+    let created = create_event(&app, json!({ "results_visibility": "after_close" })).await;
+    let public_token = created["public_token"].as_str().unwrap();
+    let results_uri = format!("/events/{}/results", public_token);
+
+    let (status, body) = send(&app, "GET", &results_uri, json!({})).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "RESULTS_LOCKED");
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!(
+            "/events/{}/close",
+            created["organizer_token"].as_str().unwrap()
+        ),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(&app, "GET", &results_uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total_participants"], 1);
+}
+
+#[sqlx::test]
+async fn test_visibility_aliases(pool: PgPool) {
+    let app = create_router(pool);
+    for (requested, stored) in [("always", "public"), ("organizer_only", "private")] {
+        let created = create_event(&app, json!({ "results_visibility": requested })).await;
+        let (_, event) = send(
+            &app,
+            "GET",
+            &format!("/events/{}", created["public_token"].as_str().unwrap()),
+            json!({}),
+        )
+        .await;
+        assert_eq!(event["results_visibility"], stored);
+    }
+}
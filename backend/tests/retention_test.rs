@@ -0,0 +1,175 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock, config::Config, db::cleanup::delete_expired_events,
+    routes::create_router_with_state, state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use chrono::{DateTime, Utc};
+use common::{create_event, send, try_create_event};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn create_test_app(pool: PgPool, clock: Arc<TestClock>, config: Config) -> Router {
+    create_router_with_state(AppState::new(pool).with_clock(clock).with_config(config))
+}
+
+fn now() -> DateTime<Utc> {
+    "2029-12-01T00:00:00Z".parse().unwrap()
+}
+
+async fn trashed(pool: &PgPool, organizer_token: &str) -> bool {
+    sqlx::query_scalar!(
+        "SELECT deleted_at IS NOT NULL AS \"trashed!\" FROM events WHERE organizer_token = $1",
+        organizer_token
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn test_requested_retention_sets_expiry(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(pool.clone(), clock.clone(), Config::default());
+    let created = create_event(&app, json!({ "retention_days": 30 })).await;
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    let (_, event) = send(
+        &app,
+        "GET",
+        &format!("/events/{}", created["public_token"].as_str().unwrap()),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(event["expires_at"], "2029-12-31T00:00:00Z");
+    let (_, organizer_view) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(organizer_view["expires_at"], "2029-12-31T00:00:00Z");
+    assert_eq!(organizer_view["retention_days"], 30);
+
+    // Past the default seven days the event is kept
+    clock.advance(Duration::from_secs(8 * 24 * 3600));
+    delete_expired_events(&pool, clock.as_ref()).await.unwrap();
+    assert!(!trashed(&pool, organizer_token).await);
+
+    // Extending keeps it for its own retention again
+    let (status, extended) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/extend", organizer_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", extended);
+    assert_eq!(extended["expires_at"], "2030-01-08T00:00:00Z");
+
+    clock.advance(Duration::from_secs(38 * 24 * 3600));
+    delete_expired_events(&pool, clock.as_ref()).await.unwrap();
+    assert!(trashed(&pool, organizer_token).await);
+}
+
+#[sqlx::test]
+async fn test_retention_defaults_to_seven_days(pool: PgPool) {
+    let app = create_test_app(pool, Arc::new(TestClock::new(now())), Config::default());
+    let created = create_event(&app, json!({})).await;
+
+    let (_, organizer_view) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/organizer/{}",
+            created["organizer_token"].as_str().unwrap()
+        ),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(organizer_view["expires_at"], "2029-12-08T00:00:00Z");
+    assert_eq!(organizer_view["retention_days"], 7);
+}
+
+#[sqlx::test]
+async fn test_retention_is_bounded_by_config(pool: PgPool) {
+    let app = create_test_app(
+        pool,
+        Arc::new(TestClock::new(now())),
+        Config {
+            max_retention_days: 14,
+            ..Default::default()
+        },
+    );
+
+    for days in [0, 15, 91] {
+        let (status, body) = try_create_event(&app, json!({ "retention_days": days })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["details"][0]["field"], "retention_days");
+    }
+    create_event(&app, json!({ "retention_days": 14 })).await;
+
+    let (_, capabilities) = send(&app, "GET", "/capabilities", Value::Null).await;
+    assert_eq!(capabilities["limits"]["retention_days"], 7);
+    assert_eq!(capabilities["limits"]["max_retention_days"], 14);
+}
+
+#[sqlx::test]
+async fn test_extend_by_days_is_capped_and_audited(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_test_app(
+        pool.clone(),
+        clock.clone(),
+        Config {
+            max_retention_days: 30,
+            ..Default::default()
+        },
+    );
+    let created = create_event(&app, json!({})).await;
+    let extend_uri = format!(
+        "/events/{}/extend",
+        created["organizer_token"].as_str().unwrap()
+    );
+
+    // Pushes the current expiry out rather than counting from now
+    clock.advance(Duration::from_secs(2 * 24 * 3600));
+    let (status, extended) = send(&app, "POST", &extend_uri, json!({ "days": 10 })).await;
+    assert_eq!(status, StatusCode::OK, "{}", extended);
+    assert_eq!(extended["expires_at"], "2029-12-18T00:00:00Z");
+
+    // At most `max_retention_days` from now
+    let (status, extended) = send(&app, "POST", &extend_uri, json!({ "days": 30 })).await;
+    assert_eq!(status, StatusCode::OK, "{}", extended);
+    assert_eq!(extended["expires_at"], "2030-01-02T00:00:00Z");
+
+    // Never brought forward
+    let (status, extended) = send(&app, "POST", &extend_uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{}", extended);
+    assert_eq!(extended["expires_at"], "2030-01-02T00:00:00Z");
+
+    for days in [0, 31] {
+        let (status, body) = send(&app, "POST", &extend_uri, json!({ "days": days })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+        assert_eq!(body["details"][0]["field"], "days");
+    }
+
+    let details: Vec<Option<String>> = sqlx::query_scalar(
+        "SELECT details FROM event_audit_log WHERE action = 'extended' ORDER BY id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        details,
+        vec![
+            Some("2029-12-08T00:00:00+00:00 -> 2029-12-18T00:00:00+00:00".to_string()),
+            Some("2029-12-18T00:00:00+00:00 -> 2030-01-02T00:00:00+00:00".to_string()),
+            Some("2030-01-02T00:00:00+00:00 -> 2030-01-02T00:00:00+00:00".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,189 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event, send, try_create_event, try_submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// Answers for `name` in `role`, available from `start` to `end`.
+async fn answer_as(
+    app: &Router,
+    public_token: &str,
+    name: &str,
+    role: &str,
+    start: &str,
+    end: &str,
+) -> StatusCode {
+    let (status, _) = try_submit(
+        app,
+        public_token,
+        json!({
+            "participant_name": name,
+            "role": role,
+            "availabilities": [{ "start_at": start, "end_at": end }]
+        }),
+    )
+    .await;
+    status
+}
+
+#[sqlx::test]
+async fn test_suggestions_only_include_fully_staffed_windows(pool: PgPool) {
+    let app = create_router(pool);
+    let created = create_event(
+        &app,
+        json!({
+            "roles": [
+                { "name": "host", "required": 1 },
+                { "name": "reviewer", "required": 2 }
+            ]
+        }),
+    )
+    .await;
+    let public_token = created["public_token"].as_str().unwrap();
+
+    let (_, event) = send(&app, "GET", &format!("/events/{}", public_token), json!({})).await;
+    assert_eq!(
+        event["roles"][1],
+        json!({ "name": "reviewer", "required": 2 })
+    );
+
+    for (name, role, start, end) in [
+        (
+            "Hana",
+            "host",
+            "2030-01-01T09:00:00Z",
+            "2030-01-01T12:00:00Z",
+        ),
+        (
+            "Rui",
+            "reviewer",
+            "2030-01-01T09:00:00Z",
+            "2030-01-01T11:00:00Z",
+        ),
+        (
+            "Sam",
+            "reviewer",
+            "2030-01-01T10:00:00Z",
+            "2030-01-01T12:00:00Z",
+        ),
+    ] {
+        assert_eq!(
+            answer_as(&app, public_token, name, role, start, end).await,
+            StatusCode::OK
+        );
+    }
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/suggestions", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let suggestions = body["suggestions"].as_array().unwrap();
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0]["start_at"], "2030-01-01T10:00:00Z");
+    assert_eq!(suggestions[0]["end_at"], "2030-01-01T11:00:00Z");
+    assert_eq!(suggestions[0]["roles"][0]["filled"], json!(["Hana"]));
+    assert_eq!(suggestions[0]["roles"][1]["filled"], json!(["Rui", "Sam"]));
+
+    let (_, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(results["participants"][1]["role"], "host");
+}
+
+#[sqlx::test]
+async fn test_unknown_role_is_rejected(pool: PgPool) {
+    let app = create_router(pool);
+    let created = create_event(
+        &app,
+        json!({ "roles": [{ "name": "host", "required": 1 }] }),
+    )
+    .await;
+    let public_token = created["public_token"].as_str().unwrap();
+
+    let status = answer_as(
+        &app,
+        public_token,
+        "Hana",
+        "panelist",
+        "2030-01-01T09:00:00Z",
+        "2030-01-01T10:00:00Z",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_invalid_role_requirements_are_rejected(pool: PgPool) {
+    let app = create_router(pool);
+    for roles in [
+        json!([{ "name": "host", "required": 0 }]),
+        json!([{ "name": " ", "required": 1 }]),
+        json!([{ "name": "host", "required": 1 }, { "name": "host", "required": 2 }]),
+    ] {
+        let (status, _) = try_create_event(&app, json!({ "roles": roles.clone() })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", roles);
+    }
+}
+
+#[sqlx::test]
+async fn test_replacing_roles_clears_dropped_roles(pool: PgPool) {
+    let app = create_router(pool);
+    let created = create_event(
+        &app,
+        json!({ "roles": [{ "name": "host", "required": 1 }] }),
+    )
+    .await;
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+    assert_eq!(
+        answer_as(
+            &app,
+            public_token,
+            "Hana",
+            "host",
+            "2030-01-01T09:00:00Z",
+            "2030-01-01T10:00:00Z"
+        )
+        .await,
+        StatusCode::OK
+    );
+
+    let (status, updated) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        json!({ "roles": [{ "name": "lead", "required": 1 }] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["roles"], json!([{ "name": "lead", "required": 1 }]));
+
+    let (_, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        json!({}),
+    )
+    .await;
+    assert_eq!(results["participants"][1]["role"], Value::Null);
+
+    // Nobody can lead yet, so nothing is suggested
+    let (_, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/suggestions", public_token),
+        json!({}),
+    )
+    .await;
+    assert!(body["suggestions"].as_array().unwrap().is_empty());
+}
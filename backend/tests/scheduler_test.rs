@@ -0,0 +1,174 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock,
+    config::Config,
+    db::jobs,
+    integrations::templates::Templates,
+    routes::create_router_with_state,
+    scheduler::{HOURLY, Scheduler, app_jobs},
+    state::AppState,
+};
+use chrono::{DateTime, Utc};
+use common::create_event;
+use serde_json::json;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn now() -> DateTime<Utc> {
+    "2029-12-01T00:00:00Z".parse().unwrap()
+}
+
+fn mail_config() -> Config {
+    Config {
+        mail_from: Some("agreed-time@example.com".to_string()),
+        deletion_notice_hours: 24,
+        ..Default::default()
+    }
+}
+
+#[sqlx::test]
+async fn test_app_jobs_warn_once_then_trash(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let app = create_router_with_state(
+        AppState::new(pool.clone())
+            .with_clock(clock.clone())
+            .with_config(mail_config()),
+    );
+    create_event(
+        &app,
+        json!({
+            "title": "Offsite",
+            "organizer_email": "alice@example.com",
+            "webhook_url": "https://hooks.example.com/agreed-time"
+        }),
+    )
+    .await;
+    let scheduler = app_jobs(
+        pool.clone(),
+        clock.clone(),
+        &mail_config(),
+        Arc::new(Templates::default()),
+    )
+    .unwrap();
+    let run = |name| {
+        let scheduler = &scheduler;
+        async move { scheduler.run(name).await.unwrap().unwrap() }
+    };
+
+    assert_eq!(run(jobs::DELETION_NOTICE).await, 0);
+    assert_eq!(run(jobs::EXPIRY_WEBHOOKS).await, 0);
+
+    // Within a day of the seven-day retention: mail and webhook, once each
+    clock.advance(Duration::from_secs(6 * 24 * 3600 + 3600));
+    assert_eq!(run(jobs::DELETION_NOTICE).await, 1);
+    assert_eq!(run(jobs::EXPIRY_WEBHOOKS).await, 1);
+    assert_eq!(run(jobs::TRASH_EXPIRED).await, 0);
+
+    clock.advance(Duration::from_secs(3600));
+    assert_eq!(run(jobs::DELETION_NOTICE).await, 0);
+    assert_eq!(run(jobs::EXPIRY_WEBHOOKS).await, 0);
+
+    let mails: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(mails, 1);
+    let hooks: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM webhook_deliveries WHERE event_type = 'event.expiring_soon'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(hooks, 1);
+
+    clock.advance(Duration::from_secs(24 * 3600));
+    assert_eq!(run(jobs::TRASH_EXPIRED).await, 1);
+
+    // Manual runs are on record for /admin/jobs like scheduled ones
+    let runs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM job_runs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(runs, 8);
+}
+
+#[sqlx::test]
+async fn test_app_jobs_depend_on_config(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(now()));
+    let templates = Arc::new(Templates::default());
+
+    let names = app_jobs(
+        pool.clone(),
+        clock.clone(),
+        &mail_config(),
+        templates.clone(),
+    )
+    .unwrap()
+    .names();
+    assert!(names.contains(&jobs::DELETION_NOTICE));
+    assert!(names.contains(&jobs::MAIL_DELIVERY));
+
+    // Warning organizers needs mail; the webhook still fires
+    let names = app_jobs(
+        pool.clone(),
+        clock.clone(),
+        &Config::default(),
+        templates.clone(),
+    )
+    .unwrap()
+    .names();
+    assert!(!names.contains(&jobs::DELETION_NOTICE));
+    assert!(names.contains(&jobs::EXPIRY_WEBHOOKS));
+    assert!(names.contains(&jobs::TRASH_EXPIRED));
+
+    let no_notice = Config {
+        deletion_notice_hours: 0,
+        ..mail_config()
+    };
+    let names = app_jobs(pool, clock, &no_notice, templates)
+        .unwrap()
+        .names();
+    assert!(!names.contains(&jobs::DELETION_NOTICE));
+    assert!(!names.contains(&jobs::EXPIRY_WEBHOOKS));
+}
+
+#[sqlx::test]
+async fn test_panicking_job_is_recorded_as_failed(pool: PgPool) {
+    let mut scheduler = Scheduler::new(pool.clone(), Arc::new(TestClock::new(now())));
+    scheduler.register("test.panics", HOURLY, |_| async {
+        if true {
+            panic!("boom");
+        }
+        Ok::<u64, String>(0)
+    });
+    scheduler.register("test.counts", HOURLY, |_| async { Ok::<u64, String>(1) });
+    // The later registration wins
+    scheduler.register("test.counts", HOURLY, |_| async { Ok::<u64, String>(3) });
+
+    assert_eq!(scheduler.names(), vec!["test.panics", "test.counts"]);
+    assert_eq!(
+        scheduler.run("test.panics").await,
+        Some(Err("panicked: boom".to_string()))
+    );
+    assert_eq!(scheduler.run("test.counts").await, Some(Ok(3)));
+    assert_eq!(scheduler.run("test.unknown").await, None);
+
+    let runs: Vec<(String, i64, Option<String>)> =
+        sqlx::query_as("SELECT job, affected_rows, error FROM job_runs ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        runs,
+        vec![
+            (
+                "test.panics".to_string(),
+                0,
+                Some("panicked: boom".to_string())
+            ),
+            ("test.counts".to_string(), 3, None),
+        ]
+    );
+}
@@ -7,7 +7,7 @@ use chrono::Utc;
 
 async fn setup_test_server() -> TestServer {
     let config = agreed_time_backend::config::Config::from_env().unwrap();
-    let pool = agreed_time_backend::db::create_pool_lazy(&config.database_url);
+    let pool = agreed_time_backend::db::create_pool_lazy(&config.database_url, &config.pool);
 
     // In actual tests, we usually mock the DB or use a test DB.
     // Assuming the test environment sets up DATABASE_URL correctly.
@@ -46,7 +46,30 @@ async fn test_input_length_validation_create_event() {
         time_slots: vec![TimeRangeRequest {
             start_at: Utc::now(),
             end_at: Utc::now(),
+            availability_level: None,
         }],
+        results_visibility: None,
+        reveal_results_at: None,
+        blind_until_closed: None,
+        anonymous_results: None,
+        roles: vec![],
+        finalization_rules: Default::default(),
+        webhook_url: None,
+        slack_webhook_url: None,
+        discord_webhook_url: None,
+        chat_webhook_url: None,
+        password: None,
+        close_at_quorum: None,
+        organizer_email: None,
+        invitees: Vec::new(),
+        slot_kind: None,
+        weekly_slots: vec![],
+        poll_type: None,
+        dates: vec![],
+        locale: None,
+        organizer_account_token: None,
+        retention_days: None,
+        captcha_token: None,
     };
 
     let response = server.post("/events").json(&payload).await;
@@ -63,6 +86,14 @@ async fn test_input_length_validation_submit_availability() {
         participant_name: long_name,
         availabilities: vec![],
         comment: None,
+        buffer_minutes: None,
+        time_zone: None,
+        participant_token: None,
+        email: None,
+        role: None,
+        password: None,
+        dates: vec![],
+        captcha_token: None,
     };
 
     let response = server
@@ -77,6 +108,14 @@ async fn test_input_length_validation_submit_availability() {
         participant_name: "User".to_string(),
         availabilities: vec![],
         comment: Some(long_comment),
+        buffer_minutes: None,
+        time_zone: None,
+        participant_token: None,
+        email: None,
+        role: None,
+        password: None,
+        dates: vec![],
+        captcha_token: None,
     };
 
     let response = server
@@ -85,3 +124,30 @@ async fn test_input_length_validation_submit_availability() {
         .await;
     assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
 }
+
+#[tokio::test]
+async fn test_buffer_minutes_validation() {
+    let server = setup_test_server().await;
+
+    for buffer in [-5, 241] {
+        let payload = SubmitAvailabilityRequest {
+            participant_name: "User".to_string(),
+            availabilities: vec![],
+            comment: None,
+            buffer_minutes: Some(buffer),
+            time_zone: None,
+            participant_token: None,
+            email: None,
+            role: None,
+            password: None,
+            dates: vec![],
+            captcha_token: None,
+        };
+
+        let response = server
+            .post("/events/some-token/availability")
+            .json(&payload)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    }
+}
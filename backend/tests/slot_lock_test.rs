@@ -0,0 +1,166 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// An event with a morning and an afternoon slot; returns its public and
+/// organizer tokens.
+async fn create_two_slot_event(app: &Router) -> (String, String) {
+    create_event_tokens(
+        app,
+        json!({
+            "title": "Offsite",
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" },
+                { "start_at": "2030-01-01T14:00:00Z", "end_at": "2030-01-01T16:00:00Z" }
+            ]
+        }),
+    )
+    .await
+}
+
+async fn slots(app: &Router, organizer_token: &str) -> Vec<Value> {
+    let (status, view) = send(
+        app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    view["event_slots"].as_array().unwrap().clone()
+}
+
+#[sqlx::test]
+async fn test_locked_slots_clip_new_responses(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = create_two_slot_event(&app).await;
+
+    let afternoon = slots(&app, &organizer_token).await[1]["id"].clone();
+    let (status, slot) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}/slots/{}", organizer_token, afternoon),
+        Some(json!({ "locked": true })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(slot["locked"], true);
+    assert_eq!(slot["start_at"], "2030-01-01T14:00:00Z");
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        Some(json!({
+            "participant_name": "Bob",
+            "availabilities": [
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T15:00:00Z" }
+            ],
+            "comment": null
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(results["event_slots"][0]["locked"], false);
+    assert_eq!(results["event_slots"][1]["locked"], true);
+    let bob = results["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == "Bob")
+        .unwrap();
+    assert_eq!(
+        bob["availabilities"],
+        json!([{
+            "start_at": "2030-01-01T10:00:00Z",
+            "end_at": "2030-01-01T14:00:00Z",
+            "availability_level": "available"
+        }])
+    );
+
+    // A slot added next to the locked one doesn't inherit or unlock it
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        Some(json!({
+            "add_slots": [{ "start_at": "2030-01-01T13:00:00Z", "end_at": "2030-01-01T15:00:00Z" }]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let locked: Vec<(Value, Value, Value)> = slots(&app, &organizer_token)
+        .await
+        .into_iter()
+        .map(|slot| {
+            (
+                slot["start_at"].clone(),
+                slot["end_at"].clone(),
+                slot["locked"].clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        locked,
+        vec![
+            (
+                json!("2030-01-01T09:00:00Z"),
+                json!("2030-01-01T12:00:00Z"),
+                json!(false)
+            ),
+            (
+                json!("2030-01-01T13:00:00Z"),
+                json!("2030-01-01T14:00:00Z"),
+                json!(false)
+            ),
+            (
+                json!("2030-01-01T14:00:00Z"),
+                json!("2030-01-01T16:00:00Z"),
+                json!(true)
+            ),
+        ]
+    );
+
+    let afternoon = slots(&app, &organizer_token).await[2]["id"].clone();
+    let (status, slot) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}/slots/{}", organizer_token, afternoon),
+        Some(json!({ "locked": false })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(slot["locked"], false);
+}
+
+#[sqlx::test]
+async fn test_lock_slot_of_another_event(pool: PgPool) {
+    let app = create_router(pool);
+    let (_, organizer_token) = create_two_slot_event(&app).await;
+    let (_, other_organizer_token) = create_two_slot_event(&app).await;
+
+    let slot = slots(&app, &organizer_token).await[0]["id"].clone();
+    for token in [other_organizer_token.as_str(), "unknown"] {
+        let (status, _) = send(
+            &app,
+            "PATCH",
+            &format!("/events/{}/slots/{}", token, slot),
+            Some(json!({ "locked": true })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    assert_eq!(slots(&app, &organizer_token).await[0]["locked"], false);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use agreed_time_backend::models::EventSuggestionsResponse;
+use axum::{Router, http::StatusCode};
+use common::{create_event, send, submit};
+use serde_json::json;
+use sqlx::PgPool;
+
+/// Answers for `name`, available from `start` to `end`.
+async fn answer(app: &Router, public_token: &str, name: &str, start: &str, end: &str) {
+    submit(
+        app,
+        public_token,
+        json!({
+            "participant_name": name,
+            "availabilities": [{ "start_at": start, "end_at": end }]
+        }),
+    )
+    .await;
+}
+
+#[sqlx::test]
+async fn test_suggestions_rank_by_overlap(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let created = create_event(
+        &app,
+        json!({
+            "organizer_name": "Organizer",
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T13:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    let public_token = created["public_token"].as_str().unwrap();
+
+    answer(
+        &app,
+        public_token,
+        "Ann",
+        "2030-01-01T10:00:00Z",
+        "2030-01-01T12:00:00Z",
+    )
+    .await;
+    answer(
+        &app,
+        public_token,
+        "Ben",
+        "2030-01-01T11:00:00Z",
+        "2030-01-01T13:00:00Z",
+    )
+    .await;
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/suggestions?limit=2", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let response: EventSuggestionsResponse = serde_json::from_value(body).unwrap();
+    assert_eq!(response.slot_duration, 60);
+    assert_eq!(response.suggestions.len(), 2);
+
+    let best = &response.suggestions[0];
+    assert_eq!(best.start_at.to_rfc3339(), "2030-01-01T11:00:00+00:00");
+    assert_eq!(best.end_at.to_rfc3339(), "2030-01-01T12:00:00+00:00");
+    assert_eq!(best.count, 3);
+    assert_eq!(best.participants, vec!["Organizer", "Ann", "Ben"]);
+    assert!(best.missing.is_empty());
+    assert!(best.organizer_available);
+
+    // Ties on count fall back to the earliest window
+    let second = &response.suggestions[1];
+    assert_eq!(second.count, 2);
+    assert_eq!(second.start_at.to_rfc3339(), "2030-01-01T10:00:00+00:00");
+    assert_eq!(second.missing, vec!["Ben"]);
+}
+
+#[sqlx::test]
+async fn test_suggestions_unknown_event(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let (status, _) = send(&app, "GET", "/events/missing/suggestions", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use agreed_time_backend::models::EventSummary;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use common::{create_event_tokens, submit};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt; // for `oneshot`
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, String, String) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(match body {
+            Some(json) => Body::from(serde_json::to_vec(&json).unwrap()),
+            None => Body::empty(),
+        })
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(bytes.to_vec()).unwrap(),
+    )
+}
+
+async fn create_answered_event(app: &Router) -> String {
+    let (public_token, _) = create_event_tokens(
+        app,
+        json!({ "title": "Offsite", "organizer_name": "Organizer" }),
+    )
+    .await;
+    for (name, availabilities) in [
+        (
+            "Ann",
+            json!([{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]),
+        ),
+        ("Ben", json!([])),
+    ] {
+        submit(
+            app,
+            &public_token,
+            json!({ "participant_name": name, "availabilities": availabilities }),
+        )
+        .await;
+    }
+    public_token
+}
+
+#[sqlx::test]
+async fn test_summary_json(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let public_token = create_answered_event(&app).await;
+
+    let (status, content_type, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/summary", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(content_type.starts_with("application/json"));
+
+    let summary: EventSummary = serde_json::from_str(&body).unwrap();
+    assert_eq!(summary.title, "Offsite");
+    assert_eq!(summary.slots.len(), 1);
+    assert!(summary.final_slots.is_empty());
+    assert_eq!(summary.respondents, vec!["Organizer", "Ann", "Ben"]);
+    assert_eq!(summary.without_availability, vec!["Ben"]);
+    assert_eq!(summary.best_candidates[0].count, 2);
+    assert!(summary.best_candidates.len() <= 3);
+}
+
+#[sqlx::test]
+async fn test_summary_markdown(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let public_token = create_answered_event(&app).await;
+
+    let (status, content_type, body) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/summary?format=markdown", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(content_type.starts_with("text/markdown"));
+    assert!(body.starts_with("# Offsite\n"));
+    assert!(body.contains("- 2030-01-01 10:00–11:00 UTC — 2/3 (Organizer, Ann); missing: Ben\n"));
+    assert!(body.contains("No availability: Ben\n"));
+}
+
+#[sqlx::test]
+async fn test_summary_rejects_unknown_format(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let public_token = create_answered_event(&app).await;
+
+    let (status, _, _) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/summary?format=yaml", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,242 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event, send};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+async fn save_template(app: &Router, organizer_token: &str, name: &str) -> Value {
+    let (status, template) = send(
+        app,
+        "POST",
+        "/templates",
+        Some(json!({ "organizer_token": organizer_token, "name": name })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", template);
+    template
+}
+
+#[sqlx::test]
+async fn test_template_round_trip(pool: PgPool) {
+    let app = create_router(pool);
+    let source = create_event(
+        &app,
+        json!({
+            "title": "Sprint planning",
+            "description": "Bring your estimates",
+            "organizer_name": "Alice",
+            "time_zone": "Europe/Paris",
+            "slot_duration": 30,
+            "time_slots": [
+                { "start_at": "2030-03-25T08:00:00Z", "end_at": "2030-03-25T09:00:00Z" }
+            ],
+            "results_visibility": "after_submit",
+            "roles": [{ "name": "facilitator", "required": 1 }],
+            "finalization_rules": { "min_participants": 3, "required_participants": ["Alice"] },
+            "close_at_quorum": 4,
+            "password": "secret",
+            "organizer_email": "alice@example.com"
+        }),
+    )
+    .await;
+
+    let template = save_template(
+        &app,
+        source["organizer_token"].as_str().unwrap(),
+        "  Planning  ",
+    )
+    .await;
+    let template_token = template["template_token"].as_str().unwrap();
+    assert_eq!(template["name"], "Planning");
+    assert_eq!(template["title"], "Sprint planning");
+    assert_eq!(template["slot_kind"], "dates");
+    assert_eq!(template["poll_type"], "datetime");
+    assert_eq!(
+        template["time_slots"][0]["start_at"],
+        "2030-03-25T08:00:00Z"
+    );
+    assert_eq!(template["finalization_rules"]["min_participants"], 3);
+    // People's secrets stay with the source event
+    assert!(template.get("password").is_none());
+    assert!(template.get("organizer_email").is_none());
+
+    let (status, fetched) =
+        send(&app, "GET", &format!("/templates/{}", template_token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched, template);
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        &format!("/templates/{}/events", template_token),
+        Some(json!({ "organizer_name": "Bob", "offset_days": 7 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", created);
+    assert_ne!(created["public_token"], source["public_token"]);
+
+    let (status, view) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/organizer/{}",
+            created["organizer_token"].as_str().unwrap()
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(view["title"], "Sprint planning");
+    assert_eq!(view["description"], "Bring your estimates");
+    assert_eq!(view["slot_duration"], 30);
+    assert_eq!(view["results_visibility"], "after_submit");
+    assert_eq!(
+        view["roles"],
+        json!([{ "name": "facilitator", "required": 1 }])
+    );
+    assert_eq!(
+        view["finalization_rules"]["required_participants"],
+        json!(["Alice"])
+    );
+    assert_eq!(view["close_at_quorum"], 4);
+    // 09:00 in Paris on both sides of the switch to summer time
+    assert_eq!(view["event_slots"][0]["start_at"], "2030-04-01T07:00:00Z");
+    assert_eq!(view["participants"][0]["name"], "Bob");
+    assert_eq!(view["participants"].as_array().unwrap().len(), 1);
+
+    // No password carried over
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/events/{}", created["public_token"].as_str().unwrap()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_date_poll_template_shifts_dates(pool: PgPool) {
+    let app = create_router(pool);
+    let source = create_event(
+        &app,
+        json!({
+            "title": "Offsite",
+            "organizer_name": "Alice",
+            "time_zone": "America/New_York",
+            "poll_type": "date",
+            "dates": ["2030-06-03", "2030-06-04", "2030-06-07"],
+            "time_slots": []
+        }),
+    )
+    .await;
+    let template =
+        save_template(&app, source["organizer_token"].as_str().unwrap(), "Offsite").await;
+    assert_eq!(
+        template["dates"],
+        json!(["2030-06-03", "2030-06-04", "2030-06-07"])
+    );
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        &format!(
+            "/templates/{}/events",
+            template["template_token"].as_str().unwrap()
+        ),
+        Some(json!({ "organizer_name": "Alice", "offset_days": -2 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, event) = send(
+        &app,
+        "GET",
+        &format!("/events/{}", created["public_token"].as_str().unwrap()),
+        None,
+    )
+    .await;
+    assert_eq!(
+        event["dates"],
+        json!(["2030-06-01", "2030-06-02", "2030-06-05"])
+    );
+}
+
+#[sqlx::test]
+async fn test_template_errors(pool: PgPool) {
+    let app = create_router(pool);
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/templates",
+        Some(json!({ "organizer_token": "nope", "name": "Planning" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, "GET", "/templates/nope", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/templates/nope/events",
+        Some(json!({ "organizer_name": "Alice" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let weekly = create_event(
+        &app,
+        json!({
+            "title": "Standup",
+            "organizer_name": "Alice",
+            "slot_kind": "weekly",
+            "weekly_slots": [{ "weekday": "Mon", "start_time": "09:00", "end_time": "10:00" }],
+            "time_slots": []
+        }),
+    )
+    .await;
+    let organizer_token = weekly["organizer_token"].as_str().unwrap();
+    let (status, error) = send(
+        &app,
+        "POST",
+        "/templates",
+        Some(json!({ "organizer_token": organizer_token, "name": " " })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["details"][0]["field"], "name");
+
+    let template = save_template(&app, organizer_token, "Standup").await;
+    assert_eq!(template["weekly_slots"][0]["weekday"], "Mon");
+    let uri = format!(
+        "/templates/{}/events",
+        template["template_token"].as_str().unwrap()
+    );
+    let (status, _) = send(
+        &app,
+        "POST",
+        &uri,
+        Some(json!({ "organizer_name": "Alice", "offset_days": 7 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, error) = send(
+        &app,
+        "POST",
+        &uri,
+        Some(json!({ "organizer_name": "", "offset_days": 400 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["details"].as_array().unwrap().len(), 2);
+    let (status, _) = send(
+        &app,
+        "POST",
+        &uri,
+        Some(json!({ "organizer_name": "Alice" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock, db::cleanup::RETENTION_DAYS, routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use chrono::{DateTime, Utc};
+use common::send;
+use serde_json::json;
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+use tower::ServiceExt; // for `oneshot`
+
+fn at(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+#[sqlx::test]
+async fn test_time_reports_server_clock(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(at("2030-01-01T12:00:00Z")));
+    let app = create_router_with_state(AppState::new(pool).with_clock(clock));
+
+    let request = Request::builder().uri("/time").body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+
+    let (_, body) = send(&app, "GET", "/time", None).await;
+    assert_eq!(
+        body["now"]
+            .as_str()
+            .unwrap()
+            .parse::<DateTime<Utc>>()
+            .unwrap(),
+        at("2030-01-01T12:00:00Z")
+    );
+    assert_eq!(body["unix_millis"], 1_893_499_200_000i64);
+}
+
+#[sqlx::test]
+async fn test_event_responses_count_down_by_server_clock(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(at("2030-01-01T12:00:00Z")));
+    let app = create_router_with_state(AppState::new(pool).with_clock(clock.clone()));
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        Some(json!({
+            "title": "Countdown",
+            "description": null,
+            "organizer_name": "Alice",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-05T09:00:00Z", "end_at": "2030-01-05T12:00:00Z" }
+            ],
+            "reveal_results_at": "2030-01-02T12:00:00Z"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+
+    let retention = RETENTION_DAYS * 86400;
+    let (_, event) = send(&app, "GET", &format!("/events/{}", public_token), None).await;
+    assert_eq!(event["seconds_until_deadline"], 86400);
+    assert_eq!(event["seconds_until_expiry"], retention);
+    assert_eq!(
+        event["expires_at"]
+            .as_str()
+            .unwrap()
+            .parse::<DateTime<Utc>>()
+            .unwrap(),
+        at("2030-01-08T12:00:00Z")
+    );
+
+    clock.advance(Duration::from_secs(2 * 86400));
+    let (_, event) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    // Past the deadline the countdown stops at zero
+    assert_eq!(event["seconds_until_deadline"], 0);
+    assert_eq!(event["seconds_until_expiry"], retention - 2 * 86400);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use agreed_time_backend::{clock::TestClock, routes::create_router_with_state, state::AppState};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use chrono::{DateTime, Utc};
+use common::send;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tower::ServiceExt; // for `oneshot`
+
+fn at(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+fn zone<'a>(body: &'a Value, name: &str) -> &'a Value {
+    body["time_zones"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|zone| zone["name"] == name)
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn test_timezones_list_current_offsets(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(at("2030-07-01T12:00:00Z")));
+    let app = create_router_with_state(AppState::new(pool).with_clock(clock));
+
+    let request = Request::builder()
+        .uri("/timezones")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CACHE_CONTROL],
+        "public, max-age=3600"
+    );
+
+    let (_, body) = send(&app, "GET", "/timezones", None).await;
+    assert_eq!(body["at"], "2030-07-01T12:00:00Z");
+    // Summer time in Berlin
+    assert_eq!(zone(&body, "Europe/Berlin")["utc_offset_seconds"], 7200);
+    assert_eq!(zone(&body, "Europe/Berlin")["utc_offset"], "+02:00");
+    assert_eq!(zone(&body, "America/St_Johns")["utc_offset"], "-02:30");
+    assert_eq!(zone(&body, "UTC")["utc_offset"], "+00:00");
+}
+
+#[sqlx::test]
+async fn test_time_zones_are_normalized_or_rejected(pool: PgPool) {
+    let app = create_router_with_state(AppState::new(pool));
+    let event = |time_zone: &str| {
+        json!({
+            "title": "Standup",
+            "organizer_name": "Alice",
+            "time_zone": time_zone,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        })
+    };
+
+    let (status, body) = send(&app, "POST", "/events", Some(event("Mars/Olympus"))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"][0]["field"], "time_zone");
+
+    let (status, body) = send(&app, "POST", "/events", Some(event(" asia/taipei "))).await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = body["public_token"].as_str().unwrap().to_string();
+
+    let (_, body) = send(&app, "GET", &format!("/events/{}", public_token), None).await;
+    assert_eq!(body["time_zone"], "Asia/Taipei");
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        Some(json!({
+            "participant_name": "Bob",
+            "time_zone": "EUROPE/LONDON",
+            "availabilities": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }
+            ]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, body) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/{}/participants/{}",
+            public_token,
+            body["participant_token"].as_str().unwrap()
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(body["time_zone"], "Europe/London");
+}
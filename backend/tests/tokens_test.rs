@@ -0,0 +1,108 @@
+mod common;
+
+use agreed_time_backend::{routes::create_router, tokens::TOKEN_LENGTH};
+use axum::http::StatusCode;
+use common::send;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+fn assert_short_token(token: &Value) {
+    let token = token.as_str().unwrap();
+    assert_eq!(token.len(), TOKEN_LENGTH, "{}", token);
+    assert!(
+        token.chars().all(|c| c.is_ascii_alphanumeric()),
+        "{}",
+        token
+    );
+}
+
+#[sqlx::test]
+async fn test_new_links_use_short_tokens(pool: PgPool) {
+    let app = create_router(pool);
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        Some(json!({
+            "title": "Short Links",
+            "description": null,
+            "organizer_name": "Alice",
+            "time_zone": "UTC",
+            "slot_duration": 60,
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_short_token(&created["public_token"]);
+    assert_short_token(&created["organizer_token"]);
+    assert_ne!(created["public_token"], created["organizer_token"]);
+
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+    let (status, transfer) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/transfer", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_short_token(&transfer["claim_token"]);
+
+    let (status, claimed) = send(
+        &app,
+        "POST",
+        &format!(
+            "/events/claim/{}",
+            transfer["claim_token"].as_str().unwrap()
+        ),
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_short_token(&claimed["organizer_token"]);
+}
+
+#[sqlx::test]
+async fn test_legacy_uuid_tokens_still_resolve(pool: PgPool) {
+    let app = create_router(pool.clone());
+    let public_token = Uuid::new_v4().to_string();
+    let organizer_token = Uuid::new_v4().to_string();
+
+    let event_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO events (public_token, organizer_token, title, time_zone, slot_duration, expires_at)
+        VALUES ($1, $2, 'Legacy', 'UTC', 60, now() + INTERVAL '7 days')
+        RETURNING id
+        "#,
+        public_token,
+        organizer_token
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    sqlx::query!(
+        "INSERT INTO participants (event_id, name, is_organizer) VALUES ($1, 'Alice', true)",
+        event_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (status, event) = send(&app, "GET", &format!("/events/{}", public_token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["title"], "Legacy");
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
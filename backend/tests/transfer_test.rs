@@ -0,0 +1,151 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock,
+    models::{ClaimOwnershipResponse, TransferOwnershipResponse},
+    routes::create_router_with_state,
+    state::AppState,
+};
+use axum::{Router, http::StatusCode};
+use chrono::Utc;
+use common::{create_event_tokens, send};
+use serde_json::json;
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+
+async fn request_transfer(app: &Router, organizer_token: &str) -> TransferOwnershipResponse {
+    let (status, body) = send(
+        app,
+        "POST",
+        &format!("/events/{}/transfer", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    serde_json::from_value(body).unwrap()
+}
+
+#[sqlx::test]
+async fn test_claim_replaces_organizer_token(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool.clone());
+    let (public_token, old_token) = create_event_tokens(&app, json!({})).await;
+
+    let transfer = request_transfer(&app, &old_token).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/events/claim/{}", transfer.claim_token),
+        Some(json!({ "organizer_name": "Bob" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let claimed: ClaimOwnershipResponse = serde_json::from_value(body).unwrap();
+    assert_eq!(claimed.public_token, public_token);
+    assert_ne!(claimed.organizer_token, old_token);
+
+    // Old token is invalidated, new one works
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", old_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, organizer_view) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", claimed.organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let organizer = organizer_view["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["is_organizer"] == true)
+        .unwrap();
+    assert_eq!(organizer["name"], "Bob");
+
+    let actions: Vec<String> = sqlx::query_scalar("SELECT action FROM event_audit_log ORDER BY id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        actions,
+        vec![
+            "created",
+            "ownership_transfer_requested",
+            "ownership_transferred"
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn test_claim_link_is_single_use(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let (_, organizer_token) = create_event_tokens(&app, json!({})).await;
+    let transfer = request_transfer(&app, &organizer_token).await;
+    let claim_uri = format!("/events/claim/{}", transfer.claim_token);
+
+    let (status, _) = send(&app, "POST", &claim_uri, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, "POST", &claim_uri, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_new_transfer_replaces_pending_claim(pool: PgPool) {
+    let app = agreed_time_backend::routes::create_router(pool);
+    let (_, organizer_token) = create_event_tokens(&app, json!({})).await;
+    let first = request_transfer(&app, &organizer_token).await;
+    let second = request_transfer(&app, &organizer_token).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/claim/{}", first.claim_token),
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/claim/{}", second.claim_token),
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_expired_claim_link_rejected(pool: PgPool) {
+    let clock = TestClock::new(Utc::now());
+    let app = create_router_with_state(AppState::new(pool).with_clock(Arc::new(clock.clone())));
+    let (_, organizer_token) = create_event_tokens(&app, json!({})).await;
+    let transfer = request_transfer(&app, &organizer_token).await;
+
+    clock.advance(Duration::from_secs(73 * 60 * 60));
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/claim/{}", transfer.claim_token),
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // The original organizer keeps control
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
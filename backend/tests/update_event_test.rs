@@ -0,0 +1,244 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// The organizer is available 09:00–13:00 UTC on 2030-01-01.
+async fn create_planning_event(app: &Router) -> (String, String) {
+    create_event_tokens(
+        app,
+        json!({
+            "title": "Planning",
+            "description": "Original",
+            "organizer_name": "Organizer",
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T13:00:00Z" }
+            ]
+        }),
+    )
+    .await
+}
+
+fn ranges(participant: &Value) -> Vec<(String, String)> {
+    participant["availabilities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["start_at"].as_str().unwrap().to_string(),
+                r["end_at"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_update_event_fields(pool: PgPool) {
+    let app = create_router(pool.clone());
+    let (public_token, organizer_token) = create_planning_event(&app).await;
+    let before = sqlx::query_scalar!(
+        "SELECT updated_at FROM events WHERE public_token = $1",
+        public_token
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let (status, body) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        json!({ "title": "  Renamed  ", "description": "", "slot_duration": 30 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["title"], "Renamed");
+    assert_eq!(body["description"], Value::Null);
+    assert_eq!(body["slot_duration"], 30);
+    assert_eq!(body["time_zone"], "UTC");
+    assert_eq!(body["event_slots"].as_array().unwrap().len(), 1);
+
+    let after = sqlx::query_scalar!(
+        "SELECT updated_at FROM events WHERE public_token = $1",
+        public_token
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(after > before);
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        json!({ "time_zone": "Mars/Olympus" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", public_token),
+        json!({ "title": "Hijacked" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_removing_slot_trims_availability(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = create_planning_event(&app).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({
+            "participant_name": "Guest",
+            "availabilities": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ],
+            "comment": null
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Cut 10–11 out of the middle and add an afternoon window
+    let (status, body) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        json!({
+            "remove_slots": [
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ],
+            "add_slots": [
+                { "start_at": "2030-01-01T15:00:00Z", "end_at": "2030-01-01T16:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let slots: Vec<(&str, &str)> = body["event_slots"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| {
+            (
+                s["start_at"].as_str().unwrap(),
+                s["end_at"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        slots,
+        vec![
+            ("2030-01-01T09:00:00Z", "2030-01-01T10:00:00Z"),
+            ("2030-01-01T11:00:00Z", "2030-01-01T13:00:00Z"),
+            ("2030-01-01T15:00:00Z", "2030-01-01T16:00:00Z"),
+        ]
+    );
+
+    let (_, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        json!({}),
+    )
+    .await;
+    let participants = results["participants"].as_array().unwrap();
+    let organizer = participants
+        .iter()
+        .find(|p| p["name"] == "Organizer")
+        .unwrap();
+    let guest = participants.iter().find(|p| p["name"] == "Guest").unwrap();
+    let range = |s: &str, e: &str| (s.to_string(), e.to_string());
+
+    let mut organizer_ranges = ranges(organizer);
+    organizer_ranges.sort();
+    assert_eq!(
+        organizer_ranges,
+        vec![
+            range("2030-01-01T09:00:00Z", "2030-01-01T10:00:00Z"),
+            range("2030-01-01T11:00:00Z", "2030-01-01T13:00:00Z"),
+            range("2030-01-01T15:00:00Z", "2030-01-01T16:00:00Z"),
+        ]
+    );
+    let mut guest_ranges = ranges(guest);
+    guest_ranges.sort();
+    assert_eq!(
+        guest_ranges,
+        vec![
+            range("2030-01-01T09:00:00Z", "2030-01-01T10:00:00Z"),
+            range("2030-01-01T11:00:00Z", "2030-01-01T12:00:00Z"),
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn test_cannot_remove_every_slot(pool: PgPool) {
+    let app = create_router(pool);
+    let (_, organizer_token) = create_planning_event(&app).await;
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        json!({
+            "title": "Gone",
+            "remove_slots": [
+                { "start_at": "2030-01-01T00:00:00Z", "end_at": "2030-01-02T00:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_finalized_event_slots_are_frozen(pool: PgPool) {
+    let app = create_router(pool);
+    let (_, organizer_token) = create_planning_event(&app).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        json!({
+            "slots": [{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        json!({
+            "remove_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Text edits are still allowed
+    let (status, body) = send(
+        &app,
+        "PATCH",
+        &format!("/events/{}", organizer_token),
+        json!({ "description": "Bring snacks" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["description"], "Bring snacks");
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use agreed_time_backend::{routes::create_router_with_state, state::AppState};
+use axum::http::StatusCode;
+use common::send;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// The `field` of every entry in `details`.
+fn invalid_fields(body: &Value) -> Vec<&str> {
+    body["details"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|detail| detail["field"].as_str().unwrap())
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_create_event_reports_every_invalid_field(pool: PgPool) {
+    let app = create_router_with_state(AppState::new(pool));
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": " ",
+            "description": "d".repeat(1001),
+            "organizer_name": "Alice",
+            "time_zone": "Mars/Olympus",
+            "organizer_email": "not-an-address",
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+    assert_eq!(
+        invalid_fields(&body),
+        ["title", "description", "time_zone", "organizer_email"]
+    );
+
+    // Slots are checked once the fields are valid
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Offsite",
+            "organizer_name": "Alice",
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" },
+                { "start_at": "2031-06-01T12:00:00Z", "end_at": "2031-06-01T09:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(invalid_fields(&body), ["time_slots[1]", "time_slots"]);
+
+    // Lengths count characters, not bytes
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "é".repeat(100),
+            "organizer_name": "Alice",
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_submissions_report_invalid_fields(pool: PgPool) {
+    let app = create_router_with_state(AppState::new(pool));
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/events",
+        json!({
+            "title": "Team sync",
+            "organizer_name": "Alice",
+            "time_slots": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let uri = format!(
+        "/events/{}/availability",
+        body["public_token"].as_str().unwrap()
+    );
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &uri,
+        json!({
+            "participant_name": "",
+            "comment": "c".repeat(501),
+            "availabilities": [
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T09:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+    assert_eq!(
+        invalid_fields(&body),
+        ["participant_name", "comment", "availabilities[0]"]
+    );
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &uri,
+        json!({
+            "participant_name": "Bob",
+            "availabilities": [
+                { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let participant_uri = format!(
+        "/events/{}/participants/{}",
+        uri.split('/').nth(2).unwrap(),
+        body["participant_token"].as_str().unwrap()
+    );
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &participant_uri,
+        json!({
+            "participant_name": "Bob",
+            "time_zone": "Nowhere/Special",
+            "email": "bob at example",
+            "availabilities": []
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(invalid_fields(&body), ["time_zone", "email"]);
+}
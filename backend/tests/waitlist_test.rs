@@ -0,0 +1,178 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event_tokens, send, submit};
+use serde_json::json;
+use sqlx::PgPool;
+
+/// Creates an event and fills it up to the participant limit of 10.
+async fn full_event(app: &Router) -> (String, String) {
+    let (public_token, organizer_token) =
+        create_event_tokens(app, json!({ "title": "Popular" })).await;
+    for i in 2..=10 {
+        let submitted = submit(
+            app,
+            &public_token,
+            json!({ "participant_name": format!("Guest {}", i) }),
+        )
+        .await;
+        assert_eq!(submitted["status"], "confirmed");
+    }
+    (public_token, organizer_token)
+}
+
+async fn result_names(app: &Router, public_token: &str) -> Vec<String> {
+    let (status, results) = send(
+        app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    results["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_full_event_waitlists_until_approved(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = full_event(&app).await;
+
+    let submitted = submit(&app, &public_token, json!({ "participant_name": "Late" })).await;
+    assert_eq!(submitted["status"], "pending");
+    assert!(
+        !result_names(&app, &public_token)
+            .await
+            .contains(&"Late".to_string())
+    );
+
+    // The participant sees their own pending state
+    let (_, participant) = send(
+        &app,
+        "GET",
+        &format!(
+            "/events/{}/participants/{}",
+            public_token,
+            submitted["participant_token"].as_str().unwrap()
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(participant["status"], "pending");
+
+    let (status, waitlist) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/waitlist", organizer_token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(waitlist["confirmed"], 10);
+    assert_eq!(waitlist["max_participants"], 10);
+    assert_eq!(waitlist["pending"][0]["name"], "Late");
+    let late_id = waitlist["pending"][0]["id"].as_i64().unwrap();
+
+    // Approving without a replacement raises the event past the cap
+    let (status, waitlist) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/waitlist/{}", organizer_token, late_id),
+        Some(json!({ "decision": "approve" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(waitlist["confirmed"], 11);
+    assert_eq!(waitlist["pending"], json!([]));
+    let names = result_names(&app, &public_token).await;
+    assert_eq!(names.len(), 11);
+    assert!(names.contains(&"Late".to_string()));
+
+    // Approved entries are no longer on the waitlist
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/waitlist/{}", organizer_token, late_id),
+        Some(json!({ "decision": "reject" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_approval_can_replace_a_dropout_and_reject_removes(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = full_event(&app).await;
+    submit(&app, &public_token, json!({ "participant_name": "Swap" })).await;
+    submit(&app, &public_token, json!({ "participant_name": "Spam" })).await;
+
+    let (_, waitlist) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/waitlist", organizer_token),
+        None,
+    )
+    .await;
+    let swap_id = waitlist["pending"][0]["id"].as_i64().unwrap();
+    let spam_id = waitlist["pending"][1]["id"].as_i64().unwrap();
+
+    let (_, organizer) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        None,
+    )
+    .await;
+    let participants = organizer["participants"].as_array().unwrap();
+    let organizer_id = participants[0]["id"].as_i64().unwrap();
+    let dropout_id = participants
+        .iter()
+        .find(|p| p["name"] == "Guest 2")
+        .unwrap()["id"]
+        .as_i64()
+        .unwrap();
+
+    // Only confirmed guests can be replaced
+    for replaces in [organizer_id, spam_id] {
+        let (status, _) = send(
+            &app,
+            "POST",
+            &format!("/events/{}/waitlist/{}", organizer_token, swap_id),
+            Some(json!({ "decision": "approve", "replaces": replaces })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let (status, waitlist) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/waitlist/{}", organizer_token, swap_id),
+        Some(json!({ "decision": "approve", "replaces": dropout_id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(waitlist["confirmed"], 10);
+
+    let (status, waitlist) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/waitlist/{}", organizer_token, spam_id),
+        Some(json!({ "decision": "reject" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(waitlist["pending"], json!([]));
+
+    let names = result_names(&app, &public_token).await;
+    assert_eq!(names.len(), 10);
+    assert!(names.contains(&"Swap".to_string()));
+    assert!(!names.contains(&"Guest 2".to_string()));
+    assert!(!names.contains(&"Spam".to_string()));
+}
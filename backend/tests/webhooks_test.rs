@@ -0,0 +1,404 @@
+mod common;
+
+use agreed_time_backend::{
+    clock::TestClock,
+    db::cleanup::{announce_upcoming_deletions, delete_expired_events, purge_deleted_events},
+    encryption::Keyring,
+    integrations::{
+        mail::DeliveryReport,
+        webhooks::{HttpSender, OutgoingWebhook, WebhookSender, deliver_pending, sign},
+    },
+    routes::create_router_with_state,
+    state::AppState,
+};
+use axum::http::StatusCode;
+use chrono::Utc;
+use common::send;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+fn create_body(webhook_url: Value) -> Value {
+    json!({
+        "title": "Hooked",
+        "description": null,
+        "organizer_name": "Organizer",
+        "time_zone": "UTC",
+        "slot_duration": 60,
+        "time_slots": [
+            { "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T12:00:00Z" }
+        ],
+        "webhook_url": webhook_url
+    })
+}
+
+struct RecordingSender {
+    fail: bool,
+    posted: Mutex<Vec<OutgoingWebhook>>,
+}
+
+impl WebhookSender for RecordingSender {
+    async fn post(&self, webhook: &OutgoingWebhook) -> Result<(), String> {
+        if self.fail {
+            return Err("HTTP 503 Service Unavailable".to_string());
+        }
+        self.posted.lock().unwrap().push(webhook.clone());
+        Ok(())
+    }
+}
+
+#[sqlx::test]
+async fn test_webhooks_fire_signed_on_activity(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(Utc::now()));
+    let app = create_router_with_state(AppState::new(pool.clone()).with_clock(clock.clone()));
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/events",
+        create_body(json!("https://hooks.example.com/agreed-time")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let public_token = created["public_token"].as_str().unwrap();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+    let secret = created["webhook_secret"].as_str().unwrap().to_string();
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({
+            "participant_name": "Bob",
+            "availabilities": [
+                { "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }
+            ],
+            "comment": null
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    for _ in 0..2 {
+        send(
+            &app,
+            "POST",
+            &format!("/events/{}/close", organizer_token),
+            json!({}),
+        )
+        .await;
+    }
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/finalize", organizer_token),
+        json!({
+            "slots": [{ "start_at": "2030-01-01T10:00:00Z", "end_at": "2030-01-01T11:00:00Z" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let sender = RecordingSender {
+        fail: false,
+        posted: Mutex::new(Vec::new()),
+    };
+    let report = deliver_pending(&pool, &sender, &Keyring::default(), clock.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(report, DeliveryReport { sent: 3, failed: 0 });
+
+    let posted = sender.posted.lock().unwrap().clone();
+    let types: Vec<&str> = posted.iter().map(|w| w.event_type.as_str()).collect();
+    // Closing twice only fires once
+    assert_eq!(
+        types,
+        vec!["participant.submitted", "event.closed", "event.finalized"]
+    );
+    for webhook in posted.iter() {
+        assert_eq!(webhook.url, "https://hooks.example.com/agreed-time");
+        assert_eq!(webhook.signature, Some(sign(&secret, &webhook.payload)));
+    }
+
+    let submitted: Value = serde_json::from_str(&posted[0].payload).unwrap();
+    assert_eq!(submitted["public_token"], public_token);
+    assert_eq!(submitted["data"]["participant_name"], "Bob");
+    assert_eq!(submitted["data"]["updated"], false);
+    let finalized: Value = serde_json::from_str(&posted[2].payload).unwrap();
+    assert_eq!(
+        finalized["data"]["final_slots"][0]["start_at"],
+        "2030-01-01T10:00:00Z"
+    );
+
+    // Delivered rows are not posted again
+    let report = deliver_pending(&pool, &sender, &Keyring::default(), clock.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(report, DeliveryReport::default());
+}
+
+#[sqlx::test]
+async fn test_failed_webhooks_back_off(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(Utc::now()));
+    let app = create_router_with_state(AppState::new(pool.clone()).with_clock(clock.clone()));
+
+    let (_, created) = send(
+        &app,
+        "POST",
+        "/events",
+        create_body(json!("https://hooks.example.com/down")),
+    )
+    .await;
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+    send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+
+    let failing = RecordingSender {
+        fail: true,
+        posted: Mutex::new(Vec::new()),
+    };
+    let report = deliver_pending(&pool, &failing, &Keyring::default(), clock.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(report, DeliveryReport { sent: 0, failed: 1 });
+
+    // Not due again until the backoff has passed
+    let report = deliver_pending(&pool, &failing, &Keyring::default(), clock.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(report, DeliveryReport::default());
+
+    clock.advance(Duration::from_secs(60));
+    let working = RecordingSender {
+        fail: false,
+        posted: Mutex::new(Vec::new()),
+    };
+    let report = deliver_pending(&pool, &working, &Keyring::default(), clock.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(report, DeliveryReport { sent: 1, failed: 0 });
+
+    let log = sqlx::query!("SELECT attempts, last_error, delivered_at FROM webhook_deliveries")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(log.attempts, 2);
+    assert_eq!(log.last_error, None);
+    assert!(log.delivered_at.is_some());
+}
+
+#[sqlx::test]
+async fn test_retention_webhooks(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(Utc::now()));
+    let app = create_router_with_state(AppState::new(pool.clone()).with_clock(clock.clone()));
+    let sender = RecordingSender {
+        fail: false,
+        posted: Mutex::new(Vec::new()),
+    };
+    let notice = chrono::Duration::hours(48);
+
+    let (_, created) = send(
+        &app,
+        "POST",
+        "/events",
+        create_body(json!("https://hooks.example.com/agreed-time")),
+    )
+    .await;
+    let public_token = created["public_token"].as_str().unwrap().to_string();
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+    let secret = created["webhook_secret"].as_str().unwrap().to_string();
+    // Events without a webhook are never announced
+    send(&app, "POST", "/events", create_body(Value::Null)).await;
+
+    assert_eq!(
+        announce_upcoming_deletions(&pool, clock.as_ref(), notice)
+            .await
+            .unwrap(),
+        0
+    );
+    clock.advance(Duration::from_secs(6 * 86400));
+    assert_eq!(
+        announce_upcoming_deletions(&pool, clock.as_ref(), notice)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        announce_upcoming_deletions(&pool, clock.as_ref(), notice)
+            .await
+            .unwrap(),
+        0
+    );
+    deliver_pending(&pool, &sender, &Keyring::default(), clock.as_ref())
+        .await
+        .unwrap();
+    let expiring: Value = serde_json::from_str(&sender.posted.lock().unwrap()[0].payload).unwrap();
+    assert_eq!(expiring["type"], "event.expiring_soon");
+    assert_eq!(expiring["public_token"], public_token.as_str());
+    assert!(expiring["data"]["expires_at"].is_string());
+
+    // Held back once trashed, then dropped with the event
+    send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+    clock.advance(Duration::from_secs(2 * 86400));
+    assert_eq!(
+        delete_expired_events(&pool, clock.as_ref()).await.unwrap(),
+        2
+    );
+    clock.advance(Duration::from_secs(8 * 86400));
+    assert_eq!(
+        purge_deleted_events(&pool, clock.as_ref()).await.unwrap(),
+        2
+    );
+
+    let report = deliver_pending(&pool, &sender, &Keyring::default(), clock.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(report, DeliveryReport { sent: 1, failed: 0 });
+    let posted = sender.posted.lock().unwrap()[1].clone();
+    assert_eq!(posted.event_type, "event.purged");
+    assert_eq!(posted.signature, Some(sign(&secret, &posted.payload)));
+    let purged: Value = serde_json::from_str(&posted.payload).unwrap();
+    assert_eq!(purged["public_token"], public_token.as_str());
+    assert!(purged["data"]["deleted_at"].is_string());
+
+    // The settled notice is pruned after another trash period
+    clock.advance(Duration::from_secs(8 * 86400));
+    purge_deleted_events(&pool, clock.as_ref()).await.unwrap();
+    let remaining = sqlx::query_scalar!("SELECT COUNT(*) FROM webhook_deliveries")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, Some(0));
+}
+
+#[sqlx::test]
+async fn test_webhook_url_validation(pool: PgPool) {
+    let app = create_router_with_state(AppState::new(pool));
+
+    for url in ["ftp://example.com/hook", "not a url", "https://"] {
+        let (status, _) = send(&app, "POST", "/events", create_body(json!(url))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", url);
+    }
+
+    let (status, created) = send(&app, "POST", "/events", create_body(Value::Null)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(created["webhook_secret"], Value::Null);
+}
+
+#[sqlx::test]
+async fn test_webhook_url_must_be_public(pool: PgPool) {
+    let app = create_router_with_state(AppState::new(pool));
+
+    for url in [
+        "http://127.0.0.1:8080/hook",
+        "http://localhost/hook",
+        "http://169.254.169.254/latest/meta-data",
+        "http://10.0.0.5/hook",
+        "http://[::1]/hook",
+    ] {
+        let (status, body) = send(&app, "POST", "/events", create_body(json!(url))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", url);
+        assert_eq!(body["details"][0]["field"], "webhook_url", "{}", url);
+        assert_eq!(
+            body["details"][0]["message"],
+            "Webhook URL must point to a public address"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_http_sender_refuses_private_hosts() {
+    let webhook = OutgoingWebhook {
+        id: 1,
+        url: "http://127.0.0.1:9/hook".to_string(),
+        event_type: "event.closed".to_string(),
+        payload: "{}".to_string(),
+        signature: None,
+    };
+
+    let error = HttpSender.post(&webhook).await.unwrap_err();
+    assert!(error.contains("private"), "{}", error);
+}
+
+/// Checks, while posting, that the row is neither locked nor handed out again.
+struct ProbingSender {
+    pool: PgPool,
+    clock: Arc<TestClock>,
+    probes: Mutex<Vec<(bool, DeliveryReport)>>,
+}
+
+impl WebhookSender for ProbingSender {
+    async fn post(&self, webhook: &OutgoingWebhook) -> Result<(), String> {
+        let unlocked =
+            sqlx::query("SELECT id FROM webhook_deliveries WHERE id = $1 FOR UPDATE NOWAIT")
+                .bind(webhook.id)
+                .fetch_one(&self.pool)
+                .await
+                .is_ok();
+        let idle = RecordingSender {
+            fail: false,
+            posted: Mutex::new(Vec::new()),
+        };
+        let nested = deliver_pending(&self.pool, &idle, &Keyring::default(), self.clock.as_ref())
+            .await
+            .unwrap();
+        self.probes.lock().unwrap().push((unlocked, nested));
+        Ok(())
+    }
+}
+
+#[sqlx::test]
+async fn test_deliveries_are_claimed_not_locked_while_posting(pool: PgPool) {
+    let clock = Arc::new(TestClock::new(Utc::now()));
+    let app = create_router_with_state(AppState::new(pool.clone()).with_clock(clock.clone()));
+
+    let (_, created) = send(
+        &app,
+        "POST",
+        "/events",
+        create_body(json!("https://hooks.example.com/agreed-time")),
+    )
+    .await;
+    let organizer_token = created["organizer_token"].as_str().unwrap();
+    send(
+        &app,
+        "POST",
+        &format!("/events/{}/close", organizer_token),
+        json!({}),
+    )
+    .await;
+
+    let sender = ProbingSender {
+        pool: pool.clone(),
+        clock: clock.clone(),
+        probes: Mutex::new(Vec::new()),
+    };
+    let report = deliver_pending(&pool, &sender, &Keyring::default(), clock.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(report, DeliveryReport { sent: 1, failed: 0 });
+    assert_eq!(
+        sender.probes.into_inner().unwrap(),
+        vec![(true, DeliveryReport::default())]
+    );
+
+    let log = sqlx::query!("SELECT attempts, delivered_at FROM webhook_deliveries")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(log.attempts, 1);
+    assert!(log.delivered_at.is_some());
+}
@@ -0,0 +1,225 @@
+mod common;
+
+use agreed_time_backend::routes::create_router;
+use axum::{Router, http::StatusCode};
+use common::{create_event, create_event_tokens, send, try_create_event};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+/// Mondays 18:00–21:00 (given as two overlapping windows) and Sunday
+/// 23:00–01:00 in Paris.
+async fn create_weekly_event(app: &Router) -> (String, String) {
+    create_event_tokens(
+        app,
+        json!({
+            "title": "Choir practice",
+            "time_zone": "Europe/Paris",
+            "slot_kind": "weekly",
+            "weekly_slots": [
+                { "weekday": "Mon", "start_time": "18:00", "end_time": "20:00" },
+                { "weekday": "Mon", "start_time": "19:00", "end_time": "21:00" },
+                { "weekday": "Sun", "start_time": "23:00", "end_time": "01:00" }
+            ],
+            "time_slots": []
+        }),
+    )
+    .await
+}
+
+fn weekly_slots(event: &Value) -> Vec<(String, String, String)> {
+    event["weekly_slots"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|slot| {
+            (
+                slot["weekday"].as_str().unwrap().to_string(),
+                slot["start_time"].as_str().unwrap().to_string(),
+                slot["end_time"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+fn slot(weekday: &str, start: &str, end: &str) -> (String, String, String) {
+    (weekday.to_string(), start.to_string(), end.to_string())
+}
+
+#[sqlx::test]
+async fn test_weekly_slots_merge_and_wrap_around_the_week(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_weekly_event(&app).await;
+
+    let (status, event) = send(
+        &app,
+        "GET",
+        &format!("/events/{}", public_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["slot_kind"], "weekly");
+    // The Sunday slot continues at the start of the week
+    assert_eq!(
+        weekly_slots(&event),
+        vec![
+            slot("Mon", "00:00:00", "01:00:00"),
+            slot("Mon", "18:00:00", "21:00:00"),
+            slot("Sun", "23:00:00", "00:00:00"),
+        ]
+    );
+    assert_eq!(event["event_slots"][1]["start_at"], "2024-01-01T17:00:00Z");
+    assert_eq!(event["event_slots"][1]["end_at"], "2024-01-01T20:00:00Z");
+}
+
+#[sqlx::test]
+async fn test_weekly_availability_feeds_results_and_suggestions(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, organizer_token) = create_weekly_event(&app).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/events/{}/availability", public_token),
+        json!({
+            "participant_name": "Bob",
+            "availabilities": [
+                { "start_at": "2024-01-01T18:00:00Z", "end_at": "2024-01-01T20:00:00Z" }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, results) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/results", public_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results["slot_kind"], "weekly");
+    assert_eq!(results["weekly_slots"].as_array().unwrap().len(), 3);
+    assert_eq!(results["total_participants"], 2);
+
+    let (status, suggestions) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/suggestions", public_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        suggestions["suggestions"][0]["start_at"],
+        "2024-01-01T18:00:00Z"
+    );
+    assert_eq!(suggestions["suggestions"][0]["count"], 2);
+
+    let (status, organizer_view) = send(
+        &app,
+        "GET",
+        &format!("/events/organizer/{}", organizer_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(organizer_view["slot_kind"], "weekly");
+    assert_eq!(organizer_view["weekly_slots"], results["weekly_slots"]);
+}
+
+#[sqlx::test]
+async fn test_update_weekly_slots(pool: PgPool) {
+    let app = create_router(pool);
+    let (_, organizer_token) = create_weekly_event(&app).await;
+    let uri = format!("/events/{}", organizer_token);
+
+    let (status, updated) = send(
+        &app,
+        "PATCH",
+        &uri,
+        json!({
+            "add_weekly_slots": [{ "weekday": "Wed", "start_time": "12:00", "end_time": "13:00" }],
+            "remove_weekly_slots": [{ "weekday": "Mon", "start_time": "20:00", "end_time": "21:00" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", updated);
+    assert_eq!(
+        weekly_slots(&updated),
+        vec![
+            slot("Mon", "00:00:00", "01:00:00"),
+            slot("Mon", "18:00:00", "20:00:00"),
+            slot("Wed", "12:00:00", "13:00:00"),
+            slot("Sun", "23:00:00", "00:00:00"),
+        ]
+    );
+
+    for body in [
+        json!({ "add_slots": [{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }] }),
+        json!({ "time_zone": "Asia/Tokyo" }),
+    ] {
+        let (status, _) = send(&app, "PATCH", &uri, body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[sqlx::test]
+async fn test_weekly_and_date_slots_do_not_mix(pool: PgPool) {
+    let app = create_router(pool);
+    let weekly = json!([{ "weekday": "Mon", "start_time": "09:00", "end_time": "10:00" }]);
+    let dates = json!([{ "start_at": "2030-01-01T09:00:00Z", "end_at": "2030-01-01T10:00:00Z" }]);
+
+    for (slot_kind, time_zone, time_slots, weekly_slots) in [
+        ("weekly", "UTC", dates.clone(), weekly.clone()),
+        ("weekly", "UTC", json!([]), json!([])),
+        ("weekly", "Mars/Olympus", json!([]), weekly.clone()),
+        ("dates", "UTC", dates.clone(), weekly.clone()),
+    ] {
+        let (status, _) = try_create_event(
+            &app,
+            json!({
+                "time_zone": time_zone,
+                "slot_kind": slot_kind,
+                "time_slots": time_slots,
+                "weekly_slots": weekly_slots
+            }),
+        )
+        .await;
+        assert_eq!(
+            status,
+            StatusCode::BAD_REQUEST,
+            "{} {}",
+            slot_kind,
+            time_zone
+        );
+    }
+
+    // Date events still report their kind
+    let created = create_event(&app, json!({ "time_slots": dates })).await;
+    let (_, event) = send(
+        &app,
+        "GET",
+        &format!("/events/{}", created["public_token"].as_str().unwrap()),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(event["slot_kind"], "dates");
+    assert_eq!(event["weekly_slots"], json!([]));
+}
+
+#[sqlx::test]
+async fn test_weekly_events_have_no_calendar_export(pool: PgPool) {
+    let app = create_router(pool);
+    let (public_token, _) = create_weekly_event(&app).await;
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/events/{}/export.ics", public_token),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
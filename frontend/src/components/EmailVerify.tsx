@@ -0,0 +1,46 @@
+import { useState, useEffect } from 'react';
+import { eventService } from '../services/eventService';
+import type { EmailVerifiedResponse } from '../types';
+
+export default function EmailVerify() {
+  const [loading, setLoading] = useState(true);
+  const [confirmed, setConfirmed] = useState<EmailVerifiedResponse | null>(null);
+
+  useEffect(() => {
+    const verify = async () => {
+      const token = new URLSearchParams(window.location.search).get('token');
+      if (token) {
+        setConfirmed(await eventService.verifyParticipantEmail(token));
+      }
+      setLoading(false);
+    };
+
+    verify();
+  }, []);
+
+  if (loading) {
+    return <div className="text-center py-12 text-ink/60 font-serif">Confirming your email...</div>;
+  }
+
+  if (!confirmed) {
+    return (
+      <div className="text-center py-12 text-ink/60 font-serif">
+        This confirmation link is unknown, already used or expired.
+      </div>
+    );
+  }
+
+  return (
+    <div className="text-center py-12 font-serif">
+      <p className="text-ink">
+        {confirmed.email} is confirmed for {confirmed.title}.
+      </p>
+      <a
+        href={`/event/${confirmed.public_token}`}
+        className="inline-block mt-4 font-semibold text-film-accent hover:text-film-accent-hover"
+      >
+        Back to the event
+      </a>
+    </div>
+  );
+}
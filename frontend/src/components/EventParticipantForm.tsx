@@ -24,7 +24,7 @@ export default function EventParticipantForm({ publicToken }: { publicToken: str
       try {
         const data = await eventService.getEvent(publicToken);
         if (data) {
-          if (data.state === 'closed') {
+          if (data.state !== 'open') {
             window.location.href = `/event/${publicToken}/result`;
             return;
           }
@@ -29,7 +29,9 @@ export default function EventResultView({ publicToken }: { publicToken: string }
     const fetchResults = async () => {
         setLoading(true);
         try {
-            const data = await eventService.getEventResults(publicToken);
+            // Private results are unlocked by the results link (?results_token=...)
+            const resultsToken = new URLSearchParams(window.location.search).get('results_token');
+            const data = await eventService.getEventResults(publicToken, resultsToken);
             if (data) {
                 setResultsData(data);
             }
@@ -30,16 +30,24 @@ export default function EventResultsDisplay({ data, publicToken: _publicToken, t
     const slotMap = new Map<string, { count: number, participants: string[] }>();
 
     data.participants.forEach(p => {
-      // Convert this participant's ranges to cells
-      const cells = rangesToCells(p.availabilities, slotDuration);
-      
-      cells.forEach(cellKey => {
+      // Convert this participant's ranges to cells; "unavailable" ranges never count
+      const level = (l: string) => p.availabilities.filter(r => (r.availability_level ?? 'available') === l);
+      const cells = rangesToCells(level('available'), slotDuration);
+      const maybeCells = rangesToCells(level('if_need_be'), slotDuration);
+
+      const add = (cellKey: string, weight: number, label: string) => {
         if (!slotMap.has(cellKey)) {
           slotMap.set(cellKey, { count: 0, participants: [] });
         }
         const entry = slotMap.get(cellKey)!;
-        entry.count += 1;
-        entry.participants.push(p.name);
+        entry.count += weight;
+        entry.participants.push(label);
+      };
+
+      cells.forEach(cellKey => add(cellKey, 1, p.name));
+      // "If need be" counts half, matching the server's suggestion score
+      maybeCells.forEach(cellKey => {
+        if (!cells.has(cellKey)) add(cellKey, 0.5, `${p.name} (if need be)`);
       });
     });
 
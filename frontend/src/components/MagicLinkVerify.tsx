@@ -0,0 +1,39 @@
+import { useState, useEffect } from 'react';
+import { eventService } from '../services/eventService';
+import type { UserResponse } from '../types';
+
+export default function MagicLinkVerify() {
+  const [loading, setLoading] = useState(true);
+  const [user, setUser] = useState<UserResponse | null>(null);
+
+  useEffect(() => {
+    const verify = async () => {
+      const token = new URLSearchParams(window.location.search).get('token');
+      if (token) {
+        setUser(await eventService.verifyMagicLink(token));
+      }
+      setLoading(false);
+    };
+
+    verify();
+  }, []);
+
+  if (loading) {
+    return <div className="text-center py-12 text-ink/60 font-serif">Logging you in...</div>;
+  }
+
+  if (!user) {
+    return (
+      <div className="text-center py-12 text-ink/60 font-serif">
+        This login link is unknown, already used or expired. Request a new one to log in.
+      </div>
+    );
+  }
+
+  return (
+    <div className="text-center py-12 font-serif">
+      <p className="text-ink">Logged in as {user.email}.</p>
+      <a href="/" className="inline-block mt-4 font-semibold text-film-accent hover:text-film-accent-hover">Go to your events</a>
+    </div>
+  );
+}
@@ -114,7 +114,10 @@ export default function OrganizerDashboard({ organizerToken }: OrganizerDashboar
   }
 
   const publicEventUrl = `${window.location.origin}/event/${organizerData.public_token}`;
-  const publicResultsUrl = `${window.location.origin}/event/${organizerData.public_token}/result`;
+  // Private results only open with the results token
+  const resultsQuery =
+    organizerData.results_visibility === "private" ? `?results_token=${organizerData.results_token}` : "";
+  const publicResultsUrl = `${window.location.origin}/event/${organizerData.public_token}/result${resultsQuery}`;
   const publicEventDisplayUrl = formatTokenUrlForDisplay(publicEventUrl);
   const publicResultsDisplayUrl = formatTokenUrlForDisplay(publicResultsUrl);
 
@@ -19,6 +19,7 @@ import { useTimeSlotDragSelection } from '../hooks/useTimeSlotDragSelection';
 import { useHistory } from '../hooks/useHistory';
 import { GRID_STYLES } from '../constants/gridStyles';
 import { mergeWeek1PatternIntoFollowingWeeks } from '../utils/weekPatternUtils';
+import { loadCore } from '../utils/core';
 
 // Helper to convert hour to time slot with duration for display logic
 const getTimeSlotFromHour = (hour: number, duration: number): { startTime: string; endTime: string } => {
@@ -118,6 +119,11 @@ export default function TimeSlotSelector({
     }));
   }, [initialSelectedCells, setState]);
 
+  // Selections merge into ranges with the server's own algorithm once loaded
+  useEffect(() => {
+    loadCore();
+  }, []);
+
   // Available cells set for fast lookup in Guest Mode
   const availableCells = useMemo(() => {
     if (!availableRanges) return null;
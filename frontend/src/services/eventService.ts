@@ -3,6 +3,7 @@ import type {
     ApiTimeRange, 
     CreateEventPayload, 
     CreateEventSuccessResponse, 
+    CreationTicketResponse,
     EventResponse, 
     SubmitAvailabilityPayload,
     SubmitAvailabilitySuccessResponse,
@@ -10,7 +11,9 @@ import type {
     ParticipantResponse, 
     EventResultsResponse,
     OrganizerEventResponse,
-    ApiErrorResponse
+    ApiErrorResponse,
+    UserResponse,
+    EmailVerifiedResponse
   } from '../types';
   
   // API base URL - adjust as needed for production vs development
@@ -76,11 +79,19 @@ import type {
         time_slots: timeSlots,
       };
   
+      const headers: Record<string, string> = {
+        'Content-Type': 'application/json',
+      };
+      // Deployments guarding event creation hand out tickets; 404 means not required
+      const ticketResponse = await fetch(`${API_BASE_URL}/events/ticket`, { method: 'POST' });
+      if (ticketResponse.ok) {
+        const { ticket }: CreationTicketResponse = await ticketResponse.json();
+        headers['X-Creation-Ticket'] = ticket;
+      }
+
       const response = await fetch(`${API_BASE_URL}/events`, {
         method: 'POST',
-        headers: {
-          'Content-Type': 'application/json',
-        },
+        headers,
         body: JSON.stringify(payload),
       });
   
@@ -171,9 +182,10 @@ import type {
     },
   
     // Fetch detailed event results with participant information
-    getEventResults: async (publicToken: string): Promise<EventResultsResponse | null> => {
+    getEventResults: async (publicToken: string, resultsToken?: string | null): Promise<EventResultsResponse | null> => {
       try {
-        const response = await fetch(`${API_BASE_URL}/events/${publicToken}/results`);
+        const query = resultsToken ? `?results_token=${encodeURIComponent(resultsToken)}` : '';
+        const response = await fetch(`${API_BASE_URL}/events/${publicToken}/results${query}`);
   
         if (response.status === 404) {
           return null;
@@ -191,6 +203,48 @@ import type {
       }
     },
   
+    // Redeems a mailed login link; the response sets the session cookie
+    verifyMagicLink: async (token: string): Promise<UserResponse | null> => {
+      try {
+        const response = await fetch(`${API_BASE_URL}/auth/verify?token=${encodeURIComponent(token)}`, {
+          credentials: 'include',
+        });
+
+        if (response.status === 401) {
+          return null;
+        }
+
+        if (!response.ok) {
+          throw new Error(`Failed to verify login link: ${response.statusText}`);
+        }
+
+        return await response.json() as UserResponse;
+      } catch (error) {
+        console.error("Error verifying login link:", error);
+        return null;
+      }
+    },
+  
+    // Confirms the address a participant left, from the mailed link
+    verifyParticipantEmail: async (token: string): Promise<EmailVerifiedResponse | null> => {
+      try {
+        const response = await fetch(`${API_BASE_URL}/participants/verify-email?token=${encodeURIComponent(token)}`);
+
+        if (response.status === 404) {
+          return null;
+        }
+
+        if (!response.ok) {
+          throw new Error(`Failed to confirm email: ${response.statusText}`);
+        }
+
+        return await response.json() as EmailVerifiedResponse;
+      } catch (error) {
+        console.error("Error confirming email:", error);
+        return null;
+      }
+    },
+  
     getOrganizerEvent: async (organizerToken: string): Promise<OrganizerEventResponse | null> => {
       try {
         const response = await fetch(`${API_BASE_URL}/events/organizer/${organizerToken}`);
@@ -1,4 +1,4 @@
-export type EventState = "open" | "closed";
+export type EventState = "open" | "closed" | "finalized" | "archived";
 
 // 基礎的時間範圍介面
 export interface ApiTimeRange {
@@ -6,25 +6,134 @@ export interface ApiTimeRange {
   end_at: string;
 }
 
+// Yes / Maybe / No; ranges without a level count as "available"
+export type AvailabilityLevel = "available" | "if_need_be" | "unavailable";
+
+export interface ApiAvailabilityRange extends ApiTimeRange {
+  availability_level?: AvailabilityLevel;
+}
+
+// "weekly": slots are weekdays + times of day, stored in a fixed reference week
+export type SlotKind = "dates" | "weekly";
+
+// "date": participants tick whole days instead of ranges
+export type PollType = "datetime" | "date";
+
+// GET results / organizer view: one entry per candidate date of a date poll
+export interface DateVotes {
+  date: string; // "2030-01-02"
+  count: number;
+  participants: string[];
+  if_need_be: string[];
+}
+
+export type Weekday = "Mon" | "Tue" | "Wed" | "Thu" | "Fri" | "Sat" | "Sun";
+
+export interface WeeklySlot {
+  weekday: Weekday;
+  start_time: string; // "18:00:00" in the event's zone
+  end_time: string; // At or before start_time means the next day
+}
+
+// Staffing polls: every suggested window needs `required` people with this role
+export interface EventRole {
+  name: string;
+  required: number;
+}
+
+// Finalize refuses slots that miss these unless sent with force: true
+export interface FinalizationRules {
+  min_participants: number | null; // Attendees needed, organizer included
+  required_participants: string[]; // Names that must be able to attend
+}
+
+export interface RoleCoverage {
+  role: string;
+  required: number;
+  filled: string[];
+}
+
+// "private": results need the results token; submission stays open
+export type ResultsVisibility = "public" | "private" | "after_submit" | "after_close";
+
 export interface CreateEventPayload {
   title: string;
   description?: string;
   organizer_name: string;
+  organizer_email?: string; // Warned before retention deletion
+  invitees?: string[]; // Addresses /remind mails until they answer
+  locale?: string; // Language of notification mails, "en" (default) or "ja"
+  time_zone?: string;
+  slot_duration?: number;
+  time_slots: ApiTimeRange[]; // Empty for weekly events
+  slot_kind?: SlotKind; // Defaults to "dates"
+  weekly_slots?: WeeklySlot[];
+  poll_type?: PollType; // Defaults to "datetime"
+  dates?: string[]; // Date polls only, instead of time_slots
+  results_visibility?: ResultsVisibility;
+  reveal_results_at?: string; // Blind poll: hide results until this time
+  blind_until_closed?: boolean; // Blind poll: hide results while open
+  anonymous_results?: boolean; // Public results show counts, not names; can't be changed later
+  roles?: EventRole[];
+  finalization_rules?: Partial<FinalizationRules>;
+  webhook_url?: string; // Receives signed activity callbacks
+  slack_webhook_url?: string; // https://hooks.slack.com/... incoming webhook
+  discord_webhook_url?: string; // https://discord.com/api/webhooks/... channel webhook
+  chat_webhook_url?: string; // any http(s) URL taking plain JSON messages
+  password?: string; // Participants must send it as X-Event-Password
+  close_at_quorum?: number; // Close once this many are available in one slot
+  organizer_account_token?: string; // Lists the event on that dashboard
+  retention_days?: number; // 1 to limits.max_retention_days; default limits.retention_days
+  captcha_token?: string; // Required when capabilities report integrations.captcha
+}
+
+// PATCH /api/events/:organizer_token — omitted fields stay unchanged
+export interface UpdateEventPayload {
+  title?: string;
+  description?: string;
   time_zone?: string;
   slot_duration?: number;
-  time_slots: ApiTimeRange[];
+  add_slots?: ApiTimeRange[];
+  remove_slots?: ApiTimeRange[];
+  add_weekly_slots?: WeeklySlot[]; // Weekly events only
+  remove_weekly_slots?: WeeklySlot[];
+  add_dates?: string[]; // Date polls only
+  remove_dates?: string[];
+  results_visibility?: ResultsVisibility;
+  reveal_results_at?: string;
+  blind_until_closed?: boolean;
+  roles?: EventRole[]; // Replaces every role
+  finalization_rules?: Partial<FinalizationRules>; // Replaces the rules; {} removes them
+  password?: string; // Empty string removes it
+  close_at_quorum?: number; // 0 turns it off
+  organizer_email?: string; // Empty string removes it
+  deletion_notice?: boolean; // false skips the retention warning
+  locale?: string;
+}
+
+export interface CreationTicketResponse {
+  ticket: string; // Sent as X-Creation-Ticket on POST /events
+  expires_at: string;
 }
 
 export interface CreateEventSuccessResponse {
   id: string; // UUID
   public_token: string;
   organizer_token: string;
+  results_token: string;
+  webhook_secret: string | null; // HMAC key for webhook signatures
 }
 
 // Backend DB: event_slots
 export interface ApiEventSlot extends ApiTimeRange {
   id: number;
   event_id: string;
+  locked: boolean; // Grey out; submissions are clipped around it
+}
+
+// PATCH /api/events/:organizer_token/slots/:id
+export interface UpdateSlotRequest {
+  locked: boolean;
 }
 
 // GET /api/events/:token
@@ -36,7 +145,58 @@ export interface EventResponse {
   slot_duration: number;
   state: EventState;
   event_slots: ApiEventSlot[];
+  slot_kind: SlotKind;
+  weekly_slots: WeeklySlot[]; // event_slots by weekday; empty unless weekly
+  poll_type: PollType;
+  dates: string[]; // Candidate dates; empty unless a date poll
   organizer_name: string;
+  results_visibility: ResultsVisibility;
+  reveal_results_at: string | null;
+  blind_until_closed: boolean;
+  anonymous_results: boolean;
+  roles: EventRole[];
+  finalization_rules: FinalizationRules;
+  close_at_quorum: number | null; // Closes automatically once this many are available in one slot
+  seconds_until_deadline: number | null; // Until reveal_results_at, by the server clock
+  expires_at: string; // When the event moves to the trash
+  seconds_until_expiry: number;
+  display: DisplayTimes | null; // Only when requested with ?tz= or X-Display-Timezone
+}
+
+// Preformatted local times, e.g. "Tue 2030-01-01 10:00–12:00 CET"
+export interface DisplayTimes {
+  time_zone: string;
+  event_slots: string[]; // Same order as event_slots
+  final_slots: string[]; // Organizer view only
+  reveal_results_at: string | null;
+  expires_at: string;
+  event_slot_ranges: LocalRange[]; // Same order as event_slots
+  final_slot_ranges: LocalRange[]; // Organizer view only
+}
+
+// A slot on the requested zone's wall clock, e.g. "2030-01-01T10:00:00+01:00"
+export interface LocalRange {
+  start_at: string;
+  end_at: string;
+  label: string;
+}
+
+// GET /api/time
+export interface TimeResponse {
+  now: string;
+  unix_millis: number;
+}
+
+// GET /api/timezones
+export interface TimeZoneInfo {
+  name: string; // IANA name, stored as sent back
+  utc_offset_seconds: number; // At `at`, daylight saving included
+  utc_offset: string; // "+05:30"
+}
+
+export interface TimeZonesResponse {
+  at: string;
+  time_zones: TimeZoneInfo[]; // Alphabetical
 }
 
 // --- UI Types ---
@@ -72,35 +232,75 @@ export interface EventData {
 
 // Form Data for Submit Availability
 export interface SubmitAvailabilityPayload {
+  participant_token?: string; // Replaces that earlier response instead of adding a new one
   participant_name: string;
-  availabilities: ApiTimeRange[];
+  availabilities: ApiAvailabilityRange[];
+  dates?: string[]; // Days ticked in a date poll
   comment?: string;
+  email?: string; // Receives invitations and reminders once confirmed through the mailed link; never shown publicly
+  role?: string; // One of the event's roles
+  password?: string; // Alternative to the X-Event-Password header
+  captcha_token?: string; // Required when capabilities report integrations.captcha
 }
 
+export type ParticipantStatus = "confirmed" | "pending";
+
 export interface SubmitAvailabilitySuccessResponse {
   participant_token: string;
+  status: ParticipantStatus; // "pending" when the event was full and awaits organizer approval
+}
+
+// POST /api/events/:public_token/availability/batch takes SubmitAvailabilityRequest[]
+export interface BatchSubmissionResult {
+  index: number; // Position in the request
+  participant_token: string | null; // Set when stored
+  status: ParticipantStatus | null;
+  error: ApiErrorResponse | null; // Why this entry was skipped
+}
+
+export interface BatchSubmitAvailabilityResponse {
+  results: BatchSubmissionResult[];
 }
 
 export interface UpdateParticipantPayload {
   participant_name: string;
-  availabilities: ApiTimeRange[];
+  availabilities: ApiAvailabilityRange[];
+  dates?: string[]; // Days ticked in a date poll
   comment?: string;
+  email?: string; // A new address has to be confirmed again; never shown publicly
+  role?: string; // One of the event's roles
 }
 
 export interface ParticipantResponse {
   participant_token: string; // This is the UUID
   name: string;
   comment?: string;
-  availabilities: ApiTimeRange[];
+  availabilities: ApiAvailabilityRange[];
+  status: ParticipantStatus;
 }
 
 // --- Results View Types ---
 
+export interface ApiFinalSlot extends ApiTimeRange {
+  id: number;
+}
+
 export interface ParticipantAvailability {
+  id: number; // For organizer endpoints such as the availability history
   name: string;
   is_organizer: boolean; // Added
   comment?: string; // Added
-  availabilities: ApiTimeRange[];
+  availabilities: ApiAvailabilityRange[];
+  rsvp_status?: "accepted" | "declined" | "tentative" | null;
+  role?: string | null;
+  email_verified?: boolean; // Organizer view only; absent without an address
+}
+
+export interface SlotCount {
+  start_at: string;
+  end_at: string; // start_at + slot_duration
+  available: number;
+  if_need_be: number;
 }
 
 export interface EventResultsResponse {
@@ -111,18 +311,439 @@ export interface EventResultsResponse {
   slot_duration: number;
   state: EventState;
   event_slots: ApiEventSlot[];
-  participants: ParticipantAvailability[];
+  slot_kind: SlotKind;
+  weekly_slots: WeeklySlot[];
+  poll_type: PollType;
+  dates: string[];
+  final_slots: ApiFinalSlot[]; // Empty until the organizer finalizes
+  participants: ParticipantAvailability[]; // Empty while results_hidden and for anonymous results
+  date_votes: DateVotes[]; // One per date of a date poll; names read "Anonymous" for anonymous results
+  slot_counts?: SlotCount[]; // Cells someone marked; absent for organizers
   total_participants: number;
+  results_hidden?: boolean; // Blind poll not revealed yet; absent for organizers
+  anonymous_results: boolean;
+  display?: DisplayTimes | null; // Only when requested with ?tz= or X-Display-Timezone
+}
+
+export interface ApiEditLock {
+  holder_name: string;
+  acquired_at: string;
+  expires_at: string;
 }
 
 export interface OrganizerEventResponse extends EventResultsResponse {
   public_token: string;
   organizer_token: string;
+  edit_lock: ApiEditLock | null; // Co-host currently editing slots
+  results_visibility: ResultsVisibility;
+  results_token: string;
+  reveal_results_at: string | null;
+  blind_until_closed: boolean;
+  roles: EventRole[];
+  finalization_rules: FinalizationRules;
+  close_at_quorum: number | null;
+  webhook_url: string | null;
+  webhook_secret: string | null;
+  slack_webhook_url: string | null;
+  discord_webhook_url: string | null;
+  chat_webhook_url: string | null;
+  password_protected: boolean;
+  organizer_email: string | null;
+  invitees: string[];
+  deletion_notice: boolean;
+  retention_days: number; // What /extend adds again
+  locale: string;
+  created_at: string;
+  seconds_until_deadline: number | null;
+  expires_at: string;
+  seconds_until_expiry: number;
+  display: DisplayTimes | null;
+}
+
+// POST /api/events/:organizer_token/clone; body optional, responds like event creation
+export interface CloneEventRequest {
+  offset_days?: number; // Moves every slot by whole days, keeping local times; not for weekly events
+}
+
+// POST /api/events/:public_token/caldav/freebusy (X-Event-Password when protected)
+export interface CaldavFreeBusyRequest {
+  url: string; // Calendar collection URL
+  username?: string;
+  password?: string; // Used for this one request; never stored
+}
+
+export interface CaldavFreeBusyResponse {
+  availabilities: ApiAvailabilityRange[]; // Free time, plus if_need_be where only tentative events sit; not saved
+  busy_periods: number;
+}
+
+// POST /api/templates
+export interface CreateTemplateRequest {
+  organizer_token: string; // The event whose structure is saved
+  name: string;
+}
+
+// POST /api/templates and GET /api/templates/:template_token; no people,
+// passwords, webhooks or addresses
+export interface TemplateResponse {
+  template_token: string; // Share to let others create events from it
+  name: string;
+  created_at: string;
+  title: string;
+  description: string | null;
+  time_zone: string | null;
+  slot_duration: number;
+  slot_kind: SlotKind;
+  poll_type: PollType;
+  time_slots: ApiTimeRange[]; // Datetime polls on dates
+  weekly_slots: WeeklySlot[]; // Weekly events
+  dates: string[]; // Date polls
+  results_visibility: ResultsVisibility;
+  blind_until_closed: boolean;
+  anonymous_results: boolean;
+  roles: EventRole[];
+  finalization_rules: FinalizationRules;
+  close_at_quorum: number | null;
+  locale: string;
+  retention_days: number;
+}
+
+// POST /api/templates/:template_token/events; responds like event creation
+export interface CreateEventFromTemplateRequest {
+  organizer_name: string;
+  offset_days?: number; // Moves the candidate times by whole days; not for weekly templates
+  captcha_token?: string; // Required when capabilities report integrations.captcha
+}
+
+// POST /api/events/:organizer_token/extend; body optional
+export interface ExtendEventRequest {
+  days?: number; // Pushes expires_at out; capped at limits.max_retention_days from now
+}
+
+// GET /api/participants/verify-email?token=
+export interface EmailVerifiedResponse {
+  email: string;
+  title: string; // Event the address was left for
+  public_token: string;
+}
+
+// POST /api/events/:organizer_token/finalize
+export interface FinalizeEventRequest {
+  slots: ApiTimeRange[];
+  force?: boolean; // Finalize despite unmet finalization rules
+  send_invitations?: boolean; // Default true; false finalizes without calendar invitations
+}
+
+export interface FinalizeEventResponse {
+  state: EventState;
+  final_slots: ApiFinalSlot[];
+  invitations_queued: number;
+}
+
+// GET /api/events/:organizer_token/invitations
+export type InvitationStatus = "not_sent" | "queued" | "sent" | "failed";
+
+export interface InvitationDelivery {
+  participant_id: number;
+  participant_name: string;
+  email: string;
+  status: InvitationStatus; // failed: retries used up
+  attempts: number;
+  last_error: string | null;
+  queued_at: string | null;
+  sent_at: string | null;
+  rsvp_status: "accepted" | "declined" | "tentative" | null;
+}
+
+export interface InvitationListResponse {
+  revision: number;
+  invitations: InvitationDelivery[];
+}
+
+// GET /api/events/:organizer_token/participants/:id/history
+export interface AvailabilityVersion {
+  id: number;
+  created_at: string;
+  source: "participant" | "organizer"; // organizer: trimmed by slot changes
+  availabilities: ApiAvailabilityRange[];
+  added: ApiAvailabilityRange[]; // Compared with the previous version
+  removed: ApiAvailabilityRange[];
+}
+
+export interface AvailabilityHistoryResponse {
+  participant_name: string;
+  versions: AvailabilityVersion[]; // Newest first
+}
+
+// GET /api/events/:organizer_token/audit
+export interface AuditEntry {
+  id: number;
+  action: string; // e.g. "created", "availability_submitted", "closed", "finalized"
+  actor: string; // "organizer", "system", "quorum" or the participant's name
+  details: string | null;
+  created_at: string;
+}
+
+export interface AuditLogResponse {
+  entries: AuditEntry[]; // Newest first
+}
+
+// POST /api/events/:public_token/comments
+export interface CreateCommentRequest {
+  participant_token: string;
+  body: string; // Up to 500 characters
+}
+
+export interface EventComment {
+  id: number;
+  author_name: string; // The author's current name
+  is_organizer: boolean;
+  body: string;
+  created_at: string;
+}
+
+// GET /api/events/:public_token/comments?limit=&offset=
+export interface CommentListResponse {
+  comments: EventComment[]; // Oldest first
+  total: number;
+}
+
+export interface WaitlistEntry {
+  id: number;
+  name: string;
+  comment: string | null;
+  role: string | null;
+  created_at: string;
+}
+
+export interface WaitlistResponse {
+  confirmed: number;
+  max_participants: number;
+  pending: WaitlistEntry[]; // Oldest first
+}
+
+export interface ModerateParticipantPayload {
+  decision: "approve" | "reject";
+  replaces?: number; // Confirmed participant removed to make room
+}
+
+export interface SlotSuggestion extends ApiTimeRange {
+  count: number;
+  participants: string[]; // Available for the whole window, organizer first
+  if_need_be: string[]; // Available for the whole window only if need be
+  missing: string[];
+  organizer_available: boolean;
+  score: number; // 2 per available participant, 1 per "if need be"
+  roles: RoleCoverage[]; // Empty unless the event requires roles
+  meets_rules: boolean; // Satisfies the event's finalization_rules; true without rules
+  display?: LocalRange; // Only when requested with ?tz= or X-Display-Timezone
+}
+
+// Frames on the /api/events/:public_token/ws socket; clients only send "presence"
+export type RealtimeMessage =
+  | {
+      type: "availability";
+      participant_name: string;
+      role: string | null;
+      availabilities: ApiAvailabilityRange[]; // Replaces the participant's previous ranges
+    }
+  | { type: "participant_removed"; participant_name: string }
+  | ({ type: "comment" } & EventComment)
+  | { type: "presence"; name: string; editing: boolean };
+
+export interface EventSuggestionsResponse {
+  slot_duration: number;
+  suggestions: SlotSuggestion[]; // Best first
+}
+
+// POST /api/events/conflicts — the organizer tokens stored in this browser
+export interface ConflictCheckRequest {
+  organizer_tokens: string[];
+  limit?: number; // Candidates per undecided event, default 5
+}
+
+export interface ConflictingTime extends ApiTimeRange {
+  public_token: string;
+  finalized: boolean; // A final slot rather than a candidate
+}
+
+export interface EventConflicts {
+  public_token: string;
+  title: string;
+  state: EventState;
+  final_slots: ApiFinalSlot[];
+  candidates: {
+    suggestion: SlotSuggestion;
+    conflicts: ConflictingTime[];
+    recommended: boolean; // Part of the best combination without double-booking
+  }[];
+}
+
+export interface ConflictCheckResponse {
+  events: EventConflicts[]; // Request order, archived events left out
+  compatible: boolean;
+}
+
+export interface AggregatedParticipation {
+  participant_token: string;
+  public_token: string;
+  title: string;
+  name: string;
+  state: EventState;
+  availabilities: ApiAvailabilityRange[];
+  final_slots: ApiFinalSlot[];
+}
+
+export interface AvailabilityOverlap extends ApiTimeRange {
+  public_tokens: string[]; // Polls that were all offered this span
+}
+
+export interface ParticipantAggregateResponse {
+  events: AggregatedParticipation[]; // Request order, weekly polls left out
+  free: ApiTimeRange[]; // Offered in any poll and not already taken
+  busy: ApiTimeRange[]; // Final slots of the polls
+  overlaps: AvailabilityOverlap[];
+}
+
+// POST /api/organizers
+export interface OrganizerAccountResponse {
+  account_token: string;
+  created_at: string;
+}
+
+export interface OrganizerEventSummary {
+  public_token: string;
+  organizer_token: string;
+  title: string;
+  state: EventState;
+  total_participants: number; // Confirmed, organizer included
+  pending_participants: number;
+  created_at: string;
+}
+
+// GET /api/organizers/:account_token/events?limit=&offset=
+// GET /api/auth/events?limit=&offset= (session cookie)
+export interface OrganizerEventsResponse {
+  events: OrganizerEventSummary[]; // Newest first, trash left out
+  total: number;
+}
+
+// POST /api/auth/magic-link
+export interface MagicLinkRequest {
+  email: string;
+  locale?: string;
+}
+
+// GET /api/auth/verify?token=, GET /api/auth/me
+export interface UserResponse {
+  email: string;
   created_at: string;
 }
 
+// POST /api/auth/token
+export interface TokenResponse {
+  token: string; // Send as Authorization: Bearer
+  expires_at: string;
+}
+
 export interface ApiErrorResponse {
   error: string;
   code?: string;
   message?: string;
-}
\ No newline at end of file
+  request_id?: string; // Same as the X-Request-Id header
+  details?: { field: string; message: string }[]; // With code VALIDATION_FAILED
+}
+
+export interface RemindParticipantsResponse {
+  queued: number;
+  recipients: string[]; // Participant names
+  invitees: number; // Registered invitees among queued
+}
+
+// GET /api/events/:organizer_token/reminders
+export interface ReminderBatch {
+  sent_at: string;
+  recipient_count: number; // Invitees included
+  invitee_count: number;
+}
+
+export interface ReminderLogResponse {
+  reminders: ReminderBatch[]; // Newest first
+  next_reminder_at: string | null; // Null when /remind may send now
+}
+
+// POST /api/events/:organizer_token/assign — omitted fields use the defaults
+export interface AssignRequest {
+  capacity?: number; // Attendees per slot; no limit when omitted
+  sessions_per_participant?: number; // Defaults to 1
+}
+
+// PUT /api/events/:organizer_token/assign — slots left out end up empty
+export interface UpdateAssignmentRequest {
+  slots: { final_slot_id: number; participant_ids: number[] }[];
+  confirm?: boolean; // Locks the assignment in
+}
+
+export interface AssignmentParticipant {
+  participant_id: number;
+  name: string;
+  role: string | null;
+}
+
+export interface AssignedParticipant extends AssignmentParticipant {
+  availability_level: AvailabilityLevel; // Manual edits may assign "unavailable" people
+}
+
+export interface SlotAssignment extends ApiTimeRange {
+  final_slot_id: number;
+  attendees: AssignedParticipant[];
+  roles: RoleCoverage[]; // Empty unless the event requires roles
+}
+
+export interface AssignmentResponse {
+  confirmed_at: string | null; // null while still a draft
+  slots: SlotAssignment[];
+  unassigned: AssignmentParticipant[];
+}
+
+// GET /api/capabilities
+export interface CapabilitiesResponse {
+  api_versions: string[];
+  server_version: string;
+  poll_types: string[];
+  limits: {
+    max_participants: number;
+    max_waitlist: number;
+    max_title_length: number;
+    max_description_length: number;
+    max_name_length: number;
+    max_comment_length: number;
+    max_time_ranges: number; // Event slots, or ranges in one submission
+    max_range_span_days: number;
+    max_buffer_minutes: number;
+    max_final_slots: number;
+    max_batch_tokens: number;
+    max_batch_submissions: number; // Entries of one availability batch
+    max_suggestions: number;
+    max_reminders_per_day: number;
+    max_invitees: number;
+    retention_days: number;
+    max_retention_days: number;
+    trash_retention_days: number;
+    archive_after_days: number;
+    edit_lock_ttl_seconds: number;
+    transfer_claim_ttl_hours: number;
+    demo_retention_hours: number;
+  };
+  integrations: {
+    ics_export: boolean;
+    email_invitations: boolean;
+    itip_replies: boolean;
+    admin_api: boolean;
+    email_login: boolean;
+    captcha: "hcaptcha" | "turnstile" | null; // Widget whose token creation and submissions need
+    caldav_import: boolean; // POST /events/:public_token/caldav/freebusy is available
+  };
+  deprecations: { feature: string; replacement: string | null; sunset: string | null }[];
+  demo_mode: boolean; // Public sandbox; see limits.demo_retention_hours
+}
@@ -0,0 +1,40 @@
+import { describe, it, expect, beforeAll } from 'vitest';
+import { existsSync, readFileSync } from 'node:fs';
+import { fileURLToPath } from 'node:url';
+import { loadCore, coreMergeTimeRanges } from './core';
+import { cellsToRanges, getCellKey } from './eventUtils';
+
+const wasmPath = fileURLToPath(
+  new URL('../wasm/agreed-time-core/agreed_time_core_bg.wasm', import.meta.url),
+);
+
+// CI builds the crate first (`npm run build:core`); plain checkouts skip this
+describe.skipIf(!existsSync(wasmPath))('agreed-time-core', () => {
+  beforeAll(async () => {
+    expect(await loadCore(readFileSync(wasmPath))).toBe(true);
+  });
+
+  it('merges overlapping and touching ranges like the server', () => {
+    expect(coreMergeTimeRanges([
+      { start_at: '2030-01-01T11:00:00.000Z', end_at: '2030-01-01T12:00:00.000Z' },
+      { start_at: '2030-01-01T09:00:00.000Z', end_at: '2030-01-01T10:30:00.000Z' },
+      { start_at: '2030-01-01T10:00:00.000Z', end_at: '2030-01-01T11:00:00.000Z' },
+      { start_at: '2030-01-01T14:00:00.000Z', end_at: '2030-01-01T15:00:00.000Z' },
+    ])).toEqual([
+      { start_at: '2030-01-01T09:00:00.000Z', end_at: '2030-01-01T12:00:00.000Z' },
+      { start_at: '2030-01-01T14:00:00.000Z', end_at: '2030-01-01T15:00:00.000Z' },
+    ]);
+  });
+
+  it('backs the grid preview', () => {
+    const cells = new Set([
+      getCellKey('2030-01-01', 9),
+      getCellKey('2030-01-01', 10),
+      getCellKey('2030-01-01', 13),
+    ]);
+    const ranges = cellsToRanges(cells, 60);
+    expect(ranges).toHaveLength(2);
+    expect(new Date(ranges[0].end_at).getTime() - new Date(ranges[0].start_at).getTime())
+      .toBe(2 * 60 * 60 * 1000);
+  });
+});
@@ -0,0 +1,54 @@
+import type { ApiTimeRange } from '../types';
+
+// Browser build of the backend's agreed-time-core crate (`npm run build:core`),
+// so the grid preview merges ranges with the same code as the server. The
+// JSON-in/JSON-out bindings live in backend/core/src/wasm.rs.
+interface CoreBindings {
+  default: (input?: { module_or_path: BufferSource | string | URL }) => Promise<unknown>;
+  mergeTimeRanges: (rangesJson: string) => string;
+}
+
+// A glob rather than an import, so type checks and tests still run on a
+// checkout where the crate hasn't been built
+const builds = import.meta.glob<CoreBindings>('../wasm/agreed-time-core/agreed_time_core.js');
+
+let core: CoreBindings | null = null;
+let loading: Promise<boolean> | null = null;
+
+/**
+ * Instantiates the WASM build once; resolves false when it isn't built or
+ * fails to load. `wasm` overrides where the binary comes from (tests pass
+ * the bytes, since Node can't fetch it).
+ */
+export function loadCore(wasm?: BufferSource): Promise<boolean> {
+  if (!loading) {
+    const load = Object.values(builds)[0];
+    loading = load
+      ? load()
+          .then(async (module) => {
+            await module.default(wasm ? { module_or_path: wasm } : undefined);
+            core = module;
+            return true;
+          })
+          .catch((error) => {
+            console.error('Failed to load agreed-time-core:', error);
+            return false;
+          })
+      : Promise.resolve(false);
+  }
+  return loading;
+}
+
+/**
+ * Sorts and merges overlapping or touching ranges with the core crate;
+ * null until loadCore() has succeeded.
+ */
+export function coreMergeTimeRanges(ranges: ApiTimeRange[]): ApiTimeRange[] | null {
+  if (!core) return null;
+  const merged: ApiTimeRange[] = JSON.parse(core.mergeTimeRanges(JSON.stringify(ranges)));
+  // chrono drops zero milliseconds; keep the format the rest of the UI uses
+  return merged.map(range => ({
+    start_at: new Date(range.start_at).toISOString(),
+    end_at: new Date(range.end_at).toISOString(),
+  }));
+}
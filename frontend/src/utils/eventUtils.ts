@@ -4,6 +4,7 @@
  */
 
 import type { ParticipantAvailability, ApiTimeRange } from '../types';
+import { coreMergeTimeRanges } from './core';
 
 interface SlotData {
   count: number;
@@ -92,7 +93,8 @@ export function rangesToCells(ranges: ApiTimeRange[], slotDuration: number): Set
 }
 
 /**
- * Convert selected grid cell keys (Local) to merged backend time ranges (UTC)
+ * Convert selected grid cell keys (Local) to merged backend time ranges (UTC).
+ * Merges with the core crate once loadCore() has run, in TypeScript before.
  */
 export function cellsToRanges(selectedCells: Set<string>, slotDuration: number): ApiTimeRange[] {
   if (selectedCells.size === 0) return [];
@@ -115,6 +117,12 @@ export function cellsToRanges(selectedCells: Set<string>, slotDuration: number):
     return start;
   });
   
+  const merged = coreMergeTimeRanges(slots.map(start => ({
+    start_at: start.toISOString(),
+    end_at: new Date(start.getTime() + slotDuration * 60 * 1000).toISOString(),
+  })));
+  if (merged) return merged;
+
   // Sort by time
   slots.sort((a, b) => a.getTime() - b.getTime());
   